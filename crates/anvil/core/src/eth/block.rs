@@ -97,6 +97,14 @@ pub struct Header {
     pub nonce: H64,
     /// BaseFee was added by EIP-1559 and is ignored in legacy headers.
     pub base_fee_per_gas: Option<U256>,
+    /// The total blob gas of the transactions in the block, added by EIP-4844 and only set from
+    /// Cancun on.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blob_gas_used: Option<U256>,
+    /// The running excess of blob gas above the target, added by EIP-4844 and only set from
+    /// Cancun on.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub excess_blob_gas: Option<U256>,
 }
 
 // == impl Header ==
@@ -120,6 +128,8 @@ impl Header {
             mix_hash: partial_header.mix_hash,
             nonce: partial_header.nonce,
             base_fee_per_gas: partial_header.base_fee,
+            blob_gas_used: partial_header.blob_gas_used,
+            excess_blob_gas: partial_header.excess_blob_gas,
         }
     }
 
@@ -151,17 +161,16 @@ impl Header {
         length += self.mix_hash.length();
         length += self.nonce.length();
         length += self.base_fee_per_gas.map(|fee| fee.length()).unwrap_or_default();
+        length += self.blob_gas_used.map(|gas| gas.length()).unwrap_or_default();
+        length += self.excess_blob_gas.map(|gas| gas.length()).unwrap_or_default();
         length
     }
 }
 
 impl rlp::Encodable for Header {
     fn rlp_append(&self, s: &mut rlp::RlpStream) {
-        if self.base_fee_per_gas.is_none() {
-            s.begin_list(15);
-        } else {
-            s.begin_list(16);
-        }
+        let optional_fields = [self.base_fee_per_gas, self.blob_gas_used, self.excess_blob_gas];
+        s.begin_list(15 + optional_fields.iter().flatten().count());
         s.append(&self.parent_hash);
         s.append(&self.ommers_hash);
         s.append(&self.beneficiary);
@@ -177,8 +186,8 @@ impl rlp::Encodable for Header {
         s.append(&self.extra_data.as_ref());
         s.append(&self.mix_hash);
         s.append(&self.nonce);
-        if let Some(ref base_fee) = self.base_fee_per_gas {
-            s.append(base_fee);
+        for field in optional_fields.iter().flatten() {
+            s.append(field);
         }
     }
 }
@@ -206,6 +215,16 @@ impl rlp::Decodable for Header {
             } else {
                 None
             },
+            blob_gas_used: if let Ok(blob_gas_used) = rlp.at(16) {
+                Some(<U256 as Decodable>::decode(&blob_gas_used)?)
+            } else {
+                None
+            },
+            excess_blob_gas: if let Ok(excess_blob_gas) = rlp.at(17) {
+                Some(<U256 as Decodable>::decode(&excess_blob_gas)?)
+            } else {
+                None
+            },
         };
         Ok(result)
     }
@@ -244,6 +263,12 @@ impl open_fastrlp::Encodable for Header {
         if let Some(base_fee_per_gas) = self.base_fee_per_gas {
             base_fee_per_gas.encode(out);
         }
+        if let Some(blob_gas_used) = self.blob_gas_used {
+            blob_gas_used.encode(out);
+        }
+        if let Some(excess_blob_gas) = self.excess_blob_gas {
+            excess_blob_gas.encode(out);
+        }
     }
 }
 
@@ -276,6 +301,16 @@ impl open_fastrlp::Decodable for Header {
             } else {
                 None
             },
+            blob_gas_used: if start_len - header.payload_length < buf.len() {
+                Some(<U256 as open_fastrlp::Decodable>::decode(buf)?)
+            } else {
+                None
+            },
+            excess_blob_gas: if start_len - header.payload_length < buf.len() {
+                Some(<U256 as open_fastrlp::Decodable>::decode(buf)?)
+            } else {
+                None
+            },
         })
     }
 }
@@ -297,6 +332,8 @@ pub struct PartialHeader {
     pub mix_hash: H256,
     pub nonce: H64,
     pub base_fee: Option<U256>,
+    pub blob_gas_used: Option<U256>,
+    pub excess_blob_gas: Option<U256>,
 }
 
 impl From<Header> for PartialHeader {
@@ -316,6 +353,8 @@ impl From<Header> for PartialHeader {
             mix_hash: header.mix_hash,
             nonce: header.nonce,
             base_fee: header.base_fee_per_gas,
+            blob_gas_used: header.blob_gas_used,
+            excess_blob_gas: header.excess_blob_gas,
         }
     }
 }
@@ -348,6 +387,8 @@ mod tests {
             mix_hash: Default::default(),
             nonce: 99u64.to_be_bytes().into(),
             base_fee_per_gas: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
 
         let encoded = rlp::encode(&header);
@@ -359,6 +400,13 @@ mod tests {
         let encoded = rlp::encode(&header);
         let decoded: Header = rlp::decode(encoded.as_ref()).unwrap();
         assert_eq!(header, decoded);
+
+        header.blob_gas_used = Some(262144u64.into());
+        header.excess_blob_gas = Some(0u64.into());
+
+        let encoded = rlp::encode(&header);
+        let decoded: Header = rlp::decode(encoded.as_ref()).unwrap();
+        assert_eq!(header, decoded);
     }
 
     #[test]
@@ -381,6 +429,8 @@ mod tests {
             mix_hash: Default::default(),
             nonce: H64::from_low_u64_be(99u64),
             base_fee_per_gas: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
 
         let mut encoded = vec![];
@@ -423,6 +473,8 @@ mod tests {
             mix_hash: H256::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
             nonce: H64::from_low_u64_be(0x0),
             base_fee_per_gas: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
         header.encode(&mut data);
         assert_eq!(hex::encode(&data), hex::encode(expected));
@@ -452,6 +504,8 @@ mod tests {
             mix_hash: H256::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
             nonce: H64::from_low_u64_be(0x0),
             base_fee_per_gas: Some(0x036b.into()),
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
         assert_eq!(header.hash(), expected_hash);
     }
@@ -478,6 +532,8 @@ mod tests {
             mix_hash: H256::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
             nonce: H64::from_low_u64_be(0x0),
             base_fee_per_gas: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
         let header = <Header as open_fastrlp::Decodable>::decode(&mut data.as_slice()).unwrap();
         assert_eq!(header, expected);
@@ -1,10 +1,15 @@
 use self::state::StateOverride;
 use crate::{
     eth::{
-        subscription::{SubscriptionId, SubscriptionKind, SubscriptionParams},
+        subscription::{
+            AnvilSubscriptionKind, SubscriptionId, SubscriptionKind, SubscriptionParams,
+        },
         transaction::EthTransactionRequest,
     },
-    types::{EvmMineOptions, Forking, Index},
+    types::{
+        Bundle, DepositTransactionParams, EvmMineOptions, FaultInjection, Forking, Index,
+        TraceFilter, TransactionConditional,
+    },
 };
 use ethers_core::{
     abi::ethereum_types::H64,
@@ -157,12 +162,42 @@ pub enum EthRequest {
     #[cfg_attr(feature = "serde", serde(rename = "eth_signTypedData_v4"))]
     EthSignTypedDataV4(Address, TypedData),
 
+    /// Signs data like `eth_sign`, with the parameters in the order of `personal_sign`. The
+    /// password is ignored, since accounts managed by the node are always unlocked.
+    #[cfg_attr(feature = "serde", serde(rename = "personal_sign"))]
+    PersonalSign(Bytes, Address, #[cfg_attr(feature = "serde", serde(default))] Option<String>),
+
+    /// Returns the address that signed data with `personal_sign` or `eth_sign`
+    #[cfg_attr(feature = "serde", serde(rename = "personal_ecRecover"))]
+    PersonalEcRecover(Bytes, Bytes),
+
+    /// Returns the accounts managed by the node
+    #[cfg_attr(feature = "serde", serde(rename = "personal_listAccounts", with = "empty_params"))]
+    PersonalListAccounts(()),
+
+    /// Unlocks an account managed by the node, which is a no-op since they are always unlocked
+    #[cfg_attr(feature = "serde", serde(rename = "personal_unlockAccount"))]
+    PersonalUnlockAccount(
+        Address,
+        #[cfg_attr(feature = "serde", serde(default))] Option<String>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<u64>,
+    ),
+
     #[cfg_attr(feature = "serde", serde(rename = "eth_sendTransaction", with = "sequence"))]
     EthSendTransaction(Box<EthTransactionRequest>),
 
     #[cfg_attr(feature = "serde", serde(rename = "eth_sendRawTransaction", with = "sequence"))]
     EthSendRawTransaction(Bytes),
 
+    /// Sends a raw transaction if its preconditions hold, used by some L2 sequencers and
+    /// ERC-4337 bundlers
+    #[cfg_attr(feature = "serde", serde(rename = "eth_sendRawTransactionConditional"))]
+    EthSendRawTransactionConditional(Bytes, TransactionConditional),
+
+    /// Sends a bundle of transactions that is applied atomically at the top of a block
+    #[cfg_attr(feature = "serde", serde(rename = "eth_sendBundle", with = "sequence"))]
+    EthSendBundle(Bundle),
+
     #[cfg_attr(feature = "serde", serde(rename = "eth_call"))]
     EthCall(
         EthTransactionRequest,
@@ -198,6 +233,17 @@ pub enum EthRequest {
     #[cfg_attr(feature = "serde", serde(rename = "eth_getTransactionReceipt", with = "sequence"))]
     EthGetTransactionReceipt(H256),
 
+    /// Returns the blobs of the blob transactions mined in a block
+    #[cfg_attr(feature = "serde", serde(rename = "eth_getBlobSidecars", with = "sequence"))]
+    EthGetBlobSidecars(BlockId),
+
+    /// Returns the blobs of a mined blob transaction
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_getBlobsByTransactionHash", with = "sequence")
+    )]
+    GetBlobsByTransactionHash(TxHash),
+
     #[cfg_attr(feature = "serde", serde(rename = "eth_getUncleByBlockHashAndIndex"))]
     EthGetUncleByBlockHashAndIndex(H256, Index),
 
@@ -285,6 +331,14 @@ pub enum EthRequest {
     )]
     TraceBlock(BlockNumber),
 
+    /// Trace endpoint for parity's `trace_get`, the trace at a position of a transaction
+    #[cfg_attr(feature = "serde", serde(rename = "trace_get"))]
+    TraceGet(H256, Vec<Index>),
+
+    /// Trace endpoint for parity's `trace_filter`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_filter", with = "sequence"))]
+    TraceFilter(TraceFilter),
+
     // Custom endpoints, they're not extracted to a separate type out of serde convenience
     /// send transactions impersonating specific account and contract addresses.
     #[cfg_attr(
@@ -383,8 +437,11 @@ pub enum EthRequest {
         #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
     ),
 
-    /// Sets the code of a contract
-    #[cfg_attr(feature = "serde", serde(rename = "anvil_setCode", alias = "hardhat_setCode"))]
+    /// Sets the code of a contract, or the EIP-7702 delegation designator of an account
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_setCode", alias = "hardhat_setCode", alias = "evm_setAccountCode")
+    )]
     SetCode(Address, Bytes),
 
     /// Sets the nonce of an address
@@ -425,6 +482,15 @@ pub enum EthRequest {
     #[cfg_attr(feature = "serde", serde(rename = "anvil_setChainId", with = "sequence"))]
     SetChainId(u64),
 
+    /// Sets the EIP-170 contract code size limit, `null` for the default limit
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setCodeSizeLimit", with = "sequence"))]
+    SetCodeSizeLimit(Option<usize>),
+
+    /// Sets the initcode size limit of contract creation transactions, `null` for twice the code
+    /// size limit
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setInitcodeSizeLimit", with = "sequence"))]
+    SetInitcodeSizeLimit(Option<usize>),
+
     /// Enable or disable logging
     #[cfg_attr(
         feature = "serde",
@@ -436,6 +502,14 @@ pub enum EthRequest {
     )]
     SetLogging(bool),
 
+    /// Sets the faults injected into RPC calls and submitted transactions
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setFaultInjection", with = "sequence"))]
+    SetFaultInjection(FaultInjection),
+
+    /// Returns the faults injected into RPC calls and submitted transactions
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_faultInjection", with = "empty_params"))]
+    FaultInjection(()),
+
     /// Set the minimum gas price for the node
     #[cfg_attr(
         feature = "serde",
@@ -486,6 +560,15 @@ pub enum EthRequest {
     )]
     LoadState(Bytes),
 
+    /// Exports the mined blocks as concatenated RLP, the format of `geth import` and `reth import`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_exportBlocks", with = "empty_params"))]
+    ExportBlocks(()),
+
+    /// Exports the genesis of the chain in the `genesis.json` format, which is required to import
+    /// the blocks of `anvil_exportBlocks`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_exportGenesis", with = "empty_params"))]
+    ExportGenesis(()),
+
     /// Retrieves the Anvil node configuration params
     #[cfg_attr(feature = "serde", serde(rename = "anvil_nodeInfo", with = "empty_params"))]
     NodeInfo(()),
@@ -586,6 +669,13 @@ pub enum EthRequest {
         #[cfg_attr(feature = "serde", serde(default))] Option<Params<Option<EvmMineOptions>>>,
     ),
 
+    /// Mine blocks and return them with the receipts and call traces of their transactions, in a
+    /// single round trip
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_mineDetailed"))]
+    MineDetailed(
+        #[cfg_attr(feature = "serde", serde(default))] Option<Params<Option<EvmMineOptions>>>,
+    ),
+
     /// Execute a transaction regardless of signature status
     #[cfg_attr(
         feature = "serde",
@@ -593,6 +683,10 @@ pub enum EthRequest {
     )]
     EthSendUnsignedTransaction(Box<EthTransactionRequest>),
 
+    /// Executes an op-stack deposit transaction, without the L1 it would be deposited on
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_depositTransaction", with = "sequence"))]
+    DepositTransaction(Box<DepositTransactionParams>),
+
     /// Turn on call traces for transactions that are returned to the user when they execute a
     /// transaction (instead of just txhash/receipt)
     #[cfg_attr(feature = "serde", serde(rename = "anvil_enableTraces", with = "empty_params"))]
@@ -717,8 +811,15 @@ pub enum EthPubSub {
     ),
 
     /// Unsubscribe from an eth subscription
-    #[cfg_attr(feature = "serde", serde(rename = "eth_unsubscribe", with = "sequence"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "eth_unsubscribe", alias = "anvil_unsubscribe", with = "sequence")
+    )]
     EthUnSubscribe(SubscriptionId),
+
+    /// Subscribe to a custom anvil subscription
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_subscribe", with = "sequence"))]
+    AnvilSubscribe(AnvilSubscriptionKind),
 }
 
 /// Container type for either a request or a pub sub
@@ -1056,6 +1157,83 @@ mod tests {
         let s = r#"{"method": "anvil_setCode", "params": ["0xd84de507f3fada7df80908082d3239466db55a71", ""]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "evm_setAccountCode", "params": ["0xd84de507f3fada7df80908082d3239466db55a71", "0xef01005fbdb2315678afecb367f032d93f642f64180aa3"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_deposit_transaction() {
+        let s = r#"{"method": "anvil_depositTransaction", "params": [{"from": "0xd84de507f3fada7df80908082d3239466db55a71", "to": "0x295a70b2de5e3953354a6a8344e616ed314d7251", "mint": "0xde0b6b3a7640000", "data": "0x1234"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DepositTransaction(deposit) => {
+                assert_eq!(deposit.mint, U256::exp10(18));
+                assert_eq!(deposit.value, U256::zero());
+                assert!(deposit.source_hash.is_none());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_send_raw_transaction_conditional() {
+        let s = r#"{"method": "eth_sendRawTransactionConditional", "params": ["0x1234", {"knownAccounts": {"0xd84de507f3fada7df80908082d3239466db55a71": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421", "0x295a70b2de5e3953354a6a8344e616ed314d7251": {"0x0000000000000000000000000000000000000000000000000000000000000000": "0x0000000000000000000000000000000000000000000000000000000000000001"}}, "blockNumberMin": "0x1", "timestampMax": "0x64"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::EthSendRawTransactionConditional(_, conditional) => {
+                assert_eq!(conditional.known_accounts.len(), 2);
+                assert!(conditional
+                    .known_accounts
+                    .values()
+                    .any(|account| matches!(account, crate::types::KnownAccount::Slots(_))));
+                assert_eq!(conditional.block_number_min, Some(1u64.into()));
+                assert_eq!(conditional.block_number_max, None);
+                assert_eq!(conditional.timestamp_max, Some(100u64.into()));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "eth_sendRawTransactionConditional", "params": ["0x1234", {}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_send_bundle() {
+        let s = r#"{"method": "eth_sendBundle", "params": [{"txs": ["0x1234", "0x5678"], "blockNumber": "0x10", "maxTimestamp": 1700000000, "revertingTxHashes": ["0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"]}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::EthSendBundle(bundle) => {
+                assert_eq!(bundle.txs.len(), 2);
+                assert_eq!(bundle.block_number, 16u64.into());
+                assert_eq!(bundle.min_timestamp, None);
+                assert_eq!(bundle.max_timestamp, Some(1700000000));
+                assert_eq!(bundle.reverting_tx_hashes.len(), 1);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_blob_sidecars() {
+        let s = r#"{"method": "eth_getBlobSidecars", "params": ["latest"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::EthGetBlobSidecars(BlockId::Number(BlockNumber::Latest)));
+
+        let s = r#"{"method": "eth_getBlobSidecars", "params": ["0x2"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::EthGetBlobSidecars(BlockId::Number(2u64.into())));
+
+        let s = r#"{"method": "anvil_getBlobsByTransactionHash", "params": ["0x7d5b3bd2f2d2a1b5b5c6b1a5d4ac8a7e1c2c4b0e3c7a6d5f4e3d2c1b0a9f8e7d"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
     #[test]
@@ -1089,6 +1267,19 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_size_limits() {
+        let s = r#"{"method": "anvil_setCodeSizeLimit", "params": [49152]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetCodeSizeLimit(Some(49152)));
+
+        let s = r#"{"method": "anvil_setInitcodeSizeLimit", "params": [null]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetInitcodeSizeLimit(None));
+    }
+
     #[test]
     fn test_serde_custom_logging() {
         let s = r#"{"method": "anvil_setLoggingEnabled", "params": [false]}"#;
@@ -1128,6 +1319,51 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_personal() {
+        let s = r#"{"method": "personal_sign", "params": ["0xdeadbeef", "0xd84de507f3fada7df80908082d3239466db55a71"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "personal_sign", "params": ["0xdeadbeef", "0xd84de507f3fada7df80908082d3239466db55a71", "password"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "personal_ecRecover", "params": ["0xdeadbeef", "0x1c"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "personal_listAccounts", "params": [] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "personal_unlockAccount", "params": ["0xd84de507f3fada7df80908082d3239466db55a71", "password", 300] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_custom_fault_injection() {
+        let s = r#"{"method": "anvil_setFaultInjection", "params": [{"latency": 50, "errorRate": 0.1, "dropRate": 0.2}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_faultInjection", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_custom_export_blocks() {
+        let s = r#"{"method": "anvil_exportBlocks", "params": [] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_exportGenesis", "params": [] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_load_state() {
         let s = r#"{"method": "anvil_loadState", "params": ["0x0001"] }"#;
@@ -1281,6 +1517,27 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_mine_detailed() {
+        let s = r#"{"method": "anvil_mineDetailed", "params": [{"blocks": 2}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::MineDetailed(params) => {
+                assert_eq!(
+                    params.unwrap().params.unwrap_or_default(),
+                    EvmMineOptions::Options { timestamp: None, blocks: Some(2) }
+                )
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_mineDetailed"}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::MineDetailed(None)));
+    }
+
     #[test]
     fn test_serde_custom_evm_mine_hex() {
         let s = r#"{"method": "evm_mine", "params": ["0x63b6ff08"]}"#;
@@ -1364,6 +1621,18 @@ mod tests {
         let _req = serde_json::from_value::<EthPubSub>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_anvil_subscribe() {
+        let s = r#"{"id": 1, "method": "anvil_subscribe", "params": ["stateDiffs"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthPubSub>(value).unwrap();
+        assert_eq!(req, EthPubSub::AnvilSubscribe(AnvilSubscriptionKind::StateDiffs));
+
+        let s = r#"{"id": 1, "method": "anvil_unsubscribe", "params": ["0x9cef478923ff08bf67fde6c64013158d"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthPubSub>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_debug_trace_transaction() {
         let s = r#"{"method": "debug_traceTransaction", "params": ["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff"]}"#;
@@ -1379,6 +1648,42 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_trace_get() {
+        let s = r#"{"method": "trace_get", "params": ["0x17104ac9d3312d8c136b7f44d4b8b47852618065ebfa534bd2d3b5ef218ca1f3", ["0x0"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::TraceGet(_, indices) => {
+                assert_eq!(indices.into_iter().map(usize::from).collect::<Vec<_>>(), vec![0])
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_trace_filter() {
+        let s = r#"{"method": "trace_filter", "params": [{"fromBlock": "0x1", "toBlock": "latest", "toAddress": ["0xd84de507f3fada7df80908082d3239466db55a71"], "count": 10}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::TraceFilter(filter) => {
+                assert_eq!(filter.from_block, Some(BlockNumber::Number(1u64.into())));
+                assert_eq!(filter.to_block, Some(BlockNumber::Latest));
+                assert!(filter.from_address.is_empty());
+                assert_eq!(filter.to_address.len(), 1);
+                assert_eq!(filter.after, None);
+                assert_eq!(filter.count, Some(10));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "trace_filter", "params": [{}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::TraceFilter(Default::default()));
+    }
+
     #[test]
     fn test_serde_debug_trace_call() {
         let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}]}"#;
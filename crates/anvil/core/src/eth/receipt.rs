@@ -94,6 +94,7 @@ impl Decodable for EIP658Receipt {
 // same underlying data structure
 pub type EIP2930Receipt = EIP658Receipt;
 pub type EIP1559Receipt = EIP658Receipt;
+pub type EIP4844Receipt = EIP658Receipt;
 pub type DepositReceipt = EIP658Receipt;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -105,6 +106,8 @@ pub enum TypedReceipt {
     EIP2930(EIP2930Receipt),
     /// EIP-1559 receipt
     EIP1559(EIP1559Receipt),
+    /// EIP-4844 receipt
+    EIP4844(EIP4844Receipt),
     /// op-stack deposit receipt
     Deposit(DepositReceipt),
 }
@@ -118,6 +121,7 @@ impl TypedReceipt {
             TypedReceipt::Legacy(r) |
             TypedReceipt::EIP2930(r) |
             TypedReceipt::EIP1559(r) |
+            TypedReceipt::EIP4844(r) |
             TypedReceipt::Deposit(r) => r.gas_used,
         }
     }
@@ -128,6 +132,7 @@ impl TypedReceipt {
             TypedReceipt::Legacy(r) |
             TypedReceipt::EIP2930(r) |
             TypedReceipt::EIP1559(r) |
+            TypedReceipt::EIP4844(r) |
             TypedReceipt::Deposit(r) => &r.logs_bloom,
         }
     }
@@ -139,6 +144,7 @@ impl Encodable for TypedReceipt {
             TypedReceipt::Legacy(r) => r.rlp_append(s),
             TypedReceipt::EIP2930(r) => enveloped(1, r, s),
             TypedReceipt::EIP1559(r) => enveloped(2, r, s),
+            TypedReceipt::EIP4844(r) => enveloped(3, r, s),
             TypedReceipt::Deposit(r) => enveloped(0x7E, r, s),
         }
     }
@@ -164,6 +170,10 @@ impl Decodable for TypedReceipt {
             return rlp::decode(s).map(TypedReceipt::EIP1559)
         }
 
+        if first == 0x03 {
+            return rlp::decode(s).map(TypedReceipt::EIP4844)
+        }
+
         if first == 0x7E {
             return rlp::decode(s).map(TypedReceipt::Deposit)
         }
@@ -181,6 +191,7 @@ impl open_fastrlp::Encodable for TypedReceipt {
                 let payload_len = match receipt {
                     TypedReceipt::EIP2930(r) => r.length() + 1,
                     TypedReceipt::EIP1559(r) => r.length() + 1,
+                    TypedReceipt::EIP4844(r) => r.length() + 1,
                     TypedReceipt::Deposit(r) => r.length() + 1,
                     _ => unreachable!("receipt already matched"),
                 };
@@ -199,6 +210,7 @@ impl open_fastrlp::Encodable for TypedReceipt {
                 let payload_len = match receipt {
                     TypedReceipt::EIP2930(r) => r.length() + 1,
                     TypedReceipt::EIP1559(r) => r.length() + 1,
+                    TypedReceipt::EIP4844(r) => r.length() + 1,
                     TypedReceipt::Deposit(r) => r.length() + 1,
                     _ => unreachable!("receipt already matched"),
                 };
@@ -220,6 +232,14 @@ impl open_fastrlp::Encodable for TypedReceipt {
                         out.put_u8(0x02);
                         r.encode(out);
                     }
+                    TypedReceipt::EIP4844(r) => {
+                        let receipt_string_header =
+                            Header { list: false, payload_length: payload_len };
+
+                        receipt_string_header.encode(out);
+                        out.put_u8(0x03);
+                        r.encode(out);
+                    }
                     TypedReceipt::Deposit(r) => {
                         let receipt_string_header =
                             Header { list: false, payload_length: payload_len };
@@ -264,6 +284,10 @@ impl open_fastrlp::Decodable for TypedReceipt {
                     buf.advance(1);
                     <EIP1559Receipt as open_fastrlp::Decodable>::decode(buf)
                         .map(TypedReceipt::EIP1559)
+                } else if receipt_type == 0x03 {
+                    buf.advance(1);
+                    <EIP4844Receipt as open_fastrlp::Decodable>::decode(buf)
+                        .map(TypedReceipt::EIP4844)
                 } else if receipt_type == 0x7E {
                     buf.advance(1);
                     <DepositReceipt as open_fastrlp::Decodable>::decode(buf)
@@ -288,6 +312,7 @@ impl From<TypedReceipt> for EIP658Receipt {
             TypedReceipt::Legacy(receipt) => receipt,
             TypedReceipt::EIP2930(receipt) => receipt,
             TypedReceipt::EIP1559(receipt) => receipt,
+            TypedReceipt::EIP4844(receipt) => receipt,
             TypedReceipt::Deposit(receipt) => receipt,
         }
     }
@@ -1,5 +1,5 @@
-use ethers_core::types::{Address, Bytes, H256, U256};
-use std::collections::HashMap;
+use ethers_core::types::{Address, Bytes, H256, U256, U64};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -14,3 +14,29 @@ pub struct AccountOverride {
 }
 
 pub type StateOverride = HashMap<Address, AccountOverride>;
+
+/// The state changes of all accounts touched by the transactions of a block
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BlockStateDiff {
+    pub block_hash: H256,
+    pub block_number: U64,
+    pub accounts: BTreeMap<Address, AccountStateDiff>,
+}
+
+/// The post-block state of an account touched by a block
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AccountStateDiff {
+    pub balance: U256,
+    pub nonce: U64,
+    /// The code of the account, only set if the account was created in the block
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub code: Option<Bytes>,
+    /// The new values of the storage slots changed in the block
+    pub storage: BTreeMap<H256, H256>,
+    /// Whether the account was self-destructed in the block
+    pub destroyed: bool,
+}
@@ -1,6 +1,6 @@
 //! Subscription types
 
-use crate::eth::block::Header;
+use crate::eth::{block::Header, state::BlockStateDiff};
 use ethers_core::{
     rand::{distributions::Alphanumeric, thread_rng, Rng},
     types::{Filter, Log, TxHash},
@@ -21,6 +21,8 @@ pub enum SubscriptionResult {
     TransactionHash(TxHash),
     /// SyncStatus
     Sync(SyncStatus),
+    /// State changes of a new block
+    StateDiff(Box<BlockStateDiff>),
 }
 
 /// Sync status
@@ -73,6 +75,16 @@ pub enum SubscriptionKind {
     Syncing,
 }
 
+/// Subscription kind of the custom `anvil_subscribe` namespace
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AnvilSubscriptionKind {
+    /// subscribe to the account and storage changes of new blocks
+    StateDiffs,
+}
+
 /// Unique subscription id
 #[derive(Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -11,7 +11,7 @@ use ethers_core::types::{
         eip2718::TypedTransaction as EthersTypedTransactionRequest, optimism::DepositTransaction,
     },
     Address, Eip1559TransactionRequest as EthersEip1559TransactionRequest,
-    Eip2930TransactionRequest as EthersEip2930TransactionRequest, NameOrAddress,
+    Eip2930TransactionRequest as EthersEip2930TransactionRequest, NameOrAddress, OtherFields,
     Transaction as EthersTransaction, TransactionRequest as EthersLegacyTransactionRequest,
     TransactionRequest, H256, U256, U64,
 };
@@ -202,6 +202,42 @@ fn to_ethers_transaction_with_hash_and_sender(
             is_system_tx: false,
             other: Default::default(),
         },
+        TypedTransaction::EIP4844(t) => {
+            let mut other = OtherFields::default();
+            other.insert(
+                "maxFeePerBlobGas".to_string(),
+                serde_json::to_value(t.max_fee_per_blob_gas).expect("Infallible"),
+            );
+            other.insert(
+                "blobVersionedHashes".to_string(),
+                serde_json::to_value(&t.blob_versioned_hashes).expect("Infallible"),
+            );
+            EthersTransaction {
+                hash,
+                nonce: t.nonce,
+                block_hash: None,
+                block_number: None,
+                transaction_index: None,
+                from,
+                to: None,
+                value: t.value,
+                gas_price: None,
+                max_fee_per_gas: Some(t.max_fee_per_gas),
+                max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas),
+                gas: t.gas_limit,
+                input: t.input.clone(),
+                chain_id: Some(t.chain_id.into()),
+                v: U64::from(t.odd_y_parity as u8),
+                r: U256::from(t.r.as_bytes()),
+                s: U256::from(t.s.as_bytes()),
+                access_list: Some(t.access_list),
+                transaction_type: Some(3u64.into()),
+                source_hash: H256::zero(),
+                mint: None,
+                is_system_tx: false,
+                other,
+            }
+        }
         TypedTransaction::Deposit(t) => EthersTransaction {
             hash,
             nonce: t.nonce,
@@ -1,9 +1,13 @@
 //! transaction related data
 
-use crate::eth::{
-    receipt::Log,
-    utils::{enveloped, to_revm_access_list},
+use crate::{
+    eth::{
+        receipt::Log,
+        utils::{enveloped, to_revm_access_list},
+    },
+    types::TransactionConditional,
 };
+use c_kzg::{Bytes48, KzgCommitment, KzgProof};
 use ethers_core::{
     types::{
         transaction::eip2930::{AccessList, AccessListItem},
@@ -18,8 +22,9 @@ use foundry_common::types::ToAlloy;
 use foundry_evm::traces::CallTraceArena;
 use revm::{
     interpreter::InstructionResult,
-    primitives::{CreateScheme, OptimismFields, TransactTo, TxEnv},
+    primitives::{kzg::EnvKzgSettings, CreateScheme, OptimismFields, SpecId, TransactTo, TxEnv},
 };
+use sha2::{Digest, Sha256};
 use std::ops::Deref;
 
 /// compatibility with `ethers-rs` types
@@ -30,6 +35,9 @@ mod ethers_compat;
 pub const IMPERSONATED_SIGNATURE: Signature =
     Signature { r: U256([0, 0, 0, 0]), s: U256([0, 0, 0, 0]), v: 0 };
 
+/// The fixed L1 gas overhead an OP stack chain charges per transaction, before Ecotone.
+pub const OP_L1_FIXED_OVERHEAD: u64 = 188;
+
 /// Container type for various Ethereum transaction requests
 ///
 /// Its variants correspond to specific allowed transactions:
@@ -450,6 +458,69 @@ impl Encodable for EIP1559TransactionRequest {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fastrlp", derive(open_fastrlp::RlpEncodable, open_fastrlp::RlpDecodable))]
+pub struct EIP4844TransactionRequest {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub kind: TransactionKind,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: Vec<AccessListItem>,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<H256>,
+}
+
+// == impl EIP4844TransactionRequest ==
+
+impl EIP4844TransactionRequest {
+    pub fn hash(&self) -> H256 {
+        let encoded = rlp::encode(self);
+        let mut out = vec![0; 1 + encoded.len()];
+        out[0] = 3;
+        out[1..].copy_from_slice(&encoded);
+        H256::from_slice(keccak256(&out).as_slice())
+    }
+}
+
+impl From<EIP4844Transaction> for EIP4844TransactionRequest {
+    fn from(t: EIP4844Transaction) -> Self {
+        Self {
+            chain_id: t.chain_id,
+            nonce: t.nonce,
+            max_priority_fee_per_gas: t.max_priority_fee_per_gas,
+            max_fee_per_gas: t.max_fee_per_gas,
+            gas_limit: t.gas_limit,
+            kind: t.kind,
+            value: t.value,
+            input: t.input,
+            access_list: t.access_list.0,
+            max_fee_per_blob_gas: t.max_fee_per_blob_gas,
+            blob_versioned_hashes: t.blob_versioned_hashes,
+        }
+    }
+}
+
+impl Encodable for EIP4844TransactionRequest {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(11);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas_limit);
+        s.append(&self.kind);
+        s.append(&self.value);
+        s.append(&self.input.as_ref());
+        s.append_list(&self.access_list);
+        s.append(&self.max_fee_per_blob_gas);
+        s.append_list(&self.blob_versioned_hashes);
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DepositTransactionRequest {
     pub from: Address,
@@ -617,6 +688,8 @@ pub enum TypedTransaction {
     EIP2930(EIP2930Transaction),
     /// EIP-1559 transaction
     EIP1559(EIP1559Transaction),
+    /// EIP-4844 blob transaction
+    EIP4844(EIP4844Transaction),
     /// op-stack deposit transaction
     Deposit(DepositTransaction),
 }
@@ -624,9 +697,9 @@ pub enum TypedTransaction {
 // == impl TypedTransaction ==
 
 impl TypedTransaction {
-    /// Returns true if the transaction uses dynamic fees: EIP1559
+    /// Returns true if the transaction uses dynamic fees: EIP1559 and EIP4844
     pub fn is_dynamic_fee(&self) -> bool {
-        matches!(self, TypedTransaction::EIP1559(_))
+        matches!(self, TypedTransaction::EIP1559(_) | TypedTransaction::EIP4844(_))
     }
 
     pub fn gas_price(&self) -> U256 {
@@ -634,6 +707,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.gas_price,
             TypedTransaction::EIP2930(tx) => tx.gas_price,
             TypedTransaction::EIP1559(tx) => tx.max_fee_per_gas,
+            TypedTransaction::EIP4844(tx) => tx.max_fee_per_gas,
             TypedTransaction::Deposit(_) => U256::from(0),
         }
     }
@@ -643,6 +717,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.gas_limit,
             TypedTransaction::EIP2930(tx) => tx.gas_limit,
             TypedTransaction::EIP1559(tx) => tx.gas_limit,
+            TypedTransaction::EIP4844(tx) => tx.gas_limit,
             TypedTransaction::Deposit(tx) => tx.gas_limit,
         }
     }
@@ -652,6 +727,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.value,
             TypedTransaction::EIP2930(tx) => tx.value,
             TypedTransaction::EIP1559(tx) => tx.value,
+            TypedTransaction::EIP4844(tx) => tx.value,
             TypedTransaction::Deposit(tx) => tx.value,
         }
     }
@@ -661,6 +737,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => &tx.input,
             TypedTransaction::EIP2930(tx) => &tx.input,
             TypedTransaction::EIP1559(tx) => &tx.input,
+            TypedTransaction::EIP4844(tx) => &tx.input,
             TypedTransaction::Deposit(tx) => &tx.input,
         }
     }
@@ -671,13 +748,42 @@ impl TypedTransaction {
             TypedTransaction::Legacy(_) => None,
             TypedTransaction::EIP2930(_) => Some(1),
             TypedTransaction::EIP1559(_) => Some(2),
+            TypedTransaction::EIP4844(_) => Some(3),
             TypedTransaction::Deposit(_) => Some(0x7E),
         }
     }
 
-    /// Max cost of the transaction
+    /// Returns the L1 gas an OP stack chain charges for posting the transaction to L1: the
+    /// calldata gas of the signed transaction plus the fixed overhead of a batch.
+    ///
+    /// Deposit transactions originate on L1 and have no L1 gas.
+    pub fn l1_gas_used(&self) -> Option<U256> {
+        let encoded = match self {
+            TypedTransaction::Legacy(tx) => rlp::encode(tx).to_vec(),
+            TypedTransaction::EIP2930(tx) => [&[1u8][..], &rlp::encode(tx)].concat(),
+            TypedTransaction::EIP1559(tx) => [&[2u8][..], &rlp::encode(tx)].concat(),
+            TypedTransaction::EIP4844(tx) => [&[3u8][..], &rlp::encode(tx)].concat(),
+            TypedTransaction::Deposit(_) => return None,
+        };
+        let calldata_gas: u64 = encoded.iter().map(|b| if *b == 0 { 4 } else { 16 }).sum();
+        Some(U256::from(calldata_gas + OP_L1_FIXED_OVERHEAD))
+    }
+
+    /// Max cost of the transaction, including the max cost of its blob gas
     pub fn max_cost(&self) -> U256 {
-        self.gas_limit().saturating_mul(self.gas_price())
+        let blob_cost = match self {
+            TypedTransaction::EIP4844(tx) => tx.max_fee_per_blob_gas.saturating_mul(tx.blob_gas()),
+            _ => U256::zero(),
+        };
+        self.gas_limit().saturating_mul(self.gas_price()).saturating_add(blob_cost)
+    }
+
+    /// Returns the versioned hashes of the blobs of an EIP-4844 transaction
+    pub fn blob_versioned_hashes(&self) -> &[H256] {
+        match self {
+            TypedTransaction::EIP4844(tx) => &tx.blob_versioned_hashes,
+            _ => &[],
+        }
     }
 
     /// Returns a helper type that contains commonly used values as fields
@@ -719,6 +825,18 @@ impl TypedTransaction {
                 chain_id: Some(t.chain_id),
                 access_list: t.access_list.clone(),
             },
+            TypedTransaction::EIP4844(t) => TransactionEssentials {
+                kind: t.kind,
+                input: t.input.clone(),
+                nonce: t.nonce,
+                gas_limit: t.gas_limit,
+                gas_price: None,
+                max_fee_per_gas: Some(t.max_fee_per_gas),
+                max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas),
+                value: t.value,
+                chain_id: Some(t.chain_id),
+                access_list: t.access_list.clone(),
+            },
             TypedTransaction::Deposit(t) => TransactionEssentials {
                 kind: t.kind,
                 input: t.input.clone(),
@@ -739,6 +857,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(t) => t.nonce(),
             TypedTransaction::EIP2930(t) => t.nonce(),
             TypedTransaction::EIP1559(t) => t.nonce(),
+            TypedTransaction::EIP4844(t) => t.nonce(),
             TypedTransaction::Deposit(t) => t.nonce(),
         }
     }
@@ -748,6 +867,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(t) => t.chain_id(),
             TypedTransaction::EIP2930(t) => Some(t.chain_id),
             TypedTransaction::EIP1559(t) => Some(t.chain_id),
+            TypedTransaction::EIP4844(t) => Some(t.chain_id),
             TypedTransaction::Deposit(t) => t.chain_id(),
         }
     }
@@ -778,6 +898,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(t) => t.hash(),
             TypedTransaction::EIP2930(t) => t.hash(),
             TypedTransaction::EIP1559(t) => t.hash(),
+            TypedTransaction::EIP4844(t) => t.hash(),
             TypedTransaction::Deposit(t) => t.hash(),
         }
     }
@@ -804,6 +925,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.recover(),
             TypedTransaction::EIP2930(tx) => tx.recover(),
             TypedTransaction::EIP1559(tx) => tx.recover(),
+            TypedTransaction::EIP4844(tx) => tx.recover(),
             TypedTransaction::Deposit(tx) => tx.recover(),
         }
     }
@@ -814,6 +936,7 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => &tx.kind,
             TypedTransaction::EIP2930(tx) => &tx.kind,
             TypedTransaction::EIP1559(tx) => &tx.kind,
+            TypedTransaction::EIP4844(tx) => &tx.kind,
             TypedTransaction::Deposit(tx) => &tx.kind,
         }
     }
@@ -839,6 +962,12 @@ impl TypedTransaction {
                 let s = U256::from_big_endian(&tx.s[..]);
                 Signature { r, s, v: v.into() }
             }
+            TypedTransaction::EIP4844(tx) => {
+                let v = tx.odd_y_parity as u8;
+                let r = U256::from_big_endian(&tx.r[..]);
+                let s = U256::from_big_endian(&tx.s[..]);
+                Signature { r, s, v: v.into() }
+            }
             TypedTransaction::Deposit(_) => Signature { r: U256::zero(), s: U256::zero(), v: 0 },
         }
     }
@@ -850,6 +979,7 @@ impl Encodable for TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.rlp_append(s),
             TypedTransaction::EIP2930(tx) => enveloped(1, tx, s),
             TypedTransaction::EIP1559(tx) => enveloped(2, tx, s),
+            TypedTransaction::EIP4844(tx) => enveloped(3, tx, s),
             TypedTransaction::Deposit(tx) => enveloped(0x7E, tx, s),
         }
     }
@@ -869,6 +999,7 @@ impl Decodable for TypedTransaction {
         match *first {
             0x01 => rlp::decode(s).map(TypedTransaction::EIP2930),
             0x02 => rlp::decode(s).map(TypedTransaction::EIP1559),
+            0x03 => rlp::decode(s).map(TypedTransaction::EIP4844),
             0x7E => rlp::decode(s).map(TypedTransaction::Deposit),
             _ => Err(DecoderError::Custom("invalid tx type")),
         }
@@ -884,6 +1015,7 @@ impl open_fastrlp::Encodable for TypedTransaction {
                 let payload_len = match tx {
                     TypedTransaction::EIP2930(tx) => tx.length() + 1,
                     TypedTransaction::EIP1559(tx) => tx.length() + 1,
+                    TypedTransaction::EIP4844(tx) => tx.length() + 1,
                     TypedTransaction::Deposit(tx) => tx.length() + 1,
                     _ => unreachable!("legacy tx length already matched"),
                 };
@@ -905,6 +1037,14 @@ impl open_fastrlp::Encodable for TypedTransaction {
                         out.put_u8(0x02);
                         tx.encode(out);
                     }
+                    TypedTransaction::EIP4844(tx) => {
+                        let tx_string_header =
+                            open_fastrlp::Header { list: false, payload_length: payload_len };
+
+                        tx_string_header.encode(out);
+                        out.put_u8(0x03);
+                        tx.encode(out);
+                    }
                     TypedTransaction::Deposit(tx) => {
                         let tx_string_header =
                             open_fastrlp::Header { list: false, payload_length: payload_len };
@@ -925,6 +1065,7 @@ impl open_fastrlp::Encodable for TypedTransaction {
                 let payload_len = match tx {
                     TypedTransaction::EIP2930(tx) => tx.length() + 1,
                     TypedTransaction::EIP1559(tx) => tx.length() + 1,
+                    TypedTransaction::EIP4844(tx) => tx.length() + 1,
                     TypedTransaction::Deposit(tx) => tx.length() + 1,
                     _ => unreachable!("legacy tx length already matched"),
                 };
@@ -971,6 +1112,10 @@ impl open_fastrlp::Decodable for TypedTransaction {
                     buf.advance(1);
                     <EIP1559Transaction as open_fastrlp::Decodable>::decode(buf)
                         .map(TypedTransaction::EIP1559)
+                } else if tx_type == 0x03 {
+                    buf.advance(1);
+                    <EIP4844Transaction as open_fastrlp::Decodable>::decode(buf)
+                        .map(TypedTransaction::EIP4844)
                 } else if tx_type == 0x7E {
                     buf.advance(1);
                     <DepositTransaction as open_fastrlp::Decodable>::decode(buf)
@@ -1250,6 +1395,235 @@ impl Decodable for EIP1559Transaction {
     }
 }
 
+/// The gas consumed by a single blob, as specified by EIP-4844
+pub const DATA_GAS_PER_BLOB: u64 = 131_072;
+
+/// The size of a blob in bytes
+pub const BYTES_PER_BLOB: usize = 131_072;
+
+/// The max number of blobs of a block, and thus of a transaction
+pub const MAX_BLOBS_PER_BLOCK: usize = 6;
+
+/// The version byte of versioned hashes of KZG commitments
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "fastrlp", derive(open_fastrlp::RlpEncodable, open_fastrlp::RlpDecodable))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EIP4844Transaction {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub kind: TransactionKind,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: AccessList,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<H256>,
+    pub odd_y_parity: bool,
+    pub r: H256,
+    pub s: H256,
+}
+
+impl EIP4844Transaction {
+    pub fn nonce(&self) -> &U256 {
+        &self.nonce
+    }
+
+    pub fn hash(&self) -> H256 {
+        let encoded = rlp::encode(self);
+        let mut out = vec![0; 1 + encoded.len()];
+        out[0] = 3;
+        out[1..].copy_from_slice(&encoded);
+        H256::from_slice(keccak256(&out).as_slice())
+    }
+
+    /// Returns the blob gas consumed by the blobs of the transaction
+    pub fn blob_gas(&self) -> U256 {
+        U256::from(DATA_GAS_PER_BLOB) * self.blob_versioned_hashes.len()
+    }
+
+    /// Recovers the Ethereum address which was used to sign the transaction.
+    pub fn recover(&self) -> Result<Address, SignatureError> {
+        let mut sig = [0u8; 65];
+        sig[0..32].copy_from_slice(&self.r[..]);
+        sig[32..64].copy_from_slice(&self.s[..]);
+        sig[64] = self.odd_y_parity as u8;
+        let signature = Signature::try_from(&sig[..])?;
+        signature.recover(EIP4844TransactionRequest::from(self.clone()).hash())
+    }
+}
+
+impl Encodable for EIP4844Transaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(14);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas_limit);
+        s.append(&self.kind);
+        s.append(&self.value);
+        s.append(&self.input.as_ref());
+        s.append(&self.access_list);
+        s.append(&self.max_fee_per_blob_gas);
+        s.append_list(&self.blob_versioned_hashes);
+        s.append(&self.odd_y_parity);
+        s.append(&U256::from_big_endian(&self.r[..]));
+        s.append(&U256::from_big_endian(&self.s[..]));
+    }
+}
+
+impl Decodable for EIP4844Transaction {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 14 {
+            return Err(DecoderError::RlpIncorrectListLen)
+        }
+
+        let kind: TransactionKind = rlp.val_at(5)?;
+        if kind == TransactionKind::Create {
+            return Err(DecoderError::Custom("blob transactions can't create contracts"))
+        }
+
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            max_priority_fee_per_gas: rlp.val_at(2)?,
+            max_fee_per_gas: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            kind,
+            value: rlp.val_at(6)?,
+            input: rlp.val_at::<Vec<u8>>(7)?.into(),
+            access_list: rlp.val_at(8)?,
+            max_fee_per_blob_gas: rlp.val_at(9)?,
+            blob_versioned_hashes: rlp.list_at(10)?,
+            odd_y_parity: rlp.val_at(11)?,
+            r: {
+                let mut rarr = [0u8; 32];
+                rlp.val_at::<U256>(12)?.to_big_endian(&mut rarr);
+                H256::from(rarr)
+            },
+            s: {
+                let mut sarr = [0u8; 32];
+                rlp.val_at::<U256>(13)?.to_big_endian(&mut sarr);
+                H256::from(sarr)
+            },
+        })
+    }
+}
+
+/// The blobs of an EIP-4844 transaction with their KZG commitments and proofs.
+///
+/// The sidecar is sent alongside the transaction, but isn't part of the signed transaction or of
+/// the block that includes it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BlobTransactionSidecar {
+    pub blobs: Vec<Bytes>,
+    pub commitments: Vec<Bytes>,
+    pub proofs: Vec<Bytes>,
+}
+
+impl BlobTransactionSidecar {
+    /// Creates the sidecar of the blobs, computing their KZG commitments and proofs
+    pub fn from_blobs(blobs: Vec<Bytes>) -> Result<Self, c_kzg::Error> {
+        let settings = EnvKzgSettings::Default;
+        let mut commitments = Vec::with_capacity(blobs.len());
+        let mut proofs = Vec::with_capacity(blobs.len());
+        for blob in &blobs {
+            let blob = c_kzg::Blob::from_bytes(blob)?;
+            let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, settings.get())?;
+            let commitment = commitment.to_bytes();
+            let proof = KzgProof::compute_blob_kzg_proof(&blob, &commitment, settings.get())?;
+            commitments.push(commitment.to_vec().into());
+            proofs.push(proof.to_bytes().to_vec().into());
+        }
+        Ok(Self { blobs, commitments, proofs })
+    }
+
+    /// Returns the versioned hashes of the commitments, `0x01 || sha256(commitment)[1..]`
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        self.commitments.iter().map(|commitment| kzg_to_versioned_hash(commitment)).collect()
+    }
+
+    /// Returns true if the KZG proofs prove that the blobs match their commitments
+    ///
+    /// Returns an error if a blob, commitment or proof is malformed.
+    pub fn verify_kzg_proofs(&self) -> Result<bool, c_kzg::Error> {
+        let blobs = self
+            .blobs
+            .iter()
+            .map(|blob| c_kzg::Blob::from_bytes(blob))
+            .collect::<Result<Vec<_>, _>>()?;
+        let commitments = self
+            .commitments
+            .iter()
+            .map(|commitment| Bytes48::from_bytes(commitment))
+            .collect::<Result<Vec<_>, _>>()?;
+        let proofs = self
+            .proofs
+            .iter()
+            .map(|proof| Bytes48::from_bytes(proof))
+            .collect::<Result<Vec<_>, _>>()?;
+        KzgProof::verify_blob_kzg_proof_batch(
+            &blobs,
+            &commitments,
+            &proofs,
+            EnvKzgSettings::Default.get(),
+        )
+    }
+}
+
+/// Returns the versioned hash of a KZG commitment, as specified by EIP-4844
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256(hash)
+}
+
+/// Encodes an EIP-4844 transaction with its blobs in the network encoding,
+/// `0x03 || rlp([tx_payload_body, blobs, commitments, proofs])`
+pub fn encode_blob_transaction_with_sidecar(
+    transaction: &EIP4844Transaction,
+    sidecar: &BlobTransactionSidecar,
+) -> Vec<u8> {
+    let mut s = RlpStream::new_list(4);
+    s.append(transaction);
+    for items in [&sidecar.blobs, &sidecar.commitments, &sidecar.proofs] {
+        s.begin_list(items.len());
+        for item in items {
+            s.append(&item.as_ref());
+        }
+    }
+    let mut out = vec![3];
+    out.extend_from_slice(&s.out());
+    out
+}
+
+/// Decodes the network encoding of an EIP-4844 transaction without its type byte,
+/// `rlp([tx_payload_body, blobs, commitments, proofs])`, as sent to `eth_sendRawTransaction`
+pub fn decode_blob_transaction_with_sidecar(
+    data: &[u8],
+) -> Result<(EIP4844Transaction, BlobTransactionSidecar), DecoderError> {
+    let rlp = Rlp::new(data);
+    if rlp.item_count()? != 4 {
+        return Err(DecoderError::RlpIncorrectListLen)
+    }
+    let transaction = rlp.val_at(0)?;
+    let bytes_at = |index| -> Result<Vec<Bytes>, DecoderError> {
+        Ok(rlp.list_at::<Vec<u8>>(index)?.into_iter().map(Bytes::from).collect())
+    };
+    let sidecar = BlobTransactionSidecar {
+        blobs: bytes_at(1)?,
+        commitments: bytes_at(2)?,
+        proofs: bytes_at(3)?,
+    };
+    Ok((transaction, sidecar))
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "fastrlp", derive(open_fastrlp::RlpEncodable, open_fastrlp::RlpDecodable))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -1343,6 +1717,10 @@ pub struct PendingTransaction {
     sender: Address,
     /// hash of `transaction`, so it can easily be reused with encoding and hashing agan
     hash: TxHash,
+    /// The blobs of an EIP-4844 transaction
+    pub blob_sidecar: Option<BlobTransactionSidecar>,
+    /// The preconditions of a transaction sent with `eth_sendRawTransactionConditional`
+    pub conditional: Option<TransactionConditional>,
 }
 
 // == impl PendingTransaction ==
@@ -1351,7 +1729,13 @@ impl PendingTransaction {
     /// Creates a new pending transaction and tries to verify transaction and recover sender.
     pub fn new(transaction: TypedTransaction) -> Result<Self, SignatureError> {
         let sender = transaction.recover()?;
-        Ok(Self { hash: transaction.hash(), transaction: transaction.into(), sender })
+        Ok(Self {
+            hash: transaction.hash(),
+            transaction: transaction.into(),
+            sender,
+            blob_sidecar: None,
+            conditional: None,
+        })
     }
 
     /// Creates a new transaction with the given sender.
@@ -1364,7 +1748,19 @@ impl PendingTransaction {
     pub fn with_impersonated(transaction: TypedTransaction, sender: Address) -> Self {
         let hash = transaction.impersonated_hash(sender);
         let transaction = MaybeImpersonatedTransaction::impersonated(transaction, sender);
-        Self { hash, transaction, sender }
+        Self { hash, transaction, sender, blob_sidecar: None, conditional: None }
+    }
+
+    /// Attaches the blobs of an EIP-4844 transaction
+    pub fn with_blob_sidecar(mut self, sidecar: BlobTransactionSidecar) -> Self {
+        self.blob_sidecar = Some(sidecar);
+        self
+    }
+
+    /// Attaches the preconditions the transaction must meet to be included in a block
+    pub fn with_conditional(mut self, conditional: TransactionConditional) -> Self {
+        self.conditional = Some(conditional);
+        self
     }
 
     pub fn nonce(&self) -> &U256 {
@@ -1461,6 +1857,37 @@ impl PendingTransaction {
                     ..Default::default()
                 }
             }
+            TypedTransaction::EIP4844(tx) => {
+                let EIP4844Transaction {
+                    chain_id,
+                    nonce,
+                    max_priority_fee_per_gas,
+                    max_fee_per_gas,
+                    gas_limit,
+                    kind,
+                    value,
+                    input,
+                    access_list,
+                    max_fee_per_blob_gas,
+                    blob_versioned_hashes,
+                    ..
+                } = tx;
+                TxEnv {
+                    caller: (caller).to_alloy(),
+                    transact_to: transact_to(kind),
+                    data: alloy_primitives::Bytes(input.0.clone()),
+                    chain_id: Some(*chain_id),
+                    nonce: Some(nonce.as_u64()),
+                    value: (*value).to_alloy(),
+                    gas_price: (*max_fee_per_gas).to_alloy(),
+                    gas_priority_fee: Some((*max_priority_fee_per_gas).to_alloy()),
+                    gas_limit: gas_limit.as_u64(),
+                    access_list: to_revm_access_list(access_list.0.clone()),
+                    blob_hashes: blob_versioned_hashes.iter().map(|hash| hash.to_alloy()).collect(),
+                    max_fee_per_blob_gas: Some((*max_fee_per_blob_gas).to_alloy()),
+                    ..Default::default()
+                }
+            }
             TypedTransaction::Deposit(tx) => {
                 let chain_id = tx.chain_id();
                 let DepositTransaction {
@@ -1498,6 +1925,62 @@ impl PendingTransaction {
     }
 }
 
+/// Breakdown of the gas used by an executed transaction
+///
+/// `gas_used = intrinsic + execution - refund`, where the refund is capped at `max_refund` as
+/// specified in [EIP-3529](https://eips.ethereum.org/EIPS/eip-3529).
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct GasDetails {
+    /// Gas charged before execution: base cost, calldata, access list and initcode
+    pub intrinsic: u64,
+    /// Gas spent executing the transaction's code
+    pub execution: u64,
+    /// Gas refunded for clearing storage, after applying the cap
+    pub refund: u64,
+    /// The maximum refund, `(intrinsic + execution) / 5` since London and `/ 2` before
+    pub max_refund: u64,
+    /// The part of the intrinsic gas charged for the access list
+    pub access_list_cost: u64,
+    /// The cold access surcharges the access list avoids if all its entries are accessed
+    pub access_list_savings: u64,
+    /// The gas used as reported by the receipt
+    pub gas_used: u64,
+}
+
+impl GasDetails {
+    /// Creates the breakdown of a transaction that used `gas_used` after a refund of `refund`
+    pub fn new(
+        spec_id: SpecId,
+        intrinsic: u64,
+        gas_used: u64,
+        refund: u64,
+        access_list: &AccessList,
+    ) -> Self {
+        let spent = gas_used + refund;
+        let max_refund = spent / if spec_id >= SpecId::LONDON { 5 } else { 2 };
+        let addresses = access_list.0.len() as u64;
+        let storage_keys =
+            access_list.0.iter().map(|item| item.storage_keys.len() as u64).sum::<u64>();
+        let (access_list_cost, access_list_savings) = if spec_id >= SpecId::BERLIN {
+            // cold account access 2600 vs. warm 100, cold sload 2100 vs. warm 100
+            (addresses * 2_400 + storage_keys * 1_900, addresses * 2_500 + storage_keys * 2_000)
+        } else {
+            (0, 0)
+        };
+        Self {
+            intrinsic,
+            execution: spent.saturating_sub(intrinsic),
+            refund,
+            max_refund,
+            access_list_cost,
+            access_list_savings,
+            gas_used,
+        }
+    }
+}
+
 /// Represents all relevant information of an executed transaction
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TransactionInfo {
@@ -1512,6 +1995,7 @@ pub struct TransactionInfo {
     pub exit: InstructionResult,
     pub out: Option<Bytes>,
     pub nonce: u64,
+    pub gas_details: GasDetails,
 }
 
 // === impl TransactionInfo ===
@@ -1850,4 +2334,15 @@ mod tests {
         let expected: Address = "0xa12e1462d0ced572f396f58b6e2d03894cd7c8a4".parse().unwrap();
         assert_eq!(expected, recovered);
     }
+
+    #[test]
+    fn test_l1_gas_used() {
+        let raw_tx = hex::decode("f9015482078b8505d21dba0083022ef1947a250d5630b4cf539739df2c5dacb4c659f2488d880c46549a521b13d8b8e47ff36ab50000000000000000000000000000000000000000000066ab5a608bd00a23f2fe000000000000000000000000000000000000000000000000000000000000008000000000000000000000000048c04ed5691981c42154c6167398f95e8f38a7ff00000000000000000000000000000000000000000000000000000000632ceac70000000000000000000000000000000000000000000000000000000000000002000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc20000000000000000000000006c6ee5e31d828de241282b9606c8e98ea48526e225a0c9077369501641a92ef7399ff81c21639ed4fd8fc69cb793cfa1dbfab342e10aa0615facb2f1bcf3274a354cfe384a38d0cc008a11c2dd23a69111bc6930ba27a8").unwrap();
+        let tx: TypedTransaction = rlp::decode(&raw_tx).unwrap();
+
+        let zeros = raw_tx.iter().filter(|b| **b == 0).count() as u64;
+        let non_zeros = raw_tx.len() as u64 - zeros;
+        let expected = zeros * 4 + non_zeros * 16 + OP_L1_FIXED_OVERHEAD;
+        assert_eq!(tx.l1_gas_used(), Some(expected.into()));
+    }
 }
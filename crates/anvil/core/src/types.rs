@@ -1,6 +1,9 @@
 use std::collections::BTreeMap;
 
-use ethers_core::types::{TxHash, H256, U256, U64};
+use ethers_core::types::{
+    Address, Block, BlockNumber, Bytes, Trace, Transaction, TransactionReceipt, TxHash, H256, U256,
+    U64,
+};
 use revm::primitives::SpecId;
 
 #[cfg(feature = "serde")]
@@ -122,6 +125,12 @@ impl From<Index> for usize {
     }
 }
 
+impl From<usize> for Index {
+    fn from(idx: usize) -> Self {
+        Index(idx)
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<'a> serde::Deserialize<'a> for Index {
     fn deserialize<D>(deserializer: D) -> Result<Index, D::Error>
@@ -204,6 +213,28 @@ pub struct NodeForkConfig {
     pub fork_url: Option<String>,
     pub fork_block_number: Option<u64>,
     pub fork_retry_backoff: Option<u128>,
+    pub cache_stats: Option<ForkCacheStats>,
+}
+
+/// Hits and misses of the queries a forked node answered from its cache instead of the remote
+/// client
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ForkCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl ForkCacheStats {
+    /// Returns the share of queries answered from the cache, between 0 and 1
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0
+        }
+        self.hits as f64 / total as f64
+    }
 }
 
 /// Anvil equivalent of `hardhat_metadata`.
@@ -222,6 +253,191 @@ pub struct AnvilMetadata {
     pub snapshots: BTreeMap<U256, (u64, H256)>,
 }
 
+/// The preconditions of a transaction sent with `eth_sendRawTransactionConditional`
+///
+/// The transaction is rejected unless all preconditions hold for the pending block, and dropped
+/// from the pool if they no longer hold when it is about to be mined.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TransactionConditional {
+    /// The expected storage of accounts
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub known_accounts: BTreeMap<Address, KnownAccount>,
+    /// The minimum block number, inclusive
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_number_min: Option<U64>,
+    /// The maximum block number, inclusive
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_number_max: Option<U64>,
+    /// The minimum block timestamp, inclusive
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub timestamp_min: Option<U64>,
+    /// The maximum block timestamp, inclusive
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub timestamp_max: Option<U64>,
+}
+
+/// A bundle of transactions sent with `eth_sendBundle`
+///
+/// The transactions are applied in order at the top of the target block, either all of them or
+/// none.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Bundle {
+    /// The signed transactions
+    pub txs: Vec<Bytes>,
+    /// The number of the block the bundle must be included in
+    pub block_number: U64,
+    /// The minimum block timestamp, inclusive
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_timestamp: Option<u64>,
+    /// The maximum block timestamp, inclusive
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_timestamp: Option<u64>,
+    /// The hashes of the transactions that are allowed to revert
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reverting_tx_hashes: Vec<TxHash>,
+}
+
+/// The response of `eth_sendBundle`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BundleHash {
+    /// The hash of the bundle, the keccak256 hash of the concatenated transaction hashes
+    pub bundle_hash: H256,
+}
+
+/// The response of `eth_signTransaction`
+///
+/// On a forked node the signature is tagged with the chain it was forked from, so that payloads
+/// signed on the fork aren't mistaken for transactions meant for the real network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum SignedTransactionResponse {
+    /// The signature of a transaction signed on a node that isn't forked
+    Signature(String),
+    /// The signature of a transaction signed on a forked node
+    Forked(ForkSignedTransaction),
+}
+
+/// A transaction signature tagged with the forked network, see [`SignedTransactionResponse`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ForkSignedTransaction {
+    /// The signature of the transaction
+    pub signature: String,
+    /// The chain id the transaction was signed for
+    pub chain_id: u64,
+    /// The chain id of the forked network
+    pub forked_chain_id: u64,
+    /// Whether the signed transaction is valid on the forked network, i.e. the chain ids match
+    pub replayable_on_fork: bool,
+}
+
+/// The expected storage of an account of a [`TransactionConditional`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum KnownAccount {
+    /// The storage root of the account
+    StorageRoot(H256),
+    /// The values of storage slots of the account
+    Slots(BTreeMap<H256, H256>),
+}
+
+/// An op-stack deposit transaction, as accepted by `anvil_depositTransaction`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DepositTransactionParams {
+    /// The sender of the deposit, which is not aliased
+    pub from: Address,
+    /// The recipient, `None` for contract creations
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub to: Option<Address>,
+    /// The amount of ETH minted to `from` on L2
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mint: U256,
+    /// The amount of ETH sent to `to`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub value: U256,
+    /// The gas limit, defaults to the block gas limit
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas: Option<U256>,
+    /// The calldata
+    #[cfg_attr(feature = "serde", serde(default, alias = "input"))]
+    pub data: Bytes,
+    /// Whether this is a system transaction
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_system_tx: bool,
+    /// The source hash that uniquely identifies the deposit, derived from the sender and its
+    /// nonce if not set
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source_hash: Option<H256>,
+}
+
+/// A single blob of a mined blob transaction, as returned by `eth_getBlobSidecars`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BlobSidecar {
+    pub block_hash: H256,
+    pub block_number: U64,
+    pub tx_hash: TxHash,
+    pub tx_index: U64,
+    /// The index of the blob within its transaction
+    pub blob_index: U64,
+    pub blob: Bytes,
+    pub kzg_commitment: Bytes,
+    pub kzg_proof: Bytes,
+    pub versioned_hash: H256,
+}
+
+/// A block mined by `anvil_mineDetailed`, with the receipts and call traces of its transactions
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct MinedBlockDetails {
+    pub block: Block<Transaction>,
+    /// The receipts of the transactions, in the order of the block
+    pub receipts: Vec<TransactionReceipt>,
+    /// The parity style call traces of all transactions of the block
+    pub traces: Vec<Trace>,
+}
+
+/// The filter of `trace_filter`
+///
+/// Traces match if their block is in the range, and their sender and receiver are in the
+/// address lists, unless the lists are empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TraceFilter {
+    /// The first block, defaults to the earliest block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub from_block: Option<BlockNumber>,
+    /// The last block, defaults to the latest block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub to_block: Option<BlockNumber>,
+    /// The senders of the traces
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub from_address: Vec<Address>,
+    /// The receivers of the traces, or the created contracts
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub to_address: Vec<Address>,
+    /// The number of matching traces to skip
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub after: Option<u64>,
+    /// The maximum number of traces to return
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub count: Option<u64>,
+}
+
 /// Information about the forked network.
 /// See <https://hardhat.org/hardhat-network/docs/reference#hardhat_metadata>
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -233,6 +449,22 @@ pub struct ForkedNetwork {
     pub fork_block_hash: TxHash,
 }
 
+/// Faults injected by the node, to test how clients handle a misbehaving node
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+pub struct FaultInjection {
+    /// The delay added to every RPC call, in milliseconds
+    pub latency: u64,
+    /// The maximum random delay added on top of `latency`, in milliseconds
+    pub latency_jitter: u64,
+    /// The probability, between 0 and 1, that an RPC call fails with a transient error
+    pub error_rate: f64,
+    /// The probability, between 0 and 1, that a submitted transaction is acknowledged but never
+    /// added to the pool
+    pub drop_rate: f64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -252,4 +484,11 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn serde_fault_injection() {
+        let s = r#"{"latency": 100, "errorRate": 0.5}"#;
+        let faults: FaultInjection = serde_json::from_str(s).unwrap();
+        assert_eq!(faults, FaultInjection { latency: 100, error_rate: 0.5, ..Default::default() });
+    }
 }
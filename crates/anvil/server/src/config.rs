@@ -1,6 +1,6 @@
 use crate::HeaderValue;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 /// Additional server options.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +24,36 @@ pub struct ServerConfig {
         clap(long, help = "Disable CORS", conflicts_with = "allow-origin")
     )]
     pub no_cors: bool,
+    /// The maximum size of a request body or websocket message in bytes
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "The maximum size of a request body or websocket message in bytes \
+                    [default: 2 MiB]",
+            value_name = "BYTES"
+        )
+    )]
+    pub max_request_size: Option<usize>,
+    /// The maximum number of calls in a batch request
+    #[cfg_attr(
+        feature = "clap",
+        clap(long, help = "The maximum number of calls in a batch request", value_name = "NUM")
+    )]
+    pub max_batch_size: Option<usize>,
+    /// The maximum number of calls per second of RPC methods by each client
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long = "rate-limit",
+            help = "Limit the calls per second of an RPC method by each client, e.g. \
+                    `eth_call=10`. `*=N` limits the calls of all methods without their own \
+                    limit together",
+            value_name = "METHOD=NUM"
+        )
+    )]
+    #[serde(default)]
+    pub rate_limits: Vec<MethodRateLimit>,
 }
 
 // === impl ServerConfig ===
@@ -40,11 +70,71 @@ impl ServerConfig {
         self.no_cors = cors;
         self
     }
+
+    /// Sets the maximum size of a request body or websocket message in bytes
+    pub fn with_max_request_size(mut self, max_request_size: Option<usize>) -> Self {
+        self.max_request_size = max_request_size;
+        self
+    }
+
+    /// Sets the maximum number of calls in a batch request
+    pub fn with_max_batch_size(mut self, max_batch_size: Option<usize>) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// Adds a limit of the calls per second of an RPC method
+    pub fn with_rate_limit(mut self, rate_limit: MethodRateLimit) -> Self {
+        self.rate_limits.push(rate_limit);
+        self
+    }
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
-        Self { allow_origin: "*".parse::<HeaderValue>().unwrap().into(), no_cors: false }
+        Self {
+            allow_origin: "*".parse::<HeaderValue>().unwrap().into(),
+            no_cors: false,
+            max_request_size: None,
+            max_batch_size: None,
+            rate_limits: Vec::new(),
+        }
+    }
+}
+
+/// The maximum number of calls per second of an RPC method, `*` applies to all methods without a
+/// limit of their own
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodRateLimit {
+    /// The name of the method, or `*`
+    pub method: String,
+    /// The maximum number of calls per second
+    pub per_second: u32,
+}
+
+impl MethodRateLimit {
+    /// The method name that matches all methods without a limit of their own
+    pub const ANY_METHOD: &'static str = "*";
+}
+
+impl FromStr for MethodRateLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (method, per_second) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid rate limit `{s}`, expected `<METHOD>=<NUM>`"))?;
+        let per_second = per_second
+            .trim()
+            .parse()
+            .map_err(|err| format!("invalid number of calls in rate limit `{s}`: {err}"))?;
+        Ok(Self { method: method.trim().to_string(), per_second })
+    }
+}
+
+impl fmt::Display for MethodRateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.method, self.per_second)
     }
 }
 
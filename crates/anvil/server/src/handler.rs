@@ -1,25 +1,43 @@
-use crate::RpcHandler;
+use crate::{limits::RequestLimits, RpcHandler};
 use anvil_rpc::{
-    error::RpcError,
+    error::{ErrorCode, RpcError},
     request::{Request, RpcCall},
     response::{Response, RpcResponse},
 };
 use axum::{
-    extract::{rejection::JsonRejection, State},
+    extract::{rejection::JsonRejection, ConnectInfo, State},
+    http::StatusCode,
     Json,
 };
 use futures::{future, FutureExt};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 
 /// Handles incoming JSON-RPC Request.
 // NOTE: `handler` must come first because the `request` extractor consumes the request body.
 pub async fn handle<Http: RpcHandler, Ws>(
-    State((handler, _)): State<(Http, Ws)>,
+    State((handler, _, limits)): State<(Http, Ws, Arc<RequestLimits>)>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     request: Result<Json<Request>, JsonRejection>,
 ) -> Json<Response> {
     Json(match request {
-        Ok(Json(req)) => handle_request(req, handler)
+        Ok(Json(req)) => handle_request(req, handler, &limits, Some(addr.ip()))
             .await
             .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
+        Err(err) if err.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+            warn!(target: "rpc", ?err, "request too large");
+            Response::error(RpcError {
+                code: ErrorCode::InvalidRequest,
+                message: format!(
+                    "request body exceeds the maximum of {} bytes",
+                    limits.max_request_size()
+                )
+                .into(),
+                data: None,
+            })
+        }
         Err(err) => {
             warn!(target: "rpc", ?err, "invalid request");
             Response::error(RpcError::invalid_request())
@@ -30,10 +48,12 @@ pub async fn handle<Http: RpcHandler, Ws>(
 /// Handle the JSON-RPC [Request]
 ///
 /// This will try to deserialize the payload into the request type of the handler and if successful
-/// invoke the handler
+/// invoke the handler, unless the request exceeds the [RequestLimits] of the client
 pub async fn handle_request<Handler: RpcHandler>(
     req: Request,
     handler: Handler,
+    limits: &RequestLimits,
+    client: Option<IpAddr>,
 ) -> Option<Response> {
     /// processes batch calls
     fn responses_as_batch(outs: Vec<Option<RpcResponse>>) -> Option<Response> {
@@ -42,19 +62,41 @@ pub async fn handle_request<Handler: RpcHandler>(
     }
 
     match req {
-        Request::Single(call) => handle_call(call, handler).await.map(Response::Single),
+        Request::Single(call) => {
+            handle_call(call, handler, limits, client).await.map(Response::Single)
+        }
         Request::Batch(calls) => {
-            future::join_all(calls.into_iter().map(move |call| handle_call(call, handler.clone())))
-                .map(responses_as_batch)
-                .await
+            if let Err(err) = limits.check_batch(calls.len()) {
+                warn!(target: "rpc", len = calls.len(), "batch too large");
+                return Some(Response::error(err))
+            }
+            future::join_all(
+                calls
+                    .into_iter()
+                    .map(move |call| handle_call(call, handler.clone(), limits, client)),
+            )
+            .map(responses_as_batch)
+            .await
         }
     }
 }
 
 /// handle a single RPC method call
-async fn handle_call<Handler: RpcHandler>(call: RpcCall, handler: Handler) -> Option<RpcResponse> {
+async fn handle_call<Handler: RpcHandler>(
+    call: RpcCall,
+    handler: Handler,
+    limits: &RequestLimits,
+    client: Option<IpAddr>,
+) -> Option<RpcResponse> {
     match call {
         RpcCall::MethodCall(call) => {
+            if let Err(err) = limits.check_call(client, &call.method) {
+                warn!(
+                    target: "rpc", id = ?call.id, method = ?call.method, ?client,
+                    "rate limit exceeded"
+                );
+                return Some(RpcResponse::new(call.id, err))
+            }
             trace!(target: "rpc", id = ?call.id , method = ?call.method,  "handling call");
             Some(handler.on_call(call).await)
         }
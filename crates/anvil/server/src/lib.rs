@@ -11,13 +11,14 @@ use anvil_rpc::{
     response::{ResponseResult, RpcResponse},
 };
 use axum::{
+    extract::{connect_info::IntoMakeServiceWithConnectInfo, DefaultBodyLimit},
     http::{header, HeaderValue, Method},
-    routing::{post, IntoMakeService},
+    routing::post,
     Router, Server,
 };
 use hyper::server::conn::AddrIncoming;
 use serde::de::DeserializeOwned;
-use std::{fmt, net::SocketAddr};
+use std::{fmt, net::SocketAddr, sync::Arc};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 
 mod config;
@@ -27,14 +28,18 @@ mod error;
 mod handler;
 #[cfg(feature = "ipc")]
 pub mod ipc;
+/// request size and rate limits
+mod limits;
 mod pubsub;
 mod ws;
 
 pub use crate::pubsub::{PubSubContext, PubSubRpcHandler};
-pub use config::ServerConfig;
+pub use config::{MethodRateLimit, ServerConfig};
+pub use limits::{RequestLimits, DEFAULT_MAX_REQUEST_SIZE};
 
-/// Type alias for the configured axum server
-pub type AnvilServer = Server<AddrIncoming, IntoMakeService<Router>>;
+/// Type alias for the configured axum server, which passes the address of the client to the
+/// handlers for the rate limits
+pub type AnvilServer = Server<AddrIncoming, IntoMakeServiceWithConnectInfo<Router, SocketAddr>>;
 
 /// Configures an [axum::Server] that handles RPC-Calls, both HTTP requests and requests via
 /// websocket
@@ -48,11 +53,13 @@ where
     Http: RpcHandler,
     Ws: PubSubRpcHandler,
 {
-    let ServerConfig { allow_origin, no_cors } = config;
+    let limits = Arc::new(RequestLimits::new(&config));
+    let ServerConfig { allow_origin, no_cors, .. } = config;
 
     let svc = Router::new()
         .route("/", post(handler::handle).get(ws::handle_ws))
-        .with_state((http, ws))
+        .with_state((http, ws, Arc::clone(&limits)))
+        .layer(DefaultBodyLimit::max(limits.max_request_size()))
         .layer(TraceLayer::new_for_http());
 
     let svc = if no_cors {
@@ -67,7 +74,7 @@ where
                 .allow_methods(vec![Method::GET, Method::POST]),
         )
     }
-    .into_make_service();
+    .into_make_service_with_connect_info::<SocketAddr>();
     Server::bind(&addr).serve(svc)
 }
 
@@ -76,11 +83,13 @@ pub fn serve_http<Http>(addr: SocketAddr, config: ServerConfig, http: Http) -> A
 where
     Http: RpcHandler,
 {
-    let ServerConfig { allow_origin, no_cors } = config;
+    let limits = Arc::new(RequestLimits::new(&config));
+    let ServerConfig { allow_origin, no_cors, .. } = config;
 
     let svc = Router::new()
         .route("/", post(handler::handle))
-        .with_state((http, ()))
+        .with_state((http, (), Arc::clone(&limits)))
+        .layer(DefaultBodyLimit::max(limits.max_request_size()))
         .layer(TraceLayer::new_for_http());
     let svc = if no_cors {
         svc
@@ -94,7 +103,7 @@ where
                 .allow_methods(vec![Method::GET, Method::POST]),
         )
     }
-    .into_make_service();
+    .into_make_service_with_connect_info::<SocketAddr>();
 
     Server::bind(&addr).serve(svc)
 }
@@ -0,0 +1,164 @@
+use crate::config::{MethodRateLimit, ServerConfig};
+use anvil_rpc::error::{ErrorCode, RpcError};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+/// The default maximum size of a request body or websocket message, which is axum's default body
+/// limit
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 2 * 1024 * 1024;
+
+/// The error code of requests that exceed a limit, see [EIP-1474](https://eips.ethereum.org/EIPS/eip-1474)
+const LIMIT_EXCEEDED: i64 = -32005;
+
+/// The limits of the calls a server accepts, shared by all connections
+#[derive(Debug)]
+pub struct RequestLimits {
+    /// the maximum size of a request body or websocket message
+    max_request_size: usize,
+    /// the maximum number of calls in a batch
+    max_batch_size: Option<usize>,
+    /// the maximum number of calls per second by method
+    rate_limits: HashMap<String, u32>,
+    /// the start of the current window and the number of calls in it, by client and configured
+    /// method, which is [MethodRateLimit::ANY_METHOD] for methods without their own limit
+    windows: Mutex<HashMap<(Option<IpAddr>, String), (Instant, u32)>>,
+}
+
+// === impl RequestLimits ===
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self::new(&ServerConfig::default())
+    }
+}
+
+impl RequestLimits {
+    /// Creates the limits configured in the [ServerConfig]
+    pub fn new(config: &ServerConfig) -> Self {
+        let rate_limits = config
+            .rate_limits
+            .iter()
+            .map(|MethodRateLimit { method, per_second }| (method.clone(), *per_second))
+            .collect();
+        Self {
+            max_request_size: config.max_request_size.unwrap_or(DEFAULT_MAX_REQUEST_SIZE),
+            max_batch_size: config.max_batch_size,
+            rate_limits,
+            windows: Default::default(),
+        }
+    }
+
+    /// Returns the maximum size of a request body or websocket message in bytes
+    pub fn max_request_size(&self) -> usize {
+        self.max_request_size
+    }
+
+    /// Returns an error if a batch has more calls than allowed
+    pub fn check_batch(&self, len: usize) -> Result<(), RpcError> {
+        match self.max_batch_size {
+            Some(max) if len > max => Err(RpcError {
+                code: ErrorCode::InvalidRequest,
+                message: format!("batch of {len} calls exceeds the maximum of {max}").into(),
+                data: None,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records a call of the method by the client and returns an error if the client called the
+    /// method more often than allowed in the current second.
+    ///
+    /// Methods without their own limit share the budget of the wildcard limit.
+    pub fn check_call(&self, client: Option<IpAddr>, method: &str) -> Result<(), RpcError> {
+        let Some((key, limit)) = self
+            .rate_limits
+            .get_key_value(method)
+            .or_else(|| self.rate_limits.get_key_value(MethodRateLimit::ANY_METHOD))
+        else {
+            return Ok(())
+        };
+        let limit = *limit;
+
+        let now = Instant::now();
+        let mut windows = self.windows.lock();
+        let key = (client, key.clone());
+        if !windows.contains_key(&key) {
+            // forget the windows that ended so the windows of past clients don't pile up
+            windows.retain(|_, (start, _)| now.duration_since(*start) < Duration::from_secs(1));
+        }
+        let (start, calls) = windows.entry(key).or_insert((now, 0));
+        if now.duration_since(*start) >= Duration::from_secs(1) {
+            *start = now;
+            *calls = 0;
+        }
+        if *calls >= limit {
+            return Err(RpcError {
+                code: ErrorCode::ServerError(LIMIT_EXCEEDED),
+                message: format!("rate limit of {limit} calls per second exceeded for {method}")
+                    .into(),
+                data: None,
+            })
+        }
+        *calls += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_rate_limit() {
+        let limit: MethodRateLimit = "eth_call=10".parse().unwrap();
+        assert_eq!(limit, MethodRateLimit { method: "eth_call".to_string(), per_second: 10 });
+        assert_eq!(limit.to_string(), "eth_call=10");
+        assert!("eth_call".parse::<MethodRateLimit>().is_err());
+        assert!("eth_call=ten".parse::<MethodRateLimit>().is_err());
+    }
+
+    #[test]
+    fn limits_calls_per_method() {
+        let config = ServerConfig::default()
+            .with_rate_limit("eth_call=2".parse().unwrap())
+            .with_rate_limit("*=1".parse().unwrap());
+        let limits = RequestLimits::new(&config);
+
+        let client = Some(IpAddr::from([127, 0, 0, 1]));
+
+        assert!(limits.check_call(client, "eth_call").is_ok());
+        assert!(limits.check_call(client, "eth_call").is_ok());
+        let err = limits.check_call(client, "eth_call").unwrap_err();
+        assert_eq!(err.code, ErrorCode::ServerError(LIMIT_EXCEEDED));
+
+        // methods without their own limit share the budget of the wildcard limit
+        assert!(limits.check_call(client, "eth_chainId").is_ok());
+        assert!(limits.check_call(client, "eth_blockNumber").is_err());
+        assert!(limits.check_call(client, "eth_doesNotExist").is_err());
+        assert_eq!(limits.windows.lock().len(), 2);
+    }
+
+    #[test]
+    fn limits_calls_per_client() {
+        let limits =
+            RequestLimits::new(&ServerConfig::default().with_rate_limit("*=1".parse().unwrap()));
+        let alice = Some(IpAddr::from([10, 0, 0, 1]));
+        let bob = Some(IpAddr::from([10, 0, 0, 2]));
+
+        assert!(limits.check_call(alice, "eth_chainId").is_ok());
+        assert!(limits.check_call(alice, "eth_chainId").is_err());
+        assert!(limits.check_call(bob, "eth_chainId").is_ok());
+    }
+
+    #[test]
+    fn limits_batch_size() {
+        let limits = RequestLimits::new(&ServerConfig::default().with_max_batch_size(Some(2)));
+        assert!(limits.check_batch(2).is_ok());
+        assert!(limits.check_batch(3).is_err());
+        assert!(RequestLimits::default().check_batch(1000).is_ok());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{error::RequestError, handler::handle_request, RpcHandler};
+use crate::{error::RequestError, handler::handle_request, limits::RequestLimits, RpcHandler};
 use anvil_rpc::{
     error::RpcError,
     request::Request,
@@ -13,6 +13,7 @@ use std::{
     fmt,
     future::Future,
     hash::Hash,
+    net::IpAddr,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -123,6 +124,10 @@ pub struct PubSubConnection<Handler: PubSubRpcHandler, Connection> {
     processing: Vec<Pin<Box<dyn Future<Output = Response> + Send>>>,
     /// pending messages to send
     pending: VecDeque<String>,
+    /// the limits of the requests of the connection
+    limits: Arc<RequestLimits>,
+    /// the address of the client the limits are tracked by
+    client: Option<IpAddr>,
 }
 
 // === impl PubSubConnection ===
@@ -135,9 +140,18 @@ impl<Handler: PubSubRpcHandler, Connection> PubSubConnection<Handler, Connection
             context: Default::default(),
             pending: Default::default(),
             processing: Default::default(),
+            limits: Default::default(),
+            client: None,
         }
     }
 
+    /// Sets the limits of the requests of the connection and the client they're tracked by
+    pub fn with_limits(mut self, limits: Arc<RequestLimits>, client: Option<IpAddr>) -> Self {
+        self.limits = limits;
+        self.client = client;
+        self
+    }
+
     /// Returns a compatibility `RpcHandler`
     fn compat_helper(&self) -> ContextAwareHandler<Handler> {
         ContextAwareHandler { handler: self.handler.clone(), context: self.context.clone() }
@@ -145,9 +159,11 @@ impl<Handler: PubSubRpcHandler, Connection> PubSubConnection<Handler, Connection
 
     fn process_request(&mut self, req: serde_json::Result<Request>) {
         let handler = self.compat_helper();
+        let limits = Arc::clone(&self.limits);
+        let client = self.client;
         self.processing.push(Box::pin(async move {
             match req {
-                Ok(req) => handle_request(req, handler)
+                Ok(req) => handle_request(req, handler, &limits, client)
                     .await
                     .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
                 Err(err) => {
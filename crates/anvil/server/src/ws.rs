@@ -1,15 +1,19 @@
-use crate::{error::RequestError, pubsub::PubSubConnection, PubSubRpcHandler};
+use crate::{
+    error::RequestError, limits::RequestLimits, pubsub::PubSubConnection, PubSubRpcHandler,
+};
 use anvil_rpc::request::Request;
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        State, WebSocketUpgrade,
+        ConnectInfo, State, WebSocketUpgrade,
     },
     response::Response,
 };
 use futures::{ready, Sink, Stream};
 use std::{
+    net::SocketAddr,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -18,9 +22,12 @@ use std::{
 /// This is the entrypoint invoked by the axum server for a websocket request
 pub async fn handle_ws<Http, Ws: PubSubRpcHandler>(
     ws: WebSocketUpgrade,
-    State((_, handler)): State<(Http, Ws)>,
+    State((_, handler, limits)): State<(Http, Ws, Arc<RequestLimits>)>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
 ) -> Response {
-    ws.on_upgrade(|socket| PubSubConnection::new(SocketConn(socket), handler))
+    ws.max_message_size(limits.max_request_size()).on_upgrade(move |socket| {
+        PubSubConnection::new(SocketConn(socket), handler).with_limits(limits, Some(addr.ip()))
+    })
 }
 
 #[pin_project::pin_project]
@@ -0,0 +1,237 @@
+//! Chain presets for `anvil --chain <name>`
+//!
+//! Presets are read from the built-in registry in `chains.toml`, and from the user's registry
+//! files in `~/.foundry/chains/*.toml`, which replace the built-in presets with the same name.
+//!
+//! Precompile quirks are out of scope: every preset runs with the Ethereum precompiles of its
+//! hardfork.
+
+use crate::Hardfork;
+use ethers::types::U256;
+use foundry_config::{Chain, Config};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The built-in chain presets
+const BUILTIN_CHAINS: &str = include_str!("chains.toml");
+
+/// The settings of a chain, selected with `--chain <name>`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainPreset {
+    /// The name of the preset
+    #[serde(skip)]
+    pub name: String,
+    /// The chain ID
+    pub chain_id: u64,
+    /// Hardfork name -> activation block
+    #[serde(default)]
+    pub hardforks: BTreeMap<String, u64>,
+    /// The decimals of the native gas token
+    #[serde(default = "default_gas_token_decimals")]
+    pub gas_token_decimals: u8,
+    /// Whether this is an OP stack chain, which supports deposit transactions
+    #[serde(default)]
+    pub optimism: bool,
+    /// The contract code size limit, if it differs from EIP-170
+    #[serde(default)]
+    pub code_size_limit: Option<usize>,
+}
+
+fn default_gas_token_decimals() -> u8 {
+    18
+}
+
+// === impl ChainPreset ===
+
+impl ChainPreset {
+    /// Returns the preset of a chain that is only known by its ID
+    pub fn from_chain_id(name: impl Into<String>, chain_id: u64) -> Self {
+        Self {
+            name: name.into(),
+            chain_id,
+            gas_token_decimals: default_gas_token_decimals(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the latest hardfork that is activated at the given block, if any
+    pub fn hardfork_at(&self, block: u64) -> Option<Hardfork> {
+        self.hardforks
+            .iter()
+            .filter(|(_, activation)| **activation <= block)
+            .filter_map(|(name, activation)| Some((*activation, Hardfork::from_str(name).ok()?)))
+            .max_by_key(|(activation, hardfork)| (*activation, *hardfork as u8))
+            .map(|(_, hardfork)| hardfork)
+    }
+
+    /// Returns the amount of the smallest unit of the gas token in one whole token
+    pub fn gas_token_unit(&self) -> U256 {
+        U256::exp10(self.gas_token_decimals as usize)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for name in self.hardforks.keys() {
+            Hardfork::from_str(name).map_err(|err| format!("chain `{}`: {err}", self.name))?;
+        }
+        if self.gas_token_decimals > 77 {
+            return Err(format!(
+                "chain `{}`: gas token decimals must be at most 77, got {}",
+                self.name, self.gas_token_decimals
+            ))
+        }
+        Ok(())
+    }
+
+    /// The clap `value_parser` function
+    ///
+    /// Resolves the preset from the chains registry, falling back to a known chain name or ID.
+    pub(crate) fn parse(s: &str) -> Result<Self, String> {
+        let registry = ChainRegistry::load()?;
+        if let Some(preset) = registry.get(s) {
+            return Ok(preset.clone())
+        }
+        let chain = Chain::from_str(s).map_err(|_| {
+            format!(
+                "unknown chain `{s}`, expected a chain ID or one of: {}",
+                registry.names().collect::<Vec<_>>().join(", ")
+            )
+        })?;
+        Ok(Self::from_chain_id(s, chain.id()))
+    }
+}
+
+/// All known chain presets, by name
+#[derive(Clone, Debug, Default)]
+pub struct ChainRegistry {
+    chains: BTreeMap<String, ChainPreset>,
+}
+
+// === impl ChainRegistry ===
+
+impl ChainRegistry {
+    /// Returns the built-in presets
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.extend_from_str(BUILTIN_CHAINS).expect("built-in chains registry is valid");
+        registry
+    }
+
+    /// Returns the built-in presets, merged with the user's presets in `~/.foundry/chains`
+    pub fn load() -> Result<Self, String> {
+        let mut registry = Self::builtin();
+        if let Some(dir) = Self::user_dir() {
+            registry.extend_from_dir(&dir)?;
+        }
+        Ok(registry)
+    }
+
+    /// Returns the directory of the user's registry files, `~/.foundry/chains`
+    pub fn user_dir() -> Option<PathBuf> {
+        Config::foundry_dir().map(|dir| dir.join("chains"))
+    }
+
+    /// Adds the presets of all `.toml` files in the directory, replacing existing presets with
+    /// the same name
+    pub fn extend_from_dir(&mut self, dir: &Path) -> Result<(), String> {
+        if !dir.is_dir() {
+            return Ok(())
+        }
+        let entries = std::fs::read_dir(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+        let mut files = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "toml"))
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for file in files {
+            let content = std::fs::read_to_string(&file)
+                .map_err(|err| format!("{}: {err}", file.display()))?;
+            self.extend_from_str(&content).map_err(|err| format!("{}: {err}", file.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Adds the presets of a registry file, replacing existing presets with the same name
+    pub fn extend_from_str(&mut self, content: &str) -> Result<(), String> {
+        let chains: BTreeMap<String, ChainPreset> =
+            toml::from_str(content).map_err(|err| err.to_string())?;
+        for (name, mut preset) in chains {
+            preset.name = name.clone();
+            preset.validate()?;
+            self.chains.insert(name, preset);
+        }
+        Ok(())
+    }
+
+    /// Returns the preset with the given name
+    pub fn get(&self, name: &str) -> Option<&ChainPreset> {
+        self.chains.get(name)
+    }
+
+    /// Returns the names of all presets
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.chains.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_builtin_chains() {
+        let registry = ChainRegistry::builtin();
+        let mainnet = registry.get("mainnet").unwrap();
+        assert_eq!(mainnet.chain_id, 1);
+        assert_eq!(mainnet.hardfork_at(0), None);
+        assert_eq!(mainnet.hardfork_at(15537394), Some(Hardfork::Paris));
+        assert_eq!(mainnet.hardfork_at(u64::MAX), Some(Hardfork::Cancun));
+        assert_eq!(mainnet.hardforks["cancun"], Hardfork::Cancun.fork_block());
+
+        let optimism = registry.get("optimism").unwrap();
+        assert!(optimism.optimism);
+        assert_eq!(optimism.gas_token_decimals, 18);
+        assert_eq!(optimism.hardfork_at(105235063), Some(Hardfork::Paris));
+        assert_eq!(optimism.hardfork_at(u64::MAX), Some(Hardfork::Cancun));
+    }
+
+    #[test]
+    fn user_chains_replace_builtin_chains() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("custom.toml"),
+            r#"
+[appchain]
+chain_id = 424242
+hardforks = { london = 0, shanghai = 0 }
+gas_token_decimals = 6
+
+[base]
+chain_id = 84532
+"#,
+        )
+        .unwrap();
+
+        let mut registry = ChainRegistry::builtin();
+        registry.extend_from_dir(dir.path()).unwrap();
+
+        let appchain = registry.get("appchain").unwrap();
+        assert_eq!(appchain.chain_id, 424242);
+        assert_eq!(appchain.hardfork_at(0), Some(Hardfork::Shanghai));
+        assert_eq!(appchain.gas_token_unit(), U256::from(1_000_000u64));
+        assert_eq!(registry.get("base").unwrap().chain_id, 84532);
+        assert!(!registry.get("base").unwrap().optimism);
+    }
+
+    #[test]
+    fn rejects_unknown_hardforks() {
+        let mut registry = ChainRegistry::default();
+        let err = registry.extend_from_str("[custom]\nchain_id = 1\nhardforks = { foo = 0 }");
+        assert!(err.unwrap_err().contains("Unknown hardfork foo"));
+    }
+}
@@ -1,16 +1,25 @@
 use crate::{
+    chains::ChainPreset,
     config::DEFAULT_MNEMONIC,
     eth::{backend::db::SerializableState, pool::transactions::TransactionOrder, EthApi},
     genesis::Genesis,
+    logging::LogFormat,
+    predeploys::Predeploy,
     AccountGenerator, Hardfork, NodeConfig, CHAIN_ID,
 };
+use anvil_core::types::FaultInjection;
 use anvil_server::ServerConfig;
 use clap::Parser;
 use core::fmt;
 use ethers::{
-    signers::coins_bip39::{English, Mnemonic},
+    core::k256::ecdsa::SigningKey,
+    signers::{
+        coins_bip39::{English, Mnemonic},
+        Wallet,
+    },
     utils::WEI_IN_ETHER,
 };
+use foundry_common::sigv4::SigV4Signer;
 use foundry_config::{Chain, Config};
 use futures::FutureExt;
 use rand::{rngs::StdRng, SeedableRng};
@@ -73,10 +82,39 @@ pub struct NodeArgs {
     #[clap(long)]
     pub derivation_path: Option<String>,
 
+    /// Keystore files, or directories of keystore files, to load as accounts managed by the node.
+    ///
+    /// The accounts are funded like the dev accounts and can sign with `eth_sign`,
+    /// `eth_signTypedData`, `eth_sendTransaction` and the `personal_*` endpoints, but their
+    /// private keys are never printed.
+    #[clap(long, value_name = "PATH", num_args(1..))]
+    pub keystore: Vec<PathBuf>,
+
+    /// The password of the keystores.
+    #[clap(
+        long,
+        env = "ANVIL_KEYSTORE_PASSWORD",
+        value_name = "PASSWORD",
+        requires = "keystore",
+        conflicts_with = "keystore_password_file"
+    )]
+    pub keystore_password: Option<String>,
+
+    /// A file with the password of the keystores.
+    #[clap(long, value_name = "PATH", requires = "keystore")]
+    pub keystore_password_file: Option<PathBuf>,
+
     /// Don't print anything on startup and don't print logs
     #[clap(long)]
     pub silent: bool,
 
+    /// The format of the logs: `text` or `json`.
+    ///
+    /// JSON logs include the structured fields of the node's events, e.g. the hash, gas used and
+    /// revert reason of mined transactions. The logged events can be filtered with `RUST_LOG`.
+    #[clap(long, value_name = "FORMAT", default_value = "text", value_parser = LogFormat::from_str)]
+    pub log_format: LogFormat,
+
     /// The EVM hardfork to use.
     ///
     /// Choose the hardfork by name, e.g. `shanghai`, `paris`, `london`, etc...
@@ -140,9 +178,22 @@ pub struct NodeArgs {
     /// Dump the state of chain on exit to the given file.
     ///
     /// If the value is a directory, the state will be written to `<VALUE>/state.json`.
+    ///
+    /// The state is also dumped on SIGTERM and every `--state-interval`. Dumps are written
+    /// atomically and synced to disk, so an interrupted dump never replaces the previous one.
     #[clap(long, value_name = "PATH", conflicts_with = "init")]
     pub dump_state: Option<PathBuf>,
 
+    /// Export the mined blocks on exit to the given file, as concatenated RLP.
+    ///
+    /// The genesis of the chain is exported next to it, e.g. to `chain.genesis.json` for
+    /// `chain.rlp`. Other clients can be initialized with the genesis, e.g. with `geth init`, and
+    /// then import the blocks with `geth import` or `reth import` to replay the chain history.
+    /// The genesis isn't exported when forking. See also `anvil_exportBlocks` and
+    /// `anvil_exportGenesis`.
+    #[clap(long, value_name = "PATH")]
+    pub export_chain: Option<PathBuf>,
+
     /// Initialize the chain from a previously saved state snapshot.
     #[clap(
         long,
@@ -152,6 +203,20 @@ pub struct NodeArgs {
     )]
     pub load_state: Option<SerializableState>,
 
+    /// Deploy canonical infrastructure contracts at genesis.
+    ///
+    /// One of `multicall3` or `permit2`. Their code is copied from the chain of
+    /// `--predeploys-rpc-url`, or the forked chain. Contracts that are already deployed on the
+    /// forked chain are left untouched.
+    #[clap(long, value_name = "NAME", value_delimiter = ',', num_args(1..))]
+    pub predeploys: Vec<Predeploy>,
+
+    /// The RPC endpoint of a chain with the predeploys deployed, used to copy their code.
+    ///
+    /// Defaults to the forked endpoint.
+    #[clap(long, value_name = "URL")]
+    pub predeploys_rpc_url: Option<String>,
+
     #[clap(long, help = IPC_HELP, value_name = "PATH", visible_alias = "ipcpath")]
     pub ipc: Option<Option<String>>,
 
@@ -164,6 +229,39 @@ pub struct NodeArgs {
     #[clap(long)]
     pub transaction_block_keeper: Option<usize>,
 
+    /// Delay every RPC call by the given number of milliseconds.
+    ///
+    /// Faults can also be configured at runtime with `anvil_setFaultInjection`.
+    #[clap(long, value_name = "MS", default_value = "0", help_heading = "Fault injection")]
+    pub rpc_latency: u64,
+
+    /// Delay every RPC call by up to the given number of milliseconds more, picked at random.
+    #[clap(long, value_name = "MS", default_value = "0", help_heading = "Fault injection")]
+    pub rpc_latency_jitter: u64,
+
+    /// The probability, between 0 and 1, that an RPC call fails with a transient error.
+    ///
+    /// The error has the JSON-RPC code 429, which clients retry like a rate limit.
+    #[clap(
+        long,
+        value_name = "RATE",
+        default_value = "0",
+        value_parser = parse_rate,
+        help_heading = "Fault injection"
+    )]
+    pub rpc_error_rate: f64,
+
+    /// The probability, between 0 and 1, that a submitted transaction is acknowledged with its
+    /// hash, but silently dropped instead of added to the pool.
+    #[clap(
+        long,
+        value_name = "RATE",
+        default_value = "0",
+        value_parser = parse_rate,
+        help_heading = "Fault injection"
+    )]
+    pub drop_tx_rate: f64,
+
     #[clap(flatten)]
     pub evm_opts: AnvilEvmArgs,
 
@@ -184,59 +282,155 @@ const DEFAULT_DUMP_INTERVAL: Duration = Duration::from_secs(60);
 
 impl NodeArgs {
     pub fn into_node_config(self) -> NodeConfig {
-        let genesis_balance = WEI_IN_ETHER.saturating_mul(self.balance.into());
+        let genesis_balance = self
+            .evm_opts
+            .chain_preset
+            .as_ref()
+            .map_or(WEI_IN_ETHER, ChainPreset::gas_token_unit)
+            .saturating_mul(self.balance.into());
+        let fork_block_number = self
+            .evm_opts
+            .fork_block_number
+            .or_else(|| self.evm_opts.fork_url.as_ref().and_then(|f| f.block));
+        // without an explicit hardfork, use the chain's hardfork active at the fork block, or its
+        // latest hardfork
+        let hardfork = self.hardfork.or_else(|| {
+            self.evm_opts
+                .chain_preset
+                .as_ref()
+                .and_then(|chain| chain.hardfork_at(fork_block_number.unwrap_or(u64::MAX)))
+        });
+        let chain_id = self.chain_id();
+        let code_size_limit = self
+            .evm_opts
+            .code_size_limit
+            .or_else(|| self.evm_opts.chain.as_ref().and_then(|chain| chain.code_size_limit));
+        let optimism =
+            self.evm_opts.optimism || self.evm_opts.chain.as_ref().map_or(false, |c| c.optimism);
         let compute_units_per_second = if self.evm_opts.no_rate_limit {
             Some(u64::MAX)
         } else {
             self.evm_opts.compute_units_per_second
         };
+        let fork_etherscan_api_key = if self.evm_opts.fork_etherscan_labels {
+            self.evm_opts.etherscan_api_key.clone().or_else(|| Config::load().etherscan_api_key)
+        } else {
+            None
+        };
 
         NodeConfig::default()
             .with_gas_limit(self.evm_opts.gas_limit)
             .disable_block_gas_limit(self.evm_opts.disable_block_gas_limit)
+            .disable_strict_validation(self.evm_opts.disable_strict_validation)
+            .with_report_gas_details(self.evm_opts.report_gas_details)
             .with_gas_price(self.evm_opts.gas_price)
-            .with_hardfork(self.hardfork)
+            .with_hardfork(hardfork)
             .with_blocktime(self.block_time.map(Duration::from_secs))
             .with_no_mining(self.no_mining)
             .with_account_generator(self.account_generator())
             .with_genesis_balance(genesis_balance)
             .with_genesis_timestamp(self.timestamp)
             .with_port(self.port)
-            .with_fork_block_number(
-                self.evm_opts
-                    .fork_block_number
-                    .or_else(|| self.evm_opts.fork_url.as_ref().and_then(|f| f.block)),
-            )
-            .with_fork_headers(self.evm_opts.fork_headers)
+            .with_fork_block_number(fork_block_number)
+            .with_fork_headers(self.evm_opts.fork_headers.clone())
+            .with_fork_bearer_token(self.evm_opts.fork_bearer_token.clone())
+            .with_fork_sigv4(self.evm_opts.fork_sigv4())
             .with_fork_chain_id(self.evm_opts.fork_chain_id.map(u64::from))
+            .with_allow_chain_id_mismatch(self.evm_opts.allow_chain_id_mismatch)
             .fork_request_timeout(self.evm_opts.fork_request_timeout.map(Duration::from_millis))
             .fork_request_retries(self.evm_opts.fork_request_retries)
             .fork_retry_backoff(self.evm_opts.fork_retry_backoff.map(Duration::from_millis))
             .fork_compute_units_per_second(compute_units_per_second)
+            .with_fork_etherscan_api_key(fork_etherscan_api_key)
+            .with_fork_follow_mempool(self.evm_opts.fork_follow_mempool)
             .with_eth_rpc_url(self.evm_opts.fork_url.map(|fork| fork.url))
             .with_base_fee(self.evm_opts.block_base_fee_per_gas)
             .with_storage_caching(self.evm_opts.no_storage_caching)
             .with_server_config(self.server_config)
             .with_host(self.host)
             .set_silent(self.silent)
+            .with_log_format(self.log_format)
             .set_config_out(self.config_out)
-            .with_chain_id(self.evm_opts.chain_id)
+            .with_chain_id(chain_id)
             .with_transaction_order(self.order)
             .with_genesis(self.init)
             .with_steps_tracing(self.evm_opts.steps_tracing)
             .with_auto_impersonate(self.evm_opts.auto_impersonate)
             .with_ipc(self.ipc)
-            .with_code_size_limit(self.evm_opts.code_size_limit)
+            .with_code_size_limit(code_size_limit)
+            .with_initcode_size_limit(self.evm_opts.initcode_size_limit)
             .set_pruned_history(self.prune_history)
             .with_init_state(self.load_state.or_else(|| self.state.and_then(|s| s.state)))
             .with_transaction_block_keeper(self.transaction_block_keeper)
-            .with_optimism(self.evm_opts.optimism)
+            .with_predeploys(self.predeploys)
+            .with_predeploys_rpc_url(self.predeploys_rpc_url)
+            .with_optimism(optimism)
+            .with_l2_receipt_fields(self.evm_opts.l2_receipt_fields)
+            .with_l1_gas_price(self.evm_opts.l1_gas_price)
+            .with_l1_fee_scalar(self.evm_opts.l1_fee_scalar)
+            .with_fault_injection(FaultInjection {
+                latency: self.rpc_latency,
+                latency_jitter: self.rpc_latency_jitter,
+                error_rate: self.rpc_error_rate,
+                drop_rate: self.drop_tx_rate,
+            })
+    }
+
+    /// Returns the chain ID set with `--chain-id`, or the ID of the `--chain` preset
+    fn chain_id(&self) -> Option<u64> {
+        self.evm_opts
+            .chain_id
+            .map(u64::from)
+            .or_else(|| self.evm_opts.chain.as_ref().map(|chain| chain.chain_id))
+    }
+
+    /// Decrypts the keystores of `--keystore`.
+    fn keystore_accounts(&self) -> Result<Vec<Wallet<SigningKey>>, Box<dyn std::error::Error>> {
+        if self.keystore.is_empty() {
+            return Ok(vec![])
+        }
+        let password = match (&self.keystore_password, &self.keystore_password_file) {
+            (Some(password), _) => password.clone(),
+            (None, Some(file)) => std::fs::read_to_string(file)
+                .map_err(|err| {
+                    format!("failed to read the keystore password file {file:?}: {err}")
+                })?
+                .trim_end()
+                .to_string(),
+            (None, None) => {
+                return Err(
+                    "--keystore requires --keystore-password or --keystore-password-file".into()
+                )
+            }
+        };
+
+        let mut files = Vec::new();
+        for path in &self.keystore {
+            if path.is_dir() {
+                let mut entries = std::fs::read_dir(path)?
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                entries.retain(|entry| entry.is_file());
+                entries.sort();
+                files.extend(entries);
+            } else {
+                files.push(path.clone());
+            }
+        }
+
+        let mut accounts = Vec::with_capacity(files.len());
+        for file in files {
+            let wallet = Wallet::decrypt_keystore(&file, &password)
+                .map_err(|err| format!("failed to decrypt keystore {file:?}: {err}"))?;
+            accounts.push(wallet);
+        }
+        Ok(accounts)
     }
 
     fn account_generator(&self) -> AccountGenerator {
         let mut gen = AccountGenerator::new(self.accounts as usize)
             .phrase(DEFAULT_MNEMONIC)
-            .chain_id(self.evm_opts.chain_id.unwrap_or_else(|| CHAIN_ID.into()));
+            .chain_id(self.chain_id().unwrap_or(CHAIN_ID));
         if let Some(ref mnemonic) = self.mnemonic {
             gen = gen.phrase(mnemonic);
         } else if let Some(count) = self.mnemonic_random {
@@ -267,10 +461,14 @@ impl NodeArgs {
     /// See also [crate::spawn()]
     pub async fn run(self) -> Result<(), Box<dyn std::error::Error>> {
         let dump_state = self.dump_state_path();
+        let export_chain = self.export_chain.clone();
         let dump_interval =
             self.state_interval.map(Duration::from_secs).unwrap_or(DEFAULT_DUMP_INTERVAL);
 
-        let (api, mut handle) = crate::spawn(self.into_node_config()).await;
+        let keystore_accounts = self.keystore_accounts()?;
+        let mut config = self.into_node_config().with_keystore_accounts(keystore_accounts);
+        config.resolve_predeploys().await?;
+        let (api, mut handle) = crate::try_spawn(config).await?;
 
         // sets the signal handler to gracefully shutdown.
         let mut fork = api.get_fork();
@@ -283,6 +481,7 @@ impl NodeArgs {
         let task_manager = handle.task_manager();
         let mut on_shutdown = task_manager.on_shutdown();
 
+        let chain_exporter = export_chain.map(|path| (api.clone(), path));
         let mut state_dumper = PeriodicStateDumper::new(api, dump_state, dump_interval);
 
         task_manager.spawn(async move {
@@ -315,16 +514,17 @@ impl NodeArgs {
 
             // shutdown received
             state_dumper.dump().await;
+            if let Some((api, path)) = chain_exporter {
+                export_chain(api, path).await;
+            }
 
             // cleaning up and shutting down
             // this will make sure that the fork RPC cache is flushed if caching is configured
             if let Some(fork) = fork.take() {
                 trace!("flushing cache on shutdown");
-                fork.database
-                    .read()
-                    .await
-                    .maybe_flush_cache()
-                    .expect("Could not flush cache on fork DB");
+                if let Err(err) = fork.database.read().await.maybe_flush_cache() {
+                    error!(?err, "Failed to flush the cache of the fork DB");
+                }
                 // cleaning up and shutting down
                 // this will make sure that the fork RPC cache is flushed if caching is configured
             }
@@ -371,6 +571,81 @@ pub struct AnvilEvmArgs {
     )]
     pub fork_headers: Vec<String>,
 
+    /// Bearer token to send in the `Authorization` header of the requests to the fork url, e.g. a
+    /// QuickNode JWT.
+    ///
+    /// See --fork-url.
+    #[clap(
+        long,
+        env = "ANVIL_FORK_BEARER_TOKEN",
+        hide_env_values = true,
+        value_name = "TOKEN",
+        help_heading = "Fork config",
+        requires = "fork_url"
+    )]
+    pub fork_bearer_token: Option<String>,
+
+    /// Sign the requests to the fork url with AWS Signature Version 4 for the given region, e.g.
+    /// to fork from Amazon Managed Blockchain.
+    ///
+    /// The credentials are read from the standard AWS environment variables.
+    ///
+    /// See --fork-url.
+    #[clap(
+        long,
+        value_name = "REGION",
+        help_heading = "Fork config",
+        requires_all = ["fork_url", "fork_aws_access_key_id", "fork_aws_secret_access_key"]
+    )]
+    pub fork_aws_region: Option<String>,
+
+    /// The AWS service to sign the requests to the fork url for.
+    ///
+    /// See --fork-aws-region.
+    #[clap(
+        long,
+        value_name = "SERVICE",
+        default_value = "managedblockchain",
+        help_heading = "Fork config"
+    )]
+    pub fork_aws_service: String,
+
+    /// The AWS access key id to sign the requests to the fork url with.
+    ///
+    /// See --fork-aws-region.
+    #[clap(
+        long,
+        env = "AWS_ACCESS_KEY_ID",
+        hide_env_values = true,
+        value_name = "KEY",
+        help_heading = "Fork config"
+    )]
+    pub fork_aws_access_key_id: Option<String>,
+
+    /// The AWS secret access key to sign the requests to the fork url with.
+    ///
+    /// See --fork-aws-region.
+    #[clap(
+        long,
+        env = "AWS_SECRET_ACCESS_KEY",
+        hide_env_values = true,
+        value_name = "KEY",
+        help_heading = "Fork config"
+    )]
+    pub fork_aws_secret_access_key: Option<String>,
+
+    /// The AWS session token of temporary credentials to sign the requests to the fork url with.
+    ///
+    /// See --fork-aws-region.
+    #[clap(
+        long,
+        env = "AWS_SESSION_TOKEN",
+        hide_env_values = true,
+        value_name = "TOKEN",
+        help_heading = "Fork config"
+    )]
+    pub fork_aws_session_token: Option<String>,
+
     /// Timeout in ms for requests sent to remote JSON-RPC server in forking mode.
     ///
     /// Default value 45000
@@ -418,6 +693,13 @@ pub struct AnvilEvmArgs {
     )]
     pub fork_chain_id: Option<Chain>,
 
+    /// Allow a `--chain-id` that differs from the chain id of the forked network.
+    ///
+    /// Transactions signed by the node use the configured chain id, so they're valid on the
+    /// network with that chain id.
+    #[clap(long, requires = "fork_url", help_heading = "Fork config")]
+    pub allow_chain_id_mismatch: bool,
+
     /// Sets the number of assumed available compute units per second for this provider
     ///
     /// default value: 330
@@ -458,6 +740,33 @@ pub struct AnvilEvmArgs {
     #[clap(long, requires = "fork_url", help_heading = "Fork config")]
     pub no_storage_caching: bool,
 
+    /// Resolve the names and ABIs of forked contracts from Etherscan.
+    ///
+    /// They are used to label addresses and decode calls in the traces printed for mined
+    /// transactions, and to add a `labels` object to `debug_traceTransaction` results. The traces
+    /// of mined transactions are labeled in the background, so they may be printed after the
+    /// block.
+    ///
+    /// See --fork-url.
+    #[clap(long, requires = "fork_url", help_heading = "Fork config")]
+    pub fork_etherscan_labels: bool,
+
+    /// The Etherscan API key used by --fork-etherscan-labels.
+    ///
+    /// Defaults to the key of the project's configuration file.
+    #[clap(long, env = "ETHERSCAN_API_KEY", value_name = "KEY", help_heading = "Fork config")]
+    pub etherscan_api_key: Option<String>,
+
+    /// Apply the pending transactions of the remote endpoint's mempool to the fork, as they are
+    /// received.
+    ///
+    /// The transactions are validated like transactions sent to anvil, so transactions that are
+    /// invalid on the fork, e.g. because of an outdated nonce, are dropped.
+    ///
+    /// See --fork-url.
+    #[clap(long, requires = "fork_url", help_heading = "Fork config")]
+    pub fork_follow_mempool: bool,
+
     /// The block gas limit.
     #[clap(long, alias = "block-gas-limit", help_heading = "Environment config")]
     pub gas_limit: Option<u64>,
@@ -472,11 +781,34 @@ pub struct AnvilEvmArgs {
     )]
     pub disable_block_gas_limit: bool,
 
+    /// Disable the transaction validation rules that mainnet enforces but tests may
+    /// intentionally violate.
+    ///
+    /// This skips the intrinsic gas (EIP-2028, EIP-3860), max initcode size and EIP-3607 (reject
+    /// transactions from senders with deployed code) checks.
+    #[clap(long, help_heading = "Environment config")]
+    pub disable_strict_validation: bool,
+
+    /// Attach a breakdown of the gas used to each transaction receipt.
+    ///
+    /// Receipts get a `gasDetails` field with the intrinsic and execution gas, the refund and its
+    /// EIP-3529 cap, and the cost and potential savings of the access list.
+    #[clap(long, help_heading = "Environment config")]
+    pub report_gas_details: bool,
+
     /// EIP-170: Contract code size limit in bytes. Useful to increase this because of tests. By
     /// default, it is 0x6000 (~25kb).
     #[clap(long, value_name = "CODE_SIZE", help_heading = "Environment config")]
     pub code_size_limit: Option<usize>,
 
+    /// EIP-3860: Initcode size limit of contract creation transactions in bytes. By default, it is
+    /// twice the code size limit.
+    ///
+    /// The EVM itself limits initcode to twice the code size limit regardless, so raising this
+    /// above that also requires raising --code-size-limit.
+    #[clap(long, value_name = "INITCODE_SIZE", help_heading = "Environment config")]
+    pub initcode_size_limit: Option<usize>,
+
     /// The gas price.
     #[clap(long, help_heading = "Environment config")]
     pub gas_price: Option<u64>,
@@ -491,9 +823,27 @@ pub struct AnvilEvmArgs {
     pub block_base_fee_per_gas: Option<u64>,
 
     /// The chain ID.
-    #[clap(long, alias = "chain", help_heading = "Environment config")]
+    #[clap(long, help_heading = "Environment config")]
     pub chain_id: Option<Chain>,
 
+    /// Use the settings of a chain preset: its chain ID, hardfork schedule, gas token decimals
+    /// and code size limit.
+    ///
+    /// Presets are read from the built-in chains registry and from `~/.foundry/chains/*.toml`.
+    /// Any other chain ID or known chain name only sets the chain ID, like `--chain-id`.
+    /// Explicit flags, like `--hardfork`, take precedence over the preset.
+    ///
+    /// Precompile quirks of chains are not modelled, every preset uses the Ethereum precompiles of
+    /// its hardfork.
+    #[clap(
+        long,
+        value_name = "NAME",
+        value_parser = ChainPreset::parse,
+        conflicts_with = "chain_id",
+        help_heading = "Environment config"
+    )]
+    pub chain: Option<ChainPreset>,
+
     /// Enable steps tracing used for debug calls returning geth-style traces
     #[clap(long, visible_alias = "tracing")]
     pub steps_tracing: bool,
@@ -505,12 +855,55 @@ pub struct AnvilEvmArgs {
     /// Run an Optimism chain
     #[clap(long, visible_alias = "optimism")]
     pub optimism: bool,
+
+    /// Whether receipts include the `l1Fee`, `l1GasUsed` and `l1GasPrice` fields of OP stack
+    /// chains.
+    ///
+    /// Enabled by default with `--optimism`, set it to `false` to get plain receipts, or to `true`
+    /// to get OP stack receipts on any chain.
+    #[clap(long, value_name = "BOOL")]
+    pub l2_receipt_fields: Option<bool>,
+
+    /// The L1 gas price, in wei, used to compute the L1 fee in receipts.
+    #[clap(long, value_name = "GAS_PRICE", default_value = "0")]
+    pub l1_gas_price: u64,
+
+    /// The scalar the L1 fee in receipts is multiplied with, e.g. `0.684` for OP mainnet before
+    /// Ecotone.
+    ///
+    /// The L1 fee is computed with the pre-Ecotone formula
+    /// `l1GasUsed * l1GasPrice * l1FeeScalar`, the blob base fee and the separate base fee and
+    /// blob base fee scalars of Ecotone are not modelled.
+    #[clap(long, value_name = "SCALAR", default_value = "1", value_parser = parse_l1_fee_scalar)]
+    pub l1_fee_scalar: f64,
+}
+
+/// Parses a non-negative, finite L1 fee scalar.
+fn parse_l1_fee_scalar(s: &str) -> Result<f64, String> {
+    let scalar = s.parse::<f64>().map_err(|err| err.to_string())?;
+    if !scalar.is_finite() || scalar < 0.0 {
+        return Err("the L1 fee scalar must be a non-negative number".to_string())
+    }
+    Ok(scalar)
 }
 
 /// Resolves an alias passed as fork-url to the matching url defined in the rpc_endpoints section
 /// of the project configuration file.
 /// Does nothing if the fork-url is not a configured alias.
 impl AnvilEvmArgs {
+    /// Returns the credentials to sign the requests to the fork url with, if `--fork-aws-region`
+    /// is set.
+    pub fn fork_sigv4(&self) -> Option<SigV4Signer> {
+        let region = self.fork_aws_region.clone()?;
+        Some(SigV4Signer {
+            access_key_id: self.fork_aws_access_key_id.clone().unwrap_or_default(),
+            secret_access_key: self.fork_aws_secret_access_key.clone().unwrap_or_default(),
+            session_token: self.fork_aws_session_token.clone(),
+            region,
+            service: self.fork_aws_service.clone(),
+        })
+    }
+
     pub fn resolve_rpc_alias(&mut self) {
         if let Some(fork_url) = &self.fork_url {
             let config = Config::load();
@@ -521,6 +914,36 @@ impl AnvilEvmArgs {
     }
 }
 
+/// Infallible export of the genesis and the mined blocks on shutdown, see `--export-chain`
+async fn export_chain(api: EthApi, path: PathBuf) {
+    trace!(?path, "Exporting chain on shutdown");
+    if !api.is_fork() {
+        let genesis_path = path.with_extension("genesis.json");
+        match api.anvil_export_genesis().await {
+            Ok(genesis) => {
+                if let Err(err) = foundry_common::fs::write_json_file(&genesis_path, &genesis) {
+                    error!(?err, "Failed to export genesis");
+                }
+            }
+            Err(err) => {
+                error!(?err, "Failed to export genesis");
+            }
+        }
+    }
+    match api.anvil_export_blocks().await {
+        Ok(blocks) => {
+            if let Err(err) = foundry_common::fs::write(&path, blocks) {
+                error!(?err, "Failed to export chain");
+            } else {
+                trace!(?path, "Exported chain on shutdown");
+            }
+        }
+        Err(err) => {
+            error!(?err, "Failed to export blocks");
+        }
+    }
+}
+
 /// Helper type to periodically dump the state of the chain to disk
 struct PeriodicStateDumper {
     in_progress_dump: Option<Pin<Box<dyn Future<Output = ()> + Send + Sync + 'static>>>,
@@ -543,21 +966,28 @@ impl PeriodicStateDumper {
         Self { in_progress_dump: None, api, dump_state, interval }
     }
 
-    async fn dump(&self) {
+    /// Dumps the state, after waiting for a periodic dump that's in progress to finish.
+    async fn dump(&mut self) {
+        if let Some(in_progress) = self.in_progress_dump.take() {
+            in_progress.await;
+        }
         if let Some(state) = self.dump_state.clone() {
             Self::dump_state(self.api.clone(), state).await
         }
     }
 
     /// Infallible state dump
+    ///
+    /// The dump is written atomically and synced to disk, so a crash or host reboot during the
+    /// dump leaves the previous dump intact.
     async fn dump_state(api: EthApi, dump_state: PathBuf) {
-        trace!(path=?dump_state, "Dumping state on shutdown");
+        trace!(path=?dump_state, "Dumping state");
         match api.serialized_state().await {
             Ok(state) => {
-                if let Err(err) = foundry_common::fs::write_json_file(&dump_state, &state) {
+                if let Err(err) = foundry_common::fs::write_json_file_durable(&dump_state, &state) {
                     error!(?err, "Failed to dump state");
                 } else {
-                    trace!(path=?dump_state, "Dumped state on shutdown");
+                    trace!(path=?dump_state, "Dumped state");
                 }
             }
             Err(err) => {
@@ -669,9 +1099,19 @@ impl FromStr for ForkUrl {
     }
 }
 
+/// Parses a probability between 0 and 1
+fn parse_rate(s: &str) -> Result<f64, String> {
+    let rate = s.parse::<f64>().map_err(|err| err.to_string())?;
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(format!("rate must be between 0 and 1, got {rate}"))
+    }
+    Ok(rate)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ethers::signers::Signer;
     use std::{env, net::Ipv4Addr};
 
     #[test]
@@ -704,12 +1144,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_parse_fault_injection() {
+        let args: NodeArgs =
+            NodeArgs::parse_from(["anvil", "--rpc-latency", "100", "--rpc-error-rate", "0.25"]);
+        let config = args.into_node_config();
+        assert_eq!(config.fault_injection.latency, 100);
+        assert_eq!(config.fault_injection.error_rate, 0.25);
+        assert_eq!(config.fault_injection.drop_rate, 0.0);
+
+        assert!(NodeArgs::try_parse_from(["anvil", "--drop-tx-rate", "1.5"]).is_err());
+    }
+
+    #[test]
+    fn can_parse_l2_receipt_fields() {
+        let config = NodeArgs::parse_from(["anvil", "--optimism"]).into_node_config();
+        assert!(config.l2_receipt_fields());
+
+        let args = NodeArgs::parse_from(["anvil", "--optimism", "--l2-receipt-fields", "false"]);
+        assert!(!args.into_node_config().l2_receipt_fields());
+
+        let args =
+            NodeArgs::parse_from(["anvil", "--l2-receipt-fields", "true", "--l1-gas-price", "7"]);
+        let config = args.into_node_config();
+        assert!(config.l2_receipt_fields());
+        assert_eq!(config.l1_gas_price, 7);
+        assert_eq!(config.l1_fee_scalar, 1.0);
+
+        let args = NodeArgs::parse_from(["anvil", "--optimism", "--l1-fee-scalar", "0.684"]);
+        assert_eq!(args.into_node_config().l1_fee_scalar, 0.684);
+        assert!(NodeArgs::try_parse_from(["anvil", "--l1-fee-scalar", "-1"]).is_err());
+    }
+
     #[test]
     fn can_parse_hardfork() {
         let args: NodeArgs = NodeArgs::parse_from(["anvil", "--hardfork", "berlin"]);
         assert_eq!(args.hardfork, Some(Hardfork::Berlin));
     }
 
+    #[test]
+    fn can_parse_chain_preset() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--chain", "optimism"]);
+        let chain = args.evm_opts.chain.clone().unwrap();
+        assert_eq!(chain.chain_id, 10);
+        assert!(chain.optimism);
+        assert_eq!(args.chain_id(), Some(10));
+
+        // chains without a preset only set the chain ID, like `--chain-id`
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--chain", "1"]);
+        assert_eq!(args.chain_id(), Some(1));
+        assert_eq!(args.evm_opts.chain.unwrap().hardforks.len(), 0);
+
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--chain", "mainnet"]);
+        let config = args.into_node_config();
+        assert_eq!(config.hardfork, Some(Hardfork::Cancun));
+        assert_eq!(config.chain_id, Some(1));
+
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--chain", "137"]);
+        assert_eq!(args.chain_id(), Some(137));
+        assert_eq!(args.evm_opts.chain.unwrap().hardforks.len(), 0);
+
+        assert!(NodeArgs::try_parse_from(["anvil", "--chain", "1", "--chain-id", "1"]).is_err());
+    }
+
     #[test]
     fn can_parse_fork_headers() {
         let args: NodeArgs = NodeArgs::parse_from([
@@ -727,6 +1224,154 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_parse_fork_auth() {
+        let args = NodeArgs::parse_from([
+            "anvil",
+            "--fork-url",
+            "http://localhost:8545",
+            "--fork-bearer-token",
+            "token",
+            "--fork-aws-region",
+            "us-east-1",
+            "--fork-aws-access-key-id",
+            "key",
+            "--fork-aws-secret-access-key",
+            "secret",
+        ]);
+        let sigv4 = args.evm_opts.fork_sigv4().unwrap();
+        assert_eq!(sigv4.region, "us-east-1");
+        assert_eq!(sigv4.service, "managedblockchain");
+        assert_eq!(sigv4.access_key_id, "key");
+
+        let config = args.into_node_config();
+        assert_eq!(config.fork_headers, vec!["Authorization: Bearer token"]);
+        assert!(config.fork_sigv4.is_some());
+    }
+
+    #[test]
+    fn can_parse_fork_etherscan_labels() {
+        let args = NodeArgs::parse_from([
+            "anvil",
+            "--fork-url",
+            "http://localhost:8545",
+            "--fork-etherscan-labels",
+            "--etherscan-api-key",
+            "key",
+        ]);
+        assert!(args.evm_opts.fork_etherscan_labels);
+        assert_eq!(args.into_node_config().fork_etherscan_api_key, Some("key".to_string()));
+
+        let args = NodeArgs::try_parse_from(["anvil", "--fork-etherscan-labels"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_fork_follow_mempool() {
+        let args = NodeArgs::parse_from([
+            "anvil",
+            "--fork-url",
+            "http://localhost:8545",
+            "--fork-follow-mempool",
+        ]);
+        assert!(args.into_node_config().fork_follow_mempool);
+
+        let args = NodeArgs::try_parse_from(["anvil", "--fork-follow-mempool"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_allow_chain_id_mismatch() {
+        let args = NodeArgs::parse_from([
+            "anvil",
+            "--fork-url",
+            "http://localhost:8545",
+            "--chain-id",
+            "1337",
+            "--allow-chain-id-mismatch",
+        ]);
+        let config = args.into_node_config();
+        assert!(config.allow_chain_id_mismatch);
+        assert_eq!(config.chain_id, Some(1337));
+
+        let args = NodeArgs::try_parse_from(["anvil", "--allow-chain-id-mismatch"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_log_format() {
+        let args = NodeArgs::parse_from(["anvil"]);
+        assert_eq!(args.log_format, LogFormat::Text);
+
+        let args = NodeArgs::parse_from(["anvil", "--log-format", "json"]);
+        assert_eq!(args.into_node_config().log_format, LogFormat::Json);
+
+        let args = NodeArgs::try_parse_from(["anvil", "--log-format", "yaml"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_server_limits() {
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--max-request-size",
+            "1024",
+            "--max-batch-size",
+            "10",
+            "--rate-limit",
+            "eth_call=5",
+            "--rate-limit",
+            "*=100",
+        ]);
+        assert_eq!(args.server_config.max_request_size, Some(1024));
+        assert_eq!(args.server_config.max_batch_size, Some(10));
+        assert_eq!(
+            args.server_config.rate_limits.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["eth_call=5", "*=100"]
+        );
+
+        assert!(NodeArgs::try_parse_from(["anvil", "--rate-limit", "eth_call"]).is_err());
+    }
+
+    #[test]
+    fn can_parse_predeploys() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil"]);
+        assert!(args.predeploys.is_empty());
+
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--predeploys",
+            "multicall3,permit2",
+            "--predeploys-rpc-url",
+            "http://localhost:8545",
+        ]);
+        assert_eq!(args.predeploys, vec![Predeploy::Multicall3, Predeploy::Permit2]);
+        let config = args.into_node_config();
+        assert_eq!(config.predeploys, vec![Predeploy::Multicall3, Predeploy::Permit2]);
+        assert_eq!(config.predeploys_rpc_url.as_deref(), Some("http://localhost:8545"));
+
+        assert!(NodeArgs::try_parse_from(["anvil", "--predeploys", "multicall2"]).is_err());
+    }
+
+    #[test]
+    fn can_load_keystore_accounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let (wallet, _) =
+            Wallet::<SigningKey>::new_keystore(dir.path(), &mut rand::thread_rng(), "anvil", None)
+                .unwrap();
+
+        let dir_path = dir.path().to_str().unwrap();
+        let args: NodeArgs =
+            NodeArgs::parse_from(["anvil", "--keystore", dir_path, "--keystore-password", "anvil"]);
+        let accounts = args.keystore_accounts().unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].address(), wallet.address());
+
+        let args: NodeArgs =
+            NodeArgs::parse_from(["anvil", "--keystore", dir_path, "--keystore-password", "wrong"]);
+        assert!(args.keystore_accounts().is_err());
+    }
+
     #[test]
     fn can_parse_prune_config() {
         let args: NodeArgs = NodeArgs::parse_from(["anvil", "--prune-history"]);
@@ -7,14 +7,20 @@ use crate::{
             mem::fork_db::ForkedDatabase,
             time::duration_since_unix_epoch,
         },
+        error::BlockchainError,
         fees::{INITIAL_BASE_FEE, INITIAL_GAS_PRICE},
+        macros::node_info,
         pool::transactions::TransactionOrder,
     },
     genesis::Genesis,
+    hooks::NodeHook,
+    logging::LogFormat,
     mem,
     mem::in_memory_db::MemDb,
+    predeploys::Predeploy,
     FeeManager, Hardfork,
 };
+use anvil_core::types::FaultInjection;
 use anvil_server::ServerConfig;
 use ethers::{
     core::k256::ecdsa::SigningKey,
@@ -24,10 +30,11 @@ use ethers::{
         coins_bip39::{English, Mnemonic},
         MnemonicBuilder, Signer,
     },
-    types::BlockNumber,
+    types::{BlockNumber, Bytes},
     utils::{format_ether, hex, to_checksum, WEI_IN_ETHER},
 };
 use foundry_common::{
+    sigv4::SigV4Signer,
     types::{ToAlloy, ToEthers},
     ProviderBuilder, ALCHEMY_FREE_TIER_CUPS, NON_ARCHIVE_NODE_WARNING, REQUEST_TIMEOUT,
 };
@@ -109,6 +116,9 @@ pub struct NodeConfig {
     pub genesis_timestamp: Option<u64>,
     /// Signer accounts that can sign messages/transactions from the EVM node
     pub signer_accounts: Vec<Wallet<SigningKey>>,
+    /// Accounts decrypted from keystores, which are funded with `genesis_balance` and can sign
+    /// like `signer_accounts`, but whose private keys are never printed
+    pub keystore_accounts: Vec<Wallet<SigningKey>>,
     /// Configured block time for the EVM chain. Use `None` to mine a new block for every tx
     pub block_time: Option<Duration>,
     /// Disable auto, interval mining mode uns use `MiningMode::None` instead
@@ -125,12 +135,16 @@ pub struct NodeConfig {
     pub fork_block_number: Option<u64>,
     /// headers to use with `eth_rpc_url`
     pub fork_headers: Vec<String>,
+    /// credentials to sign the requests to `eth_rpc_url` with AWS Signature Version 4
+    pub fork_sigv4: Option<SigV4Signer>,
     /// specifies chain id for cache to skip fetching from remote in offline-start mode
     pub fork_chain_id: Option<U256>,
     /// The generator used to generate the dev accounts
     pub account_generator: Option<AccountGenerator>,
     /// whether to enable tracing
     pub enable_tracing: bool,
+    /// The format of the logs written to stdout
+    pub log_format: LogFormat,
     /// Explicitly disables the use of RPC caching.
     pub no_storage_caching: bool,
     /// How to configure the server
@@ -149,6 +163,13 @@ pub struct NodeConfig {
     pub fork_request_retries: u32,
     /// The initial retry backoff
     pub fork_retry_backoff: Duration,
+    /// If set, forked contracts are resolved from Etherscan with this API key, to label them in
+    /// traces
+    pub fork_etherscan_api_key: Option<String>,
+    /// Whether to apply the pending transactions of the forked chain's mempool to the fork
+    pub fork_follow_mempool: bool,
+    /// Whether to allow a chain id that differs from the chain id of the forked network
+    pub allow_chain_id_mismatch: bool,
     /// available CUPS
     pub compute_units_per_second: u64,
     /// The ipc path
@@ -159,6 +180,9 @@ pub struct NodeConfig {
     pub enable_auto_impersonate: bool,
     /// Configure the code size limit
     pub code_size_limit: Option<usize>,
+    /// Configure the initcode size limit of contract creation transactions, twice the code size
+    /// limit by default
+    pub initcode_size_limit: Option<usize>,
     /// Configures how to remove historic state.
     ///
     /// If set to `Some(num)` keep latest num state in memory only.
@@ -169,8 +193,30 @@ pub struct NodeConfig {
     pub transaction_block_keeper: Option<usize>,
     /// Disable the default CREATE2 deployer
     pub disable_default_create2_deployer: bool,
+    /// Infrastructure contracts to deploy at genesis
+    pub predeploys: Vec<Predeploy>,
+    /// The endpoint to copy the code of predeploys from, defaults to the forked endpoint
+    pub predeploys_rpc_url: Option<String>,
+    /// The code of the predeploys, see [NodeConfig::resolve_predeploys]
+    pub predeploy_codes: Vec<(Predeploy, Bytes)>,
+    /// Disable the mainnet transaction validation rules that tests may intentionally violate:
+    /// intrinsic gas, initcode size and EIP-3607
+    pub disable_strict_validation: bool,
+    /// Attach a breakdown of the gas used to each receipt, as `gasDetails`
+    pub report_gas_details: bool,
+    /// The faults injected into RPC calls and submitted transactions
+    pub fault_injection: FaultInjection,
     /// Enable Optimism deposit transaction
     pub enable_optimism: bool,
+    /// Whether receipts include the L1 fee fields of OP stack chains, defaults to whether
+    /// Optimism is enabled
+    pub l2_receipt_fields: Option<bool>,
+    /// The L1 gas price used for the L1 fee in receipts
+    pub l1_gas_price: u64,
+    /// The scalar the L1 fee in receipts is multiplied with
+    pub l1_fee_scalar: f64,
+    /// The hooks called when transactions are validated and executed, and when blocks are mined
+    pub hooks: Vec<Arc<dyn NodeHook>>,
 }
 
 impl NodeConfig {
@@ -215,6 +261,24 @@ Private Keys
             let _ = write!(config_string, "\n({idx}) 0x{hex}");
         }
 
+        if !self.keystore_accounts.is_empty() {
+            let _ = write!(
+                config_string,
+                r#"
+
+Keystore Accounts
+==================
+"#
+            );
+            for (idx, wallet) in self.keystore_accounts.iter().enumerate() {
+                let _ = write!(
+                    config_string,
+                    "\n({idx}) {:?} ({balance} ETH)",
+                    to_checksum(&wallet.address(), None)
+                );
+            }
+        }
+
         if let Some(ref gen) = self.account_generator {
             let _ = write!(
                 config_string,
@@ -247,6 +311,16 @@ Chain ID:       {}
                 fork.block_hash(),
                 fork.chain_id()
             );
+            if fork.chain_id() == fork.remote_chain_id() {
+                let _ = writeln!(
+                    config_string,
+                    "{}",
+                    Paint::yellow(
+                        "Transactions signed by this node are valid on the forked network, \
+never broadcast them there."
+                    )
+                );
+            }
         } else {
             let _ = write!(
                 config_string,
@@ -314,6 +388,11 @@ Genesis Timestamp
             available_accounts.push(format!("{:?}", wallet.address()));
             private_keys.push(format!("0x{}", hex::encode(wallet.signer().to_bytes())));
         }
+        let keystore_accounts = self
+            .keystore_accounts
+            .iter()
+            .map(|wallet| format!("{:?}", wallet.address()))
+            .collect::<Vec<_>>();
 
         if let Some(ref gen) = self.account_generator {
             let phrase = gen.get_phrase().to_string();
@@ -327,6 +406,7 @@ Genesis Timestamp
             json!({
               "available_accounts": available_accounts,
               "private_keys": private_keys,
+              "keystore_accounts": keystore_accounts,
               "endpoint": fork.eth_rpc_url(),
               "block_number": fork.block_number(),
               "block_hash": fork.block_hash(),
@@ -340,6 +420,7 @@ Genesis Timestamp
             json!({
               "available_accounts": available_accounts,
               "private_keys": private_keys,
+              "keystore_accounts": keystore_accounts,
               "wallet": wallet_description,
               "base_fee": format!("{}", self.get_base_fee()),
               "gas_price": format!("{}", self.get_gas_price()),
@@ -372,6 +453,7 @@ impl Default for NodeConfig {
             gas_price: None,
             hardfork: None,
             signer_accounts: genesis_accounts.clone(),
+            keystore_accounts: vec![],
             genesis_timestamp: None,
             genesis_accounts,
             // 100ETH default balance
@@ -387,6 +469,7 @@ impl Default for NodeConfig {
             account_generator: None,
             base_fee: None,
             enable_tracing: true,
+            log_format: Default::default(),
             enable_steps_tracing: false,
             enable_auto_impersonate: false,
             no_storage_caching: false,
@@ -397,18 +480,33 @@ impl Default for NodeConfig {
             genesis: None,
             fork_request_timeout: REQUEST_TIMEOUT,
             fork_headers: vec![],
+            fork_sigv4: None,
             fork_request_retries: 5,
             fork_retry_backoff: Duration::from_millis(1_000),
+            fork_etherscan_api_key: None,
+            fork_follow_mempool: false,
+            allow_chain_id_mismatch: false,
             fork_chain_id: None,
             // alchemy max cpus <https://docs.alchemy.com/reference/compute-units#what-are-cups-compute-units-per-second>
             compute_units_per_second: ALCHEMY_FREE_TIER_CUPS,
             ipc_path: None,
             code_size_limit: None,
+            initcode_size_limit: None,
             prune_history: Default::default(),
             init_state: None,
             transaction_block_keeper: None,
             disable_default_create2_deployer: false,
+            predeploys: Vec::new(),
+            predeploys_rpc_url: None,
+            predeploy_codes: Vec::new(),
+            disable_strict_validation: false,
+            report_gas_details: false,
+            fault_injection: Default::default(),
             enable_optimism: false,
+            l2_receipt_fields: None,
+            l1_gas_price: 0,
+            l1_fee_scalar: 1.0,
+            hooks: Vec::new(),
         }
     }
 }
@@ -438,6 +536,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets a custom initcode size limit for contract creation transactions
+    #[must_use]
+    pub fn with_initcode_size_limit(mut self, initcode_size_limit: Option<usize>) -> Self {
+        self.initcode_size_limit = initcode_size_limit;
+        self
+    }
+
     /// Sets a custom code size limit
     #[must_use]
     pub fn with_init_state(mut self, init_state: Option<SerializableState>) -> Self {
@@ -445,6 +550,38 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the infrastructure contracts to deploy at genesis
+    #[must_use]
+    pub fn with_predeploys(mut self, predeploys: Vec<Predeploy>) -> Self {
+        self.predeploys = predeploys;
+        self
+    }
+
+    /// Sets the endpoint to copy the code of predeploys from
+    #[must_use]
+    pub fn with_predeploys_rpc_url(mut self, predeploys_rpc_url: Option<String>) -> Self {
+        self.predeploys_rpc_url = predeploys_rpc_url;
+        self
+    }
+
+    /// Fetches the code of the predeploys from `predeploys_rpc_url`, or the forked endpoint.
+    ///
+    /// This is done before the node is spawned so that a missing endpoint or contract is reported
+    /// as an error. Predeploys that haven't been resolved when the node is set up are skipped.
+    pub async fn resolve_predeploys(&mut self) -> Result<(), String> {
+        let rpc_url = self.predeploys_rpc_url.as_deref().or(self.eth_rpc_url.as_deref());
+        let mut codes = Vec::with_capacity(self.predeploys.len());
+        for predeploy in &self.predeploys {
+            let code = predeploy
+                .code(rpc_url)
+                .await
+                .map_err(|err| format!("Failed to predeploy {predeploy}: {err}"))?;
+            codes.push((*predeploy, code));
+        }
+        self.predeploy_codes = codes;
+        Ok(())
+    }
+
     /// Sets the chain ID
     #[must_use]
     pub fn with_chain_id<U: Into<u64>>(mut self, chain_id: Option<U>) -> Self {
@@ -468,6 +605,9 @@ impl NodeConfig {
         });
         self.signer_accounts.iter_mut().for_each(|wallet| {
             *wallet = wallet.clone().with_chain_id(chain_id);
+        });
+        self.keystore_accounts.iter_mut().for_each(|wallet| {
+            *wallet = wallet.clone().with_chain_id(chain_id);
         })
     }
 
@@ -489,6 +629,39 @@ impl NodeConfig {
         self
     }
 
+    /// Disables the strict transaction validation
+    ///
+    /// If set to `true` the intrinsic gas, initcode size and EIP-3607 checks will not be enforced
+    #[must_use]
+    pub fn disable_strict_validation(mut self, disable_strict_validation: bool) -> Self {
+        self.disable_strict_validation = disable_strict_validation;
+        self
+    }
+
+    /// Sets whether receipts include a `gasDetails` breakdown of intrinsic gas, execution gas,
+    /// refunds and access list savings
+    #[must_use]
+    pub fn with_report_gas_details(mut self, report_gas_details: bool) -> Self {
+        self.report_gas_details = report_gas_details;
+        self
+    }
+
+    /// Sets the faults injected into RPC calls and submitted transactions
+    #[must_use]
+    pub fn with_fault_injection(mut self, fault_injection: FaultInjection) -> Self {
+        self.fault_injection = fault_injection;
+        self
+    }
+
+    /// Adds a hook into the transaction lifecycle, see [NodeHook]
+    ///
+    /// Hooks are called in the order they were added.
+    #[must_use]
+    pub fn with_hook(mut self, hook: impl NodeHook + 'static) -> Self {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
     /// Sets the gas price
     #[must_use]
     pub fn with_gas_price<U: Into<U256>>(mut self, gas_price: Option<U>) -> Self {
@@ -564,6 +737,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the accounts decrypted from keystores
+    #[must_use]
+    pub fn with_keystore_accounts(mut self, accounts: Vec<Wallet<SigningKey>>) -> Self {
+        self.keystore_accounts = accounts;
+        self
+    }
+
     /// Sets both the genesis accounts and the signer accounts
     /// so that `genesis_accounts == accounts`
     #[must_use]
@@ -672,6 +852,22 @@ impl NodeConfig {
         self
     }
 
+    /// Sends the bearer token in the `Authorization` header of the requests to `eth_rpc_url`
+    #[must_use]
+    pub fn with_fork_bearer_token(mut self, token: Option<String>) -> Self {
+        if let Some(token) = token {
+            self.fork_headers.push(format!("Authorization: Bearer {token}"));
+        }
+        self
+    }
+
+    /// Sets the credentials to sign the requests to `eth_rpc_url` with AWS Signature Version 4
+    #[must_use]
+    pub fn with_fork_sigv4(mut self, sigv4: Option<SigV4Signer>) -> Self {
+        self.fork_sigv4 = sigv4;
+        self
+    }
+
     /// Sets the `fork_request_timeout` to use for requests
     #[must_use]
     pub fn fork_request_timeout(mut self, fork_request_timeout: Option<Duration>) -> Self {
@@ -699,6 +895,27 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the Etherscan API key used to label forked contracts in traces
+    #[must_use]
+    pub fn with_fork_etherscan_api_key(mut self, etherscan_api_key: Option<String>) -> Self {
+        self.fork_etherscan_api_key = etherscan_api_key;
+        self
+    }
+
+    /// Sets whether to apply the pending transactions of the forked chain's mempool to the fork
+    #[must_use]
+    pub fn with_fork_follow_mempool(mut self, fork_follow_mempool: bool) -> Self {
+        self.fork_follow_mempool = fork_follow_mempool;
+        self
+    }
+
+    /// Sets whether to allow a chain id that differs from the chain id of the forked network
+    #[must_use]
+    pub fn with_allow_chain_id_mismatch(mut self, allow_chain_id_mismatch: bool) -> Self {
+        self.allow_chain_id_mismatch = allow_chain_id_mismatch;
+        self
+    }
+
     /// Sets the number of assumed available compute units per second
     ///
     /// See also, <https://docs.alchemy.com/reference/compute-units#what-are-cups-compute-units-per-second>
@@ -717,6 +934,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the format of the logs written to stdout
+    #[must_use]
+    pub fn with_log_format(mut self, log_format: LogFormat) -> Self {
+        self.log_format = log_format;
+        self
+    }
+
     /// Sets whether to enable steps tracing
     #[must_use]
     pub fn with_steps_tracing(mut self, enable_steps_tracing: bool) -> Self {
@@ -772,7 +996,10 @@ impl NodeConfig {
             return
         }
 
-        println!("{}", self.as_string(fork))
+        match self.log_format {
+            LogFormat::Text => println!("{}", self.as_string(fork)),
+            LogFormat::Json => node_info!(config = %self.as_json(fork), "Starting anvil"),
+        }
     }
 
     /// Returns the path where the cache file should be stored
@@ -794,11 +1021,62 @@ impl NodeConfig {
         self
     }
 
+    /// Sets whether receipts include the `l1Fee`, `l1GasUsed` and `l1GasPrice` fields of OP stack
+    /// chains, by default they do if Optimism is enabled
+    #[must_use]
+    pub fn with_l2_receipt_fields(mut self, l2_receipt_fields: Option<bool>) -> Self {
+        self.l2_receipt_fields = l2_receipt_fields;
+        self
+    }
+
+    /// Sets the L1 gas price used to compute the L1 fee in receipts
+    #[must_use]
+    pub fn with_l1_gas_price(mut self, l1_gas_price: u64) -> Self {
+        self.l1_gas_price = l1_gas_price;
+        self
+    }
+
+    /// Sets the scalar the L1 fee in receipts is multiplied with, see
+    /// [`AnvilEvmArgs::l1_fee_scalar`](crate::cmd::AnvilEvmArgs::l1_fee_scalar)
+    #[must_use]
+    pub fn with_l1_fee_scalar(mut self, l1_fee_scalar: f64) -> Self {
+        self.l1_fee_scalar = l1_fee_scalar;
+        self
+    }
+
+    /// Returns whether receipts include the L1 fee fields of OP stack chains
+    pub fn l2_receipt_fields(&self) -> bool {
+        self.l2_receipt_fields.unwrap_or(self.enable_optimism)
+    }
+
+    /// Spawns the node in-process, without launching any HTTP, WS or IPC servers.
+    ///
+    /// This is useful for embedding the node in integration tests, the node can be accessed via
+    /// the returned [EthApi](crate::eth::EthApi) or the provider returned by
+    /// [NodeHandle::in_process_provider](crate::NodeHandle::in_process_provider).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use anvil::NodeConfig;
+    /// # use ethers::providers::Middleware;
+    /// # async fn spawn() {
+    /// let (_api, handle) = NodeConfig::test().spawn().await;
+    /// let provider = handle.in_process_provider();
+    /// let block_number = provider.get_block_number().await.unwrap();
+    /// # }
+    /// ```
+    pub async fn spawn(mut self) -> (crate::eth::EthApi, crate::NodeHandle) {
+        self.host.clear();
+        self.ipc_path = None;
+        crate::spawn(self).await
+    }
+
     /// Configures everything related to env, backend and database and returns the
     /// [Backend](mem::Backend)
     ///
     /// *Note*: only memory based backend for now
-    pub(crate) async fn setup(&mut self) -> mem::Backend {
+    pub(crate) async fn setup(&mut self) -> eyre::Result<mem::Backend> {
         // configure the revm environment
 
         let mut cfg = CfgEnv::default();
@@ -825,7 +1103,7 @@ impl NodeConfig {
 
         let (db, fork): (Arc<tokio::sync::RwLock<Box<dyn Db>>>, Option<ClientFork>) =
             if let Some(eth_rpc_url) = self.eth_rpc_url.clone() {
-                self.setup_fork_db(eth_rpc_url, &mut env, &fees).await
+                self.setup_fork_db(eth_rpc_url, &mut env, &fees).await?
             } else {
                 (Arc::new(tokio::sync::RwLock::new(Box::<MemDb>::default())), None)
             };
@@ -838,7 +1116,12 @@ impl NodeConfig {
         let genesis = GenesisConfig {
             timestamp: self.get_genesis_timestamp(),
             balance: self.genesis_balance.to_alloy(),
-            accounts: self.genesis_accounts.iter().map(|acc| acc.address()).collect(),
+            accounts: self
+                .genesis_accounts
+                .iter()
+                .chain(&self.keystore_accounts)
+                .map(|acc| acc.address())
+                .collect(),
             fork_genesis_account_infos: Arc::new(Default::default()),
             genesis_init: self.genesis.clone(),
         };
@@ -867,6 +1150,17 @@ impl NodeConfig {
                 .expect("Failed to create default create2 deployer");
         }
 
+        // Writes the requested predeploys, unless a contract is already deployed at their address,
+        // e.g. on the forked chain
+        for (predeploy, code) in &self.predeploy_codes {
+            let address = predeploy.address();
+            let existing = backend.get_code(address, None).await.unwrap_or_default();
+            if !existing.is_empty() {
+                continue
+            }
+            backend.set_code(address, code.clone()).await.expect("Failed to write predeploy");
+        }
+
         if let Some(ref state) = self.init_state {
             backend
                 .get_db()
@@ -876,7 +1170,9 @@ impl NodeConfig {
                 .expect("Failed to load init state");
         }
 
-        backend
+        backend.record_genesis_state().await;
+
+        Ok(backend)
     }
 
     /// Configures everything related to forking based on the passed `eth_rpc_url`:
@@ -890,15 +1186,15 @@ impl NodeConfig {
         eth_rpc_url: String,
         env: &mut revm::primitives::Env,
         fees: &FeeManager,
-    ) -> (Arc<tokio::sync::RwLock<Box<dyn Db>>>, Option<ClientFork>) {
-        let (db, config) = self.setup_fork_db_config(eth_rpc_url, env, fees).await;
+    ) -> eyre::Result<(Arc<tokio::sync::RwLock<Box<dyn Db>>>, Option<ClientFork>)> {
+        let (db, config) = self.setup_fork_db_config(eth_rpc_url, env, fees).await?;
 
         let db: Arc<tokio::sync::RwLock<Box<dyn Db>>> =
             Arc::new(tokio::sync::RwLock::new(Box::new(db)));
 
         let fork = ClientFork::new(config, Arc::clone(&db));
 
-        (db, Some(fork))
+        Ok((db, Some(fork)))
     }
 
     /// Configures everything related to forking based on the passed `eth_rpc_url`:
@@ -912,7 +1208,7 @@ impl NodeConfig {
         eth_rpc_url: String,
         env: &mut revm::primitives::Env,
         fees: &FeeManager,
-    ) -> (ForkedDatabase, ClientForkConfig) {
+    ) -> eyre::Result<(ForkedDatabase, ClientForkConfig)> {
         // TODO make provider agnostic
         let provider = Arc::new(
             ProviderBuilder::new(&eth_rpc_url)
@@ -923,6 +1219,7 @@ impl NodeConfig {
                 .max_retry(10)
                 .initial_backoff(1000)
                 .headers(self.fork_headers.clone())
+                .sigv4(self.fork_sigv4.clone())
                 .build()
                 .expect("Failed to establish provider to fork url"),
         );
@@ -1032,15 +1329,35 @@ latest block number: {latest_block}"
 
         let block_hash = block.hash.unwrap_or_default();
 
+        let remote_chain_id = if let Some(fork_chain_id) = fork_chain_id {
+            fork_chain_id
+        } else {
+            provider.get_chainid().await.expect("Failed to fetch network chain id")
+        }
+        .as_u64();
+
         let chain_id = if let Some(chain_id) = self.chain_id {
+            // a chain id of another network makes the transactions signed by this node valid on
+            // that network
+            if chain_id != remote_chain_id {
+                if !self.allow_chain_id_mismatch {
+                    eyre::bail!(
+                        "{}",
+                        BlockchainError::ChainIdMismatch { chain_id, remote_chain_id }
+                    )
+                }
+                eprintln!(
+                    "{}",
+                    Paint::yellow(format!(
+                        "Warning: the chain id {chain_id} differs from the chain id \
+{remote_chain_id} of the forked network"
+                    ))
+                    .bold()
+                );
+            }
             chain_id
         } else {
-            let chain_id = if let Some(fork_chain_id) = fork_chain_id {
-                fork_chain_id
-            } else {
-                provider.get_chainid().await.unwrap()
-            }
-            .as_u64();
+            let chain_id = remote_chain_id;
 
             // need to update the dev signers and env with the chain id
             self.set_chain_id(Some(chain_id));
@@ -1057,6 +1374,12 @@ latest block number: {latest_block}"
             BlockchainDb::new(meta, self.block_cache_path(fork_block_number))
         };
 
+        // when forking another anvil instance, fetch its entire state with a single request
+        // instead of fetching every account and storage slot on demand
+        if let Some(state) = fetch_anvil_state(&provider, fork_block_number).await {
+            state.insert_into_fork_cache(&block_chain_db);
+        }
+
         // This will spawn the background thread that will use the provider to fetch
         // blockchain data from the other client
         let backend = SharedBackend::spawn_backend_thread(
@@ -1072,6 +1395,8 @@ latest block number: {latest_block}"
             provider,
             chain_id,
             override_chain_id,
+            remote_chain_id,
+            allow_chain_id_mismatch: self.allow_chain_id_mismatch,
             timestamp: block.timestamp.as_u64(),
             base_fee: block.base_fee_per_gas,
             timeout: self.fork_request_timeout,
@@ -1079,9 +1404,11 @@ latest block number: {latest_block}"
             backoff: self.fork_retry_backoff,
             compute_units_per_second: self.compute_units_per_second,
             total_difficulty: block.total_difficulty.unwrap_or_default(),
+            headers: self.fork_headers.clone(),
+            sigv4: self.fork_sigv4.clone(),
         };
 
-        (ForkedDatabase::new(backend, block_chain_db), config)
+        Ok((ForkedDatabase::new(backend, block_chain_db), config))
     }
 }
 
@@ -1210,6 +1537,28 @@ async fn find_latest_fork_block<M: Middleware>(provider: M) -> Result<u64, M::Er
     Ok(num)
 }
 
+/// Returns the state of the fork block if the endpoint is another anvil instance.
+///
+/// anvil can only dump its latest state, so this returns `None` if the fork block is not the
+/// latest block of the instance, in which case the state is fetched on demand as usual.
+async fn fetch_anvil_state<M: Middleware>(
+    provider: M,
+    fork_block_number: u64,
+) -> Option<SerializableState> {
+    let version = provider.client_version().await.ok()?;
+    if !version.starts_with("anvil/") ||
+        provider.get_block_number().await.ok()?.as_u64() != fork_block_number
+    {
+        return None
+    }
+    let dump: Bytes = provider.provider().request("anvil_dumpState", ()).await.ok()?;
+    // a block could have been mined while the state was dumped
+    if provider.get_block_number().await.ok()?.as_u64() != fork_block_number {
+        return None
+    }
+    SerializableState::decode(&dump).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,105 @@
+//! Support for embedding the node in another process.
+//!
+//! The [InProcessClient] dispatches JSON-RPC requests directly to an [EthApi], without going
+//! through a HTTP, WS or IPC server, which makes it possible to run a node inside integration
+//! tests without spawning a subprocess or binding any ports.
+
+use crate::EthApi;
+use anvil_core::eth::EthRequest;
+use anvil_rpc::response::ResponseResult;
+use async_trait::async_trait;
+use ethers::providers::{JsonRpcClient, JsonRpcError, Provider, ProviderError, RpcError};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt::Debug, time::Duration};
+use thiserror::Error;
+
+/// Error type of the [InProcessClient]
+#[derive(Debug, Error)]
+pub enum InProcessClientError {
+    /// Failed to (de)serialize the request or the response
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    /// The node returned an error response
+    #[error(transparent)]
+    Rpc(#[from] JsonRpcError),
+}
+
+impl RpcError for InProcessClientError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            InProcessClientError::Rpc(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            InProcessClientError::Serde(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<InProcessClientError> for ProviderError {
+    fn from(src: InProcessClientError) -> Self {
+        match src {
+            InProcessClientError::Serde(err) => ProviderError::SerdeJson(err),
+            _ => ProviderError::JsonRpcClientError(Box::new(src)),
+        }
+    }
+}
+
+/// A [JsonRpcClient] that executes all requests against an in-process [EthApi]
+#[derive(Clone)]
+pub struct InProcessClient {
+    api: EthApi,
+}
+
+// === impl InProcessClient ===
+
+impl InProcessClient {
+    /// Creates a new client for the given `EthApi`
+    pub fn new(api: EthApi) -> Self {
+        Self { api }
+    }
+
+    /// Returns a [Provider] that uses this client as transport
+    pub fn into_provider(self) -> Provider<Self> {
+        // there's no network roundtrip, so there's no need to wait between polls
+        Provider::new(self).interval(Duration::from_millis(10))
+    }
+}
+
+impl Debug for InProcessClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InProcessClient").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for InProcessClient {
+    type Error = InProcessClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        trace!(target: "rpc::embedded", ?method, "received method call");
+        let call = serde_json::json!({
+            "method": method,
+            "params": serde_json::to_value(params)?
+        });
+        let request = serde_json::from_value::<EthRequest>(call)?;
+
+        match self.api.execute(request).await {
+            ResponseResult::Success(value) => Ok(serde_json::from_value(value)?),
+            ResponseResult::Error(err) => Err(JsonRpcError {
+                code: err.code.code(),
+                message: err.message.into_owned(),
+                data: err.data,
+            }
+            .into()),
+        }
+    }
+}
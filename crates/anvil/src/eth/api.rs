@@ -4,14 +4,16 @@ use crate::{
         backend,
         backend::{
             db::SerializableState,
-            mem::{MIN_CREATE_GAS, MIN_TRANSACTION_GAS},
+            delegation,
+            mem::{PendingBundle, MIN_TRANSACTION_GAS},
             notifications::NewBlockNotifications,
-            validate::TransactionValidator,
+            validate::{ensure_transaction_conditional, TransactionValidator},
         },
         error::{
             decode_revert_reason, BlockchainError, FeeHistoryError, InvalidTransactionError,
             Result, ToRpcResponseResult,
         },
+        faults::FaultInjector,
         fees::{FeeDetails, FeeHistoryCache},
         macros::node_info,
         miner::FixedBlockTimeMiner,
@@ -35,14 +37,17 @@ use anvil_core::{
         proof::AccountProof,
         state::StateOverride,
         transaction::{
-            EthTransactionRequest, LegacyTransaction, PendingTransaction, TransactionKind,
-            TypedTransaction, TypedTransactionRequest,
+            decode_blob_transaction_with_sidecar, BlobTransactionSidecar, EthTransactionRequest,
+            LegacyTransaction, OptimismDepositRequestFields, PendingTransaction, TypedTransaction,
+            TypedTransactionRequest, BYTES_PER_BLOB, MAX_BLOBS_PER_BLOCK,
         },
         EthRequest,
     },
     types::{
-        AnvilMetadata, EvmMineOptions, ForkedNetwork, Forking, Index, NodeEnvironment,
-        NodeForkConfig, NodeInfo, Work,
+        AnvilMetadata, BlobSidecar, Bundle, BundleHash, DepositTransactionParams, EvmMineOptions,
+        FaultInjection, ForkSignedTransaction, ForkedNetwork, Forking, Index, MinedBlockDetails,
+        NodeEnvironment, NodeForkConfig, NodeInfo, SignedTransactionResponse, TraceFilter,
+        TransactionConditional, Work,
     },
 };
 use anvil_rpc::{error::RpcError, response::ResponseResult};
@@ -59,7 +64,7 @@ use ethers::{
         GethDebugTracingOptions, GethTrace, Log, Signature, Trace, Transaction, TransactionReceipt,
         TxHash, TxpoolContent, TxpoolInspectSummary, TxpoolStatus, H256, U256, U64,
     },
-    utils::rlp,
+    utils::{keccak256, rlp},
 };
 use foundry_common::{types::ToEthers, ProviderBuilder};
 use foundry_evm::{
@@ -77,6 +82,12 @@ use std::{collections::HashSet, future::Future, sync::Arc, time::Duration};
 /// The client version: `anvil/v{major}.{minor}.{patch}`
 pub const CLIENT_VERSION: &str = concat!("anvil/v", env!("CARGO_PKG_VERSION"));
 
+/// The gas that's added to calls that transfer value.
+const CALL_STIPEND: U256 = U256([2_300, 0, 0, 0]);
+
+/// The tolerated error of gas estimates in per mille, which is 1.5% like in geth.
+const ESTIMATE_GAS_ERROR_RATIO_PER_MILLE: u64 = 15;
+
 /// The entry point for executing eth api RPC call - The Eth RPC interface.
 ///
 /// This type is cheap to clone and can be used concurrently
@@ -110,6 +121,8 @@ pub struct EthApi {
     net_listening: bool,
     /// The instance ID. Changes on every reset.
     instance_id: Arc<RwLock<H256>>,
+    /// The faults injected into RPC calls and submitted transactions
+    faults: FaultInjector,
 }
 
 // === impl Eth RPC API ===
@@ -127,6 +140,7 @@ impl EthApi {
         logger: LoggingManager,
         filters: Filters,
         transactions_order: TransactionOrder,
+        faults: FaultInjector,
     ) -> Self {
         Self {
             pool,
@@ -141,12 +155,16 @@ impl EthApi {
             net_listening: true,
             transaction_order: Arc::new(RwLock::new(transactions_order)),
             instance_id: Arc::new(RwLock::new(H256::random())),
+            faults,
         }
     }
 
     /// Executes the [EthRequest] and returns an RPC [RpcResponse]
     pub async fn execute(&self, request: EthRequest) -> ResponseResult {
         trace!(target: "rpc::api", "executing eth request");
+        if let Some(err) = self.faults.on_request(&request).await {
+            return err.into()
+        }
         match request {
             EthRequest::Web3ClientVersion(()) => self.client_version().to_rpc_result(),
             EthRequest::Web3Sha3(content) => self.sha3(content).to_rpc_result(),
@@ -207,6 +225,16 @@ impl EthApi {
                 self.get_proof(addr, keys, block).await.to_rpc_result()
             }
             EthRequest::EthSign(addr, content) => self.sign(addr, content).await.to_rpc_result(),
+            EthRequest::PersonalSign(content, addr, _) => {
+                self.personal_sign(content, addr).await.to_rpc_result()
+            }
+            EthRequest::PersonalEcRecover(content, signature) => {
+                self.personal_ec_recover(content, signature).to_rpc_result()
+            }
+            EthRequest::PersonalListAccounts(()) => self.accounts().to_rpc_result(),
+            EthRequest::PersonalUnlockAccount(addr, _, _) => {
+                self.personal_unlock_account(addr).to_rpc_result()
+            }
             EthRequest::EthSignTransaction(request) => {
                 self.sign_transaction(*request).await.to_rpc_result()
             }
@@ -222,6 +250,10 @@ impl EthApi {
             EthRequest::EthSendRawTransaction(tx) => {
                 self.send_raw_transaction(tx).await.to_rpc_result()
             }
+            EthRequest::EthSendRawTransactionConditional(tx, conditional) => {
+                self.send_raw_transaction_conditional(tx, conditional).await.to_rpc_result()
+            }
+            EthRequest::EthSendBundle(bundle) => self.send_bundle(bundle).await.to_rpc_result(),
             EthRequest::EthCall(call, block, overrides) => {
                 self.call(call, block, overrides).await.to_rpc_result()
             }
@@ -240,6 +272,12 @@ impl EthApi {
             EthRequest::EthGetTransactionReceipt(tx) => {
                 self.transaction_receipt(tx).await.to_rpc_result()
             }
+            EthRequest::EthGetBlobSidecars(block) => {
+                self.blob_sidecars(block).await.to_rpc_result()
+            }
+            EthRequest::GetBlobsByTransactionHash(hash) => {
+                self.anvil_get_blobs_by_transaction_hash(hash).await.to_rpc_result()
+            }
             EthRequest::EthGetUncleByBlockHashAndIndex(hash, index) => {
                 self.uncle_by_block_hash_and_index(hash, index).await.to_rpc_result()
             }
@@ -269,6 +307,8 @@ impl EthApi {
             }
             EthRequest::TraceTransaction(tx) => self.trace_transaction(tx).await.to_rpc_result(),
             EthRequest::TraceBlock(block) => self.trace_block(block).await.to_rpc_result(),
+            EthRequest::TraceGet(tx, indices) => self.trace_get(tx, indices).await.to_rpc_result(),
+            EthRequest::TraceFilter(filter) => self.trace_filter(filter).await.to_rpc_result(),
             EthRequest::ImpersonateAccount(addr) => {
                 self.anvil_impersonate_account(addr).await.to_rpc_result()
             }
@@ -308,7 +348,17 @@ impl EthApi {
             }
             EthRequest::SetCoinbase(addr) => self.anvil_set_coinbase(addr).await.to_rpc_result(),
             EthRequest::SetChainId(id) => self.anvil_set_chain_id(id).await.to_rpc_result(),
+            EthRequest::SetCodeSizeLimit(limit) => {
+                self.anvil_set_code_size_limit(limit).await.to_rpc_result()
+            }
+            EthRequest::SetInitcodeSizeLimit(limit) => {
+                self.anvil_set_initcode_size_limit(limit).await.to_rpc_result()
+            }
             EthRequest::SetLogging(log) => self.anvil_set_logging(log).await.to_rpc_result(),
+            EthRequest::SetFaultInjection(faults) => {
+                self.anvil_set_fault_injection(faults).await.to_rpc_result()
+            }
+            EthRequest::FaultInjection(()) => self.anvil_fault_injection().await.to_rpc_result(),
             EthRequest::SetMinGasPrice(gas) => {
                 self.anvil_set_min_gas_price(gas).await.to_rpc_result()
             }
@@ -317,6 +367,8 @@ impl EthApi {
             }
             EthRequest::DumpState(_) => self.anvil_dump_state().await.to_rpc_result(),
             EthRequest::LoadState(buf) => self.anvil_load_state(buf).await.to_rpc_result(),
+            EthRequest::ExportBlocks(()) => self.anvil_export_blocks().await.to_rpc_result(),
+            EthRequest::ExportGenesis(()) => self.anvil_export_genesis().await.to_rpc_result(),
             EthRequest::NodeInfo(_) => self.anvil_node_info().await.to_rpc_result(),
             EthRequest::AnvilMetadata(_) => self.anvil_metadata().await.to_rpc_result(),
             EthRequest::EvmSnapshot(_) => self.evm_snapshot().await.to_rpc_result(),
@@ -349,10 +401,16 @@ impl EthApi {
             EthRequest::EvmMineDetailed(mine) => {
                 self.evm_mine_detailed(mine.and_then(|p| p.params)).await.to_rpc_result()
             }
+            EthRequest::MineDetailed(mine) => {
+                self.anvil_mine_detailed(mine.and_then(|p| p.params)).await.to_rpc_result()
+            }
             EthRequest::SetRpcUrl(url) => self.anvil_set_rpc_url(url).to_rpc_result(),
             EthRequest::EthSendUnsignedTransaction(tx) => {
                 self.eth_send_unsigned_transaction(*tx).await.to_rpc_result()
             }
+            EthRequest::DepositTransaction(deposit) => {
+                self.anvil_deposit_transaction(*deposit).await.to_rpc_result()
+            }
             EthRequest::EnableTraces(_) => self.anvil_enable_traces().await.to_rpc_result(),
             EthRequest::EthNewFilter(filter) => self.new_filter(filter).await.to_rpc_result(),
             EthRequest::EthGetFilterChanges(id) => self.get_filter_changes(&id).await,
@@ -812,10 +870,45 @@ impl EthApi {
         Ok(format!("0x{signature}"))
     }
 
+    /// Signs data like `eth_sign`
+    ///
+    /// Handler for RPC call: `personal_sign`
+    pub async fn personal_sign(
+        &self,
+        content: impl AsRef<[u8]>,
+        address: Address,
+    ) -> Result<String> {
+        node_info!("personal_sign");
+        let signer = self.get_signer(address).ok_or(BlockchainError::NoSignerAvailable)?;
+        let signature = signer.sign(address, content.as_ref()).await?;
+        Ok(format!("0x{signature}"))
+    }
+
+    /// Returns the address that signed the content with `personal_sign`
+    ///
+    /// Handler for RPC call: `personal_ecRecover`
+    pub fn personal_ec_recover(&self, content: Bytes, signature: Bytes) -> Result<Address> {
+        node_info!("personal_ecRecover");
+        let signature = Signature::try_from(signature.as_ref())?;
+        Ok(signature.recover(content.to_vec())?)
+    }
+
+    /// Unlocks an account, which succeeds for all accounts managed by the node since they're
+    /// always unlocked
+    ///
+    /// Handler for RPC call: `personal_unlockAccount`
+    pub fn personal_unlock_account(&self, address: Address) -> Result<bool> {
+        node_info!("personal_unlockAccount");
+        Ok(self.get_signer(address).is_some())
+    }
+
     /// Signs a transaction
     ///
     /// Handler for ETH RPC call: `eth_signTransaction`
-    pub async fn sign_transaction(&self, request: EthTransactionRequest) -> Result<String> {
+    pub async fn sign_transaction(
+        &self,
+        request: EthTransactionRequest,
+    ) -> Result<SignedTransactionResponse> {
         node_info!("eth_signTransaction");
 
         let from = request.from.map(Ok).unwrap_or_else(|| {
@@ -827,8 +920,27 @@ impl EthApi {
         let request = self.build_typed_tx_request(request, nonce)?;
 
         let signer = self.get_signer(from).ok_or(BlockchainError::NoSignerAvailable)?;
-        let signature = signer.sign_transaction(request, &from)?;
-        Ok(format!("0x{signature}"))
+        let signature = format!("0x{}", signer.sign_transaction(request, &from)?);
+        let Some(fork) = self.get_fork() else {
+            return Ok(SignedTransactionResponse::Signature(signature))
+        };
+
+        // tag the signature, so it isn't mistaken for a transaction meant for the forked network
+        let chain_id = self.chain_id();
+        let forked_chain_id = fork.remote_chain_id();
+        let replayable_on_fork = chain_id == forked_chain_id;
+        if replayable_on_fork {
+            node_info!(
+                "Warning: the signed transaction is valid on the forked network, never broadcast \
+                 it there"
+            );
+        }
+        Ok(SignedTransactionResponse::Forked(ForkSignedTransaction {
+            signature,
+            chain_id,
+            forked_chain_id,
+            replayable_on_fork,
+        }))
     }
 
     /// Sends a transaction
@@ -872,16 +984,53 @@ impl EthApi {
     /// Handler for ETH RPC call: `eth_sendRawTransaction`
     pub async fn send_raw_transaction(&self, tx: Bytes) -> Result<TxHash> {
         node_info!("eth_sendRawTransaction");
+        let pending_transaction = self.decode_raw_transaction(tx)?;
+        self.submit_raw_transaction(pending_transaction).await
+    }
+
+    /// Validates a decoded signed transaction and adds it to the pool.
+    async fn submit_raw_transaction(
+        &self,
+        pending_transaction: PendingTransaction,
+    ) -> Result<TxHash> {
+        // pre-validate
+        self.backend.validate_pool_transaction(&pending_transaction).await?;
+
+        let on_chain_nonce = self.backend.current_nonce(*pending_transaction.sender()).await?;
+        let from = *pending_transaction.sender();
+        let nonce = *pending_transaction.transaction.nonce();
+        let requires = required_marker(nonce, on_chain_nonce, from);
+        let provides = vec![to_marker(nonce.as_u64(), from)];
+
+        self.add_pending_transaction(pending_transaction, requires, provides)
+    }
+
+    /// Decodes a signed transaction in its network form.
+    fn decode_raw_transaction(&self, tx: Bytes) -> Result<PendingTransaction> {
         let data = tx.as_ref();
         if data.is_empty() {
             return Err(BlockchainError::EmptyRawTransactionData)
         }
+        let mut blob_sidecar = None;
         let transaction = if data[0] > 0x7f {
             // legacy transaction
             match rlp::decode::<LegacyTransaction>(data) {
                 Ok(transaction) => TypedTransaction::Legacy(transaction),
                 Err(_) => return Err(BlockchainError::FailedToDecodeSignedTransaction),
             }
+        } else if data[0] == 0x03 {
+            // blob transactions are sent in their network form, with the blobs attached
+            let (tx, sidecar) = match decode_blob_transaction_with_sidecar(&data[1..]) {
+                Ok(decoded) => decoded,
+                Err(_) => return Err(BlockchainError::FailedToDecodeSignedTransaction),
+            };
+            let tx = TypedTransaction::EIP4844(tx);
+
+            self.ensure_typed_transaction_supported(&tx)?;
+            ensure_valid_blob_sidecar(tx.blob_versioned_hashes(), &sidecar)?;
+            blob_sidecar = Some(sidecar);
+
+            tx
         } else {
             // the [TypedTransaction] requires a valid rlp input,
             // but EIP-1559 prepends a version byte, so we need to encode the data first to get a
@@ -898,27 +1047,83 @@ impl EthApi {
             tx
         };
 
-        let pending_transaction = PendingTransaction::new(transaction)?;
+        let mut pending_transaction = PendingTransaction::new(transaction)?;
+        if let Some(sidecar) = blob_sidecar {
+            pending_transaction = pending_transaction.with_blob_sidecar(sidecar);
+        }
+        Ok(pending_transaction)
+    }
 
-        // pre-validate
-        self.backend.validate_pool_transaction(&pending_transaction).await?;
+    /// Sends a bundle of signed transactions that is applied atomically at the top of its target
+    /// block, like a block builder would.
+    ///
+    /// The bundle is dropped if any of its transactions is invalid or reverts, unless it's
+    /// allowed to revert. In auto-mine mode, a bundle for the next block is mined immediately.
+    ///
+    /// Handler for ETH RPC call: `eth_sendBundle`
+    pub async fn send_bundle(&self, bundle: Bundle) -> Result<BundleHash> {
+        node_info!("eth_sendBundle");
+        let Bundle { txs, block_number, min_timestamp, max_timestamp, reverting_tx_hashes } =
+            bundle;
+        if txs.is_empty() {
+            return Err(BlockchainError::InvalidBundle("the bundle has no transactions".to_string()))
+        }
+        let next_block = self.backend.best_number() + U64::one();
+        if block_number < next_block {
+            return Err(BlockchainError::InvalidBundle(format!(
+                "block {block_number} is already mined, the next block is {next_block}"
+            )))
+        }
 
-        let on_chain_nonce = self.backend.current_nonce(*pending_transaction.sender()).await?;
-        let from = *pending_transaction.sender();
-        let nonce = *pending_transaction.transaction.nonce();
-        let requires = required_marker(nonce, on_chain_nonce, from);
+        let mut transactions = Vec::with_capacity(txs.len());
+        let mut hashes = Vec::with_capacity(txs.len() * 32);
+        for tx in txs {
+            let pending_transaction = self.decode_raw_transaction(tx)?;
+            hashes.extend_from_slice(pending_transaction.hash().as_bytes());
+            let nonce = pending_transaction.transaction.nonce().as_u64();
+            let priority = self.transaction_priority(&pending_transaction.transaction);
+            transactions.push(Arc::new(PoolTransaction {
+                requires: vec![],
+                provides: vec![to_marker(nonce, *pending_transaction.sender())],
+                pending_transaction,
+                priority,
+            }));
+        }
 
-        let priority = self.transaction_priority(&pending_transaction.transaction);
-        let pool_transaction = PoolTransaction {
-            requires,
-            provides: vec![to_marker(nonce.as_u64(), *pending_transaction.sender())],
-            pending_transaction,
-            priority,
-        };
+        self.backend.add_bundle(PendingBundle {
+            transactions,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+        });
+        if self.miner.is_auto_mine() && block_number == next_block {
+            self.mine_one().await;
+        }
 
-        let tx = self.pool.add_transaction(pool_transaction)?;
-        trace!(target: "node", "Added transaction: [{:?}] sender={:?}", tx.hash(), from);
-        Ok(*tx.hash())
+        Ok(BundleHash { bundle_hash: H256::from(keccak256(hashes)) })
+    }
+
+    /// Sends a signed transaction that is only included in a block if its preconditions hold.
+    ///
+    /// Handler for ETH RPC call: `eth_sendRawTransactionConditional`
+    pub async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> Result<TxHash> {
+        node_info!("eth_sendRawTransactionConditional");
+        let pending_transaction = self.decode_raw_transaction(tx)?;
+
+        // reject the transaction right away if it couldn't be included in the pending block
+        let number = self.backend.best_number().as_u64() + 1;
+        let timestamp = self.backend.time().current_call_timestamp();
+        {
+            let db = self.backend.get_db().read().await;
+            ensure_transaction_conditional(&conditional, &**db, number, timestamp)?;
+        }
+
+        self.submit_raw_transaction(pending_transaction.with_conditional(conditional)).await
     }
 
     /// Call contract, returning the output data.
@@ -1102,6 +1307,14 @@ impl EthApi {
         self.backend.transaction_receipt(hash).await
     }
 
+    /// Returns the blobs of the blob transactions mined in the given block.
+    ///
+    /// Handler for ETH RPC call: `eth_getBlobSidecars`
+    pub async fn blob_sidecars(&self, block: BlockId) -> Result<Option<Vec<BlobSidecar>>> {
+        node_info!("eth_getBlobSidecars");
+        Ok(self.backend.blob_sidecars_by_block(block))
+    }
+
     /// Returns an uncles at given block and index.
     ///
     /// Handler for ETH RPC call: `eth_getUncleByBlockHashAndIndex`
@@ -1190,7 +1403,13 @@ impl EthApi {
         reward_percentiles: Vec<f64>,
     ) -> Result<FeeHistory> {
         node_info!("eth_feeHistory");
-        // max number of blocks in the requested range
+
+        // the percentiles must be in range and sorted, like geth requires
+        if reward_percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) ||
+            reward_percentiles.windows(2).any(|w| w[0] > w[1])
+        {
+            return Err(FeeHistoryError::InvalidRewardPercentiles(reward_percentiles).into())
+        }
 
         let current = self.backend.best_number().as_u64();
         let slots_in_an_epoch = 32u64;
@@ -1222,6 +1441,17 @@ impl EthApi {
         let range_limit = U256::from(MAX_BLOCK_COUNT);
         let block_count =
             if block_count > range_limit { range_limit.as_u64() } else { block_count.as_u64() };
+        if block_count == 0 {
+            return Ok(FeeHistory {
+                oldest_block: U256::zero(),
+                base_fee_per_gas: Vec::new(),
+                gas_used_ratio: Vec::new(),
+                reward: Vec::new(),
+            })
+        }
+        if number > current {
+            return Err(FeeHistoryError::InvalidBlockRange.into())
+        }
 
         // highest and lowest block num in the requested range
         let highest = number;
@@ -1251,19 +1481,9 @@ impl EthApi {
 
                 // requested percentiles
                 if !reward_percentiles.is_empty() {
-                    let mut block_rewards = Vec::new();
-                    let resolution_per_percentile: f64 = 2.0;
-                    for p in &reward_percentiles {
-                        let p = p.clamp(0.0, 100.0);
-                        let index = ((p.round() / 2f64) * 2f64) * resolution_per_percentile;
-                        let reward = if let Some(r) = block.rewards.get(index as usize) {
-                            U256::from(*r)
-                        } else {
-                            U256::zero()
-                        };
-                        block_rewards.push(reward);
-                    }
-                    rewards.push(block_rewards);
+                    rewards.push(
+                        block.rewards_at(&reward_percentiles).into_iter().map(U256::from).collect(),
+                    );
                 }
             }
         }
@@ -1271,25 +1491,17 @@ impl EthApi {
         response.reward = rewards;
 
         // calculate next base fee
-        if let (Some(last_gas_used), Some(last_fee_per_gas)) =
-            (response.gas_used_ratio.last(), response.base_fee_per_gas.last())
-        {
-            let elasticity = self.backend.elasticity();
-            let last_fee_per_gas = last_fee_per_gas.as_u64() as f64;
-            if last_gas_used > &0.5 {
-                // increase base gas
-                let increase = ((last_gas_used - 0.5) * 2f64) * elasticity;
-                let new_base_fee = (last_fee_per_gas + (last_fee_per_gas * increase)) as u64;
-                response.base_fee_per_gas.push(U256::from(new_base_fee));
-            } else if last_gas_used < &0.5 {
-                // decrease gas
-                let increase = ((0.5 - last_gas_used) * 2f64) * elasticity;
-                let new_base_fee = (last_fee_per_gas - (last_fee_per_gas * increase)) as u64;
-                response.base_fee_per_gas.push(U256::from(new_base_fee));
+        if let Some(block) = fee_history.get(&highest) {
+            let next_base_fee = if self.backend.is_eip1559() {
+                self.backend.fees().get_next_block_base_fee_per_gas(
+                    block.gas_used.into(),
+                    block.gas_limit.into(),
+                    block.base_fee.into(),
+                )
             } else {
-                // same base gas
-                response.base_fee_per_gas.push(U256::from(last_fee_per_gas as u64));
-            }
+                block.base_fee
+            };
+            response.base_fee_per_gas.push(U256::from(next_base_fee));
         }
 
         Ok(response)
@@ -1426,6 +1638,27 @@ impl EthApi {
         node_info!("trace_block");
         self.backend.trace_block(block).await
     }
+
+    /// Returns the trace at the position of the transaction via parity's tracing endpoint
+    ///
+    /// Handler for RPC call: `trace_get`
+    pub async fn trace_get(&self, tx_hash: H256, indices: Vec<Index>) -> Result<Option<Trace>> {
+        node_info!("trace_get");
+        // like parity, only the first index is used
+        let Some(index) = indices.first().copied() else {
+            return Err(RpcError::invalid_params("expected a trace index").into())
+        };
+        let traces = self.backend.trace_transaction(tx_hash).await?;
+        Ok(traces.into_iter().nth(index.into()))
+    }
+
+    /// Returns the traces matching the filter via parity's tracing endpoint
+    ///
+    /// Handler for RPC call: `trace_filter`
+    pub async fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<Trace>> {
+        node_info!("trace_filter");
+        self.backend.trace_filter(filter).await
+    }
 }
 
 // == impl EthApi anvil endpoints ==
@@ -1558,6 +1791,25 @@ impl EthApi {
         Ok(())
     }
 
+    /// Sets the EIP-170 contract code size limit, `None` for the default limit.
+    ///
+    /// Handler for RPC call: `anvil_setCodeSizeLimit`
+    pub async fn anvil_set_code_size_limit(&self, limit: Option<usize>) -> Result<()> {
+        node_info!("anvil_setCodeSizeLimit");
+        self.backend.set_code_size_limit(limit);
+        Ok(())
+    }
+
+    /// Sets the initcode size limit of contract creation transactions, `None` for twice the code
+    /// size limit.
+    ///
+    /// Handler for RPC call: `anvil_setInitcodeSizeLimit`
+    pub async fn anvil_set_initcode_size_limit(&self, limit: Option<usize>) -> Result<()> {
+        node_info!("anvil_setInitcodeSizeLimit");
+        self.backend.set_initcode_size_limit(limit);
+        Ok(())
+    }
+
     /// Modifies the balance of an account.
     ///
     /// Handler for RPC call: `anvil_setBalance`
@@ -1569,9 +1821,15 @@ impl EthApi {
 
     /// Sets the code of a contract.
     ///
+    /// The code can also be an EIP-7702 delegation designator, `0xef0100 ++ address`, in which
+    /// case calls to the account execute the code of the delegated address.
+    ///
     /// Handler for RPC call: `anvil_setCode`
     pub async fn anvil_set_code(&self, address: Address, code: Bytes) -> Result<()> {
         node_info!("anvil_setCode");
+        if delegation::is_malformed_designator(&code) {
+            return Err(BlockchainError::InvalidDelegationDesignator)
+        }
         self.backend.set_code(address, code).await?;
         Ok(())
     }
@@ -1608,6 +1866,23 @@ impl EthApi {
         Ok(())
     }
 
+    /// Sets the faults injected into RPC calls and submitted transactions.
+    ///
+    /// Handler for RPC call: `anvil_setFaultInjection`
+    pub async fn anvil_set_fault_injection(&self, faults: FaultInjection) -> Result<()> {
+        node_info!("anvil_setFaultInjection");
+        self.faults.set_faults(faults);
+        Ok(())
+    }
+
+    /// Returns the faults injected into RPC calls and submitted transactions.
+    ///
+    /// Handler for RPC call: `anvil_faultInjection`
+    pub async fn anvil_fault_injection(&self) -> Result<FaultInjection> {
+        node_info!("anvil_faultInjection");
+        Ok(self.faults.faults())
+    }
+
     /// Set the minimum gas price for the node.
     ///
     /// Handler for RPC call: `anvil_setMinGasPrice`
@@ -1670,6 +1945,24 @@ impl EthApi {
         self.backend.load_state(buf).await
     }
 
+    /// Exports the blocks mined on top of the genesis block as concatenated RLP, which can be
+    /// imported into other clients with `geth import` or `reth import`
+    ///
+    /// Handler for RPC call: `anvil_exportBlocks`
+    pub async fn anvil_export_blocks(&self) -> Result<Bytes> {
+        node_info!("anvil_exportBlocks");
+        Ok(self.backend.export_blocks())
+    }
+
+    /// Exports the genesis of the chain in the `genesis.json` format of geth and reth, which is
+    /// required to import the blocks of `anvil_exportBlocks`
+    ///
+    /// Handler for RPC call: `anvil_exportGenesis`
+    pub async fn anvil_export_genesis(&self) -> Result<serde_json::Value> {
+        node_info!("anvil_exportGenesis");
+        self.backend.export_genesis()
+    }
+
     /// Retrieves the Anvil node configuration params.
     ///
     /// Handler for RPC call: `anvil_nodeInfo`
@@ -1703,6 +1996,7 @@ impl EthApi {
                         fork_url: Some(config.eth_rpc_url.clone()),
                         fork_block_number: Some(config.block_number),
                         fork_retry_backoff: Some(config.backoff.as_millis()),
+                        cache_stats: Some(fork.metrics.stats()),
                     }
                 })
                 .unwrap_or_default(),
@@ -1836,13 +2130,11 @@ impl EthApi {
 
         let mined_blocks = self.do_evm_mine(opts).await?;
 
-        let mut blocks = Vec::with_capacity(mined_blocks as usize);
+        let mut blocks = Vec::with_capacity(mined_blocks.len());
 
-        let latest = self.backend.best_number().as_u64();
-        for offset in (0..mined_blocks).rev() {
-            let block_num = latest - offset;
+        for block_num in mined_blocks {
             if let Some(mut block) =
-                self.backend.block_by_number_full(BlockNumber::Number(block_num.into())).await?
+                self.backend.block_by_number_full(BlockNumber::Number(block_num)).await?
             {
                 for tx in block.transactions.iter_mut() {
                     if let Some(receipt) = self.backend.mined_transaction_receipt(tx.hash) {
@@ -1870,6 +2162,36 @@ impl EthApi {
         Ok(blocks)
     }
 
+    /// Mines blocks and returns them with the receipts and call traces of their transactions
+    ///
+    /// This saves test frameworks from querying every receipt and trace after mining.
+    ///
+    /// Handler for RPC call: `anvil_mineDetailed`
+    pub async fn anvil_mine_detailed(
+        &self,
+        opts: Option<EvmMineOptions>,
+    ) -> Result<Vec<MinedBlockDetails>> {
+        node_info!("anvil_mineDetailed");
+
+        let mined_blocks = self.do_evm_mine(opts).await?;
+
+        let mut details = Vec::with_capacity(mined_blocks.len());
+        for number in mined_blocks {
+            let number = BlockNumber::Number(number);
+            let Some(block) = self.backend.block_by_number_full(number).await? else { continue };
+            let receipts = block
+                .transactions
+                .iter()
+                .filter_map(|tx| self.backend.mined_transaction_receipt(tx.hash))
+                .map(|receipt| receipt.inner)
+                .collect();
+            let traces = self.backend.trace_block(number).await?;
+            details.push(MinedBlockDetails { block, receipts, traces });
+        }
+
+        Ok(details)
+    }
+
     /// Sets the reported block number
     ///
     /// Handler for ETH RPC call: `anvil_setBlock`
@@ -1891,6 +2213,8 @@ impl EthApi {
                 ProviderBuilder::new(&url)
                     .max_retry(10)
                     .initial_backoff(1000)
+                    .headers(config.headers.clone())
+                    .sigv4(config.sigv4.clone())
                     .build()
                     .map_err(|_| {
                         ProviderError::CustomError(format!("Failed to parse invalid url {url}"))
@@ -1944,6 +2268,79 @@ impl EthApi {
         self.add_pending_transaction(pending_transaction, requires, provides)
     }
 
+    /// Returns the blobs of a mined blob transaction, or `None` if the transaction is unknown or
+    /// isn't a blob transaction.
+    ///
+    /// Handler for RPC call: `anvil_getBlobsByTransactionHash`
+    pub async fn anvil_get_blobs_by_transaction_hash(
+        &self,
+        hash: TxHash,
+    ) -> Result<Option<Vec<BlobSidecar>>> {
+        node_info!("anvil_getBlobsByTransactionHash");
+        Ok(self.backend.blob_sidecars_by_transaction(hash))
+    }
+
+    /// Executes an op-stack deposit transaction, as if it was deposited on L1.
+    ///
+    /// `mint` is credited to the sender before the transaction is executed, and the transaction
+    /// doesn't pay for gas. Requires `--optimism`.
+    ///
+    /// Handler for RPC call: `anvil_depositTransaction`
+    pub async fn anvil_deposit_transaction(
+        &self,
+        deposit: DepositTransactionParams,
+    ) -> Result<TxHash> {
+        node_info!("anvil_depositTransaction");
+        self.backend.ensure_op_deposits_active()?;
+
+        let DepositTransactionParams {
+            from,
+            to,
+            mint,
+            value,
+            gas,
+            data,
+            is_system_tx,
+            source_hash,
+        } = deposit;
+        let request = EthTransactionRequest {
+            from: Some(from),
+            to,
+            gas,
+            value: Some(value),
+            data: Some(data),
+            transaction_type: Some(U256::from(0x7E)),
+            ..Default::default()
+        };
+        let (nonce, on_chain_nonce) = self.request_nonce(&request, from).await?;
+
+        // without an L1 log to derive it from, the source hash is derived from the sender, its
+        // nonce and the current block, which is unique for every deposit
+        let source_hash = source_hash.unwrap_or_else(|| {
+            let mut preimage = from.as_bytes().to_vec();
+            preimage.extend_from_slice(&H256::from_uint(&nonce).0);
+            preimage
+                .extend_from_slice(&H256::from_low_u64_be(self.backend.best_number().as_u64()).0);
+            H256::from(keccak256(preimage))
+        });
+        let request = EthTransactionRequest {
+            optimism_fields: Some(OptimismDepositRequestFields { source_hash, mint, is_system_tx }),
+            ..request
+        };
+
+        let request = self.build_typed_tx_request(request, nonce)?;
+        let transaction = self.sign_request(&from, request)?;
+        let pending_transaction = PendingTransaction::new(transaction)?;
+
+        // pre-validate
+        self.backend.validate_pool_transaction(&pending_transaction).await?;
+
+        let requires = required_marker(nonce, on_chain_nonce, from);
+        let provides = vec![to_marker(nonce.as_u64(), from)];
+
+        self.add_pending_transaction(pending_transaction, requires, provides)
+    }
+
     /// Returns the number of transactions currently pending for inclusion in the next block(s), as
     /// well as the ones that are being scheduled for future execution only.
     /// Ref: [Here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_status)
@@ -2054,8 +2451,8 @@ impl EthApi {
         rx.await.map_err(|_| BlockchainError::Internal("blocking task panicked".to_string()))?
     }
 
-    /// Executes the `evm_mine` and returns the number of blocks mined
-    async fn do_evm_mine(&self, opts: Option<EvmMineOptions>) -> Result<u64> {
+    /// Executes the `evm_mine` and returns the numbers of the mined blocks
+    async fn do_evm_mine(&self, opts: Option<EvmMineOptions>) -> Result<Vec<U64>> {
         let mut blocks_to_mine = 1u64;
 
         if let Some(opts) = opts {
@@ -2075,11 +2472,12 @@ impl EthApi {
         }
 
         // mine all the blocks
+        let mut mined = Vec::with_capacity(blocks_to_mine as usize);
         for _ in 0..blocks_to_mine {
-            self.mine_one().await;
+            mined.push(self.mine_one().await);
         }
 
-        Ok(blocks_to_mine)
+        Ok(mined)
     }
 
     async fn do_estimate_gas(
@@ -2109,7 +2507,7 @@ impl EthApi {
     /// This will execute the [EthTransactionRequest] and find the best gas limit via binary search
     fn do_estimate_gas_with_state<D>(
         &self,
-        mut request: EthTransactionRequest,
+        request: EthTransactionRequest,
         state: D,
         block_env: BlockEnv,
     ) -> Result<U256>
@@ -2223,74 +2621,54 @@ impl EthApi {
         }
 
         // at this point we know the call succeeded but want to find the _best_ (lowest) gas the
-        // transaction succeeds with. we find this by doing a binary search over the
-        // possible range NOTE: this is the gas the transaction used, which is less than the
-        // transaction requires to succeed
+        // transaction succeeds with, which we find by a binary search like geth does:
+        // <https://github.com/ethereum/go-ethereum/blob/master/eth/gasestimator/gasestimator.go>
+        //
+        // the transaction can't succeed with less gas than it used
         let gas: U256 = gas.into();
-        // Get the starting lowest gas needed depending on the transaction kind.
-        let mut lowest_gas_limit = determine_base_gas_by_kind(request.clone());
-
-        // pick a point that's close to the estimated gas
-        let mut mid_gas_limit = std::cmp::min(gas * 3, (highest_gas_limit + lowest_gas_limit) / 2);
-
-        // Binary search for the ideal gas limit
-        while (highest_gas_limit - lowest_gas_limit) > U256::one() {
-            request.gas = Some(mid_gas_limit);
-            let ethres = self.backend.call_with_state(
+        let mut lowest_gas_limit = gas.saturating_sub(U256::one());
+
+        // most transactions succeed with the gas they used, plus the gas that the 63/64 rule
+        // withholds from calls and the stipend of value transfers, so try that first
+        let optimistic_gas_limit = (gas + CALL_STIPEND) * 64 / 63;
+        if optimistic_gas_limit < highest_gas_limit {
+            if self.estimate_gas_succeeds(
+                &request,
+                optimistic_gas_limit,
                 &state,
-                request.clone(),
-                fees.clone(),
-                block_env.clone(),
-            );
+                &fees,
+                &block_env,
+            )? {
+                highest_gas_limit = optimistic_gas_limit;
+            } else {
+                lowest_gas_limit = optimistic_gas_limit;
+            }
+        }
 
-            // Exceptional case: init used too much gas, we need to increase the gas limit and try
-            // again
-            if let Err(BlockchainError::InvalidTransaction(InvalidTransactionError::GasTooHigh(
-                _,
-            ))) = ethres
+        // Binary search for the ideal gas limit
+        while lowest_gas_limit + U256::one() < highest_gas_limit {
+            // stop once the estimate is close enough to the ideal gas limit
+            if (highest_gas_limit - lowest_gas_limit) * 1000 <
+                highest_gas_limit * ESTIMATE_GAS_ERROR_RATIO_PER_MILLE
             {
-                // increase the lowest gas limit
+                break
+            }
+
+            // most transactions need about the gas they used, so the search is biased towards the
+            // lowest gas limit
+            let mid_gas_limit =
+                std::cmp::min((highest_gas_limit + lowest_gas_limit) / 2, lowest_gas_limit * 2);
+            if self.estimate_gas_succeeds(&request, mid_gas_limit, &state, &fees, &block_env)? {
+                // If the transaction succeeded, we can set a ceiling for the highest gas limit
+                // at the current midpoint, as spending any more gas would make no sense (as the TX
+                // would still succeed).
+                highest_gas_limit = mid_gas_limit;
+            } else {
+                // If the transaction failed due to lack of gas, we can set a floor for the lowest
+                // gas limit at the current midpoint, as spending any less gas would make no sense
+                // (as the TX would still revert due to lack of gas).
                 lowest_gas_limit = mid_gas_limit;
-
-                // new midpoint
-                mid_gas_limit = (highest_gas_limit + lowest_gas_limit) / 2;
-                continue
             }
-
-            match ethres {
-                Ok((exit, _, _gas, _)) => match exit {
-                    // If the transaction succeeded, we can set a ceiling for the highest gas limit
-                    // at the current midpoint, as spending any more gas would
-                    // make no sense (as the TX would still succeed).
-                    return_ok!() => {
-                        highest_gas_limit = mid_gas_limit;
-                    }
-                    // If the transaction failed due to lack of gas, we can set a floor for the
-                    // lowest gas limit at the current midpoint, as spending any
-                    // less gas would make no sense (as the TX would still revert due to lack of
-                    // gas).
-                    InstructionResult::Revert |
-                    InstructionResult::OutOfGas |
-                    InstructionResult::OutOfFund |
-                    // we're also checking for InvalidFEOpcode here because this can be used to trigger an error <https://github.com/foundry-rs/foundry/issues/6138> common usage in openzeppelin <https://github.com/OpenZeppelin/openzeppelin-contracts/blob/94697be8a3f0dfcd95dfb13ffbd39b5973f5c65d/contracts/metatx/ERC2771Forwarder.sol#L360-L367>
-                    InstructionResult::InvalidFEOpcode => {
-                        lowest_gas_limit = mid_gas_limit;
-                    }
-                    // The tx failed for some other reason.
-                    reason => {
-                        warn!(target: "node", "estimation failed due to {:?}", reason);
-                        return Err(BlockchainError::EvmError(reason))
-                    }
-                },
-                // We've already checked for the exceptional GasTooHigh case above, so this is a
-                // real error.
-                Err(reason) => {
-                    warn!(target: "node", "estimation failed due to {:?}", reason);
-                    return Err(reason)
-                }
-            }
-            // new midpoint
-            mid_gas_limit = (highest_gas_limit + lowest_gas_limit) / 2;
         }
 
         trace!(target : "node", "Estimated Gas for call {:?}", highest_gas_limit);
@@ -2298,6 +2676,46 @@ impl EthApi {
         Ok(highest_gas_limit)
     }
 
+    /// Executes the `request` with the `gas_limit` and returns whether it succeeded, or whether it
+    /// failed due to lack of gas.
+    fn estimate_gas_succeeds<D>(
+        &self,
+        request: &EthTransactionRequest,
+        gas_limit: U256,
+        state: &D,
+        fees: &FeeDetails,
+        block_env: &BlockEnv,
+    ) -> Result<bool>
+    where
+        D: DatabaseRef<Error = DatabaseError>,
+    {
+        let mut request = request.clone();
+        request.gas = Some(gas_limit);
+        match self.backend.call_with_state(state, request, fees.clone(), block_env.clone()) {
+            Ok((exit, _, _, _)) => match exit {
+                return_ok!() => Ok(true),
+                InstructionResult::Revert |
+                InstructionResult::OutOfGas |
+                InstructionResult::OutOfFund |
+                // we're also checking for InvalidFEOpcode here because this can be used to trigger an error <https://github.com/foundry-rs/foundry/issues/6138> common usage in openzeppelin <https://github.com/OpenZeppelin/openzeppelin-contracts/blob/94697be8a3f0dfcd95dfb13ffbd39b5973f5c65d/contracts/metatx/ERC2771Forwarder.sol#L360-L367>
+                InstructionResult::InvalidFEOpcode => Ok(false),
+                // The tx failed for some other reason.
+                reason => {
+                    warn!(target: "node", "estimation failed due to {:?}", reason);
+                    Err(BlockchainError::EvmError(reason))
+                }
+            },
+            // Exceptional case: init used too much gas, the gas limit needs to be increased
+            Err(BlockchainError::InvalidTransaction(InvalidTransactionError::GasTooHigh(_))) => {
+                Ok(false)
+            }
+            Err(reason) => {
+                warn!(target: "node", "estimation failed due to {:?}", reason);
+                Err(reason)
+            }
+        }
+    }
+
     /// Updates the `TransactionOrder`
     pub fn set_transaction_order(&self, order: TransactionOrder) {
         *self.transaction_order.write() = order;
@@ -2353,13 +2771,15 @@ impl EthApi {
         self.backend.is_fork()
     }
 
-    /// Mines exactly one block
-    pub async fn mine_one(&self) {
+    /// Mines exactly one block and returns its number
+    pub async fn mine_one(&self) -> U64 {
         let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
         let outcome = self.backend.mine_block(transactions).await;
+        let block_number = outcome.block_number;
 
-        trace!(target: "node", blocknumber = ?outcome.block_number, "mined block");
+        trace!(target: "node", blocknumber = ?block_number, "mined block");
         self.pool.on_mined_block(outcome);
+        block_number
     }
 
     /// Returns the pending block with tx hashes
@@ -2497,6 +2917,11 @@ impl EthApi {
         provides: Vec<TxMarker>,
     ) -> Result<TxHash> {
         let from = *pending_transaction.sender();
+        if self.faults.should_drop_transaction() {
+            let hash = *pending_transaction.hash();
+            trace!(target: "node", "Dropping transaction: [{:?}] sender={:?}", hash, from);
+            return Ok(hash)
+        }
         let priority = self.transaction_priority(&pending_transaction.transaction);
         let pool_transaction =
             PoolTransaction { requires, provides, pending_transaction, priority };
@@ -2515,12 +2940,69 @@ impl EthApi {
         match &tx {
             TypedTransaction::EIP2930(_) => self.backend.ensure_eip2930_active(),
             TypedTransaction::EIP1559(_) => self.backend.ensure_eip1559_active(),
+            TypedTransaction::EIP4844(_) => self.backend.ensure_eip4844_active(),
             TypedTransaction::Deposit(_) => self.backend.ensure_op_deposits_active(),
             TypedTransaction::Legacy(_) => Ok(()),
         }
     }
 }
 
+/// Checks that the blobs of a blob transaction are well-formed, match the versioned hashes the
+/// transaction commits to, and that their KZG proofs are valid.
+fn ensure_valid_blob_sidecar(
+    versioned_hashes: &[H256],
+    sidecar: &BlobTransactionSidecar,
+) -> Result<()> {
+    let invalid = |msg: String| Err(BlockchainError::InvalidBlobSidecar(msg));
+
+    let BlobTransactionSidecar { blobs, commitments, proofs } = sidecar;
+    if blobs.is_empty() {
+        return invalid("blob transaction without blobs".to_string())
+    }
+    if blobs.len() > MAX_BLOBS_PER_BLOCK {
+        return invalid(format!("too many blobs: {} > {MAX_BLOBS_PER_BLOCK}", blobs.len()))
+    }
+    if blobs.len() != commitments.len() ||
+        blobs.len() != proofs.len() ||
+        blobs.len() != versioned_hashes.len()
+    {
+        return invalid(format!(
+            "mismatched number of blobs ({}), commitments ({}), proofs ({}) and versioned hashes ({})",
+            blobs.len(),
+            commitments.len(),
+            proofs.len(),
+            versioned_hashes.len()
+        ))
+    }
+    for (index, blob) in blobs.iter().enumerate() {
+        if blob.len() != BYTES_PER_BLOB {
+            return invalid(format!(
+                "blob {index} has {} bytes, expected {BYTES_PER_BLOB}",
+                blob.len()
+            ))
+        }
+    }
+    for (index, (commitment, proof)) in commitments.iter().zip(proofs).enumerate() {
+        if commitment.len() != 48 || proof.len() != 48 {
+            return invalid(format!("commitment or proof {index} is not 48 bytes"))
+        }
+    }
+    for (index, (expected, actual)) in
+        versioned_hashes.iter().zip(sidecar.versioned_hashes()).enumerate()
+    {
+        if *expected != actual {
+            return invalid(format!(
+                "versioned hash {index} {expected:?} doesn't match its commitment {actual:?}"
+            ))
+        }
+    }
+    match sidecar.verify_kzg_proofs() {
+        Ok(true) => Ok(()),
+        Ok(false) => invalid("the KZG proofs don't match the blobs and commitments".to_string()),
+        Err(err) => invalid(format!("malformed blob, commitment or proof: {err:?}")),
+    }
+}
+
 fn required_marker(provided_nonce: U256, on_chain_nonce: U256, from: Address) -> Vec<TxMarker> {
     if provided_nonce == on_chain_nonce {
         return Vec::new()
@@ -2586,31 +3068,3 @@ where
         }
     }
 }
-
-/// Determines the minimum gas needed for a transaction depending on the transaction kind.
-#[inline]
-fn determine_base_gas_by_kind(request: EthTransactionRequest) -> U256 {
-    match request.into_typed_request() {
-        Some(request) => match request {
-            TypedTransactionRequest::Legacy(req) => match req.kind {
-                TransactionKind::Call(_) => MIN_TRANSACTION_GAS,
-                TransactionKind::Create => MIN_CREATE_GAS,
-            },
-            TypedTransactionRequest::EIP1559(req) => match req.kind {
-                TransactionKind::Call(_) => MIN_TRANSACTION_GAS,
-                TransactionKind::Create => MIN_CREATE_GAS,
-            },
-            TypedTransactionRequest::EIP2930(req) => match req.kind {
-                TransactionKind::Call(_) => MIN_TRANSACTION_GAS,
-                TransactionKind::Create => MIN_CREATE_GAS,
-            },
-            TypedTransactionRequest::Deposit(req) => match req.kind {
-                TransactionKind::Call(_) => MIN_TRANSACTION_GAS,
-                TransactionKind::Create => MIN_CREATE_GAS,
-            },
-        },
-        // Tighten the gas limit upwards if we don't know the transaction type to avoid deployments
-        // failing.
-        _ => MIN_CREATE_GAS,
-    }
-}
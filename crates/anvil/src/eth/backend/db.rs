@@ -8,6 +8,7 @@ use ethers::{
     types::{BlockId, H256},
     utils::keccak256,
 };
+use flate2::read::GzDecoder;
 use foundry_common::{errors::FsPathError, types::ToAlloy};
 use foundry_evm::{
     backend::{DatabaseError, DatabaseResult, MemDb, RevertSnapshotAction, StateSnapshot},
@@ -21,7 +22,12 @@ use foundry_evm::{
 };
 use hash_db::HashDB;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt, path::Path};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Read},
+    path::Path,
+};
 
 /// Type alias for the `HashDB` representation of the Database
 pub type AsHashDB = Box<dyn HashDB<KeccakHasher, Vec<u8>>>;
@@ -348,6 +354,42 @@ impl SerializableState {
     pub(crate) fn parse(path: &str) -> Result<Self, String> {
         Self::load(path).map_err(|err| err.to_string())
     }
+
+    /// Decodes the payload of `anvil_dumpState`, which is gzipped JSON, or plain JSON
+    pub fn decode(buf: &[u8]) -> io::Result<Self> {
+        let mut decoder = GzDecoder::new(buf);
+        if decoder.header().is_some() {
+            let mut decoded = Vec::new();
+            decoder.read_to_end(&mut decoded)?;
+            Ok(serde_json::from_slice(&decoded)?)
+        } else {
+            Ok(serde_json::from_slice(buf)?)
+        }
+    }
+
+    /// Inserts all accounts and their storage into the cache of a fork, so that they don't have
+    /// to be fetched from the remote endpoint
+    pub fn insert_into_fork_cache(self, db: &BlockchainDb) {
+        let mut accounts = db.accounts().write();
+        let mut storage = db.storage().write();
+        for (addr, account) in self.accounts {
+            let code = (!account.code.0.is_empty())
+                .then(|| Bytecode::new_raw(alloy_primitives::Bytes(account.code.0)).to_checked());
+            accounts.insert(
+                addr.to_alloy(),
+                AccountInfo {
+                    balance: account.balance.to_alloy(),
+                    nonce: account.nonce,
+                    code_hash: code.as_ref().map(|code| code.hash_slow()).unwrap_or(KECCAK_EMPTY),
+                    code,
+                },
+            );
+            storage
+                .entry(addr.to_alloy())
+                .or_default()
+                .extend(account.storage.into_iter().map(|(k, v)| (k.to_alloy(), v.to_alloy())));
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -0,0 +1,227 @@
+//! Support for [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702) delegation designators.
+//!
+//! An account whose code is a delegation designator, `0xef0100 ++ address`, executes the code of
+//! the delegated address in its own context, with its own storage and balance. Designators are set
+//! with `evm_setAccountCode` and resolved by [DelegationInspector] when the account is called, so
+//! `EXTCODESIZE`, `EXTCODEHASH` and `EXTCODECOPY` still see the designator itself.
+
+use alloy_primitives::{Address, Bytes, B256, U256};
+use foundry_evm::revm::{
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::AccountInfo,
+    Database, EVMData, Inspector,
+};
+
+/// The prefix of a delegation designator.
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// The length of a delegation designator, the prefix followed by the delegated address.
+pub const DELEGATION_DESIGNATOR_LEN: usize = DELEGATION_PREFIX.len() + 20;
+
+/// The gas charged for accessing the delegated account when calling a delegated account, as for
+/// any other account access of [EIP-2929](https://eips.ethereum.org/EIPS/eip-2929).
+pub const COLD_ACCOUNT_ACCESS_COST: u64 = 2600;
+
+/// The gas charged for accessing an already accessed delegated account.
+pub const WARM_ACCOUNT_ACCESS_COST: u64 = 100;
+
+/// Returns the delegated address if the code is a delegation designator.
+pub fn delegation_target(code: &[u8]) -> Option<Address> {
+    (code.len() == DELEGATION_DESIGNATOR_LEN && code.starts_with(&DELEGATION_PREFIX))
+        .then(|| Address::from_slice(&code[DELEGATION_PREFIX.len()..]))
+}
+
+/// Returns whether the code starts with the `0xef01` prefix reserved for delegation designators,
+/// without being a valid designator.
+pub fn is_malformed_designator(code: &[u8]) -> bool {
+    code.starts_with(&DELEGATION_PREFIX[..2]) && delegation_target(code).is_none()
+}
+
+/// Returns the delegated address of the account, if its code is a delegation designator.
+pub fn account_delegation_target(info: &AccountInfo) -> Option<Address> {
+    let code = info.code.as_ref()?;
+    delegation_target(&code.bytes()[..code.len()])
+}
+
+/// An inspector that executes the code of the delegated account for calls to accounts with a
+/// delegation designator, and forwards everything else to the wrapped inspector.
+///
+/// Calling a delegated account charges the access of the delegated account from the gas of the
+/// call. Delegations are not followed recursively: a designator pointing to another delegated
+/// account executes the designator itself, which halts on the invalid `0xef` opcode.
+#[derive(Debug)]
+pub struct DelegationInspector<'a, I> {
+    inner: &'a mut I,
+}
+
+impl<'a, I> DelegationInspector<'a, I> {
+    /// Wraps the given inspector.
+    pub fn new(inner: &'a mut I) -> Self {
+        Self { inner }
+    }
+}
+
+/// Replaces the code address of the call with the delegated address if the called code is a
+/// delegation designator, and charges the access of the delegated account.
+///
+/// Returns `false` if the call doesn't have enough gas for the access.
+fn resolve_delegation<DB: Database>(data: &mut EVMData<'_, DB>, call: &mut CallInputs) -> bool {
+    let target = match data.journaled_state.load_code(call.contract, data.db) {
+        Ok((account, _)) => account_delegation_target(&account.info),
+        Err(_) => None,
+    };
+    let Some(target) = target else { return true };
+    let Ok((_, is_cold)) = data.journaled_state.load_account(target, data.db) else { return true };
+
+    let cost = if is_cold { COLD_ACCOUNT_ACCESS_COST } else { WARM_ACCOUNT_ACCESS_COST };
+    if call.gas_limit < cost {
+        return false
+    }
+    call.gas_limit -= cost;
+    call.contract = target;
+    true
+}
+
+impl<DB: Database, I: Inspector<DB>> Inspector<DB> for DelegationInspector<'_, I> {
+    #[inline]
+    fn initialize_interp(&mut self, interp: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        self.inner.initialize_interp(interp, data);
+    }
+
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        self.inner.step(interp, data);
+    }
+
+    #[inline]
+    fn log(
+        &mut self,
+        evm_data: &mut EVMData<'_, DB>,
+        address: &Address,
+        topics: &[B256],
+        data: &Bytes,
+    ) {
+        self.inner.log(evm_data, address, topics, data);
+    }
+
+    #[inline]
+    fn step_end(&mut self, interp: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        self.inner.step_end(interp, data);
+    }
+
+    #[inline]
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        call: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        let res = self.inner.call(data, call);
+        if res.0 != InstructionResult::Continue {
+            return res
+        }
+        if !resolve_delegation(data, call) {
+            return (InstructionResult::OutOfGas, Gas::new(0), Bytes::new())
+        }
+        res
+    }
+
+    #[inline]
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.inner.call_end(data, inputs, remaining_gas, ret, out)
+    }
+
+    #[inline]
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        call: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.inner.create(data, call)
+    }
+
+    #[inline]
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CreateInputs,
+        status: InstructionResult,
+        address: Option<Address>,
+        gas: Gas,
+        retdata: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.inner.create_end(data, inputs, status, address, gas, retdata)
+    }
+
+    #[inline]
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        self.inner.selfdestruct(contract, target, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, hex, keccak256};
+    use foundry_evm::revm::{
+        db::{CacheDB, EmptyDB},
+        inspectors::NoOpInspector,
+        primitives::{Bytecode, ExecutionResult, Output, TransactTo},
+        EVM,
+    };
+
+    #[test]
+    fn parses_designators() {
+        let target = address!("00000000000000000000000000000000000000aa");
+        let designator = [&DELEGATION_PREFIX[..], target.as_slice()].concat();
+        assert_eq!(delegation_target(&designator), Some(target));
+        assert!(!is_malformed_designator(&designator));
+
+        assert_eq!(delegation_target(&designator[..22]), None);
+        assert!(is_malformed_designator(&designator[..22]));
+        assert!(!is_malformed_designator(&hex!("6080")));
+    }
+
+    #[test]
+    fn executes_delegated_code() {
+        let eoa = address!("00000000000000000000000000000000000000bb");
+        let target = address!("00000000000000000000000000000000000000aa");
+        // returns the address of the executing account and the code size of the EOA:
+        // ADDRESS PUSH1 0 MSTORE PUSH20 eoa EXTCODESIZE PUSH1 32 MSTORE PUSH1 64 PUSH1 0 RETURN
+        let target_code =
+            [&hex!("3060005273")[..], eoa.as_slice(), &hex!("3b60205260406000f3")].concat();
+        let designator = [&DELEGATION_PREFIX[..], target.as_slice()].concat();
+        let account = |code: &[u8]| AccountInfo {
+            code_hash: keccak256(code),
+            code: Some(Bytecode::new_raw(code.to_vec().into())),
+            ..Default::default()
+        };
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(target, account(&target_code));
+        db.insert_account_info(eoa, account(&designator));
+
+        let mut evm = EVM::new();
+        evm.database(db);
+        evm.env.tx.transact_to = TransactTo::Call(eoa);
+        evm.env.tx.gas_limit = 100_000;
+        let mut inspector = NoOpInspector;
+        let result = evm.inspect_ref(DelegationInspector::new(&mut inspector)).unwrap().result;
+        let ExecutionResult::Success { output: Output::Call(out), .. } = result else {
+            panic!("{result:?}")
+        };
+        assert_eq!(Address::from_slice(&out[12..32]), eoa);
+        assert_eq!(U256::from_be_slice(&out[32..]), U256::from(DELEGATION_DESIGNATOR_LEN));
+
+        // the call runs out of gas for the access of the delegated account
+        evm.env.tx.gas_limit = 21_000 + COLD_ACCOUNT_ACCESS_COST - 1;
+        let result = evm.inspect_ref(DelegationInspector::new(&mut inspector)).unwrap().result;
+        assert!(matches!(result, ExecutionResult::Halt { .. }), "{result:?}");
+    }
+}
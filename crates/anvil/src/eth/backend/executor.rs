@@ -1,6 +1,11 @@
 use crate::{
     eth::{
-        backend::{db::Db, validate::TransactionValidator},
+        backend::{
+            db::Db,
+            delegation::DelegationInspector,
+            mem::intrinsic_gas,
+            validate::{ensure_transaction_conditional, TransactionValidator},
+        },
         error::InvalidTransactionError,
         pool::transactions::PoolTransaction,
     },
@@ -8,13 +13,20 @@ use crate::{
 };
 use anvil_core::eth::{
     block::{Block, BlockInfo, Header, PartialHeader},
-    receipt::{DepositReceipt, EIP1559Receipt, EIP2930Receipt, EIP658Receipt, Log, TypedReceipt},
-    transaction::{PendingTransaction, TransactionInfo, TypedTransaction},
+    receipt::{
+        DepositReceipt, EIP1559Receipt, EIP2930Receipt, EIP4844Receipt, EIP658Receipt, Log,
+        TypedReceipt,
+    },
+    state::AccountStateDiff,
+    transaction::{
+        GasDetails, PendingTransaction, TransactionInfo, TypedTransaction, DATA_GAS_PER_BLOB,
+        MAX_BLOBS_PER_BLOCK,
+    },
     trie,
 };
 use ethers::{
-    abi::ethereum_types::BloomInput,
-    types::{Bloom, H256, U256},
+    abi::ethereum_types::{BigEndianHash, BloomInput},
+    types::{Address, Bloom, H256, U256},
     utils::rlp,
 };
 use foundry_common::types::{ToAlloy, ToEthers};
@@ -23,12 +35,16 @@ use foundry_evm::{
     revm,
     revm::{
         interpreter::InstructionResult,
-        primitives::{BlockEnv, CfgEnv, EVMError, Env, ExecutionResult, Output, SpecId},
+        primitives::{
+            Account, BlockEnv, CfgEnv, EVMError, Env, ExecutionResult, Output, ResultAndState,
+            SpecId,
+        },
+        DatabaseCommit,
     },
     traces::{CallTraceArena, CallTraceNode},
     utils::{eval_to_instruction_result, halt_to_instruction_result},
 };
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 /// Represents an executed transaction (transacted on the DB)
 pub struct ExecutedTransaction {
@@ -36,9 +52,12 @@ pub struct ExecutedTransaction {
     exit_reason: InstructionResult,
     out: Option<Output>,
     gas_used: u64,
+    gas_refunded: u64,
     logs: Vec<Log>,
     traces: Vec<CallTraceNode>,
     nonce: u64,
+    /// The state changes of the transaction
+    state_diff: BTreeMap<Address, AccountStateDiff>,
 }
 
 // == impl ExecutedTransaction ==
@@ -72,6 +91,12 @@ impl ExecutedTransaction {
                 logs_bloom: bloom,
                 logs,
             }),
+            TypedTransaction::EIP4844(_) => TypedReceipt::EIP4844(EIP4844Receipt {
+                status_code,
+                gas_used: used_gas,
+                logs_bloom: bloom,
+                logs,
+            }),
             TypedTransaction::Deposit(_) => TypedReceipt::Deposit(DepositReceipt {
                 status_code,
                 gas_used: used_gas,
@@ -92,6 +117,8 @@ pub struct ExecutedTransactions {
     /// All transactions that were invalid at the point of their execution and were not included in
     /// the block
     pub invalid: Vec<Arc<PoolTransaction>>,
+    /// The combined state changes of the `included` transactions
+    pub state_diff: BTreeMap<Address, AccountStateDiff>,
 }
 
 /// An executor for a series of transactions
@@ -107,6 +134,8 @@ pub struct TransactionExecutor<'a, Db: ?Sized, Validator: TransactionValidator>
     pub parent_hash: H256,
     /// Cumulative gas used by all executed transactions
     pub gas_used: U256,
+    /// Cumulative blob gas used by all executed transactions
+    pub blob_gas_used: u64,
     pub enable_steps_tracing: bool,
 }
 
@@ -120,6 +149,7 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
         let mut cumulative_gas_used = U256::zero();
         let mut invalid = Vec::new();
         let mut included = Vec::new();
+        let mut state_diff = BTreeMap::<Address, AccountStateDiff>::new();
         let gas_limit = self.block_env.gas_limit;
         let parent_hash = self.parent_hash;
         let block_number = self.block_env.number;
@@ -131,6 +161,13 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
         } else {
             None
         };
+        let is_cancun = (self.cfg_env.spec_id as u8) >= (SpecId::CANCUN as u8);
+        let excess_blob_gas = self
+            .block_env
+            .blob_excess_gas_and_price
+            .as_ref()
+            .map(|blob| blob.excess_blob_gas)
+            .unwrap_or_default();
 
         for tx in self.into_iter() {
             let tx = match tx {
@@ -152,8 +189,25 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
             };
             let receipt = tx.create_receipt();
             cumulative_gas_used = cumulative_gas_used.saturating_add(receipt.gas_used());
-            let ExecutedTransaction { transaction, logs, out, traces, exit_reason: exit, .. } = tx;
+            let typed_tx = &tx.transaction.pending_transaction.transaction.transaction;
+            let gas_details = GasDetails::new(
+                self.cfg_env.spec_id,
+                intrinsic_gas(typed_tx, self.cfg_env.spec_id),
+                tx.gas_used,
+                tx.gas_refunded,
+                &typed_tx.essentials().access_list,
+            );
+            let ExecutedTransaction {
+                transaction,
+                logs,
+                out,
+                traces,
+                exit_reason: exit,
+                state_diff: tx_diff,
+                ..
+            } = tx;
             logs_bloom(logs.clone(), &mut bloom);
+            merge_state_diff(&mut state_diff, tx_diff);
 
             let contract_address = if let Some(Output::Create(_, contract_address)) = out {
                 trace!(target: "backend", "New contract deployed: at {:?}", contract_address);
@@ -179,6 +233,7 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
                     _ => None,
                 },
                 nonce: tx.nonce,
+                gas_details,
             };
 
             transaction_infos.push(info);
@@ -204,11 +259,13 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
             mix_hash: Default::default(),
             nonce: Default::default(),
             base_fee: base_fee.map(|b| b.to_ethers()),
+            blob_gas_used: is_cancun.then(|| self.blob_gas_used.into()),
+            excess_blob_gas: is_cancun.then(|| excess_blob_gas.into()),
         };
 
         let block = Block::new(partial_header, transactions.clone(), ommers);
         let block = BlockInfo { block, transactions: transaction_infos, receipts };
-        ExecutedTransactions { block, included, invalid }
+        ExecutedTransactions { block, included, invalid, state_diff }
     }
 
     fn env_for(&self, tx: &PendingTransaction) -> Env {
@@ -246,6 +303,12 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
         if max_gas > env.block.gas_limit.to_ethers() {
             return Some(TransactionExecutionOutcome::Exhausted(transaction))
         }
+        // check that we comply with the block's blob limit
+        let blob_gas = DATA_GAS_PER_BLOB *
+            transaction.pending_transaction.transaction.blob_versioned_hashes().len() as u64;
+        if self.blob_gas_used + blob_gas > DATA_GAS_PER_BLOB * MAX_BLOBS_PER_BLOCK as u64 {
+            return Some(TransactionExecutionOutcome::Exhausted(transaction))
+        }
 
         // validate before executing
         if let Err(err) = self.validator.validate_pool_transaction_for(
@@ -256,6 +319,17 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
             warn!(target: "backend", "Skipping invalid tx execution [{:?}] {}", transaction.hash(), err);
             return Some(TransactionExecutionOutcome::Invalid(transaction, err))
         }
+        if let Some(conditional) = &transaction.pending_transaction.conditional {
+            if let Err(err) = ensure_transaction_conditional(
+                conditional,
+                &*self.db,
+                env.block.number.saturating_to(),
+                env.block.timestamp.saturating_to(),
+            ) {
+                warn!(target: "backend", "Skipping tx with unmet conditional [{:?}] {}", transaction.hash(), err);
+                return Some(TransactionExecutionOutcome::Invalid(transaction, err))
+            }
+        }
 
         let nonce = account.nonce;
 
@@ -270,9 +344,12 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
         }
 
         trace!(target: "backend", "[{:?}] executing", transaction.hash());
-        // transact and commit the transaction
-        let exec_result = match evm.inspect_commit(&mut inspector) {
-            Ok(exec_result) => exec_result,
+        // transact the transaction, resolving the EIP-7702 delegation designators of the called
+        // accounts, the state changes are committed below
+        let ResultAndState { result: exec_result, state } = match evm
+            .inspect(DelegationInspector::new(&mut inspector))
+        {
+            Ok(result_and_state) => result_and_state,
             Err(err) => {
                 warn!(target: "backend", "[{:?}] failed to execute: {:?}", transaction.hash(), err);
                 match err {
@@ -292,15 +369,22 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
         };
         inspector.print_logs();
 
-        let (exit_reason, gas_used, out, logs) = match exec_result {
-            ExecutionResult::Success { reason, gas_used, logs, output, .. } => {
-                (eval_to_instruction_result(reason), gas_used, Some(output), Some(logs))
-            }
+        let state_diff = account_state_diffs(&state);
+        self.db.commit(state);
+
+        let (exit_reason, gas_used, gas_refunded, out, logs) = match exec_result {
+            ExecutionResult::Success { reason, gas_used, gas_refunded, logs, output } => (
+                eval_to_instruction_result(reason),
+                gas_used,
+                gas_refunded,
+                Some(output),
+                Some(logs),
+            ),
             ExecutionResult::Revert { gas_used, output } => {
-                (InstructionResult::Revert, gas_used, Some(Output::Call(output)), None)
+                (InstructionResult::Revert, gas_used, 0, Some(Output::Call(output)), None)
             }
             ExecutionResult::Halt { reason, gas_used } => {
-                (halt_to_instruction_result(reason), gas_used, None, None)
+                (halt_to_instruction_result(reason), gas_used, 0, None, None)
             }
         };
 
@@ -312,6 +396,7 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
         trace!(target: "backend", ?exit_reason, ?gas_used, "[{:?}] executed with out={:?}", transaction.hash(), out);
 
         self.gas_used.saturating_add(U256::from(gas_used));
+        self.blob_gas_used += blob_gas;
 
         trace!(target: "backend::executor", "transacted [{:?}], result: {:?} gas {}", transaction.hash(), exit_reason, gas_used);
 
@@ -320,15 +405,72 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
             exit_reason,
             out,
             gas_used,
+            gas_refunded,
             logs: logs.unwrap_or_default().into_iter().map(Into::into).collect(),
             traces: inspector.tracer.unwrap_or_default().traces.arena,
             nonce,
+            state_diff,
         };
 
         Some(TransactionExecutionOutcome::Executed(tx))
     }
 }
 
+/// Returns the post-transaction state of all accounts touched by the transaction
+fn account_state_diffs(state: &revm::primitives::State) -> BTreeMap<Address, AccountStateDiff> {
+    state
+        .iter()
+        .filter(|(_, account)| account.is_touched())
+        .map(|(address, account)| (address.to_ethers(), account_state_diff(account)))
+        .collect()
+}
+
+fn account_state_diff(account: &Account) -> AccountStateDiff {
+    let code = account
+        .is_created()
+        .then(|| account.info.code.as_ref())
+        .flatten()
+        .map(|code| ethers::types::Bytes::from(code.bytes()[..code.len()].to_vec()));
+    AccountStateDiff {
+        balance: account.info.balance.to_ethers(),
+        nonce: account.info.nonce.into(),
+        code,
+        storage: account
+            .storage
+            .iter()
+            .filter(|(_, slot)| slot.is_changed())
+            .map(|(key, slot)| {
+                (
+                    H256::from_uint(&key.to_ethers()),
+                    H256::from_uint(&slot.present_value().to_ethers()),
+                )
+            })
+            .collect(),
+        destroyed: account.is_selfdestructed(),
+    }
+}
+
+/// Merges the state changes of a transaction into the state changes of the block
+fn merge_state_diff(
+    block_diff: &mut BTreeMap<Address, AccountStateDiff>,
+    tx_diff: BTreeMap<Address, AccountStateDiff>,
+) {
+    for (address, diff) in tx_diff {
+        let Some(account) = block_diff.get_mut(&address) else {
+            block_diff.insert(address, diff);
+            continue
+        };
+        if diff.destroyed || diff.code.is_some() {
+            // the account was destroyed or (re)created, so earlier storage changes are stale
+            *account = diff;
+            continue
+        }
+        account.balance = diff.balance;
+        account.nonce = diff.nonce;
+        account.storage.extend(diff.storage);
+    }
+}
+
 /// Inserts all logs into the bloom
 fn logs_bloom(logs: Vec<Log>, bloom: &mut Bloom) {
     for log in logs {
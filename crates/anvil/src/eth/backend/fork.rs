@@ -1,7 +1,10 @@
 //! Support for forking off another client
 
 use crate::eth::{backend::db::Db, error::BlockchainError};
-use anvil_core::eth::{proof::AccountProof, transaction::EthTransactionRequest};
+use anvil_core::{
+    eth::{proof::AccountProof, transaction::EthTransactionRequest},
+    types::ForkCacheStats,
+};
 use ethers::{
     prelude::BlockNumber,
     providers::{Middleware, ProviderError},
@@ -11,13 +14,22 @@ use ethers::{
         TxHash, H256, U256,
     },
 };
-use foundry_common::{ProviderBuilder, RetryProvider};
+use foundry_common::{sigv4::SigV4Signer, ProviderBuilder, RetryProvider};
 use foundry_evm::utils::u256_to_h256_be;
+use lru::LruCache;
 use parking_lot::{
     lock_api::{RwLockReadGuard, RwLockWriteGuard},
     RawRwLock, RwLock,
 };
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tokio::sync::RwLock as AsyncRwLock;
 
 /// Represents a fork of a remote client
@@ -34,6 +46,8 @@ pub struct ClientFork {
     pub config: Arc<RwLock<ClientForkConfig>>,
     /// This also holds a handle to the underlying database
     pub database: Arc<AsyncRwLock<Box<dyn Db>>>,
+    /// Hit rate of the account queries served from `storage`
+    pub metrics: Arc<ForkCacheMetrics>,
 }
 
 // === impl ClientFork ===
@@ -41,7 +55,12 @@ pub struct ClientFork {
 impl ClientFork {
     /// Creates a new instance of the fork
     pub fn new(config: ClientForkConfig, database: Arc<AsyncRwLock<Box<dyn Db>>>) -> Self {
-        Self { storage: Default::default(), config: Arc::new(RwLock::new(config)), database }
+        Self {
+            storage: Default::default(),
+            config: Arc::new(RwLock::new(config)),
+            database,
+            metrics: Default::default(),
+        }
     }
 
     /// Reset the fork to a fresh forked state, and optionally update the fork config
@@ -51,23 +70,34 @@ impl ClientFork {
         block_number: impl Into<BlockId>,
     ) -> Result<(), BlockchainError> {
         let block_number = block_number.into();
+
+        // check the chain id of the new network before switching to it
+        let new_config = if let Some(url) = url.clone() {
+            let mut config = self.config.read().clone();
+            config.update_url(url)?;
+            let remote_chain_id = config.provider.get_chainid().await?.as_u64();
+            if let Some(chain_id) = config.override_chain_id {
+                if chain_id != remote_chain_id && !config.allow_chain_id_mismatch {
+                    return Err(BlockchainError::ChainIdMismatch { chain_id, remote_chain_id })
+                }
+            }
+            config.chain_id = config.override_chain_id.unwrap_or(remote_chain_id);
+            config.remote_chain_id = remote_chain_id;
+            Some(config)
+        } else {
+            None
+        };
+
         {
             self.database
                 .write()
                 .await
-                .maybe_reset(url.clone(), block_number)
+                .maybe_reset(url, block_number)
                 .map_err(BlockchainError::Internal)?;
         }
 
-        if let Some(url) = url {
-            self.config.write().update_url(url)?;
-            let override_chain_id = self.config.read().override_chain_id;
-            let chain_id = if let Some(chain_id) = override_chain_id {
-                chain_id.into()
-            } else {
-                self.provider().get_chainid().await?
-            };
-            self.config.write().chain_id = chain_id.as_u64();
+        if let Some(config) = new_config {
+            *self.config.write() = config;
         }
 
         let provider = self.provider();
@@ -133,7 +163,12 @@ impl ClientFork {
         self.config.read().chain_id
     }
 
-    fn provider(&self) -> Arc<RetryProvider> {
+    /// Returns the chain id of the forked network, which can differ from the chain id of the node
+    pub fn remote_chain_id(&self) -> u64 {
+        self.config.read().remote_chain_id
+    }
+
+    pub(crate) fn provider(&self) -> Arc<RetryProvider> {
         self.config.read().provider.clone()
     }
 
@@ -240,8 +275,26 @@ impl ClientFork {
         index: U256,
         number: Option<BlockNumber>,
     ) -> Result<H256, ProviderError> {
-        let index = u256_to_h256_be(index);
-        self.provider().get_storage_at(address, index, number.map(Into::into)).await
+        // only values at a fixed block are cached, since they can't change upstream
+        let Some(BlockNumber::Number(blocknumber)) = number else {
+            return self
+                .provider()
+                .get_storage_at(address, u256_to_h256_be(index), number.map(Into::into))
+                .await
+        };
+        let key = (address, index, blocknumber.as_u64());
+        if let Some(value) = self.storage_write().storage_at.get(&key).copied() {
+            self.metrics.hit();
+            return Ok(value)
+        }
+        self.metrics.miss();
+
+        let value = self
+            .provider()
+            .get_storage_at(address, u256_to_h256_be(index), Some(blocknumber.into()))
+            .await?;
+        self.storage_write().storage_at.put(key, value);
+        Ok(value)
     }
 
     pub async fn logs(&self, filter: &Filter) -> Result<Vec<Log>, ProviderError> {
@@ -263,8 +316,10 @@ impl ClientFork {
     ) -> Result<Bytes, ProviderError> {
         trace!(target: "backend::fork", "get_code={:?}", address);
         if let Some(code) = self.storage_read().code_at.get(&(address, blocknumber)).cloned() {
+            self.metrics.hit();
             return Ok(code)
         }
+        self.metrics.miss();
 
         let code = self.provider().get_code(address, Some(blocknumber.into())).await?;
         let mut storage = self.storage_write();
@@ -279,7 +334,16 @@ impl ClientFork {
         blocknumber: u64,
     ) -> Result<U256, ProviderError> {
         trace!(target: "backend::fork", "get_balance={:?}", address);
-        self.provider().get_balance(address, Some(blocknumber.into())).await
+        if let Some(balance) = self.storage_write().balance_at.get(&(address, blocknumber)).copied()
+        {
+            self.metrics.hit();
+            return Ok(balance)
+        }
+        self.metrics.miss();
+
+        let balance = self.provider().get_balance(address, Some(blocknumber.into())).await?;
+        self.storage_write().balance_at.put((address, blocknumber), balance);
+        Ok(balance)
     }
 
     pub async fn get_nonce(
@@ -288,7 +352,16 @@ impl ClientFork {
         blocknumber: u64,
     ) -> Result<U256, ProviderError> {
         trace!(target: "backend::fork", "get_nonce={:?}", address);
-        self.provider().get_transaction_count(address, Some(blocknumber.into())).await
+        if let Some(nonce) = self.storage_write().nonce_at.get(&(address, blocknumber)).copied() {
+            self.metrics.hit();
+            return Ok(nonce)
+        }
+        self.metrics.miss();
+
+        let nonce =
+            self.provider().get_transaction_count(address, Some(blocknumber.into())).await?;
+        self.storage_write().nonce_at.put((address, blocknumber), nonce);
+        Ok(nonce)
     }
 
     pub async fn transaction_by_block_number_and_index(
@@ -531,6 +604,10 @@ pub struct ClientForkConfig {
     pub provider: Arc<RetryProvider>,
     pub chain_id: u64,
     pub override_chain_id: Option<u64>,
+    /// The chain id of the forked network
+    pub remote_chain_id: u64,
+    /// Whether `override_chain_id` may differ from the chain id of the forked network
+    pub allow_chain_id_mismatch: bool,
     /// The timestamp for the forked block
     pub timestamp: u64,
     /// The basefee of the forked block
@@ -545,6 +622,10 @@ pub struct ClientForkConfig {
     pub compute_units_per_second: u64,
     /// total difficulty of the chain until this block
     pub total_difficulty: U256,
+    /// http headers of the requests to the fork url
+    pub headers: Vec<String>,
+    /// credentials to sign the requests to the fork url with AWS Signature Version 4
+    pub sigv4: Option<SigV4Signer>,
 }
 
 // === impl ClientForkConfig ===
@@ -564,6 +645,8 @@ impl ClientForkConfig {
                 .max_retry(10)
                 .initial_backoff(self.backoff.as_millis() as u64)
                 .compute_units_per_second(self.compute_units_per_second)
+                .headers(self.headers.clone())
+                .sigv4(self.sigv4.clone())
                 .build()
                 .map_err(|_| BlockchainError::InvalidUrl(url.clone()))?
                 .interval(interval),
@@ -590,8 +673,15 @@ impl ClientForkConfig {
     }
 }
 
+/// The number of historical account values [`ForkedStorage`] keeps per kind of query, the least
+/// recently used ones are evicted first
+const ACCOUNT_CACHE_CAPACITY: NonZeroUsize = match NonZeroUsize::new(100_000) {
+    Some(capacity) => capacity,
+    None => unreachable!(),
+};
+
 /// Contains cached state fetched to serve EthApi requests
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ForkedStorage {
     pub uncles: HashMap<H256, Vec<Block<TxHash>>>,
     pub blocks: HashMap<H256, Block<TxHash>>,
@@ -605,6 +695,31 @@ pub struct ForkedStorage {
     pub eth_gas_estimations: HashMap<(Arc<EthTransactionRequest>, u64), U256>,
     pub eth_call: HashMap<(Arc<EthTransactionRequest>, u64), Bytes>,
     pub code_at: HashMap<(Address, u64), Bytes>,
+    pub balance_at: LruCache<(Address, u64), U256>,
+    pub nonce_at: LruCache<(Address, u64), U256>,
+    pub storage_at: LruCache<(Address, U256, u64), H256>,
+}
+
+impl Default for ForkedStorage {
+    fn default() -> Self {
+        Self {
+            uncles: Default::default(),
+            blocks: Default::default(),
+            hashes: Default::default(),
+            transactions: Default::default(),
+            transaction_receipts: Default::default(),
+            transaction_traces: Default::default(),
+            logs: Default::default(),
+            geth_transaction_traces: Default::default(),
+            block_traces: Default::default(),
+            eth_gas_estimations: Default::default(),
+            eth_call: Default::default(),
+            code_at: Default::default(),
+            balance_at: LruCache::new(ACCOUNT_CACHE_CAPACITY),
+            nonce_at: LruCache::new(ACCOUNT_CACHE_CAPACITY),
+            storage_at: LruCache::new(ACCOUNT_CACHE_CAPACITY),
+        }
+    }
 }
 
 // === impl ForkedStorage ===
@@ -616,3 +731,33 @@ impl ForkedStorage {
         *self = Self::default()
     }
 }
+
+/// Counts how many account queries of a [`ClientFork`] were answered from its [`ForkedStorage`]
+///
+/// Queries are only cached for a fixed block, so a cached value is exactly what the remote client
+/// returned for that block, even if the remote chain moved on since.
+#[derive(Debug, Default)]
+pub struct ForkCacheMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+// === impl ForkCacheMetrics ===
+
+impl ForkCacheMetrics {
+    fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the counters
+    pub fn stats(&self) -> ForkCacheStats {
+        ForkCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -143,6 +143,6 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
 #[inline]
 pub fn print_logs(logs: &[Log]) {
     for log in decode_console_logs(logs) {
-        node_info!("{}", log);
+        node_info!(kind = "console", "{}", log);
     }
 }
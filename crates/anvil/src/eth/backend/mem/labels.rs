@@ -0,0 +1,61 @@
+//! Labeling of forked contracts in traces
+
+use alloy_primitives::Address;
+use foundry_config::{Chain, Config};
+use foundry_evm::traces::{
+    identifier::EtherscanIdentifier, CallTraceArena, CallTraceDecoder, CallTraceDecoderBuilder,
+};
+use std::collections::BTreeMap;
+
+/// Resolves the names and ABIs of the contracts in a trace from Etherscan, and uses them to label
+/// addresses and decode calls.
+///
+/// Contracts are only fetched once, the decoder keeps track of all identified contracts.
+pub struct TraceLabeler {
+    decoder: CallTraceDecoder,
+    identifier: EtherscanIdentifier,
+}
+
+// === impl TraceLabeler ===
+
+impl TraceLabeler {
+    /// Creates a new labeler that queries the Etherscan instance of the given chain.
+    ///
+    /// Returns `None` if no Etherscan client could be configured for the chain.
+    pub fn new(etherscan_api_key: String, chain_id: u64) -> Option<Self> {
+        let config = Config { etherscan_api_key: Some(etherscan_api_key), ..Default::default() };
+        match EtherscanIdentifier::new(&config, Some(Chain::from(chain_id))) {
+            Ok(identifier) => {
+                Some(Self { decoder: CallTraceDecoderBuilder::new().build(), identifier })
+            }
+            Err(err) => {
+                warn!(target: "backend", ?err, chain_id, "failed to configure etherscan trace labels");
+                None
+            }
+        }
+    }
+
+    /// Identifies all contracts in the trace and decodes the trace in place.
+    pub async fn decode(&mut self, traces: &mut CallTraceArena) {
+        self.decoder.identify(traces, &mut self.identifier);
+        self.decoder.decode(traces).await;
+    }
+
+    /// Returns the labels of all contracts in the trace that could be identified.
+    pub fn labels(&mut self, traces: &CallTraceArena) -> BTreeMap<Address, String> {
+        self.decoder.identify(traces, &mut self.identifier);
+        traces
+            .addresses()
+            .into_iter()
+            .filter_map(|(address, _)| {
+                self.decoder.labels.get(address).map(|label| (*address, label.clone()))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for TraceLabeler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraceLabeler").finish_non_exhaustive()
+    }
+}
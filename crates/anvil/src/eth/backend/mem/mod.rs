@@ -5,6 +5,7 @@ use crate::{
         backend::{
             cheats::CheatsManager,
             db::{AsHashDB, Db, MaybeHashDatabase, SerializableState},
+            delegation::{self, DelegationInspector},
             executor::{ExecutedTransactions, TransactionExecutor},
             fork::ClientFork,
             genesis::GenesisConfig,
@@ -19,8 +20,10 @@ use crate::{
         pool::transactions::PoolTransaction,
         util::get_precompiles_for,
     },
+    hooks::NodeHook,
     mem::{
         inspector::Inspector,
+        labels::TraceLabeler,
         storage::{BlockchainStorage, InMemoryBlockStates, MinedBlockOutcome},
     },
     revm::{
@@ -34,28 +37,28 @@ use anvil_core::{
         block::{Block, BlockInfo, Header},
         proof::{AccountProof, BasicAccount, StorageProof},
         receipt::{EIP658Receipt, TypedReceipt},
-        state::StateOverride,
+        state::{BlockStateDiff, StateOverride},
         transaction::{
-            EthTransactionRequest, MaybeImpersonatedTransaction, PendingTransaction,
-            TransactionInfo, TypedTransaction,
+            kzg_to_versioned_hash, BlobTransactionSidecar, EthTransactionRequest,
+            MaybeImpersonatedTransaction, PendingTransaction, TransactionInfo, TypedTransaction,
         },
         trie::RefTrieDB,
         utils::to_revm_access_list,
     },
-    types::{Forking, Index},
+    types::{BlobSidecar, Forking, Index, TraceFilter},
 };
 use anvil_rpc::error::RpcError;
 use ethers::{
     abi::ethereum_types::BigEndianHash,
     prelude::{BlockNumber, GethTraceFrame, TxHash, H256, U256, U64},
     types::{
-        transaction::eip2930::AccessList, Address, Block as EthersBlock, BlockId, Bytes,
+        transaction::eip2930::AccessList, Action, Address, Block as EthersBlock, BlockId, Bytes,
         DefaultFrame, Filter, FilteredParams, GethDebugTracingOptions, GethTrace, Log, OtherFields,
-        Trace, Transaction, TransactionReceipt, H160,
+        Res, Trace, Transaction, TransactionReceipt, H160,
     },
     utils::{keccak256, rlp},
 };
-use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use flate2::{write::GzEncoder, Compression};
 use foundry_common::types::{ToAlloy, ToEthers};
 use foundry_evm::{
     backend::{DatabaseError, DatabaseResult, RevertSnapshotAction},
@@ -67,8 +70,9 @@ use foundry_evm::{
         db::CacheDB,
         interpreter::InstructionResult,
         primitives::{
-            Account, BlockEnv, CreateScheme, EVMError, Env, ExecutionResult, InvalidHeader, Output,
-            SpecId, TransactTo, TxEnv, KECCAK_EMPTY,
+            calc_excess_blob_gas, Account, BlobExcessGasAndPrice, BlockEnv, CreateScheme, EVMError,
+            Env, ExecutionResult, InvalidHeader, Output, SpecId, TransactTo, TxEnv, KECCAK_EMPTY,
+            MAX_INITCODE_SIZE,
         },
     },
     utils::{eval_to_instruction_result, halt_to_instruction_result, u256_to_h256_be},
@@ -78,19 +82,20 @@ use hash_db::HashDB;
 use parking_lot::{Mutex, RwLock};
 use std::{
     collections::{BTreeMap, HashMap},
-    io::{Read, Write},
+    io::Write,
     ops::Deref,
     sync::Arc,
     time::Duration,
 };
 use storage::{Blockchain, MinedTransaction};
-use tokio::sync::RwLock as AsyncRwLock;
+use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
 use trie_db::{Recorder, Trie};
 
 pub mod cache;
 pub mod fork_db;
 pub mod in_memory_db;
 pub mod inspector;
+pub mod labels;
 pub mod state;
 pub mod storage;
 
@@ -98,6 +103,8 @@ pub mod storage;
 pub const MIN_TRANSACTION_GAS: U256 = U256([21_000, 0, 0, 0]);
 // Gas per transaction creating a contract.
 pub const MIN_CREATE_GAS: U256 = U256([53_000, 0, 0, 0]);
+// The maximum number of blocks a `trace_filter` request can span.
+pub const MAX_TRACE_FILTER_BLOCK_RANGE: u64 = 10_000;
 
 // TODO: This is the same as foundry_evm::utils::StateChangeset but with ethers H160
 pub type State = foundry_evm::hashbrown::HashMap<Address, Account>;
@@ -118,6 +125,29 @@ impl BlockRequest {
     }
 }
 
+/// A bundle sent with `eth_sendBundle` that waits for its target block
+#[derive(Debug, Clone)]
+pub struct PendingBundle {
+    /// The transactions of the bundle, in order
+    pub transactions: Vec<Arc<PoolTransaction>>,
+    /// The number of the block the bundle must be included in
+    pub block_number: U64,
+    /// The minimum block timestamp, inclusive
+    pub min_timestamp: Option<u64>,
+    /// The maximum block timestamp, inclusive
+    pub max_timestamp: Option<u64>,
+    /// The hashes of the transactions that are allowed to revert
+    pub reverting_tx_hashes: Vec<TxHash>,
+}
+
+impl PendingBundle {
+    /// Returns whether the bundle can be included in a block with the given timestamp
+    fn is_valid_at(&self, timestamp: u64) -> bool {
+        self.min_timestamp.map_or(true, |min| timestamp >= min) &&
+            self.max_timestamp.map_or(true, |max| timestamp <= max)
+    }
+}
+
 /// Gives access to the [revm::Database]
 #[derive(Clone)]
 pub struct Backend {
@@ -166,6 +196,25 @@ pub struct Backend {
     /// max number of blocks with transactions in memory
     transaction_block_keeper: Option<usize>,
     node_config: Arc<AsyncRwLock<NodeConfig>>,
+    /// labels forked contracts in traces, if enabled
+    trace_labeler: Option<Arc<AsyncMutex<TraceLabeler>>>,
+    /// whether to skip the intrinsic gas, initcode size and EIP-3607 checks of pool transactions
+    disable_strict_validation: bool,
+    /// the initcode size limit of contract creation transactions, if it differs from twice the
+    /// code size limit
+    initcode_size_limit: Arc<RwLock<Option<usize>>>,
+    /// whether to attach a breakdown of the gas used to receipts
+    report_gas_details: bool,
+    /// the L1 gas price of the L1 fee fields of receipts, `None` if receipts don't include them
+    receipt_l1_gas_price: Option<U256>,
+    /// the scalar the L1 fee of receipts is multiplied with
+    receipt_l1_fee_scalar: f64,
+    /// bundles sent with `eth_sendBundle` that wait for their target block
+    bundles: Arc<Mutex<Vec<PendingBundle>>>,
+    /// the hooks into the transaction lifecycle
+    hooks: Vec<Arc<dyn NodeHook>>,
+    /// the state of the genesis block, see [Backend::record_genesis_state]
+    genesis_state: Arc<RwLock<Option<SerializableState>>>,
 }
 
 impl Backend {
@@ -201,6 +250,32 @@ impl Backend {
             genesis.timestamp
         };
 
+        let is_fork = fork.read().is_some();
+        let (
+            etherscan_api_key,
+            disable_strict_validation,
+            initcode_size_limit,
+            report_gas_details,
+            receipt_l1_gas_price,
+            receipt_l1_fee_scalar,
+            hooks,
+        ) = {
+            let config = node_config.read().await;
+            (
+                config.fork_etherscan_api_key.clone(),
+                config.disable_strict_validation,
+                config.initcode_size_limit,
+                config.report_gas_details,
+                config.l2_receipt_fields().then(|| U256::from(config.l1_gas_price)),
+                config.l1_fee_scalar,
+                config.hooks.clone(),
+            )
+        };
+        let trace_labeler = etherscan_api_key
+            .filter(|_| is_fork)
+            .and_then(|api_key| TraceLabeler::new(api_key, env.read().cfg.chain_id))
+            .map(|labeler| Arc::new(AsyncMutex::new(labeler)));
+
         let states = if prune_state_history_config.is_config_enabled() {
             // if prune state history is enabled, configure the state cache only for memory
             prune_state_history_config
@@ -228,6 +303,15 @@ impl Backend {
             prune_state_history_config,
             transaction_block_keeper,
             node_config,
+            trace_labeler,
+            disable_strict_validation,
+            initcode_size_limit: Arc::new(RwLock::new(initcode_size_limit)),
+            report_gas_details,
+            receipt_l1_gas_price,
+            receipt_l1_fee_scalar,
+            bundles: Default::default(),
+            hooks,
+            genesis_state: Default::default(),
         };
 
         if let Some(interval_block_time) = automine_block_time {
@@ -361,8 +445,10 @@ impl Backend {
 
                 let mut node_config = self.node_config.write().await;
 
-                let (db, config) =
-                    node_config.setup_fork_db_config(eth_rpc_url, &mut env, &self.fees).await;
+                let (db, config) = node_config
+                    .setup_fork_db_config(eth_rpc_url, &mut env, &self.fees)
+                    .await
+                    .map_err(|err| BlockchainError::Internal(err.to_string()))?;
 
                 *self.db.write().await = Box::new(db);
 
@@ -494,6 +580,27 @@ impl Backend {
         self.env.write().cfg.chain_id = chain_id;
     }
 
+    /// Sets the EIP-170 contract code size limit, `None` for the default limit.
+    pub fn set_code_size_limit(&self, limit: Option<usize>) {
+        self.env.write().cfg.limit_contract_code_size = limit;
+    }
+
+    /// Sets the initcode size limit of contract creation transactions, `None` for twice the code
+    /// size limit.
+    pub fn set_initcode_size_limit(&self, limit: Option<usize>) {
+        *self.initcode_size_limit.write() = limit;
+    }
+
+    /// Returns the EIP-3860 initcode size limit of contract creation transactions in the `env`.
+    fn max_initcode_size(&self, env: &Env) -> usize {
+        self.initcode_size_limit.read().unwrap_or_else(|| {
+            env.cfg
+                .limit_contract_code_size
+                .map(|limit| limit.saturating_mul(2))
+                .unwrap_or(MAX_INITCODE_SIZE)
+        })
+    }
+
     /// Returns balance of the given account.
     pub async fn current_balance(&self, address: Address) -> DatabaseResult<U256> {
         Ok(self.get_account(address).await?.balance.to_ethers())
@@ -554,6 +661,11 @@ impl Backend {
         (self.spec_id() as u8) >= (SpecId::BERLIN as u8)
     }
 
+    /// Returns true for post Cancun
+    pub fn is_eip4844(&self) -> bool {
+        (self.spec_id() as u8) >= (SpecId::CANCUN as u8)
+    }
+
     /// Returns true if op-stack deposits are active
     pub fn is_optimism(&self) -> bool {
         self.env.read().cfg.optimism
@@ -575,6 +687,14 @@ impl Backend {
         Err(BlockchainError::EIP2930TransactionUnsupportedAtHardfork)
     }
 
+    /// Returns an error if EIP4844 is not active (pre Cancun)
+    pub fn ensure_eip4844_active(&self) -> Result<(), BlockchainError> {
+        if self.is_eip4844() {
+            return Ok(())
+        }
+        Err(BlockchainError::EIP4844TransactionUnsupportedAtHardfork)
+    }
+
     /// Returns an error if op-stack deposits are not active
     pub fn ensure_op_deposits_active(&self) -> Result<(), BlockchainError> {
         if self.is_optimism() {
@@ -713,21 +833,136 @@ impl Backend {
         Ok(encoder.finish().unwrap_or_default().into())
     }
 
+    /// Returns the RLP encodings of all blocks after the genesis block, in order and
+    /// concatenated.
+    ///
+    /// When forking, the genesis block is the fork block, so only the locally mined blocks are
+    /// exported.
+    pub fn export_blocks(&self) -> Bytes {
+        let storage = self.blockchain.storage.read();
+        let mut hashes = storage
+            .hashes
+            .iter()
+            .filter(|(_, hash)| **hash != storage.genesis_hash)
+            .collect::<Vec<_>>();
+        hashes.sort_by_key(|(number, _)| **number);
+
+        let mut buf = Vec::new();
+        for (_, hash) in hashes {
+            if let Some(block) = storage.blocks.get(hash) {
+                buf.extend_from_slice(&rlp::encode(block));
+            }
+        }
+        buf.into()
+    }
+
+    /// Records the current state as the state of the genesis block, for [Backend::export_genesis].
+    ///
+    /// This is called once all genesis accounts and contracts are written.
+    pub async fn record_genesis_state(&self) {
+        if self.is_fork() {
+            return
+        }
+        if let Ok(state) = self.db.read().await.dump_state() {
+            *self.genesis_state.write() = state;
+        }
+    }
+
+    /// Returns the genesis of the chain in the `genesis.json` format of geth and reth: the chain
+    /// config, the header fields of the genesis block and the state at genesis.
+    ///
+    /// Together with [Backend::export_blocks] this allows replaying the chain in other clients.
+    /// This isn't supported when forking, since the state of the fork block isn't available.
+    pub fn export_genesis(&self) -> Result<serde_json::Value, BlockchainError> {
+        let Some(state) = self.genesis_state.read().clone() else {
+            return Err(RpcError::invalid_params(
+                "Exporting the genesis is not supported with the current configuration",
+            )
+            .into())
+        };
+        let header = {
+            let storage = self.blockchain.storage.read();
+            storage
+                .blocks
+                .get(&storage.genesis_hash)
+                .map(|block| block.header.clone())
+                .ok_or(BlockchainError::DataUnavailable)?
+        };
+
+        let spec_id = self.spec_id();
+        let mut config = serde_json::Map::new();
+        config.insert("chainId".to_string(), self.chain_id().into());
+        for (name, fork) in [
+            ("homesteadBlock", SpecId::HOMESTEAD),
+            ("eip150Block", SpecId::TANGERINE),
+            ("eip155Block", SpecId::SPURIOUS_DRAGON),
+            ("eip158Block", SpecId::SPURIOUS_DRAGON),
+            ("byzantiumBlock", SpecId::BYZANTIUM),
+            ("constantinopleBlock", SpecId::CONSTANTINOPLE),
+            ("petersburgBlock", SpecId::PETERSBURG),
+            ("istanbulBlock", SpecId::ISTANBUL),
+            ("muirGlacierBlock", SpecId::MUIR_GLACIER),
+            ("berlinBlock", SpecId::BERLIN),
+            ("londonBlock", SpecId::LONDON),
+            ("arrowGlacierBlock", SpecId::GRAY_GLACIER),
+            ("grayGlacierBlock", SpecId::GRAY_GLACIER),
+        ] {
+            if spec_id >= fork {
+                config.insert(name.to_string(), 0.into());
+            }
+        }
+        if spec_id >= SpecId::MERGE {
+            config.insert("terminalTotalDifficulty".to_string(), 0.into());
+            config.insert("terminalTotalDifficultyPassed".to_string(), true.into());
+        }
+        if spec_id >= SpecId::SHANGHAI {
+            config.insert("shanghaiTime".to_string(), 0.into());
+        }
+        if spec_id >= SpecId::CANCUN {
+            config.insert("cancunTime".to_string(), 0.into());
+        }
+
+        let alloc = state
+            .accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let mut entry = serde_json::json!({ "balance": account.balance });
+                if account.nonce != 0 {
+                    entry["nonce"] = serde_json::json!(U64::from(account.nonce));
+                }
+                if !account.code.is_empty() {
+                    entry["code"] = serde_json::json!(account.code);
+                }
+                if !account.storage.is_empty() {
+                    let storage = account
+                        .storage
+                        .into_iter()
+                        .map(|(slot, value)| (H256::from_uint(&slot), H256::from_uint(&value)))
+                        .collect::<BTreeMap<_, _>>();
+                    entry["storage"] = serde_json::json!(storage);
+                }
+                (format!("{address:?}"), entry)
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        Ok(serde_json::json!({
+            "config": config,
+            "nonce": header.nonce,
+            "timestamp": U64::from(header.timestamp),
+            "extraData": header.extra_data,
+            "gasLimit": header.gas_limit,
+            "difficulty": header.difficulty,
+            "mixHash": header.mix_hash,
+            "coinbase": header.beneficiary,
+            "baseFeePerGas": header.base_fee_per_gas,
+            "alloc": alloc,
+        }))
+    }
+
     /// Deserialize and add all chain data to the backend storage
     pub async fn load_state(&self, buf: Bytes) -> Result<bool, BlockchainError> {
-        let orig_buf = &buf.0[..];
-        let mut decoder = GzDecoder::new(orig_buf);
-        let mut decoded_data = Vec::new();
-
-        let state: SerializableState = serde_json::from_slice(if decoder.header().is_some() {
-            decoder
-                .read_to_end(decoded_data.as_mut())
-                .map_err(|_| BlockchainError::FailedToDecodeStateDump)?;
-            &decoded_data
-        } else {
-            &buf.0
-        })
-        .map_err(|_| BlockchainError::FailedToDecodeStateDump)?;
+        let state = SerializableState::decode(&buf.0)
+            .map_err(|_| BlockchainError::FailedToDecodeStateDump)?;
 
         if !self.db.write().await.load_state(state)? {
             Err(RpcError::invalid_params(
@@ -746,9 +981,27 @@ impl Backend {
         env.block.number = env.block.number.saturating_add(rU256::from(1));
         env.block.basefee = self.base_fee().to_alloy();
         env.block.timestamp = rU256::from(self.time.current_call_timestamp());
+        env.block.blob_excess_gas_and_price = self.next_blob_excess_gas_and_price();
         env
     }
 
+    /// Returns the excess blob gas and the blob gas price of the next block, which follow from the
+    /// blob gas of the best block, if Cancun is active.
+    fn next_blob_excess_gas_and_price(&self) -> Option<BlobExcessGasAndPrice> {
+        if !self.is_eip4844() {
+            return None
+        }
+        let storage = self.blockchain.storage.read();
+        // the best block of a fork isn't stored locally
+        let excess_blob_gas = storage.blocks.get(&storage.best_hash).map_or(0, |block| {
+            calc_excess_blob_gas(
+                block.header.excess_blob_gas.unwrap_or_default().as_u64(),
+                block.header.blob_gas_used.unwrap_or_default().as_u64(),
+            )
+        });
+        Some(BlobExcessGasAndPrice::new(excess_blob_gas))
+    }
+
     /// executes the transactions without writing to the underlying database
     pub async fn inspect_tx(
         &self,
@@ -765,7 +1018,7 @@ impl Backend {
         let mut evm = revm::EVM::new();
         evm.env = env;
         evm.database(&*db);
-        let result_and_state = match evm.inspect_ref(&mut inspector) {
+        let result_and_state = match evm.inspect_ref(DelegationInspector::new(&mut inspector)) {
             Ok(res) => res,
             Err(e) => return Err(e.into()),
         };
@@ -822,6 +1075,7 @@ impl Backend {
             cfg_env: env.cfg,
             parent_hash: storage.best_hash,
             gas_used: U256::zero(),
+            blob_gas_used: 0,
             enable_steps_tracing: self.enable_steps_tracing,
         };
 
@@ -830,6 +1084,70 @@ impl Backend {
         f(Box::new(cache_db), executed.block)
     }
 
+    /// Adds a bundle that is applied at the top of its target block, if all of its transactions
+    /// can be included.
+    pub fn add_bundle(&self, bundle: PendingBundle) {
+        self.bundles.lock().push(bundle);
+    }
+
+    /// Removes the bundles of the block of the given `env` and returns the transactions of all
+    /// bundles that can be applied atomically, in the order the bundles were sent.
+    ///
+    /// A bundle is applied if all of its transactions are valid and don't revert, unless they are
+    /// allowed to. Bundles of past blocks are dropped.
+    async fn take_bundle_transactions(
+        &self,
+        env: &Env,
+        parent_hash: H256,
+    ) -> Vec<Arc<PoolTransaction>> {
+        let number = U64::from(env.block.number.to_ethers().as_u64());
+        let timestamp = env.block.timestamp.to_ethers().as_u64();
+        let bundles = {
+            let mut bundles = self.bundles.lock();
+            let (current, pending) = std::mem::take(&mut *bundles)
+                .into_iter()
+                .partition::<Vec<_>, _>(|bundle| bundle.block_number <= number);
+            *bundles = pending;
+            current
+        };
+
+        let db = self.db.read().await;
+        let mut transactions = Vec::<Arc<PoolTransaction>>::new();
+        for bundle in bundles {
+            if bundle.block_number != number || !bundle.is_valid_at(timestamp) {
+                continue
+            }
+
+            // simulate the bundle on top of all previously applied bundles
+            let mut candidates = transactions.clone();
+            candidates.extend(bundle.transactions.iter().cloned());
+            let mut cache_db = CacheDB::new(&*db);
+            let executor = TransactionExecutor {
+                db: &mut cache_db,
+                validator: self,
+                pending: candidates.clone().into_iter(),
+                block_env: env.block.clone(),
+                cfg_env: env.cfg.clone(),
+                parent_hash,
+                gas_used: U256::zero(),
+                blob_gas_used: 0,
+                enable_steps_tracing: false,
+            };
+            let executed = executor.execute();
+
+            let applied = executed.included.len() == candidates.len() &&
+                executed.block.transactions[transactions.len()..].iter().all(|info| {
+                    info.exit.is_ok() || bundle.reverting_tx_hashes.contains(&info.transaction_hash)
+                });
+            if applied {
+                transactions = candidates;
+            } else {
+                trace!(target: "backend", block_number = ?number, "dropping bundle that can't be applied");
+            }
+        }
+        transactions
+    }
+
     /// Mines a new block and stores it.
     ///
     /// this will execute all transaction in the order they come in and return all the markers they
@@ -847,7 +1165,7 @@ impl Backend {
     ) -> MinedBlockOutcome {
         trace!(target: "backend", "creating new block with {} transactions", pool_transactions.len());
 
-        let (outcome, header, block_hash) = {
+        let (outcome, header, block_hash, state_diff, finalized_block) = {
             let current_base_fee = self.base_fee();
 
             let mut env = self.env.read().clone();
@@ -862,9 +1180,21 @@ impl Backend {
             env.block.number = env.block.number.saturating_add(rU256::from(1));
             env.block.basefee = current_base_fee.to_alloy();
             env.block.timestamp = rU256::from(self.time.next_timestamp());
+            env.block.blob_excess_gas_and_price = self.next_blob_excess_gas_and_price();
 
             let best_hash = self.blockchain.storage.read().best_hash;
 
+            // bundles are applied at the top of the block
+            let bundle_transactions = self.take_bundle_transactions(&env, best_hash).await;
+            let pool_transactions = if bundle_transactions.is_empty() {
+                pool_transactions
+            } else {
+                let pool_transactions = pool_transactions
+                    .into_iter()
+                    .filter(|tx| bundle_transactions.iter().all(|b| b.hash() != tx.hash()));
+                bundle_transactions.into_iter().chain(pool_transactions).collect()
+            };
+
             if self.prune_state_history_config.is_state_history_supported() {
                 let db = self.db.read().await.current_state();
                 // store current state before executing all transactions
@@ -881,6 +1211,7 @@ impl Backend {
                     cfg_env: env.cfg.clone(),
                     parent_hash: best_hash,
                     gas_used: U256::zero(),
+                    blob_gas_used: 0,
                     enable_steps_tracing: self.enable_steps_tracing,
                 };
                 let executed_tx = executor.execute();
@@ -893,8 +1224,11 @@ impl Backend {
             };
 
             // create the new block with the current timestamp
-            let ExecutedTransactions { block, included, invalid } = executed_tx;
+            let ExecutedTransactions { block, included, invalid, state_diff } = executed_tx;
             let BlockInfo { block, transactions, receipts } = block;
+            for (info, receipt) in transactions.iter().zip(&receipts) {
+                self.hooks.iter().for_each(|hook| hook.on_transaction_executed(info, receipt));
+            }
 
             let header = block.header.clone();
             let block_number: U64 = (env.block.number.to_ethers()).as_u64().into();
@@ -907,6 +1241,22 @@ impl Backend {
                 transactions.iter().map(|tx| tx.transaction_hash).collect::<Vec<_>>()
             );
 
+            // the traces are labeled in the background, since this may fetch contracts from
+            // etherscan which must not delay mining
+            if let Some(labeler) = self.trace_labeler.clone() {
+                let traces = transactions
+                    .iter()
+                    .map(|info| (info.transaction_hash, info.traces.clone()))
+                    .collect::<Vec<_>>();
+                tokio::spawn(async move {
+                    let mut labeler = labeler.lock().await;
+                    for (hash, mut traces) in traces {
+                        labeler.decode(&mut traces).await;
+                        node_info!("    Traces of {hash:?}:\n{traces}");
+                    }
+                });
+            }
+
             let mut storage = self.blockchain.storage.write();
             // update block metadata
             storage.best_number = block_number;
@@ -918,27 +1268,34 @@ impl Backend {
                     storage.total_difficulty.saturating_add(header.difficulty);
             }
 
+            let finalized_block = (!self.hooks.is_empty()).then(|| block.clone());
             storage.blocks.insert(block_hash, block);
             storage.hashes.insert(block_number, block_hash);
 
             node_info!("");
             // insert all transactions
             for (info, receipt) in transactions.into_iter().zip(receipts) {
-                // log some tx info
-                node_info!("    Transaction: {:?}", info.transaction_hash);
+                // log some tx info, as a single event with structured fields
+                let mut message = format!("    Transaction: {:?}", info.transaction_hash);
                 if let Some(contract) = &info.contract_address {
-                    node_info!("    Contract created: {contract:?}");
+                    message.push_str(&format!("\n    Contract created: {contract:?}"));
                 }
-                node_info!("    Gas used: {}", receipt.gas_used());
-                if !info.exit.is_ok() {
-                    let r = decode_revert(
-                        info.out.as_deref().unwrap_or_default(),
-                        None,
-                        Some(info.exit),
-                    );
-                    node_info!("    Error: reverted with: {r}");
+                message.push_str(&format!("\n    Gas used: {}", receipt.gas_used()));
+                let revert_reason = (!info.exit.is_ok()).then(|| {
+                    decode_revert(info.out.as_deref().unwrap_or_default(), None, Some(info.exit))
+                });
+                if let Some(r) = &revert_reason {
+                    message.push_str(&format!("\n    Error: reverted with: {r}"));
                 }
-                node_info!("");
+                node_info!(
+                    kind = "transaction",
+                    tx_hash = ?info.transaction_hash,
+                    contract_address = info.contract_address.map(tracing::field::debug),
+                    gas_used = %receipt.gas_used(),
+                    success = info.exit.is_ok(),
+                    revert_reason = revert_reason.as_deref(),
+                    "{message}\n"
+                );
 
                 let mined_tx = MinedTransaction {
                     info,
@@ -949,6 +1306,13 @@ impl Backend {
                 storage.transactions.insert(mined_tx.info.transaction_hash, mined_tx);
             }
 
+            // keep the blobs of the included blob transactions
+            for tx in &included {
+                if let Some(sidecar) = &tx.pending_transaction.blob_sidecar {
+                    storage.blob_sidecars.insert(*tx.hash(), sidecar.clone());
+                }
+            }
+
             // remove old transactions that exceed the transaction block keeper
             if let Some(transaction_block_keeper) = self.transaction_block_keeper {
                 if storage.blocks.len() > transaction_block_keeper {
@@ -967,14 +1331,25 @@ impl Backend {
 
             let timestamp = utc_from_secs(header.timestamp);
 
-            node_info!("    Block Number: {}", block_number);
-            node_info!("    Block Hash: {:?}", block_hash);
-            node_info!("    Block Time: {:?}\n", timestamp.to_rfc2822());
+            node_info!(
+                kind = "block",
+                block_number = %block_number,
+                block_hash = ?block_hash,
+                block_time = %timestamp.to_rfc2822(),
+                "    Block Number: {}\n    Block Hash: {:?}\n    Block Time: {:?}\n",
+                block_number,
+                block_hash,
+                timestamp.to_rfc2822()
+            );
 
             let outcome = MinedBlockOutcome { block_number, included, invalid };
+            let state_diff = BlockStateDiff { block_hash, block_number, accounts: state_diff };
 
-            (outcome, header, block_hash)
+            (outcome, header, block_hash, state_diff, finalized_block)
         };
+        if let Some(block) = finalized_block {
+            self.hooks.iter().for_each(|hook| hook.on_block_finalized(&block));
+        }
         let next_block_base_fee = self.fees.get_next_block_base_fee_per_gas(
             header.gas_used,
             header.gas_limit,
@@ -982,7 +1357,7 @@ impl Backend {
         );
 
         // notify all listeners
-        self.notify_on_new_block(header, block_hash);
+        self.notify_on_new_block(header, block_hash, state_diff);
 
         // update next base fee
         self.fees.set_base_fee(next_block_base_fee.into());
@@ -1080,7 +1455,7 @@ impl Backend {
         let mut evm = revm::EVM::new();
         evm.env = self.build_call_env(request, fee_details, block_env);
         evm.database(state);
-        let result_and_state = match evm.inspect_ref(&mut inspector) {
+        let result_and_state = match evm.inspect_ref(DelegationInspector::new(&mut inspector)) {
             Ok(result_and_state) => result_and_state,
             Err(e) => match e {
                 EVMError::Transaction(invalid_tx) => {
@@ -1129,7 +1504,7 @@ impl Backend {
             evm.env = self.build_call_env(request, fee_details, block);
             evm.database(state);
             let result_and_state =
-                match evm.inspect_ref(&mut inspector) {
+                match evm.inspect_ref(DelegationInspector::new(&mut inspector)) {
                     Ok(result_and_state) => result_and_state,
                     Err(e) => return Err(e.into()),
                 };
@@ -1179,7 +1554,7 @@ impl Backend {
         let mut evm = revm::EVM::new();
         evm.env = self.build_call_env(request, fee_details, block_env);
         evm.database(state);
-        let result_and_state = match evm.inspect_ref(&mut tracer) {
+        let result_and_state = match evm.inspect_ref(DelegationInspector::new(&mut tracer)) {
             Ok(result_and_state) => result_and_state,
             Err(e) => return Err(e.into()),
         };
@@ -1519,8 +1894,24 @@ impl Backend {
             mix_hash,
             nonce,
             base_fee_per_gas,
+            blob_gas_used,
+            excess_blob_gas,
         } = header;
 
+        let mut other = OtherFields::default();
+        if let Some(blob_gas_used) = blob_gas_used {
+            other.insert(
+                "blobGasUsed".to_string(),
+                serde_json::to_value(blob_gas_used).expect("Infallible"),
+            );
+        }
+        if let Some(excess_blob_gas) = excess_blob_gas {
+            other.insert(
+                "excessBlobGas".to_string(),
+                serde_json::to_value(excess_blob_gas).expect("Infallible"),
+            );
+        }
+
         EthersBlock {
             hash: Some(hash),
             parent_hash,
@@ -1544,7 +1935,7 @@ impl Backend {
             mix_hash: Some(mix_hash),
             nonce: Some(nonce),
             base_fee_per_gas,
-            other: Default::default(),
+            other,
             ..Default::default()
         }
     }
@@ -1822,6 +2213,21 @@ impl Backend {
         opts: GethDebugTracingOptions,
     ) -> Result<GethTrace, BlockchainError> {
         if let Some(traces) = self.mined_geth_trace_transaction(hash, opts.clone()) {
+            if let Some(labeler) = &self.trace_labeler {
+                // non-standard extension: add the labels of all identified contracts
+                let arena = self
+                    .blockchain
+                    .storage
+                    .read()
+                    .transactions
+                    .get(&hash)
+                    .map(|tx| tx.info.traces.clone());
+                let labels = labeler.lock().await.labels(&arena.unwrap_or_default());
+                if let Ok(serde_json::Value::Object(mut frame)) = serde_json::to_value(&traces) {
+                    frame.insert("labels".to_string(), serde_json::json!(labels));
+                    return Ok(GethTrace::Unknown(frame.into()))
+                }
+            }
             return Ok(GethTrace::Known(GethTraceFrame::Default(traces)))
         }
 
@@ -1856,6 +2262,92 @@ impl Backend {
         Ok(vec![])
     }
 
+    /// Returns the traces of the blocks in the range of the filter that match its addresses
+    ///
+    /// Blocks that predate the fork are only traced if the filter starts before the fork. The range
+    /// ends at the best block at the latest and can't span more than
+    /// [MAX_TRACE_FILTER_BLOCK_RANGE] blocks.
+    pub async fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<Trace>, BlockchainError> {
+        let from = match filter.from_block {
+            Some(block) => self.convert_block_number(Some(block)),
+            None => self.get_fork().map(|fork| fork.block_number()).unwrap_or_default(),
+        };
+        let to = self.convert_block_number(filter.to_block).min(self.best_number().as_u64());
+        if to.saturating_sub(from) >= MAX_TRACE_FILTER_BLOCK_RANGE {
+            return Err(RpcError::invalid_params(format!(
+                "block range too large, at most {MAX_TRACE_FILTER_BLOCK_RANGE} blocks can be traced"
+            ))
+            .into())
+        }
+        let mut after = filter.after.unwrap_or_default();
+        let count = filter.count.unwrap_or(u64::MAX) as usize;
+
+        let matches = |addresses: &[Address], address: Option<Address>| {
+            addresses.is_empty() || address.is_some_and(|address| addresses.contains(&address))
+        };
+
+        let mut traces = Vec::new();
+        for number in from..=to {
+            if traces.len() >= count {
+                break
+            }
+            for trace in self.trace_block(BlockNumber::Number(number.into())).await? {
+                let (sender, receiver) = trace_addresses(&trace);
+                if !matches(&filter.from_address, sender) || !matches(&filter.to_address, receiver)
+                {
+                    continue
+                }
+                if after > 0 {
+                    after -= 1;
+                    continue
+                }
+                traces.push(trace);
+                if traces.len() >= count {
+                    break
+                }
+            }
+        }
+        Ok(traces)
+    }
+
+    /// Returns the blobs of the blob transactions mined in the given block, or `None` if the block
+    /// doesn't exist
+    pub fn blob_sidecars_by_block(&self, id: impl Into<BlockId>) -> Option<Vec<BlobSidecar>> {
+        let block = self.get_block(id)?;
+        let block_hash = block.header.hash();
+        let block_number = block.header.number.as_u64().into();
+
+        let storage = self.blockchain.storage.read();
+        let mut sidecars = Vec::new();
+        for (tx_index, tx) in block.transactions.iter().enumerate() {
+            let tx_hash = tx.hash();
+            if let Some(sidecar) = storage.blob_sidecars.get(&tx_hash) {
+                sidecars.extend(to_blob_sidecars(
+                    block_hash,
+                    block_number,
+                    tx_hash,
+                    tx_index.into(),
+                    sidecar,
+                ));
+            }
+        }
+        Some(sidecars)
+    }
+
+    /// Returns the blobs of the mined blob transaction with the given hash
+    pub fn blob_sidecars_by_transaction(&self, hash: TxHash) -> Option<Vec<BlobSidecar>> {
+        let storage = self.blockchain.storage.read();
+        let sidecar = storage.blob_sidecars.get(&hash)?;
+        let tx = storage.transactions.get(&hash)?;
+        Some(to_blob_sidecars(
+            tx.block_hash,
+            tx.block_number.into(),
+            hash,
+            tx.info.transaction_index.into(),
+            sidecar,
+        ))
+    }
+
     pub async fn transaction_receipt(
         &self,
         hash: H256,
@@ -1918,6 +2410,18 @@ impl Backend {
 
         let transaction_type = transaction.transaction.r#type();
 
+        // the L1 fee fields of OP stack receipts, deposit transactions don't have them
+        let (l1_fee, l1_fee_scalar, l1_gas_price, l1_gas_used) =
+            match (self.receipt_l1_gas_price, transaction.transaction.l1_gas_used()) {
+                (Some(price), Some(used)) => {
+                    // the scalar has 6 decimals, like in the `GasPriceOracle` predeploy
+                    let scalar = U256::from((self.receipt_l1_fee_scalar * 1e6).round() as u64);
+                    let l1_fee = used.saturating_mul(price).saturating_mul(scalar) / U256::exp10(6);
+                    (Some(l1_fee), Some(self.receipt_l1_fee_scalar), Some(price), Some(used))
+                }
+                _ => (None, None, None, None),
+            };
+
         let effective_gas_price = match transaction.transaction {
             TypedTransaction::Legacy(t) => t.gas_price,
             TypedTransaction::EIP2930(t) => t.gas_price,
@@ -1927,12 +2431,18 @@ impl Backend {
                 .unwrap_or(self.base_fee())
                 .checked_add(t.max_priority_fee_per_gas)
                 .unwrap_or_else(U256::max_value),
+            TypedTransaction::EIP4844(t) => block
+                .header
+                .base_fee_per_gas
+                .unwrap_or(self.base_fee())
+                .checked_add(t.max_priority_fee_per_gas)
+                .unwrap_or_else(U256::max_value),
             TypedTransaction::Deposit(_) => U256::from(0),
         };
 
         let deposit_nonce = transaction_type.and_then(|x| (x == 0x7E).then_some(info.nonce));
 
-        let inner = TransactionReceipt {
+        let mut inner = TransactionReceipt {
             transaction_hash: info.transaction_hash,
             transaction_index: info.transaction_index.into(),
             block_hash: Some(block_hash),
@@ -1974,12 +2484,18 @@ impl Backend {
             transaction_type: transaction_type.map(Into::into),
             effective_gas_price: Some(effective_gas_price),
             deposit_nonce,
-            l1_fee: None,
-            l1_fee_scalar: None,
-            l1_gas_price: None,
-            l1_gas_used: None,
+            l1_fee,
+            l1_fee_scalar,
+            l1_gas_price,
+            l1_gas_used,
             other: OtherFields::default(),
         };
+        if self.report_gas_details {
+            inner.other.insert(
+                "gasDetails".to_string(),
+                serde_json::to_value(info.gas_details).expect("Infallible"),
+            );
+        }
 
         Some(MinedTransactionReceipt { inner, out: info.out })
     }
@@ -2170,12 +2686,16 @@ impl Backend {
     }
 
     /// Notifies all `new_block_listeners` about the new block
-    fn notify_on_new_block(&self, header: Header, hash: H256) {
+    fn notify_on_new_block(&self, header: Header, hash: H256, state_diff: BlockStateDiff) {
         // cleanup closed notification streams first, if the channel is closed we can remove the
         // sender half for the set
         self.new_block_listeners.lock().retain(|tx| !tx.is_closed());
 
-        let notification = NewBlockNotification { hash, header: Arc::new(header) };
+        let notification = NewBlockNotification {
+            hash,
+            header: Arc::new(header),
+            state_diff: Arc::new(state_diff),
+        };
 
         self.new_block_listeners
             .lock()
@@ -2247,6 +2767,35 @@ impl TransactionValidator for Backend {
             return Err(InvalidTransactionError::GasTooLow)
         }
 
+        let is_deposit_tx =
+            matches!(&pending.transaction.transaction, TypedTransaction::Deposit(_));
+
+        if !self.disable_strict_validation && !is_deposit_tx {
+            // <https://github.com/ethereum/EIPs/blob/master/EIPS/eip-3860.md>
+            if env.cfg.spec_id >= SpecId::SHANGHAI && tx.to().is_none() {
+                if tx.data().len() > self.max_initcode_size(env) {
+                    warn!(target: "backend", "[{:?}] max initcode size exceeded", tx.hash());
+                    return Err(InvalidTransactionError::MaxInitCodeSizeExceeded)
+                }
+            }
+
+            let intrinsic_gas = intrinsic_gas(tx, env.cfg.spec_id);
+            if tx.gas_limit() < U256::from(intrinsic_gas) {
+                warn!(target: "backend", "[{:?}] gas too low, intrinsic gas={}", tx.hash(), intrinsic_gas);
+                return Err(InvalidTransactionError::GasTooLow)
+            }
+
+            // <https://github.com/ethereum/EIPs/blob/master/EIPS/eip-3607.md>, accounts with an
+            // EIP-7702 delegation designator are still EOAs
+            if account.code_hash != KECCAK_EMPTY &&
+                delegation::account_delegation_target(account).is_none() &&
+                !pending.transaction.is_impersonated()
+            {
+                warn!(target: "backend", "[{:?}] sender {:?} is not an EOA", tx.hash(), *pending.sender());
+                return Err(InvalidTransactionError::SenderNoEOA)
+            }
+        }
+
         // Check gas limit, iff block gas limit is set.
         if !env.cfg.disable_block_gas_limit && tx.gas_limit() > env.block.gas_limit.to_ethers() {
             warn!(target: "backend", "[{:?}] gas too high", tx.hash());
@@ -2256,8 +2805,6 @@ impl TransactionValidator for Backend {
         }
 
         // check nonce
-        let is_deposit_tx =
-            matches!(&pending.transaction.transaction, TypedTransaction::Deposit(_));
         let nonce: u64 =
             (*tx.nonce()).try_into().map_err(|_| InvalidTransactionError::NonceMaxValue)?;
         if nonce < account.nonce && !is_deposit_tx {
@@ -2294,6 +2841,13 @@ impl TransactionValidator for Backend {
             warn!(target: "backend", "[{:?}] insufficient allowance={}, required={} account={:?}", tx.hash(), account.balance, req_funds, *pending.sender());
             return Err(InvalidTransactionError::InsufficientFunds)
         }
+
+        for hook in &self.hooks {
+            hook.validate_transaction(pending, env).map_err(|reason| {
+                warn!(target: "backend", "[{:?}] rejected by hook: {reason}", tx.hash());
+                InvalidTransactionError::Rejected(reason)
+            })?;
+        }
         Ok(())
     }
 
@@ -2311,6 +2865,52 @@ impl TransactionValidator for Backend {
     }
 }
 
+/// Returns the sender and the receiver of a parity trace, as matched by `trace_filter`
+///
+/// The receiver of a contract creation is the created contract, and the receiver of a
+/// self-destruct is the refund address.
+fn trace_addresses(trace: &Trace) -> (Option<Address>, Option<Address>) {
+    match &trace.action {
+        Action::Call(call) => (Some(call.from), Some(call.to)),
+        Action::Create(create) => {
+            let address = match &trace.result {
+                Some(Res::Create(result)) => Some(result.address),
+                _ => None,
+            };
+            (Some(create.from), address)
+        }
+        Action::Suicide(suicide) => (Some(suicide.address), Some(suicide.refund_address)),
+        Action::Reward(reward) => (None, Some(reward.author)),
+    }
+}
+
+/// Returns the gas a transaction is charged before execution starts.
+///
+/// This is the base cost of the transaction, plus the cost of its calldata ([EIP-2028](https://eips.ethereum.org/EIPS/eip-2028)),
+/// its access list ([EIP-2930](https://eips.ethereum.org/EIPS/eip-2930)) and the initcode of
+/// contract creations ([EIP-3860](https://eips.ethereum.org/EIPS/eip-3860)).
+pub(crate) fn intrinsic_gas(tx: &TypedTransaction, spec_id: SpecId) -> u64 {
+    let data = tx.data();
+    let zero_bytes = data.iter().filter(|b| **b == 0).count() as u64;
+    let non_zero_bytes = data.len() as u64 - zero_bytes;
+    let non_zero_byte_gas = if spec_id >= SpecId::ISTANBUL { 16 } else { 68 };
+    let mut gas = 21_000 + zero_bytes * 4 + non_zero_bytes * non_zero_byte_gas;
+
+    if tx.to().is_none() {
+        if spec_id >= SpecId::HOMESTEAD {
+            gas += 32_000;
+        }
+        if spec_id >= SpecId::SHANGHAI {
+            // 2 gas per 32 byte word of initcode
+            gas += (data.len() as u64).div_ceil(32) * 2;
+        }
+    }
+
+    let access_list = tx.essentials().access_list;
+    let storage_keys = access_list.0.iter().map(|item| item.storage_keys.len() as u64).sum::<u64>();
+    gas + access_list.0.len() as u64 * 2_400 + storage_keys * 1_900
+}
+
 /// Creates a `Transaction` as it's expected for the `eth` RPC api from storage data
 #[allow(clippy::too_many_arguments)]
 pub fn transaction_build(
@@ -2375,6 +2975,34 @@ pub fn transaction_build(
     transaction
 }
 
+/// Splits the sidecar of a mined blob transaction into its blobs
+fn to_blob_sidecars(
+    block_hash: H256,
+    block_number: U64,
+    tx_hash: TxHash,
+    tx_index: U64,
+    sidecar: &BlobTransactionSidecar,
+) -> Vec<BlobSidecar> {
+    sidecar
+        .blobs
+        .iter()
+        .zip(&sidecar.commitments)
+        .zip(&sidecar.proofs)
+        .enumerate()
+        .map(|(blob_index, ((blob, commitment), proof))| BlobSidecar {
+            block_hash,
+            block_number,
+            tx_hash,
+            tx_index,
+            blob_index: blob_index.into(),
+            blob: blob.clone(),
+            kzg_commitment: commitment.clone(),
+            kzg_proof: proof.clone(),
+            versioned_hash: kzg_to_versioned_hash(commitment),
+        })
+        .collect()
+}
+
 /// Prove a storage key's existence or nonexistence in the account's storage
 /// trie.
 /// `storage_key` is the hash of the desired storage key, meaning
@@ -12,7 +12,7 @@ use foundry_evm::{
     backend::DatabaseError,
     hashbrown::HashMap as Map,
     revm::{
-        db::{CacheDB, DatabaseRef, DbAccount},
+        db::{AccountState, CacheDB, DatabaseRef, DbAccount},
         primitives::{AccountInfo, Bytecode, Log},
     },
 };
@@ -82,9 +82,16 @@ pub fn trie_hash_db(accounts: &Map<Address, DbAccount>) -> (AsHashDB, H256) {
 }
 
 /// Returns all RLP-encoded Accounts
+///
+/// Accounts that are only cached as non-existent, after a missed lookup, and empty accounts
+/// without storage (EIP-161) are not part of the state trie.
 pub fn trie_accounts(accounts: &Map<Address, DbAccount>) -> Vec<(Address, Bytes)> {
     accounts
         .iter()
+        .filter(|(_, account)| {
+            account.account_state != AccountState::NotExisting &&
+                !(account.info.is_empty() && account.storage.values().all(|v| *v == rU256::ZERO))
+        })
         .map(|(address, account)| {
             let storage_root = trie_account_rlp(&account.info, &account.storage);
             (*address, storage_root)
@@ -162,3 +169,28 @@ where
     }
     Ok(cache_db)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_missing_and_empty_accounts() {
+        let funded = DbAccount {
+            info: AccountInfo { balance: rU256::from(1), ..Default::default() },
+            account_state: AccountState::Touched,
+            ..Default::default()
+        };
+        let mut accounts = Map::new();
+        accounts.insert(Address::with_last_byte(1), funded.clone());
+        let root = state_merkle_trie_root(&accounts);
+
+        accounts.insert(Address::with_last_byte(2), DbAccount::new_not_existing());
+        accounts.insert(
+            Address::with_last_byte(3),
+            DbAccount { account_state: AccountState::Touched, ..Default::default() },
+        );
+        assert_eq!(trie_accounts(&accounts).len(), 1);
+        assert_eq!(state_merkle_trie_root(&accounts), root);
+    }
+}
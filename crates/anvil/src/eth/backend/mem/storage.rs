@@ -9,7 +9,7 @@ use crate::eth::{
 use anvil_core::eth::{
     block::{Block, PartialHeader},
     receipt::TypedReceipt,
-    transaction::{MaybeImpersonatedTransaction, TransactionInfo},
+    transaction::{BlobTransactionSidecar, MaybeImpersonatedTransaction, TransactionInfo},
 };
 use ethers::{
     prelude::{BlockId, BlockNumber, DefaultFrame, Trace, H256, H256 as TxHash, U64},
@@ -216,6 +216,8 @@ pub struct BlockchainStorage {
     /// Mapping from the transaction hash to a tuple containing the transaction as well as the
     /// transaction receipt
     pub transactions: HashMap<TxHash, MinedTransaction>,
+    /// The blobs of mined blob transactions, by transaction hash
+    pub blob_sidecars: HashMap<TxHash, BlobTransactionSidecar>,
     /// The total difficulty of the chain until this block
     pub total_difficulty: U256,
 }
@@ -244,6 +246,7 @@ impl BlockchainStorage {
             best_number,
             genesis_hash,
             transactions: Default::default(),
+            blob_sidecars: Default::default(),
             total_difficulty: Default::default(),
         }
     }
@@ -256,6 +259,7 @@ impl BlockchainStorage {
             best_number: block_number.into(),
             genesis_hash: Default::default(),
             transactions: Default::default(),
+            blob_sidecars: Default::default(),
             total_difficulty,
         }
     }
@@ -269,6 +273,7 @@ impl BlockchainStorage {
             best_number: Default::default(),
             genesis_hash: Default::default(),
             transactions: Default::default(),
+            blob_sidecars: Default::default(),
             total_difficulty: Default::default(),
         }
     }
@@ -285,6 +290,7 @@ impl BlockchainStorage {
         if let Some(block) = self.blocks.get_mut(&block_hash) {
             for tx in block.transactions.iter() {
                 self.transactions.remove(&tx.hash());
+                self.blob_sidecars.remove(&tx.hash());
             }
             block.transactions.clear();
         }
@@ -396,7 +402,9 @@ impl MinedTransaction {
         let mut traces = Vec::with_capacity(self.info.traces.arena.len());
         for (idx, node) in self.info.traces.arena.iter().cloned().enumerate() {
             let action = node.parity_action();
-            let result = node.parity_result();
+            // failed calls have an error instead of a result
+            let error = node.parity_error();
+            let result = error.is_none().then(|| node.parity_result());
 
             let action_type = if node.status() == InstructionResult::SelfDestruct {
                 ActionType::Suicide
@@ -406,7 +414,7 @@ impl MinedTransaction {
 
             let trace = Trace {
                 action,
-                result: Some(result),
+                result,
                 trace_address: self.info.trace_address(idx),
                 subtraces: node.children.len(),
                 transaction_position: Some(self.info.transaction_index as usize),
@@ -414,7 +422,7 @@ impl MinedTransaction {
                 block_number: self.block_number,
                 block_hash: self.block_hash,
                 action_type,
-                error: None,
+                error,
             };
             traces.push(trace)
         }
@@ -1,6 +1,6 @@
 //! Notifications emitted from the backed
 
-use anvil_core::eth::block::Header;
+use anvil_core::eth::{block::Header, state::BlockStateDiff};
 use ethers::types::H256;
 use futures::channel::mpsc::UnboundedReceiver;
 use std::sync::Arc;
@@ -12,6 +12,8 @@ pub struct NewBlockNotification {
     pub hash: H256,
     /// block header
     pub header: Arc<Header>,
+    /// The state changes of the block's transactions
+    pub state_diff: Arc<BlockStateDiff>,
 }
 
 /// Type alias for a receiver that receives [NewBlockNotification]
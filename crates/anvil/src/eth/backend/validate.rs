@@ -1,8 +1,16 @@
 //! Support for validating transactions at certain stages
 
-use crate::eth::error::{BlockchainError, InvalidTransactionError};
-use anvil_core::eth::transaction::PendingTransaction;
-use foundry_evm::revm::primitives::{AccountInfo, Env};
+use crate::eth::{
+    backend::db::MaybeHashDatabase,
+    error::{BlockchainError, InvalidTransactionError},
+};
+use anvil_core::{
+    eth::transaction::PendingTransaction,
+    types::{KnownAccount, TransactionConditional},
+};
+use ethers::types::H256;
+use foundry_common::types::ToAlloy;
+use foundry_evm::revm::primitives::{AccountInfo, Env, U256 as rU256};
 
 /// A trait for validating transactions
 #[async_trait::async_trait]
@@ -35,3 +43,72 @@ pub trait TransactionValidator {
         env: &Env,
     ) -> Result<(), InvalidTransactionError>;
 }
+
+/// Returns an error if a precondition of `eth_sendRawTransactionConditional` doesn't hold for the
+/// block with the given `number` and `timestamp`, on top of the state of `db`
+///
+/// This is checked when the transaction is sent, for the pending block, and again when it is
+/// about to be included in a block.
+pub fn ensure_transaction_conditional<DB: MaybeHashDatabase + ?Sized>(
+    conditional: &TransactionConditional,
+    db: &DB,
+    number: u64,
+    timestamp: u64,
+) -> Result<(), InvalidTransactionError> {
+    let TransactionConditional {
+        known_accounts,
+        block_number_min,
+        block_number_max,
+        timestamp_min,
+        timestamp_max,
+    } = conditional;
+    let not_met = |msg: String| Err(InvalidTransactionError::ConditionalNotMet(msg));
+
+    if let Some(min) = block_number_min.filter(|min| number < min.as_u64()) {
+        return not_met(format!("block number {number} is below the minimum {min}"))
+    }
+    if let Some(max) = block_number_max.filter(|max| number > max.as_u64()) {
+        return not_met(format!("block number {number} is above the maximum {max}"))
+    }
+    if let Some(min) = timestamp_min.filter(|min| timestamp < min.as_u64()) {
+        return not_met(format!("block timestamp {timestamp} is below the minimum {min}"))
+    }
+    if let Some(max) = timestamp_max.filter(|max| timestamp > max.as_u64()) {
+        return not_met(format!("block timestamp {timestamp} is above the maximum {max}"))
+    }
+
+    for (address, account) in known_accounts {
+        match account {
+            KnownAccount::StorageRoot(expected) => {
+                let Some((_, root)) = db.maybe_account_db(*address) else {
+                    return not_met(format!("storage root of {address:?} is unavailable"))
+                };
+                if root != *expected {
+                    return not_met(format!(
+                        "storage root of {address:?} is {root:?}, expected {expected:?}"
+                    ))
+                }
+            }
+            KnownAccount::Slots(slots) => {
+                for (slot, expected) in slots {
+                    let index = rU256::from_be_bytes(slot.0);
+                    let value = match db.storage_ref(address.to_alloy(), index) {
+                        Ok(value) => H256(value.to_be_bytes()),
+                        Err(err) => {
+                            return not_met(format!(
+                                "storage slot {slot:?} of {address:?} is unavailable: {err}"
+                            ))
+                        }
+                    };
+                    if value != *expected {
+                        return not_met(format!(
+                            "storage slot {slot:?} of {address:?} is {value:?}, expected \
+                             {expected:?}"
+                        ))
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
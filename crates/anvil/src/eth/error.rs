@@ -62,6 +62,8 @@ pub enum BlockchainError {
     EvmError(InstructionResult),
     #[error("Invalid url {0:?}")]
     InvalidUrl(String),
+    #[error("The chain id {chain_id} differs from the chain id {remote_chain_id} of the forked network, transactions signed by this node could be replayed on the network with chain id {chain_id}.\nPass `--allow-chain-id-mismatch` to use it anyway.")]
+    ChainIdMismatch { chain_id: u64, remote_chain_id: u64 },
     #[error("Internal error: {0:?}")]
     Internal(String),
     #[error("BlockOutOfRangeError: block height is {0} but requested was {1}")]
@@ -86,8 +88,16 @@ pub enum BlockchainError {
     EIP2930TransactionUnsupportedAtHardfork,
     #[error("op-stack deposit tx received but is not supported.\n\nYou can use it by running anvil with '--optimism'.")]
     DepositTransactionUnsupported,
+    #[error("Blob transaction received but is not supported by the current hardfork.\n\nYou can use it by running anvil with '--hardfork cancun' or later.")]
+    EIP4844TransactionUnsupportedAtHardfork,
+    #[error("Invalid blob sidecar: {0}")]
+    InvalidBlobSidecar(String),
     #[error("Excess blob gas not set.")]
     ExcessBlobGasNotSet,
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
+    #[error("Invalid delegation designator, expected 0xef0100 followed by an address")]
+    InvalidDelegationDesignator,
 }
 
 impl From<RpcError> for BlockchainError {
@@ -129,6 +139,8 @@ pub enum PoolError {
 pub enum FeeHistoryError {
     #[error("Requested block range is out of bounds")]
     InvalidBlockRange,
+    #[error("Invalid reward percentiles {0:?}, must be sorted and between 0 and 100")]
+    InvalidRewardPercentiles(Vec<f64>),
 }
 
 #[derive(Debug)]
@@ -205,6 +217,13 @@ pub enum InvalidTransactionError {
     /// Thrown when `max_fee_per_blob_gas` is not supported for blocks before the Cancun hardfork.
     #[error("`max_fee_per_blob_gas` is not supported for blocks before the Cancun hardfork.")]
     MaxFeePerBlobGasNotSupported,
+    /// Thrown when a [NodeHook](crate::hooks::NodeHook) rejects the transaction.
+    #[error("transaction rejected: {0}")]
+    Rejected(String),
+    /// Thrown when a precondition of a transaction sent with
+    /// `eth_sendRawTransactionConditional` doesn't hold.
+    #[error("Transaction conditional not met: {0}")]
+    ConditionalNotMet(String),
 }
 
 impl From<revm::primitives::InvalidTransaction> for InvalidTransactionError {
@@ -375,6 +394,9 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                     RpcError::internal_error_with(err.to_string())
                 }
                 err @ BlockchainError::InvalidUrl(_) => RpcError::invalid_params(err.to_string()),
+                err @ BlockchainError::ChainIdMismatch { .. } => {
+                    RpcError::invalid_params(err.to_string())
+                }
                 BlockchainError::Internal(err) => RpcError::internal_error_with(err),
                 err @ BlockchainError::BlockOutOfRange(_, _) => {
                     RpcError::invalid_params(err.to_string())
@@ -410,9 +432,21 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                 err @ BlockchainError::DepositTransactionUnsupported => {
                     RpcError::invalid_params(err.to_string())
                 }
+                err @ BlockchainError::EIP4844TransactionUnsupportedAtHardfork => {
+                    RpcError::invalid_params(err.to_string())
+                }
+                err @ BlockchainError::InvalidBlobSidecar(_) => {
+                    RpcError::invalid_params(err.to_string())
+                }
                 err @ BlockchainError::ExcessBlobGasNotSet => {
                     RpcError::invalid_params(err.to_string())
                 }
+                err @ BlockchainError::InvalidBundle(_) => {
+                    RpcError::invalid_params(err.to_string())
+                }
+                err @ BlockchainError::InvalidDelegationDesignator => {
+                    RpcError::invalid_params(err.to_string())
+                }
             }
             .into(),
         }
@@ -0,0 +1,109 @@
+//! Faults injected by the node, to test how clients handle a misbehaving node
+
+use anvil_core::{eth::EthRequest, types::FaultInjection};
+use anvil_rpc::error::{ErrorCode, RpcError};
+use parking_lot::RwLock;
+use rand::Rng;
+use std::{sync::Arc, time::Duration};
+
+/// The JSON-RPC error code of injected transient errors.
+///
+/// This is the code of rate limit errors, which clients, like foundry's `RetryProvider`, retry.
+pub const TRANSIENT_ERROR_CODE: i64 = 429;
+
+/// Injects the configured [FaultInjection] into RPC calls and submitted transactions.
+///
+/// This type is cheap to clone, the faults can be reconfigured at runtime with
+/// `anvil_setFaultInjection`.
+#[derive(Clone, Debug, Default)]
+pub struct FaultInjector {
+    faults: Arc<RwLock<FaultInjection>>,
+}
+
+impl FaultInjector {
+    pub fn new(faults: FaultInjection) -> Self {
+        Self { faults: Arc::new(RwLock::new(faults)) }
+    }
+
+    /// Returns the currently injected faults
+    pub fn faults(&self) -> FaultInjection {
+        self.faults.read().clone()
+    }
+
+    /// Replaces the injected faults
+    pub fn set_faults(&self, faults: FaultInjection) {
+        *self.faults.write() = faults;
+    }
+
+    /// Delays the RPC call by the configured latency and returns a transient error if the call
+    /// should fail.
+    ///
+    /// The calls that configure the faults are never affected, so injected faults can always be
+    /// turned off again.
+    pub async fn on_request(&self, request: &EthRequest) -> Option<RpcError> {
+        if matches!(request, EthRequest::SetFaultInjection(_) | EthRequest::FaultInjection(_)) {
+            return None
+        }
+
+        let (latency, fail) = {
+            let faults = self.faults.read();
+            let mut rng = rand::thread_rng();
+            let jitter = if faults.latency_jitter > 0 {
+                rng.gen_range(0..=faults.latency_jitter)
+            } else {
+                0
+            };
+            (faults.latency + jitter, happens(&mut rng, faults.error_rate))
+        };
+
+        if latency > 0 {
+            tokio::time::sleep(Duration::from_millis(latency)).await;
+        }
+
+        fail.then(|| {
+            trace!(target: "node", "injecting transient error");
+            RpcError {
+                code: ErrorCode::ServerError(TRANSIENT_ERROR_CODE),
+                message: "injected transient error, try again".into(),
+                data: None,
+            }
+        })
+    }
+
+    /// Returns true if a submitted transaction should be acknowledged, but not added to the pool
+    pub fn should_drop_transaction(&self) -> bool {
+        happens(&mut rand::thread_rng(), self.faults.read().drop_rate)
+    }
+}
+
+/// Returns true with the given probability
+fn happens(rng: &mut impl Rng, probability: f64) -> bool {
+    probability > 0.0 && rng.gen::<f64>() < probability
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn injects_configured_faults() {
+        let injector = FaultInjector::default();
+        assert!(injector.on_request(&EthRequest::EthChainId(())).await.is_none());
+        assert!(!injector.should_drop_transaction());
+
+        injector.set_faults(FaultInjection {
+            error_rate: 1.0,
+            drop_rate: 1.0,
+            ..Default::default()
+        });
+        let err = injector.on_request(&EthRequest::EthChainId(())).await.unwrap();
+        assert_eq!(err.code.code(), TRANSIENT_ERROR_CODE);
+        assert!(injector.should_drop_transaction());
+
+        // faults can always be turned off
+        assert!(injector
+            .on_request(&EthRequest::SetFaultInjection(Default::default()))
+            .await
+            .is_none());
+    }
+}
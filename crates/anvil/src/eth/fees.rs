@@ -200,28 +200,12 @@ impl FeeHistoryService {
     }
 
     /// Create a new history entry for the block
-    fn create_cache_entry(
-        &self,
-        hash: H256,
-        elasticity: f64,
-    ) -> (FeeHistoryCacheItem, Option<u64>) {
-        // percentile list from 0.0 to 100.0 with a 0.5 resolution.
-        // this will create 200 percentile points
-        let reward_percentiles: Vec<f64> = {
-            let mut percentile: f64 = 0.0;
-            (0..=200)
-                .map(|_| {
-                    let val = percentile;
-                    percentile += 0.5;
-                    val
-                })
-                .collect()
-        };
-
+    fn create_cache_entry(&self, hash: H256) -> (FeeHistoryCacheItem, Option<u64>) {
         let mut block_number: Option<u64> = None;
-        let base_fee = self.fees.base_fee();
         let mut item = FeeHistoryCacheItem {
-            base_fee: base_fee.as_u64(),
+            base_fee: self.fees.base_fee().as_u64(),
+            gas_used: 0,
+            gas_limit: 0,
             gas_used_ratio: 0f64,
             rewards: Vec::new(),
         };
@@ -232,11 +216,18 @@ impl FeeHistoryService {
         if let (Some(block), Some(receipts)) = (current_block, current_receipts) {
             block_number = Some(block.header.number.as_u64());
 
-            let gas_used = block.header.gas_used.as_u64() as f64;
-            let gas_limit = block.header.gas_limit.as_u64() as f64;
+            // the base fee the block was mined with, the fee manager already holds the base fee of
+            // the next block
+            if let Some(base_fee) = block.header.base_fee_per_gas {
+                item.base_fee = base_fee.as_u64();
+            }
+            let base_fee = U256::from(item.base_fee);
 
-            let gas_target = gas_limit / elasticity;
-            item.gas_used_ratio = gas_used / (gas_target * elasticity);
+            item.gas_used = block.header.gas_used.as_u64();
+            item.gas_limit = block.header.gas_limit.as_u64();
+            if item.gas_limit > 0 {
+                item.gas_used_ratio = item.gas_used as f64 / item.gas_limit as f64;
+            }
 
             // extract useful tx info (gas_used, effective_reward)
             let mut transactions: Vec<(u64, u64)> = receipts
@@ -256,6 +247,10 @@ impl FeeHistoryService {
                             .max_priority_fee_per_gas
                             .min(t.max_fee_per_gas.saturating_sub(base_fee))
                             .as_u64(),
+                        Some(TypedTransaction::EIP4844(t)) => t
+                            .max_priority_fee_per_gas
+                            .min(t.max_fee_per_gas.saturating_sub(base_fee))
+                            .as_u64(),
                         Some(TypedTransaction::Deposit(_)) => 0,
                         None => 0,
                     };
@@ -266,24 +261,7 @@ impl FeeHistoryService {
 
             // sort by effective reward asc
             transactions.sort_by(|(_, a), (_, b)| a.cmp(b));
-
-            // calculate percentile rewards
-            item.rewards = reward_percentiles
-                .into_iter()
-                .filter_map(|p| {
-                    let target_gas = (p * gas_used / 100f64) as u64;
-                    let mut sum_gas = 0;
-                    for (gas_used, effective_reward) in transactions.iter().cloned() {
-                        sum_gas += gas_used;
-                        if target_gas <= sum_gas {
-                            return Some(effective_reward)
-                        }
-                    }
-                    None
-                })
-                .collect();
-        } else {
-            item.rewards = reward_percentiles.iter().map(|_| 0).collect();
+            item.rewards = transactions;
         }
         (item, block_number)
     }
@@ -314,11 +292,8 @@ impl Future for FeeHistoryService {
         let pin = self.get_mut();
 
         while let Poll::Ready(Some(notification)) = pin.new_blocks.poll_next_unpin(cx) {
-            let hash = notification.hash;
-            let elasticity = default_elasticity();
-
             // add the imported block.
-            let (result, block_number) = pin.create_cache_entry(hash, elasticity);
+            let (result, block_number) = pin.create_cache_entry(notification.hash);
             pin.insert_cache_entry(result, block_number)
         }
 
@@ -332,8 +307,40 @@ pub type FeeHistoryCache = Arc<Mutex<BTreeMap<u64, FeeHistoryCacheItem>>>;
 #[derive(Debug, Clone)]
 pub struct FeeHistoryCacheItem {
     pub base_fee: u64,
+    pub gas_used: u64,
+    pub gas_limit: u64,
     pub gas_used_ratio: f64,
-    pub rewards: Vec<u64>,
+    /// The gas used and the effective priority fee of every transaction in the block, sorted by
+    /// the priority fee
+    pub rewards: Vec<(u64, u64)>,
+}
+
+impl FeeHistoryCacheItem {
+    /// Returns the effective priority fees paid at the given percentiles of the gas used in the
+    /// block, which must be sorted.
+    ///
+    /// The reward of a percentile is the priority fee of the transaction in which the gas used,
+    /// summed up over the transactions sorted by priority fee, reaches the percentile. This is
+    /// how geth computes the rewards of `eth_feeHistory`.
+    pub fn rewards_at(&self, percentiles: &[f64]) -> Vec<u64> {
+        let Some(&(first_gas_used, _)) = self.rewards.first() else {
+            return vec![0; percentiles.len()]
+        };
+
+        let mut index = 0;
+        let mut sum_gas_used = first_gas_used;
+        percentiles
+            .iter()
+            .map(|p| {
+                let threshold = (self.gas_used as f64 * p / 100f64) as u64;
+                while sum_gas_used < threshold && index < self.rewards.len() - 1 {
+                    index += 1;
+                    sum_gas_used += self.rewards[index].0;
+                }
+                self.rewards[index].1
+            })
+            .collect()
+    }
 }
 
 #[derive(Default, Clone)]
@@ -414,3 +421,23 @@ impl fmt::Debug for FeeDetails {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_compute_reward_percentiles() {
+        let item = FeeHistoryCacheItem {
+            base_fee: INITIAL_BASE_FEE,
+            gas_used: 100_000,
+            gas_limit: 200_000,
+            gas_used_ratio: 0.5,
+            rewards: vec![(21_000, 1), (50_000, 2), (29_000, 3)],
+        };
+        assert_eq!(item.rewards_at(&[0.0, 20.0, 25.0, 71.0, 72.0, 100.0]), [1, 1, 2, 2, 3, 3]);
+
+        let empty = FeeHistoryCacheItem { rewards: vec![], gas_used: 0, ..item };
+        assert_eq!(empty.rewards_at(&[10.0, 90.0]), [0, 0]);
+    }
+}
@@ -6,6 +6,8 @@ pub mod backend;
 
 pub mod error;
 
+pub mod faults;
+
 pub mod fees;
 pub(crate) mod macros;
 pub mod miner;
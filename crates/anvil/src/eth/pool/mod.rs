@@ -91,6 +91,21 @@ impl Pool {
         // remove invalid transactions from the pool
         self.remove_invalid(invalid.into_iter().map(|tx| *tx.hash()).collect());
 
+        // remove transactions whose conditional can't be met by any later block
+        let expired = self
+            .ready_transactions()
+            .chain(self.pending_transactions())
+            .filter(|tx| {
+                tx.pending_transaction
+                    .conditional
+                    .as_ref()
+                    .and_then(|conditional| conditional.block_number_max)
+                    .is_some_and(|max| max <= block_number)
+            })
+            .map(|tx| *tx.hash())
+            .collect();
+        self.remove_invalid(expired);
+
         // prune all the markers the mined transactions provide
         let res = self
             .prune_markers(block_number, included.into_iter().flat_map(|tx| tx.provides.clone()));
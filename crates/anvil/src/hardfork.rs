@@ -45,9 +45,7 @@ impl Hardfork {
             Hardfork::GrayGlacier => 15050000,
             Hardfork::Paris => 15537394,
             Hardfork::Shanghai | Hardfork::Latest => 17034870,
-
-            // TODO: set block number after activation
-            Hardfork::Cancun => unreachable!(),
+            Hardfork::Cancun => 19426587,
         }
     }
 
@@ -0,0 +1,66 @@
+//! Hooks into the transaction lifecycle of the node.
+//!
+//! Crates that embed anvil can implement [NodeHook] to add custom sequencer rules, fee logic or
+//! censorship policies, and register it with [NodeConfig::with_hook](crate::NodeConfig::with_hook).
+//!
+//! ```no_run
+//! use anvil::{hooks::NodeHook, NodeConfig};
+//! use anvil_core::eth::transaction::PendingTransaction;
+//! use ethers::types::Address;
+//! use foundry_evm::revm::primitives::Env;
+//!
+//! /// Rejects all transactions sent by a blocked address.
+//! #[derive(Debug)]
+//! struct Blocklist(Address);
+//!
+//! impl NodeHook for Blocklist {
+//!     fn validate_transaction(&self, tx: &PendingTransaction, _env: &Env) -> Result<(), String> {
+//!         if *tx.sender() == self.0 {
+//!             return Err("sender is blocked".to_string())
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! # async fn spawn() {
+//! let config = NodeConfig::test().with_hook(Blocklist(Address::zero()));
+//! let (api, handle) = anvil::spawn(config).await;
+//! # }
+//! ```
+
+use anvil_core::eth::{
+    block::Block,
+    receipt::TypedReceipt,
+    transaction::{PendingTransaction, TransactionInfo},
+};
+use foundry_evm::revm::primitives::Env;
+use std::fmt;
+
+/// A hook that is called when transactions are validated and executed, and when blocks are mined.
+///
+/// All methods do nothing by default, so implementations only override the hooks they need.
+///
+/// The hooks are called synchronously while the node validates transactions or mines a block, so
+/// they should return quickly and must not call into the node.
+pub trait NodeHook: fmt::Debug + Send + Sync {
+    /// Validates a transaction in the environment of the next block.
+    ///
+    /// This is called before the transaction enters the pool, and again before it is executed in
+    /// a block, so a policy that changes in the meantime also applies to pending transactions.
+    /// Returning an error rejects the transaction with the given reason.
+    fn validate_transaction(&self, tx: &PendingTransaction, env: &Env) -> Result<(), String> {
+        let _ = (tx, env);
+        Ok(())
+    }
+
+    /// Called for every transaction that was executed in a new block, in order, before the block
+    /// is stored.
+    fn on_transaction_executed(&self, info: &TransactionInfo, receipt: &TypedReceipt) {
+        let _ = (info, receipt);
+    }
+
+    /// Called after a new block was stored, before subscribers are notified of it.
+    fn on_block_finalized(&self, block: &Block) {
+        let _ = block;
+    }
+}
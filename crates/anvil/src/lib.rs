@@ -4,6 +4,7 @@ extern crate tracing;
 use crate::{
     eth::{
         backend::{info::StorageInfo, mem},
+        faults::FaultInjector,
         fees::{FeeHistoryService, FeeManager},
         miner::{Miner, MiningMode},
         pool::Pool,
@@ -11,7 +12,7 @@ use crate::{
         EthApi,
     },
     filter::Filters,
-    logging::{LoggingManager, NodeLogLayer},
+    logging::{self, LogFormat, LoggingManager, NodeLogLayer, NODE_USER_LOG_TARGET},
     service::NodeService,
     shutdown::Signal,
     tasks::TaskManager,
@@ -53,14 +54,23 @@ use crate::server::{
 };
 pub use hardfork::Hardfork;
 
+/// custom chain presets for `--chain`
+pub mod chains;
+/// in-process transport for embedding the node
+pub mod embedded;
+use embedded::InProcessClient;
 /// ethereum related implementations
 pub mod eth;
 /// support for polling filters
 pub mod filter;
 /// support for handling `genesis.json` files
 pub mod genesis;
+/// hooks into the transaction lifecycle for embedders
+pub mod hooks;
 /// commandline output
 pub mod logging;
+/// canonical infrastructure contracts deployed at genesis
+pub mod predeploys;
 /// types for subscriptions
 pub mod pubsub;
 /// axum RPC server implementations
@@ -93,11 +103,24 @@ pub mod cmd;
 /// handle.await.unwrap();
 /// # }
 /// ```
-pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
-    let logger = if config.enable_tracing { init_tracing() } else { Default::default() };
+///
+/// # Panics
+///
+/// Panics if the node can't be set up, see [try_spawn] for a fallible version
+pub async fn spawn(config: NodeConfig) -> (EthApi, NodeHandle) {
+    try_spawn(config).await.expect("failed to spawn node")
+}
+
+/// Creates the node and runs the server, like [spawn]
+///
+/// Returns an error if the node can't be set up, e.g. if its chain id differs from the chain id
+/// of the forked network.
+pub async fn try_spawn(mut config: NodeConfig) -> eyre::Result<(EthApi, NodeHandle)> {
+    let logger =
+        if config.enable_tracing { init_tracing(config.log_format) } else { Default::default() };
     logger.set_enabled(!config.silent);
 
-    let backend = Arc::new(config.setup().await);
+    let backend = Arc::new(config.setup().await?);
 
     if config.enable_auto_impersonate {
         backend.auto_impersonate_account(true).await;
@@ -107,6 +130,7 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
 
     let NodeConfig {
         signer_accounts,
+        keystore_accounts,
         block_time,
         port,
         max_transactions,
@@ -132,6 +156,9 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
 
     let dev_signer: Box<dyn EthSigner> = Box::new(DevSigner::new(signer_accounts));
     let mut signers = vec![dev_signer];
+    if !keystore_accounts.is_empty() {
+        signers.push(Box::new(DevSigner::new(keystore_accounts)));
+    }
     if let Some(genesis) = genesis {
         // include all signers from genesis.json if any
         let genesis_signers = genesis.private_keys();
@@ -163,6 +190,7 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
         logger,
         filters.clone(),
         transaction_order,
+        FaultInjector::new(config.fault_injection.clone()),
     );
 
     // spawn the node service
@@ -187,10 +215,17 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
     let (signal, on_shutdown) = shutdown::signal();
     let task_manager = TaskManager::new(tokio_handle, on_shutdown);
 
+    if config.fork_follow_mempool {
+        if let Some(fork) = &fork {
+            task_manager.spawn_follow_mempool(fork.provider().as_ref().clone(), api.clone());
+        }
+    }
+
     let ipc_task = config.get_ipc_path().map(|path| spawn_ipc(api.clone(), path));
 
     let handle = NodeHandle {
         config,
+        api: api.clone(),
         node_service,
         servers,
         ipc_task,
@@ -201,7 +236,7 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
 
     handle.print(fork.as_ref());
 
-    (api, handle)
+    Ok((api, handle))
 }
 
 type IpcTask = JoinHandle<io::Result<()>>;
@@ -211,6 +246,8 @@ type IpcTask = JoinHandle<io::Result<()>>;
 /// This future will resolve if either the node or server task resolve/fail.
 pub struct NodeHandle {
     config: NodeConfig,
+    /// Access to the node, used by the in-process provider
+    api: EthApi,
     /// The address of the running rpc server
     addresses: Vec<SocketAddr>,
     /// Join handle for the Node Service
@@ -234,15 +271,19 @@ impl NodeHandle {
     /// Prints the launch info
     pub(crate) fn print(&self, fork: Option<&ClientFork>) {
         self.config.print(fork);
-        if !self.config.silent {
-            println!(
-                "Listening on {}",
-                self.addresses
-                    .iter()
-                    .map(|addr| { addr.to_string() })
-                    .collect::<Vec<String>>()
-                    .join(", ")
-            )
+        if !self.config.silent && !self.addresses.is_empty() {
+            let addresses = self
+                .addresses
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            match self.config.log_format {
+                LogFormat::Text => println!("Listening on {addresses}"),
+                LogFormat::Json => {
+                    eth::macros::node_info!(%addresses, "Listening on {addresses}")
+                }
+            }
         }
     }
 
@@ -250,6 +291,10 @@ impl NodeHandle {
     ///
     /// **N.B.** this may not necessarily be the same `host + port` as configured in the
     /// `NodeConfig`, if port was set to 0, then the OS auto picks an available port
+    ///
+    /// # Panics
+    ///
+    /// if the node was launched without any servers, see [NodeConfig::spawn]
     pub fn socket_address(&self) -> &SocketAddr {
         &self.addresses[0]
     }
@@ -287,6 +332,12 @@ impl NodeHandle {
         ProviderBuilder::new(&self.config.get_ipc_path()?).build().ok()
     }
 
+    /// Constructs a [`Provider`](ethers::providers::Provider) that sends all requests directly
+    /// to the node, without going through any of its servers.
+    pub fn in_process_provider(&self) -> ethers::providers::Provider<InProcessClient> {
+        InProcessClient::new(self.api.clone()).into_provider()
+    }
+
     /// Signer accounts that can sign messages/transactions from the EVM node
     pub fn dev_accounts(&self) -> impl Iterator<Item = Address> + '_ {
         self.config.signer_accounts.iter().map(|wallet| wallet.address())
@@ -376,26 +427,35 @@ impl Future for NodeHandle {
 }
 
 #[doc(hidden)]
-pub fn init_tracing() -> LoggingManager {
-    use tracing_subscriber::prelude::*;
+pub fn init_tracing(format: LogFormat) -> LoggingManager {
+    use tracing_subscriber::{prelude::*, EnvFilter};
 
     let manager = LoggingManager::default();
-    // check whether `RUST_LOG` is explicitly set
-    let _ = if std::env::var("RUST_LOG").is_ok() {
-        tracing_subscriber::Registry::default()
-            .with(tracing_subscriber::EnvFilter::from_default_env())
-            .with(tracing_subscriber::fmt::layer())
-            .try_init()
+    // check whether `RUST_LOG` is explicitly set, otherwise only the node's events are logged
+    let rust_log = std::env::var("RUST_LOG").is_ok();
+    let filter = if rust_log {
+        EnvFilter::from_default_env()
     } else {
-        tracing_subscriber::Registry::default()
-            .with(NodeLogLayer::new(manager.clone()))
+        EnvFilter::new(format!("{NODE_USER_LOG_TARGET}=info"))
+    };
+    let registry = tracing_subscriber::Registry::default()
+        .with(filter)
+        .with(NodeLogLayer::new(manager.clone()));
+
+    let _ = match format {
+        LogFormat::Json => {
+            registry.with(tracing_subscriber::fmt::layer().json().flatten_event(true)).try_init()
+        }
+        LogFormat::Text if rust_log => registry.with(tracing_subscriber::fmt::layer()).try_init(),
+        LogFormat::Text => registry
             .with(
                 tracing_subscriber::fmt::layer()
                     .without_time()
                     .with_target(false)
-                    .with_level(false),
+                    .with_level(false)
+                    .fmt_fields(logging::message_fields()),
             )
-            .try_init()
+            .try_init(),
     };
 
     manager
@@ -1,17 +1,67 @@
 //! User facing Logger
 
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::{fmt, str::FromStr, sync::Arc};
 use tracing::{subscriber::Interest, Metadata};
-use tracing_subscriber::{layer::Context, Layer};
+use tracing_subscriber::{
+    fmt::format::{self, FormatFields},
+    layer::Context,
+    Layer,
+};
 
 /// The target that identifies the events intended to be logged to stdout
 pub(crate) const NODE_USER_LOG_TARGET: &str = "node::user";
 
+/// The format of the logs written to stdout
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable text, only the messages of the node's events are printed
+    #[default]
+    Text,
+    /// One JSON object per event, including the structured fields of the event, e.g. the
+    /// transaction hash, gas used and revert reason of mined transactions
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown log format `{s}`, expected `text` or `json`")),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text => f.write_str("text"),
+            Self::Json => f.write_str("json"),
+        }
+    }
+}
+
+/// Returns a field formatter that only writes the message of an event, so that the structured
+/// fields of the node's events don't clutter the text output
+pub(crate) fn message_fields() -> impl for<'w> FormatFields<'w> + Send + Sync + 'static {
+    format::debug_fn(
+        |writer, field, value| {
+            if field.name() == "message" {
+                write!(writer, "{value:?}")
+            } else {
+                Ok(())
+            }
+        },
+    )
+}
+
 /// A logger that listens for node related events and displays them.
 ///
 /// This layer is intended to be used as filter for `NODE_USER_LOG_TARGET` events that will
-/// eventually be logged to stdout
+/// eventually be logged to stdout, all other events are left to the `RUST_LOG` filter
 #[derive(Debug, Clone, Default)]
 pub struct NodeLogLayer {
     state: LoggingManager,
@@ -32,15 +82,16 @@ where
     S: tracing::Subscriber,
 {
     fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
-        if self.state.is_enabled() && metadata.target() == NODE_USER_LOG_TARGET {
-            Interest::always()
+        if metadata.target() == NODE_USER_LOG_TARGET {
+            // logging can be toggled at runtime, so this needs to be checked for every event
+            Interest::sometimes()
         } else {
-            Interest::never()
+            Interest::always()
         }
     }
 
     fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
-        self.state.is_enabled() && metadata.target() == NODE_USER_LOG_TARGET
+        metadata.target() != NODE_USER_LOG_TARGET || self.state.is_enabled()
     }
 }
 
@@ -71,3 +122,16 @@ impl Default for LoggingManager {
         Self { enabled: Arc::new(RwLock::new(true)) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_log_format() {
+        assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Text);
+        assert_eq!("JSON".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert!("yaml".parse::<LogFormat>().is_err());
+        assert_eq!(LogFormat::Json.to_string(), "json");
+    }
+}
@@ -0,0 +1,95 @@
+//! Canonical infrastructure contracts that can be deployed at genesis
+
+use ethers::{
+    providers::Middleware,
+    types::{Address, Bytes},
+};
+use foundry_common::ProviderBuilder;
+use std::{fmt, str::FromStr};
+
+/// The canonical address of Multicall3
+pub const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+/// The canonical address of Uniswap's Permit2
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+/// A contract that is deployed at the same address on most chains and that scripts commonly rely
+/// on.
+///
+/// The CREATE2 deployer isn't listed since anvil already installs it by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Predeploy {
+    /// Multicall3
+    Multicall3,
+    /// Uniswap's Permit2
+    Permit2,
+}
+
+impl Predeploy {
+    /// Returns the canonical address of the contract
+    pub fn address(&self) -> Address {
+        match self {
+            Self::Multicall3 => MULTICALL3_ADDRESS.parse().unwrap(),
+            Self::Permit2 => PERMIT2_ADDRESS.parse().unwrap(),
+        }
+    }
+
+    /// Returns the runtime code of the contract, copied from its canonical address on the chain of
+    /// `rpc_url`.
+    pub async fn code(&self, rpc_url: Option<&str>) -> Result<Bytes, String> {
+        let Some(rpc_url) = rpc_url else {
+            return Err(format!(
+                "the code of {self} is copied from another chain, pass `--predeploys-rpc-url` \
+                 with the endpoint of a chain it's deployed on"
+            ))
+        };
+        let provider = ProviderBuilder::new(rpc_url).build().map_err(|err| err.to_string())?;
+        let code = provider.get_code(self.address(), None).await.map_err(|err| err.to_string())?;
+        if code.is_empty() {
+            return Err(format!("{self} isn't deployed at {:?} on {rpc_url}", self.address()))
+        }
+        Ok(code)
+    }
+}
+
+impl FromStr for Predeploy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "multicall3" => Ok(Self::Multicall3),
+            "permit2" => Ok(Self::Permit2),
+            _ => Err(format!("unknown predeploy `{s}`, expected `multicall3` or `permit2`")),
+        }
+    }
+}
+
+impl fmt::Display for Predeploy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Multicall3 => f.write_str("multicall3"),
+            Self::Permit2 => f.write_str("permit2"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_predeploys() {
+        for predeploy in [Predeploy::Multicall3, Predeploy::Permit2] {
+            assert_eq!(predeploy.to_string().parse::<Predeploy>().unwrap(), predeploy);
+        }
+        assert_eq!("Permit2".parse::<Predeploy>().unwrap(), Predeploy::Permit2);
+        assert!("multicall2".parse::<Predeploy>().is_err());
+        assert!("create2-deployer".parse::<Predeploy>().is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn requires_rpc_url() {
+        assert!(Predeploy::Multicall3.code(None).await.is_err());
+        assert!(Predeploy::Permit2.code(None).await.is_err());
+    }
+}
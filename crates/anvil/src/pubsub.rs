@@ -96,6 +96,7 @@ pub enum EthSubscription {
     Logs(Box<LogsSubscription>),
     Header(NewBlockNotifications, StorageInfo, SubscriptionId),
     PendingTransactions(Receiver<TxHash>, SubscriptionId),
+    StateDiffs(NewBlockNotifications, SubscriptionId),
 }
 
 // === impl EthSubscription ===
@@ -122,6 +123,18 @@ impl EthSubscription {
                     }
                 }
             }
+            EthSubscription::StateDiffs(blocks, id) => {
+                let res = ready!(blocks.poll_next_unpin(cx))
+                    .map(|block| {
+                        SubscriptionResult::StateDiff(Box::new((*block.state_diff).clone()))
+                    })
+                    .map(to_rpc_result)
+                    .map(|result| {
+                        let params = EthSubscriptionParams { subscription: id.clone(), result };
+                        EthSubscriptionResponse::new(params)
+                    });
+                Poll::Ready(res)
+            }
             EthSubscription::PendingTransactions(tx, id) => {
                 let res = ready!(tx.poll_next_unpin(cx))
                     .map(SubscriptionResult::TransactionHash)
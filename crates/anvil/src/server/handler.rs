@@ -5,7 +5,7 @@ use crate::{
     EthApi,
 };
 use anvil_core::eth::{
-    subscription::{SubscriptionId, SubscriptionKind},
+    subscription::{AnvilSubscriptionKind, SubscriptionId, SubscriptionKind},
     EthPubSub, EthRequest, EthRpcCall,
 };
 use anvil_rpc::{error::RpcError, response::ResponseResult};
@@ -96,6 +96,19 @@ impl PubSubEthRpcHandler {
 
                 cx.add_subscription(id.clone(), subscription);
 
+                trace!(target: "rpc::ws", "created new subscription: {:?}", id);
+                to_rpc_result(id)
+            }
+            EthPubSub::AnvilSubscribe(kind) => {
+                let subscription = match kind {
+                    AnvilSubscriptionKind::StateDiffs => {
+                        trace!(target: "rpc::ws", "received state diffs subscription");
+                        EthSubscription::StateDiffs(self.api.new_block_notifications(), id.clone())
+                    }
+                };
+
+                cx.add_subscription(id.clone(), subscription);
+
                 trace!(target: "rpc::ws", "created new subscription: {:?}", id);
                 to_rpc_result(id)
             }
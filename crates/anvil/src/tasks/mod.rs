@@ -7,6 +7,7 @@ use ethers::{
     providers::{JsonRpcClient, PubsubClient},
     types::{Block, H256},
 };
+use futures::StreamExt;
 use std::{fmt, future::Future};
 use tokio::{runtime::Handle, task::JoinHandle};
 
@@ -84,6 +85,43 @@ impl TaskManager {
         })
     }
 
+    /// Spawns a new task that watches the pending transactions of the provider's mempool
+    /// (poll-based) and submits every new transaction to the node, like
+    /// `eth_sendRawTransaction`
+    ///
+    /// Transactions the node rejects, for example because their nonce was already used, are
+    /// dropped.
+    pub fn spawn_follow_mempool<P>(&self, provider: P, api: EthApi)
+    where
+        P: Middleware + Unpin + 'static + Send + Sync,
+        <P as Middleware>::Provider: JsonRpcClient,
+    {
+        let shutdown = self.on_shutdown.clone();
+        self.spawn(async move {
+            let pending = match provider.watch_pending_transactions().await {
+                Ok(pending) => pending,
+                Err(err) => {
+                    error!(target: "node", %err, "failed to watch the pending transactions of the fork");
+                    return
+                }
+            };
+            let mut pending = pending.take_until(shutdown);
+            while let Some(hash) = pending.next().await {
+                let tx = match provider.get_transaction(hash).await {
+                    Ok(Some(tx)) => tx,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        trace!(target: "node", %err, ?hash, "failed to fetch pending transaction");
+                        continue
+                    }
+                };
+                if let Err(err) = api.send_raw_transaction(tx.rlp()).await {
+                    trace!(target: "node", ?err, ?hash, "dropped pending transaction of the fork");
+                }
+            }
+        });
+    }
+
     /// Spawns a new [`BlockListener`] task that listens for new blocks (poll-based) See also
     /// [`Provider::watch_blocks`] and executes the future the `task_factory` returns for the new
     /// block hash
@@ -1,7 +1,21 @@
 //! tests for anvil specific logic
 
-use anvil::{spawn, NodeConfig};
-use ethers::{prelude::Middleware, types::Address};
+use anvil::{hooks::NodeHook, predeploys::Predeploy, spawn, NodeConfig};
+use anvil_core::eth::{
+    block::Block,
+    receipt::TypedReceipt,
+    transaction::{PendingTransaction, TransactionInfo},
+};
+use anvil_server::ServerConfig;
+use ethers::{
+    prelude::Middleware,
+    providers::{Http, Provider},
+    signers::Signer,
+    types::{Address, Bytes, TransactionRequest, H256},
+};
+use foundry_evm::revm::primitives::Env;
+use parking_lot::Mutex;
+use std::sync::Arc;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_can_change_mining_mode() {
@@ -51,6 +65,53 @@ async fn can_set_empty_code() {
     assert!(code.as_ref().is_empty());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_delegate_account_code() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    // returns the address of the executing account: ADDRESS PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let target = Address::random();
+    api.anvil_set_code(target, vec![0x30, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3].into())
+        .await
+        .unwrap();
+
+    let eoa = handle.dev_accounts().next().unwrap();
+    let designator = [&[0xef, 0x01, 0x00][..], target.as_bytes()].concat();
+    api.anvil_set_code(eoa, designator.clone().into()).await.unwrap();
+    assert_eq!(api.get_code(eoa, None).await.unwrap().as_ref(), designator.as_slice());
+
+    // the delegated code is executed in the context of the account
+    let tx = TransactionRequest::new().to(eoa);
+    let out = provider.call(&tx.clone().into(), None).await.unwrap();
+    assert_eq!(Address::from_slice(&out[12..]), eoa);
+
+    // calling the account charges the cold access of the delegated account
+    let delegated_gas = provider.estimate_gas(&tx.into(), None).await.unwrap();
+    let target_gas =
+        provider.estimate_gas(&TransactionRequest::new().to(target).into(), None).await.unwrap();
+    assert_eq!(delegated_gas - target_gas, 2600u64.into());
+
+    // other contracts see the designator as the code of the account:
+    // PUSH20 eoa EXTCODESIZE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let probe = Address::random();
+    let probe_code =
+        [&[0x73][..], eoa.as_bytes(), &[0x3b, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]]
+            .concat();
+    api.anvil_set_code(probe, probe_code.into()).await.unwrap();
+    let out = provider.call(&TransactionRequest::new().to(probe).into(), None).await.unwrap();
+    assert_eq!(out[31], 23);
+
+    // the delegated account can still send transactions
+    let tx = TransactionRequest::new().from(eoa).to(Address::random()).value(1);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(1u64.into()));
+
+    // the designator must be followed by an address
+    let err = api.anvil_set_code(eoa, designator[..22].to_vec().into()).await.unwrap_err();
+    assert!(err.to_string().contains("delegation designator"), "{err}");
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_can_set_genesis_timestamp() {
     let genesis_timestamp = 1000u64;
@@ -68,3 +129,110 @@ async fn test_can_use_default_genesis_timestamp() {
 
     assert_ne!(0u64, provider.get_block(0).await.unwrap().unwrap().timestamp.as_u64());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_use_in_process_provider() {
+    let (api, handle) = NodeConfig::test().spawn().await;
+    let provider = handle.in_process_provider();
+
+    let dev_accounts = handle.dev_accounts().collect::<Vec<_>>();
+    let accounts = provider.get_accounts().await.unwrap();
+    assert_eq!(dev_accounts, accounts);
+
+    api.evm_mine(None).await.unwrap();
+    let num = provider.get_block_number().await.unwrap();
+    assert_eq!(num.as_u64(), 1);
+
+    // unknown methods are rejected
+    let err = provider.request::<_, ()>("eth_doesNotExist", ()).await.unwrap_err();
+    assert!(err.to_string().contains("eth_doesNotExist"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_predeploy_multicall3() {
+    // the chain the code is copied from
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+    origin_api.anvil_set_code(Predeploy::Multicall3.address(), code.clone()).await.unwrap();
+
+    // predeploys without an endpoint to copy them from are rejected
+    let mut config = NodeConfig::test().with_predeploys(vec![Predeploy::Multicall3]);
+    assert!(config.resolve_predeploys().await.is_err());
+
+    // contracts that aren't deployed on the chain of the endpoint are rejected
+    let mut config = NodeConfig::test()
+        .with_predeploys(vec![Predeploy::Permit2])
+        .with_predeploys_rpc_url(Some(origin_handle.http_endpoint()));
+    let err = config.resolve_predeploys().await.unwrap_err();
+    assert!(err.contains("isn't deployed"), "{err}");
+
+    let mut config = NodeConfig::test()
+        .with_predeploys(vec![Predeploy::Multicall3])
+        .with_predeploys_rpc_url(Some(origin_handle.http_endpoint()));
+    config.resolve_predeploys().await.unwrap();
+    let (_api, handle) = spawn(config).await;
+    let provider = handle.http_provider();
+
+    assert_eq!(provider.get_code(Predeploy::Multicall3.address(), None).await.unwrap(), code);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_rate_limit_rpc_methods() {
+    let server_config = ServerConfig::default().with_rate_limit("eth_chainId=1".parse().unwrap());
+    let (_api, handle) = spawn(NodeConfig::test().with_server_config(server_config)).await;
+    // a provider without retries, which would retry rate limited calls
+    let provider = Provider::<Http>::try_from(handle.http_endpoint()).unwrap();
+
+    provider.get_chainid().await.unwrap();
+    let err = provider.get_chainid().await.unwrap_err();
+    assert!(err.to_string().contains("rate limit"), "{err}");
+
+    // other methods aren't limited
+    provider.get_block_number().await.unwrap();
+    provider.get_block_number().await.unwrap();
+}
+
+/// Rejects the transactions of a sender and records the executed transactions and mined blocks.
+#[derive(Debug, Default)]
+struct RecordingHook {
+    blocked: Address,
+    executed: Arc<Mutex<Vec<H256>>>,
+    blocks: Arc<Mutex<Vec<u64>>>,
+}
+
+impl NodeHook for RecordingHook {
+    fn validate_transaction(&self, tx: &PendingTransaction, _env: &Env) -> Result<(), String> {
+        if *tx.sender() == self.blocked {
+            return Err("sender is blocked".to_string())
+        }
+        Ok(())
+    }
+
+    fn on_transaction_executed(&self, info: &TransactionInfo, _receipt: &TypedReceipt) {
+        self.executed.lock().push(info.transaction_hash);
+    }
+
+    fn on_block_finalized(&self, block: &Block) {
+        self.blocks.lock().push(block.header.number.as_u64());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_use_node_hooks() {
+    let accounts = NodeConfig::test().genesis_accounts;
+    let hook = RecordingHook { blocked: accounts[1].address(), ..Default::default() };
+    let (executed, blocks) = (hook.executed.clone(), hook.blocks.clone());
+    let (_api, handle) = spawn(NodeConfig::test().with_hook(hook)).await;
+    let provider = handle.http_provider();
+
+    let to = Address::random();
+    let tx = TransactionRequest::new().from(accounts[0].address()).to(to).value(1);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(*executed.lock(), [receipt.transaction_hash]);
+    assert_eq!(*blocks.lock(), [1]);
+
+    let tx = TransactionRequest::new().from(accounts[1].address()).to(to).value(1);
+    let err = provider.send_transaction(tx, None).await.unwrap_err();
+    assert!(err.to_string().contains("transaction rejected: sender is blocked"), "{err}");
+    assert_eq!(blocks.lock().len(), 1);
+}
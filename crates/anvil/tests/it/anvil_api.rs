@@ -1,10 +1,17 @@
 //! tests for custom anvil endpoints
 use crate::{abi::*, fork::fork_config};
-use anvil::{eth::api::CLIENT_VERSION, spawn, Hardfork, NodeConfig};
+use anvil::{
+    eth::{api::CLIENT_VERSION, faults::TRANSIENT_ERROR_CODE},
+    spawn, Hardfork, NodeConfig,
+};
 use anvil_core::{
-    eth::EthRequest,
-    types::{AnvilMetadata, ForkedNetwork, Forking, NodeEnvironment, NodeForkConfig, NodeInfo},
+    eth::{block::Block, EthRequest},
+    types::{
+        AnvilMetadata, FaultInjection, ForkedNetwork, Forking, NodeEnvironment, NodeForkConfig,
+        NodeInfo,
+    },
 };
+use anvil_rpc::response::ResponseResult;
 use ethers::{
     abi::{ethereum_types::BigEndianHash, AbiDecode},
     prelude::{Middleware, SignerMiddleware},
@@ -12,7 +19,7 @@ use ethers::{
         transaction::eip2718::TypedTransaction, Address, BlockNumber, Eip1559TransactionRequest,
         TransactionRequest, H256, U256, U64,
     },
-    utils::hex,
+    utils::{hex, rlp},
 };
 use foundry_evm::revm::primitives::SpecId;
 use std::{
@@ -273,6 +280,50 @@ async fn can_mine_manually() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_export_blocks() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    for _ in 0..3 {
+        api.evm_mine(None).await.unwrap();
+    }
+
+    let exported = api.anvil_export_blocks().await.unwrap();
+    let mut blocks = Vec::new();
+    let mut buf = exported.as_ref();
+    while !buf.is_empty() {
+        let item = rlp::Rlp::new(buf);
+        let len = item.payload_info().unwrap().total();
+        blocks.push(item.as_val::<Block>().unwrap());
+        buf = &buf[len..];
+    }
+
+    // the genesis block isn't exported
+    assert_eq!(blocks.len(), 3);
+    for (idx, block) in blocks.iter().enumerate() {
+        assert_eq!(block.header.number, (idx + 1).into());
+        let expected = provider.get_block(idx as u64 + 1).await.unwrap().unwrap();
+        assert_eq!(Some(block.header.hash()), expected.hash);
+    }
+
+    // the genesis has the state of the genesis block, not the current one
+    let account = handle.dev_accounts().next().unwrap();
+    api.anvil_set_balance(account, 1u64.into()).await.unwrap();
+    let genesis = api.anvil_export_genesis().await.unwrap();
+    let genesis_block = provider.get_block(0).await.unwrap().unwrap();
+    assert_eq!(genesis["config"]["chainId"], api.chain_id());
+    assert_eq!(genesis["config"]["shanghaiTime"], 0);
+    assert_eq!(
+        genesis["timestamp"],
+        serde_json::json!(U64::from(genesis_block.timestamp.as_u64()))
+    );
+    assert_eq!(
+        genesis["alloc"][format!("{account:?}")]["balance"],
+        serde_json::json!(handle.genesis_balance())
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_set_next_timestamp() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -448,6 +499,7 @@ async fn can_get_node_info() {
             fork_url: None,
             fork_block_number: None,
             fork_retry_backoff: None,
+            cache_stats: None,
         },
     };
 
@@ -617,3 +669,99 @@ async fn test_fork_revert_call_latest_block_timestamp() {
         latest_block.author.unwrap_or_default()
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_inject_transient_errors() {
+    let faults = FaultInjection { error_rate: 1.0, ..Default::default() };
+    let (api, _handle) = spawn(NodeConfig::test().with_fault_injection(faults)).await;
+
+    match api.execute(EthRequest::EthChainId(())).await {
+        ResponseResult::Error(err) => assert_eq!(err.code.code(), TRANSIENT_ERROR_CODE),
+        res => panic!("expected an injected error, got {res:?}"),
+    }
+
+    // the faults can always be reconfigured
+    let res = api.execute(EthRequest::SetFaultInjection(FaultInjection::default())).await;
+    assert!(matches!(res, ResponseResult::Success(_)));
+    assert!(matches!(api.execute(EthRequest::EthChainId(())).await, ResponseResult::Success(_)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retry_provider_recovers_from_injected_errors() {
+    let faults = FaultInjection { latency: 10, error_rate: 0.2, ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_fault_injection(faults)).await;
+    let provider = handle.http_provider();
+
+    for _ in 0..10 {
+        api.mine_one().await;
+        let number = api.block_number().unwrap().as_u64();
+        assert_eq!(provider.get_block_number().await.unwrap().as_u64(), number);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_drop_transactions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+    let from = accounts[0];
+    let to = accounts[1];
+
+    api.anvil_set_fault_injection(FaultInjection { drop_rate: 1.0, ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(api.anvil_fault_injection().await.unwrap().drop_rate, 1.0);
+
+    let tx = TransactionRequest::new().from(from).to(to).value(1337u64);
+    let hash = provider.send_transaction(tx, None).await.unwrap().tx_hash();
+
+    // the transaction was acknowledged, but never mined
+    assert!(provider.get_transaction_receipt(hash).await.unwrap().is_none());
+    assert!(api.txpool_content().await.unwrap().pending.is_empty());
+    assert_eq!(provider.get_transaction_count(from, None).await.unwrap(), 0u64.into());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_mine_detailed() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let tx = TransactionRequest::new().from(accounts[0]).to(accounts[1]).value(1337u64);
+    let hash = provider.send_transaction(tx, None).await.unwrap().tx_hash();
+
+    let mined = api.anvil_mine_detailed(None).await.unwrap();
+    assert_eq!(mined.len(), 1);
+    let details = &mined[0];
+    assert_eq!(details.block.number, Some(1u64.into()));
+    assert_eq!(details.block.transactions.len(), 1);
+    assert_eq!(details.receipts.len(), 1);
+    assert_eq!(details.receipts[0].transaction_hash, hash);
+    assert!(!details.traces.is_empty());
+    assert!(details.traces.iter().all(|trace| trace.transaction_hash == Some(hash)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_set_code_and_initcode_size_limits() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+    // deploys a contract with two zero bytes of code
+    let deploy = || TransactionRequest::new().from(from).data(hex::decode("60026000f3").unwrap());
+
+    // the initcode size limit defaults to twice the code size limit
+    api.anvil_set_code_size_limit(Some(2)).await.unwrap();
+    assert!(provider.send_transaction(deploy(), None).await.is_err());
+
+    api.anvil_set_code_size_limit(Some(4)).await.unwrap();
+    let receipt = provider.send_transaction(deploy(), None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(1u64.into()));
+
+    api.anvil_set_initcode_size_limit(Some(4)).await.unwrap();
+    assert!(provider.send_transaction(deploy(), None).await.is_err());
+
+    api.anvil_set_initcode_size_limit(None).await.unwrap();
+    let receipt = provider.send_transaction(deploy(), None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(1u64.into()));
+}
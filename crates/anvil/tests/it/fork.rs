@@ -2,7 +2,10 @@
 
 use crate::{abi::*, utils};
 use anvil::{eth::EthApi, spawn, NodeConfig, NodeHandle};
-use anvil_core::{eth::transaction::EthTransactionRequest, types::Forking};
+use anvil_core::{
+    eth::transaction::EthTransactionRequest,
+    types::{Forking, SignedTransactionResponse},
+};
 use ethers::{
     core::rand,
     prelude::{Bytes, LocalWallet, Middleware, SignerMiddleware},
@@ -10,7 +13,7 @@ use ethers::{
     signers::Signer,
     types::{
         transaction::eip2718::TypedTransaction, Address, BlockNumber, Chain, TransactionRequest,
-        U256,
+        H256, U256,
     },
 };
 use foundry_common::{
@@ -126,6 +129,27 @@ async fn test_fork_eth_get_code_after_mine() {
         provider.get_code(address, Some(BlockNumber::Number(number.into()).into())).await.unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_caches_historical_account_queries() {
+    let (api, handle) = spawn(fork_config()).await;
+    let provider = handle.http_provider();
+    let block = Some(BlockNumber::Number((BLOCK_NUMBER - 1).into()).into());
+    let address = Address::random();
+
+    let balance = provider.get_balance(address, block).await.unwrap();
+    let nonce = provider.get_transaction_count(address, block).await.unwrap();
+    let stats = api.anvil_node_info().await.unwrap().fork_config.cache_stats.unwrap();
+    assert_eq!(stats.misses, 2);
+
+    for _ in 0..3 {
+        assert_eq!(provider.get_balance(address, block).await.unwrap(), balance);
+        assert_eq!(provider.get_transaction_count(address, block).await.unwrap(), nonce);
+    }
+    let stats = api.anvil_node_info().await.unwrap().fork_config.cache_stats.unwrap();
+    assert_eq!(stats.hits, 6);
+    assert_eq!(stats.misses, 2);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_eth_get_code() {
     let (api, handle) = spawn(fork_config()).await;
@@ -440,6 +464,30 @@ async fn can_deploy_greeter_on_fork() {
     assert_eq!("Hello World!", greeting);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_syncs_state_of_anvil_instance() {
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    let account = Address::random();
+    origin_api.anvil_set_balance(account, 1337u64.into()).await.unwrap();
+    origin_api.anvil_set_code(account, Bytes::from_static(&[0x00])).await.unwrap();
+    origin_api.anvil_set_storage_at(account, 1u64.into(), H256::from_low_u64_be(42)).await.unwrap();
+
+    let (fork_api, _fork_handle) =
+        spawn(NodeConfig::test().with_eth_rpc_url(Some(origin_handle.http_endpoint()))).await;
+
+    // changes of the origin after forking are not visible, since the state was synced up front
+    // and not fetched on demand
+    origin_api.anvil_set_balance(account, 1u64.into()).await.unwrap();
+    origin_api.anvil_set_storage_at(account, 1u64.into(), H256::from_low_u64_be(1)).await.unwrap();
+
+    assert_eq!(fork_api.balance(account, None).await.unwrap(), 1337u64.into());
+    assert_eq!(fork_api.get_code(account, None).await.unwrap(), Bytes::from_static(&[0x00]));
+    assert_eq!(
+        fork_api.storage_at(account, 1u64.into(), None).await.unwrap(),
+        H256::from_low_u64_be(42)
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_reset_properly() {
     let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
@@ -627,7 +675,8 @@ async fn test_fork_with_custom_chain_id() {
         fork_config()
             .with_fork_block_number(Some(14812197u64))
             .with_blocktime(Some(Duration::from_secs(5)))
-            .with_chain_id(3145u64.into()),
+            .with_chain_id(3145u64.into())
+            .with_allow_chain_id_mismatch(true),
     )
     .await;
 
@@ -967,7 +1016,8 @@ async fn can_override_fork_chain_id() {
     let (_api, handle) = spawn(
         fork_config()
             .with_fork_block_number(Some(16506610u64))
-            .with_chain_id(Some(chain_id_override)),
+            .with_chain_id(Some(chain_id_override))
+            .with_allow_chain_id_mismatch(true),
     )
     .await;
     let provider = handle.http_provider();
@@ -1026,3 +1076,70 @@ async fn test_fork_reset_moonbeam() {
     let tx = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
     assert_eq!(tx.status, Some(1u64.into()));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_chain_id_mismatch_requires_allow() {
+    let (_origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    let fork = NodeConfig::test()
+        .with_eth_rpc_url(Some(origin_handle.http_endpoint()))
+        .with_chain_id(Some(1u64));
+
+    let err = anvil::try_spawn(fork.clone()).await.unwrap_err();
+    assert!(err.to_string().contains("--allow-chain-id-mismatch"), "{err}");
+
+    let (api, _handle) = spawn(fork.with_allow_chain_id_mismatch(true)).await;
+    assert_eq!(api.chain_id(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_reset_checks_chain_id() {
+    let (_origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    let (_other_api, other_handle) = spawn(NodeConfig::test().with_chain_id(Some(5u64))).await;
+    let origin_chain_id = origin_handle.config().get_chain_id();
+
+    let (api, _handle) = spawn(
+        NodeConfig::test()
+            .with_eth_rpc_url(Some(origin_handle.http_endpoint()))
+            .with_chain_id(Some(origin_chain_id)),
+    )
+    .await;
+
+    let err = api
+        .anvil_reset(Some(Forking {
+            json_rpc_url: Some(other_handle.http_endpoint()),
+            block_number: None,
+        }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("--allow-chain-id-mismatch"), "{err}");
+
+    // the fork still points to the original network
+    let fork = api.get_fork().unwrap();
+    assert_eq!(fork.eth_rpc_url(), origin_handle.http_endpoint());
+    assert_eq!(fork.remote_chain_id(), origin_chain_id);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_tags_signed_transactions() {
+    let fork = LocalFork::new().await;
+    let from = fork.fork_handle.dev_accounts().next().unwrap();
+    let request = EthTransactionRequest {
+        from: Some(from),
+        to: Some(Address::random()),
+        value: Some(1337u64.into()),
+        ..Default::default()
+    };
+
+    let signed = fork.fork_api.sign_transaction(request.clone()).await.unwrap();
+    let SignedTransactionResponse::Forked(signed) = signed else {
+        panic!("expected a tagged signature, got {signed:?}")
+    };
+    let origin_chain_id = fork.origin_api.chain_id();
+    assert_eq!(signed.chain_id, origin_chain_id);
+    assert_eq!(signed.forked_chain_id, origin_chain_id);
+    assert!(signed.replayable_on_fork);
+
+    // a node that isn't forked returns the plain signature
+    let signed = fork.origin_api.sign_transaction(request).await.unwrap();
+    assert!(matches!(signed, SignedTransactionResponse::Signature(_)), "{signed:?}");
+}
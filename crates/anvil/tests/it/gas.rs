@@ -1,13 +1,18 @@
 //! Gas related tests
 
+use crate::abi::Greeter;
 use anvil::{eth::fees::INITIAL_BASE_FEE, spawn, NodeConfig};
 use ethers::{
     prelude::Middleware,
     types::{
-        transaction::eip2718::TypedTransaction, Address, BlockNumber, Eip1559TransactionRequest,
-        TransactionRequest,
+        transaction::{
+            eip2718::TypedTransaction,
+            eip2930::{AccessList, AccessListItem},
+        },
+        Address, BlockNumber, Eip1559TransactionRequest, TransactionRequest, H256,
     },
 };
+use std::{sync::Arc, time::Duration};
 
 const GAS_TRANSFER: u64 = 21_000u64;
 
@@ -110,3 +115,93 @@ async fn test_tip_above_fee_cap() {
         .to_string()
         .contains("max priority fee per gas higher than max fee per gas"));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_report_gas_details() {
+    let (_api, handle) = spawn(NodeConfig::test().with_report_gas_details(true)).await;
+    let provider = handle.http_provider();
+
+    let access_list = AccessList(vec![AccessListItem {
+        address: Address::random(),
+        storage_keys: vec![H256::zero()],
+    }]);
+    let tx = TransactionRequest::new().to(Address::random()).value(1337u64);
+    let tx = TypedTransaction::Eip2930(tx.with_access_list(access_list));
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let details = receipt.other.get("gasDetails").unwrap();
+    assert_eq!(details["intrinsic"], GAS_TRANSFER + 2_400 + 1_900);
+    assert_eq!(details["execution"], 0);
+    assert_eq!(details["refund"], 0);
+    assert_eq!(details["accessListCost"], 2_400 + 1_900);
+    assert_eq!(details["accessListSavings"], 2_500 + 2_000);
+    assert_eq!(details["gasUsed"], receipt.gas_used.unwrap().as_u64());
+
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let tx = TransactionRequest::new().to(Address::random()).value(1337u64);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert!(receipt.other.get("gasDetails").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_estimate_gas_close_to_gas_used() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = Arc::new(handle.http_provider());
+
+    let greeter = Greeter::deploy(Arc::clone(&provider), "Hello World!".to_string())
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+
+    let call =
+        greeter.set_greeting("Hello World! This greeting takes a few more storage slots".into());
+    let estimate = call.estimate_gas().await.unwrap();
+
+    // the transaction succeeds with the estimate, which is within the tolerance of the gas used
+    let receipt = call.gas(estimate).send().await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(1u64.into()));
+    let gas_used = receipt.gas_used.unwrap();
+    assert!(estimate >= gas_used);
+    assert!(estimate <= (gas_used + 2_300) * 64 / 63, "{estimate} {gas_used}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fee_history_rewards() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let gwei = 1_000_000_000u64;
+    for (nonce, tip) in [(0u64, 3 * gwei), (1, gwei)] {
+        let tx = Eip1559TransactionRequest::new()
+            .from(from)
+            .to(Address::random())
+            .nonce(nonce)
+            .gas(GAS_TRANSFER)
+            .max_fee_per_gas(100 * gwei)
+            .max_priority_fee_per_gas(tip);
+        provider.send_transaction(tx, None).await.unwrap();
+    }
+    api.mine_one().await;
+    // the fee history cache is updated in the background
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    let history = api.fee_history(1u64.into(), BlockNumber::Latest, vec![0.0, 50.0, 100.0]).await;
+    let history = history.unwrap();
+    assert_eq!(history.oldest_block, block.number.unwrap().as_u64().into());
+    assert_eq!(history.base_fee_per_gas.len(), 2);
+    assert_eq!(history.base_fee_per_gas[0], block.base_fee_per_gas.unwrap());
+    assert_eq!(
+        history.gas_used_ratio,
+        vec![block.gas_used.as_u64() as f64 / block.gas_limit.as_u64() as f64]
+    );
+    assert_eq!(history.reward, vec![vec![gwei.into(), gwei.into(), (3 * gwei).into()]]);
+
+    // the percentiles must be sorted
+    assert!(api.fee_history(1u64.into(), BlockNumber::Latest, vec![50.0, 10.0]).await.is_err());
+    assert!(api.fee_history(1u64.into(), BlockNumber::Latest, vec![101.0]).await.is_err());
+}
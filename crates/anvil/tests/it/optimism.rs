@@ -1,6 +1,7 @@
 //! Tests for OP chain support.
 
 use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::types::DepositTransactionParams;
 use ethers::{
     abi::Address,
     providers::Middleware,
@@ -139,3 +140,100 @@ async fn test_send_value_raw_deposit_transaction() {
     let balance = provider.get_balance(to_addr, None).await.unwrap();
     assert_eq!(balance, send_value);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deposit_transaction_rpc() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_optimism(true).with_hardfork(Some(Hardfork::Paris))).await;
+    let provider = handle.http_provider();
+
+    let mint = U256::exp10(18);
+    let send_value = U256::from(1234);
+    let from_addr: Address = "cf7f9e66af820a19257a2108375b180b0ec49167".parse().unwrap();
+    let to_addr: Address = "71562b71999873db5b286df957af199ec94617f7".parse().unwrap();
+
+    // the unfunded sender is funded by the minted value
+    let deposit = DepositTransactionParams {
+        from: from_addr,
+        to: Some(to_addr),
+        mint,
+        value: send_value,
+        gas: Some(U256::from(21000)),
+        ..Default::default()
+    };
+    let hash = api.anvil_deposit_transaction(deposit.clone()).await.unwrap();
+
+    // deposits from the same sender get distinct source hashes
+    let second = api.anvil_deposit_transaction(deposit).await.unwrap();
+    assert_ne!(hash, second);
+
+    // mine block
+    api.evm_mine(None).await.unwrap();
+
+    let receipt = provider.get_transaction_receipt(hash).await.unwrap().unwrap();
+    assert_eq!(receipt.from, from_addr);
+    assert_eq!(receipt.to, Some(to_addr));
+    assert_eq!(receipt.transaction_type, Some(0x7E.into()));
+
+    let balance = provider.get_balance(to_addr, None).await.unwrap();
+    assert_eq!(balance, send_value * 2);
+    let balance = provider.get_balance(from_addr, None).await.unwrap();
+    assert_eq!(balance, (mint - send_value) * 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deposit_transaction_rpc_requires_optimism() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    let deposit = DepositTransactionParams { mint: U256::exp10(18), ..Default::default() };
+    assert!(api.anvil_deposit_transaction(deposit).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_receipts_include_l1_fee_fields() {
+    let (api, handle) = spawn(
+        NodeConfig::test()
+            .with_optimism(true)
+            .with_hardfork(Some(Hardfork::Paris))
+            .with_l1_gas_price(7)
+            .with_l1_fee_scalar(0.684),
+    )
+    .await;
+    let provider = handle.http_provider();
+
+    let from = handle.dev_accounts().next().unwrap();
+    let to: Address = "71562b71999873db5b286df957af199ec94617f7".parse().unwrap();
+    let tx = TransactionRequest::new().from(from).to(to).value(1234);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let l1_gas_used = receipt.l1_gas_used.unwrap();
+    assert!(l1_gas_used > U256::zero());
+    assert_eq!(receipt.l1_gas_price, Some(U256::from(7)));
+    assert_eq!(receipt.l1_fee_scalar, Some(0.684));
+    assert_eq!(receipt.l1_fee, Some(l1_gas_used * 7 * 684_000 / 1_000_000));
+    assert!(receipt.effective_gas_price.is_some());
+
+    // deposits are not posted to L1
+    let deposit = DepositTransactionParams { mint: U256::exp10(18), ..Default::default() };
+    let hash = api.anvil_deposit_transaction(deposit).await.unwrap();
+    api.evm_mine(None).await.unwrap();
+    let receipt = provider.get_transaction_receipt(hash).await.unwrap().unwrap();
+    assert_eq!(receipt.l1_fee, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_l1_fee_fields_can_be_disabled() {
+    let (_api, handle) = spawn(
+        NodeConfig::test()
+            .with_optimism(true)
+            .with_hardfork(Some(Hardfork::Paris))
+            .with_l2_receipt_fields(Some(false)),
+    )
+    .await;
+    let provider = handle.http_provider();
+
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1234);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.l1_gas_used, None);
+    assert_eq!(receipt.l1_fee, None);
+}
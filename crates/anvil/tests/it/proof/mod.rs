@@ -8,7 +8,8 @@ use anvil_core::eth::{
 };
 use ethers::{
     abi::ethereum_types::BigEndianHash,
-    types::{Address, H256, U256},
+    providers::Middleware,
+    types::{Address, BlockNumber, TransactionRequest, H256, U256},
     utils::{keccak256, rlp},
 };
 use foundry_common::types::ToEthers;
@@ -79,3 +80,41 @@ async fn can_get_random_account_proofs() {
             .unwrap_or_else(|_| panic!("Failed to get proof for {acc:?}"));
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_verify_proof_against_block_state_root() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+    let tx = TransactionRequest::new().to(to).value(1337u64).from(from);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_ne!(block.state_root, H256::zero());
+    assert_eq!(block.state_root, api.state_root().await.unwrap());
+
+    let proof: AccountProof = api.get_proof(from, Vec::new(), None).await.unwrap();
+    assert_eq!(proof.nonce, 1u64.into());
+    let account = BasicAccount {
+        nonce: proof.nonce.as_u64().into(),
+        balance: proof.balance,
+        storage_root: proof.storage_hash,
+        code_hash: proof.code_hash,
+    };
+    let acc_proof: Vec<Vec<u8>> = proof
+        .account_proof
+        .into_iter()
+        .map(|node| rlp::decode::<Vec<u8>>(&node).unwrap())
+        .collect();
+
+    verify_proof::<ExtensionLayout>(
+        &block.state_root.0,
+        &acc_proof,
+        &keccak256(from.as_bytes())[..],
+        Some(rlp::encode(&account).as_ref()),
+    )
+    .unwrap();
+}
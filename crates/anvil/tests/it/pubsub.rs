@@ -1,6 +1,7 @@
 //! tests for subscriptions
 
 use anvil::{spawn, NodeConfig};
+use anvil_core::eth::state::BlockStateDiff;
 use ethers::{
     contract::abigen,
     middleware::SignerMiddleware,
@@ -249,6 +250,51 @@ async fn test_subscriptions() {
     assert_eq!(blocks, vec![1, 2, 3])
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_state_diffs() {
+    abigen!(EmitLogs, "test-data/emit_logs.json");
+
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ws_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let sender = wallet.address();
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let contract = EmitLogs::deploy(Arc::clone(&client), "First Message".to_string())
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+
+    let ws = Ws::connect(handle.ws_endpoint()).await.unwrap();
+    let sub_id: U256 = ws.request("anvil_subscribe", ["stateDiffs"]).await.unwrap();
+    let mut stream = ws.subscribe(sub_id).unwrap();
+
+    let receipt = contract
+        .set_value("Next Message".to_string())
+        .send()
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let item = stream.next().await.unwrap();
+    let diff: BlockStateDiff = serde_json::from_str(item.get()).unwrap();
+    assert_eq!(Some(diff.block_hash), receipt.block_hash);
+    assert_eq!(Some(diff.block_number), receipt.block_number);
+
+    let sender_diff = &diff.accounts[&sender];
+    assert_eq!(sender_diff.balance, client.get_balance(sender, None).await.unwrap());
+    assert_eq!(sender_diff.nonce, 2u64.into());
+
+    let contract_diff = &diff.accounts[&contract.address()];
+    assert!(contract_diff.code.is_none());
+    assert!(!contract_diff.storage.is_empty());
+    assert!(!contract_diff.destroyed);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_sub_new_heads_fast() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -1,7 +1,7 @@
 use anvil::{spawn, NodeConfig};
 use ethers::{
     prelude::{Middleware, SignerMiddleware},
-    signers::Signer,
+    signers::{LocalWallet, Signer},
     types::{transaction::eip712::TypedData, Address, Chain, TransactionRequest},
 };
 
@@ -280,6 +280,34 @@ async fn can_sign_typed_data_os() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_sign_with_keystore_accounts() {
+    let keystore_wallet = LocalWallet::new(&mut rand::thread_rng());
+    let (api, handle) =
+        spawn(NodeConfig::test().with_keystore_accounts(vec![keystore_wallet.clone()])).await;
+    let provider = handle.http_provider();
+    let address = keystore_wallet.address();
+
+    // the keystore account is funded and managed by the node
+    assert!(api.accounts().unwrap().contains(&address));
+    assert!(provider.get_balance(address, None).await.unwrap() > 0u64.into());
+    assert!(api.personal_unlock_account(address).unwrap());
+
+    let message = b"hello anvil";
+    let signature = api.personal_sign(message, address).await.unwrap();
+    assert_eq!(signature, api.sign(address, message).await.unwrap());
+    let expected = keystore_wallet.sign_message(message).await.unwrap();
+    assert_eq!(signature, format!("0x{expected}"));
+
+    let recovered =
+        api.personal_ec_recover(message.to_vec().into(), expected.to_vec().into()).unwrap();
+    assert_eq!(recovered, address);
+
+    let tx = TransactionRequest::new().from(address).to(Address::random()).value(100u64);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.from, address);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn rejects_different_chain_id() {
     let (_api, handle) = spawn(NodeConfig::test()).await;
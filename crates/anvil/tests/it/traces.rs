@@ -1,5 +1,6 @@
 use crate::fork::fork_config;
 use anvil::{spawn, NodeConfig};
+use anvil_core::types::TraceFilter;
 use ethers::{
     contract::ContractInstance,
     prelude::{
@@ -46,6 +47,59 @@ async fn test_get_transfer_parity_traces() {
     assert_eq!(traces, block_traces);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parity_revert_trace_and_filter() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    // PUSH1 0 PUSH1 0 REVERT
+    let reverter = Address::random();
+    api.anvil_set_code(reverter, hex::decode("60006000fd").unwrap().into()).await.unwrap();
+
+    let tx = TransactionRequest::new().to(to).value(1u64).from(from);
+    let transfer = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let tx = TransactionRequest::new().to(reverter).from(from).gas(100_000u64);
+    let revert = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let traces = provider.trace_transaction(revert.transaction_hash).await.unwrap();
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].error.as_deref(), Some("Reverted"));
+    assert!(traces[0].result.is_none());
+
+    let trace = api.trace_get(revert.transaction_hash, vec![0usize.into()]).await.unwrap();
+    assert_eq!(trace, Some(traces[0].clone()));
+
+    // all traces of the node
+    let all = api.trace_filter(Default::default()).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].transaction_hash, Some(transfer.transaction_hash));
+
+    let filter = TraceFilter { to_address: vec![reverter], ..Default::default() };
+    assert_eq!(api.trace_filter(filter).await.unwrap(), traces);
+
+    let filter = TraceFilter { after: Some(1), count: Some(1), ..Default::default() };
+    assert_eq!(api.trace_filter(filter).await.unwrap(), traces);
+
+    let filter = TraceFilter {
+        from_block: Some(revert.block_number.unwrap().as_u64().into()),
+        from_address: vec![to],
+        ..Default::default()
+    };
+    assert!(api.trace_filter(filter).await.unwrap().is_empty());
+
+    // the range ends at the best block
+    let filter = TraceFilter { to_block: Some(0xffffffffffu64.into()), ..Default::default() };
+    assert_eq!(api.trace_filter(filter).await.unwrap(), all);
+
+    // and is capped
+    api.anvil_mine(Some(10_000u64.into()), None).await.unwrap();
+    assert!(api.trace_filter(Default::default()).await.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_parity_suicide_trace() {
     let prj = TempProject::dapptools().unwrap();
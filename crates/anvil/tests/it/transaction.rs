@@ -1,18 +1,34 @@
 use crate::abi::*;
 use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::{
+    eth::transaction::{
+        encode_blob_transaction_with_sidecar, BlobTransactionSidecar, EIP4844Transaction,
+        EIP4844TransactionRequest, TransactionKind, BYTES_PER_BLOB, DATA_GAS_PER_BLOB,
+        MAX_BLOBS_PER_BLOCK,
+    },
+    types::{Bundle, KnownAccount, TransactionConditional},
+};
 use ethers::{
     abi::ethereum_types::BigEndianHash,
     prelude::{
-        signer::SignerMiddlewareError, BlockId, Middleware, Signer, SignerMiddleware,
+        signer::SignerMiddlewareError, BlockId, LocalWallet, Middleware, Signer, SignerMiddleware,
         TransactionRequest,
     },
     types::{
-        transaction::eip2930::{AccessList, AccessListItem},
+        transaction::{
+            eip2718::TypedTransaction,
+            eip2930::{AccessList, AccessListItem},
+        },
         Address, BlockNumber, Transaction, TransactionReceipt, H256, U256,
     },
+    utils::hex,
 };
 use futures::{future::join_all, FutureExt, StreamExt};
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::time::timeout;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -1016,3 +1032,374 @@ async fn test_reject_eip1559_pre_london() {
     let greeting = greeter_contract.greet().call().await.unwrap();
     assert_eq!("Hello World!", greeting);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_calldata_gas_too_low() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let account = handle.dev_accounts().next().unwrap();
+
+    // 21_000 does not cover the calldata: 4 gas per zero byte, 16 gas per non-zero byte
+    let tx = TransactionRequest::new()
+        .to(Address::random())
+        .from(account)
+        .data(vec![0x00, 0x01])
+        .gas(21_000u64);
+    let err = provider.send_transaction(tx.clone(), None).await.unwrap_err().to_string();
+    assert!(err.contains("intrinsic gas too low"), "{err}");
+
+    provider.send_transaction(tx.gas(21_020u64), None).await.unwrap().await.unwrap().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_sender_with_code() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    api.anvil_set_code(wallet.address(), vec![0x00].into()).await.unwrap();
+
+    let client = SignerMiddleware::new(provider, wallet);
+    let tx = TransactionRequest::new().to(Address::random()).value(1u64);
+    let err = client.send_transaction(tx, None).await.unwrap_err().to_string();
+    assert!(err.contains("sender not an eoa"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_disable_strict_validation() {
+    let (api, handle) = spawn(NodeConfig::test().disable_strict_validation(true)).await;
+    let provider = handle.http_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    api.anvil_set_code(wallet.address(), vec![0x00].into()).await.unwrap();
+
+    let client = SignerMiddleware::new(provider, wallet);
+    let tx = TransactionRequest::new().to(Address::random()).value(1u64);
+    let receipt = client.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(1u64.into()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_raw_transaction_conditional() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap().with_chain_id(api.chain_id());
+    let to = Address::random();
+    let mut tx: TypedTransaction =
+        TransactionRequest::new().from(wallet.address()).to(to).value(1337u64).into();
+    provider.fill_transaction(&mut tx, None).await.unwrap();
+    let signature = wallet.sign_transaction(&tx).await.unwrap();
+    let raw = tx.rlp_signed(&signature);
+
+    // the preconditions are checked against the pending block
+    let rejected = [
+        TransactionConditional { block_number_min: Some(2u64.into()), ..Default::default() },
+        TransactionConditional { block_number_max: Some(0u64.into()), ..Default::default() },
+        TransactionConditional { timestamp_max: Some(1u64.into()), ..Default::default() },
+        TransactionConditional {
+            known_accounts: BTreeMap::from([(
+                to,
+                KnownAccount::Slots(BTreeMap::from([(H256::zero(), H256::from_low_u64_be(1))])),
+            )]),
+            ..Default::default()
+        },
+        TransactionConditional {
+            known_accounts: BTreeMap::from([(to, KnownAccount::StorageRoot(H256::zero()))]),
+            ..Default::default()
+        },
+    ];
+    for conditional in rejected {
+        let err = api.send_raw_transaction_conditional(raw.clone(), conditional).await.unwrap_err();
+        assert!(err.to_string().contains("Transaction conditional not met"), "{err}");
+    }
+
+    // the storage root of an account without storage is the root of the empty trie
+    let empty_root = H256::from_slice(
+        &hex::decode("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421").unwrap(),
+    );
+    let conditional = TransactionConditional {
+        known_accounts: BTreeMap::from([
+            (to, KnownAccount::Slots(BTreeMap::from([(H256::zero(), H256::zero())]))),
+            (wallet.address(), KnownAccount::StorageRoot(empty_root)),
+        ]),
+        block_number_min: Some(1u64.into()),
+        block_number_max: Some(1u64.into()),
+        ..Default::default()
+    };
+    let hash = api.send_raw_transaction_conditional(raw, conditional).await.unwrap();
+    let receipt = provider.get_transaction_receipt(hash).await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(1u64.into()));
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), 1337u64.into());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_bundle() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let wallets: Vec<_> =
+        handle.dev_wallets().map(|wallet| wallet.with_chain_id(api.chain_id())).collect();
+    let to = Address::random();
+    let sign = |wallet: &LocalWallet, nonce: u64| {
+        let wallet = wallet.clone();
+        let provider = provider.clone();
+        async move {
+            let mut tx: TypedTransaction =
+                TransactionRequest::new().from(wallet.address()).to(to).value(1u64).into();
+            provider.fill_transaction(&mut tx, None).await.unwrap();
+            tx.set_nonce(nonce);
+            let signature = wallet.sign_transaction(&tx).await.unwrap();
+            tx.rlp_signed(&signature)
+        }
+    };
+
+    // a transaction in the pool is included after the bundle
+    let pending = provider.send_transaction(
+        TransactionRequest::new().from(wallets[1].address()).to(to).value(1u64),
+        None,
+    );
+    let pending_hash = pending.await.unwrap().tx_hash();
+
+    let bundle = Bundle {
+        txs: vec![sign(&wallets[0], 0).await, sign(&wallets[0], 1).await],
+        block_number: 1u64.into(),
+        ..Default::default()
+    };
+    api.send_bundle(bundle).await.unwrap();
+
+    // the bundle is dropped as a whole, since the nonce of its second transaction is too high
+    let dropped = Bundle {
+        txs: vec![sign(&wallets[2], 0).await, sign(&wallets[2], 5).await],
+        block_number: 1u64.into(),
+        ..Default::default()
+    };
+    api.send_bundle(dropped).await.unwrap();
+
+    api.evm_mine(None).await.unwrap();
+
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 3);
+    assert_eq!(block.transactions[2], pending_hash);
+    for hash in &block.transactions[..2] {
+        let tx = provider.get_transaction(*hash).await.unwrap().unwrap();
+        assert_eq!(tx.from, wallets[0].address());
+    }
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), 3u64.into());
+    assert_eq!(
+        provider.get_transaction_count(wallets[2].address(), None).await.unwrap(),
+        0u64.into()
+    );
+
+    // bundles for mined blocks are rejected
+    let bundle = Bundle {
+        txs: vec![sign(&wallets[0], 2).await],
+        block_number: 1u64.into(),
+        ..Default::default()
+    };
+    let err = api.send_bundle(bundle).await.unwrap_err();
+    assert!(err.to_string().contains("Invalid bundle"), "{err}");
+}
+
+/// Signs the blob transaction with the given wallet
+fn sign_blob_transaction(
+    wallet: &LocalWallet,
+    request: EIP4844TransactionRequest,
+) -> EIP4844Transaction {
+    let signature = wallet.sign_hash(request.hash()).unwrap();
+    EIP4844Transaction {
+        chain_id: request.chain_id,
+        nonce: request.nonce,
+        max_priority_fee_per_gas: request.max_priority_fee_per_gas,
+        max_fee_per_gas: request.max_fee_per_gas,
+        gas_limit: request.gas_limit,
+        kind: request.kind,
+        value: request.value,
+        input: request.input,
+        access_list: AccessList(request.access_list),
+        max_fee_per_blob_gas: request.max_fee_per_blob_gas,
+        blob_versioned_hashes: request.blob_versioned_hashes,
+        odd_y_parity: signature.v == 28,
+        r: H256::from_uint(&signature.r),
+        s: H256::from_uint(&signature.s),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_blob_transaction_and_get_sidecars() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Cancun))).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+
+    let sidecar = BlobTransactionSidecar::from_blobs(vec![
+        vec![1u8; BYTES_PER_BLOB].into(),
+        vec![2u8; BYTES_PER_BLOB].into(),
+    ])
+    .unwrap();
+    let request = EIP4844TransactionRequest {
+        chain_id: api.chain_id(),
+        nonce: U256::zero(),
+        max_priority_fee_per_gas: U256::exp10(9),
+        max_fee_per_gas: api.gas_price().unwrap() * 2,
+        gas_limit: U256::from(21_000u64),
+        kind: TransactionKind::Call(Address::random()),
+        value: U256::from(1337u64),
+        input: Default::default(),
+        access_list: vec![],
+        max_fee_per_blob_gas: U256::exp10(9),
+        blob_versioned_hashes: sidecar.versioned_hashes(),
+    };
+    let tx = sign_blob_transaction(&wallet, request.clone());
+
+    // the blobs must match the versioned hashes the transaction commits to
+    let mut mismatched = sidecar.clone();
+    mismatched.commitments.swap(0, 1);
+    let raw = encode_blob_transaction_with_sidecar(&tx, &mismatched);
+    let err = api.send_raw_transaction(raw.into()).await.unwrap_err();
+    assert!(err.to_string().contains("Invalid blob sidecar"), "{err}");
+
+    // the proofs must match the blobs and their commitments
+    let mut tampered = sidecar.clone();
+    tampered.proofs.swap(0, 1);
+    let raw = encode_blob_transaction_with_sidecar(&tx, &tampered);
+    let err = api.send_raw_transaction(raw.into()).await.unwrap_err();
+    assert!(err.to_string().contains("Invalid blob sidecar"), "{err}");
+
+    let raw = encode_blob_transaction_with_sidecar(&tx, &sidecar);
+    let hash = api.send_raw_transaction(raw.into()).await.unwrap();
+    let receipt = api.transaction_receipt(hash).await.unwrap().unwrap();
+    assert_eq!(receipt.transaction_type, Some(3u64.into()));
+
+    let block = api.block_by_number(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(
+        block.other.get_deserialized::<U256>("blobGasUsed").unwrap().unwrap(),
+        U256::from(2 * DATA_GAS_PER_BLOB)
+    );
+
+    let sidecars = api.blob_sidecars(BlockId::Number(BlockNumber::Latest)).await.unwrap().unwrap();
+    assert_eq!(sidecars.len(), 2);
+    for (index, blob) in sidecars.iter().enumerate() {
+        assert_eq!(blob.tx_hash, hash);
+        assert_eq!(blob.block_hash, receipt.block_hash.unwrap());
+        assert_eq!(blob.blob_index, index.into());
+        assert_eq!(blob.blob, sidecar.blobs[index]);
+        assert_eq!(blob.versioned_hash, request.blob_versioned_hashes[index]);
+    }
+    assert_eq!(api.anvil_get_blobs_by_transaction_hash(hash).await.unwrap(), Some(sidecars));
+
+    // blocks without blob transactions have no sidecars
+    let sidecars = api.blob_sidecars(BlockId::Number(BlockNumber::Earliest)).await.unwrap();
+    assert_eq!(sidecars, Some(vec![]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_blob_transaction_pre_cancun() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Shanghai))).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+
+    let sidecar = BlobTransactionSidecar {
+        blobs: vec![vec![1u8; BYTES_PER_BLOB].into()],
+        commitments: vec![vec![2u8; 48].into()],
+        proofs: vec![vec![3u8; 48].into()],
+    };
+    let request = EIP4844TransactionRequest {
+        chain_id: api.chain_id(),
+        nonce: U256::zero(),
+        max_priority_fee_per_gas: U256::exp10(9),
+        max_fee_per_gas: api.gas_price().unwrap() * 2,
+        gas_limit: U256::from(21_000u64),
+        kind: TransactionKind::Call(Address::random()),
+        value: U256::zero(),
+        input: Default::default(),
+        access_list: vec![],
+        max_fee_per_blob_gas: U256::exp10(9),
+        blob_versioned_hashes: sidecar.versioned_hashes(),
+    };
+    let tx = sign_blob_transaction(&wallet, request);
+    let raw = encode_blob_transaction_with_sidecar(&tx, &sidecar);
+    let err = api.send_raw_transaction(raw.into()).await.unwrap_err();
+    assert!(err.to_string().contains("not supported by the current hardfork"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn caps_blobs_per_block() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Cancun))).await;
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let wallet = handle.dev_wallets().next().unwrap();
+
+    let sidecar = BlobTransactionSidecar::from_blobs(
+        (0..MAX_BLOBS_PER_BLOCK).map(|i| vec![i as u8; BYTES_PER_BLOB].into()).collect(),
+    )
+    .unwrap();
+    let mut hashes = Vec::new();
+    for nonce in 0..2u64 {
+        let request = EIP4844TransactionRequest {
+            chain_id: api.chain_id(),
+            nonce: nonce.into(),
+            max_priority_fee_per_gas: U256::exp10(9),
+            max_fee_per_gas: api.gas_price().unwrap() * 2,
+            gas_limit: U256::from(21_000u64),
+            kind: TransactionKind::Call(Address::random()),
+            value: U256::zero(),
+            input: Default::default(),
+            access_list: vec![],
+            max_fee_per_blob_gas: U256::exp10(9),
+            blob_versioned_hashes: sidecar.versioned_hashes(),
+        };
+        let tx = sign_blob_transaction(&wallet, request);
+        let raw = encode_blob_transaction_with_sidecar(&tx, &sidecar);
+        hashes.push(api.send_raw_transaction(raw.into()).await.unwrap());
+    }
+
+    // only one of the transactions fits into a block
+    let max_blob_gas = U256::from(DATA_GAS_PER_BLOB * MAX_BLOBS_PER_BLOCK as u64);
+    for hash in hashes {
+        api.evm_mine(None).await.unwrap();
+        let block = api.block_by_number(BlockNumber::Latest).await.unwrap().unwrap();
+        assert_eq!(block.transactions, vec![hash]);
+        assert_eq!(
+            block.other.get_deserialized::<U256>("blobGasUsed").unwrap().unwrap(),
+            max_blob_gas
+        );
+    }
+
+    // the blobs above the target of the previous block are carried over as excess blob gas
+    let block = api.block_by_number(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(
+        block.other.get_deserialized::<U256>("excessBlobGas").unwrap().unwrap(),
+        max_blob_gas / 2
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn drops_transaction_conditional_not_met_when_mined() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let wallet = handle.dev_wallets().next().unwrap().with_chain_id(api.chain_id());
+    let to = Address::random();
+    let mut tx: TypedTransaction =
+        TransactionRequest::new().from(wallet.address()).to(to).value(1337u64).into();
+    provider.fill_transaction(&mut tx, None).await.unwrap();
+    let signature = wallet.sign_transaction(&tx).await.unwrap();
+    let raw = tx.rlp_signed(&signature);
+
+    let conditional = TransactionConditional {
+        known_accounts: BTreeMap::from([(
+            to,
+            KnownAccount::Slots(BTreeMap::from([(H256::zero(), H256::zero())])),
+        )]),
+        ..Default::default()
+    };
+    let hash = api.send_raw_transaction_conditional(raw, conditional).await.unwrap();
+
+    // the slot changes before the transaction is mined
+    api.anvil_set_storage_at(to, U256::zero(), H256::from_low_u64_be(1)).await.unwrap();
+    api.evm_mine(None).await.unwrap();
+
+    assert!(provider.get_transaction_receipt(hash).await.unwrap().is_none());
+    assert_eq!(api.txpool_status().await.unwrap().pending, 0u64.into());
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), U256::zero());
+}
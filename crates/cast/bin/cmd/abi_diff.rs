@@ -0,0 +1,364 @@
+use alloy_json_abi::{ContractObject, Event, Function, JsonAbi, StateMutability};
+use clap::Parser;
+use eyre::{Context, Result};
+use foundry_block_explorers::Client;
+use foundry_cli::opts::EtherscanOpts;
+use foundry_common::fs;
+use foundry_config::Config;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt, path::Path};
+use yansi::Paint;
+
+/// CLI arguments for `cast abi-diff`.
+#[derive(Debug, Clone, Parser)]
+pub struct AbiDiffArgs {
+    /// The old ABI: the path to an ABI or artifact file, or a contract address.
+    ///
+    /// If an address is specified, then the ABI is fetched from Etherscan.
+    old: String,
+
+    /// The new ABI: the path to an ABI or artifact file, or a contract address.
+    new: String,
+
+    /// Print the changes as JSON.
+    #[clap(long, short)]
+    json: bool,
+
+    #[clap(flatten)]
+    etherscan: EtherscanOpts,
+}
+
+impl AbiDiffArgs {
+    pub async fn run(self) -> Result<()> {
+        let AbiDiffArgs { old, new, json, etherscan } = self;
+        let old = load_abi(&old, &etherscan).await?;
+        let new = load_abi(&new, &etherscan).await?;
+
+        let changes = diff_abis(&old, &new);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&changes)?);
+        } else if changes.is_empty() {
+            println!("The ABIs are identical.");
+        } else {
+            for change in &changes {
+                println!("{change}");
+            }
+        }
+
+        let breaking = changes.iter().filter(|change| change.breaking).count();
+        if breaking > 0 {
+            eyre::bail!("found {breaking} breaking change(s)");
+        }
+        Ok(())
+    }
+}
+
+/// Loads an ABI from a file, which is either an ABI or an artifact containing one, or from
+/// Etherscan.
+pub(super) async fn load_abi(source: &str, etherscan: &EtherscanOpts) -> Result<JsonAbi> {
+    if Path::new(source).is_file() {
+        let content = fs::read_to_string(source)?;
+        if let Ok(abi) = serde_json::from_str::<JsonAbi>(&content) {
+            return Ok(abi)
+        }
+        let object: ContractObject = serde_json::from_str(&content)
+            .wrap_err_with(|| format!("{source} is neither an ABI nor an artifact"))?;
+        return object.abi.ok_or_else(|| eyre::eyre!("could not find ABI in file {source}"))
+    }
+
+    let address = source.parse().wrap_err_with(|| format!("invalid path or address: {source}"))?;
+    let config = Config::from(etherscan);
+    let chain = config.chain.unwrap_or_default();
+    let api_key = config.get_etherscan_api_key(Some(chain)).unwrap_or_default();
+    let client = Client::new(chain, api_key)?;
+    let source = client.contract_source_code(address).await?;
+    source.abis()?.into_iter().next().ok_or_else(|| eyre::eyre!("no ABI found for {address}"))
+}
+
+/// What changed about an ABI item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+    SelectorCollision,
+}
+
+/// A change of a function or event between two ABIs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AbiChange {
+    kind: ChangeKind,
+    /// `function` or `event`.
+    item: &'static str,
+    signature: String,
+    /// Whether existing callers or log consumers break.
+    breaking: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+}
+
+impl AbiChange {
+    fn new(kind: ChangeKind, item: &'static str, signature: String, breaking: bool) -> Self {
+        Self { kind, item, signature, breaking, details: None }
+    }
+
+    fn with_details(mut self, details: String) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl fmt::Display for AbiChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self.kind {
+            ChangeKind::Added => "+",
+            ChangeKind::Removed => "-",
+            ChangeKind::Changed => "~",
+            ChangeKind::SelectorCollision => "!",
+        };
+        write!(f, "{symbol} {} {}", self.item, self.signature)?;
+        if let Some(details) = &self.details {
+            write!(f, ": {details}")?;
+        }
+        if self.breaking {
+            write!(f, " {}", Paint::red("(breaking)"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Diffs the functions and events of two ABIs.
+///
+/// Removing or changing the signature of an item breaks existing callers, so do changes of the
+/// return types, of the indexed event parameters and state mutability changes that make calls
+/// revert. An added function whose selector was used by a removed function is a selector
+/// collision, since callers of the old function silently call the new one.
+fn diff_abis(old: &JsonAbi, new: &JsonAbi) -> Vec<AbiChange> {
+    let mut changes = Vec::new();
+
+    let old_functions = functions(old);
+    let new_functions = functions(new);
+    for (signature, function) in &old_functions {
+        match new_functions.get(signature) {
+            Some(new_function) => changes.extend(diff_functions(function, new_function)),
+            None => {
+                let mut change =
+                    AbiChange::new(ChangeKind::Removed, "function", signature.clone(), true);
+                let overloads = new_functions
+                    .iter()
+                    .filter(|(new_signature, new_function)| {
+                        new_function.name == function.name &&
+                            !old_functions.contains_key(*new_signature)
+                    })
+                    .map(|(new_signature, _)| format!("`{new_signature}`"))
+                    .collect::<Vec<_>>();
+                if !overloads.is_empty() {
+                    change = change
+                        .with_details(format!("signature changed to {}", overloads.join(", ")));
+                }
+                changes.push(change);
+            }
+        }
+    }
+    for (signature, function) in &new_functions {
+        if old_functions.contains_key(signature) {
+            continue
+        }
+        let collision = old_functions.values().chain(new_functions.values()).find(|other| {
+            other.signature() != *signature && other.selector() == function.selector()
+        });
+        match collision {
+            Some(other) => changes.push(
+                AbiChange::new(ChangeKind::SelectorCollision, "function", signature.clone(), true)
+                    .with_details(format!(
+                        "selector {} is also used by `{}`",
+                        function.selector(),
+                        other.signature()
+                    )),
+            ),
+            None => changes.push(AbiChange::new(
+                ChangeKind::Added,
+                "function",
+                signature.clone(),
+                false,
+            )),
+        }
+    }
+
+    let old_events = events(old);
+    let new_events = events(new);
+    for (signature, event) in &old_events {
+        match new_events.get(signature) {
+            Some(new_event) => changes.extend(diff_events(event, new_event)),
+            None => {
+                changes.push(AbiChange::new(ChangeKind::Removed, "event", signature.clone(), true))
+            }
+        }
+    }
+    for signature in new_events.keys() {
+        if !old_events.contains_key(signature) {
+            changes.push(AbiChange::new(ChangeKind::Added, "event", signature.clone(), false));
+        }
+    }
+
+    changes
+}
+
+/// Returns the functions of the ABI by signature.
+fn functions(abi: &JsonAbi) -> BTreeMap<String, &Function> {
+    abi.functions().map(|function| (function.signature(), function)).collect()
+}
+
+/// Returns the events of the ABI by signature.
+fn events(abi: &JsonAbi) -> BTreeMap<String, &Event> {
+    abi.events().map(|event| (event.signature(), event)).collect()
+}
+
+/// Diffs two functions with the same signature.
+fn diff_functions(old: &Function, new: &Function) -> Vec<AbiChange> {
+    let mut changes = Vec::new();
+    let changed = |details: String, breaking: bool| {
+        AbiChange::new(ChangeKind::Changed, "function", old.signature(), breaking)
+            .with_details(details)
+    };
+
+    let outputs = |function: &Function| {
+        function.outputs.iter().map(|param| param.selector_type().into_owned()).collect::<Vec<_>>()
+    };
+    let (old_outputs, new_outputs) = (outputs(old), outputs(new));
+    if old_outputs != new_outputs {
+        let details = format!("returns ({}) -> ({})", old_outputs.join(","), new_outputs.join(","));
+        changes.push(changed(details, true));
+    }
+
+    if old.state_mutability != new.state_mutability {
+        let details =
+            format!("{} -> {}", mutability(old.state_mutability), mutability(new.state_mutability));
+        let breaking = is_breaking_mutability(old.state_mutability, new.state_mutability);
+        changes.push(changed(details, breaking));
+    }
+
+    changes
+}
+
+/// Diffs two events with the same signature.
+fn diff_events(old: &Event, new: &Event) -> Vec<AbiChange> {
+    let mut changes = Vec::new();
+    let indexed = |event: &Event| {
+        event
+            .inputs
+            .iter()
+            .map(|param| if param.indexed { "indexed" } else { "-" })
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let (old_indexed, new_indexed) = (indexed(old), indexed(new));
+    if old_indexed != new_indexed {
+        changes.push(
+            AbiChange::new(ChangeKind::Changed, "event", old.signature(), true)
+                .with_details(format!("indexed parameters ({old_indexed}) -> ({new_indexed})")),
+        );
+    }
+    if old.anonymous != new.anonymous {
+        let details = if new.anonymous { "became anonymous" } else { "is no longer anonymous" };
+        changes.push(
+            AbiChange::new(ChangeKind::Changed, "event", old.signature(), true)
+                .with_details(details.to_string()),
+        );
+    }
+    changes
+}
+
+fn mutability(mutability: StateMutability) -> &'static str {
+    match mutability {
+        StateMutability::Pure => "pure",
+        StateMutability::View => "view",
+        StateMutability::NonPayable => "nonpayable",
+        StateMutability::Payable => "payable",
+    }
+}
+
+/// Returns whether calls that worked with the old state mutability may revert with the new one:
+/// calls sending value to a function that's no longer payable, and static calls to a function
+/// that may now modify state.
+fn is_breaking_mutability(old: StateMutability, new: StateMutability) -> bool {
+    match (old, new) {
+        (StateMutability::Payable, StateMutability::Payable) => false,
+        (StateMutability::Payable, _) => true,
+        (
+            StateMutability::Pure | StateMutability::View,
+            StateMutability::NonPayable | StateMutability::Payable,
+        ) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi(json: &str) -> JsonAbi {
+        serde_json::from_str(json).unwrap()
+    }
+
+    const OLD: &str = r#"[
+        {"type":"function","name":"balanceOf","inputs":[{"name":"owner","type":"address"}],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+        {"type":"function","name":"mint","inputs":[{"name":"to","type":"address"}],"outputs":[],"stateMutability":"payable"},
+        {"type":"function","name":"burn","inputs":[{"name":"amount","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"},
+        {"type":"event","name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true}],"anonymous":false}
+    ]"#;
+
+    #[test]
+    fn identical_abis_have_no_changes() {
+        assert!(diff_abis(&abi(OLD), &abi(OLD)).is_empty());
+    }
+
+    #[test]
+    fn flags_breaking_changes() {
+        let new = abi(r#"[
+            {"type":"function","name":"balanceOf","inputs":[{"name":"owner","type":"address"}],"outputs":[{"name":"","type":"uint128"}],"stateMutability":"nonpayable"},
+            {"type":"function","name":"mint","inputs":[{"name":"to","type":"address"}],"outputs":[],"stateMutability":"nonpayable"},
+            {"type":"function","name":"burn","inputs":[{"name":"amount","type":"uint256"},{"name":"from","type":"address"}],"outputs":[],"stateMutability":"nonpayable"},
+            {"type":"function","name":"pause","inputs":[],"outputs":[],"stateMutability":"nonpayable"},
+            {"type":"event","name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":false}],"anonymous":false}
+        ]"#);
+        let changes = diff_abis(&abi(OLD), &new);
+        let summary = changes
+            .iter()
+            .map(|change| (change.kind, change.signature.as_str(), change.breaking))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (ChangeKind::Changed, "balanceOf(address)", true),
+                (ChangeKind::Changed, "balanceOf(address)", true),
+                (ChangeKind::Removed, "burn(uint256)", true),
+                (ChangeKind::Changed, "mint(address)", true),
+                (ChangeKind::Added, "burn(uint256,address)", false),
+                (ChangeKind::Added, "pause()", false),
+                (ChangeKind::Changed, "Transfer(address,address)", true),
+            ]
+        );
+        assert_eq!(
+            changes[2].details.as_deref(),
+            Some("signature changed to `burn(uint256,address)`")
+        );
+    }
+
+    #[test]
+    fn flags_selector_collisions() {
+        // `transfer(address,uint256)` and `many_msg_babbage(bytes1)` share the selector 0xa9059cbb
+        let old = abi(
+            r#"[{"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"}]"#,
+        );
+        let new = abi(
+            r#"[{"type":"function","name":"many_msg_babbage","inputs":[{"name":"","type":"bytes1"}],"outputs":[],"stateMutability":"nonpayable"}]"#,
+        );
+        let changes = diff_abis(&old, &new);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].kind, ChangeKind::SelectorCollision);
+        assert!(changes[1].breaking);
+    }
+}
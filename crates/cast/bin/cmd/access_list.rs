@@ -1,4 +1,4 @@
-use cast::{Cast, TxBuilder};
+use cast::{checksum::parse_name_or_address, Cast, TxBuilder};
 use clap::Parser;
 use ethers_core::types::{BlockId, NameOrAddress};
 use ethers_providers::Middleware;
@@ -9,7 +9,6 @@ use foundry_cli::{
 };
 use foundry_common::types::ToEthers;
 use foundry_config::{Chain, Config};
-use std::str::FromStr;
 
 /// CLI arguments for `cast access-list`.
 #[derive(Debug, Parser)]
@@ -17,7 +16,7 @@ pub struct AccessListArgs {
     /// The destination of the transaction.
     #[clap(
         value_name = "TO",
-        value_parser = NameOrAddress::from_str
+        value_parser = parse_name_or_address
     )]
     to: Option<NameOrAddress>,
 
@@ -0,0 +1,343 @@
+use super::token::TokenContract;
+use alloy_primitives::{Address, U256};
+use cast::{checksum::parse_name_or_address, Cast, SimpleCast};
+use clap::{Parser, ValueHint};
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use ethers_core::types::{BlockId, NameOrAddress};
+use ethers_providers::Middleware;
+use eyre::{Result, WrapErr};
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_common::{
+    fs,
+    term::cli_warn,
+    types::{ToAlloy, ToEthers},
+    units::format_units,
+};
+use foundry_config::Config;
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+/// The CoinGecko API endpoint of the USD prices of coins.
+const COINGECKO_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+/// The CoinGecko API endpoint of the USD prices of tokens, by the platform of the tokens.
+const COINGECKO_TOKEN_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/token_price";
+
+/// The CoinGecko IDs of the native tokens and of the token platforms of chains, by chain ID.
+///
+/// Testnets are omitted, since their tokens have no price.
+const COINGECKO_CHAINS: &[(u64, &str, &str)] = &[
+    (1, "ethereum", "ethereum"),
+    (10, "ethereum", "optimistic-ethereum"),
+    (56, "binancecoin", "binance-smart-chain"),
+    (100, "xdai", "xdai"),
+    (137, "matic-network", "polygon-pos"),
+    (250, "fantom", "fantom"),
+    (8453, "ethereum", "base"),
+    (42161, "ethereum", "arbitrum-one"),
+    (43114, "avalanche-2", "avalanche"),
+];
+
+/// CLI arguments for `cast balance`.
+#[derive(Debug, Clone, Parser)]
+pub struct BalanceArgs {
+    /// The block height to query at.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[clap(long, short = 'B')]
+    block: Option<BlockId>,
+
+    /// The account to query.
+    #[clap(value_parser = parse_name_or_address)]
+    who: NameOrAddress,
+
+    /// Format the balance in ether.
+    #[clap(long, short)]
+    ether: bool,
+
+    /// The ERC-20 tokens to get the balances of as well.
+    ///
+    /// Prints a table of the native and token balances, formatted with the decimals of the
+    /// tokens.
+    #[clap(long = "erc20", value_name = "TOKEN", value_delimiter = ',')]
+    tokens: Vec<Address>,
+
+    /// A file with the ERC-20 tokens to get the balances of as well.
+    ///
+    /// Either a token list in the Uniswap format, whose tokens of the chain are used, or one token
+    /// address per line.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    token_list: Option<PathBuf>,
+
+    /// Print the USD values of the balances, using the prices of CoinGecko.
+    #[clap(long)]
+    usd: bool,
+
+    #[clap(flatten)]
+    rpc: RpcOpts,
+}
+
+impl BalanceArgs {
+    pub async fn run(self) -> Result<()> {
+        let BalanceArgs { block, who, ether, mut tokens, token_list, usd, rpc } = self;
+        let config = Config::from(&rpc);
+        let provider = utils::get_provider(&config)?;
+        let chain = utils::get_chain(config.chain, &provider).await?;
+
+        if let Some(path) = &token_list {
+            tokens.extend(parse_token_list(&fs::read_to_string(path)?, chain.id())?);
+        }
+        let mut seen = HashSet::new();
+        tokens.retain(|token| seen.insert(*token));
+
+        let who = match who {
+            NameOrAddress::Name(name) => provider.resolve_name(&name).await?,
+            NameOrAddress::Address(address) => address,
+        };
+        let native = Cast::new(provider.clone()).balance(who, block).await?;
+
+        if tokens.is_empty() && token_list.is_none() && !usd {
+            if ether {
+                println!("{}", SimpleCast::from_wei(&native.to_string(), "eth")?);
+            } else {
+                println!("{native}");
+            }
+            return Ok(())
+        }
+
+        let mut sheet = vec![AssetBalance {
+            asset: "native".to_string(),
+            token: None,
+            balance: native,
+            decimals: 18,
+            usd: None,
+        }];
+        for token in tokens {
+            let contract = TokenContract::new(
+                provider.clone(),
+                Some(chain),
+                NameOrAddress::Address(token.to_ethers()),
+                block,
+            )
+            .await?;
+            let args = vec![who.to_alloy().to_string()];
+            let balance = contract
+                .call_uint("balanceOf(address)(uint256)", args)
+                .await
+                .wrap_err_with(|| format!("could not get the balance of the token {token}"))?;
+            let decimals = contract.decimals().await?;
+            // some old tokens return the symbol as bytes32, only the address is shown for them
+            let asset = contract
+                .call_string("symbol()(string)", vec![])
+                .await
+                .unwrap_or_else(|_| token.to_string());
+            sheet.push(AssetBalance { asset, token: Some(token), balance, decimals, usd: None });
+        }
+
+        if usd {
+            if let Err(err) = add_usd_values(&mut sheet, chain.id()).await {
+                cli_warn!("Could not get the USD prices: {err}");
+            }
+        }
+
+        println!("{}", format_sheet(&sheet, usd)?);
+        Ok(())
+    }
+}
+
+/// The balance of an asset of the account.
+#[derive(Clone, Debug, PartialEq)]
+struct AssetBalance {
+    /// The symbol of the asset.
+    asset: String,
+    /// The token contract, `None` for the native token.
+    token: Option<Address>,
+    balance: U256,
+    decimals: u8,
+    /// The USD value of the balance, if known.
+    usd: Option<f64>,
+}
+
+impl AssetBalance {
+    /// Returns the balance in whole tokens, as floating point number for the USD value.
+    fn amount(&self) -> f64 {
+        format_units(self.balance, self.decimals as u32)
+            .ok()
+            .and_then(|amount| amount.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Formats the balances as a table, with a column of the USD values and their total if `usd` is
+/// set.
+fn format_sheet(sheet: &[AssetBalance], usd: bool) -> Result<String> {
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    let mut header = vec!["Asset", "Token", "Balance"];
+    if usd {
+        header.push("USD");
+    }
+    table.set_header(header);
+
+    let format_usd = |value: Option<f64>| match value {
+        Some(value) => format!("{value:.2}"),
+        None => "-".to_string(),
+    };
+    for balance in sheet {
+        let mut row = vec![
+            balance.asset.clone(),
+            balance.token.map(|token| token.to_string()).unwrap_or_else(|| "-".to_string()),
+            format_units(balance.balance, balance.decimals as u32)?,
+        ];
+        if usd {
+            row.push(format_usd(balance.usd));
+        }
+        table.add_row(row);
+    }
+    if usd {
+        let total = sheet.iter().filter_map(|balance| balance.usd).sum();
+        table.add_row(["Total".to_string(), String::new(), String::new(), format_usd(Some(total))]);
+    }
+    Ok(table.to_string())
+}
+
+/// Parses a token list, either in the Uniswap format, of which the tokens of the chain are
+/// returned, or with one address per line.
+///
+/// Empty lines and lines starting with `#` are ignored.
+fn parse_token_list(content: &str, chain: u64) -> Result<Vec<Address>> {
+    if content.trim_start().starts_with('{') {
+        #[derive(Deserialize)]
+        struct TokenList {
+            tokens: Vec<ListedToken>,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ListedToken {
+            chain_id: u64,
+            address: Address,
+        }
+        let list: TokenList = serde_json::from_str(content).wrap_err("invalid token list")?;
+        return Ok(list
+            .tokens
+            .into_iter()
+            .filter(|token| token.chain_id == chain)
+            .map(|token| token.address)
+            .collect())
+    }
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.parse().wrap_err_with(|| format!("invalid token address `{line}`")))
+        .collect()
+}
+
+/// Sets the USD values of the balances, from the CoinGecko prices of the assets.
+async fn add_usd_values(sheet: &mut [AssetBalance], chain: u64) -> Result<()> {
+    let Some(&(_, coin, platform)) = COINGECKO_CHAINS.iter().find(|(id, ..)| *id == chain) else {
+        eyre::bail!("CoinGecko has no prices of chain {chain}")
+    };
+    let client = reqwest::Client::new();
+
+    let prices: HashMap<String, HashMap<String, f64>> = client
+        .get(COINGECKO_PRICE_URL)
+        .query(&[("ids", coin), ("vs_currencies", "usd")])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .wrap_err("Failed to decode the CoinGecko response")?;
+    let native_price = prices.get(coin).and_then(|prices| prices.get("usd")).copied();
+
+    let tokens = sheet.iter().filter_map(|balance| balance.token).collect::<Vec<_>>();
+    let mut token_prices = HashMap::new();
+    if !tokens.is_empty() {
+        let addresses = tokens.iter().map(|token| token.to_string()).collect::<Vec<_>>();
+        let prices: HashMap<String, HashMap<String, f64>> = client
+            .get(format!("{COINGECKO_TOKEN_PRICE_URL}/{platform}"))
+            .query(&[("contract_addresses", addresses.join(",")), ("vs_currencies", "usd".into())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .wrap_err("Failed to decode the CoinGecko response")?;
+        for (address, prices) in prices {
+            if let (Ok(address), Some(price)) = (address.parse::<Address>(), prices.get("usd")) {
+                token_prices.insert(address, *price);
+            }
+        }
+    }
+
+    for balance in sheet {
+        let price = match balance.token {
+            Some(token) => token_prices.get(&token).copied(),
+            None => native_price,
+        };
+        balance.usd = price.map(|price| balance.amount() * price);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    const USDC: Address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+
+    #[test]
+    fn can_parse_token_lists() {
+        let list = r#"{
+            "name": "list",
+            "tokens": [
+                {"chainId": 1, "address": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", "symbol": "USDC", "decimals": 6},
+                {"chainId": 10, "address": "0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85", "symbol": "USDC", "decimals": 6}
+            ]
+        }"#;
+        assert_eq!(parse_token_list(list, 1).unwrap(), [USDC]);
+        assert!(parse_token_list(list, 5).unwrap().is_empty());
+
+        let list = "# stablecoins\n0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48\n\n";
+        assert_eq!(parse_token_list(list, 1).unwrap(), [USDC]);
+        assert!(parse_token_list("usdc", 1).is_err());
+    }
+
+    #[test]
+    fn formats_balance_sheet() {
+        let mut sheet = vec![
+            AssetBalance {
+                asset: "native".to_string(),
+                token: None,
+                balance: U256::from(1_500_000_000_000_000_000u128),
+                decimals: 18,
+                usd: None,
+            },
+            AssetBalance {
+                asset: "USDC".to_string(),
+                token: Some(USDC),
+                balance: U256::from(2_500_000u64),
+                decimals: 6,
+                usd: None,
+            },
+        ];
+        assert_eq!(sheet[0].amount(), 1.5);
+        assert_eq!(sheet[1].amount(), 2.5);
+
+        let table = format_sheet(&sheet, false).unwrap();
+        assert!(table.contains("1.500000000000000000"), "{table}");
+        assert!(table.contains("2.500000"), "{table}");
+        assert!(!table.contains("USD"), "{table}");
+
+        sheet[0].usd = Some(3000.0);
+        sheet[1].usd = Some(2.5);
+        let table = format_sheet(&sheet, true).unwrap();
+        assert!(table.contains("3000.00"), "{table}");
+        assert!(table.contains("3002.50"), "{table}");
+    }
+}
@@ -0,0 +1,402 @@
+use alloy_primitives::Address;
+use cast::checksum::parse_address;
+use clap::{Parser, ValueHint};
+use ethers_core::types::{BlockId, NameOrAddress};
+use ethers_providers::Middleware;
+use eyre::{Result, WrapErr};
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_common::types::ToEthers;
+use foundry_config::Config;
+use std::{collections::BTreeSet, ops::Range, path::PathBuf};
+
+/// The `PUSH32` opcode, which the compiler uses to load immutables.
+const PUSH32: u8 = 0x7f;
+
+/// CLI arguments for `cast bytecode-diff`.
+#[derive(Debug, Parser)]
+pub struct BytecodeDiffArgs {
+    /// The address of the first contract.
+    #[clap(value_parser = parse_address)]
+    address: Address,
+
+    /// The address of the second contract, defaults to the first one.
+    ///
+    /// Useful to compare the same address on two chains with `--other-rpc-url`.
+    #[clap(value_parser = parse_address)]
+    other_address: Option<Address>,
+
+    /// The RPC endpoint of the second contract, defaults to the one of the first contract.
+    #[clap(long, value_name = "URL")]
+    other_rpc_url: Option<String>,
+
+    /// The block height to query the first contract at.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[clap(long, short = 'B')]
+    block: Option<BlockId>,
+
+    /// The block height to query the second contract at.
+    #[clap(long, value_name = "BLOCK")]
+    other_block: Option<BlockId>,
+
+    /// The compiler artifact of the contract, e.g. `out/Counter.sol/Counter.json`.
+    ///
+    /// The immutables listed in its `immutableReferences` may differ between the contracts.
+    /// Without an artifact, every difference apart from the metadata is reported.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    artifact: Option<PathBuf>,
+
+    /// Don't ignore differences in the metadata hashes and immutables.
+    #[clap(long)]
+    strict: bool,
+
+    #[clap(flatten)]
+    rpc: RpcOpts,
+}
+
+impl BytecodeDiffArgs {
+    pub async fn run(self) -> Result<()> {
+        let BytecodeDiffArgs {
+            address,
+            other_address,
+            other_rpc_url,
+            block,
+            other_block,
+            artifact,
+            strict,
+            rpc,
+        } = self;
+
+        let immutables = match artifact {
+            Some(path) => immutable_offsets(&foundry_common::fs::read_json_file(&path)?)
+                .wrap_err_with(|| format!("invalid artifact {}", path.display()))?,
+            None => BTreeSet::new(),
+        };
+
+        let config = Config::from(&rpc);
+        let provider = utils::get_provider(&config)?;
+        let code = provider.get_code(NameOrAddress::Address(address.to_ethers()), block).await?;
+
+        let other_rpc = RpcOpts { url: other_rpc_url.or(rpc.url.clone()), ..rpc };
+        let other_config = Config::from(&other_rpc);
+        let other_provider = utils::get_provider(&other_config)?;
+        let other_code = other_provider
+            .get_code(
+                NameOrAddress::Address(other_address.unwrap_or(address).to_ethers()),
+                other_block,
+            )
+            .await?;
+
+        if code.is_empty() || other_code.is_empty() {
+            eyre::bail!("no code is deployed at one of the addresses");
+        }
+
+        let diff = BytecodeDiff::new(&code, &other_code, &immutables, strict);
+        print!("{diff}");
+        if !diff.is_equivalent() {
+            eyre::bail!("the bytecodes differ");
+        }
+        Ok(())
+    }
+}
+
+/// How a region of two bytecodes compares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RegionKind {
+    /// The instructions are identical.
+    Matching,
+    /// The instructions push different values of immutables.
+    Immutable,
+    /// The instructions differ.
+    Differing,
+}
+
+/// A region of two bytecodes, as byte ranges into both codes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Region {
+    kind: RegionKind,
+    a: Range<usize>,
+    b: Range<usize>,
+}
+
+/// A structural diff of two bytecodes, without their CBOR encoded metadata.
+#[derive(Debug)]
+struct BytecodeDiff {
+    regions: Vec<Region>,
+    metadata: (usize, usize),
+    metadata_matches: bool,
+    strict: bool,
+}
+
+impl BytecodeDiff {
+    /// Diffs the instructions of two bytecodes.
+    ///
+    /// The common instructions at the start and end of the codes are matched up, anything in
+    /// between is a single differing region. Unless `strict`, `PUSH32` instructions with different
+    /// values are classified as immutables if their values are at the `immutables` offsets in both
+    /// codes.
+    fn new(a: &[u8], b: &[u8], immutables: &BTreeSet<usize>, strict: bool) -> Self {
+        let (a, a_metadata) = split_metadata(a);
+        let (b, b_metadata) = split_metadata(b);
+        let a_instructions = instructions(a);
+        let b_instructions = instructions(b);
+
+        let classify = |x: &Instruction, y: &Instruction| {
+            if x.bytes(a) == y.bytes(b) {
+                Some(RegionKind::Matching)
+            } else if !strict &&
+                a[x.pc] == PUSH32 &&
+                b[y.pc] == PUSH32 &&
+                immutables.contains(&(x.pc + 1)) &&
+                immutables.contains(&(y.pc + 1))
+            {
+                Some(RegionKind::Immutable)
+            } else {
+                None
+            }
+        };
+
+        let mut prefix = Vec::new();
+        for (x, y) in a_instructions.iter().zip(&b_instructions) {
+            let Some(kind) = classify(x, y) else { break };
+            push_region(&mut prefix, kind, x.pc..x.end, y.pc..y.end);
+        }
+        let matched = prefix_len(&prefix, &a_instructions);
+
+        let mut suffix = Vec::new();
+        let max_suffix = a_instructions.len().min(b_instructions.len()) - matched;
+        for (x, y) in a_instructions.iter().rev().zip(b_instructions.iter().rev()).take(max_suffix)
+        {
+            let Some(kind) = classify(x, y) else { break };
+            push_region(&mut suffix, kind, x.pc..x.end, y.pc..y.end);
+        }
+        suffix.reverse();
+
+        let a_start = prefix.last().map_or(0, |r| r.a.end);
+        let b_start = prefix.last().map_or(0, |r| r.b.end);
+        let a_end = suffix.first().map_or(a.len(), |r| r.a.start);
+        let b_end = suffix.first().map_or(b.len(), |r| r.b.start);
+
+        let mut regions = prefix;
+        if a_start < a_end || b_start < b_end {
+            regions.push(Region {
+                kind: RegionKind::Differing,
+                a: a_start..a_end,
+                b: b_start..b_end,
+            });
+        }
+        for region in suffix {
+            push_region(&mut regions, region.kind, region.a, region.b);
+        }
+
+        Self {
+            regions,
+            metadata: (a_metadata.len(), b_metadata.len()),
+            metadata_matches: a_metadata == b_metadata,
+            strict,
+        }
+    }
+
+    /// Returns whether the codes are identical, modulo metadata and immutables unless strict.
+    fn is_equivalent(&self) -> bool {
+        self.regions.iter().all(|r| r.kind != RegionKind::Differing) &&
+            (!self.strict || self.metadata_matches)
+    }
+}
+
+impl std::fmt::Display for BytecodeDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for Region { kind, a, b } in &self.regions {
+            let kind = match kind {
+                RegionKind::Matching => "matching",
+                RegionKind::Immutable => "immutable",
+                RegionKind::Differing => "differing",
+            };
+            writeln!(f, "{:<10} {} | {}", kind, fmt_range(a), fmt_range(b))?;
+        }
+        let (a, b) = self.metadata;
+        let metadata = if self.metadata_matches { "matching" } else { "differing" };
+        writeln!(f, "metadata   {a} bytes | {b} bytes ({metadata})")?;
+
+        let immutables = self.regions.iter().filter(|r| r.kind == RegionKind::Immutable).count();
+        // the command fails with an error if the bytecodes differ
+        if !self.is_equivalent() {
+            Ok(())
+        } else if immutables == 0 && self.metadata_matches {
+            writeln!(f, "\nThe bytecodes are identical")
+        } else {
+            writeln!(
+                f,
+                "\nThe bytecodes are identical, apart from the metadata and {immutables} \
+                 immutable region(s)"
+            )
+        }
+    }
+}
+
+fn fmt_range(range: &Range<usize>) -> String {
+    format!("{:#06x}..{:#06x}", range.start, range.end)
+}
+
+/// A single instruction, with its immediate value.
+#[derive(Debug)]
+struct Instruction {
+    pc: usize,
+    end: usize,
+}
+
+impl Instruction {
+    fn bytes<'a>(&self, code: &'a [u8]) -> &'a [u8] {
+        &code[self.pc..self.end]
+    }
+}
+
+/// Splits code into its instructions, truncating the immediate value of a trailing `PUSH`.
+fn instructions(code: &[u8]) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let immediate = match code[pc] {
+            op @ 0x60..=PUSH32 => (op - 0x5f) as usize,
+            _ => 0,
+        };
+        let end = (pc + 1 + immediate).min(code.len());
+        instructions.push(Instruction { pc, end });
+        pc = end;
+    }
+    instructions
+}
+
+/// Appends a region, merging it into the last region if it has the same kind.
+fn push_region(regions: &mut Vec<Region>, kind: RegionKind, a: Range<usize>, b: Range<usize>) {
+    if let Some(last) = regions.last_mut() {
+        if last.kind == kind {
+            last.a = last.a.start.min(a.start)..last.a.end.max(a.end);
+            last.b = last.b.start.min(b.start)..last.b.end.max(b.end);
+            return
+        }
+    }
+    regions.push(Region { kind, a, b });
+}
+
+/// Returns the number of instructions covered by the regions at the start of the code.
+fn prefix_len(regions: &[Region], instructions: &[Instruction]) -> usize {
+    let end = regions.last().map_or(0, |r| r.a.end);
+    instructions.iter().take_while(|i| i.end <= end).count()
+}
+
+/// Returns the offsets of the immutables in the deployed code of a compiler artifact, from the
+/// `immutableReferences` of its `deployedBytecode`.
+fn immutable_offsets(artifact: &serde_json::Value) -> Result<BTreeSet<usize>> {
+    let Some(references) = artifact["deployedBytecode"]["immutableReferences"].as_object() else {
+        eyre::bail!("the artifact has no `deployedBytecode.immutableReferences`");
+    };
+    let mut offsets = BTreeSet::new();
+    for reference in references.values().filter_map(|refs| refs.as_array()).flatten() {
+        let start = reference["start"]
+            .as_u64()
+            .ok_or_else(|| eyre::eyre!("invalid immutable reference {reference}"))?;
+        offsets.insert(start as usize);
+    }
+    Ok(offsets)
+}
+
+/// Splits the CBOR encoded metadata off the end of the code.
+///
+/// The metadata is a CBOR map followed by its length as a big-endian `u16`.
+fn split_metadata(code: &[u8]) -> (&[u8], &[u8]) {
+    let Some(len) = code.len().checked_sub(2).map(|i| u16::from_be_bytes([code[i], code[i + 1]]))
+    else {
+        return (code, &[])
+    };
+    let len = len as usize + 2;
+    match code.len().checked_sub(len) {
+        // a CBOR map with up to 5 entries, e.g. `ipfs`, `solc` and `experimental`
+        Some(start) if len > 2 && (0xa1..=0xa5).contains(&code[start]) => code.split_at(start),
+        _ => (code, &[]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    // PUSH1 0x80 PUSH1 0x40 MSTORE, followed by a metadata map `{"solc": 0x000814}`
+    const CODE: &str = "6080604052a164736f6c6343000814000a";
+
+    fn code(s: &str) -> Vec<u8> {
+        hex::decode(s).unwrap()
+    }
+
+    #[test]
+    fn can_split_metadata() {
+        let code = code(CODE);
+        let (code, metadata) = split_metadata(&code);
+        assert_eq!(code, hex::decode("6080604052").unwrap());
+        assert_eq!(metadata.len(), 12);
+
+        let (code, metadata) = split_metadata(&[0x60, 0x80]);
+        assert_eq!(code, [0x60, 0x80]);
+        assert!(metadata.is_empty());
+    }
+
+    #[test]
+    fn ignores_metadata_and_immutables() {
+        let a = code("7f0000000000000000000000000000000000000000000000000000000000000001600052a164736f6c6343000814000a");
+        let b = code("7f0000000000000000000000000000000000000000000000000000000000000002600052a164736f6c6343000815000a");
+
+        let immutables = BTreeSet::from([1]);
+
+        let diff = BytecodeDiff::new(&a, &b, &immutables, false);
+        assert!(diff.is_equivalent());
+        assert_eq!(
+            diff.regions,
+            vec![
+                Region { kind: RegionKind::Immutable, a: 0..33, b: 0..33 },
+                Region { kind: RegionKind::Matching, a: 33..36, b: 33..36 },
+            ]
+        );
+        assert!(!diff.metadata_matches);
+
+        assert!(!BytecodeDiff::new(&a, &b, &immutables, true).is_equivalent());
+
+        // a `PUSH32` of a constant that isn't an immutable is a difference
+        let diff = BytecodeDiff::new(&a, &b, &BTreeSet::new(), false);
+        assert!(!diff.is_equivalent());
+        assert_eq!(diff.regions[0].kind, RegionKind::Differing);
+    }
+
+    #[test]
+    fn can_read_immutable_references() {
+        let artifact = serde_json::json!({
+            "deployedBytecode": {
+                "object": "0x",
+                "immutableReferences": {
+                    "3": [{ "start": 1, "length": 32 }, { "start": 70, "length": 32 }],
+                    "5": [{ "start": 120, "length": 32 }]
+                }
+            }
+        });
+        assert_eq!(immutable_offsets(&artifact).unwrap(), BTreeSet::from([1, 70, 120]));
+        assert!(immutable_offsets(&serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn finds_differing_regions() {
+        // the second code has an extra `CALLER POP` in the middle
+        let a = code("60806040525b00");
+        let b = code("608060405233505b00");
+
+        let diff = BytecodeDiff::new(&a, &b, &BTreeSet::new(), false);
+        assert!(!diff.is_equivalent());
+        assert_eq!(
+            diff.regions,
+            vec![
+                Region { kind: RegionKind::Matching, a: 0..5, b: 0..5 },
+                Region { kind: RegionKind::Differing, a: 5..5, b: 5..7 },
+                Region { kind: RegionKind::Matching, a: 5..7, b: 7..9 },
+            ]
+        );
+    }
+}
@@ -1,20 +1,31 @@
-use alloy_primitives::U256;
-use cast::{Cast, TxBuilder};
-use clap::Parser;
-use ethers_core::types::{BlockId, NameOrAddress};
-use eyre::{Result, WrapErr};
+use alloy_dyn_abi::FunctionExt;
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, U256};
+use cast::{
+    batch::{self, BatchCall, BatchCallResult, MULTICALL3_ADDRESS},
+    checksum::parse_name_or_address,
+    Cast, TxBuilder,
+};
+use clap::{Parser, ValueEnum, ValueHint};
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, BlockId, NameOrAddress, TransactionRequest,
+};
+use ethers_providers::Middleware;
+use eyre::{ContextCompat, Result, WrapErr};
 use foundry_cli::{
     opts::{EthereumOpts, TransactionOpts},
     utils::{self, handle_traces, parse_ether_value, TraceResult},
 };
 use foundry_common::{
+    fmt::format_token,
     runtime_client::RuntimeClient,
     types::{ToAlloy, ToEthers},
 };
 use foundry_compilers::EvmVersion;
-use foundry_config::{find_project_root_path, Config};
+use foundry_config::{find_project_root_path, Chain, Config};
 use foundry_evm::{executors::TracingExecutor, opts::EvmOpts};
-use std::str::FromStr;
+use futures::{stream, StreamExt};
+use std::path::PathBuf;
 
 type Provider = ethers_providers::Provider<RuntimeClient>;
 
@@ -22,7 +33,7 @@ type Provider = ethers_providers::Provider<RuntimeClient>;
 #[derive(Debug, Parser)]
 pub struct CallArgs {
     /// The destination of the transaction.
-    #[clap(value_parser = NameOrAddress::from_str)]
+    #[clap(value_parser = parse_name_or_address)]
     to: Option<NameOrAddress>,
 
     /// The signature of the function to call.
@@ -74,9 +85,23 @@ pub struct CallArgs {
     #[clap(long, short)]
     block: Option<BlockId>,
 
+    /// Print the decoded return values as a JSON array.
+    #[clap(long, conflicts_with = "trace")]
+    json: bool,
+
+    /// Print the raw and decoded return data with the output types, the gas estimate and
+    /// the revert data of a failed call as a JSON object.
+    ///
+    /// A reverting call doesn't fail the command, the revert is part of the output.
+    #[clap(long, requires = "json")]
+    full: bool,
+
     #[clap(subcommand)]
     command: Option<CallSubcommands>,
 
+    #[clap(flatten)]
+    batch: BatchArgs,
+
     #[clap(flatten)]
     tx: TransactionOpts,
 
@@ -108,6 +133,58 @@ pub enum CallSubcommands {
     },
 }
 
+/// CLI arguments for executing many read calls with `cast call --batch`.
+#[derive(Debug, Clone, Parser)]
+#[clap(next_help_heading = "Batch options")]
+pub struct BatchArgs {
+    /// Execute the read calls of a JSON or CSV file, instead of a single call.
+    ///
+    /// JSON files contain an array of `{"to", "sig", "args", "data"}` objects, CSV files one call
+    /// per row in the format `to,sig,args...`.
+    #[clap(
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        conflicts_with_all = &["to", "sig", "args", "data", "trace"]
+    )]
+    batch: Option<PathBuf>,
+
+    /// A read call in the format `to,sig,args...`, like a row of a CSV batch.
+    ///
+    /// Can be repeated to execute many calls. Multiple calls are aggregated through Multicall3 if
+    /// it's deployed on the chain, and a failing call doesn't fail the others.
+    #[clap(
+        long = "call",
+        value_name = "CALL",
+        conflicts_with_all = &["to", "sig", "args", "data", "trace", "batch"]
+    )]
+    calls: Vec<String>,
+
+    /// The maximum number of requests of a batch that are executed concurrently.
+    #[clap(long, default_value_t = 10, value_name = "N")]
+    concurrency: usize,
+
+    /// Aggregate the calls of a batch through the Multicall3 contract.
+    #[clap(long, requires = "batch")]
+    multicall: bool,
+
+    /// The number of calls aggregated in a single Multicall3 call.
+    #[clap(long, default_value_t = 100, value_name = "SIZE")]
+    multicall_size: usize,
+
+    /// The output format of the results of `--batch` or `--call`.
+    #[clap(long, value_enum, default_value_t = BatchFormat::Json)]
+    format: BatchFormat,
+}
+
+/// The output format of `cast call --batch`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BatchFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
 impl CallArgs {
     pub async fn run(self) -> Result<()> {
         let CallArgs {
@@ -118,7 +195,10 @@ impl CallArgs {
             tx,
             eth,
             command,
+            batch: batch_args,
             block,
+            json,
+            full,
             trace,
             evm_version,
             debug,
@@ -131,6 +211,40 @@ impl CallArgs {
         let chain = utils::get_chain(config.chain, &provider).await?;
         let sender = eth.wallet.sender().await;
 
+        if batch_args.batch.is_some() || !batch_args.calls.is_empty() {
+            if command.is_some() {
+                eyre::bail!("--batch and --call can not be used with --create")
+            }
+            let calls = match &batch_args.batch {
+                Some(path) => batch::read_batch_file(path)?,
+                None => batch_args
+                    .calls
+                    .iter()
+                    .map(|call| batch::parse_call(call))
+                    .collect::<Result<Vec<_>>>()?,
+            };
+            let caller = BatchCaller {
+                provider: &provider,
+                sender,
+                chain,
+                legacy: tx.legacy,
+                etherscan_api_key: config.get_etherscan_api_key(Some(chain)),
+                block,
+            };
+            // repeated `--call` flags are aggregated whenever possible, batches only on request
+            let multicall = if batch_args.batch.is_some() {
+                batch_args.multicall
+            } else {
+                calls.len() > 1 && caller.has_multicall3().await
+            };
+            let results = caller.execute(&calls, multicall, &batch_args).await;
+            match batch_args.format {
+                BatchFormat::Json => println!("{}", batch::format_json(&results)?),
+                BatchFormat::Csv => print!("{}", batch::format_csv(&results)),
+            }
+            return Ok(())
+        }
+
         let mut builder: TxBuilder<'_, Provider> =
             TxBuilder::new(&provider, sender.to_ethers(), to, chain, tx.legacy).await?;
 
@@ -208,12 +322,188 @@ impl CallArgs {
         };
 
         let builder_output = builder.build();
+        if json {
+            let result = Cast::new(provider).call_full(builder_output, block).await?;
+            if full {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else if !result.success {
+                eyre::bail!("{}", result.error.unwrap_or_default())
+            } else if result.decoded.is_empty() {
+                println!("{}", serde_json::to_string(&[&result.return_data])?);
+            } else {
+                let values = result.decoded.iter().map(|value| &value.value).collect::<Vec<_>>();
+                println!("{}", serde_json::to_string(&values)?);
+            }
+            return Ok(())
+        }
         println!("{}", Cast::new(provider).call(builder_output, block).await?);
 
         Ok(())
     }
 }
 
+/// Executes the read calls of a batch.
+struct BatchCaller<'a> {
+    provider: &'a Provider,
+    sender: Address,
+    chain: Chain,
+    legacy: bool,
+    etherscan_api_key: Option<String>,
+    block: Option<BlockId>,
+}
+
+impl BatchCaller<'_> {
+    /// Executes the calls, and returns their results in the same order.
+    ///
+    /// A failing call doesn't fail the batch, its error is part of its result.
+    async fn execute(
+        &self,
+        calls: &[BatchCall],
+        multicall: bool,
+        args: &BatchArgs,
+    ) -> Vec<BatchCallResult> {
+        let concurrency = args.concurrency.max(1);
+        if multicall {
+            return self.execute_multicall(calls, concurrency, args.multicall_size.max(1)).await
+        }
+
+        let cast = Cast::new(self.provider.clone());
+        stream::iter(calls)
+            .map(|call| {
+                let cast = &cast;
+                async move {
+                    let outcome = async {
+                        let (tx, func) = self.build(call).await?;
+                        cast.call((tx, func), self.block).await
+                    }
+                    .await;
+                    BatchCallResult::new(call, outcome)
+                }
+            })
+            .buffered(concurrency)
+            .collect()
+            .await
+    }
+
+    /// Executes the calls through Multicall3 `aggregate3`, in chunks of `size` calls.
+    async fn execute_multicall(
+        &self,
+        calls: &[BatchCall],
+        concurrency: usize,
+        size: usize,
+    ) -> Vec<BatchCallResult> {
+        let built: Vec<_> =
+            stream::iter(calls).map(|call| self.build(call)).buffered(concurrency).collect().await;
+
+        let mut outcomes: Vec<Option<Result<String>>> = Vec::with_capacity(calls.len());
+        let mut pending = Vec::new();
+        for (i, output) in built.into_iter().enumerate() {
+            let call = output.and_then(|(tx, func)| {
+                let to = tx.to_addr().copied().wrap_err("call without destination")?;
+                Ok((to.to_alloy(), tx.data().cloned().unwrap_or_default().to_vec(), func))
+            });
+            match call {
+                Ok(call) => {
+                    outcomes.push(None);
+                    pending.push((i, call));
+                }
+                Err(err) => outcomes.push(Some(Err(err))),
+            }
+        }
+
+        let chunks: Vec<_> = stream::iter(pending.chunks(size))
+            .map(|chunk| async move {
+                let calls = chunk
+                    .iter()
+                    .map(|(_, (target, data, _))| (*target, data.clone()))
+                    .collect::<Vec<_>>();
+                (chunk, self.aggregate3(&calls).await)
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        for (chunk, results) in chunks {
+            match results {
+                Ok(results) => {
+                    for ((i, (_, _, func)), (success, data)) in chunk.iter().zip(results) {
+                        outcomes[*i] = Some(if success {
+                            decode_output(func.as_ref(), &data)
+                        } else {
+                            Err(eyre::eyre!("execution reverted, data: 0x{}", hex::encode(data)))
+                        });
+                    }
+                }
+                Err(err) => {
+                    for (i, _) in chunk {
+                        outcomes[*i] = Some(Err(eyre::eyre!("multicall failed: {err:#}")));
+                    }
+                }
+            }
+        }
+
+        calls
+            .iter()
+            .zip(outcomes)
+            .map(|(call, outcome)| {
+                BatchCallResult::new(call, outcome.expect("every call has an outcome"))
+            })
+            .collect()
+    }
+
+    /// Returns whether Multicall3 is deployed at the block of the calls.
+    async fn has_multicall3(&self) -> bool {
+        self.provider
+            .get_code(MULTICALL3_ADDRESS.to_ethers(), self.block)
+            .await
+            .map_or(false, |code| !code.is_empty())
+    }
+
+    /// Calls Multicall3 `aggregate3` with the given calls.
+    async fn aggregate3(&self, calls: &[(Address, Vec<u8>)]) -> Result<Vec<(bool, Vec<u8>)>> {
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(self.sender.to_ethers())
+            .to(MULTICALL3_ADDRESS.to_ethers())
+            .data(batch::encode_aggregate3(calls)?)
+            .into();
+        let res = self.provider.call(&tx, self.block).await?;
+        batch::decode_aggregate3(&res)
+    }
+
+    /// Builds the transaction of a call.
+    async fn build(&self, call: &BatchCall) -> Result<(TypedTransaction, Option<Function>)> {
+        let to = parse_name_or_address(&call.to)
+            .map_err(|err| eyre::eyre!("invalid call destination `{}`: {err}", call.to))?;
+        let mut builder: TxBuilder<'_, Provider> = TxBuilder::new(
+            self.provider,
+            self.sender.to_ethers(),
+            Some(to),
+            self.chain,
+            self.legacy,
+        )
+        .await?;
+        builder.etherscan_api_key(self.etherscan_api_key.clone());
+        let data = call.data.as_ref().map(|data| data.trim_start_matches("0x").to_string());
+        fill_tx(&mut builder, None, call.sig.clone(), call.args.clone(), data).await?;
+        Ok(builder.build())
+    }
+}
+
+/// Decodes the return data of a call like [`Cast::call`].
+fn decode_output(func: Option<&Function>, data: &[u8]) -> Result<String> {
+    let decoded = match func {
+        Some(func) => func.abi_decode_output(data, false).wrap_err(
+            "could not decode output; did you specify the wrong function return data type?",
+        )?,
+        None => vec![],
+    };
+    Ok(if decoded.is_empty() {
+        format!("0x{}", hex::encode(data))
+    } else {
+        decoded.iter().map(format_token).collect::<Vec<_>>().join("\n")
+    })
+}
+
 /// fills the builder from create arg
 async fn fill_create(
     builder: &mut TxBuilder<'_, Provider>,
@@ -288,4 +578,53 @@ mod tests {
 
         assert!(args.is_err());
     }
+
+    #[test]
+    fn can_parse_batch_args() {
+        let args = CallArgs::parse_from([
+            "foundry-cli",
+            "--batch",
+            "calls.csv",
+            "--concurrency",
+            "4",
+            "--multicall",
+            "--format",
+            "csv",
+        ]);
+        assert_eq!(args.batch.batch, Some(PathBuf::from("calls.csv")));
+        assert_eq!(args.batch.concurrency, 4);
+        assert!(args.batch.multicall);
+        assert_eq!(args.batch.format, BatchFormat::Csv);
+
+        let args = CallArgs::try_parse_from([
+            "foundry-cli",
+            Address::ZERO.to_string().as_str(),
+            "--batch",
+            "calls.json",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_repeated_calls() {
+        let args = CallArgs::parse_from([
+            "foundry-cli",
+            "--call",
+            "weth.eth,name()(string)",
+            "--call",
+            "weth.eth,balanceOf(address)(uint256),0x0000000000000000000000000000000000000001",
+            "--format",
+            "csv",
+        ]);
+        assert_eq!(args.batch.calls.len(), 2);
+        assert_eq!(args.batch.format, BatchFormat::Csv);
+
+        let args = CallArgs::try_parse_from([
+            "foundry-cli",
+            Address::ZERO.to_string().as_str(),
+            "--call",
+            "weth.eth,name()(string)",
+        ]);
+        assert!(args.is_err());
+    }
 }
@@ -0,0 +1,172 @@
+//! Shell completions, with a hook that completes values depending on the environment at runtime.
+
+use clap::{builder::TypedValueParser, Command, ValueEnum};
+use clap_complete::Shell;
+use foundry_cli::opts::ChainValueParser;
+use foundry_config::Config;
+use std::io::{self, Write};
+
+/// The values that are completed at runtime by calling `cast __complete <KIND>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CompletionValues {
+    /// The names of the known chains, for `--chain`.
+    Chains,
+    /// The accounts in the default keystore directory, for `--account`.
+    Accounts,
+    /// The RPC aliases of the `[rpc_endpoints]` config, for `--rpc-url`.
+    RpcAliases,
+}
+
+impl CompletionValues {
+    /// Returns the values to complete.
+    ///
+    /// Errors are ignored, since they can't be shown while completing.
+    pub fn values(self) -> Vec<String> {
+        match self {
+            CompletionValues::Chains => ChainValueParser::default()
+                .inner
+                .possible_values()
+                .map(|values| values.map(|value| value.get_name().to_string()).collect())
+                .unwrap_or_default(),
+            CompletionValues::Accounts => {
+                let Some(dir) = Config::foundry_keystores_dir() else { return Vec::new() };
+                let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+                let mut accounts = entries
+                    .flatten()
+                    .filter(|entry| entry.path().is_file())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .collect::<Vec<_>>();
+                accounts.sort();
+                accounts
+            }
+            CompletionValues::RpcAliases => Config::try_from(Config::figment())
+                .map(|config| config.rpc_endpoints.keys().cloned().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Writes the completion script of the shell, with the runtime completion hook if the shell
+/// supports it.
+pub fn generate_completions(
+    shell: Shell,
+    cmd: &mut Command,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let name = cmd.get_name().to_string();
+    let mut script = Vec::new();
+    clap_complete::generate(shell, cmd, &name, &mut script);
+    let mut script = String::from_utf8_lossy(&script).into_owned();
+    match completion_hook(shell) {
+        // The zsh script is autoloaded as the `_cast` function of its `#compdef` header, so the
+        // generated function is renamed and the hook is defined as `_cast` in its place, before
+        // the trailer that calls or registers it.
+        Some(hook) if shell == Shell::Zsh => {
+            script = script.replacen("\n_cast() {", "\n_cast_static() {", 1);
+            let trailer = script.rfind("if [ \"$funcstack[1]\"").unwrap_or(script.len());
+            script.insert_str(trailer, hook.trim_start());
+        }
+        Some(hook) => script.push_str(hook),
+        None => {}
+    }
+    out.write_all(script.as_bytes())
+}
+
+/// Returns the script that completes the values of `--chain`, `--account` and `--rpc-url` with
+/// `cast __complete`, instead of the static script.
+fn completion_hook(shell: Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Bash => Some(BASH_HOOK),
+        Shell::Zsh => Some(ZSH_HOOK),
+        Shell::Fish => Some(FISH_HOOK),
+        _ => None,
+    }
+}
+
+const BASH_HOOK: &str = r#"
+_cast_runtime() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}" kind
+    # `--chain=value` is split into `--chain`, `=` and `value`
+    if [[ "${cur}" == "=" ]]; then
+        cur=""
+    elif [[ "${prev}" == "=" ]]; then
+        prev="${COMP_WORDS[COMP_CWORD-2]}"
+    fi
+    case "${prev}" in
+        --chain|--chain-id) kind=chains ;;
+        --account) kind=accounts ;;
+        --rpc-url|--fork-url) kind=rpc-aliases ;;
+        *)
+            _cast "$@"
+            return
+            ;;
+    esac
+    COMPREPLY=($(compgen -W "$(cast __complete "${kind}" 2>/dev/null)" -- "${cur}"))
+}
+
+complete -F _cast_runtime -o nosort -o bashdefault -o default cast
+"#;
+
+const ZSH_HOOK: &str = r#"
+_cast() {
+    local kind opt="${words[CURRENT-1]}"
+    if [[ "${words[CURRENT]}" == --*=* ]]; then
+        opt="${words[CURRENT]%%=*}"
+    fi
+    case "${opt}" in
+        --chain|--chain-id) kind=chains ;;
+        --account) kind=accounts ;;
+        --rpc-url|--fork-url) kind=rpc-aliases ;;
+        *)
+            _cast_static "$@"
+            return
+            ;;
+    esac
+    [[ "${words[CURRENT]}" == --*=* ]] && compset -P '*='
+    local -a values
+    values=(${(f)"$(cast __complete "${kind}" 2>/dev/null)"})
+    compadd -a values
+}
+
+"#;
+
+const FISH_HOOK: &str = r#"
+complete -c cast -l chain -f -a '(cast __complete chains 2>/dev/null)'
+complete -c cast -l account -f -a '(cast __complete accounts 2>/dev/null)'
+complete -c cast -l rpc-url -f -a '(cast __complete rpc-aliases 2>/dev/null)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_chains() {
+        let chains = CompletionValues::Chains.values();
+        assert!(chains.iter().any(|chain| chain == "mainnet"));
+        assert!(chains.iter().any(|chain| chain == "sepolia"));
+    }
+
+    #[test]
+    fn appends_completion_hook() {
+        let mut cmd = Command::new("cast").arg(clap::Arg::new("chain").long("chain"));
+        let mut out = Vec::new();
+        generate_completions(Shell::Bash, &mut cmd, &mut out).unwrap();
+        let script = String::from_utf8(out).unwrap();
+        assert!(script.contains("_cast()"));
+        assert!(script.contains("cast __complete"));
+
+        let mut out = Vec::new();
+        generate_completions(Shell::Zsh, &mut cmd, &mut out).unwrap();
+        let script = String::from_utf8(out).unwrap();
+        assert_eq!(script.matches("#compdef").count(), 1);
+        assert!(script.contains("_cast_static()"));
+        assert_eq!(script.matches("\n_cast() {").count(), 1);
+        let hook = script.find("cast __complete").unwrap();
+        assert!(hook < script.find("compdef _cast cast").unwrap());
+
+        let mut out = Vec::new();
+        generate_completions(Shell::PowerShell, &mut cmd, &mut out).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("cast __complete"));
+    }
+}
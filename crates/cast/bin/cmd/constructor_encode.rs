@@ -0,0 +1,306 @@
+use super::abi_diff::load_abi;
+use alloy_dyn_abi::{DynSolType, JsonAbiExt, ResolveSolType};
+use alloy_json_abi::{Constructor, ContractObject, JsonAbi, Param};
+use alloy_primitives::{Address, Bytes};
+use clap::{Parser, ValueHint};
+use eyre::{Result, WrapErr};
+use foundry_cli::{opts::EtherscanOpts, utils::read_constructor_args_file};
+use foundry_common::fs;
+use foundry_config::Config;
+use itertools::Itertools;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// CLI arguments for `cast constructor-encode`.
+#[derive(Debug, Clone, Parser)]
+pub struct ConstructorEncodeArgs {
+    /// The contract: the path to an artifact or ABI file, the name of a contract in the artifacts
+    /// of the current project, as `<NAME>` or `<FILE>:<NAME>`, or a contract address.
+    ///
+    /// If an address is specified, then the ABI is fetched from Etherscan, and the creation code
+    /// has to be passed with `--bytecode`.
+    contract: String,
+
+    /// The constructor arguments.
+    #[clap(allow_hyphen_values = true, conflicts_with = "constructor_args_path")]
+    args: Vec<String>,
+
+    /// The path to a file containing the constructor arguments.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    constructor_args_path: Option<PathBuf>,
+
+    /// The creation code to append the arguments to, instead of the bytecode of the artifact.
+    ///
+    /// Required for ABIs without bytecode and for bytecode with unlinked libraries.
+    #[clap(long, value_name = "BYTECODE")]
+    bytecode: Option<Bytes>,
+
+    /// Only print the encoded arguments.
+    #[clap(long, conflicts_with = "bytecode_only")]
+    args_only: bool,
+
+    /// Only print the deploy bytecode, e.g. to pass it to `cast send --create`.
+    #[clap(long)]
+    bytecode_only: bool,
+
+    /// Print the encoded arguments and deploy bytecode as JSON.
+    #[clap(long, short, conflicts_with_all = ["args_only", "bytecode_only"])]
+    json: bool,
+
+    #[clap(flatten)]
+    etherscan: EtherscanOpts,
+}
+
+impl ConstructorEncodeArgs {
+    pub async fn run(self) -> Result<()> {
+        let ConstructorEncodeArgs {
+            contract,
+            args,
+            constructor_args_path,
+            bytecode,
+            args_only,
+            bytecode_only,
+            json,
+            etherscan,
+        } = self;
+
+        let (abi, artifact_bytecode) = load_artifact(&contract, &etherscan).await?;
+        let args = match constructor_args_path {
+            Some(path) => read_constructor_args_file(path)?,
+            None => args,
+        };
+        let encoding = ConstructorEncoding::new(&abi, &args, bytecode.or(artifact_bytecode))?;
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&encoding)?);
+        } else if args_only {
+            println!("{}", encoding.args);
+        } else if bytecode_only {
+            let Some(bytecode) = encoding.bytecode else {
+                eyre::bail!("{contract} has no bytecode, pass the creation code with --bytecode")
+            };
+            println!("{bytecode}");
+        } else {
+            println!("Constructor: {}", encoding.constructor);
+            println!("Arguments:   {}", encoding.args);
+            if let Some(bytecode) = &encoding.bytecode {
+                println!("Bytecode:    {bytecode}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The encoded constructor arguments of a contract.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct ConstructorEncoding {
+    /// The signature of the constructor, with the names of its parameters.
+    constructor: String,
+    /// The ABI-encoded arguments.
+    args: Bytes,
+    /// The creation code followed by the arguments, if the creation code is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytecode: Option<Bytes>,
+}
+
+impl ConstructorEncoding {
+    /// Encodes the arguments, checking their number and types against the constructor of the
+    /// ABI, and appends them to the creation code.
+    fn new(abi: &JsonAbi, args: &[String], bytecode: Option<Bytes>) -> Result<Self> {
+        let (constructor, args) = match &abi.constructor {
+            Some(constructor) => (signature(constructor), encode_args(constructor, args)?),
+            None if args.is_empty() => ("constructor()".to_string(), Bytes::new()),
+            None => eyre::bail!(
+                "the contract has no constructor, but {} argument(s) were given",
+                args.len()
+            ),
+        };
+        let bytecode = bytecode.map(|code| [code.as_ref(), args.as_ref()].concat().into());
+        Ok(Self { constructor, args, bytecode })
+    }
+}
+
+/// Returns the signature of the constructor, with the names of its parameters.
+fn signature(constructor: &Constructor) -> String {
+    let params = constructor
+        .inputs
+        .iter()
+        .map(|param| {
+            let ty = param.selector_type();
+            if param.name.is_empty() {
+                ty.into_owned()
+            } else {
+                format!("{ty} {}", param.name)
+            }
+        })
+        .join(", ");
+    format!("constructor({params})")
+}
+
+/// Describes the parameter at `index` for error messages.
+fn describe(index: usize, param: &Param) -> String {
+    if param.name.is_empty() {
+        format!("#{index} ({})", param.selector_type())
+    } else {
+        format!("`{}` ({})", param.name, param.selector_type())
+    }
+}
+
+/// ABI-encodes the arguments, failing on missing or extra arguments and on values that don't fit
+/// the type of their parameter.
+fn encode_args(constructor: &Constructor, args: &[String]) -> Result<Bytes> {
+    let inputs = &constructor.inputs;
+    if args.len() != inputs.len() {
+        eyre::bail!(
+            "expected {} argument(s) for `{}`, got {}",
+            inputs.len(),
+            signature(constructor),
+            args.len()
+        )
+    }
+
+    let mut values = Vec::with_capacity(args.len());
+    for (index, (param, arg)) in inputs.iter().zip(args).enumerate() {
+        let ty = param.resolve().wrap_err_with(|| {
+            format!("unsupported type of constructor parameter {}", describe(index, param))
+        })?;
+        let value = DynSolType::coerce_str(&ty, arg).map_err(|err| {
+            eyre::eyre!(
+                "invalid value `{arg}` for constructor parameter {}: {err}",
+                describe(index, param)
+            )
+        })?;
+        values.push(value);
+    }
+    Ok(constructor.abi_encode_input(&values)?.into())
+}
+
+/// Loads the ABI and creation code of the contract, from an artifact or ABI file, from the
+/// artifacts of the current project, or the ABI from Etherscan.
+async fn load_artifact(
+    contract: &str,
+    etherscan: &EtherscanOpts,
+) -> Result<(JsonAbi, Option<Bytes>)> {
+    let path = if Path::new(contract).is_file() {
+        PathBuf::from(contract)
+    } else if contract.parse::<Address>().is_ok() {
+        return Ok((load_abi(contract, etherscan).await?, None))
+    } else {
+        let config = Config::from(etherscan);
+        find_artifact(&config.project_paths().artifacts, contract)?
+    };
+
+    let content = fs::read_to_string(&path)?;
+    if let Ok(abi) = serde_json::from_str::<JsonAbi>(&content) {
+        return Ok((abi, None))
+    }
+    let object: ContractObject = serde_json::from_str(&content).map_err(|err| {
+        if content.contains("__$") {
+            eyre::eyre!(
+                "the bytecode of {} has unlinked libraries, pass the linked creation code with \
+                 --bytecode",
+                path.display()
+            )
+        } else {
+            eyre::eyre!("{} is neither an ABI nor an artifact: {err}", path.display())
+        }
+    })?;
+    let abi =
+        object.abi.ok_or_else(|| eyre::eyre!("could not find ABI in file {}", path.display()))?;
+    Ok((abi, object.bytecode.filter(|code| !code.is_empty())))
+}
+
+/// Finds the artifact of a contract in the artifacts directory, by `<NAME>` or `<FILE>:<NAME>`.
+fn find_artifact(out: &Path, contract: &str) -> Result<PathBuf> {
+    let (file, name) = match contract.rsplit_once(':') {
+        Some((file, name)) => (Path::new(file).file_name(), name),
+        None => (None, contract),
+    };
+
+    let mut found = Vec::new();
+    if out.is_dir() {
+        for dir in std::fs::read_dir(out)?.flatten() {
+            if file.is_some_and(|file| dir.file_name() != file) {
+                continue
+            }
+            let path = dir.path().join(format!("{name}.json"));
+            if path.is_file() {
+                found.push(path);
+            }
+        }
+    }
+
+    match found.len() {
+        0 => eyre::bail!(
+            "could not find the artifact of `{contract}` in {}, build the project first or pass \
+             the path to the artifact",
+            out.display()
+        ),
+        1 => Ok(found.pop().unwrap()),
+        _ => {
+            found.sort();
+            eyre::bail!(
+                "found multiple artifacts of `{name}`, pass `<FILE>:{name}` instead:\n{}",
+                found.iter().map(|path| path.display()).format("\n")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    fn abi() -> JsonAbi {
+        serde_json::from_str(
+            r#"[{"type":"constructor","inputs":[{"name":"owner","type":"address","internalType":"address"},{"name":"supply","type":"uint8","internalType":"uint8"}],"stateMutability":"nonpayable"}]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn encodes_constructor_args() {
+        let args = ["0x0000000000000000000000000000000000000001".to_string(), "7".to_string()];
+        let encoding = ConstructorEncoding::new(&abi(), &args, Some(hex!("6080").into())).unwrap();
+        assert_eq!(encoding.constructor, "constructor(address owner, uint8 supply)");
+        assert_eq!(encoding.args.len(), 64);
+        assert_eq!(encoding.args[31], 1);
+        assert_eq!(encoding.args[63], 7);
+        let bytecode = encoding.bytecode.unwrap();
+        assert_eq!(&bytecode[..2], &hex!("6080"));
+        assert_eq!(&bytecode[2..], &encoding.args[..]);
+    }
+
+    #[test]
+    fn rejects_invalid_constructor_args() {
+        let err = ConstructorEncoding::new(&abi(), &["0x01".to_string()], None).unwrap_err();
+        assert!(err.to_string().contains("expected 2 argument(s)"), "{err}");
+
+        let args = ["0x0000000000000000000000000000000000000001".to_string(), "256".to_string()];
+        let err = ConstructorEncoding::new(&abi(), &args, None).unwrap_err();
+        assert!(err.to_string().contains("`supply` (uint8)"), "{err}");
+
+        let err =
+            ConstructorEncoding::new(&JsonAbi::default(), &["1".to_string()], None).unwrap_err();
+        assert!(err.to_string().contains("has no constructor"), "{err}");
+        assert!(ConstructorEncoding::new(&JsonAbi::default(), &[], None).unwrap().args.is_empty());
+    }
+
+    #[test]
+    fn finds_artifacts_by_name() {
+        let out = tempfile::tempdir().unwrap();
+        for file in ["Token.sol", "Other.sol"] {
+            std::fs::create_dir(out.path().join(file)).unwrap();
+            std::fs::write(out.path().join(file).join("Token.json"), "{}").unwrap();
+        }
+        std::fs::write(out.path().join("Token.sol").join("Vault.json"), "{}").unwrap();
+
+        let vault = find_artifact(out.path(), "Vault").unwrap();
+        assert_eq!(vault, out.path().join("Token.sol").join("Vault.json"));
+        let token = find_artifact(out.path(), "src/Token.sol:Token").unwrap();
+        assert_eq!(token, out.path().join("Token.sol").join("Token.json"));
+        assert!(find_artifact(out.path(), "Token").is_err());
+        assert!(find_artifact(out.path(), "Missing").is_err());
+    }
+}
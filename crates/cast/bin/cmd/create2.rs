@@ -1,20 +1,27 @@
 use alloy_primitives::{keccak256, Address, B256, U256};
-use clap::Parser;
+use cast::checksum::parse_address;
+use clap::{Parser, ValueHint};
 use eyre::{Result, WrapErr};
+use foundry_common::fs;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
-use regex::RegexSetBuilder;
+use regex::{Regex, RegexSetBuilder};
+use serde::{Deserialize, Serialize};
 use std::{
     num::NonZeroUsize,
+    path::{Path, PathBuf},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 // https://etherscan.io/address/0x4e59b44847b379578588920ca78fbf26c0b4956c#code
 const DEPLOYER: &str = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
 
+/// How often the progress is saved with `--checkpoint`.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
 /// CLI arguments for `cast create2`.
 #[derive(Debug, Clone, Parser)]
 pub struct Create2Args {
@@ -22,7 +29,7 @@ pub struct Create2Args {
     #[clap(
         long,
         short,
-        required_unless_present_any = &["ends_with", "matching"],
+        required_unless_present_any = &["ends_with", "matching", "regex", "leading_zeros", "zero_bytes"],
         value_name = "HEX"
     )]
     starts_with: Option<String>,
@@ -35,6 +42,20 @@ pub struct Create2Args {
     #[clap(long, short, value_name = "HEX")]
     matching: Option<String>,
 
+    /// Regular expression that the address, without the `0x` prefix, has to match.
+    #[clap(long, value_name = "REGEX")]
+    regex: Option<String>,
+
+    /// Minimum number of leading zero bytes of the address.
+    ///
+    /// Leading zero bytes make calldata containing the address cheaper.
+    #[clap(long, value_name = "N")]
+    leading_zeros: Option<usize>,
+
+    /// Minimum number of zero bytes anywhere in the address.
+    #[clap(long, value_name = "N")]
+    zero_bytes: Option<usize>,
+
     /// Case sensitive matching.
     #[clap(short, long)]
     case_sensitive: bool,
@@ -44,7 +65,8 @@ pub struct Create2Args {
         short,
         long,
         default_value = DEPLOYER,
-        value_name = "ADDRESS"
+        value_name = "ADDRESS",
+        value_parser = parse_address
     )]
     deployer: Address,
 
@@ -61,7 +83,7 @@ pub struct Create2Args {
     jobs: Option<NonZeroUsize>,
 
     /// Address of the caller. Used for the first 20 bytes of the salt.
-    #[clap(long, value_name = "ADDRESS")]
+    #[clap(long, value_name = "ADDRESS", value_parser = parse_address)]
     caller: Option<Address>,
 
     /// The random number generator's seed, used to initialize the salt.
@@ -71,6 +93,40 @@ pub struct Create2Args {
     /// Don't initialize the salt with a random value, and instead use the default value of 0.
     #[clap(long, conflicts_with = "seed")]
     no_random: bool,
+
+    /// Periodically save the progress of the search to this file, and resume from it if it
+    /// exists.
+    ///
+    /// The file is removed once an address is found.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    checkpoint: Option<PathBuf>,
+}
+
+/// The progress of a search, saved with `--checkpoint`.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Checkpoint {
+    /// The parameters of the search, a checkpoint can only be resumed with the same parameters.
+    search: String,
+    /// The first salt that wasn't tried yet.
+    salt: B256,
+}
+
+impl Checkpoint {
+    /// Reads the checkpoint of the search, if any.
+    fn read(path: &Path, search: &str) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None)
+        }
+        let checkpoint: Self = fs::read_json_file(path)?;
+        if checkpoint.search != search {
+            eyre::bail!(
+                "The checkpoint {} is for another search, remove it to start a new search",
+                path.display()
+            )
+        }
+        Ok(Some(checkpoint))
+    }
 }
 
 #[allow(dead_code)]
@@ -85,6 +141,9 @@ impl Create2Args {
             starts_with,
             ends_with,
             matching,
+            regex,
+            leading_zeros,
+            zero_bytes,
             case_sensitive,
             deployer,
             init_code,
@@ -93,6 +152,7 @@ impl Create2Args {
             caller,
             seed,
             no_random,
+            checkpoint,
         } = self;
 
         let mut regexs = vec![];
@@ -132,6 +192,16 @@ impl Create2Args {
             "vanity patterns length exceeded. cannot be more than 40 characters",
         );
 
+        if let Some(regex) = regex {
+            Regex::new(&regex).wrap_err("invalid regex provided")?;
+            regexs.push(regex);
+        }
+        let leading_zeros = leading_zeros.unwrap_or_default();
+        let zero_bytes = zero_bytes.unwrap_or_default();
+        if leading_zeros > 20 || zero_bytes > 20 {
+            eyre::bail!("An address has only 20 bytes");
+        }
+
         let regex = RegexSetBuilder::new(regexs).case_insensitive(!case_sensitive).build()?;
 
         let init_code_hash = if let Some(init_code_hash) = init_code_hash {
@@ -144,6 +214,16 @@ impl Create2Args {
             unreachable!();
         };
 
+        // the parameters that determine the result of the search
+        let search = format!(
+            "{deployer} {init_code_hash} {:?} {case_sensitive} {leading_zeros} {zero_bytes}",
+            regex.patterns()
+        );
+        let resumed = match &checkpoint {
+            Some(path) => Checkpoint::read(path, &search)?,
+            None => None,
+        };
+
         let mut n_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
         if let Some(jobs) = jobs {
             n_threads = n_threads.min(jobs.get());
@@ -160,7 +240,9 @@ impl Create2Args {
             &mut salt[..]
         };
 
-        if !no_random {
+        if let Some(resumed) = &resumed {
+            salt = resumed.salt;
+        } else if !no_random {
             let mut rng = match seed {
                 Some(seed) => StdRng::from_seed(seed.0),
                 None => StdRng::from_entropy(),
@@ -168,9 +250,16 @@ impl Create2Args {
             rng.fill_bytes(remaining);
         }
 
-        println!("Starting to generate deterministic contract address...");
+        if resumed.is_some() {
+            println!("Resuming to generate deterministic contract address from {salt}...");
+        } else {
+            println!("Starting to generate deterministic contract address...");
+        }
         let mut handles = Vec::with_capacity(n_threads);
         let found = Arc::new(AtomicBool::new(false));
+        // the number of salts each thread has tried, updated periodically
+        let progress: Arc<Vec<AtomicUsize>> =
+            Arc::new((0..n_threads).map(|_| AtomicUsize::new(0)).collect());
         let timer = Instant::now();
 
         // Loops through all possible salts in parallel until a result is found.
@@ -181,6 +270,7 @@ impl Create2Args {
             let regex = regex.clone();
             let regex_len = regex.patterns().len();
             let found = Arc::clone(&found);
+            let progress = Arc::clone(&progress);
             handles.push(std::thread::spawn(move || {
                 // Read the first bytes of the salt as a usize to be able to increment it.
                 struct B256Aligned(B256, [usize; 0]);
@@ -193,6 +283,7 @@ impl Create2Args {
                 *salt_word = salt_word.wrapping_add(i);
 
                 let mut checksum = [0; 42];
+                let mut tried = 0usize;
                 loop {
                     // Stop if a result was found in another thread.
                     if found.load(Ordering::Relaxed) {
@@ -203,23 +294,53 @@ impl Create2Args {
                     #[allow(clippy::needless_borrows_for_generic_args)]
                     let addr = deployer.create2(&salt.0, init_code_hash);
 
-                    // Check if the the regex matches the calculated address' checksum.
-                    let _ = addr.to_checksum_raw(&mut checksum, None);
-                    // SAFETY: stripping 2 ASCII bytes ("0x") off of an already valid UTF-8 string
-                    // is safe.
-                    let s = unsafe { std::str::from_utf8_unchecked(checksum.get_unchecked(2..)) };
-                    if regex.matches(s).into_iter().count() == regex_len {
-                        // Notify other threads that we found a result.
-                        found.store(true, Ordering::Relaxed);
-                        break Some((salt.0, addr));
+                    // Check the zero bytes first, they are cheaper to check than the regex.
+                    let matches_zeros = addr[..leading_zeros].iter().all(|b| *b == 0) &&
+                        (zero_bytes == 0 ||
+                            addr.iter().filter(|b| **b == 0).count() >= zero_bytes);
+                    if matches_zeros {
+                        // Check if the the regex matches the calculated address' checksum.
+                        let _ = addr.to_checksum_raw(&mut checksum, None);
+                        // SAFETY: stripping 2 ASCII bytes ("0x") off of an already valid UTF-8
+                        // string is safe.
+                        let s =
+                            unsafe { std::str::from_utf8_unchecked(checksum.get_unchecked(2..)) };
+                        if regex.matches(s).into_iter().count() == regex_len {
+                            // Notify other threads that we found a result.
+                            found.store(true, Ordering::Relaxed);
+                            break Some((salt.0, addr));
+                        }
                     }
 
                     // Increment the salt for the next iteration.
                     *salt_word = salt_word.wrapping_add(increment);
+                    tried += 1;
+                    if tried % 4096 == 0 {
+                        progress[i].store(tried, Ordering::Relaxed);
+                    }
                 }
             }));
         }
 
+        // save the progress periodically, every thread has tried at least the salts below the
+        // minimum progress of all threads
+        if let Some(path) = &checkpoint {
+            let mut last_save = Instant::now();
+            while !handles.iter().all(|handle| handle.is_finished()) {
+                std::thread::sleep(Duration::from_millis(100));
+                if last_save.elapsed() < CHECKPOINT_INTERVAL {
+                    continue
+                }
+                last_save = Instant::now();
+                let tried = progress.iter().map(|p| p.load(Ordering::Relaxed)).min().unwrap_or(0);
+                let checkpoint = Checkpoint {
+                    search: search.clone(),
+                    salt: add_to_salt(salt, tried.wrapping_mul(n_threads)),
+                };
+                fs::write_json_file(path, &checkpoint)?;
+            }
+        }
+
         let results = handles.into_iter().filter_map(|h| h.join().unwrap()).collect::<Vec<_>>();
         println!("Successfully found contract address(es) in {:?}", timer.elapsed());
         for (i, (salt, address)) in results.iter().enumerate() {
@@ -227,6 +348,15 @@ impl Create2Args {
                 println!("---");
             }
             println!("Address: {address}\nSalt: {salt} ({})", U256::from_be_bytes(salt.0));
+            let leading = address.iter().take_while(|b| **b == 0).count();
+            let zeros = address.iter().filter(|b| **b == 0).count();
+            println!("Zero bytes: {zeros} ({leading} leading)");
+        }
+
+        if let Some(path) = &checkpoint {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
         }
 
         let (salt, address) = results.into_iter().next().unwrap();
@@ -234,6 +364,15 @@ impl Create2Args {
     }
 }
 
+/// Adds `n` to the salt like the search threads increment it.
+fn add_to_salt(mut salt: B256, n: usize) -> B256 {
+    const WORD: usize = usize::BITS as usize / 8;
+    let word = &mut salt[32 - WORD..];
+    let value = usize::from_ne_bytes(word.try_into().unwrap()).wrapping_add(n);
+    word.copy_from_slice(&value.to_ne_bytes());
+    salt
+}
+
 fn get_regex_hex_string(s: String) -> Result<String> {
     let s = s.strip_prefix("0x").unwrap_or(&s);
     let pad_width = s.len() + s.len() % 2;
@@ -391,6 +530,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn matches_regex_and_zero_bytes() {
+        let args = Create2Args::parse_from([
+            "foundry-cli",
+            "--init-code-hash=0x0000000000000000000000000000000000000000000000000000000000000000",
+            "--regex=^[0-9]+[a-f]",
+            "--leading-zeros=1",
+        ]);
+        let address = args.run().unwrap().address;
+        assert_eq!(address[0], 0);
+        let address = format!("{address:x}");
+        assert!(Regex::new("^[0-9]+[a-f]").unwrap().is_match(&address), "{address}");
+
+        let args = Create2Args::parse_from([
+            "foundry-cli",
+            "--init-code-hash=0x0000000000000000000000000000000000000000000000000000000000000000",
+            "--zero-bytes=2",
+        ]);
+        let address = args.run().unwrap().address;
+        assert!(address.iter().filter(|b| **b == 0).count() >= 2);
+
+        let args = Create2Args::try_parse_from(["foundry-cli", "--regex=(", "--init-code=00"]);
+        assert!(args.unwrap().run().is_err());
+    }
+
+    #[test]
+    fn can_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("create2.json");
+        let mk_args = |args: &[&str]| {
+            Create2Args::parse_from(
+                [
+                    "foundry-cli",
+                    "--init-code-hash=0x479d7e8f31234e208d704ba1a123c76385cea8a6981fd675b784fbd9cffb918d",
+                    "--checkpoint",
+                    path.to_str().unwrap(),
+                    "-j1",
+                ]
+                .iter()
+                .chain(args),
+            )
+        };
+
+        // resumes from the saved salt
+        let salt = b256!("000000000000000000000000000000000000000000000000df00000000000000");
+        let search = format!(
+            "{} 0x479d7e8f31234e208d704ba1a123c76385cea8a6981fd675b784fbd9cffb918d [\"^00\"] false 0 0",
+            Address::from_str(DEPLOYER).unwrap()
+        );
+        fs::write_json_file(&path, &Checkpoint { search, salt }).unwrap();
+        let out = mk_args(&["--starts-with=0x00"]).run().unwrap();
+        assert_eq!(out.address, address!("00bF495b8b42fdFeb91c8bCEB42CA4eE7186AEd2"));
+        assert_eq!(out.salt, salt);
+        assert!(!path.exists());
+
+        // a checkpoint of another search is rejected
+        fs::write_json_file(&path, &Checkpoint { search: "other".to_string(), salt }).unwrap();
+        assert!(mk_args(&["--starts-with=0x00"]).run().is_err());
+    }
+
+    #[test]
+    fn can_add_to_salt() {
+        let salt = add_to_salt(B256::ZERO, 1);
+        assert_eq!(add_to_salt(salt, usize::MAX), B256::ZERO);
+        assert_ne!(salt, B256::ZERO);
+    }
+
     #[test]
     fn j0() {
         let e =
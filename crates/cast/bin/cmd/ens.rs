@@ -0,0 +1,260 @@
+use alloy_dyn_abi::{DynSolValue, FunctionExt};
+use alloy_primitives::{address, hex, Address};
+use cast::{checksum::parse_address, SimpleCast, TxBuilder};
+use clap::Parser;
+use ethers_core::types::{BlockId, NameOrAddress};
+use ethers_providers::Middleware;
+use eyre::{ContextCompat, Result};
+use foundry_cli::{opts::RpcOpts, stdin, utils};
+use foundry_common::{types::ToEthers, RetryProvider};
+use foundry_config::{Chain, Config};
+
+/// The address of the ENS registry, which is the same on mainnet and the testnets.
+pub const ENS_REGISTRY: Address = address!("00000000000C2E074eC69A0dFb2997BA6C7d2e1e");
+
+/// CLI arguments for `cast ens`.
+#[derive(Debug, Parser)]
+pub enum EnsSubcommands {
+    /// Calculate the namehash of a name.
+    #[clap(visible_aliases = &["na", "nh"])]
+    Namehash { name: Option<String> },
+
+    /// Calculate the labelhash of a single label, e.g. `vitalik` of `vitalik.eth`.
+    #[clap(visible_alias = "lh")]
+    Labelhash { label: Option<String> },
+
+    /// Get the resolver of a name.
+    #[clap(visible_alias = "r")]
+    Resolver {
+        /// The ENS name.
+        name: String,
+
+        #[clap(flatten)]
+        opts: EnsOpts,
+    },
+
+    /// Get the address a name resolves to.
+    #[clap(visible_aliases = &["addr", "resolve"])]
+    Address {
+        /// The ENS name.
+        name: String,
+
+        #[clap(flatten)]
+        opts: EnsOpts,
+    },
+
+    /// Get a text record of a name, e.g. `url`, `avatar` or `com.twitter`.
+    #[clap(visible_alias = "t")]
+    Text {
+        /// The ENS name.
+        name: String,
+
+        /// The key of the text record.
+        key: String,
+
+        #[clap(flatten)]
+        opts: EnsOpts,
+    },
+
+    /// Get the content hash of a name, annotated with its protocol, e.g. `ipfs`.
+    #[clap(visible_alias = "ch")]
+    Contenthash {
+        /// The ENS name.
+        name: String,
+
+        #[clap(flatten)]
+        opts: EnsOpts,
+    },
+
+    /// Get the primary name of an address and check that it resolves back to the address.
+    ///
+    /// Fails if the address has no primary name, or if the name doesn't resolve to the address.
+    #[clap(visible_alias = "rev")]
+    Reverse {
+        /// The address to look up.
+        #[clap(value_parser = parse_address)]
+        address: Address,
+
+        #[clap(flatten)]
+        opts: EnsOpts,
+    },
+}
+
+impl EnsSubcommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            EnsSubcommands::Namehash { name } => {
+                let name = stdin::unwrap_line(name)?;
+                println!("{}", SimpleCast::namehash(&name)?);
+            }
+            EnsSubcommands::Labelhash { label } => {
+                let label = stdin::unwrap_line(label)?;
+                println!("{}", SimpleCast::labelhash(&label)?);
+            }
+            EnsSubcommands::Resolver { name, opts } => {
+                let ens = opts.connect().await?;
+                println!("{}", ens.resolver(&name).await?.to_checksum(None));
+            }
+            EnsSubcommands::Address { name, opts } => {
+                let ens = opts.connect().await?;
+                println!("{}", ens.address(&name).await?.to_checksum(None));
+            }
+            EnsSubcommands::Text { name, key, opts } => {
+                let ens = opts.connect().await?;
+                let resolver = ens.resolver(&name).await?;
+                let node = SimpleCast::namehash(&name)?;
+                let text =
+                    ens.call(resolver, "text(bytes32,string)(string)", vec![node, key]).await?;
+                println!("{}", text.as_str().wrap_err("expected a string")?);
+            }
+            EnsSubcommands::Contenthash { name, opts } => {
+                let ens = opts.connect().await?;
+                let resolver = ens.resolver(&name).await?;
+                let node = SimpleCast::namehash(&name)?;
+                let hash = ens.call(resolver, "contenthash(bytes32)(bytes)", vec![node]).await?;
+                let hash = hash.as_bytes().wrap_err("expected bytes")?;
+                if hash.is_empty() {
+                    eyre::bail!("{name} has no content hash");
+                }
+                match contenthash_protocol(hash) {
+                    Some(protocol) => println!("{} ({protocol})", hex::encode_prefixed(hash)),
+                    None => println!("{}", hex::encode_prefixed(hash)),
+                }
+            }
+            EnsSubcommands::Reverse { address, opts } => {
+                let ens = opts.connect().await?;
+                let reverse_name = format!("{}.addr.reverse", hex::encode(address));
+                let resolver = ens.resolver(&reverse_name).await?;
+                let node = SimpleCast::namehash(&reverse_name)?;
+                let name = ens.call(resolver, "name(bytes32)(string)", vec![node]).await?;
+                let name = name.as_str().wrap_err("expected a string")?;
+                if name.is_empty() {
+                    eyre::bail!("{} has no primary name", address.to_checksum(None));
+                }
+                let resolved = ens.address(name).await?;
+                eyre::ensure!(
+                    resolved == address,
+                    "the primary name of {} is {name}, but {name} resolves to {}",
+                    address.to_checksum(None),
+                    resolved.to_checksum(None)
+                );
+                println!("{name}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Options of the subcommands that read from the ENS contracts.
+#[derive(Debug, Parser)]
+pub struct EnsOpts {
+    /// The address of the ENS registry.
+    #[clap(long, value_name = "ADDRESS", value_parser = parse_address, default_value_t = ENS_REGISTRY)]
+    registry: Address,
+
+    /// The block height to query at.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[clap(long, short = 'B')]
+    block: Option<BlockId>,
+
+    #[clap(flatten)]
+    rpc: RpcOpts,
+}
+
+impl EnsOpts {
+    async fn connect(&self) -> Result<Ens> {
+        let config = Config::from(&self.rpc);
+        let provider = utils::get_provider(&config)?;
+        let chain = utils::get_chain(config.chain, &provider).await?;
+        Ok(Ens { provider, chain, registry: self.registry, block: self.block })
+    }
+}
+
+/// The ENS contracts of a chain, called at a fixed block.
+struct Ens {
+    provider: RetryProvider,
+    chain: Chain,
+    registry: Address,
+    block: Option<BlockId>,
+}
+
+impl Ens {
+    /// Returns the resolver of a name, failing if none is set.
+    async fn resolver(&self, name: &str) -> Result<Address> {
+        let resolver = self
+            .call(self.registry, "resolver(bytes32)(address)", vec![SimpleCast::namehash(name)?])
+            .await?
+            .as_address()
+            .wrap_err("expected an address")?;
+        if resolver.is_zero() {
+            eyre::bail!("{name} has no resolver");
+        }
+        Ok(resolver)
+    }
+
+    /// Returns the address a name resolves to.
+    async fn address(&self, name: &str) -> Result<Address> {
+        let resolver = self.resolver(name).await?;
+        self.call(resolver, "addr(bytes32)(address)", vec![SimpleCast::namehash(name)?])
+            .await?
+            .as_address()
+            .wrap_err("expected an address")
+    }
+
+    /// Calls a view function and returns its first return value.
+    async fn call(&self, to: Address, sig: &str, args: Vec<String>) -> Result<DynSolValue> {
+        let mut builder = TxBuilder::new(
+            &self.provider,
+            Address::ZERO.to_ethers(),
+            Some(NameOrAddress::Address(to.to_ethers())),
+            self.chain,
+            true,
+        )
+        .await?;
+        builder.set_args(sig, args).await?;
+        let (tx, func) = builder.build();
+
+        let res = self.provider.call(&tx, self.block).await?;
+        let func = func.expect("function is set");
+        func.abi_decode_output(res.as_ref(), false)
+            .ok()
+            .and_then(|values| values.into_iter().next())
+            .wrap_err_with(|| format!("could not decode the result of `{}`", func.name))
+    }
+}
+
+/// Returns the protocol of an ENSIP-7 content hash, from its multicodec prefix.
+fn contenthash_protocol(hash: &[u8]) -> Option<&'static str> {
+    match hash {
+        [0xe3, 0x01, ..] => Some("ipfs"),
+        [0xe4, 0x01, ..] => Some("swarm"),
+        [0xe5, 0x01, ..] => Some("ipns"),
+        [0xbc, 0x03, ..] => Some("onion"),
+        [0xbd, 0x03, ..] => Some("onion3"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_detect_contenthash_protocol() {
+        let ipfs = hex::decode(
+            "e3010170122029f2d17be6139079dc48696d1f582a8530eb9805b561eda517e22a892c7e3f1f",
+        )
+        .unwrap();
+        assert_eq!(contenthash_protocol(&ipfs), Some("ipfs"));
+        assert_eq!(contenthash_protocol(&[0xe5, 0x01, 0x72]), Some("ipns"));
+        assert_eq!(contenthash_protocol(&[0x12, 0x20]), None);
+    }
+
+    #[test]
+    fn can_parse_ens_registry() {
+        let args = EnsSubcommands::parse_from(["foundry-cli", "resolver", "vitalik.eth"]);
+        let EnsSubcommands::Resolver { opts, .. } = args else { panic!("expected a resolver") };
+        assert_eq!(opts.registry, ENS_REGISTRY);
+    }
+}
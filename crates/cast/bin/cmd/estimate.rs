@@ -1,5 +1,5 @@
 use alloy_primitives::U256;
-use cast::{Cast, TxBuilder};
+use cast::{checksum::parse_name_or_address, Cast, TxBuilder};
 use clap::Parser;
 use ethers_core::types::NameOrAddress;
 use eyre::Result;
@@ -8,13 +8,12 @@ use foundry_cli::{
     utils::{self, parse_ether_value},
 };
 use foundry_config::{figment::Figment, Config};
-use std::str::FromStr;
 
 /// CLI arguments for `cast estimate`.
 #[derive(Debug, Parser)]
 pub struct EstimateArgs {
     /// The destination of the transaction.
-    #[clap(value_parser = NameOrAddress::from_str)]
+    #[clap(value_parser = parse_name_or_address)]
     to: Option<NameOrAddress>,
 
     /// The signature of the function to call.
@@ -27,7 +26,7 @@ pub struct EstimateArgs {
     #[clap(
         short,
         long,
-        value_parser = NameOrAddress::from_str,
+        value_parser = parse_name_or_address,
         default_value = "0x0000000000000000000000000000000000000000",
         env = "ETH_FROM",
     )]
@@ -0,0 +1,290 @@
+use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use ethers_core::types::{Address, DefaultFrame, StructLog, H256};
+use ethers_providers::Middleware;
+use eyre::{Result, WrapErr};
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_config::Config;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use yansi::Paint;
+
+/// The opcodes that are highlighted as storage hotspots.
+const STORAGE_OPCODES: &[&str] = &["SLOAD", "SSTORE"];
+
+/// CLI arguments for `cast gas-used`.
+#[derive(Debug, Parser)]
+pub struct GasUsedArgs {
+    /// The transaction hash.
+    tx_hash: H256,
+
+    /// Break the gas used down by opcode and by call frame.
+    ///
+    /// Requires a node that supports `debug_traceTransaction`.
+    #[clap(long)]
+    by_opcode: bool,
+
+    /// Print the gas used as JSON.
+    #[clap(long, short, help_heading = "Display options")]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: RpcOpts,
+}
+
+impl GasUsedArgs {
+    pub async fn run(self) -> Result<()> {
+        let GasUsedArgs { tx_hash, by_opcode, json, rpc } = self;
+
+        let config = Config::from(&rpc);
+        let provider = utils::get_provider(&config)?;
+        let receipt = provider
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .ok_or_else(|| eyre::eyre!("transaction {tx_hash:?} not found"))?;
+        let gas_used = receipt.gas_used.unwrap_or_default().as_u64();
+
+        if !by_opcode {
+            if json {
+                println!("{}", json!({ "gasUsed": gas_used }));
+            } else {
+                println!("{gas_used}");
+            }
+            return Ok(())
+        }
+
+        let frame: DefaultFrame = provider
+            .request(
+                "debug_traceTransaction",
+                (tx_hash, json!({ "disableStorage": true, "enableMemory": false })),
+            )
+            .await
+            .wrap_err(
+                "failed to trace the transaction, the node must support `debug_traceTransaction`",
+            )?;
+        let root = receipt.to.or(receipt.contract_address).unwrap_or_default();
+        let profile = GasProfile::new(&frame.struct_logs, root, gas_used);
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&profile)?);
+        } else {
+            println!("{profile}");
+        }
+        Ok(())
+    }
+}
+
+/// The gas used by an opcode.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OpcodeGas {
+    opcode: String,
+    count: u64,
+    gas: u64,
+}
+
+/// The gas used by the instructions of a call frame, excluding its subcalls.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FrameGas {
+    /// The depth of the frame, starting at 1 for the transaction's call.
+    depth: u64,
+    /// The called address, or the zero address for contract creations.
+    address: Address,
+    gas: u64,
+}
+
+/// The gas used by a transaction, grouped by opcode and call frame.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GasProfile {
+    /// The gas used by the transaction, as in its receipt.
+    gas_used: u64,
+    /// The gas used by the executed instructions.
+    execution_gas: u64,
+    /// The opcodes, by descending gas.
+    opcodes: Vec<OpcodeGas>,
+    /// The call frames, in execution order.
+    frames: Vec<FrameGas>,
+}
+
+impl GasProfile {
+    /// Groups the gas costs of the struct logs of a transaction to `root`.
+    ///
+    /// The cost of a call is reported by geth including the gas forwarded to the callee, which is
+    /// subtracted, so every unit of gas is attributed to the frame that used it.
+    fn new(logs: &[StructLog], root: Address, gas_used: u64) -> Self {
+        let mut opcodes: BTreeMap<&str, OpcodeGas> = BTreeMap::new();
+        let mut frames = Vec::new();
+        // the indices into `frames` of the frames entered so far
+        let mut stack: Vec<usize> = Vec::new();
+
+        for (i, log) in logs.iter().enumerate() {
+            while stack.len() as u64 > log.depth.max(1) {
+                stack.pop();
+            }
+            if stack.len() as u64 != log.depth.max(1) {
+                let address = match logs[..i].last() {
+                    Some(call) => call_target(call),
+                    None => root,
+                };
+                frames.push(FrameGas { depth: log.depth.max(1), address, gas: 0 });
+                stack.push(frames.len() - 1);
+            }
+
+            let mut cost = log.gas_cost;
+            if let Some(next) = logs.get(i + 1).filter(|next| next.depth > log.depth) {
+                if cost >= next.gas {
+                    cost -= next.gas;
+                }
+            }
+
+            let entry = opcodes.entry(log.op.as_str()).or_default();
+            entry.count += 1;
+            entry.gas += cost;
+            if let Some(&frame) = stack.last() {
+                frames[frame].gas += cost;
+            }
+        }
+
+        let mut opcodes = opcodes
+            .into_iter()
+            .map(|(opcode, gas)| OpcodeGas { opcode: opcode.to_string(), ..gas })
+            .collect::<Vec<_>>();
+        opcodes.sort_by(|a, b| b.gas.cmp(&a.gas).then_with(|| a.opcode.cmp(&b.opcode)));
+        let execution_gas = opcodes.iter().map(|op| op.gas).sum();
+
+        Self { gas_used, execution_gas, opcodes, frames }
+    }
+}
+
+impl std::fmt::Display for GasProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let share = |gas: u64| {
+            if self.execution_gas == 0 {
+                0.0
+            } else {
+                gas as f64 * 100.0 / self.execution_gas as f64
+            }
+        };
+
+        writeln!(f, "Gas used: {} (execution: {})", self.gas_used, self.execution_gas)?;
+
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["Opcode", "Count", "Gas", "%"]);
+        for op in &self.opcodes {
+            let opcode = if STORAGE_OPCODES.contains(&op.opcode.as_str()) {
+                Paint::yellow(&op.opcode).bold().to_string()
+            } else {
+                op.opcode.clone()
+            };
+            table.add_row([
+                opcode,
+                op.count.to_string(),
+                op.gas.to_string(),
+                format!("{:.2}", share(op.gas)),
+            ]);
+        }
+        writeln!(f, "\n{table}")?;
+
+        let storage_gas = self
+            .opcodes
+            .iter()
+            .filter(|op| STORAGE_OPCODES.contains(&op.opcode.as_str()))
+            .map(|op| op.gas)
+            .sum::<u64>();
+        if storage_gas > 0 {
+            writeln!(
+                f,
+                "\n{} SLOAD/SSTORE used {storage_gas} gas ({:.2}% of execution)",
+                Paint::yellow("Storage:").bold(),
+                share(storage_gas)
+            )?;
+        }
+
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["Frame", "Gas", "%"]);
+        for frame in &self.frames {
+            let indent = "  ".repeat(frame.depth.saturating_sub(1) as usize);
+            table.add_row([
+                format!("{indent}{:?}", frame.address),
+                frame.gas.to_string(),
+                format!("{:.2}", share(frame.gas)),
+            ]);
+        }
+        write!(f, "\n{table}")
+    }
+}
+
+/// Returns the address called by a `CALL`-like instruction, or the zero address for creations.
+fn call_target(log: &StructLog) -> Address {
+    let stack = log.stack.as_deref().unwrap_or_default();
+    match log.op.as_str() {
+        // the address is the second item from the top of the stack, after the gas
+        "CALL" | "CALLCODE" | "DELEGATECALL" | "STATICCALL" if stack.len() >= 2 => {
+            let mut word = [0u8; 32];
+            stack[stack.len() - 2].to_big_endian(&mut word);
+            Address::from_slice(&word[12..])
+        }
+        _ => Address::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::U256;
+
+    fn log(op: &str, depth: u64, gas: u64, gas_cost: u64, stack: Vec<U256>) -> StructLog {
+        StructLog {
+            depth,
+            error: None,
+            gas,
+            gas_cost,
+            memory: None,
+            op: op.to_string(),
+            pc: 0,
+            refund_counter: None,
+            stack: Some(stack),
+            storage: None,
+            return_data: None,
+            mem_size: None,
+        }
+    }
+
+    #[test]
+    fn profiles_opcodes_and_frames() {
+        let root = Address::repeat_byte(1);
+        let callee = Address::repeat_byte(2);
+        let callee_word = U256::from_big_endian(callee.as_bytes());
+        let logs = vec![
+            log("SLOAD", 1, 10_000, 2100, vec![]),
+            // forwards 5000 gas to the callee
+            log("CALL", 1, 7_900, 5_100, vec![callee_word, U256::from(5_000)]),
+            log("SSTORE", 2, 5_000, 2_900, vec![]),
+            log("STOP", 2, 2_100, 0, vec![]),
+            log("STOP", 1, 4_700, 0, vec![]),
+        ];
+
+        let profile = GasProfile::new(&logs, root, 30_000);
+        assert_eq!(profile.execution_gas, 2100 + 100 + 2900);
+        assert_eq!(
+            profile.opcodes[..3],
+            [
+                OpcodeGas { opcode: "SSTORE".to_string(), count: 1, gas: 2900 },
+                OpcodeGas { opcode: "SLOAD".to_string(), count: 1, gas: 2100 },
+                OpcodeGas { opcode: "CALL".to_string(), count: 1, gas: 100 },
+            ]
+        );
+        assert_eq!(
+            profile.frames,
+            [
+                FrameGas { depth: 1, address: root, gas: 2200 },
+                FrameGas { depth: 2, address: callee, gas: 2900 },
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use cast::Cast;
+use cast::{checksum::parse_name_or_address, Cast};
 use clap::Parser;
 use ethers_core::{
     abi::{
@@ -34,7 +34,7 @@ pub struct LogsArgs {
     /// The contract address to filter on.
     #[clap(
         long,
-        value_parser = NameOrAddress::from_str
+        value_parser = parse_name_or_address
     )]
     address: Option<NameOrAddress>,
 
@@ -5,16 +5,31 @@
 //! implement `figment::Provider` which allows the subcommand to override the config's defaults, see
 //! [`foundry_config::Config`].
 
+pub mod abi_diff;
 pub mod access_list;
+pub mod balance;
 pub mod bind;
+pub mod bytecode_diff;
 pub mod call;
+pub mod completions;
+pub mod constructor_encode;
 pub mod create2;
+pub mod ens;
 pub mod estimate;
 pub mod find_block;
+pub mod gas_used;
 pub mod interface;
 pub mod logs;
+pub mod nonce;
+pub mod prettify_abi;
 pub mod rpc;
 pub mod run;
+pub mod safe;
 pub mod send;
+pub mod sig_collision;
+pub mod signatures;
+pub mod status;
 pub mod storage;
+pub mod token;
+pub mod trace_filter;
 pub mod wallet;
@@ -0,0 +1,336 @@
+use cast::{checksum::parse_name_or_address, Cast};
+use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use ethers_core::{
+    types::{Address, BlockId, BlockNumber, NameOrAddress, Transaction, H256, U256},
+    utils::format_units,
+};
+use ethers_providers::Middleware;
+use eyre::Result;
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_config::Config;
+use yansi::Paint;
+
+/// The minimum fee bump, in percent, of a replacement transaction accepted by geth's mempool.
+const REPLACEMENT_FEE_BUMP_PERCENT: u64 = 10;
+
+/// CLI arguments for `cast nonce`.
+#[derive(Debug, Clone, Parser)]
+pub struct NonceArgs {
+    /// The block height to query at.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[clap(long, short = 'B')]
+    block: Option<BlockId>,
+
+    /// Get the nonce including the transactions in the mempool, the same as `--block pending`.
+    #[clap(long, conflicts_with = "block")]
+    pending: bool,
+
+    /// Diagnose stuck transactions.
+    ///
+    /// Compares the latest and the pending nonce, lists the transactions of the account in the
+    /// mempool with their fees, and suggests the fees to replace the stuck ones with. Requires a
+    /// node that supports `txpool_content`.
+    #[clap(long, conflicts_with_all = &["block", "pending"])]
+    diagnose: bool,
+
+    /// The address to get the nonce for.
+    #[clap(value_parser = parse_name_or_address)]
+    who: NameOrAddress,
+
+    #[clap(flatten)]
+    rpc: RpcOpts,
+}
+
+impl NonceArgs {
+    pub async fn run(self) -> Result<()> {
+        let NonceArgs { block, pending, diagnose, who, rpc } = self;
+        let config = Config::from(&rpc);
+        let provider = utils::get_provider(&config)?;
+
+        if !diagnose {
+            let block = if pending { Some(BlockNumber::Pending.into()) } else { block };
+            println!("{}", Cast::new(provider).nonce(who, block).await?);
+            return Ok(())
+        }
+
+        let address = match who {
+            NameOrAddress::Name(name) => provider.resolve_name(&name).await?,
+            NameOrAddress::Address(address) => address,
+        };
+        let latest = provider.get_transaction_count(address, None).await?.as_u64();
+        let pending = provider
+            .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+            .await?
+            .as_u64();
+
+        let content = provider.txpool_content().await.map_err(|err| {
+            eyre::eyre!("failed to get the mempool, the node must support `txpool_content`: {err}")
+        })?;
+        let mut txs = Vec::new();
+        for (pool, queued) in [(&content.pending, false), (&content.queued, true)] {
+            let Some(account_txs) = pool.get(&address) else { continue };
+            txs.extend(account_txs.values().map(|tx| PendingTx::new(tx, queued)));
+        }
+
+        let block = provider
+            .get_block(BlockNumber::Latest)
+            .await?
+            .ok_or_else(|| eyre::eyre!("latest block not found"))?;
+        let fees = NetworkFees {
+            gas_price: provider.get_gas_price().await?,
+            base_fee: block.base_fee_per_gas,
+        };
+
+        println!("{}", NonceDiagnosis::new(address, latest, pending, txs, fees));
+        Ok(())
+    }
+}
+
+/// The current fee levels of the network.
+#[derive(Clone, Copy, Debug, Default)]
+struct NetworkFees {
+    /// The gas price suggested by `eth_gasPrice`.
+    gas_price: U256,
+    /// The base fee of the latest block, on EIP-1559 chains.
+    base_fee: Option<U256>,
+}
+
+/// A transaction of the account in the mempool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PendingTx {
+    hash: H256,
+    nonce: u64,
+    /// The gas price of a legacy transaction, or the max fee of an EIP-1559 transaction.
+    max_fee: U256,
+    /// The max priority fee of an EIP-1559 transaction.
+    max_priority_fee: Option<U256>,
+    /// Whether the transaction is queued behind a nonce gap, instead of executable.
+    queued: bool,
+}
+
+impl PendingTx {
+    fn new(tx: &Transaction, queued: bool) -> Self {
+        Self {
+            hash: tx.hash,
+            nonce: tx.nonce.as_u64(),
+            max_fee: tx.max_fee_per_gas.or(tx.gas_price).unwrap_or_default(),
+            max_priority_fee: tx.max_priority_fee_per_gas,
+            queued,
+        }
+    }
+
+    /// Returns the fees that a replacement of this transaction needs: the replaced fees bumped by
+    /// [REPLACEMENT_FEE_BUMP_PERCENT], and at least the current network fees.
+    fn replacement_fees(&self, fees: &NetworkFees) -> (U256, Option<U256>) {
+        let bump = |fee: U256| {
+            let bumped = fee * (100 + REPLACEMENT_FEE_BUMP_PERCENT);
+            (bumped + 99) / 100
+        };
+        match (self.max_priority_fee, fees.base_fee) {
+            (Some(priority_fee), Some(base_fee)) => {
+                let priority_fee = bump(priority_fee).max(fees.gas_price.saturating_sub(base_fee));
+                let max_fee = bump(self.max_fee).max(base_fee * 2 + priority_fee);
+                (max_fee, Some(priority_fee))
+            }
+            _ => (bump(self.max_fee).max(fees.gas_price), None),
+        }
+    }
+}
+
+/// The nonces and mempool transactions of an account, with suggestions to unstick them.
+#[derive(Clone, Debug)]
+struct NonceDiagnosis {
+    address: Address,
+    latest: u64,
+    pending: u64,
+    /// The transactions in the mempool, by nonce.
+    txs: Vec<PendingTx>,
+    fees: NetworkFees,
+}
+
+impl NonceDiagnosis {
+    fn new(
+        address: Address,
+        latest: u64,
+        pending: u64,
+        mut txs: Vec<PendingTx>,
+        fees: NetworkFees,
+    ) -> Self {
+        txs.sort_by_key(|tx| tx.nonce);
+        Self { address, latest, pending, txs, fees }
+    }
+
+    /// Returns whether the transaction can't be included at the current base fee.
+    fn is_underpriced(&self, tx: &PendingTx) -> bool {
+        tx.max_fee < self.fees.base_fee.unwrap_or(self.fees.gas_price)
+    }
+
+    /// Returns whether the fees of the transaction are below the gas price suggested by the node,
+    /// so it's likely outbid by other transactions.
+    fn is_outbid(&self, tx: &PendingTx) -> bool {
+        match (tx.max_priority_fee, self.fees.base_fee) {
+            (Some(priority_fee), Some(base_fee)) => {
+                priority_fee < self.fees.gas_price.saturating_sub(base_fee)
+            }
+            _ => tx.max_fee < self.fees.gas_price,
+        }
+    }
+
+    /// Returns the nonces that are missing before the queued transactions.
+    fn nonce_gaps(&self) -> Vec<u64> {
+        let Some(last) = self.txs.iter().map(|tx| tx.nonce).max() else { return Vec::new() };
+        (self.latest..last).filter(|nonce| self.txs.iter().all(|tx| tx.nonce != *nonce)).collect()
+    }
+
+    /// Returns the suggestions to unstick the transactions.
+    fn suggestions(&self) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        for nonce in self.nonce_gaps() {
+            suggestions.push(format!(
+                "nonce {nonce} is missing, so the transactions after it are queued: send a \
+                 transaction with `--nonce {nonce}`"
+            ));
+        }
+
+        // the first transaction blocks all others, so it's the one to replace
+        if let Some(tx) = self.txs.iter().find(|tx| tx.nonce == self.latest) {
+            if self.is_underpriced(tx) || self.is_outbid(tx) {
+                let (max_fee, priority_fee) = tx.replacement_fees(&self.fees);
+                let flags = match priority_fee {
+                    Some(priority_fee) => {
+                        format!("--gas-price {max_fee} --priority-gas-price {priority_fee}")
+                    }
+                    None => format!("--legacy --gas-price {max_fee}"),
+                };
+                let reason = if self.is_underpriced(tx) {
+                    "its max fee is below the current base fee"
+                } else {
+                    "its fees are below the current gas price"
+                };
+                suggestions.push(format!(
+                    "the transaction with nonce {} is stuck, {reason}: replace it with `cast send \
+                     --nonce {} {flags} ...`, or cancel it by sending 0 ETH to yourself with the \
+                     same flags",
+                    tx.nonce, tx.nonce
+                ));
+            }
+        }
+        suggestions
+    }
+}
+
+impl std::fmt::Display for NonceDiagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Latest nonce:  {}", self.latest)?;
+        writeln!(f, "Pending nonce: {}", self.pending)?;
+        let base_fee = self.fees.base_fee.map(gwei).unwrap_or_else(|| "-".to_string());
+        writeln!(f, "Gas price:     {} (base fee: {base_fee})", gwei(self.fees.gas_price))?;
+
+        if self.txs.is_empty() {
+            return write!(f, "\nNo transactions of {:?} in the mempool", self.address)
+        }
+
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["Nonce", "Hash", "Max fee", "Priority fee", "Status"]);
+        for tx in &self.txs {
+            let status = if tx.queued {
+                Paint::yellow("queued").to_string()
+            } else if self.is_underpriced(tx) {
+                Paint::red("underpriced").to_string()
+            } else {
+                "pending".to_string()
+            };
+            table.add_row([
+                tx.nonce.to_string(),
+                format!("{:?}", tx.hash),
+                gwei(tx.max_fee),
+                tx.max_priority_fee.map(gwei).unwrap_or_else(|| "-".to_string()),
+                status,
+            ]);
+        }
+        write!(f, "\n{table}")?;
+
+        let suggestions = self.suggestions();
+        if !suggestions.is_empty() {
+            write!(f, "\n\n{}", Paint::new("Suggestions:").bold())?;
+            for suggestion in suggestions {
+                write!(f, "\n  - {suggestion}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats the wei amount in gwei, without trailing zeros.
+fn gwei(wei: U256) -> String {
+    let gwei = format_units(wei, "gwei").unwrap_or_default();
+    let gwei = if gwei.contains('.') {
+        gwei.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &gwei[..]
+    };
+    format!("{gwei} gwei")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u64 = 1_000_000_000;
+
+    fn tx(nonce: u64, max_fee: u64, max_priority_fee: Option<u64>, queued: bool) -> PendingTx {
+        PendingTx {
+            hash: H256::from_low_u64_be(nonce),
+            nonce,
+            max_fee: U256::from(max_fee * GWEI),
+            max_priority_fee: max_priority_fee.map(|fee| U256::from(fee * GWEI)),
+            queued,
+        }
+    }
+
+    #[test]
+    fn suggests_replacement_of_underpriced_tx() {
+        let fees =
+            NetworkFees { gas_price: U256::from(32 * GWEI), base_fee: Some(U256::from(30 * GWEI)) };
+        let diagnosis = NonceDiagnosis::new(
+            Address::zero(),
+            5,
+            7,
+            vec![tx(6, 40, Some(2), false), tx(5, 20, Some(1), false)],
+            fees,
+        );
+        assert_eq!(diagnosis.txs[0].nonce, 5);
+        assert!(diagnosis.is_underpriced(&diagnosis.txs[0]));
+        assert!(diagnosis.nonce_gaps().is_empty());
+
+        let (max_fee, priority_fee) = diagnosis.txs[0].replacement_fees(&fees);
+        assert_eq!(priority_fee, Some(U256::from(2 * GWEI)));
+        assert_eq!(max_fee, U256::from(62 * GWEI));
+
+        let suggestions = diagnosis.suggestions();
+        assert_eq!(suggestions.len(), 1);
+        assert!(suggestions[0].contains("--nonce 5"), "{suggestions:?}");
+        assert!(suggestions[0].contains("below the current base fee"), "{suggestions:?}");
+    }
+
+    #[test]
+    fn detects_nonce_gaps() {
+        let fees = NetworkFees { gas_price: U256::from(10 * GWEI), base_fee: None };
+        let diagnosis =
+            NonceDiagnosis::new(Address::zero(), 3, 3, vec![tx(5, 20, None, true)], fees);
+        assert_eq!(diagnosis.nonce_gaps(), [3, 4]);
+        assert_eq!(diagnosis.suggestions().len(), 2);
+
+        let (gas_price, priority_fee) = diagnosis.txs[0].replacement_fees(&fees);
+        assert_eq!((gas_price, priority_fee), (U256::from(22 * GWEI), None));
+    }
+
+    #[test]
+    fn formats_gwei() {
+        assert_eq!(gwei(U256::from(1_500_000_000u64)), "1.5 gwei");
+        assert_eq!(gwei(U256::from(2 * GWEI)), "2 gwei");
+    }
+}
@@ -0,0 +1,52 @@
+use cast::human_abi::{from_human_readable, is_json_abi, to_human_readable};
+use clap::Parser;
+use eyre::Result;
+use foundry_cli::stdin;
+use foundry_common::fs;
+use std::path::Path;
+
+/// CLI arguments for `cast prettify-abi`.
+#[derive(Debug, Clone, Parser)]
+pub struct PrettifyAbiArgs {
+    /// The ABI to convert, or the path to a file containing it.
+    ///
+    /// JSON ABIs, and artifacts containing one, are converted to a human-readable ABI. A
+    /// human-readable ABI, one declaration per line or as a JSON array of strings, is converted to
+    /// a JSON ABI.
+    ///
+    /// If not specified, the ABI is read from stdin.
+    abi: Option<String>,
+
+    /// Print the JSON ABI on a single line.
+    #[clap(long)]
+    compact: bool,
+}
+
+impl PrettifyAbiArgs {
+    pub fn run(self) -> Result<()> {
+        let PrettifyAbiArgs { abi, compact } = self;
+        let input = match abi {
+            Some(abi) if Path::new(&abi).is_file() => fs::read_to_string(abi)?,
+            Some(abi) => abi,
+            None => stdin::read(false)?,
+        };
+
+        if is_json_abi(&input) {
+            for declaration in to_human_readable(&input)? {
+                println!("{declaration}");
+            }
+            return Ok(())
+        }
+
+        let abi = match serde_json::from_str::<Vec<String>>(&input) {
+            Ok(declarations) => from_human_readable(declarations.iter().map(String::as_str))?,
+            Err(_) => from_human_readable(input.lines())?,
+        };
+        if compact {
+            println!("{}", serde_json::to_string(&abi)?);
+        } else {
+            println!("{}", serde_json::to_string_pretty(&abi)?);
+        }
+        Ok(())
+    }
+}
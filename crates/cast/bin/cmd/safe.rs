@@ -0,0 +1,548 @@
+use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::{b256, hex, keccak256, Address, Bytes, B256, U256};
+use cast::checksum::parse_address;
+use clap::Parser;
+use ethers_core::types::{RecoveryMessage, Signature, H256};
+use eyre::{Result, WrapErr};
+use foundry_cli::opts::ChainValueParser;
+use foundry_common::types::ToAlloy;
+use foundry_config::Chain;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::Value;
+use std::fmt::Write;
+
+/// `keccak256("EIP712Domain(uint256 chainId,address verifyingContract)")`, used from Safe 1.3.0.
+const DOMAIN_SEPARATOR_TYPEHASH: B256 =
+    b256!("47e79534a245952e8b16893a336b85a3d9ea9fa8c573f3d803afb92a79469218");
+
+/// `keccak256("EIP712Domain(address verifyingContract)")`, used before Safe 1.3.0.
+const LEGACY_DOMAIN_SEPARATOR_TYPEHASH: B256 =
+    b256!("035aff83d86937d35b32e04f0ddc6ff469290eef2f1b692d8a815c89404d4749");
+
+/// `keccak256("SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,
+/// uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256 nonce)")`
+const SAFE_TX_TYPEHASH: B256 =
+    b256!("bb8310d486368db6bd6f849402fdd73ad53d316b5a4b2644ad6efe0f941286d8");
+
+/// The network names of the official Safe Transaction Service, by chain ID.
+const SERVICE_NETWORKS: &[(u64, &str)] = &[
+    (1, "mainnet"),
+    (5, "goerli"),
+    (10, "optimism"),
+    (56, "bsc"),
+    (100, "gnosis-chain"),
+    (137, "polygon"),
+    (324, "zksync"),
+    (1101, "zkevm"),
+    (8453, "base"),
+    (42161, "arbitrum"),
+    (42220, "celo"),
+    (43114, "avalanche"),
+    (59144, "linea"),
+    (84532, "base-sepolia"),
+    (11155111, "sepolia"),
+];
+
+/// CLI arguments for `cast safe`.
+#[derive(Debug, Parser)]
+pub enum SafeSubcommands {
+    /// Decode and pretty-print a Safe transaction from the Safe Transaction Service.
+    ///
+    /// Shows the decoded calls, including the calls batched with `multiSend`, whether the hash of
+    /// the transaction matches its contents, and who confirmed it so far.
+    #[clap(visible_alias = "d")]
+    Decode {
+        /// The hash of the Safe transaction, `safeTxHash`.
+        safe_tx_hash: B256,
+
+        /// The address of the Safe.
+        #[clap(long, value_name = "ADDRESS", value_parser = parse_address)]
+        safe: Address,
+
+        /// The chain of the Safe.
+        #[clap(
+            long,
+            short,
+            env = "CHAIN",
+            default_value = "mainnet",
+            value_parser = ChainValueParser::default()
+        )]
+        chain: Chain,
+
+        /// The URL of the Safe Transaction Service, defaults to the official one of the chain.
+        #[clap(long, value_name = "URL")]
+        service_url: Option<String>,
+
+        /// Print the transaction as returned by the service, as JSON.
+        #[clap(long, short)]
+        json: bool,
+    },
+}
+
+impl SafeSubcommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            SafeSubcommands::Decode { safe_tx_hash, safe, chain, service_url, json } => {
+                let service_url = match service_url {
+                    Some(url) => url,
+                    None => service_url_of(chain.id())?,
+                };
+                let service = SafeService::new(service_url);
+
+                if json {
+                    let tx: Value = service.transaction(safe_tx_hash).await?;
+                    println!("{}", serde_json::to_string_pretty(&tx)?);
+                    return Ok(())
+                }
+
+                let tx: SafeTransaction = service.transaction(safe_tx_hash).await?;
+                eyre::ensure!(
+                    tx.safe == safe,
+                    "the transaction belongs to the Safe {}, not {}",
+                    tx.safe.to_checksum(None),
+                    safe.to_checksum(None)
+                );
+                let info = service.safe(safe).await?;
+                print!("{}", tx.pretty(safe_tx_hash, chain.id(), &info));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the URL of the official Safe Transaction Service of a chain.
+fn service_url_of(chain: u64) -> Result<String> {
+    let network = SERVICE_NETWORKS
+        .iter()
+        .find(|(id, _)| *id == chain)
+        .map(|(_, network)| *network)
+        .ok_or_else(|| {
+            eyre::eyre!("there is no Safe Transaction Service for chain {chain}, use --service-url")
+        })?;
+    Ok(format!("https://safe-transaction-{network}.safe.global"))
+}
+
+/// A client of the Safe Transaction Service.
+struct SafeService {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl SafeService {
+    fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url: url.trim_end_matches('/').to_string() }
+    }
+
+    async fn transaction<T: DeserializeOwned>(&self, safe_tx_hash: B256) -> Result<T> {
+        self.get(&format!("/api/v1/multisig-transactions/{safe_tx_hash}/")).await
+    }
+
+    async fn safe(&self, safe: Address) -> Result<SafeInfo> {
+        self.get(&format!("/api/v1/safes/{}/", safe.to_checksum(None))).await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{path}", self.url);
+        let res = self.client.get(&url).send().await?;
+        if res.status() == reqwest::StatusCode::NOT_FOUND {
+            eyre::bail!("not found on the Safe Transaction Service: {url}");
+        }
+        res.error_for_status()?
+            .json()
+            .await
+            .wrap_err_with(|| format!("Failed to decode the response of {url}"))
+    }
+}
+
+/// The owners and threshold of a Safe.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeInfo {
+    threshold: u64,
+    owners: Vec<Address>,
+    version: Option<String>,
+}
+
+/// A Safe transaction, as returned by the Safe Transaction Service.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeTransaction {
+    safe: Address,
+    to: Address,
+    #[serde(deserialize_with = "deserialize_u256")]
+    value: U256,
+    data: Option<Bytes>,
+    operation: u8,
+    #[serde(deserialize_with = "deserialize_u256")]
+    safe_tx_gas: U256,
+    #[serde(deserialize_with = "deserialize_u256")]
+    base_gas: U256,
+    #[serde(deserialize_with = "deserialize_u256")]
+    gas_price: U256,
+    gas_token: Address,
+    refund_receiver: Address,
+    nonce: u64,
+    is_executed: bool,
+    is_successful: Option<bool>,
+    transaction_hash: Option<B256>,
+    confirmations_required: Option<u64>,
+    #[serde(default)]
+    confirmations: Vec<Confirmation>,
+    data_decoded: Option<Value>,
+}
+
+/// A confirmation of a Safe transaction by an owner.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Confirmation {
+    owner: Address,
+    signature: Option<Bytes>,
+}
+
+impl SafeTransaction {
+    /// Returns the EIP-712 hash of the transaction, which the owners sign.
+    fn hash(&self, chain: u64, version: Option<&str>) -> B256 {
+        let legacy = version
+            .and_then(|version| semver::Version::parse(version).ok())
+            .map_or(false, |version| version < semver::Version::new(1, 3, 0));
+        let domain = if legacy {
+            DynSolValue::Tuple(vec![LEGACY_DOMAIN_SEPARATOR_TYPEHASH.into(), self.safe.into()])
+        } else {
+            DynSolValue::Tuple(vec![
+                DOMAIN_SEPARATOR_TYPEHASH.into(),
+                U256::from(chain).into(),
+                self.safe.into(),
+            ])
+        };
+        let domain_separator = keccak256(domain.abi_encode());
+
+        let data = self.data.clone().unwrap_or_default();
+        let safe_tx = DynSolValue::Tuple(vec![
+            SAFE_TX_TYPEHASH.into(),
+            self.to.into(),
+            self.value.into(),
+            keccak256(&data).into(),
+            DynSolValue::Uint(U256::from(self.operation), 8),
+            self.safe_tx_gas.into(),
+            self.base_gas.into(),
+            self.gas_price.into(),
+            self.gas_token.into(),
+            self.refund_receiver.into(),
+            U256::from(self.nonce).into(),
+        ]);
+        let struct_hash = keccak256(safe_tx.abi_encode());
+
+        let mut preimage = Vec::with_capacity(66);
+        preimage.extend_from_slice(&[0x19, 0x01]);
+        preimage.extend_from_slice(domain_separator.as_slice());
+        preimage.extend_from_slice(struct_hash.as_slice());
+        keccak256(preimage)
+    }
+
+    /// Formats the transaction for review.
+    fn pretty(&self, safe_tx_hash: B256, chain: u64, info: &SafeInfo) -> String {
+        let mut s = String::new();
+        let data = self.data.clone().unwrap_or_default();
+        let _ = writeln!(s, "Safe transaction {safe_tx_hash}\n");
+        let _ = writeln!(s, "safe          {}", self.safe.to_checksum(None));
+        let _ = writeln!(s, "nonce         {}", self.nonce);
+        let _ = writeln!(s, "to            {}", self.to.to_checksum(None));
+        let _ = writeln!(s, "value         {}", self.value);
+        let _ = writeln!(s, "operation     {}", operation_name(self.operation));
+        let _ = writeln!(s, "data          {} ({} bytes)", selector_of(&data), data.len());
+        if !self.gas_token.is_zero() || !self.gas_price.is_zero() {
+            let _ = writeln!(
+                s,
+                "refund        gas price {} in {}, to {}",
+                self.gas_price,
+                self.gas_token.to_checksum(None),
+                self.refund_receiver.to_checksum(None)
+            );
+        }
+
+        if let Some(decoded) = &self.data_decoded {
+            let _ = writeln!(s, "\nDecoded call:");
+            write_decoded(&mut s, decoded, 1);
+        } else if !data.is_empty() {
+            let _ = writeln!(s, "\nThe service couldn't decode the calldata:\n  {data}");
+        }
+
+        let computed = self.hash(chain, info.version.as_deref());
+        let _ = writeln!(s);
+        if computed == safe_tx_hash {
+            let _ = writeln!(s, "hash          matches the transaction");
+        } else {
+            let _ = writeln!(
+                s,
+                "hash          MISMATCH, the transaction hashes to {computed}; don't sign it"
+            );
+        }
+
+        let status = match (self.is_executed, self.is_successful, self.transaction_hash) {
+            (true, Some(false), Some(hash)) => format!("failed in {hash}"),
+            (true, _, Some(hash)) => format!("executed in {hash}"),
+            (true, _, None) => "executed".to_string(),
+            (false, ..) => "pending".to_string(),
+        };
+        let _ = writeln!(s, "status        {status}");
+
+        // only ECDSA signatures are verified here, contract signatures and approved hashes are
+        // checked on chain on execution
+        let threshold = self.confirmations_required.unwrap_or(info.threshold);
+        let owner_statuses = self
+            .confirmations
+            .iter()
+            .filter(|confirmation| info.owners.contains(&confirmation.owner))
+            .map(|confirmation| confirmation.verify(safe_tx_hash))
+            .collect::<Vec<_>>();
+        let valid =
+            owner_statuses.iter().filter(|status| **status == SignatureStatus::Valid).count();
+        let unverified = owner_statuses
+            .iter()
+            .filter(|status| {
+                matches!(status, SignatureStatus::Contract | SignatureStatus::ApprovedHash)
+            })
+            .count();
+        let ready = if valid as u64 >= threshold {
+            " (ready to execute)".to_string()
+        } else if unverified > 0 {
+            format!(" (+{unverified} unverified)")
+        } else {
+            String::new()
+        };
+        let _ = writeln!(s, "confirmations {valid}/{threshold}{ready}");
+        for confirmation in &self.confirmations {
+            let owner =
+                if info.owners.contains(&confirmation.owner) { "" } else { ", NOT AN OWNER" };
+            let _ = writeln!(
+                s,
+                "  {} {}{owner}",
+                confirmation.owner.to_checksum(None),
+                confirmation.verify(safe_tx_hash)
+            );
+        }
+        s
+    }
+}
+
+impl Confirmation {
+    /// Checks the signature of the owner, see the `checkNSignatures` function of the Safe.
+    fn verify(&self, safe_tx_hash: B256) -> SignatureStatus {
+        let Some(signature) = &self.signature else { return SignatureStatus::Missing };
+        let Ok(signature) = Signature::try_from(signature.as_ref()) else {
+            return SignatureStatus::Invalid(None)
+        };
+        let hash = H256::from(safe_tx_hash.0);
+        let (v, message) = match signature.v {
+            0 => return SignatureStatus::Contract,
+            1 => return SignatureStatus::ApprovedHash,
+            // `eth_sign` signatures have 4 added to `v`
+            v if v > 30 => (v - 4, RecoveryMessage::Data(hash.as_bytes().to_vec())),
+            v => (v, RecoveryMessage::Hash(hash)),
+        };
+        let signature = Signature { v, ..signature };
+        match signature.recover(message) {
+            Ok(signer) if signer.to_alloy() == self.owner => SignatureStatus::Valid,
+            Ok(signer) => SignatureStatus::Invalid(Some(signer.to_alloy())),
+            Err(_) => SignatureStatus::Invalid(None),
+        }
+    }
+}
+
+/// The status of the signature of a confirmation.
+#[derive(Debug, PartialEq, Eq)]
+enum SignatureStatus {
+    Valid,
+    /// The signature is invalid, or recovers to another address.
+    Invalid(Option<Address>),
+    /// A contract signature, which is checked by the owner contract on execution and not verified
+    /// here.
+    Contract,
+    /// The owner claims to have approved the hash on-chain with `approveHash`, which is checked on
+    /// execution and not verified here.
+    ApprovedHash,
+    Missing,
+}
+
+impl std::fmt::Display for SignatureStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Valid => f.write_str("valid signature"),
+            Self::Invalid(Some(signer)) => {
+                write!(f, "INVALID signature, signed by {}", signer.to_checksum(None))
+            }
+            Self::Invalid(None) => f.write_str("INVALID signature"),
+            Self::Contract => f.write_str("unverified contract signature, checked on execution"),
+            Self::ApprovedHash => f.write_str("unverified on-chain approval, checked on execution"),
+            Self::Missing => f.write_str("no signature"),
+        }
+    }
+}
+
+/// Writes the calldata decoded by the service, including the nested calls of `multiSend`.
+fn write_decoded(s: &mut String, decoded: &Value, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let method = decoded.get("method").and_then(Value::as_str).unwrap_or("<unknown>");
+    let parameters =
+        decoded.get("parameters").and_then(Value::as_array).cloned().unwrap_or_default();
+    let _ = writeln!(s, "{indent}{method}(");
+    for param in &parameters {
+        let name = param.get("name").and_then(Value::as_str).unwrap_or_default();
+        let ty = param.get("type").and_then(Value::as_str).unwrap_or_default();
+        let value = match param.get("value") {
+            Some(Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+
+        // the batched calls of `multiSend`
+        if let Some(calls) = param.get("valueDecoded").and_then(Value::as_array) {
+            let _ = writeln!(s, "{indent}  {name} ({ty}): {} calls", calls.len());
+            for (idx, call) in calls.iter().enumerate() {
+                let operation = call.get("operation").and_then(Value::as_u64).unwrap_or_default();
+                let to = call.get("to").and_then(Value::as_str).unwrap_or_default();
+                let value = call.get("value").and_then(Value::as_str).unwrap_or("0");
+                let _ = writeln!(
+                    s,
+                    "{indent}    [{idx}] {} to {to}, value {value}",
+                    operation_name(operation as u8)
+                );
+                match call.get("dataDecoded") {
+                    Some(decoded) if !decoded.is_null() => write_decoded(s, decoded, depth + 3),
+                    _ => {
+                        let data = call.get("data").and_then(Value::as_str).unwrap_or_default();
+                        if !data.is_empty() && data != "0x" {
+                            let _ = writeln!(s, "{indent}      {data}");
+                        }
+                    }
+                }
+            }
+        } else {
+            let _ = writeln!(s, "{indent}  {name} ({ty}): {value}");
+        }
+    }
+    let _ = writeln!(s, "{indent})");
+}
+
+fn operation_name(operation: u8) -> &'static str {
+    match operation {
+        0 => "call",
+        1 => "DELEGATECALL",
+        _ => "unknown operation",
+    }
+}
+
+fn selector_of(data: &[u8]) -> String {
+    match data.get(..4) {
+        Some(selector) => hex::encode_prefixed(selector),
+        None if data.is_empty() => "empty".to_string(),
+        None => hex::encode_prefixed(data),
+    }
+}
+
+/// Deserializes a number the service returns either as a string or as a number.
+fn deserialize_u256<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        Value::Number(n) => n.to_string().parse().map_err(serde::de::Error::custom),
+        Value::Null => Ok(U256::ZERO),
+        value => Err(serde::de::Error::custom(format!("expected a number, got {value}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction() -> SafeTransaction {
+        serde_json::from_value(serde_json::json!({
+            "safe": "0x5afe3855358e112b5647b952709e6165e1c1eeee",
+            "to": "0x6b175474e89094c44da98b954eedeac495271d0f",
+            "value": "0",
+            "data": "0x095ea7b3000000000000000000000000000000000000000000000000000000000000beef0000000000000000000000000000000000000000000000000000000000000064",
+            "operation": 0,
+            "safeTxGas": 0,
+            "baseGas": 0,
+            "gasPrice": "0",
+            "gasToken": "0x0000000000000000000000000000000000000000",
+            "refundReceiver": "0x0000000000000000000000000000000000000000",
+            "nonce": 7,
+            "isExecuted": false,
+            "isSuccessful": null,
+            "transactionHash": null,
+            "confirmationsRequired": 2,
+            "confirmations": [],
+            "dataDecoded": {
+                "method": "approve",
+                "parameters": [
+                    {"name": "spender", "type": "address", "value": "0x000000000000000000000000000000000000bEEF"},
+                    {"name": "amount", "type": "uint256", "value": "100"}
+                ]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn can_hash_safe_transactions() {
+        let tx = transaction();
+        // the hash only depends on the version of the domain separator
+        assert_eq!(tx.hash(1, Some("1.3.0")), tx.hash(1, None));
+        assert_ne!(tx.hash(1, Some("1.3.0")), tx.hash(5, Some("1.3.0")));
+        assert_ne!(tx.hash(1, Some("1.3.0")), tx.hash(1, Some("1.2.0")));
+    }
+
+    #[test]
+    fn can_verify_confirmations() {
+        let wallet: ethers_signers::LocalWallet =
+            "0000000000000000000000000000000000000000000000000000000000000001".parse().unwrap();
+        let owner = ethers_signers::Signer::address(&wallet).to_alloy();
+        let hash = transaction().hash(1, None);
+
+        let signature = wallet.sign_hash(H256::from(hash.0)).unwrap();
+        let confirmation = Confirmation { owner, signature: Some(signature.to_vec().into()) };
+        assert_eq!(confirmation.verify(hash), SignatureStatus::Valid);
+        assert!(matches!(confirmation.verify(B256::ZERO), SignatureStatus::Invalid(_)));
+
+        let mut approved = [0u8; 65];
+        approved[64] = 1;
+        let confirmation = Confirmation { owner, signature: Some(approved.to_vec().into()) };
+        assert_eq!(confirmation.verify(hash), SignatureStatus::ApprovedHash);
+    }
+
+    #[test]
+    fn can_pretty_print() {
+        let tx = transaction();
+        let info = SafeInfo { threshold: 2, owners: vec![], version: Some("1.3.0".to_string()) };
+        let hash = tx.hash(1, info.version.as_deref());
+        let pretty = tx.pretty(hash, 1, &info);
+        assert!(pretty.contains("hash          matches the transaction"), "{pretty}");
+        assert!(pretty.contains("approve("), "{pretty}");
+        assert!(pretty.contains("amount (uint256): 100"), "{pretty}");
+        assert!(pretty.contains("confirmations 0/2"), "{pretty}");
+
+        let pretty = tx.pretty(B256::ZERO, 1, &info);
+        assert!(pretty.contains("MISMATCH"), "{pretty}");
+
+        // approved hashes and contract signatures don't count towards the threshold
+        let owner = Address::with_last_byte(1);
+        let mut approved = [0u8; 65];
+        approved[64] = 1;
+        let mut tx = transaction();
+        tx.confirmations = vec![
+            Confirmation { owner, signature: Some(approved.to_vec().into()) },
+            Confirmation { owner, signature: Some([0u8; 65].to_vec().into()) },
+        ];
+        let info = SafeInfo { threshold: 1, owners: vec![owner], version: None };
+        let pretty = tx.pretty(tx.hash(1, None), 1, &info);
+        assert!(pretty.contains("confirmations 0/2 (+2 unverified)"), "{pretty}");
+        assert!(!pretty.contains("ready to execute"), "{pretty}");
+        assert!(pretty.contains("unverified on-chain approval"), "{pretty}");
+    }
+
+    #[test]
+    fn knows_service_urls() {
+        assert_eq!(service_url_of(1).unwrap(), "https://safe-transaction-mainnet.safe.global");
+        assert!(service_url_of(31337).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use cast::{Cast, TxBuilder};
+use cast::{checksum::parse_name_or_address, Cast, TxBuilder};
 use clap::Parser;
 use ethers_core::types::NameOrAddress;
 use ethers_middleware::MiddlewareBuilder;
@@ -14,7 +14,6 @@ use foundry_common::{
     types::{ToAlloy, ToEthers},
 };
 use foundry_config::{Chain, Config};
-use std::str::FromStr;
 
 /// CLI arguments for `cast send`.
 #[derive(Debug, Parser)]
@@ -22,7 +21,7 @@ pub struct SendTxArgs {
     /// The destination of the transaction.
     ///
     /// If not provided, you must use cast send --create.
-    #[clap(value_parser = NameOrAddress::from_str)]
+    #[clap(value_parser = parse_name_or_address)]
     to: Option<NameOrAddress>,
 
     /// The signature of the function to call.
@@ -212,7 +211,7 @@ corresponds to the sender, or let foundry automatically detect it by not specify
 }
 
 #[allow(clippy::too_many_arguments)]
-async fn cast_send<M: Middleware, F: Into<NameOrAddress>, T: Into<NameOrAddress>>(
+pub(crate) async fn cast_send<M: Middleware, F: Into<NameOrAddress>, T: Into<NameOrAddress>>(
     provider: M,
     from: F,
     to: Option<T>,
@@ -0,0 +1,313 @@
+use alloy_json_abi::Function;
+use alloy_primitives::{keccak256, Selector};
+use clap::{Parser, ValueHint};
+use eyre::{Result, WrapErr};
+use foundry_common::fs;
+use rayon::prelude::*;
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+/// CLI arguments for `cast sig-collision`.
+#[derive(Debug, Clone, Parser)]
+pub struct SigCollisionArgs {
+    /// The selector to find colliding signatures for, or a function signature whose selector is
+    /// used, e.g. `0xa9059cbb` or `transfer(address,uint256)`.
+    target: String,
+
+    /// The comma-separated parameter types of the colliding functions.
+    ///
+    /// Defaults to the parameter types of the target signature, or to no parameters if the
+    /// target is a selector.
+    #[clap(long, value_name = "TYPES")]
+    params: Option<String>,
+
+    /// A file with candidate words, one per line.
+    ///
+    /// Can be passed multiple times. Words are combined into camelCase names, e.g. `transfer`
+    /// and `from` into `transferFrom`.
+    #[clap(long, short, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    wordlist: Vec<PathBuf>,
+
+    /// The maximum number of words combined into a name.
+    #[clap(long, default_value = "2", value_name = "N")]
+    max_words: u32,
+
+    /// Brute-force names made of the characters of the alphabet.
+    ///
+    /// This is the default if no wordlist is passed.
+    #[clap(long)]
+    brute_force: bool,
+
+    /// The characters of the brute-forced names.
+    ///
+    /// About 2^32 names have to be tried on average to find a collision, so small alphabets need
+    /// long names.
+    #[clap(
+        long,
+        default_value = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_"
+    )]
+    alphabet: String,
+
+    /// The minimum length of the brute-forced names, excluding the prefix.
+    #[clap(long, default_value = "1", value_name = "N")]
+    min_length: u32,
+
+    /// The maximum length of the brute-forced names, excluding the prefix.
+    #[clap(long, default_value = "6", value_name = "N")]
+    max_length: u32,
+
+    /// A prefix of all candidate names.
+    #[clap(long, default_value = "")]
+    prefix: String,
+
+    /// The number of colliding signatures to find.
+    #[clap(long, default_value = "1", value_name = "N")]
+    limit: usize,
+}
+
+impl SigCollisionArgs {
+    pub fn run(self) -> Result<()> {
+        let Self {
+            target,
+            params,
+            wordlist,
+            max_words,
+            brute_force,
+            alphabet,
+            min_length,
+            max_length,
+            prefix,
+            limit,
+        } = self;
+
+        let mut search = if target.starts_with("0x") {
+            let selector = target.parse().wrap_err("invalid selector")?;
+            CollisionSearch::new(selector, params.unwrap_or_default(), None)
+        } else {
+            let func = Function::parse(&target).wrap_err("invalid function signature")?;
+            let signature = func.signature();
+            let params = params
+                .unwrap_or_else(|| signature[func.name.len() + 1..signature.len() - 1].to_string());
+            CollisionSearch::new(func.selector(), params, Some(signature))
+        };
+        search.prefix = prefix;
+        search.limit = limit;
+
+        if !wordlist.is_empty() {
+            let mut words = Vec::new();
+            for path in &wordlist {
+                words.extend(
+                    fs::read_to_string(path)?
+                        .lines()
+                        .map(str::trim)
+                        .filter(|word| !word.is_empty() && !word.starts_with('#'))
+                        .map(str::to_string),
+                );
+            }
+            words.sort();
+            words.dedup();
+            search.words(&words, max_words)?;
+        }
+        if brute_force || wordlist.is_empty() {
+            let alphabet = alphabet.chars().collect::<Vec<_>>();
+            search.brute_force(&alphabet, min_length, max_length)?;
+        }
+
+        let selector = search.selector;
+        let found = search.found();
+        if found.is_empty() {
+            eyre::bail!("No colliding signature found for {selector}")
+        }
+        for signature in found {
+            println!("{signature}");
+        }
+        Ok(())
+    }
+}
+
+/// A search for function signatures with a given selector.
+#[derive(Debug)]
+struct CollisionSearch {
+    selector: Selector,
+    params: String,
+    /// The signature the selector was computed from, which doesn't count as a collision.
+    exclude: Option<String>,
+    prefix: String,
+    limit: usize,
+    found: Mutex<Vec<String>>,
+    count: AtomicUsize,
+}
+
+impl CollisionSearch {
+    fn new(selector: Selector, params: String, exclude: Option<String>) -> Self {
+        Self {
+            selector,
+            params: params.replace(' ', ""),
+            exclude,
+            prefix: String::new(),
+            limit: 1,
+            found: Mutex::default(),
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the colliding signatures found so far, sorted.
+    fn found(self) -> Vec<String> {
+        let mut found = self.found.into_inner().unwrap();
+        found.sort();
+        found
+    }
+
+    fn is_done(&self) -> bool {
+        self.count.load(Ordering::Relaxed) >= self.limit
+    }
+
+    /// Checks whether the function with the given name, after the prefix, collides.
+    fn check(&self, name: &str) {
+        let signature = format!("{}{name}({})", self.prefix, self.params);
+        if signature.starts_with(|c: char| c.is_ascii_digit()) ||
+            keccak256(signature.as_bytes())[..4] != self.selector[..] ||
+            self.exclude.as_ref() == Some(&signature)
+        {
+            return
+        }
+        let mut found = self.found.lock().unwrap();
+        if found.len() < self.limit && !found.contains(&signature) {
+            found.push(signature);
+            self.count.store(found.len(), Ordering::Relaxed);
+        }
+    }
+
+    /// Tries the `count` names returned by `name` in parallel, until enough collisions are found.
+    fn search(&self, count: u64, name: impl Fn(u64) -> String + Sync) {
+        let _ = (0..count).into_par_iter().try_for_each(|i| {
+            if self.is_done() {
+                return Err(())
+            }
+            self.check(&name(i));
+            Ok(())
+        });
+    }
+
+    /// Tries all camelCase combinations of up to `max_words` of the words.
+    fn words(&self, words: &[String], max_words: u32) -> Result<()> {
+        for n in 1..=max_words {
+            if self.is_done() {
+                break
+            }
+            let len = words.len() as u64;
+            let Some(count) = len.checked_pow(n) else {
+                eyre::bail!("too many combinations of {n} words, lower --max-words")
+            };
+            self.search(count, |mut i| {
+                let mut name = String::new();
+                for _ in 0..n {
+                    let word = &words[(i % len) as usize];
+                    i /= len;
+                    if name.is_empty() && self.prefix.is_empty() {
+                        name.push_str(word);
+                    } else {
+                        name.push_str(&capitalize(word));
+                    }
+                }
+                name
+            });
+        }
+        Ok(())
+    }
+
+    /// Tries all names of the given lengths made of the characters of the alphabet, shortest first.
+    fn brute_force(&self, alphabet: &[char], min_length: u32, max_length: u32) -> Result<()> {
+        if alphabet.is_empty() {
+            eyre::bail!("the alphabet must not be empty")
+        }
+        if let Some(c) =
+            alphabet.iter().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$')))
+        {
+            eyre::bail!("the alphabet contains `{c}`, which can't be used in function names")
+        }
+        let len = alphabet.len() as u64;
+        for length in min_length.max(1)..=max_length {
+            if self.is_done() {
+                break
+            }
+            let Some(count) = len.checked_pow(length) else {
+                eyre::bail!("too many names of length {length}, lower --max-length")
+            };
+            self.search(count, |mut i| {
+                let mut name = String::with_capacity(length as usize);
+                for _ in 0..length {
+                    name.push(alphabet[(i % len) as usize]);
+                    i /= len;
+                }
+                name
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Uppercases the first character of the word.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_word_combinations() {
+        // transferFrom(address,address,uint256)
+        let search = CollisionSearch::new(
+            "0x23b872dd".parse().unwrap(),
+            "address, address, uint256".to_string(),
+            None,
+        );
+        search.words(&words(&["from", "to", "transfer"]), 2).unwrap();
+        assert_eq!(search.found(), ["transferFrom(address,address,uint256)"]);
+    }
+
+    #[test]
+    fn brute_forces_names() {
+        let func = Function::parse("ab()").unwrap();
+        let mut search = CollisionSearch::new(func.selector(), String::new(), None);
+        search.limit = 5;
+        search.brute_force(&['a', 'b'], 1, 3).unwrap();
+        assert_eq!(search.found(), ["ab()"]);
+
+        // the target itself isn't a collision
+        let search = CollisionSearch::new(func.selector(), String::new(), Some(func.signature()));
+        search.brute_force(&['a', 'b'], 1, 3).unwrap();
+        assert!(search.found().is_empty());
+    }
+
+    #[test]
+    fn respects_prefix() {
+        let func = Function::parse("_xb(uint256)").unwrap();
+        let mut search = CollisionSearch::new(func.selector(), "uint256".to_string(), None);
+        search.prefix = "_x".to_string();
+        search.brute_force(&['a', 'b'], 1, 1).unwrap();
+        assert_eq!(search.found(), ["_xb(uint256)"]);
+    }
+
+    #[test]
+    fn rejects_invalid_alphabet() {
+        let search = CollisionSearch::new(Selector::ZERO, String::new(), None);
+        assert!(search.brute_force(&['a', '('], 1, 1).is_err());
+        assert!(search.brute_force(&[], 1, 1).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use clap::Parser;
+use eyre::Result;
+use foundry_cli::stdin;
+use foundry_common::selectors::{parse_signatures, SelectorType, SignaturesDb};
+
+/// CLI arguments for `cast signatures`.
+#[derive(Debug, Parser)]
+pub enum SignaturesSubcommands {
+    /// Add signatures to the local database.
+    ///
+    /// Example inputs:
+    /// - "transfer(address,uint256)"
+    /// - "function transfer(address,uint256)" "event Transfer(address,address,uint256)"
+    /// - "./out/Contract.sol/Contract.json"
+    #[clap(visible_alias = "a")]
+    Add {
+        /// The signatures to add.
+        ///
+        /// Prefix with 'function', 'event', or 'error'. Defaults to function if no prefix given.
+        /// Can also take paths to contract artifact JSON.
+        signatures: Vec<String>,
+    },
+
+    /// List the signatures in the local database.
+    #[clap(visible_alias = "ls")]
+    List,
+
+    /// Remove selectors or single signatures from the local database.
+    #[clap(visible_alias = "rm")]
+    Remove {
+        /// The selectors, event topics or signatures to remove.
+        #[clap(required = true)]
+        selectors: Vec<String>,
+    },
+
+    /// Remove all signatures from the local database.
+    Clear,
+}
+
+impl SignaturesSubcommands {
+    pub fn run(self) -> Result<()> {
+        let mut db = SignaturesDb::load();
+        match self {
+            SignaturesSubcommands::Add { signatures } => {
+                let signatures = stdin::unwrap_vec(signatures)?;
+                let added = db.import(&parse_signatures(signatures))?;
+                db.save()?;
+                println!("Added {added} signatures to the local database");
+            }
+            SignaturesSubcommands::List => {
+                for (selector, sig) in db.iter(SelectorType::Function) {
+                    println!("{selector} function {sig}");
+                }
+                for (topic, sig) in db.iter(SelectorType::Event) {
+                    println!("{topic} event {sig}");
+                }
+            }
+            SignaturesSubcommands::Remove { selectors } => {
+                for selector in selectors {
+                    if !db.remove(&selector) {
+                        eyre::bail!("`{selector}` is not in the local database")
+                    }
+                }
+                db.save()?;
+            }
+            SignaturesSubcommands::Clear => {
+                db.clear();
+                db.save()?;
+            }
+        }
+        Ok(())
+    }
+}
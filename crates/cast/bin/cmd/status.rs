@@ -0,0 +1,143 @@
+use alloy_primitives::U256;
+use clap::Parser;
+use ethers_core::types::BlockNumber;
+use ethers_providers::Middleware;
+use eyre::Result;
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_common::{types::ToAlloy, units::UnitsDisplay};
+use foundry_config::Config;
+use serde::Serialize;
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// CLI arguments for `cast status`.
+#[derive(Debug, Clone, Parser)]
+pub struct StatusArgs {
+    /// Keep printing the status whenever a new block is mined.
+    #[clap(long, short)]
+    watch: bool,
+
+    /// How often to poll for new blocks with `--watch`, in seconds.
+    #[clap(long, default_value = "2", requires = "watch", value_name = "SECONDS")]
+    interval: u64,
+
+    /// Print the status as JSON.
+    #[clap(long, short, help_heading = "Display options")]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: RpcOpts,
+}
+
+/// The status of a chain at its latest block.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainStatus {
+    chain_id: u64,
+    client_version: String,
+    block_number: u64,
+    block_timestamp: u64,
+    base_fee: Option<U256>,
+    blob_base_fee: Option<U256>,
+    gas_used: U256,
+    gas_limit: U256,
+}
+
+impl StatusArgs {
+    pub async fn run(self) -> Result<()> {
+        let StatusArgs { watch, interval, json, rpc } = self;
+
+        let config = Config::from(&rpc);
+        let provider = utils::get_provider(&config)?;
+
+        let chain_id = provider.get_chainid().await?.as_u64();
+        let client_version = provider.client_version().await?;
+
+        let mut last_block = None;
+        let mut poll = tokio::time::interval(Duration::from_secs(interval.max(1)));
+        loop {
+            poll.tick().await;
+
+            let block = provider
+                .get_block(BlockNumber::Latest)
+                .await?
+                .ok_or_else(|| eyre::eyre!("The latest block was not found"))?;
+
+            let block_number = block.number.unwrap_or_default().as_u64();
+            if last_block != Some(block_number) {
+                last_block = Some(block_number);
+                // only clients that support Cancun know the blob base fee
+                let blob_base_fee =
+                    provider.provider().request::<_, U256>("eth_blobBaseFee", ()).await.ok();
+                let status = ChainStatus {
+                    chain_id,
+                    client_version: client_version.clone(),
+                    block_number,
+                    block_timestamp: block.timestamp.as_u64(),
+                    base_fee: block.base_fee_per_gas.map(|fee| fee.to_alloy()),
+                    blob_base_fee,
+                    gas_used: block.gas_used.to_alloy(),
+                    gas_limit: block.gas_limit.to_alloy(),
+                };
+                if json {
+                    println!("{}", serde_json::to_string(&status)?);
+                } else {
+                    println!("{status}");
+                }
+            }
+
+            if !watch {
+                return Ok(())
+            }
+        }
+    }
+}
+
+impl ChainStatus {
+    /// Returns the gas target of EIP-1559, which is half of the gas limit.
+    fn gas_target(&self) -> U256 {
+        self.gas_limit / U256::from(2)
+    }
+
+    /// Returns the gas used relative to the gas target, in percent.
+    fn target_utilization(&self) -> Option<f64> {
+        let target = self.gas_target();
+        (!target.is_zero()).then(|| {
+            self.gas_used.saturating_to::<u64>() as f64 / target.saturating_to::<u64>() as f64 *
+                100.0
+        })
+    }
+}
+
+impl fmt::Display for ChainStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gwei = UnitsDisplay { precision: Some(3), thousands_separator: None };
+        let price = |fee: Option<U256>| match fee {
+            Some(fee) => match gwei.format(fee, 9) {
+                Ok(fee) => format!("{fee} gwei"),
+                Err(_) => format!("{fee} wei"),
+            },
+            None => "-".to_string(),
+        };
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let age = now.saturating_sub(self.block_timestamp);
+
+        writeln!(f, "chain id            {}", self.chain_id)?;
+        writeln!(f, "client              {}", self.client_version)?;
+        writeln!(f, "latest block        {} ({age}s ago)", self.block_number)?;
+        writeln!(f, "base fee            {}", price(self.base_fee))?;
+        writeln!(f, "blob base fee       {}", price(self.blob_base_fee))?;
+        match self.target_utilization() {
+            Some(utilization) => write!(
+                f,
+                "gas target usage    {utilization:.1}% ({} / {})",
+                self.gas_used,
+                self.gas_target()
+            ),
+            None => write!(f, "gas target usage    -"),
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use crate::opts::parse_slot;
-use alloy_primitives::{B256, U256};
-use cast::Cast;
-use clap::Parser;
+use alloy_primitives::{b256, Address, B256, U256};
+use cast::{checksum::parse_name_or_address, Cast};
+use clap::{Parser, ValueHint};
 use comfy_table::{presets::ASCII_MARKDOWN, Table};
 use ethers_core::types::{BlockId, NameOrAddress};
 use ethers_providers::Middleware;
-use eyre::Result;
+use eyre::{Result, WrapErr};
 use foundry_block_explorers::Client;
 use foundry_cli::{
     opts::{CoreBuildArgs, EtherscanOpts, RpcOpts},
@@ -17,37 +17,69 @@ use foundry_common::{
     types::{ToAlloy, ToEthers},
     RetryProvider,
 };
-use foundry_compilers::{artifacts::StorageLayout, ConfigurableContractArtifact, Project, Solc};
+use foundry_compilers::{
+    artifacts::StorageLayout, ConfigurableContractArtifact, Project, ProjectCompileOutput, Solc,
+};
 use foundry_config::{
     figment::{self, value::Dict, Metadata, Profile},
-    impl_figment_convert_cast, Config,
+    impl_figment_convert_cast, Chain, Config,
 };
 use futures::future::join_all;
 use semver::Version;
-use std::str::FromStr;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 /// The minimum Solc version for outputting storage layouts.
 ///
 /// https://github.com/ethereum/solidity/blob/develop/Changelog.md#065-2020-04-06
 const MIN_SOLC: Version = Version::new(0, 6, 5);
 
+/// The EIP-1967 implementation slot, `keccak256("eip1967.proxy.implementation") - 1`.
+const IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
 /// CLI arguments for `cast storage`.
 #[derive(Debug, Clone, Parser)]
 pub struct StorageArgs {
     /// The contract address.
-    #[clap(value_parser = NameOrAddress::from_str)]
+    #[clap(value_parser = parse_name_or_address)]
     address: NameOrAddress,
 
     /// The storage slot number.
     #[clap(value_parser = parse_slot)]
     slot: Option<B256>,
 
+    /// Other contract addresses to compare side by side with the first one, for example other
+    /// instances of the same proxy.
+    ///
+    /// Can be passed multiple times or as a comma separated list.
+    #[clap(
+        long,
+        value_name = "ADDRESS",
+        value_parser = parse_name_or_address,
+        value_delimiter = ','
+    )]
+    compare: Vec<NameOrAddress>,
+
+    /// A file with other contract addresses to compare, one per line.
+    #[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    compare_file: Option<PathBuf>,
+
     /// The block height to query at.
     ///
     /// Can also be the tags earliest, finalized, safe, latest, or pending.
     #[clap(long, short)]
     block: Option<BlockId>,
 
+    /// The API URL of an Etherscan compatible explorer to fetch verified sources from, e.g. a
+    /// Blockscout instance of a private network.
+    ///
+    /// Defaults to the `[etherscan]` config or the Etherscan API of the chain.
+    #[clap(long, value_name = "URL")]
+    explorer_api_url: Option<String>,
+
     #[clap(flatten)]
     rpc: RpcOpts,
 
@@ -77,40 +109,133 @@ impl figment::Provider for StorageArgs {
 impl StorageArgs {
     pub async fn run(self) -> Result<()> {
         let config = Config::from(&self);
-
-        let Self { address, slot, block, build, .. } = self;
-
         let provider = utils::get_provider(&config)?;
 
+        let mut addresses = vec![self.address.clone()];
+        addresses.extend(self.compare.iter().cloned());
+        if let Some(path) = &self.compare_file {
+            addresses.extend(read_addresses(path)?);
+        }
+
         // Slot was provided, perform a simple RPC call
-        if let Some(slot) = slot {
+        if let Some(slot) = self.slot {
             let cast = Cast::new(provider);
-            println!("{}", cast.storage(address, slot.to_ethers(), block).await?);
+            if addresses.len() == 1 {
+                println!("{}", cast.storage(self.address, slot.to_ethers(), self.block).await?);
+                return Ok(())
+            }
+            let values = join_all(
+                addresses
+                    .iter()
+                    .map(|address| cast.storage(address.clone(), slot.to_ethers(), self.block)),
+            )
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+            print_slot_comparison(&addresses, &values);
             return Ok(())
         }
 
-        // No slot was provided
+        // No slot was provided, resolve the storage layout of every contract from the artifacts
+        // of the current project, which is compiled only once for all of them
+        let output = self.compile_project()?;
+        let mut layouts = Vec::with_capacity(addresses.len());
+        for address in &addresses {
+            layouts.push(self.storage_layout(&config, &provider, output.as_ref(), address).await?);
+        }
+
+        if addresses.len() == 1 {
+            return fetch_and_print_storage(
+                provider,
+                self.address,
+                self.block,
+                layouts.pop().unwrap(),
+                true,
+            )
+            .await
+        }
+
+        // Compare the values of all contracts that share the first contract's layout
+        let Some(layout) = layouts[0].clone() else {
+            eyre::bail!("Storage layout of {} is empty", format_address(&addresses[0]))
+        };
+        let mut compared = Vec::new();
+        for (address, other) in addresses.iter().zip(&layouts) {
+            match other {
+                Some(other) if same_layout(&layout, other) => compared.push(address.clone()),
+                _ => eprintln!(
+                    "Storage layout of {} differs from {}, skipping it",
+                    format_address(address),
+                    format_address(&addresses[0])
+                ),
+            }
+        }
+        let values = join_all(compared.iter().map(|address| {
+            fetch_storage_slots(provider.clone(), address.clone(), self.block, &layout)
+        }))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+        print_storage_comparison(&layout, &compared, &values);
+        Ok(())
+    }
+
+    /// Compiles the current project with storage layouts, if we're in a forge project.
+    fn compile_project(&self) -> Result<Option<ProjectCompileOutput>> {
+        let mut project = self.build.project()?;
+        if !project.paths.has_input_files() {
+            return Ok(None)
+        }
+        add_storage_layout_output(&mut project);
+        Ok(Some(compile(&project, false, false)?))
+    }
+
+    /// Returns the storage layout of the contract at the given address, from the artifacts of the
+    /// current project or from its verified source on Etherscan.
+    ///
+    /// If the contract is an EIP-1967 proxy, the layout of its implementation is returned, since
+    /// the proxy's storage is laid out by the implementation.
+    async fn storage_layout(
+        &self,
+        config: &Config,
+        provider: &RetryProvider,
+        output: Option<&ProjectCompileOutput>,
+        address: &NameOrAddress,
+    ) -> Result<Option<StorageLayout>> {
         // Get deployed bytecode at given address
-        let address_code = provider.get_code(address.clone(), block).await?.to_alloy();
+        let address_code = provider.get_code(address.clone(), self.block).await?.to_alloy();
         if address_code.is_empty() {
-            eyre::bail!("Provided address has no deployed code and thus no storage");
+            eyre::bail!("{} has no deployed code and thus no storage", format_address(address));
         }
 
+        // Resolve the implementation of proxies
+        let implementation = self.implementation(provider, address).await?;
+        let (address, code) = match implementation {
+            Some(implementation) => {
+                let code = provider
+                    .get_code(NameOrAddress::Address(implementation.to_ethers()), self.block)
+                    .await?
+                    .to_alloy();
+                eprintln!(
+                    "{} is a proxy, using the storage layout of its implementation {implementation}",
+                    format_address(address)
+                );
+                (NameOrAddress::Address(implementation.to_ethers()), code)
+            }
+            None => (address.clone(), address_code),
+        };
+
         // Check if we're in a forge project and if we can find the address' code
-        let mut project = build.project()?;
-        if project.paths.has_input_files() {
-            // Find in artifacts and pretty print
-            add_storage_layout_output(&mut project);
-            let out = compile(&project, false, false)?;
+        if let Some(out) = output {
             let match_code = |artifact: &ConfigurableContractArtifact| -> Option<bool> {
                 let bytes =
                     artifact.deployed_bytecode.as_ref()?.bytecode.as_ref()?.object.as_bytes()?;
-                Some(bytes == &address_code)
+                Some(bytes == &code)
             };
             let artifact =
                 out.artifacts().find(|(_, artifact)| match_code(artifact).unwrap_or_default());
             if let Some((_, artifact)) = artifact {
-                return fetch_and_print_storage(provider, address.clone(), artifact, true).await
+                return Ok(non_empty_layout(artifact))
             }
         }
 
@@ -118,13 +243,8 @@ impl StorageArgs {
         // Get code from Etherscan
         eprintln!("No matching artifacts found, fetching source code from Etherscan...");
 
-        if self.etherscan.key.is_none() {
-            eyre::bail!("You must provide an Etherscan API key if you're fetching a remote contract's storage.");
-        }
-
-        let chain = utils::get_chain(config.chain, &provider).await?;
-        let api_key = config.get_etherscan_api_key(Some(chain)).unwrap_or_default();
-        let client = Client::new(chain, api_key)?;
+        let chain = utils::get_chain(config.chain, provider).await?;
+        let client = self.explorer_client(config, chain)?;
         let addr = address
             .as_address()
             .ok_or_else(|| eyre::eyre!("Could not resolve address"))?
@@ -148,7 +268,7 @@ impl StorageArgs {
 
         // Compile
         let mut out = suppress_compile(&project)?;
-        let artifact = {
+        let layout = {
             let (_, mut artifact) = out
                 .artifacts()
                 .find(|(name, _)| name == &metadata.contract_name)
@@ -170,35 +290,80 @@ impl StorageArgs {
                 }
             }
 
-            artifact
+            non_empty_layout(artifact)
         };
 
         // Clear temp directory
         root.close()?;
 
-        fetch_and_print_storage(provider, address, artifact, true).await
+        Ok(layout)
+    }
+
+    /// Returns the implementation of the contract at the given address if it's an EIP-1967 proxy.
+    async fn implementation(
+        &self,
+        provider: &RetryProvider,
+        address: &NameOrAddress,
+    ) -> Result<Option<Address>> {
+        let value = provider
+            .get_storage_at(address.clone(), IMPLEMENTATION_SLOT.to_ethers(), self.block)
+            .await?
+            .to_alloy();
+        let implementation = Address::from_word(value);
+        Ok((!implementation.is_zero()).then_some(implementation))
+    }
+
+    /// Returns the client of the explorer to fetch verified sources of the chain from.
+    ///
+    /// The chain only needs to be known to Etherscan if neither `--explorer-api-url` nor an
+    /// `[etherscan]` config of the chain is provided.
+    fn explorer_client(&self, config: &Config, chain: Chain) -> Result<Client> {
+        if let Some(url) = &self.explorer_api_url {
+            let key = config.etherscan_api_key.clone().unwrap_or_default();
+            return Client::builder()
+                .with_api_url(url.as_str())?
+                .with_url(url.as_str())?
+                .with_api_key(key)
+                .build()
+                .wrap_err("Failed to create explorer client")
+        }
+
+        match config.get_etherscan_config_with_chain(Some(chain))? {
+            Some(etherscan) => Ok(etherscan.into_client()?),
+            None if chain.etherscan_urls().is_some() => eyre::bail!(
+                "You must provide an Etherscan API key if you're fetching a remote contract's storage."
+            ),
+            None => eyre::bail!(
+                "Etherscan doesn't support chain {chain}, pass the API URL of its explorer with \
+                 --explorer-api-url or configure it in the [etherscan] section of foundry.toml"
+            ),
+        }
     }
 }
 
 async fn fetch_and_print_storage(
     provider: RetryProvider,
     address: NameOrAddress,
-    artifact: &ConfigurableContractArtifact,
+    block: Option<BlockId>,
+    layout: Option<StorageLayout>,
     pretty: bool,
 ) -> Result<()> {
-    if is_storage_layout_empty(&artifact.storage_layout) {
-        eprintln!("Storage layout is empty.");
-        Ok(())
-    } else {
-        let layout = artifact.storage_layout.as_ref().unwrap().clone();
-        let values = fetch_storage_slots(provider, address, &layout).await?;
-        print_storage(layout, values, pretty)
+    match layout {
+        Some(layout) => {
+            let values = fetch_storage_slots(provider, address, block, &layout).await?;
+            print_storage(layout, values, pretty)
+        }
+        None => {
+            eprintln!("Storage layout is empty.");
+            Ok(())
+        }
     }
 }
 
 async fn fetch_storage_slots(
     provider: RetryProvider,
     address: NameOrAddress,
+    block: Option<BlockId>,
     layout: &StorageLayout,
 ) -> Result<Vec<B256>> {
     // TODO: Batch request
@@ -207,7 +372,7 @@ async fn fetch_storage_slots(
         .iter()
         .map(|slot| {
             let slot = B256::from(U256::from_str(&slot.slot)?);
-            Ok(provider.get_storage_at(address.clone(), slot.to_ethers(), None))
+            Ok(provider.get_storage_at(address.clone(), slot.to_ethers(), block))
         })
         .collect::<Result<_>>()?;
 
@@ -246,6 +411,93 @@ fn print_storage(layout: StorageLayout, values: Vec<B256>, pretty: bool) -> Resu
     Ok(())
 }
 
+/// Prints the values of a single slot of all contracts.
+fn print_slot_comparison(addresses: &[NameOrAddress], values: &[String]) {
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    table.set_header(["Address", "Value", "Differs"]);
+    for (address, value) in addresses.iter().zip(values) {
+        let differs = if value != &values[0] { "*" } else { "" };
+        table.add_row([format_address(address).as_str(), value, differs]);
+    }
+    println!("{table}");
+}
+
+/// Prints the storage of all contracts with the same layout side by side, marking the variables
+/// whose values differ between the contracts.
+fn print_storage_comparison(
+    layout: &StorageLayout,
+    addresses: &[NameOrAddress],
+    values: &[Vec<B256>],
+) {
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    let mut header = vec!["Name".to_string(), "Type".to_string(), "Slot".to_string()];
+    header.extend(["Offset".to_string(), "Bytes".to_string()]);
+    header.extend(addresses.iter().map(format_address));
+    header.push("Differs".to_string());
+    table.set_header(header);
+
+    for (i, slot) in layout.storage.iter().enumerate() {
+        let storage_type = layout.types.get(&slot.storage_type);
+        let mut row = vec![
+            slot.label.clone(),
+            storage_type.map_or("?", |t| &t.label).to_string(),
+            slot.slot.clone(),
+            slot.offset.to_string(),
+            storage_type.map_or("?", |t| &t.number_of_bytes).to_string(),
+        ];
+        row.extend(values.iter().map(|values| values[i].to_string()));
+        let differs = values.iter().any(|values| values[i] != values[0][i]);
+        row.push(if differs { "*" } else { "" }.to_string());
+        table.add_row(row);
+    }
+
+    println!("{table}");
+}
+
+/// Returns `true` if both layouts declare the same variables of the same types in the same slots.
+fn same_layout(a: &StorageLayout, b: &StorageLayout) -> bool {
+    let shape = |layout: &StorageLayout| {
+        layout
+            .storage
+            .iter()
+            .map(|slot| {
+                let storage_type = layout.types.get(&slot.storage_type).map(|t| t.label.clone());
+                (slot.label.clone(), slot.slot.clone(), slot.offset, storage_type)
+            })
+            .collect::<Vec<_>>()
+    };
+    shape(a) == shape(b)
+}
+
+/// Reads addresses from a file, one per line, ignoring empty lines and `#` comments.
+fn read_addresses(path: &Path) -> Result<Vec<NameOrAddress>> {
+    let content = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            parse_name_or_address(line).wrap_err_with(|| format!("invalid address `{line}`"))
+        })
+        .collect()
+}
+
+fn format_address(address: &NameOrAddress) -> String {
+    match address {
+        NameOrAddress::Name(name) => name.clone(),
+        NameOrAddress::Address(address) => address.to_alloy().to_string(),
+    }
+}
+
+fn non_empty_layout(artifact: &ConfigurableContractArtifact) -> Option<StorageLayout> {
+    (!is_storage_layout_empty(&artifact.storage_layout))
+        .then(|| artifact.storage_layout.clone())
+        .flatten()
+}
+
 fn add_storage_layout_output(project: &mut Project) {
     project.artifacts.additional_values.storage_layout = true;
     let output_selection = project.artifacts.output_selection();
@@ -259,3 +511,45 @@ fn is_storage_layout_empty(storage_layout: &Option<StorageLayout>) -> bool {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explorer_client_of_unknown_chain() {
+        let address = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+        let config = Config::default();
+        let chain = Chain::from_id(1337_1337);
+
+        let args = StorageArgs::parse_from(["foundry-cli", address]);
+        let err = args.explorer_client(&config, chain).unwrap_err();
+        assert!(err.to_string().contains("--explorer-api-url"), "{err}");
+
+        let args = StorageArgs::parse_from([
+            "foundry-cli",
+            address,
+            "--explorer-api-url",
+            "https://explorer.example.com/api",
+        ]);
+        args.explorer_client(&config, chain).unwrap();
+    }
+
+    #[test]
+    fn compare_does_not_take_the_slot() {
+        let address = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+        let other = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+        let args = StorageArgs::parse_from(["foundry-cli", address, "--compare", other, "0x09"]);
+        assert_eq!(args.compare.len(), 1);
+        assert_eq!(args.slot, Some(B256::with_last_byte(9)));
+
+        let args = StorageArgs::parse_from([
+            "foundry-cli",
+            address,
+            "--compare",
+            &format!("{other},{address}"),
+        ]);
+        assert_eq!(args.compare.len(), 2);
+        assert_eq!(args.slot, None);
+    }
+}
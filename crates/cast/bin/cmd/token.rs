@@ -0,0 +1,585 @@
+use super::send::cast_send;
+use alloy_dyn_abi::{DynSolValue, FunctionExt};
+use alloy_primitives::{Address, U256};
+use cast::{checksum::parse_name_or_address, SimpleCast, TxBuilder};
+use clap::Parser;
+use ethers_core::{
+    types::{BlockId, NameOrAddress},
+    utils::parse_units,
+};
+use ethers_middleware::MiddlewareBuilder;
+use ethers_providers::Middleware;
+use ethers_signers::Signer;
+use eyre::{ContextCompat, Result, WrapErr};
+use foundry_cli::{
+    opts::{EthereumOpts, RpcOpts, TransactionOpts},
+    utils,
+};
+use foundry_common::{
+    types::{ToAlloy, ToEthers},
+    units::format_units,
+    RetryProvider,
+};
+use foundry_config::{Chain, Config};
+use std::str::FromStr;
+
+/// CLI arguments for `cast erc20`.
+#[derive(Debug, Parser)]
+pub enum Erc20Subcommands {
+    /// Get the token balance of an account.
+    #[clap(visible_alias = "b")]
+    Balance {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The account to query.
+        #[clap(value_parser = parse_name_or_address)]
+        owner: NameOrAddress,
+
+        /// Print the amount in the smallest unit, instead of formatting it with the token's
+        /// decimals.
+        #[clap(long)]
+        raw: bool,
+
+        #[clap(flatten)]
+        opts: TokenCallOpts,
+    },
+
+    /// Get the amount of tokens a spender is allowed to transfer from an owner.
+    #[clap(visible_alias = "a")]
+    Allowance {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The owner of the tokens.
+        #[clap(value_parser = parse_name_or_address)]
+        owner: NameOrAddress,
+
+        /// The spender of the tokens.
+        #[clap(value_parser = parse_name_or_address)]
+        spender: NameOrAddress,
+
+        /// Print the amount in the smallest unit, instead of formatting it with the token's
+        /// decimals.
+        #[clap(long)]
+        raw: bool,
+
+        #[clap(flatten)]
+        opts: TokenCallOpts,
+    },
+
+    /// Get the name, symbol, decimals and total supply of the token.
+    #[clap(visible_alias = "i")]
+    Info {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        #[clap(flatten)]
+        opts: TokenCallOpts,
+    },
+
+    /// Transfer tokens to an account.
+    ///
+    /// Prints the calldata of the transfer, unless --send is passed.
+    #[clap(visible_alias = "t")]
+    Transfer {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The recipient of the tokens.
+        to: Address,
+
+        /// The amount to transfer, in whole tokens, e.g. `1.5`.
+        amount: String,
+
+        /// The amount is in the smallest unit, instead of whole tokens.
+        #[clap(long)]
+        raw: bool,
+
+        #[clap(flatten)]
+        opts: TokenSendOpts,
+    },
+
+    /// Allow a spender to transfer tokens of the sender.
+    ///
+    /// Prints the calldata of the approval, unless --send is passed.
+    Approve {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The spender of the tokens.
+        spender: Address,
+
+        /// The amount to approve, in whole tokens, e.g. `1.5`.
+        amount: String,
+
+        /// The amount is in the smallest unit, instead of whole tokens.
+        #[clap(long)]
+        raw: bool,
+
+        #[clap(flatten)]
+        opts: TokenSendOpts,
+    },
+}
+
+impl Erc20Subcommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Erc20Subcommands::Balance { token, owner, raw, opts } => {
+                let token = opts.connect(token).await?;
+                let owner = token.resolve(owner).await?;
+                let balance =
+                    token.call_uint("balanceOf(address)(uint256)", vec![owner.to_string()]).await?;
+                println!("{}", token.format_amount(balance, raw).await?);
+            }
+            Erc20Subcommands::Allowance { token, owner, spender, raw, opts } => {
+                let token = opts.connect(token).await?;
+                let args = vec![
+                    token.resolve(owner).await?.to_string(),
+                    token.resolve(spender).await?.to_string(),
+                ];
+                let allowance =
+                    token.call_uint("allowance(address,address)(uint256)", args).await?;
+                println!("{}", token.format_amount(allowance, raw).await?);
+            }
+            Erc20Subcommands::Info { token, opts } => {
+                let token = opts.connect(token).await?;
+                let decimals = token.decimals().await?;
+                let total_supply = token.call_uint("totalSupply()(uint256)", vec![]).await?;
+                println!("name: {}", token.call_string("name()(string)", vec![]).await?);
+                println!("symbol: {}", token.call_string("symbol()(string)", vec![]).await?);
+                println!("decimals: {decimals}");
+                println!(
+                    "totalSupply: {} ({total_supply})",
+                    format_units(total_supply, decimals as u32)?
+                );
+            }
+            Erc20Subcommands::Transfer { token, to, amount, raw, opts } => {
+                let amount = opts.parse_amount(&token, &amount, raw).await?;
+                opts.send(token, "transfer(address,uint256)", |_| {
+                    Ok(vec![to.to_string(), amount.to_string()])
+                })
+                .await?;
+            }
+            Erc20Subcommands::Approve { token, spender, amount, raw, opts } => {
+                let amount = opts.parse_amount(&token, &amount, raw).await?;
+                opts.send(token, "approve(address,uint256)", |_| {
+                    Ok(vec![spender.to_string(), amount.to_string()])
+                })
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// CLI arguments for `cast erc721`.
+#[derive(Debug, Parser)]
+pub enum Erc721Subcommands {
+    /// Get the owner of a token.
+    #[clap(visible_alias = "o")]
+    Owner {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The token ID.
+        id: U256,
+
+        #[clap(flatten)]
+        opts: TokenCallOpts,
+    },
+
+    /// Get the number of tokens owned by an account.
+    #[clap(visible_alias = "b")]
+    Balance {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The account to query.
+        #[clap(value_parser = parse_name_or_address)]
+        owner: NameOrAddress,
+
+        #[clap(flatten)]
+        opts: TokenCallOpts,
+    },
+
+    /// Get the metadata URI of a token.
+    #[clap(visible_aliases = &["tokenuri", "uri"])]
+    TokenUri {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The token ID.
+        id: U256,
+
+        #[clap(flatten)]
+        opts: TokenCallOpts,
+    },
+
+    /// Transfer a token of the sender to an account, using `safeTransferFrom`.
+    ///
+    /// Prints the calldata of the transfer, unless --send is passed.
+    #[clap(visible_alias = "t")]
+    Transfer {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The recipient of the token.
+        to: Address,
+
+        /// The token ID.
+        id: U256,
+
+        #[clap(flatten)]
+        opts: TokenSendOpts,
+    },
+
+    /// Allow an account to transfer a token of the sender.
+    ///
+    /// Prints the calldata of the approval, unless --send is passed.
+    Approve {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The approved account.
+        spender: Address,
+
+        /// The token ID.
+        id: U256,
+
+        #[clap(flatten)]
+        opts: TokenSendOpts,
+    },
+}
+
+impl Erc721Subcommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Erc721Subcommands::Owner { token, id, opts } => {
+                let token = opts.connect(token).await?;
+                let owner =
+                    token.call_address("ownerOf(uint256)(address)", vec![id.to_string()]).await?;
+                println!("{}", owner.to_checksum(None));
+            }
+            Erc721Subcommands::Balance { token, owner, opts } => {
+                let token = opts.connect(token).await?;
+                let owner = token.resolve(owner).await?;
+                let balance =
+                    token.call_uint("balanceOf(address)(uint256)", vec![owner.to_string()]).await?;
+                println!("{balance}");
+            }
+            Erc721Subcommands::TokenUri { token, id, opts } => {
+                let token = opts.connect(token).await?;
+                println!(
+                    "{}",
+                    token.call_string("tokenURI(uint256)(string)", vec![id.to_string()]).await?
+                );
+            }
+            Erc721Subcommands::Transfer { token, to, id, opts } => {
+                opts.send(token, "safeTransferFrom(address,address,uint256)", |from| {
+                    Ok(vec![from?.to_string(), to.to_string(), id.to_string()])
+                })
+                .await?;
+            }
+            Erc721Subcommands::Approve { token, spender, id, opts } => {
+                opts.send(token, "approve(address,uint256)", |_| {
+                    Ok(vec![spender.to_string(), id.to_string()])
+                })
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// CLI arguments for `cast erc1155`.
+#[derive(Debug, Parser)]
+pub enum Erc1155Subcommands {
+    /// Get the balance of a token of an account.
+    #[clap(visible_alias = "b")]
+    Balance {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The account to query.
+        #[clap(value_parser = parse_name_or_address)]
+        owner: NameOrAddress,
+
+        /// The token ID.
+        id: U256,
+
+        #[clap(flatten)]
+        opts: TokenCallOpts,
+    },
+
+    /// Get the metadata URI of a token.
+    Uri {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The token ID.
+        id: U256,
+
+        #[clap(flatten)]
+        opts: TokenCallOpts,
+    },
+
+    /// Transfer tokens of the sender to an account, using `safeTransferFrom`.
+    ///
+    /// Prints the calldata of the transfer, unless --send is passed.
+    #[clap(visible_alias = "t")]
+    Transfer {
+        /// The token contract.
+        #[clap(value_parser = parse_name_or_address)]
+        token: NameOrAddress,
+
+        /// The recipient of the tokens.
+        to: Address,
+
+        /// The token ID.
+        id: U256,
+
+        /// The amount of tokens to transfer.
+        amount: U256,
+
+        #[clap(flatten)]
+        opts: TokenSendOpts,
+    },
+}
+
+impl Erc1155Subcommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Erc1155Subcommands::Balance { token, owner, id, opts } => {
+                let token = opts.connect(token).await?;
+                let owner = token.resolve(owner).await?;
+                let balance = token
+                    .call_uint(
+                        "balanceOf(address,uint256)(uint256)",
+                        vec![owner.to_string(), id.to_string()],
+                    )
+                    .await?;
+                println!("{balance}");
+            }
+            Erc1155Subcommands::Uri { token, id, opts } => {
+                let token = opts.connect(token).await?;
+                println!(
+                    "{}",
+                    token.call_string("uri(uint256)(string)", vec![id.to_string()]).await?
+                );
+            }
+            Erc1155Subcommands::Transfer { token, to, id, amount, opts } => {
+                opts.send(
+                    token,
+                    "safeTransferFrom(address,address,uint256,uint256,bytes)",
+                    |from| {
+                        Ok(vec![
+                            from?.to_string(),
+                            to.to_string(),
+                            id.to_string(),
+                            amount.to_string(),
+                            "0x".to_string(),
+                        ])
+                    },
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Options of the subcommands that read from a token contract.
+#[derive(Debug, Parser)]
+pub struct TokenCallOpts {
+    /// The block height to query at.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[clap(long, short = 'B')]
+    block: Option<BlockId>,
+
+    #[clap(flatten)]
+    rpc: RpcOpts,
+}
+
+impl TokenCallOpts {
+    async fn connect(&self, token: NameOrAddress) -> Result<TokenContract> {
+        let config = Config::from(&self.rpc);
+        let provider = utils::get_provider(&config)?;
+        TokenContract::new(provider, config.chain, token, self.block).await
+    }
+}
+
+/// Options of the subcommands that send a transaction to a token contract.
+#[derive(Debug, Parser)]
+pub struct TokenSendOpts {
+    /// Sign and send the transaction, instead of printing its calldata.
+    #[clap(long)]
+    send: bool,
+
+    /// Only print the transaction hash and exit immediately.
+    #[clap(name = "async", long = "async", requires = "send", env = "CAST_ASYNC")]
+    cast_async: bool,
+
+    /// The number of confirmations until the receipt is fetched.
+    #[clap(long, default_value = "1")]
+    confirmations: usize,
+
+    /// Print the transaction receipt as JSON.
+    #[clap(long, short, help_heading = "Display options")]
+    json: bool,
+
+    #[clap(flatten)]
+    tx: TransactionOpts,
+
+    #[clap(flatten)]
+    eth: EthereumOpts,
+}
+
+impl TokenSendOpts {
+    /// Converts an amount of whole tokens into the smallest unit, using the token's decimals.
+    async fn parse_amount(&self, token: &NameOrAddress, amount: &str, raw: bool) -> Result<U256> {
+        if raw {
+            return U256::from_str(amount).wrap_err_with(|| format!("invalid amount `{amount}`"))
+        }
+        let config = Config::from(&self.eth);
+        let provider = utils::get_provider(&config)?;
+        let token = TokenContract::new(provider, config.chain, token.clone(), None).await?;
+        let decimals = token.decimals().await?;
+        let amount: ethers_core::types::U256 = parse_units(amount, decimals as u32)
+            .wrap_err_with(|| format!("invalid amount `{amount}` for {decimals} decimals"))?
+            .into();
+        Ok(amount.to_alloy())
+    }
+
+    /// Sends the call to the token contract, or prints its calldata if `--send` isn't set.
+    ///
+    /// The arguments are built from the sender's address, which is only known when sending or if
+    /// `--from` is set.
+    async fn send(
+        self,
+        token: NameOrAddress,
+        sig: &str,
+        args: impl FnOnce(Result<Address>) -> Result<Vec<String>>,
+    ) -> Result<()> {
+        if !self.send {
+            let from = self.eth.wallet.from.wrap_err("--from must be set to encode the call");
+            println!("{}", SimpleCast::calldata_encode(sig, &args(from)?)?);
+            return Ok(())
+        }
+
+        let config = Config::from(&self.eth);
+        let provider = utils::get_provider(&config)?;
+        let chain = utils::get_chain(config.chain, &provider).await?;
+        let api_key = config.get_etherscan_api_key(Some(chain));
+
+        let signer = self.eth.wallet.signer(chain.id()).await?;
+        let from = signer.address();
+        let args = args(Ok(from.to_alloy()))?;
+        let provider = provider.with_signer(signer);
+
+        cast_send(
+            provider,
+            from,
+            Some(token),
+            None,
+            (sig.to_string(), args),
+            self.tx,
+            chain,
+            api_key,
+            self.cast_async,
+            self.confirmations,
+            self.json,
+        )
+        .await
+    }
+}
+
+/// A token contract that is called at a fixed block.
+pub(super) struct TokenContract {
+    provider: RetryProvider,
+    chain: Chain,
+    token: NameOrAddress,
+    block: Option<BlockId>,
+}
+
+impl TokenContract {
+    pub(super) async fn new(
+        provider: RetryProvider,
+        chain: Option<Chain>,
+        token: NameOrAddress,
+        block: Option<BlockId>,
+    ) -> Result<Self> {
+        let chain = utils::get_chain(chain, &provider).await?;
+        Ok(Self { provider, chain, token, block })
+    }
+
+    /// Resolves an ENS name.
+    async fn resolve(&self, who: NameOrAddress) -> Result<Address> {
+        Ok(match who {
+            NameOrAddress::Address(addr) => addr.to_alloy(),
+            NameOrAddress::Name(name) => self.provider.resolve_name(&name).await?.to_alloy(),
+        })
+    }
+
+    /// Calls a view function of the token and returns its first return value.
+    async fn call(&self, sig: &str, args: Vec<String>) -> Result<DynSolValue> {
+        let mut builder = TxBuilder::new(
+            &self.provider,
+            Address::ZERO.to_ethers(),
+            Some(self.token.clone()),
+            self.chain,
+            true,
+        )
+        .await?;
+        builder.set_args(sig, args).await?;
+        let (tx, func) = builder.build();
+
+        let res = self.provider.call(&tx, self.block).await?;
+        let func = func.expect("function is set");
+        func.abi_decode_output(res.as_ref(), false)
+            .ok()
+            .and_then(|values| values.into_iter().next())
+            .wrap_err_with(|| {
+                format!("could not decode the result of `{}`, is this a token contract?", func.name)
+            })
+    }
+
+    pub(super) async fn call_uint(&self, sig: &str, args: Vec<String>) -> Result<U256> {
+        self.call(sig, args).await?.as_uint().map(|(value, _)| value).wrap_err("expected a uint")
+    }
+
+    async fn call_address(&self, sig: &str, args: Vec<String>) -> Result<Address> {
+        self.call(sig, args).await?.as_address().wrap_err("expected an address")
+    }
+
+    pub(super) async fn call_string(&self, sig: &str, args: Vec<String>) -> Result<String> {
+        Ok(self.call(sig, args).await?.as_str().wrap_err("expected a string")?.to_string())
+    }
+
+    /// Returns the decimals of an ERC-20 token.
+    pub(super) async fn decimals(&self) -> Result<u8> {
+        let decimals = self.call_uint("decimals()(uint8)", vec![]).await?;
+        u8::try_from(decimals).wrap_err("invalid decimals")
+    }
+
+    /// Formats an amount with the token's decimals, unless `raw` is set.
+    async fn format_amount(&self, amount: U256, raw: bool) -> Result<String> {
+        if raw {
+            return Ok(amount.to_string())
+        }
+        Ok(format_units(amount, self.decimals().await? as u32)?)
+    }
+}
@@ -0,0 +1,420 @@
+use alloy_primitives::Selector;
+use cast::checksum::parse_name_or_address;
+use clap::Parser;
+use ethers_core::types::{
+    Action, Address, Block, BlockId, BlockNumber, Bytes, CallType, NameOrAddress, Trace,
+    TraceFilter, H256, U256,
+};
+use ethers_providers::Middleware;
+use eyre::{Result, WrapErr};
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_common::{
+    abi::{abi_decode_calldata, get_func},
+    fmt::format_tokens,
+    selectors::SignaturesDb,
+    RetryProvider,
+};
+use foundry_config::Config;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{collections::HashMap, str::FromStr};
+
+/// CLI arguments for `cast trace-filter`.
+#[derive(Debug, Parser)]
+pub struct TraceFilterArgs {
+    /// The block to start the search at.
+    ///
+    /// Defaults to the block to stop the search at.
+    #[clap(long)]
+    from_block: Option<BlockId>,
+
+    /// The block to stop the search at, inclusive.
+    ///
+    /// Defaults to the latest block.
+    #[clap(long)]
+    to_block: Option<BlockId>,
+
+    /// Only find calls to these addresses.
+    #[clap(long, value_parser = parse_name_or_address, value_name = "ADDRESS")]
+    to: Vec<NameOrAddress>,
+
+    /// Only find calls from these addresses.
+    #[clap(long, value_parser = parse_name_or_address, value_name = "ADDRESS")]
+    from: Vec<NameOrAddress>,
+
+    /// Only find calls to this function, given as a signature or a 4 byte selector.
+    ///
+    /// The arguments of the calls are decoded with the signature.
+    #[clap(long, value_name = "SIG_OR_SELECTOR")]
+    sig: Option<String>,
+
+    /// Don't look up the signatures of unknown selectors on https://openchain.xyz.
+    #[clap(long)]
+    offline: bool,
+
+    /// Print the calls as JSON.
+    #[clap(long, short, help_heading = "Display options")]
+    json: bool,
+
+    #[clap(flatten)]
+    rpc: RpcOpts,
+}
+
+/// A call found in the traces of a block range.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FoundCall {
+    block_number: u64,
+    transaction_hash: Option<H256>,
+    /// The position of the call in the call tree of the transaction.
+    trace_address: Vec<usize>,
+    /// `call`, `staticcall`, `delegatecall` or `callcode`.
+    call_type: String,
+    from: Address,
+    to: Address,
+    value: U256,
+    input: Bytes,
+    error: Option<String>,
+    /// The signature and decoded arguments of the called function, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded: Option<String>,
+}
+
+/// A frame of geth's `callTracer`.
+#[derive(Debug, Deserialize)]
+struct CallFrame {
+    #[serde(rename = "type")]
+    typ: String,
+    from: Address,
+    #[serde(default)]
+    to: Option<Address>,
+    #[serde(default)]
+    value: Option<U256>,
+    #[serde(default)]
+    input: Bytes,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    calls: Vec<CallFrame>,
+}
+
+/// The filters applied to the found calls.
+struct CallFilter {
+    to: Vec<Address>,
+    from: Vec<Address>,
+    selector: Option<Selector>,
+}
+
+impl CallFilter {
+    fn matches(&self, call: &FoundCall) -> bool {
+        (self.to.is_empty() || self.to.contains(&call.to)) &&
+            (self.from.is_empty() || self.from.contains(&call.from)) &&
+            self.selector.map_or(true, |selector| call.input.starts_with(selector.as_slice()))
+    }
+}
+
+impl TraceFilterArgs {
+    pub async fn run(self) -> Result<()> {
+        let TraceFilterArgs { from_block, to_block, to, from, sig, offline, json, rpc } = self;
+
+        let config = Config::from(&rpc);
+        let provider = utils::get_provider(&config)?;
+
+        let resolve = |addresses: Vec<NameOrAddress>| {
+            let provider = &provider;
+            async move {
+                let mut resolved = Vec::with_capacity(addresses.len());
+                for address in addresses {
+                    resolved.push(match address {
+                        NameOrAddress::Name(name) => provider.resolve_name(&name).await?,
+                        NameOrAddress::Address(address) => address,
+                    });
+                }
+                eyre::Ok(resolved)
+            }
+        };
+        let to = resolve(to).await?;
+        let from = resolve(from).await?;
+
+        let (selector, signature) = match sig.as_deref() {
+            Some(sig) if sig.starts_with("0x") => {
+                (Some(Selector::from_str(sig).wrap_err("invalid function selector")?), None)
+            }
+            Some(sig) => {
+                let func = get_func(sig)?;
+                (Some(func.selector()), Some(func.signature()))
+            }
+            None => (None, None),
+        };
+
+        let to_block = match to_block {
+            Some(block) => block_number(&provider, block).await?,
+            None => provider.get_block_number().await?.as_u64(),
+        };
+        let from_block = match from_block {
+            Some(block) => block_number(&provider, block).await?,
+            None => to_block,
+        };
+        if from_block > to_block {
+            eyre::bail!("--from-block {from_block} is after --to-block {to_block}")
+        }
+
+        let filter = CallFilter { to, from, selector };
+        let mut calls = find_calls(&provider, from_block, to_block, &filter).await?;
+
+        // decode the calls, looking up every selector once
+        let mut db = SignaturesDb::load();
+        let mut signatures: HashMap<Selector, Option<String>> = HashMap::new();
+        for call in &mut calls {
+            if call.input.len() < 4 {
+                continue
+            }
+            let selector = Selector::from_slice(&call.input[..4]);
+            let signature = match (&signature, signatures.get(&selector)) {
+                (Some(signature), _) => Some(signature.clone()),
+                (None, Some(signature)) => signature.clone(),
+                (None, None) => {
+                    let signature = db
+                        .decode_calldata(&call.input.to_string(), offline)
+                        .await
+                        .ok()
+                        .and_then(|sigs| sigs.into_iter().next());
+                    signatures.insert(selector, signature.clone());
+                    signature
+                }
+            };
+            call.decoded = signature.and_then(|signature| {
+                let tokens =
+                    abi_decode_calldata(&signature, &call.input.to_string(), true, true).ok()?;
+                Some(format!(
+                    "{signature}({})",
+                    format_tokens(&tokens).collect::<Vec<_>>().join(", ")
+                ))
+            });
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&calls)?);
+            return Ok(())
+        }
+        if calls.is_empty() {
+            println!("No calls found in blocks {from_block} to {to_block}.");
+        }
+        for call in &calls {
+            let tx = call.transaction_hash.map(|hash| format!("{hash:?}")).unwrap_or_default();
+            println!(
+                "block {} tx {tx} [{}] {} {:?} -> {:?} value {}",
+                call.block_number,
+                call.trace_address.iter().map(ToString::to_string).collect::<Vec<_>>().join(","),
+                call.call_type,
+                call.from,
+                call.to,
+                call.value
+            );
+            match &call.decoded {
+                Some(decoded) => println!("  {decoded}"),
+                None => println!("  {}", call.input),
+            }
+            if let Some(error) = &call.error {
+                println!("  error: {error}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the number of a block.
+async fn block_number(provider: &RetryProvider, block: BlockId) -> Result<u64> {
+    if let BlockId::Number(BlockNumber::Number(number)) = block {
+        return Ok(number.as_u64())
+    }
+    provider
+        .get_block(block)
+        .await?
+        .and_then(|block| block.number)
+        .map(|number| number.as_u64())
+        .ok_or_else(|| eyre::eyre!("block {block:?} not found"))
+}
+
+/// Finds the calls matching the filter in a block range.
+///
+/// Uses `trace_filter` if the node supports it, and falls back to tracing every block with
+/// `trace_block`, or with geth's `debug_traceBlockByNumber` and its `callTracer`.
+async fn find_calls(
+    provider: &RetryProvider,
+    from_block: u64,
+    to_block: u64,
+    filter: &CallFilter,
+) -> Result<Vec<FoundCall>> {
+    let trace_filter = TraceFilter::default()
+        .from_block(from_block)
+        .to_block(to_block)
+        .to_address(filter.to.clone())
+        .from_address(filter.from.clone());
+    if let Ok(traces) = provider.trace_filter(trace_filter).await {
+        return Ok(traces
+            .into_iter()
+            .filter_map(from_trace)
+            .filter(|call| filter.matches(call))
+            .collect())
+    }
+
+    let mut calls = Vec::new();
+    for number in from_block..=to_block {
+        let block_calls = match provider.trace_block(number.into()).await {
+            Ok(traces) => traces.into_iter().filter_map(from_trace).collect(),
+            Err(_) => debug_trace_block(provider, number).await.wrap_err_with(|| {
+                format!(
+                    "failed to trace block {number}, the node supports neither `trace_filter`, \
+                     `trace_block` nor `debug_traceBlockByNumber`"
+                )
+            })?,
+        };
+        calls.extend(block_calls.into_iter().filter(|call| filter.matches(call)));
+    }
+    Ok(calls)
+}
+
+/// Converts a parity style trace of a call.
+fn from_trace(trace: Trace) -> Option<FoundCall> {
+    let Action::Call(call) = trace.action else { return None };
+    let call_type = match call.call_type {
+        CallType::StaticCall => "staticcall",
+        CallType::DelegateCall => "delegatecall",
+        CallType::CallCode => "callcode",
+        CallType::Call | CallType::None => "call",
+    };
+    Some(FoundCall {
+        block_number: trace.block_number,
+        transaction_hash: trace.transaction_hash,
+        trace_address: trace.trace_address,
+        call_type: call_type.to_string(),
+        from: call.from,
+        to: call.to,
+        value: call.value,
+        input: call.input,
+        error: trace.error,
+        decoded: None,
+    })
+}
+
+/// Traces the calls of a block with geth's `callTracer`.
+async fn debug_trace_block(provider: &RetryProvider, number: u64) -> Result<Vec<FoundCall>> {
+    let block: Block<H256> =
+        provider.get_block(number).await?.ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+    let results: Vec<Value> = provider
+        .request(
+            "debug_traceBlockByNumber",
+            (BlockNumber::Number(number.into()), json!({ "tracer": "callTracer" })),
+        )
+        .await?;
+
+    let mut calls = Vec::new();
+    for (i, result) in results.into_iter().enumerate() {
+        // geth wraps every frame in an object with the transaction hash since v1.11
+        let tx_hash = result
+            .get("txHash")
+            .and_then(|hash| serde_json::from_value(hash.clone()).ok())
+            .or_else(|| block.transactions.get(i).copied());
+        let frame = match result.get("result") {
+            Some(frame) => frame.clone(),
+            None => result,
+        };
+        let frame: CallFrame =
+            serde_json::from_value(frame).wrap_err("failed to decode the call frame")?;
+        flatten_frame(frame, number, tx_hash, vec![], &mut calls);
+    }
+    Ok(calls)
+}
+
+/// Collects the calls of a `callTracer` frame and its subcalls, in execution order.
+fn flatten_frame(
+    frame: CallFrame,
+    block_number: u64,
+    transaction_hash: Option<H256>,
+    trace_address: Vec<usize>,
+    calls: &mut Vec<FoundCall>,
+) {
+    let typ = frame.typ.to_lowercase();
+    if let (Some(to), "call" | "staticcall" | "delegatecall" | "callcode") =
+        (frame.to, typ.as_str())
+    {
+        calls.push(FoundCall {
+            block_number,
+            transaction_hash,
+            trace_address: trace_address.clone(),
+            call_type: typ,
+            from: frame.from,
+            to,
+            value: frame.value.unwrap_or_default(),
+            input: frame.input,
+            error: frame.error,
+            decoded: None,
+        });
+    }
+    for (i, subcall) in frame.calls.into_iter().enumerate() {
+        let mut address = trace_address.clone();
+        address.push(i);
+        flatten_frame(subcall, block_number, transaction_hash, address, calls);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_flatten_call_frames() {
+        let frame: CallFrame = serde_json::from_value(json!({
+            "type": "CALL",
+            "from": "0x0000000000000000000000000000000000000001",
+            "to": "0x0000000000000000000000000000000000000002",
+            "value": "0x10",
+            "input": "0xa9059cbb",
+            "calls": [
+                {
+                    "type": "CREATE",
+                    "from": "0x0000000000000000000000000000000000000002",
+                    "to": "0x0000000000000000000000000000000000000003",
+                    "input": "0x60806040",
+                    "calls": [{
+                        "type": "STATICCALL",
+                        "from": "0x0000000000000000000000000000000000000003",
+                        "to": "0x0000000000000000000000000000000000000002",
+                        "input": "0x70a08231",
+                    }]
+                },
+                {
+                    "type": "DELEGATECALL",
+                    "from": "0x0000000000000000000000000000000000000002",
+                    "to": "0x0000000000000000000000000000000000000004",
+                    "input": "0xa9059cbb",
+                    "error": "execution reverted"
+                }
+            ]
+        }))
+        .unwrap();
+
+        let mut calls = Vec::new();
+        flatten_frame(frame, 1, None, vec![], &mut calls);
+        let summary = calls
+            .iter()
+            .map(|call| (call.call_type.as_str(), call.trace_address.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [("call", vec![]), ("staticcall", vec![0, 0]), ("delegatecall", vec![1])]
+        );
+        assert_eq!(calls[0].value, U256::from(16));
+        assert_eq!(calls[2].error.as_deref(), Some("execution reverted"));
+
+        let filter = CallFilter {
+            to: vec![],
+            from: vec![Address::from_low_u64_be(2)],
+            selector: Some(Selector::from_str("0xa9059cbb").unwrap()),
+        };
+        let matched = calls.iter().filter(|call| filter.matches(call)).collect::<Vec<_>>();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].call_type, "delegatecall");
+    }
+}
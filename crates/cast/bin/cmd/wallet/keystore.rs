@@ -0,0 +1,194 @@
+use super::list::default_keystore_dir;
+use clap::Parser;
+use ethers_core::rand::thread_rng;
+use eyre::{Context, Result};
+use foundry_cli::prompt;
+use foundry_common::fs;
+use std::path::PathBuf;
+use yansi::Paint;
+
+/// An account in a keystore directory, as used with `--account`.
+#[derive(Clone, Debug, Parser)]
+pub struct KeystoreAccount {
+    /// The name of the account in the keystore directory.
+    #[clap(value_name = "ACCOUNT_NAME")]
+    account_name: String,
+
+    /// The keystore directory of the account, instead of the default keystores directory
+    /// (~/.foundry/keystores).
+    #[clap(long, short, value_name = "DIR")]
+    keystore_dir: Option<PathBuf>,
+}
+
+impl KeystoreAccount {
+    /// Returns the keystore directory of the account.
+    fn dir(&self) -> Result<PathBuf> {
+        match &self.keystore_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => default_keystore_dir(),
+        }
+    }
+
+    /// Returns the path of the keystore of the account, which must exist.
+    fn path(&self) -> Result<PathBuf> {
+        let dir = self.dir()?;
+        let path = dir.join(&self.account_name);
+        if !path.is_file() {
+            eyre::bail!("Keystore account `{}` not found in {}", self.account_name, dir.display())
+        }
+        Ok(path)
+    }
+
+    /// Decrypts the private key of the account, prompting for the password if none is given.
+    fn decrypt(&self, password: Option<String>) -> Result<Vec<u8>> {
+        let path = self.path()?;
+        let password = match password {
+            Some(password) => password,
+            None => rpassword::prompt_password(format!(
+                "Enter the password of `{}`: ",
+                self.account_name
+            ))?,
+        };
+        eth_keystore::decrypt_key(&path, password).wrap_err_with(|| {
+            format!("Failed to decrypt `{}`, is the password correct?", self.account_name)
+        })
+    }
+}
+
+/// CLI arguments for `cast wallet change-password`.
+#[derive(Clone, Debug, Parser)]
+pub struct ChangePasswordArgs {
+    #[clap(flatten)]
+    account: KeystoreAccount,
+
+    /// The current password of the keystore, in cleartext.
+    ///
+    /// This is UNSAFE to use, if not provided the password is prompted for.
+    #[clap(long, env = "CAST_PASSWORD", value_name = "PASSWORD")]
+    unsafe_password: Option<String>,
+
+    /// The new password of the keystore, in cleartext.
+    ///
+    /// This is UNSAFE to use, if not provided the password is prompted for.
+    #[clap(long, env = "CAST_NEW_PASSWORD", value_name = "PASSWORD")]
+    unsafe_new_password: Option<String>,
+}
+
+impl ChangePasswordArgs {
+    pub fn run(self) -> Result<()> {
+        let ChangePasswordArgs { account, unsafe_password, unsafe_new_password } = self;
+        let private_key = account.decrypt(unsafe_password)?;
+
+        let new_password = match unsafe_new_password {
+            Some(password) => password,
+            None => {
+                let password = rpassword::prompt_password("Enter the new password: ")?;
+                if password != rpassword::prompt_password("Confirm the new password: ")? {
+                    eyre::bail!("The passwords don't match")
+                }
+                password
+            }
+        };
+
+        // write the re-encrypted keystore next to the old one and replace it, so the key is never
+        // lost if writing fails
+        let dir = account.dir()?;
+        let tmp_name = format!(".{}.tmp", account.account_name);
+        eth_keystore::encrypt_key(
+            &dir,
+            &mut thread_rng(),
+            private_key,
+            new_password,
+            Some(&tmp_name),
+        )?;
+        fs::rename(dir.join(&tmp_name), account.path()?)?;
+
+        let message =
+            format!("The password of `{}` was changed successfully.", account.account_name);
+        println!("{}", Paint::green(message));
+        Ok(())
+    }
+}
+
+/// CLI arguments for `cast wallet export`.
+#[derive(Clone, Debug, Parser)]
+pub struct ExportArgs {
+    #[clap(flatten)]
+    account: KeystoreAccount,
+
+    /// The password of the keystore, in cleartext.
+    ///
+    /// This is UNSAFE to use, if not provided the password is prompted for.
+    #[clap(long, env = "CAST_PASSWORD", value_name = "PASSWORD")]
+    unsafe_password: Option<String>,
+}
+
+impl ExportArgs {
+    pub fn run(self) -> Result<()> {
+        let ExportArgs { account, unsafe_password } = self;
+        let name = &account.account_name;
+        account.path()?;
+
+        println!(
+            "{} This prints the private key of `{name}` in cleartext. Anyone who sees it has full \
+             control over the account.",
+            Paint::yellow("Warning:").bold()
+        );
+        let answer: String = prompt!("Do you want to continue? [y/N] ")?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            eyre::bail!("Aborted, the export was not confirmed.")
+        }
+        let answer: String = prompt!("Type the name of the account to confirm: ")?;
+        if answer.trim() != name {
+            eyre::bail!("Aborted, the account name doesn't match.")
+        }
+
+        let private_key = account.decrypt(unsafe_password)?;
+        println!("0x{}", hex::encode(private_key));
+        Ok(())
+    }
+}
+
+/// CLI arguments for `cast wallet rename`.
+#[derive(Clone, Debug, Parser)]
+pub struct RenameArgs {
+    #[clap(flatten)]
+    account: KeystoreAccount,
+
+    /// The new name of the account.
+    #[clap(value_name = "NEW_NAME")]
+    new_name: String,
+}
+
+impl RenameArgs {
+    pub fn run(self) -> Result<()> {
+        let RenameArgs { account, new_name } = self;
+        if new_name.is_empty() || new_name.contains(['/', '\\']) || new_name.starts_with('.') {
+            eyre::bail!("Invalid account name `{new_name}`")
+        }
+
+        let from = account.path()?;
+        let to = account.dir()?.join(&new_name);
+        if to.exists() {
+            eyre::bail!("Keystore file already exists at {}", to.display());
+        }
+        fs::rename(from, to)?;
+
+        let message = format!("`{}` was renamed to `{new_name}`.", account.account_name);
+        println!("{}", Paint::green(message));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_keystore_account() {
+        let args = RenameArgs::parse_from(["foundry-cli", "old", "new", "--keystore-dir", "keys"]);
+        assert_eq!(args.account.account_name, "old");
+        assert_eq!(args.account.dir().unwrap(), PathBuf::from("keys"));
+        assert_eq!(args.new_name, "new");
+    }
+}
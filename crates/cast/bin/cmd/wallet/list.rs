@@ -0,0 +1,323 @@
+use alloy_primitives::Address;
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::Parser;
+use ethers_signers::{HDPath as LedgerHDPath, Ledger, LocalWallet, Signer, Trezor, TrezorHDPath};
+use eyre::{Context, Result};
+use foundry_cli::opts::{MultiWallet, Wallet};
+use foundry_common::{fs, term::cli_warn, types::ToAlloy};
+use foundry_config::Config;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// The chain id the signers are created with, the addresses don't depend on it.
+const CHAIN_ID: u64 = 1;
+
+/// CLI arguments for `cast wallet list`.
+#[derive(Clone, Debug, Parser)]
+pub struct ListArgs {
+    /// Number of Ledger Live derivation paths to list for the connected Ledger and Trezor
+    /// devices, unless derivation paths are provided.
+    #[clap(long, default_value_t = 3, value_name = "NUMBER")]
+    max_senders: usize,
+
+    /// Print the address and the source of every signer, and when the accounts in the keystore
+    /// directory were created.
+    ///
+    /// By default, only the names of the accounts in the keystore directory and the addresses of
+    /// the other signers are printed.
+    #[clap(long)]
+    long: bool,
+
+    /// Print the signers as JSON.
+    #[clap(long, short)]
+    json: bool,
+
+    #[clap(flatten)]
+    wallets: MultiWallet,
+}
+
+/// A signer available to cast, annotated with where it was found.
+#[derive(Clone, Debug, Serialize)]
+struct ListedSigner {
+    /// The address of the signer, `None` for keystores without a cleartext address.
+    address: Option<Address>,
+    /// The name of the account, for the accounts in the keystore directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// Where the signer was found.
+    source: String,
+    /// When the keystore of the signer was created, in RFC 3339 format, for the accounts in the
+    /// keystore directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created: Option<String>,
+}
+
+impl ListArgs {
+    pub async fn run(self) -> Result<()> {
+        let mut signers = default_keystores()?;
+        signers.extend(self.keystores()?);
+
+        let local = [
+            ("private key", self.private_keys()?),
+            ("interactive", self.wallets.interactives()?),
+            ("mnemonic", self.wallets.mnemonics()?),
+        ];
+        for (source, wallets) in local {
+            signers.extend(wallets.into_iter().flatten().map(|wallet| ListedSigner {
+                address: Some(wallet.address().to_alloy()),
+                name: None,
+                source: source.to_string(),
+                created: None,
+            }));
+        }
+
+        if self.wallets.ledger {
+            for path in self.hd_paths() {
+                let ledger = Ledger::new(LedgerHDPath::Other(path.clone()), CHAIN_ID).await;
+                signers.extend(hw_signer("ledger", &path, ledger.map(|ledger| ledger.address())));
+            }
+        }
+        if self.wallets.trezor {
+            for path in self.hd_paths() {
+                let trezor = Trezor::new(TrezorHDPath::Other(path.clone()), CHAIN_ID, None).await;
+                signers.extend(hw_signer("trezor", &path, trezor.map(|trezor| trezor.address())));
+            }
+        }
+
+        if let Some(aws_signers) = self.wallets.aws_signers(CHAIN_ID).await? {
+            signers.extend(aws_signers.into_iter().map(|signer| ListedSigner {
+                address: Some(signer.address().to_alloy()),
+                name: None,
+                source: "aws".to_string(),
+                created: None,
+            }));
+        }
+        if let Some(pkcs11_signers) = self.wallets.pkcs11.signers(CHAIN_ID)? {
+            signers.extend(pkcs11_signers.into_iter().map(|signer| ListedSigner {
+                address: Some(signer.address().to_alloy()),
+                name: None,
+                source: "pkcs11".to_string(),
+                created: None,
+            }));
+        }
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&signers)?);
+            return Ok(())
+        }
+        for ListedSigner { address, name, source, created } in signers {
+            let address = match address {
+                Some(address) => address.to_checksum(None),
+                None => "<unknown address>".to_string(),
+            };
+            match (self.long, name, created) {
+                (true, _, Some(created)) => println!("{address} ({source}, created {created})"),
+                (true, _, None) => println!("{address} ({source})"),
+                (false, Some(name), _) => println!("{name}"),
+                (false, None, _) => println!("{address}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the signers of the cleartext private keys.
+    fn private_keys(&self) -> Result<Option<Vec<LocalWallet>>> {
+        let mut wallets = self.wallets.clone();
+        if let Some(private_key) = wallets.private_key.take() {
+            wallets.private_keys.get_or_insert_with(Vec::new).push(private_key);
+        }
+        wallets.private_keys()
+    }
+
+    /// Returns the signers of the keystores passed with `--keystore` or `--account`.
+    ///
+    /// The addresses are read from the keystore files, so no password is needed.
+    fn keystores(&self) -> Result<Vec<ListedSigner>> {
+        let mut paths = self.wallets.keystore_paths.clone().unwrap_or_default();
+        if let Some(names) = &self.wallets.keystore_account_names {
+            let dir = default_keystore_dir()?;
+            paths.extend(names.iter().map(|name| dir.join(name).to_string_lossy().into_owned()));
+        }
+        paths
+            .into_iter()
+            .map(|path| {
+                let address = keystore_address(Path::new(&path))?;
+                Ok(ListedSigner {
+                    address,
+                    name: None,
+                    source: format!("keystore {path}"),
+                    created: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the derivation paths to list for a hardware wallet.
+    fn hd_paths(&self) -> Vec<String> {
+        match &self.wallets.hd_paths {
+            Some(hd_paths) => hd_paths.clone(),
+            None => (0..self.max_senders).map(ledger_live_path).collect(),
+        }
+    }
+}
+
+/// Prints the addresses of the first `n` accounts of the Ledger or Trezor under the Ledger Live
+/// and the legacy derivation paths, for `cast wallet address --derivation-preview`.
+pub(super) async fn derivation_preview(wallet: &Wallet, n: usize) -> Result<()> {
+    let kind = match (wallet.ledger, wallet.trezor) {
+        (true, _) => "ledger",
+        (_, true) => "trezor",
+        _ => eyre::bail!("--derivation-preview requires --ledger or --trezor"),
+    };
+    for (scheme, path) in preview_paths(n) {
+        let address = if wallet.ledger {
+            Ledger::new(LedgerHDPath::Other(path.clone()), CHAIN_ID)
+                .await
+                .map(|ledger| ledger.address())
+                .map_err(|err| err.to_string())
+        } else {
+            Trezor::new(TrezorHDPath::Other(path.clone()), CHAIN_ID, None)
+                .await
+                .map(|trezor| trezor.address())
+                .map_err(|err| err.to_string())
+        };
+        let address = address
+            .map_err(|err| eyre::eyre!("Could not connect to the {kind} for {path}: {err}"))?;
+        println!("{scheme:<11} {path:<18} {}", address.to_alloy().to_checksum(None));
+    }
+    Ok(())
+}
+
+/// Returns the first `n` Ledger Live and legacy derivation paths, with the name of their scheme.
+fn preview_paths(n: usize) -> Vec<(&'static str, String)> {
+    let ledger_live = (0..n).map(|index| ("ledger-live", ledger_live_path(index)));
+    let legacy = (0..n).map(|index| ("legacy", legacy_path(index)));
+    ledger_live.chain(legacy).collect()
+}
+
+/// Returns the signers of all the keystores in the default keystore directory.
+fn default_keystores() -> Result<Vec<ListedSigner>> {
+    let dir = default_keystore_dir()?;
+    // Create the keystore directory if it doesn't exist
+    fs::create_dir_all(&dir)?;
+
+    let mut keystores = Vec::new();
+    for entry in std::fs::read_dir(&dir).wrap_err("Failed to read the directory")? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_file() || path.extension().is_some() {
+            continue
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let address = keystore_address(&path).unwrap_or_default();
+        // not all platforms record the creation time, the last modification is the closest
+        let metadata = entry.metadata()?;
+        let created = metadata
+            .created()
+            .or_else(|_| metadata.modified())
+            .ok()
+            .map(|time| DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true));
+        keystores.push(ListedSigner {
+            address,
+            source: format!("keystore account {name}"),
+            name: Some(name),
+            created,
+        });
+    }
+    keystores.sort_by(|a, b| a.source.cmp(&b.source));
+    Ok(keystores)
+}
+
+/// Returns the default keystore directory, used by `--account`.
+pub(super) fn default_keystore_dir() -> Result<PathBuf> {
+    Config::foundry_keystores_dir()
+        .ok_or_else(|| eyre::eyre!("Could not find the default keystore directory."))
+}
+
+/// Reads the cleartext address of a JSON keystore, which is optional in the keystore format.
+fn keystore_address(path: &Path) -> Result<Option<Address>> {
+    #[derive(serde::Deserialize)]
+    struct Keystore {
+        address: Option<Address>,
+    }
+    let keystore: Keystore = fs::read_json_file(path)
+        .wrap_err_with(|| format!("Failed to read the keystore {}", path.display()))?;
+    Ok(keystore.address)
+}
+
+/// Returns the signer of a hardware wallet, or warns if the device isn't available.
+fn hw_signer<E: std::fmt::Display>(
+    kind: &str,
+    path: &str,
+    address: Result<ethers_core::types::Address, E>,
+) -> Option<ListedSigner> {
+    match address {
+        Ok(address) => Some(ListedSigner {
+            address: Some(address.to_alloy()),
+            name: None,
+            source: format!("{kind} {path}"),
+            created: None,
+        }),
+        Err(err) => {
+            cli_warn!("Could not connect to the {kind} for {path}: {err}");
+            None
+        }
+    }
+}
+
+/// Returns the Ledger Live derivation path of the index, which Trezor Suite uses as well.
+fn ledger_live_path(index: usize) -> String {
+    format!("m/44'/60'/{index}'/0/0")
+}
+
+/// Returns the legacy derivation path of the index, used by the Ledger Chrome app and MEW.
+fn legacy_path(index: usize) -> String {
+    format!("m/44'/60'/0'/{index}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_read_keystore_address() {
+        let keystore = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/keystore/UTC--2022-12-20T10-30-43.591916000Z--ec554aeafe75601aaab43bd4621a22284db566c2"
+        ));
+        assert_eq!(
+            keystore_address(keystore).unwrap(),
+            Some("0xec554aeafe75601aaab43bd4621a22284db566c2".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn can_parse_list_args() {
+        let args = ListArgs::parse_from(["foundry-cli", "--ledger", "--max-senders", "2"]);
+        assert!(args.wallets.ledger);
+        assert_eq!(args.hd_paths(), vec!["m/44'/60'/0'/0/0", "m/44'/60'/1'/0/0"]);
+
+        let args = ListArgs::parse_from([
+            "foundry-cli",
+            "--trezor",
+            "--mnemonic-derivation-paths",
+            "m/44'/60'/0'/0/7",
+        ]);
+        assert!(args.wallets.trezor);
+        assert_eq!(args.hd_paths(), vec!["m/44'/60'/0'/0/7"]);
+    }
+
+    #[test]
+    fn can_list_preview_paths() {
+        let paths = preview_paths(2);
+        assert_eq!(
+            paths,
+            vec![
+                ("ledger-live", "m/44'/60'/0'/0/0".to_string()),
+                ("ledger-live", "m/44'/60'/1'/0/0".to_string()),
+                ("legacy", "m/44'/60'/0'/0".to_string()),
+                ("legacy", "m/44'/60'/0'/1".to_string()),
+            ]
+        );
+    }
+}
@@ -1,24 +1,31 @@
 use alloy_primitives::Address;
+use cast::checksum::parse_address;
 use clap::Parser;
 use ethers_core::{
     rand::thread_rng,
-    types::{transaction::eip712::TypedData, Signature},
+    types::{transaction::eip712::TypedData, Signature, H256},
 };
 use ethers_signers::{
     coins_bip39::{English, Mnemonic},
     LocalWallet, MnemonicBuilder, Signer,
 };
 use eyre::{Context, Result};
-use foundry_cli::opts::{RawWallet, Wallet};
+use foundry_cli::opts::{RawWallet, Wallet, WalletSigner};
 use foundry_common::{
     fs,
     types::{ToAlloy, ToEthers},
 };
 use foundry_config::Config;
 use serde_json::json;
-use std::path::Path;
+use std::{path::Path, str::FromStr};
 use yansi::Paint;
 
+pub mod keystore;
+use keystore::{ChangePasswordArgs, ExportArgs, RenameArgs};
+
+pub mod list;
+use list::ListArgs;
+
 pub mod vanity;
 use vanity::VanityArgs;
 
@@ -78,6 +85,13 @@ pub enum WalletSubcommands {
         )]
         private_key_override: Option<String>,
 
+        /// List the addresses of the first N accounts of the Ledger or Trezor under both the
+        /// Ledger Live and the legacy derivation paths, to find the path of an account.
+        ///
+        /// Used with --ledger or --trezor.
+        #[clap(long, value_name = "N", conflicts_with_all = ["private_key_override", "hd_path"])]
+        derivation_preview: Option<usize>,
+
         #[clap(flatten)]
         wallet: Wallet,
     },
@@ -108,6 +122,13 @@ pub enum WalletSubcommands {
         #[clap(long, requires = "data")]
         from_file: bool,
 
+        /// If provided, the message will be treated as a raw 32-byte digest and signed as is,
+        /// without the Ethereum Signed Message header.
+        ///
+        /// Not supported by Ledger and Trezor devices.
+        #[clap(long, conflicts_with = "data")]
+        no_hash: bool,
+
         #[clap(flatten)]
         wallet: Wallet,
     },
@@ -122,7 +143,7 @@ pub enum WalletSubcommands {
         signature: Signature,
 
         /// The address of the message signer.
-        #[clap(long, short)]
+        #[clap(long, short, value_parser = parse_address)]
         address: Address,
     },
     /// Import a private key into an encrypted keystore.
@@ -138,9 +159,23 @@ pub enum WalletSubcommands {
         #[clap(flatten)]
         raw_wallet_options: RawWallet,
     },
-    /// List all the accounts in the keystore default directory
+    /// List all the signers cast can use: the accounts in the keystore default directory, and the
+    /// keystores, keys and devices provided with the wallet options.
     #[clap(visible_alias = "ls")]
-    List,
+    List(ListArgs),
+
+    /// Change the password of an account in the keystore directory.
+    ChangePassword(ChangePasswordArgs),
+
+    /// Print the private key of an account in the keystore directory.
+    ///
+    /// The export has to be confirmed twice, as anyone who sees the private key has full control
+    /// over the account.
+    Export(ExportArgs),
+
+    /// Rename an account in the keystore directory.
+    #[clap(visible_alias = "mv")]
+    Rename(RenameArgs),
 }
 
 impl WalletSubcommands {
@@ -234,7 +269,18 @@ impl WalletSubcommands {
             WalletSubcommands::Vanity(cmd) => {
                 cmd.run()?;
             }
-            WalletSubcommands::Address { wallet, private_key_override } => {
+            WalletSubcommands::Address {
+                wallet,
+                private_key_override: _,
+                derivation_preview: Some(n),
+            } => {
+                list::derivation_preview(&wallet, n).await?;
+            }
+            WalletSubcommands::Address {
+                wallet,
+                private_key_override,
+                derivation_preview: None,
+            } => {
                 let wallet = private_key_override
                     .map(|pk| Wallet {
                         raw: RawWallet { private_key: Some(pk), ..Default::default() },
@@ -246,7 +292,7 @@ impl WalletSubcommands {
                 let addr = wallet.address();
                 println!("{}", addr.to_alloy().to_checksum(None));
             }
-            WalletSubcommands::Sign { message, data, from_file, wallet } => {
+            WalletSubcommands::Sign { message, data, from_file, no_hash, wallet } => {
                 let wallet = wallet.signer(0).await?;
                 let sig = if data {
                     let typed_data: TypedData = if from_file {
@@ -256,9 +302,20 @@ impl WalletSubcommands {
                         // data is a json string
                         serde_json::from_str(&message)?
                     };
-                    wallet.sign_typed_data(&typed_data).await?
+                    wallet
+                        .sign_typed_data(&typed_data)
+                        .await
+                        .map_err(|err| signing_error(&wallet, err.into(), true))?
+                } else if no_hash {
+                    let hash = H256::from_str(&message).wrap_err(
+                        "With --no-hash the message must be a 0x-prefixed 32-byte hex digest",
+                    )?;
+                    wallet.sign_hash(hash).await?
                 } else {
-                    wallet.sign_message(Self::hex_str_to_bytes(&message)?).await?
+                    wallet
+                        .sign_message(Self::hex_str_to_bytes(&message)?)
+                        .await
+                        .map_err(|err| signing_error(&wallet, err.into(), false))?
                 };
                 println!("0x{sig}");
             }
@@ -320,41 +377,17 @@ flag to set your key via:
                 );
                 println!("{}", Paint::green(success_message));
             }
-            WalletSubcommands::List => {
-                let default_keystore_dir = Config::foundry_keystores_dir()
-                    .ok_or_else(|| eyre::eyre!("Could not find the default keystore directory."))?;
-                // Create the keystore directory if it doesn't exist
-                fs::create_dir_all(&default_keystore_dir)?;
-                // List all files in keystore directory
-                let keystore_files: Result<Vec<_>, eyre::Report> =
-                    std::fs::read_dir(&default_keystore_dir)
-                        .wrap_err("Failed to read the directory")?
-                        .filter_map(|entry| match entry {
-                            Ok(entry) => {
-                                let path = entry.path();
-                                if path.is_file() && path.extension().is_none() {
-                                    Some(Ok(path))
-                                } else {
-                                    None
-                                }
-                            }
-                            Err(e) => Some(Err(e.into())),
-                        })
-                        .collect::<Result<Vec<_>, eyre::Report>>();
-                // Print the names of the keystore files
-                match keystore_files {
-                    Ok(files) => {
-                        // Print the names of the keystore files
-                        for file in files {
-                            if let Some(file_name) = file.file_name() {
-                                if let Some(name) = file_name.to_str() {
-                                    println!("{}", name);
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => return Err(e),
-                }
+            WalletSubcommands::List(cmd) => {
+                cmd.run().await?;
+            }
+            WalletSubcommands::ChangePassword(cmd) => {
+                cmd.run()?;
+            }
+            WalletSubcommands::Export(cmd) => {
+                cmd.run()?;
+            }
+            WalletSubcommands::Rename(cmd) => {
+                cmd.run()?;
             }
         };
 
@@ -369,6 +402,27 @@ flag to set your key via:
     }
 }
 
+/// Adds the steps to resolve common hardware wallet failures to a signing error.
+fn signing_error(wallet: &WalletSigner, err: eyre::Report, typed_data: bool) -> eyre::Report {
+    let hint = match wallet {
+        WalletSigner::Ledger(_) if typed_data => {
+            "Make sure the Ledger is unlocked and the Ethereum app is open. Typed data the device \
+             can't display requires \"Blind signing\" to be enabled in the Ethereum app settings."
+        }
+        WalletSigner::Ledger(_) => {
+            "Make sure the Ledger is unlocked and the Ethereum app is open. Messages the device \
+             can't display as text may require \"Blind signing\" to be enabled in the Ethereum app settings."
+        }
+        WalletSigner::Trezor(_) if typed_data => {
+            "Make sure the Trezor is unlocked and its firmware is up to date, older firmware \
+             versions can't sign typed data."
+        }
+        WalletSigner::Trezor(_) => "Make sure the Trezor is unlocked and confirm the message on the device.",
+        _ => return err,
+    };
+    err.wrap_err(format!("Failed to sign with {}.\n{hint}", wallet.kind()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -430,4 +484,53 @@ mod tests {
             _ => panic!("expected WalletSubcommands::Sign"),
         }
     }
+
+    #[test]
+    fn can_parse_wallet_sign_no_hash() {
+        let args = WalletSubcommands::parse_from(["foundry-cli", "sign", "--no-hash", "0x00"]);
+        match args {
+            WalletSubcommands::Sign { no_hash, data, .. } => {
+                assert!(no_hash);
+                assert!(!data);
+            }
+            _ => panic!("expected WalletSubcommands::Sign"),
+        }
+        assert!(WalletSubcommands::try_parse_from([
+            "foundry-cli",
+            "sign",
+            "--no-hash",
+            "--data",
+            "{}"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn can_parse_wallet_address_derivation_preview() {
+        let args = WalletSubcommands::parse_from([
+            "foundry-cli",
+            "address",
+            "--ledger",
+            "--derivation-preview",
+            "5",
+        ]);
+        match args {
+            WalletSubcommands::Address { derivation_preview, wallet, .. } => {
+                assert_eq!(derivation_preview, Some(5));
+                assert!(wallet.ledger);
+            }
+            _ => panic!("expected WalletSubcommands::Address"),
+        }
+
+        let args = WalletSubcommands::try_parse_from([
+            "foundry-cli",
+            "address",
+            "--ledger",
+            "--derivation-preview",
+            "5",
+            "--mnemonic-derivation-path",
+            "m/44'/60'/0'/0/0",
+        ]);
+        assert!(args.is_err());
+    }
 }
@@ -2,29 +2,33 @@
 extern crate tracing;
 
 use alloy_primitives::{keccak256, Address, B256};
-use cast::{Cast, SimpleCast};
+use cast::{
+    checksum, parse_abi_file, parse_calldata_list, BulkCalldataDecoder, CalldataDecoder, Cast,
+    SimpleCast,
+};
 use clap::{CommandFactory, Parser};
-use clap_complete::generate;
 use ethers_core::types::{BlockId, BlockNumber::Latest};
 use ethers_providers::Middleware;
-use eyre::Result;
+use eyre::{Result, WrapErr};
 use foundry_cli::{handler, prompt, stdin, utils};
 use foundry_common::{
-    abi::get_event,
+    abi::{get_event, get_func},
     fmt::format_tokens,
     fs,
     selectors::{
-        decode_calldata, decode_event_topic, decode_function_selector, import_selectors,
-        parse_signatures, pretty_calldata, ParsedSignatures, SelectorImportData,
+        import_selectors, parse_signatures, ParsedSignatures, SelectorImportData, SelectorType,
+        SignaturesDb,
     },
     types::{ToAlloy, ToEthers},
+    units::UnitsDisplay,
 };
 use foundry_config::Config;
-use std::time::Instant;
+use std::{path::Path, time::Instant};
 
 pub mod cmd;
 pub mod opts;
 
+use cmd::completions;
 use opts::{Opts, Subcommands, ToBaseArgs};
 
 #[tokio::main]
@@ -34,6 +38,10 @@ async fn main() -> Result<()> {
     utils::subscriber();
     utils::enable_paint();
 
+    // the address arguments are validated by their parsers, so `--no-checksum-validate` has to be
+    // known before the arguments are parsed
+    let matches = Opts::command().ignore_errors(true).get_matches();
+    checksum::set_checksum_validation(!matches.get_flag("no_checksum_validate"));
     let opts = Opts::parse();
     match opts.sub {
         // Constants
@@ -91,9 +99,9 @@ async fn main() -> Result<()> {
             };
             println!("0x{output}");
         }
-        Subcommands::ToCheckSumAddress { address } => {
+        Subcommands::ToCheckSumAddress { address, chain } => {
             let value = stdin::unwrap_line(address)?;
-            println!("{}", value.to_checksum(None));
+            println!("{}", value.to_checksum(chain.map(|chain| chain.id())));
         }
         Subcommands::ToUint256 { value } => {
             let value = stdin::unwrap_line(value)?;
@@ -115,6 +123,15 @@ async fn main() -> Result<()> {
             let value = stdin::unwrap_line(value)?;
             println!("{}", SimpleCast::to_wei(&value, &unit)?);
         }
+        Subcommands::ParseUnits { value, unit } => {
+            let value = stdin::unwrap_line(value)?;
+            println!("{}", SimpleCast::parse_units(&value, &unit)?);
+        }
+        Subcommands::FormatUnits { value, unit, precision, separator } => {
+            let value = stdin::unwrap_line(value)?;
+            let display = UnitsDisplay { precision, thousands_separator: separator };
+            println!("{}", SimpleCast::format_units(&value, &unit, display)?);
+        }
         Subcommands::FromRlp { value } => {
             let value = stdin::unwrap_line(value)?;
             println!("{}", SimpleCast::from_rlp(value)?);
@@ -161,7 +178,28 @@ async fn main() -> Result<()> {
         Subcommands::AbiEncode { sig, args } => {
             println!("{}", SimpleCast::abi_encode(&sig, &args)?);
         }
-        Subcommands::CalldataDecode { sig, calldata } => {
+        Subcommands::ConstructorEncode(cmd) => cmd.run().await?,
+        Subcommands::CalldataDecode { sig, calldata, file: Some(file), abi, offline } => {
+            let content = if file == Path::new("-") {
+                std::io::read_to_string(std::io::stdin())?
+            } else {
+                fs::read_to_string(&file)?
+            };
+            let mut decoder = if let Some(sig) = sig {
+                BulkCalldataDecoder::new([get_func(&sig)?])
+            } else if let Some(abi) = abi {
+                let abi = parse_abi_file(&fs::read_to_string(&abi)?)
+                    .wrap_err_with(|| format!("failed to load ABI from {}", abi.display()))?;
+                BulkCalldataDecoder::new(abi.functions().cloned())
+            } else {
+                BulkCalldataDecoder::with_signatures_db(offline)
+            };
+            for (i, calldata) in parse_calldata_list(&content)?.iter().enumerate() {
+                println!("{}", serde_json::to_string(&decoder.decode(i, calldata).await)?);
+            }
+        }
+        Subcommands::CalldataDecode { sig, calldata, .. } => {
+            let (sig, calldata) = sig.zip(calldata).expect("required by clap");
             let tokens = SimpleCast::calldata_decode(&sig, &calldata, true)?;
             let tokens = format_tokens(&tokens);
             tokens.for_each(|t| println!("{t}"));
@@ -170,10 +208,12 @@ async fn main() -> Result<()> {
             println!("{}", SimpleCast::calldata_encode(sig, &args)?);
         }
         Subcommands::Interface(cmd) => cmd.run().await?,
+        Subcommands::PrettifyAbi(cmd) => cmd.run()?,
+        Subcommands::AbiDiff(cmd) => cmd.run().await?,
         Subcommands::Bind(cmd) => cmd.run().await?,
         Subcommands::PrettyCalldata { calldata, offline } => {
             let calldata = stdin::unwrap_line(calldata)?;
-            println!("{}", pretty_calldata(&calldata, offline).await?);
+            println!("{}", SignaturesDb::load().pretty_calldata(&calldata, offline).await?);
         }
         Subcommands::Sig { sig, optimize } => {
             let sig = stdin::unwrap_line(sig)?;
@@ -200,16 +240,7 @@ async fn main() -> Result<()> {
                 Cast::new(provider).age(block.unwrap_or(BlockId::Number(Latest))).await?
             );
         }
-        Subcommands::Balance { block, who, ether, rpc } => {
-            let config = Config::from(&rpc);
-            let provider = utils::get_provider(&config)?;
-            let value = Cast::new(provider).balance(who, block).await?;
-            if ether {
-                println!("{}", SimpleCast::from_wei(&value.to_string(), "eth")?);
-            } else {
-                println!("{value}");
-            }
-        }
+        Subcommands::Balance(cmd) => cmd.run().await?,
         Subcommands::BaseFee { block, rpc } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
@@ -253,6 +284,7 @@ async fn main() -> Result<()> {
             let provider = utils::get_provider(&config)?;
             println!("{}", Cast::new(provider).code(who, block, disassemble).await?);
         }
+        Subcommands::BytecodeDiff(cmd) => cmd.run().await?,
         Subcommands::Codesize { block, who, rpc } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
@@ -270,6 +302,17 @@ async fn main() -> Result<()> {
             println!("{}", SimpleCast::disassemble(&bytecode)?);
         }
         Subcommands::FindBlock(cmd) => cmd.run().await?,
+        Subcommands::Status(cmd) => cmd.run().await?,
+        Subcommands::Codehash { block, who, rpc } => {
+            let config = Config::from(&rpc);
+            let provider = utils::get_provider(&config)?;
+            println!("{}", Cast::new(provider).codehash(who, block).await?);
+        }
+        Subcommands::StorageRoot { block, who, rpc } => {
+            let config = Config::from(&rpc);
+            let provider = utils::get_provider(&config)?;
+            println!("{}", Cast::new(provider).storage_root(who, block).await?);
+        }
         Subcommands::GasPrice { rpc } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
@@ -288,11 +331,7 @@ async fn main() -> Result<()> {
             let provider = utils::get_provider(&config)?;
             println!("{}", Cast::new(provider).admin(who, block).await?);
         }
-        Subcommands::Nonce { block, who, rpc } => {
-            let config = Config::from(&rpc);
-            let provider = utils::get_provider(&config)?;
-            println!("{}", Cast::new(provider).nonce(who, block).await?);
-        }
+        Subcommands::Nonce(cmd) => cmd.run().await?,
         Subcommands::Proof { address, slots, rpc, block } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
@@ -347,7 +386,9 @@ async fn main() -> Result<()> {
         // 4Byte
         Subcommands::FourByte { selector } => {
             let selector = stdin::unwrap_line(selector)?;
-            let sigs = decode_function_selector(&selector).await?;
+            let sigs = SignaturesDb::load()
+                .decode_selector(&selector, SelectorType::Function, false)
+                .await?;
             if sigs.is_empty() {
                 eyre::bail!("No matching function signatures found for selector `{selector}`");
             }
@@ -357,7 +398,7 @@ async fn main() -> Result<()> {
         }
         Subcommands::FourByteDecode { calldata } => {
             let calldata = stdin::unwrap_line(calldata)?;
-            let sigs = decode_calldata(&calldata).await?;
+            let sigs = SignaturesDb::load().decode_calldata(&calldata, false).await?;
             sigs.iter().enumerate().for_each(|(i, sig)| println!("{}) \"{sig}\"", i + 1));
 
             let sig = match sigs.len() {
@@ -376,7 +417,8 @@ async fn main() -> Result<()> {
         }
         Subcommands::FourByteEvent { topic } => {
             let topic = stdin::unwrap_line(topic)?;
-            let sigs = decode_event_topic(&topic).await?;
+            let sigs =
+                SignaturesDb::load().decode_selector(&topic, SelectorType::Event, false).await?;
             if sigs.is_empty() {
                 eyre::bail!("No matching event signatures found for topic `{topic}`");
             }
@@ -386,7 +428,14 @@ async fn main() -> Result<()> {
         }
         Subcommands::UploadSignature { signatures } => {
             let signatures = stdin::unwrap_vec(signatures)?;
-            let ParsedSignatures { signatures, abis } = parse_signatures(signatures);
+            let parsed = parse_signatures(signatures);
+
+            // uploaded signatures are also known locally, for offline decoding
+            let mut db = SignaturesDb::load();
+            db.import(&parsed)?;
+            db.save()?;
+
+            let ParsedSignatures { signatures, abis } = parsed;
             if !abis.is_empty() {
                 import_selectors(SelectorImportData::Abi(abis)).await?.describe();
             }
@@ -400,6 +449,8 @@ async fn main() -> Result<()> {
             let name = stdin::unwrap_line(name)?;
             println!("{}", SimpleCast::namehash(&name)?);
         }
+        Subcommands::Ens { command } => command.run().await?,
+        Subcommands::Safe { command } => command.run().await?,
         Subcommands::LookupAddress { who, rpc, verify } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
@@ -432,6 +483,10 @@ async fn main() -> Result<()> {
         }
 
         // Misc
+        Subcommands::HashMessage { message } => {
+            let message = stdin::unwrap_line(message)?;
+            println!("{}", SimpleCast::hash_message(&message)?);
+        }
         Subcommands::Keccak { data } => {
             let bytes = match data {
                 Some(data) => data.into_bytes(),
@@ -460,7 +515,7 @@ async fn main() -> Result<()> {
         Subcommands::RightShift { value, bits, base_in, base_out } => {
             println!("{}", SimpleCast::right_shift(&value, &bits, base_in.as_deref(), &base_out)?);
         }
-        Subcommands::EtherscanSource { address, directory, etherscan } => {
+        Subcommands::EtherscanSource { address, directory, project, etherscan } => {
             let config = Config::from(&etherscan);
             let chain = config.chain.unwrap_or_default();
             let api_key = config.get_etherscan_api_key(Some(chain)).unwrap_or_default();
@@ -469,6 +524,17 @@ async fn main() -> Result<()> {
                     SimpleCast::expand_etherscan_source_to_directory(chain, address, api_key, dir)
                         .await?
                 }
+                None if project => {
+                    let dir = SimpleCast::expand_etherscan_source_to_project(
+                        chain,
+                        address,
+                        api_key,
+                        &config.__root.0,
+                        &config.__root.0.join(&config.src),
+                    )
+                    .await?;
+                    println!("Sources written to {}", dir.display());
+                }
                 None => {
                     println!("{}", SimpleCast::etherscan_source(chain, address, api_key).await?);
                 }
@@ -477,9 +543,19 @@ async fn main() -> Result<()> {
         Subcommands::Create2(cmd) => {
             cmd.run()?;
         }
+        Subcommands::SigCollision(cmd) => cmd.run()?,
         Subcommands::Wallet { command } => command.run().await?,
+        Subcommands::Signatures { command } => command.run()?,
+        Subcommands::Erc20 { command } => command.run().await?,
+        Subcommands::Erc721 { command } => command.run().await?,
+        Subcommands::Erc1155 { command } => command.run().await?,
         Subcommands::Completions { shell } => {
-            generate(shell, &mut Opts::command(), "cast", &mut std::io::stdout())
+            completions::generate_completions(shell, &mut Opts::command(), &mut std::io::stdout())?
+        }
+        Subcommands::Complete { kind } => {
+            for value in kind.values() {
+                println!("{value}");
+            }
         }
         Subcommands::GenerateFigSpec => clap_complete::generate(
             clap_complete_fig::Fig,
@@ -488,6 +564,8 @@ async fn main() -> Result<()> {
             &mut std::io::stdout(),
         ),
         Subcommands::Logs(cmd) => cmd.run().await?,
+        Subcommands::TraceFilter(cmd) => cmd.run().await?,
+        Subcommands::GasUsed(cmd) => cmd.run().await?,
         Subcommands::DecodeTransaction { tx } => {
             let tx = stdin::unwrap_line(tx)?;
             let (tx, sig) = SimpleCast::decode_raw_transaction(&tx)?;
@@ -501,6 +579,12 @@ async fn main() -> Result<()> {
 
             println!("{}", serde_json::to_string_pretty(&tx)?);
         }
+        Subcommands::DecodeTransactionInput { calldata, guess, offline } => {
+            let calldata = stdin::unwrap_line(calldata)?;
+            let calldata = hex::decode(calldata)?;
+            let call = CalldataDecoder::new(guess, offline)?.decode(&calldata).await?;
+            print!("{call}");
+        }
     };
     Ok(())
 }
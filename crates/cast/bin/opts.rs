@@ -1,13 +1,40 @@
 use crate::cmd::{
-    access_list::AccessListArgs, bind::BindArgs, call::CallArgs, create2::Create2Args,
-    estimate::EstimateArgs, find_block::FindBlockArgs, interface::InterfaceArgs, logs::LogsArgs,
-    rpc::RpcArgs, run::RunArgs, send::SendTxArgs, storage::StorageArgs, wallet::WalletSubcommands,
+    abi_diff::AbiDiffArgs,
+    access_list::AccessListArgs,
+    balance::BalanceArgs,
+    bind::BindArgs,
+    bytecode_diff::BytecodeDiffArgs,
+    call::CallArgs,
+    completions::CompletionValues,
+    constructor_encode::ConstructorEncodeArgs,
+    create2::Create2Args,
+    ens::EnsSubcommands,
+    estimate::EstimateArgs,
+    find_block::FindBlockArgs,
+    gas_used::GasUsedArgs,
+    interface::InterfaceArgs,
+    logs::LogsArgs,
+    nonce::NonceArgs,
+    prettify_abi::PrettifyAbiArgs,
+    rpc::RpcArgs,
+    run::RunArgs,
+    safe::SafeSubcommands,
+    send::SendTxArgs,
+    sig_collision::SigCollisionArgs,
+    signatures::SignaturesSubcommands,
+    status::StatusArgs,
+    storage::StorageArgs,
+    token::{Erc1155Subcommands, Erc20Subcommands, Erc721Subcommands},
+    trace_filter::TraceFilterArgs,
+    wallet::WalletSubcommands,
 };
 use alloy_primitives::{Address, B256, U256};
+use cast::checksum::{parse_address, parse_name_or_address};
 use clap::{Parser, Subcommand, ValueHint};
 use ethers_core::types::{BlockId, NameOrAddress};
 use eyre::Result;
-use foundry_cli::opts::{EtherscanOpts, RpcOpts};
+use foundry_cli::opts::{ChainValueParser, EtherscanOpts, RpcOpts};
+use foundry_config::Chain;
 use std::{path::PathBuf, str::FromStr};
 
 const VERSION_MESSAGE: &str = concat!(
@@ -24,6 +51,14 @@ const VERSION_MESSAGE: &str = concat!(
 pub struct Opts {
     #[clap(subcommand)]
     pub sub: Subcommands,
+
+    /// Don't validate the checksums of mixed-case address arguments.
+    ///
+    /// Lowercase and uppercase addresses carry no checksum and are never rejected.
+    // read before the arguments are parsed, see `main`
+    #[allow(dead_code)]
+    #[clap(long, global = true, env = "CAST_NO_CHECKSUM_VALIDATE")]
+    pub no_checksum_validate: bool,
 }
 
 /// Perform Ethereum RPC calls from the comfort of your command line.
@@ -114,6 +149,12 @@ pub enum Subcommands {
     ToCheckSumAddress {
         /// The address to convert.
         address: Option<Address>,
+
+        /// Include the chain ID in the checksum, as specified in EIP-1191.
+        ///
+        /// Only the chains in EIP-1191, such as RSK (30), use these checksums.
+        #[clap(long, value_parser = ChainValueParser::default())]
+        chain: Option<Chain>,
     },
 
     /// Convert hex data to an ASCII string.
@@ -248,6 +289,47 @@ pub enum Subcommands {
         unit: String,
     },
 
+    /// Convert a decimal amount into the smallest unit, without losing precision.
+    ///
+    /// Examples:
+    /// - 1.5 ether
+    /// - 1.5e18 wei
+    /// - -- -2.5 6
+    #[clap(visible_aliases = &["--parse-units", "pun"])]
+    ParseUnits {
+        /// The amount to convert, optionally negative or in scientific notation.
+        #[clap(allow_hyphen_values = true)]
+        value: Option<String>,
+
+        /// The unit to convert from, either a unit name or a number of decimals.
+        #[clap(default_value = "ether")]
+        unit: String,
+    },
+
+    /// Convert an amount in the smallest unit into a decimal amount.
+    ///
+    /// Examples:
+    /// - 1500000000000000000 ether
+    /// - 1234567891 6 --precision 2 --separator ,
+    #[clap(visible_aliases = &["--format-units", "fun"])]
+    FormatUnits {
+        /// The amount to convert.
+        #[clap(allow_hyphen_values = true)]
+        value: Option<String>,
+
+        /// The unit to convert to, either a unit name or a number of decimals.
+        #[clap(default_value = "ether")]
+        unit: String,
+
+        /// Round to this many decimals instead of trimming trailing zeros.
+        #[clap(long)]
+        precision: Option<usize>,
+
+        /// Group the integer digits in thousands with this separator, e.g. `,` or `_`.
+        #[clap(long, value_name = "CHAR", value_parser = parse_thousands_separator)]
+        separator: Option<char>,
+    },
+
     /// RLP encodes hex data, or an array of hex data
     #[clap(visible_aliases = &["--to-rlp"])]
     ToRlp {
@@ -294,6 +376,21 @@ pub enum Subcommands {
     /// Get logs by signature or topic.
     #[clap(visible_alias = "l")]
     Logs(LogsArgs),
+
+    /// Find the internal calls to an address or function in a block range.
+    ///
+    /// Uses `trace_filter` if the node supports it, and traces every block of the range
+    /// otherwise.
+    #[clap(visible_alias = "trf")]
+    TraceFilter(TraceFilterArgs),
+
+    /// Get the gas used by a transaction.
+    ///
+    /// With `--by-opcode`, the gas is broken down by opcode and by call frame, from a
+    /// `debug_traceTransaction` trace of the transaction.
+    #[clap(visible_alias = "gu")]
+    GasUsed(GasUsedArgs),
+
     /// Get information about a block.
     #[clap(visible_alias = "bl")]
     Block {
@@ -384,6 +481,18 @@ pub enum Subcommands {
     #[clap(visible_aliases = &["na", "nh"])]
     Namehash { name: Option<String> },
 
+    /// ENS utilities: hashing, resolvers, records and reverse records.
+    Ens {
+        #[clap(subcommand)]
+        command: EnsSubcommands,
+    },
+
+    /// Safe multisig utilities: decoding transactions for review.
+    Safe {
+        #[clap(subcommand)]
+        command: SafeSubcommands,
+    },
+
     /// Get information about a transaction.
     #[clap(visible_alias = "t")]
     Tx {
@@ -457,13 +566,35 @@ pub enum Subcommands {
     ///
     /// Similar to `abi-decode --input`, but function selector MUST be prefixed in `calldata`
     /// string
+    ///
+    /// With --file, every calldata in the file is decoded and printed as a JSON object per line.
     #[clap(visible_aliases = &["--calldata-decode","cdd"])]
     CalldataDecode {
         /// The function signature in the format `<name>(<in-types>)(<out-types>)`.
-        sig: String,
+        ///
+        /// Optional with --file, in which case the functions are looked up in --abi or in the
+        /// signature database.
+        #[clap(required_unless_present = "file")]
+        sig: Option<String>,
 
         /// The ABI-encoded calldata.
-        calldata: String,
+        #[clap(required_unless_present = "file")]
+        calldata: Option<String>,
+
+        /// Decode every calldata in the file, which contains either one calldata per line or a
+        /// JSON array.
+        ///
+        /// Use `-` to read from stdin.
+        #[clap(long, value_name = "PATH", conflicts_with = "calldata")]
+        file: Option<PathBuf>,
+
+        /// The ABI or artifact to decode the calldata of --file against.
+        #[clap(long, value_name = "PATH", requires = "file", conflicts_with = "sig")]
+        abi: Option<PathBuf>,
+
+        /// Only look up the functions of --file in the local signature database.
+        #[clap(long, requires = "file")]
+        offline: bool,
     },
 
     /// Decode ABI-encoded input or output data.
@@ -495,6 +626,11 @@ pub enum Subcommands {
         args: Vec<String>,
     },
 
+    /// ABI encode the constructor arguments of a contract, checked against its artifact, and
+    /// print them with the deploy bytecode.
+    #[clap(visible_alias = "cte")]
+    ConstructorEncode(ConstructorEncodeArgs),
+
     /// Compute the storage slot for an entry in a mapping.
     #[clap(visible_alias = "in")]
     Index {
@@ -518,7 +654,7 @@ pub enum Subcommands {
         block: Option<BlockId>,
 
         /// The address to get the nonce for.
-        #[clap(value_parser = NameOrAddress::from_str)]
+        #[clap(value_parser = parse_name_or_address)]
         who: NameOrAddress,
 
         #[clap(flatten)]
@@ -535,28 +671,30 @@ pub enum Subcommands {
         block: Option<BlockId>,
 
         /// The address to get the nonce for.
-        #[clap(value_parser = NameOrAddress::from_str)]
+        #[clap(value_parser = parse_name_or_address)]
         who: NameOrAddress,
 
         #[clap(flatten)]
         rpc: RpcOpts,
     },
 
-    /// Get the function signatures for the given selector from https://openchain.xyz.
+    /// Get the function signatures for the given selector from the local signature database or
+    /// https://openchain.xyz.
     #[clap(name = "4byte", visible_aliases = &["4", "4b"])]
     FourByte {
         /// The function selector.
         selector: Option<String>,
     },
 
-    /// Decode ABI-encoded calldata using https://openchain.xyz.
+    /// Decode ABI-encoded calldata using the local signature database or https://openchain.xyz.
     #[clap(name = "4byte-decode", visible_aliases = &["4d", "4bd"])]
     FourByteDecode {
         /// The ABI-encoded calldata.
         calldata: Option<String>,
     },
 
-    /// Get the event signature for a given topic 0 from https://openchain.xyz.
+    /// Get the event signature for a given topic 0 from the local signature database or
+    /// https://openchain.xyz.
     #[clap(name = "4byte-event", visible_aliases = &["4e", "4be", "topic0-event", "t0e"])]
     FourByteEvent {
         /// Topic 0
@@ -566,6 +704,8 @@ pub enum Subcommands {
 
     /// Upload the given signatures to https://openchain.xyz.
     ///
+    /// The signatures are also added to the local signature database.
+    ///
     /// Example inputs:
     /// - "transfer(address,uint256)"
     /// - "function transfer(address,uint256)"
@@ -580,6 +720,15 @@ pub enum Subcommands {
         signatures: Vec<String>,
     },
 
+    /// Manage the local signature database in `~/.foundry/signatures`.
+    ///
+    /// Signatures in the local database are used to decode selectors and calldata offline.
+    #[clap(visible_alias = "sigs")]
+    Signatures {
+        #[clap(subcommand)]
+        command: SignaturesSubcommands,
+    },
+
     /// Pretty print calldata.
     ///
     /// Tries to decode the calldata using https://openchain.xyz unless --offline is passed.
@@ -606,25 +755,11 @@ pub enum Subcommands {
     },
 
     /// Get the balance of an account in wei.
+    ///
+    /// With `--erc20` or `--token-list`, prints a table of the native and ERC-20 token balances
+    /// instead.
     #[clap(visible_alias = "b")]
-    Balance {
-        /// The block height to query at.
-        ///
-        /// Can also be the tags earliest, finalized, safe, latest, or pending.
-        #[clap(long, short = 'B')]
-        block: Option<BlockId>,
-
-        /// The account to query.
-        #[clap(value_parser = NameOrAddress::from_str)]
-        who: NameOrAddress,
-
-        /// Format the balance in ether.
-        #[clap(long, short)]
-        ether: bool,
-
-        #[clap(flatten)]
-        rpc: RpcOpts,
-    },
+    Balance(BalanceArgs),
 
     /// Get the basefee of a block.
     #[clap(visible_aliases = &["ba", "fee", "basefee"])]
@@ -648,7 +783,7 @@ pub enum Subcommands {
         block: Option<BlockId>,
 
         /// The contract address.
-        #[clap(value_parser = NameOrAddress::from_str)]
+        #[clap(value_parser = parse_name_or_address)]
         who: NameOrAddress,
 
         /// Disassemble bytecodes into individual opcodes.
@@ -659,6 +794,13 @@ pub enum Subcommands {
         rpc: RpcOpts,
     },
 
+    /// Compare the runtime bytecodes of two contracts, possibly on different chains.
+    ///
+    /// Metadata hashes and immutables are ignored, so identical deployments match. Fails if the
+    /// bytecodes differ.
+    #[clap(visible_alias = "bd")]
+    BytecodeDiff(BytecodeDiffArgs),
+
     /// Get the runtime bytecode size of a contract.
     #[clap(visible_alias = "cs")]
     Codesize {
@@ -669,7 +811,42 @@ pub enum Subcommands {
         block: Option<BlockId>,
 
         /// The contract address.
-        #[clap(value_parser = NameOrAddress::from_str)]
+        #[clap(value_parser = parse_name_or_address)]
+        who: NameOrAddress,
+
+        #[clap(flatten)]
+        rpc: RpcOpts,
+    },
+
+    /// Get the code hash of an account, verified with a Merkle proof.
+    ///
+    /// Like `EXTCODEHASH`, this is zero for accounts that do not exist.
+    Codehash {
+        /// The block height to query at.
+        ///
+        /// Can also be the tags earliest, finalized, safe, latest, or pending.
+        #[clap(long, short = 'B')]
+        block: Option<BlockId>,
+
+        /// The account address.
+        #[clap(value_parser = parse_name_or_address)]
+        who: NameOrAddress,
+
+        #[clap(flatten)]
+        rpc: RpcOpts,
+    },
+
+    /// Get the storage root of an account, verified with a Merkle proof.
+    #[clap(visible_alias = "sr")]
+    StorageRoot {
+        /// The block height to query at.
+        ///
+        /// Can also be the tags earliest, finalized, safe, latest, or pending.
+        #[clap(long, short = 'B')]
+        block: Option<BlockId>,
+
+        /// The account address.
+        #[clap(value_parser = parse_name_or_address)]
         who: NameOrAddress,
 
         #[clap(flatten)]
@@ -697,6 +874,16 @@ pub enum Subcommands {
         data: Option<String>,
     },
 
+    /// Hash a message according to EIP-191, as signed by `cast wallet sign`.
+    #[clap(visible_aliases = &["--hash-message", "hm"])]
+    HashMessage {
+        /// The message to hash.
+        ///
+        /// Messages starting with 0x are expected to be hex encoded, which get decoded before
+        /// being hashed.
+        message: Option<String>,
+    },
+
     /// Perform an ENS lookup.
     #[clap(visible_alias = "rn")]
     ResolveName {
@@ -715,6 +902,7 @@ pub enum Subcommands {
     #[clap(visible_alias = "la")]
     LookupAddress {
         /// The account to perform the lookup for.
+        #[clap(value_parser = parse_address)]
         who: Option<Address>,
 
         /// Perform a normal lookup to verify that the address is correct.
@@ -733,7 +921,7 @@ pub enum Subcommands {
     #[clap(visible_alias = "pr")]
     Proof {
         /// The contract address.
-        #[clap(value_parser = NameOrAddress::from_str)]
+        #[clap(value_parser = parse_name_or_address)]
         address: NameOrAddress,
 
         /// The storage slot numbers (hex or decimal).
@@ -751,21 +939,11 @@ pub enum Subcommands {
     },
 
     /// Get the nonce for an account.
+    ///
+    /// With `--diagnose`, also lists the transactions of the account in the mempool and suggests
+    /// how to replace the stuck ones.
     #[clap(visible_alias = "n")]
-    Nonce {
-        /// The block height to query at.
-        ///
-        /// Can also be the tags earliest, finalized, safe, latest, or pending.
-        #[clap(long, short = 'B')]
-        block: Option<BlockId>,
-
-        /// The address to get the nonce for.
-        #[clap(value_parser = NameOrAddress::from_str)]
-        who: NameOrAddress,
-
-        #[clap(flatten)]
-        rpc: RpcOpts,
-    },
+    Nonce(NonceArgs),
 
     /// Get the source code of a contract from Etherscan.
     #[clap(visible_aliases = &["et", "src"])]
@@ -777,10 +955,40 @@ pub enum Subcommands {
         #[clap(short, value_hint = ValueHint::DirPath)]
         directory: Option<PathBuf>,
 
+        /// Write the sources into the current project, under `<src>/vendor/<ContractName>/`.
+        ///
+        /// The remappings required to compile the sources are appended to the project's
+        /// `remappings.txt`.
+        #[clap(long, conflicts_with = "directory")]
+        project: bool,
+
         #[clap(flatten)]
         etherscan: EtherscanOpts,
     },
 
+    /// Read and transfer ERC-20 tokens.
+    ///
+    /// Amounts are formatted and parsed with the token's decimals.
+    #[clap(name = "erc20", visible_alias = "erc-20")]
+    Erc20 {
+        #[clap(subcommand)]
+        command: Erc20Subcommands,
+    },
+
+    /// Read and transfer ERC-721 tokens.
+    #[clap(name = "erc721", visible_alias = "erc-721")]
+    Erc721 {
+        #[clap(subcommand)]
+        command: Erc721Subcommands,
+    },
+
+    /// Read and transfer ERC-1155 tokens.
+    #[clap(name = "erc1155", visible_alias = "erc-1155")]
+    Erc1155 {
+        #[clap(subcommand)]
+        command: Erc1155Subcommands,
+    },
+
     /// Wallet management utilities.
     #[clap(visible_alias = "w")]
     Wallet {
@@ -794,6 +1002,21 @@ pub enum Subcommands {
     #[clap(visible_alias = "i")]
     Interface(InterfaceArgs),
 
+    /// Convert a JSON ABI to a human-readable ABI, or a human-readable ABI to a JSON ABI.
+    ///
+    /// Tuples are named after their structs, using the `internalType` of the JSON ABI, and
+    /// declared with `struct` definitions in the human-readable ABI.
+    #[clap(visible_alias = "pa")]
+    PrettifyAbi(PrettifyAbiArgs),
+
+    /// Compare two ABIs and report the added, removed and changed functions and events.
+    ///
+    /// Fails if there are breaking changes: removed items, changed signatures, return types or
+    /// indexed event parameters, state mutability changes that make calls revert, and selector
+    /// collisions.
+    #[clap(visible_alias = "abd")]
+    AbiDiff(AbiDiffArgs),
+
     /// Generate a rust binding from a given ABI.
     #[clap(visible_alias = "bi")]
     Bind(BindArgs),
@@ -808,6 +1031,12 @@ pub enum Subcommands {
         optimize: Option<usize>,
     },
 
+    /// Find function signatures whose selector collides with a given one.
+    ///
+    /// Useful to audit proxies for function selector clashes.
+    #[clap(visible_alias = "sc")]
+    SigCollision(SigCollisionArgs),
+
     /// Generate a deterministic contract address using CREATE2.
     #[clap(visible_alias = "c2")]
     Create2(Create2Args),
@@ -816,13 +1045,27 @@ pub enum Subcommands {
     #[clap(visible_alias = "f")]
     FindBlock(FindBlockArgs),
 
+    /// Get the chain id, latest block, fees, gas usage and client version of a chain at once.
+    #[clap(visible_alias = "stat")]
+    Status(StatusArgs),
+
     /// Generate shell completions script.
+    ///
+    /// The scripts of bash, zsh and fish complete the chain names of `--chain`, the keystore
+    /// accounts of `--account` and the RPC aliases of `--rpc-url` by calling cast at runtime.
     #[clap(visible_alias = "com")]
     Completions {
         #[clap(value_enum)]
         shell: clap_complete::Shell,
     },
 
+    /// Prints the values to complete at runtime, used by the shell completion scripts.
+    #[clap(name = "__complete", hide = true)]
+    Complete {
+        #[clap(value_enum)]
+        kind: CompletionValues,
+    },
+
     /// Generate Fig autocompletion spec.
     #[clap(visible_alias = "fig")]
     GenerateFigSpec,
@@ -858,6 +1101,26 @@ pub enum Subcommands {
     /// Decodes a raw signed EIP 2718 typed transaction
     #[clap(visible_alias = "dt")]
     DecodeTransaction { tx: Option<String> },
+
+    /// Decode transaction input data as a tree of calls.
+    ///
+    /// Calls embedded in Multicall3, Safe and ERC-4337 payloads are decoded recursively, using
+    /// signatures resolved from https://openchain.xyz.
+    #[clap(visible_alias = "dti")]
+    DecodeTransactionInput {
+        /// The transaction input data.
+        calldata: Option<String>,
+
+        /// Also decode every `bytes` argument that looks like an embedded call, not only the ones
+        /// of known multicall, Safe and ERC-4337 functions.
+        #[clap(long)]
+        guess: bool,
+
+        /// Skip the https://openchain.xyz lookup, only using the local signature database and the
+        /// known wrapper functions.
+        #[clap(long, short)]
+        offline: bool,
+    },
 }
 
 /// CLI arguments for `cast --to-base`.
@@ -877,6 +1140,14 @@ pub fn parse_slot(s: &str) -> Result<B256> {
     Ok(B256::from(slot))
 }
 
+fn parse_thousands_separator(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c != '.' && !c.is_ascii_digit() => Ok(c),
+        _ => Err(format!("expected a single character other than a digit or `.`, got `{s}`")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -955,6 +1226,18 @@ mod tests {
         };
     }
 
+    #[test]
+    fn can_parse_format_units_separator() {
+        let args: Opts = Opts::parse_from(["foundry-cli", "format-units", "1", "--separator", "_"]);
+        match args.sub {
+            Subcommands::FormatUnits { separator, .. } => assert_eq!(separator, Some('_')),
+            _ => unreachable!(),
+        };
+        assert!(
+            Opts::try_parse_from(["foundry-cli", "format-units", "1", "--separator", "."]).is_err()
+        );
+    }
+
     #[test]
     fn parse_block_ids() {
         struct TestCase {
@@ -0,0 +1,307 @@
+//! Batches of read calls, used by `cast call --batch` and repeated `cast call --call` flags.
+//!
+//! A batch is either a JSON array of [`BatchCall`] objects, or a CSV file with one call per row in
+//! the format `to,sig,args...`.
+
+use alloy_dyn_abi::{DynSolValue, FunctionExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{address, Address};
+use eyre::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::Path;
+
+/// The address of the Multicall3 contract, which is deployed at the same address on most chains.
+pub const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// The signature of the Multicall3 function used to aggregate the calls of a batch.
+const AGGREGATE3_SIGNATURE: &str = "aggregate3((address,bool,bytes)[])((bool,bytes)[])";
+
+/// A single read call of a batch.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchCall {
+    /// The destination of the call, an address or an ENS name.
+    pub to: String,
+    /// The signature of the function to call.
+    #[serde(default)]
+    pub sig: Option<String>,
+    /// The arguments of the function to call.
+    #[serde(default, deserialize_with = "deserialize_args")]
+    pub args: Vec<String>,
+    /// Raw calldata, instead of `sig` and `args`.
+    #[serde(default)]
+    pub data: Option<String>,
+}
+
+/// The result of a single call of a batch.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BatchCallResult {
+    /// The destination of the call.
+    pub to: String,
+    /// The signature of the called function, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sig: Option<String>,
+    /// Whether the call succeeded.
+    pub success: bool,
+    /// The decoded return values, or the raw return data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    /// The error of a failed call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchCallResult {
+    /// Creates the result of a call from its outcome.
+    pub fn new(call: &BatchCall, outcome: Result<String>) -> Self {
+        let (success, result, error) = match outcome {
+            Ok(result) => (true, Some(result.trim_end().to_string()), None),
+            Err(err) => (false, None, Some(format!("{err:#}"))),
+        };
+        Self { to: call.to.clone(), sig: call.sig.clone(), success, result, error }
+    }
+}
+
+/// Reads the calls of a batch file.
+///
+/// Files with a `.csv` extension are parsed as CSV, all others as JSON.
+pub fn read_batch_file(path: &Path) -> Result<Vec<BatchCall>> {
+    let content = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("failed to read batch file {}", path.display()))?;
+    if path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("csv")) {
+        parse_csv_batch(&content)
+    } else {
+        serde_json::from_str(&content)
+            .wrap_err_with(|| format!("failed to parse batch file {}", path.display()))
+    }
+}
+
+/// Parses a CSV batch with one call per row: `to,sig,args...`.
+///
+/// Empty lines, lines starting with `#` and a leading `to,sig,...` header are ignored. Fields can
+/// be quoted with `"`, and commas inside of `[]` or `()` don't separate fields, so array and tuple
+/// arguments don't need to be quoted.
+pub fn parse_csv_batch(content: &str) -> Result<Vec<BatchCall>> {
+    let mut calls = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue
+        }
+        let mut fields = split_csv_line(line).into_iter();
+        let to = fields.next().unwrap_or_default();
+        if calls.is_empty() && to.eq_ignore_ascii_case("to") {
+            continue
+        }
+        if to.is_empty() {
+            eyre::bail!("missing call destination on line {}", i + 1)
+        }
+        let sig = fields.next().filter(|sig| !sig.is_empty());
+        calls.push(BatchCall { to, sig, args: fields.collect(), data: None });
+    }
+    Ok(calls)
+}
+
+/// Parses a single call in the format of a CSV batch row, `to,sig,args...`, as passed to
+/// `cast call --call`.
+pub fn parse_call(spec: &str) -> Result<BatchCall> {
+    let mut fields = split_csv_line(spec.trim()).into_iter();
+    let to = fields.next().unwrap_or_default();
+    if to.is_empty() {
+        eyre::bail!("missing call destination in `{spec}`")
+    }
+    let sig = fields.next().filter(|sig| !sig.is_empty());
+    Ok(BatchCall { to, sig, args: fields.collect(), data: None })
+}
+
+/// Formats the results as a JSON array.
+pub fn format_json(results: &[BatchCallResult]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(results)?)
+}
+
+/// Formats the results as CSV, with a header row.
+pub fn format_csv(results: &[BatchCallResult]) -> String {
+    let mut out = String::from("to,sig,success,result,error\n");
+    for result in results {
+        let fields = [
+            result.to.as_str(),
+            result.sig.as_deref().unwrap_or_default(),
+            if result.success { "true" } else { "false" },
+            result.result.as_deref().unwrap_or_default(),
+            result.error.as_deref().unwrap_or_default(),
+        ];
+        out.push_str(&fields.map(escape_csv_field).join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Encodes a Multicall3 `aggregate3` call of the given `(target, calldata)` pairs, allowing every
+/// call to fail.
+pub fn encode_aggregate3(calls: &[(Address, Vec<u8>)]) -> Result<Vec<u8>> {
+    let calls = calls
+        .iter()
+        .map(|(target, data)| {
+            DynSolValue::Tuple(vec![
+                DynSolValue::Address(*target),
+                DynSolValue::Bool(true),
+                DynSolValue::Bytes(data.clone()),
+            ])
+        })
+        .collect();
+    Ok(aggregate3()?.abi_encode_input(&[DynSolValue::Array(calls)])?)
+}
+
+/// Decodes the `(success, returnData)` pairs returned by a Multicall3 `aggregate3` call.
+pub fn decode_aggregate3(data: &[u8]) -> Result<Vec<(bool, Vec<u8>)>> {
+    let decoded = aggregate3()?.abi_decode_output(data, false)?;
+    let Some(DynSolValue::Array(results)) = decoded.into_iter().next() else {
+        eyre::bail!("unexpected aggregate3 return data")
+    };
+    results
+        .into_iter()
+        .map(|result| match result.as_tuple() {
+            Some([DynSolValue::Bool(success), DynSolValue::Bytes(data)]) => {
+                Ok((*success, data.clone()))
+            }
+            _ => eyre::bail!("unexpected aggregate3 return data"),
+        })
+        .collect()
+}
+
+fn aggregate3() -> Result<Function> {
+    Ok(Function::parse(AGGREGATE3_SIGNATURE)?)
+}
+
+/// Accepts JSON strings, numbers and booleans as arguments.
+fn deserialize_args<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let args = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(args
+        .into_iter()
+        .map(|arg| match arg {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        })
+        .collect())
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut depth = 0usize;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            '[' | '(' if !quoted => {
+                depth += 1;
+                field.push(c);
+            }
+            ']' | ')' if !quoted => {
+                depth = depth.saturating_sub(1);
+                field.push(c);
+            }
+            ',' if !quoted && depth == 0 => fields.push(std::mem::take(&mut field).trim().into()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_json_batch() {
+        let calls: Vec<BatchCall> = serde_json::from_str(
+            r#"[
+                {"to": "vitalik.eth", "sig": "balanceOf(address)(uint256)", "args": ["0x0000000000000000000000000000000000000001"]},
+                {"to": "0x0000000000000000000000000000000000000002", "sig": "get(uint256,bool)", "args": [1, true]},
+                {"to": "0x0000000000000000000000000000000000000003", "data": "0x06fdde03"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[1].args, vec!["1", "true"]);
+        assert_eq!(calls[2].data.as_deref(), Some("0x06fdde03"));
+    }
+
+    #[test]
+    fn can_parse_csv_batch() {
+        let calls = parse_csv_batch(
+            "to,sig,args\n\
+             # comment\n\
+             0x0000000000000000000000000000000000000001,name()(string)\n\
+             0x0000000000000000000000000000000000000002,\"f(uint256[],string)\",[1,2],\"a, \"\"b\"\"\"\n",
+        )
+        .unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].sig.as_deref(), Some("name()(string)"));
+        assert!(calls[0].args.is_empty());
+        assert_eq!(calls[1].sig.as_deref(), Some("f(uint256[],string)"));
+        assert_eq!(calls[1].args, vec!["[1,2]", "a, \"b\""]);
+    }
+
+    #[test]
+    fn can_parse_call() {
+        let call = parse_call(
+            "vitalik.eth,balanceOf(address)(uint256),0x0000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        assert_eq!(call.to, "vitalik.eth");
+        assert_eq!(call.sig.as_deref(), Some("balanceOf(address)(uint256)"));
+        assert_eq!(call.args, vec!["0x0000000000000000000000000000000000000001"]);
+
+        let call =
+            parse_call("0x0000000000000000000000000000000000000002,f(uint256[2]),[1,2]").unwrap();
+        assert_eq!(call.args, vec!["[1,2]"]);
+
+        assert!(parse_call(",name()").is_err());
+    }
+
+    #[test]
+    fn can_format_csv_results() {
+        let call =
+            BatchCall { to: "a".to_string(), sig: Some("f()".to_string()), ..Default::default() };
+        let results = vec![
+            BatchCallResult::new(&call, Ok("1\n2\n".to_string())),
+            BatchCallResult::new(&call, Err(eyre::eyre!("execution reverted, data: \"0x\""))),
+        ];
+        assert_eq!(
+            format_csv(&results),
+            "to,sig,success,result,error\n\
+             a,f(),true,\"1\n2\",\n\
+             a,f(),false,,\"execution reverted, data: \"\"0x\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn can_roundtrip_aggregate3() {
+        let calls =
+            vec![(Address::repeat_byte(1), vec![1, 2, 3]), (Address::repeat_byte(2), vec![])];
+        let encoded = encode_aggregate3(&calls).unwrap();
+        assert_eq!(&encoded[..4], &[0x82, 0xad, 0x56, 0xcb]);
+
+        let results = DynSolValue::Array(vec![
+            DynSolValue::Tuple(vec![DynSolValue::Bool(true), DynSolValue::Bytes(vec![4])]),
+            DynSolValue::Tuple(vec![DynSolValue::Bool(false), DynSolValue::Bytes(vec![])]),
+        ]);
+        let data = DynSolValue::Tuple(vec![results]).abi_encode_params();
+        assert_eq!(decode_aggregate3(&data).unwrap(), vec![(true, vec![4]), (false, vec![])]);
+    }
+}
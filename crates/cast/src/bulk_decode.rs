@@ -0,0 +1,196 @@
+//! Decoding of many calldata blobs at once, e.g. all the calls of an incident.
+
+use alloy_dyn_abi::FunctionExt;
+use alloy_json_abi::{ContractObject, Function, JsonAbi};
+use alloy_primitives::{hex, Selector};
+use eyre::{Result, WrapErr};
+use foundry_common::{
+    fmt::format_token_raw,
+    selectors::{SelectorType, SignaturesDb},
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Parses a list of calldata blobs, which is either a JSON array of hex strings, or one blob per
+/// line.
+///
+/// Empty lines and lines starting with `#` are ignored.
+pub fn parse_calldata_list(content: &str) -> Result<Vec<String>> {
+    if content.trim_start().starts_with('[') {
+        return serde_json::from_str(content).wrap_err("invalid JSON array of calldata")
+    }
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Parses the content of an ABI file, which is either an ABI or an artifact containing one.
+pub fn parse_abi_file(content: &str) -> Result<JsonAbi> {
+    if let Ok(abi) = serde_json::from_str::<JsonAbi>(content) {
+        return Ok(abi)
+    }
+    let object: ContractObject =
+        serde_json::from_str(content).wrap_err("the file is neither an ABI nor an artifact")?;
+    object.abi.ok_or_else(|| eyre::eyre!("could not find an ABI in the artifact"))
+}
+
+/// The result of decoding one calldata blob, as printed by `cast calldata-decode --file`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DecodedCalldata {
+    /// The position of the calldata in the input.
+    pub index: usize,
+    /// The calldata as given.
+    pub calldata: String,
+    /// The function selector, if the calldata has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Selector>,
+    /// The signature of the function that decoded the calldata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// The decoded arguments, formatted so they can be parsed again.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Why the calldata could not be decoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Decodes calldata blobs against a fixed set of functions, or against the signatures of the
+/// local signature database and https://openchain.xyz.
+///
+/// The signatures of a selector are only looked up once.
+#[derive(Debug)]
+pub struct BulkCalldataDecoder {
+    /// The signature database, `None` if only the given functions are used.
+    db: Option<SignaturesDb>,
+    offline: bool,
+    functions: HashMap<Selector, Vec<Function>>,
+}
+
+impl BulkCalldataDecoder {
+    /// Creates a decoder that only decodes calls to the given functions.
+    pub fn new(functions: impl IntoIterator<Item = Function>) -> Self {
+        let mut decoder = Self { db: None, offline: true, functions: HashMap::new() };
+        for func in functions {
+            decoder.functions.entry(func.selector()).or_default().push(func);
+        }
+        decoder
+    }
+
+    /// Creates a decoder that looks up the functions in the signature database, and on
+    /// https://openchain.xyz unless `offline` is set.
+    pub fn with_signatures_db(offline: bool) -> Self {
+        Self { db: Some(SignaturesDb::load()), offline, functions: HashMap::new() }
+    }
+
+    /// Decodes the calldata at `index` of the input.
+    ///
+    /// Errors are reported in the result, so that one bad blob doesn't stop the decoding of the
+    /// others.
+    pub async fn decode(&mut self, index: usize, calldata: &str) -> DecodedCalldata {
+        let mut decoded =
+            DecodedCalldata { index, calldata: calldata.to_string(), ..Default::default() };
+        let data = match hex::decode(calldata) {
+            Ok(data) if data.len() >= 4 => data,
+            Ok(_) => {
+                decoded.error = Some("calldata must be at least 4 bytes long".to_string());
+                return decoded
+            }
+            Err(err) => {
+                decoded.error = Some(format!("invalid hex: {err}"));
+                return decoded
+            }
+        };
+        let selector = Selector::from_slice(&data[..4]);
+        decoded.selector = Some(selector);
+
+        let functions = self.functions(selector).await;
+        if functions.is_empty() {
+            decoded.error = Some(format!("no function found for selector {selector}"));
+            return decoded
+        }
+        match functions
+            .iter()
+            .find_map(|func| func.abi_decode_input(&data[4..], false).ok().map(|args| (func, args)))
+        {
+            Some((func, args)) => {
+                decoded.signature = Some(func.signature());
+                decoded.args = args.iter().map(format_token_raw).collect();
+            }
+            None => {
+                let signatures = functions.iter().map(Function::signature).collect::<Vec<_>>();
+                decoded.error = Some(format!(
+                    "the arguments could not be decoded as any of {}",
+                    signatures.join(", ")
+                ));
+            }
+        }
+        decoded
+    }
+
+    /// Returns the functions with the given selector, looking them up in the signature database
+    /// if it is used.
+    async fn functions(&mut self, selector: Selector) -> &[Function] {
+        if let Some(db) = self.db.as_mut().filter(|_| !self.functions.contains_key(&selector)) {
+            let functions = db
+                .decode_selector(&selector.to_string(), SelectorType::Function, self.offline)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|signature| Function::parse(signature).ok())
+                .collect();
+            self.functions.insert(selector, functions);
+        }
+        self.functions.get(&selector).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFER: &str = "0xa9059cbb000000000000000000000000111111111111111111111111111111111111111100000000000000000000000000000000000000000000000000000000000003e8";
+
+    #[test]
+    fn can_parse_calldata_list() {
+        let list = parse_calldata_list("0x01\n\n# comment\n 0x02 \n").unwrap();
+        assert_eq!(list, ["0x01", "0x02"]);
+        let list = parse_calldata_list(r#"["0x01", "0x02"]"#).unwrap();
+        assert_eq!(list, ["0x01", "0x02"]);
+        assert!(parse_calldata_list("[0x01").is_err());
+    }
+
+    #[test]
+    fn can_parse_abi_file() {
+        let abi = r#"[{"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"}]"#;
+        assert_eq!(parse_abi_file(abi).unwrap().functions().count(), 1);
+        let artifact = format!(r#"{{"abi":{abi},"bytecode":"0x"}}"#);
+        assert_eq!(parse_abi_file(&artifact).unwrap().functions().count(), 1);
+        assert!(parse_abi_file("{}").is_err());
+    }
+
+    #[tokio::test]
+    async fn decodes_calldata_against_functions() {
+        let func = Function::parse("transfer(address to, uint256 amount)").unwrap();
+        let mut decoder = BulkCalldataDecoder::new([func]);
+
+        let decoded = decoder.decode(0, TRANSFER).await;
+        assert_eq!(decoded.signature.as_deref(), Some("transfer(address,uint256)"));
+        assert_eq!(decoded.args, ["0x1111111111111111111111111111111111111111", "1000"]);
+        assert_eq!(decoded.error, None);
+
+        let decoded = decoder.decode(1, "0xdeadbeef").await;
+        assert_eq!(decoded.selector, Some(Selector::new([0xde, 0xad, 0xbe, 0xef])));
+        assert!(decoded.error.unwrap().contains("no function found"));
+
+        let decoded = decoder.decode(2, "0xzz").await;
+        assert!(decoded.error.unwrap().starts_with("invalid hex"));
+
+        let json = serde_json::to_string(&decoder.decode(3, &TRANSFER[..20]).await).unwrap();
+        assert!(json.contains("\"index\":3"), "{json}");
+        assert!(json.contains("could not be decoded"), "{json}");
+    }
+}
@@ -0,0 +1,107 @@
+//! Structured results of `eth_call`s
+
+use alloy_dyn_abi::FunctionExt;
+use alloy_json_abi::Function;
+use ethers_core::types::Bytes;
+use eyre::Result;
+use foundry_common::fmt::format_token_raw;
+use foundry_evm::decode::decode_revert;
+use serde::Serialize;
+
+/// The result of an `eth_call`, with the return data both raw and decoded.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallResult {
+    /// Whether the call succeeded.
+    pub success: bool,
+    /// The raw return data of a successful call.
+    pub return_data: Bytes,
+    /// The return data decoded with the output types of the function, if known.
+    pub decoded: Vec<DecodedValue>,
+    /// The gas estimated for the call with `eth_estimateGas`, if it succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_estimate: Option<u64>,
+    /// The raw revert data of a failed call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_data: Option<Bytes>,
+    /// The decoded revert reason or error of a failed call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A value of the decoded return data of a call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DecodedValue {
+    /// The name of the output parameter, if any.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// The Solidity type of the value.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The value, formatted so it can be parsed again.
+    pub value: String,
+}
+
+impl CallResult {
+    /// Creates the result of a successful call, decoding the return data with the outputs of the
+    /// function, if known.
+    pub fn success(return_data: Bytes, func: Option<&Function>) -> Result<Self> {
+        let decoded = match func {
+            Some(func) => func
+                .abi_decode_output(&return_data, false)?
+                .iter()
+                .zip(&func.outputs)
+                .map(|(value, param)| DecodedValue {
+                    name: param.name.clone(),
+                    ty: param.selector_type().into_owned(),
+                    value: format_token_raw(value),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(Self { success: true, return_data, decoded, ..Default::default() })
+    }
+
+    /// Creates the result of a reverted call.
+    pub fn revert(revert_data: Option<Bytes>, error: String) -> Self {
+        let error = match &revert_data {
+            Some(data) if !data.is_empty() => decode_revert(data, None, None),
+            _ => error,
+        };
+        Self { success: false, revert_data, error: Some(error), ..Default::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_decode_call_result() {
+        let func = Function::parse("balances(address)(uint256 balance, bool)").unwrap();
+        let data: Bytes = format!("0x{:064x}{:064x}", 42, 1).parse().unwrap();
+        let result = CallResult::success(data.clone(), Some(&func)).unwrap();
+        assert!(result.success);
+        assert_eq!(result.return_data, data);
+        assert_eq!(
+            result.decoded,
+            [
+                DecodedValue { name: "balance".into(), ty: "uint256".into(), value: "42".into() },
+                DecodedValue { name: String::new(), ty: "bool".into(), value: "true".into() },
+            ]
+        );
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["decoded"][1], serde_json::json!({ "type": "bool", "value": "true" }));
+        assert!(json.get("revertData").is_none());
+    }
+
+    #[test]
+    fn can_decode_revert() {
+        // Error("nope")
+        let data: Bytes = "0x08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000046e6f706500000000000000000000000000000000000000000000000000000000".parse().unwrap();
+        let result = CallResult::revert(Some(data), "execution reverted".into());
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("nope"));
+    }
+}
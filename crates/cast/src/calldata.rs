@@ -0,0 +1,405 @@
+//! Recursive decoding of calldata that embeds other calls, like multicalls, Safe transactions and
+//! ERC-4337 user operations.
+
+use alloy_dyn_abi::{DynSolValue, FunctionExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, Selector, U256};
+use eyre::Result;
+use foundry_common::{
+    fmt::format_token,
+    selectors::{SelectorType, SignaturesDb},
+};
+use futures::{future::BoxFuture, FutureExt};
+use std::{collections::HashMap, fmt};
+
+/// The maximum depth of nested calls that are decoded.
+const MAX_DEPTH: usize = 16;
+
+/// Signatures of calls that wrap other calls, which are always resolved without a lookup.
+const WRAPPER_SIGNATURES: &[&str] = &[
+    // Multicall3
+    "aggregate((address,bytes)[])",
+    "blockAndAggregate((address,bytes)[])",
+    "tryAggregate(bool,(address,bytes)[])",
+    "tryBlockAndAggregate(bool,(address,bytes)[])",
+    "aggregate3((address,bool,bytes)[])",
+    "aggregate3Value((address,bool,uint256,bytes)[])",
+    // self multicalls, e.g. Uniswap
+    "multicall(bytes[])",
+    "multicall(uint256,bytes[])",
+    "multicall(bytes32,bytes[])",
+    // Safe
+    "execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,bytes)",
+    "multiSend(bytes)",
+    // ERC-4337 entry points v0.6 and v0.7
+    "handleOps((address,uint256,bytes,bytes,uint256,uint256,uint256,uint256,uint256,bytes,bytes)[],address)",
+    "handleOps((address,uint256,bytes,bytes,bytes32,uint256,bytes32,bytes,bytes)[],address)",
+    // ERC-4337 accounts
+    "execute(address,uint256,bytes)",
+    "executeBatch(address[],bytes[])",
+    "executeBatch(address[],uint256[],bytes[])",
+    "executeUserOp(address,uint256,bytes,uint8)",
+];
+
+/// A decoded call and the calls embedded in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCall {
+    /// The callee, if known.
+    pub target: Option<Address>,
+    /// The value sent with the call, if any.
+    pub value: Option<U256>,
+    /// The function selector.
+    pub selector: Selector,
+    /// The resolved function signature.
+    pub signature: Option<String>,
+    /// The decoded arguments, empty if the signature could not be resolved.
+    pub args: Vec<DynSolValue>,
+    /// The calls embedded in the arguments.
+    pub calls: Vec<DecodedCall>,
+}
+
+impl DecodedCall {
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>, indent: &str) -> fmt::Result {
+        if let Some(target) = self.target {
+            write!(f, "{target}::")?;
+        }
+        match &self.signature {
+            Some(signature) => write!(f, "{signature}")?,
+            None => write!(f, "{} (unknown)", self.selector)?,
+        }
+        if let Some(value) = self.value.filter(|value| !value.is_zero()) {
+            write!(f, " {{value: {value}}}")?;
+        }
+        writeln!(f)?;
+
+        let child_indent = if self.calls.is_empty() { "   " } else { "│  " };
+        for arg in &self.args {
+            writeln!(f, "{indent}{child_indent}{}", format_token(arg))?;
+        }
+
+        for (i, call) in self.calls.iter().enumerate() {
+            let last = i + 1 == self.calls.len();
+            write!(f, "{indent}{}[{i}] ", if last { "└─ " } else { "├─ " })?;
+            call.fmt_tree(f, &format!("{indent}{}", if last { "   " } else { "│  " }))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DecodedCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f, "")
+    }
+}
+
+/// Decodes calldata into a tree of calls.
+///
+/// Calls that are known to wrap other calls (Multicall3, Safe, ERC-4337 and self multicalls) are
+/// always unwrapped. When guessing is enabled, every `bytes` argument that decodes as a call with
+/// a resolved signature is unwrapped as well.
+#[derive(Debug)]
+pub struct CalldataDecoder {
+    db: SignaturesDb,
+    offline: bool,
+    guess: bool,
+    signatures: HashMap<Selector, Vec<String>>,
+}
+
+impl CalldataDecoder {
+    /// Creates a new decoder.
+    ///
+    /// Signatures are looked up in the local signature database, and on https://openchain.xyz
+    /// unless `offline` is set.
+    pub fn new(guess: bool, offline: bool) -> Result<Self> {
+        let mut signatures = HashMap::<Selector, Vec<String>>::new();
+        for signature in WRAPPER_SIGNATURES {
+            let func = Function::parse(signature)?;
+            signatures.entry(func.selector()).or_default().push(func.signature());
+        }
+        Ok(Self { db: SignaturesDb::load(), offline, guess, signatures })
+    }
+
+    /// Decodes the given calldata.
+    pub async fn decode(&mut self, calldata: &[u8]) -> Result<DecodedCall> {
+        eyre::ensure!(calldata.len() >= 4, "calldata must be at least 4 bytes long");
+        Ok(self.decode_call(None, None, calldata.to_vec(), 0).await.expect("calldata has selector"))
+    }
+
+    /// Decodes a single call and its embedded calls.
+    ///
+    /// Returns `None` if the data is shorter than a selector.
+    fn decode_call(
+        &mut self,
+        target: Option<Address>,
+        value: Option<U256>,
+        data: Vec<u8>,
+        depth: usize,
+    ) -> BoxFuture<'_, Option<DecodedCall>> {
+        async move {
+            if data.len() < 4 {
+                return None
+            }
+            let selector = Selector::from_slice(&data[..4]);
+            let mut call = DecodedCall {
+                target,
+                value,
+                selector,
+                signature: None,
+                args: vec![],
+                calls: vec![],
+            };
+
+            let Some((func, args)) = self.resolve(selector, &data[4..]).await else {
+                return Some(call)
+            };
+            call.signature = Some(func.signature());
+
+            if depth < MAX_DEPTH {
+                let embedded = match embedded_calls(&func.signature(), &args, target) {
+                    Some(embedded) => embedded,
+                    None if self.guess => guess_embedded_calls(&args, target),
+                    None => vec![],
+                };
+                let known = WRAPPER_SIGNATURES.contains(&func.signature().as_str());
+                for (target, value, data) in embedded {
+                    if let Some(inner) = self.decode_call(target, value, data, depth + 1).await {
+                        // guessed calls are only kept if they could be decoded
+                        if known || inner.signature.is_some() {
+                            call.calls.push(inner);
+                        }
+                    }
+                }
+            }
+
+            call.args = args;
+            Some(call)
+        }
+        .boxed()
+    }
+
+    /// Returns the first signature of the selector that can decode the arguments.
+    async fn resolve(
+        &mut self,
+        selector: Selector,
+        args: &[u8],
+    ) -> Option<(Function, Vec<DynSolValue>)> {
+        if !self.signatures.contains_key(&selector) {
+            let signatures = self
+                .db
+                .decode_selector(&selector.to_string(), SelectorType::Function, self.offline)
+                .await
+                .unwrap_or_default();
+            self.signatures.insert(selector, signatures);
+        }
+
+        self.signatures[&selector].iter().find_map(|signature| {
+            let func = Function::parse(signature).ok()?;
+            let args = func.abi_decode_input(args, false).ok()?;
+            Some((func, args))
+        })
+    }
+}
+
+/// A call embedded in another call: target, value and calldata.
+type EmbeddedCall = (Option<Address>, Option<U256>, Vec<u8>);
+
+/// Returns the calls embedded in a known wrapper call, or `None` if the call is not a known
+/// wrapper.
+fn embedded_calls(
+    signature: &str,
+    args: &[DynSolValue],
+    target: Option<Address>,
+) -> Option<Vec<EmbeddedCall>> {
+    let name = signature.split('(').next().unwrap_or_default();
+    let calls = match (name, args) {
+        // Multicall3
+        ("aggregate" | "blockAndAggregate", [calls]) |
+        ("tryAggregate" | "tryBlockAndAggregate", [_, calls]) => array(calls)
+            .iter()
+            .filter_map(|call| match tuple(call) {
+                [to, data] => Some((address(to), None, bytes(data)?)),
+                _ => None,
+            })
+            .collect(),
+        ("aggregate3", [calls]) => array(calls)
+            .iter()
+            .filter_map(|call| match tuple(call) {
+                [to, _, data] => Some((address(to), None, bytes(data)?)),
+                _ => None,
+            })
+            .collect(),
+        ("aggregate3Value", [calls]) => array(calls)
+            .iter()
+            .filter_map(|call| match tuple(call) {
+                [to, _, value, data] => Some((address(to), uint(value), bytes(data)?)),
+                _ => None,
+            })
+            .collect(),
+        // self multicalls are executed on the called contract
+        ("multicall", [.., calls]) => {
+            array(calls).iter().filter_map(|data| Some((target, None, bytes(data)?))).collect()
+        }
+        // Safe
+        ("execTransaction", [to, value, data, ..]) => {
+            vec![(address(to), uint(value), bytes(data)?)]
+        }
+        ("multiSend", [transactions]) => multi_send(&bytes(transactions)?)?,
+        // ERC-4337 entry points execute the call data of a user operation on its sender
+        ("handleOps", [ops, _]) => array(ops)
+            .iter()
+            .filter_map(|op| match tuple(op) {
+                [sender, _, _, call_data, ..] => Some((address(sender), None, bytes(call_data)?)),
+                _ => None,
+            })
+            .collect(),
+        // ERC-4337 accounts
+        ("execute" | "executeUserOp", [to, value, data, ..]) => {
+            vec![(address(to), uint(value), bytes(data)?)]
+        }
+        ("executeBatch", [targets, datas]) => array(targets)
+            .iter()
+            .zip(array(datas))
+            .filter_map(|(to, data)| Some((address(to), None, bytes(data)?)))
+            .collect(),
+        ("executeBatch", [targets, values, datas]) => array(targets)
+            .iter()
+            .zip(array(values))
+            .zip(array(datas))
+            .filter_map(|((to, value), data)| Some((address(to), uint(value), bytes(data)?)))
+            .collect(),
+        _ => return None,
+    };
+    Some(calls)
+}
+
+/// Decodes the packed transactions of a Safe `multiSend` call.
+///
+/// Every transaction is encoded as `operation (uint8), to (address), value (uint256), data length
+/// (uint256), data (bytes)`.
+fn multi_send(mut transactions: &[u8]) -> Option<Vec<EmbeddedCall>> {
+    let mut calls = vec![];
+    while !transactions.is_empty() {
+        if transactions.len() < 85 {
+            return None
+        }
+        let to = Address::from_slice(&transactions[1..21]);
+        let value = U256::from_be_slice(&transactions[21..53]);
+        let len: usize = U256::from_be_slice(&transactions[53..85]).try_into().ok()?;
+        let data = transactions.get(85..85usize.checked_add(len)?)?;
+        calls.push((Some(to), Some(value), data.to_vec()));
+        transactions = &transactions[85 + len..];
+    }
+    Some(calls)
+}
+
+/// Returns every `bytes` value in the arguments as a potential call, targeting the closest
+/// preceding address in the same arguments or tuple.
+fn guess_embedded_calls(args: &[DynSolValue], target: Option<Address>) -> Vec<EmbeddedCall> {
+    let mut calls = vec![];
+    let mut target = target;
+    for arg in args {
+        match arg {
+            DynSolValue::Address(address) => target = Some(*address),
+            DynSolValue::Bytes(data) if data.len() >= 4 => calls.push((target, None, data.clone())),
+            DynSolValue::Tuple(values) => calls.extend(guess_embedded_calls(values, target)),
+            DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+                for value in values {
+                    calls.extend(guess_embedded_calls(std::slice::from_ref(value), target));
+                }
+            }
+            _ => {}
+        }
+    }
+    calls
+}
+
+fn array(value: &DynSolValue) -> &[DynSolValue] {
+    match value {
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => values,
+        _ => &[],
+    }
+}
+
+fn tuple(value: &DynSolValue) -> &[DynSolValue] {
+    match value {
+        DynSolValue::Tuple(values) => values,
+        _ => &[],
+    }
+}
+
+fn address(value: &DynSolValue) -> Option<Address> {
+    value.as_address()
+}
+
+fn uint(value: &DynSolValue) -> Option<U256> {
+    value.as_uint().map(|(value, _)| value)
+}
+
+fn bytes(value: &DynSolValue) -> Option<Vec<u8>> {
+    value.as_bytes().map(<[u8]>::to_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_dyn_abi::JsonAbiExt;
+    use alloy_primitives::hex;
+
+    fn calldata(signature: &str, args: &[DynSolValue]) -> Vec<u8> {
+        Function::parse(signature).unwrap().abi_encode_input(args).unwrap()
+    }
+
+    #[tokio::test]
+    async fn decodes_nested_multicall() {
+        let token = Address::repeat_byte(0x11);
+        let safe = Address::repeat_byte(0x22);
+        let inner = calldata(
+            "execute(address,uint256,bytes)",
+            &[
+                DynSolValue::Address(token),
+                DynSolValue::Uint(U256::from(1), 256),
+                DynSolValue::Bytes(vec![0xab; 4]),
+            ],
+        );
+        let data = calldata(
+            "aggregate3((address,bool,bytes)[])",
+            &[DynSolValue::Array(vec![DynSolValue::Tuple(vec![
+                DynSolValue::Address(safe),
+                DynSolValue::Bool(false),
+                DynSolValue::Bytes(inner),
+            ])])],
+        );
+
+        let mut decoder = CalldataDecoder::new(false, true).unwrap();
+        let call = decoder.decode(&data).await.unwrap();
+        assert_eq!(call.signature.as_deref(), Some("aggregate3((address,bool,bytes)[])"));
+        assert_eq!(call.calls.len(), 1);
+
+        let execute = &call.calls[0];
+        assert_eq!(execute.target, Some(safe));
+        assert_eq!(execute.signature.as_deref(), Some("execute(address,uint256,bytes)"));
+        assert_eq!(execute.calls.len(), 1);
+
+        let unknown = &execute.calls[0];
+        assert_eq!(unknown.target, Some(token));
+        assert_eq!(unknown.value, Some(U256::from(1)));
+        assert_eq!(unknown.selector, Selector::new([0xab; 4]));
+        assert_eq!(unknown.signature, None);
+    }
+
+    #[test]
+    fn decodes_multi_send_transactions() {
+        let to = Address::repeat_byte(0x33);
+        let mut packed = vec![0u8];
+        packed.extend_from_slice(to.as_slice());
+        packed.extend_from_slice(&U256::from(5).to_be_bytes::<32>());
+        packed.extend_from_slice(&U256::from(4).to_be_bytes::<32>());
+        packed.extend_from_slice(&hex!("deadbeef"));
+
+        let calls = multi_send(&packed).unwrap();
+        assert_eq!(calls, vec![(Some(to), Some(U256::from(5)), hex!("deadbeef").to_vec())]);
+
+        // truncated transactions are rejected
+        assert_eq!(multi_send(&packed[..packed.len() - 1]), None);
+    }
+}
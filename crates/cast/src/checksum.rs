@@ -0,0 +1,106 @@
+//! Validation of the EIP-55 and EIP-1191 checksums of addresses
+
+use alloy_primitives::Address;
+use ethers_core::types::NameOrAddress;
+use eyre::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The chains that use EIP-1191 checksums, which include the chain ID in the hash: RSK mainnet and
+/// testnet
+pub const EIP1191_CHAIN_IDS: [u64; 2] = [30, 31];
+
+/// Whether [parse_address] and [parse_name_or_address] validate checksums
+static VALIDATE_CHECKSUMS: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables the checksum validation of [parse_address] and [parse_name_or_address].
+///
+/// This has to be set before the arguments are parsed since the parsers run during parsing.
+pub fn set_checksum_validation(enabled: bool) {
+    VALIDATE_CHECKSUMS.store(enabled, Ordering::Relaxed);
+}
+
+/// Parses an address argument and validates its checksum, see [validate_checksum].
+pub fn parse_address(s: &str) -> Result<Address> {
+    if VALIDATE_CHECKSUMS.load(Ordering::Relaxed) {
+        validate_checksum(s)?;
+    }
+    Ok(s.parse()?)
+}
+
+/// Parses an ENS name or address argument and validates the checksum of addresses, see
+/// [validate_checksum].
+pub fn parse_name_or_address(s: &str) -> Result<NameOrAddress> {
+    if VALIDATE_CHECKSUMS.load(Ordering::Relaxed) && is_address_like(s) {
+        validate_checksum(s)?;
+    }
+    s.parse().map_err(|err| eyre::eyre!("{err}"))
+}
+
+/// Validates the checksum of an address.
+///
+/// Lowercase and uppercase addresses don't carry a checksum and are always valid, mixed-case
+/// addresses must have a valid EIP-55 checksum, or an EIP-1191 checksum of one of the
+/// [EIP1191_CHAIN_IDS].
+///
+/// The error suggests the correctly checksummed address.
+pub fn validate_checksum(s: &str) -> Result<()> {
+    let address: Address = s.parse()?;
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    let has_lowercase = hex.chars().any(|c| c.is_ascii_lowercase());
+    let has_uppercase = hex.chars().any(|c| c.is_ascii_uppercase());
+    if !(has_lowercase && has_uppercase) {
+        return Ok(())
+    }
+
+    let prefixed = format!("0x{hex}");
+    let is_valid = std::iter::once(None)
+        .chain(EIP1191_CHAIN_IDS.map(Some))
+        .any(|chain_id| address.to_checksum(chain_id) == prefixed);
+    if !is_valid {
+        eyre::bail!(
+            "Invalid checksum of address `{s}`, did you mean `{}`?\n\
+             Pass --no-checksum-validate to skip the checksum validation",
+            address.to_checksum(None)
+        );
+    }
+    Ok(())
+}
+
+fn is_address_like(s: &str) -> bool {
+    s.strip_prefix("0x")
+        .map_or(false, |hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_eip55_checksums() {
+        validate_checksum("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap();
+        validate_checksum("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+        validate_checksum("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED").unwrap();
+
+        let err = validate_checksum("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").unwrap_err();
+        assert!(
+            err.to_string().contains("did you mean `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn validates_eip1191_checksums() {
+        // chain 30
+        validate_checksum("0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD").unwrap();
+        // chain 31
+        validate_checksum("0x5aAeb6053F3e94c9b9A09F33669435E7EF1BEaEd").unwrap();
+    }
+
+    #[test]
+    fn parses_names_and_addresses() {
+        assert!(parse_name_or_address("vitalik.eth").is_ok());
+        assert!(parse_name_or_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok());
+        assert!(parse_name_or_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
+        assert!(parse_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
+    }
+}
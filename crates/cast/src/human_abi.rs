@@ -0,0 +1,475 @@
+//! Conversion between JSON ABIs and human-readable ABIs
+//!
+//! Human-readable ABIs are lists of Solidity-like declarations, as used by ethers.js, e.g.
+//! `function transfer(address to, uint256 amount) returns (bool)`, which are parsed with
+//! [alloy_json_abi]. Tuples that are structs in the source are declared as
+//! `struct Exchange.Order { address maker; uint256 amount; }` and referenced by their
+//! fully-qualified name, using the `internalType` of the JSON ABI, so struct names survive a
+//! roundtrip and structs of different contracts with the same name are kept apart.
+
+use alloy_dyn_abi::DynSolType;
+use alloy_json_abi::{
+    Constructor, Error, Event, EventParam, Fallback, Function, InternalType, JsonAbi, Param,
+    Receive, StateMutability,
+};
+use eyre::{Result, WrapErr};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The maximum nesting depth of structs, to detect recursive struct definitions.
+const MAX_STRUCT_DEPTH: usize = 32;
+
+/// Returns whether the input is a JSON ABI, or an artifact containing one, rather than a
+/// human-readable ABI.
+pub fn is_json_abi(input: &str) -> bool {
+    match serde_json::from_str::<Value>(input) {
+        Ok(Value::Array(items)) => items.iter().all(Value::is_object),
+        Ok(Value::Object(artifact)) => artifact.contains_key("abi"),
+        _ => false,
+    }
+}
+
+/// Converts a JSON ABI, or an artifact containing one, to a human-readable ABI.
+///
+/// The struct definitions come first, followed by the constructor, fallback and receive
+/// functions, the functions, the events and the errors.
+pub fn to_human_readable(json: &str) -> Result<Vec<String>> {
+    let mut value: Value = serde_json::from_str(json).wrap_err("invalid JSON ABI")?;
+    if let Some(abi) = value.get_mut("abi") {
+        value = abi.take();
+    }
+    let abi: JsonAbi = serde_json::from_value(value).wrap_err("invalid JSON ABI")?;
+
+    let mut structs = Structs::default();
+    let mut declarations = Vec::new();
+    if let Some(constructor) = &abi.constructor {
+        let inputs = structs.params(&constructor.inputs);
+        let mutability = mutability(&constructor.state_mutability);
+        declarations.push(format!("constructor({inputs}){mutability}"));
+    }
+    if let Some(fallback) = &abi.fallback {
+        declarations.push(format!("fallback(){}", mutability(&fallback.state_mutability)));
+    }
+    if abi.receive.is_some() {
+        declarations.push("receive() external payable".to_string());
+    }
+    for function in abi.functions() {
+        let inputs = structs.params(&function.inputs);
+        let returns = if function.outputs.is_empty() {
+            String::new()
+        } else {
+            format!(" returns ({})", structs.params(&function.outputs))
+        };
+        let mutability = mutability(&function.state_mutability);
+        declarations.push(format!("function {}({inputs}){mutability}{returns}", function.name));
+    }
+    for event in abi.events() {
+        let inputs = event
+            .inputs
+            .iter()
+            .map(|param| {
+                let ty = structs.ty(&param.ty, &param.components, param.internal_type.as_ref());
+                declaration(ty, param.indexed, &param.name)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let anonymous = if event.anonymous { " anonymous" } else { "" };
+        declarations.push(format!("event {}({inputs}){anonymous}", event.name));
+    }
+    for error in abi.errors() {
+        declarations.push(format!("error {}({})", error.name, structs.params(&error.inputs)));
+    }
+
+    Ok(structs
+        .definitions
+        .into_iter()
+        .map(|(_, definition)| definition)
+        .chain(declarations)
+        .collect())
+}
+
+/// Converts a human-readable ABI to a JSON ABI.
+///
+/// Empty lines and `//` comments are ignored, struct definitions may span multiple lines.
+pub fn from_human_readable<'a>(lines: impl IntoIterator<Item = &'a str>) -> Result<JsonAbi> {
+    let mut declarations = Vec::new();
+    let mut pending_struct: Option<String> = None;
+    for line in lines {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue
+        }
+        let line = match pending_struct.take() {
+            Some(start) => format!("{start} {line}"),
+            None => line.to_string(),
+        };
+        if line.starts_with("struct ") && !line.contains('}') {
+            pending_struct = Some(line);
+        } else {
+            declarations.push(line);
+        }
+    }
+    if let Some(definition) = pending_struct {
+        eyre::bail!("unterminated struct definition `{definition}`");
+    }
+
+    let (definitions, items): (Vec<_>, Vec<_>) =
+        declarations.iter().partition(|declaration| declaration.starts_with("struct "));
+    let parser = Parser::new(&definitions)?;
+    let mut abi = JsonAbi::default();
+    for declaration in items {
+        parser
+            .parse_item(declaration, &mut abi)
+            .wrap_err_with(|| format!("failed to parse `{declaration}`"))?;
+    }
+    Ok(abi)
+}
+
+/// Returns the human-readable state mutability modifier, with a leading space.
+fn mutability(state_mutability: &StateMutability) -> &'static str {
+    match state_mutability {
+        StateMutability::Pure => " pure",
+        StateMutability::View => " view",
+        StateMutability::Payable => " payable",
+        StateMutability::NonPayable => "",
+    }
+}
+
+/// Returns the human-readable declaration of a parameter, e.g. `address indexed from`.
+fn declaration(mut ty: String, indexed: bool, name: &str) -> String {
+    if indexed {
+        ty.push_str(" indexed");
+    }
+    if !name.is_empty() {
+        ty.push(' ');
+        ty.push_str(name);
+    }
+    ty
+}
+
+/// The struct definitions collected while converting a JSON ABI, by fully-qualified name in order
+/// of first use.
+#[derive(Default)]
+struct Structs {
+    definitions: Vec<(String, String)>,
+}
+
+impl Structs {
+    /// Returns the comma-separated human-readable parameters.
+    fn params(&mut self, params: &[Param]) -> String {
+        params
+            .iter()
+            .map(|param| {
+                let ty = self.ty(&param.ty, &param.components, param.internal_type.as_ref());
+                declaration(ty, false, &param.name)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Returns the human-readable type of a parameter, the fully-qualified struct name for struct
+    /// tuples.
+    fn ty(
+        &mut self,
+        ty: &str,
+        components: &[Param],
+        internal_type: Option<&InternalType>,
+    ) -> String {
+        let Some(dimensions) = ty.strip_prefix("tuple") else { return ty.to_string() };
+        let Some(InternalType::Struct { contract, ty: struct_ty }) = internal_type else {
+            return format!("({}){dimensions}", self.params(components))
+        };
+
+        let base = struct_ty.split('[').next().unwrap_or(struct_ty);
+        let name = match contract {
+            Some(contract) => format!("{contract}.{base}"),
+            None => base.to_string(),
+        };
+        if !self.definitions.iter().any(|(defined, _)| *defined == name) {
+            let fields = components
+                .iter()
+                .map(|field| {
+                    let ty = self.ty(&field.ty, &field.components, field.internal_type.as_ref());
+                    format!("{ty} {};", field.name)
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            self.definitions.push((name.clone(), format!("struct {name} {{ {fields} }}")));
+        }
+        format!("{name}{dimensions}")
+    }
+}
+
+/// Parses human-readable declarations with the alloy parser, resolving struct names.
+struct Parser {
+    /// The fully-qualified names of the structs that are qualified with a contract name.
+    qualified: BTreeSet<String>,
+    /// The fields of the structs, by the name the alloy parser sees, see [Parser::alias].
+    structs: BTreeMap<String, (String, Vec<Param>)>,
+}
+
+impl Parser {
+    /// Parses struct definitions, e.g. `struct Order { address maker; uint256 amount; }`.
+    fn new(definitions: &[&String]) -> Result<Self> {
+        let mut names = Vec::with_capacity(definitions.len());
+        for definition in definitions {
+            let invalid = || eyre::eyre!("invalid struct definition `{definition}`");
+            let rest = definition.strip_prefix("struct ").ok_or_else(invalid)?;
+            let (name, body) = rest.split_once('{').ok_or_else(invalid)?;
+            let body = body.trim().strip_suffix('}').ok_or_else(invalid)?;
+            names.push((name.trim(), body));
+        }
+
+        let qualified = names
+            .iter()
+            .filter(|(name, _)| name.contains('.'))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let mut parser = Self { qualified, structs: BTreeMap::new() };
+        for (name, body) in names {
+            let fields = parser
+                .alias(body)
+                .split(';')
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .map(|field| {
+                    Param::parse(field)
+                        .map_err(|err| eyre::eyre!("invalid field `{field}` of `{name}`: {err}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            parser.structs.insert(name.replace('.', "$"), (name.to_string(), fields));
+        }
+        Ok(parser)
+    }
+
+    /// Replaces the qualified struct names, e.g. `Exchange.Order`, with `Exchange$Order`, since
+    /// the alloy parser reads qualified names as enums.
+    fn alias(&self, declaration: &str) -> String {
+        let mut out = String::with_capacity(declaration.len());
+        let mut name = String::new();
+        for c in declaration.chars().map(Some).chain([None]) {
+            if let Some(c) =
+                c.filter(|c| c.is_ascii_alphanumeric() || matches!(*c, '_' | '$' | '.'))
+            {
+                name.push(c);
+                continue
+            }
+            if self.qualified.contains(&name) {
+                out.push_str(&name.replace('.', "$"));
+            } else {
+                out.push_str(&name);
+            }
+            name.clear();
+            out.extend(c);
+        }
+        out
+    }
+
+    /// Parses a function, event, error, constructor, fallback or receive declaration into the
+    /// ABI.
+    fn parse_item(&self, declaration: &str, abi: &mut JsonAbi) -> Result<()> {
+        let (declaration, state_mutability) = strip_modifiers(&self.alias(declaration))?;
+        let keyword =
+            declaration.split(|c: char| c.is_whitespace() || c == '(').next().unwrap_or_default();
+        match keyword {
+            "constructor" => {
+                let mut constructor = Constructor::parse(&declaration)?;
+                self.resolve_params(&mut constructor.inputs)?;
+                constructor.state_mutability = state_mutability;
+                if abi.constructor.replace(constructor).is_some() {
+                    eyre::bail!("duplicate constructor");
+                }
+            }
+            "fallback" => abi.fallback = Some(Fallback { state_mutability }),
+            "receive" => abi.receive = Some(Receive { state_mutability: StateMutability::Payable }),
+            "event" => {
+                let mut event = Event::parse(&declaration)?;
+                for param in &mut event.inputs {
+                    self.resolve_event_param(param)?;
+                }
+                abi.events.entry(event.name.clone()).or_default().push(event);
+            }
+            "error" => {
+                let mut error = Error::parse(&declaration)?;
+                self.resolve_params(&mut error.inputs)?;
+                abi.errors.entry(error.name.clone()).or_default().push(error);
+            }
+            _ => {
+                let mut function = Function::parse(&declaration)?;
+                self.resolve_params(&mut function.inputs)?;
+                self.resolve_params(&mut function.outputs)?;
+                function.state_mutability = state_mutability;
+                abi.functions.entry(function.name.clone()).or_default().push(function);
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_params(&self, params: &mut [Param]) -> Result<()> {
+        params.iter_mut().try_for_each(|param| self.resolve(param, 0))
+    }
+
+    fn resolve_event_param(&self, param: &mut EventParam) -> Result<()> {
+        let mut resolved = Param {
+            name: std::mem::take(&mut param.name),
+            ty: std::mem::take(&mut param.ty),
+            components: std::mem::take(&mut param.components),
+            internal_type: param.internal_type.take(),
+        };
+        self.resolve(&mut resolved, 0)?;
+        param.name = resolved.name;
+        param.ty = resolved.ty;
+        param.components = resolved.components;
+        param.internal_type = resolved.internal_type;
+        Ok(())
+    }
+
+    /// Expands the struct names in the type of a parameter to tuples, and checks the other types.
+    fn resolve(&self, param: &mut Param, depth: usize) -> Result<()> {
+        if depth > MAX_STRUCT_DEPTH {
+            eyre::bail!("recursive struct definition of `{}`", param.ty);
+        }
+        if !param.components.is_empty() {
+            return param.components.iter_mut().try_for_each(|param| self.resolve(param, depth))
+        }
+
+        let (base, dimensions) = param.ty.split_at(param.ty.find('[').unwrap_or(param.ty.len()));
+        if let Some((name, fields)) = self.structs.get(base) {
+            let mut components = fields.clone();
+            for field in &mut components {
+                self.resolve(field, depth + 1)?;
+            }
+            let (contract, name) = match name.rsplit_once('.') {
+                Some((contract, name)) => (Some(contract.to_string()), name),
+                None => (None, name.as_str()),
+            };
+            param.internal_type =
+                Some(InternalType::Struct { contract, ty: format!("{name}{dimensions}") });
+            param.ty = format!("tuple{dimensions}");
+            param.components = components;
+            return Ok(())
+        }
+
+        DynSolType::parse(&param.ty)
+            .map_err(|err| eyre::eyre!("invalid type `{}`: {err}", param.ty))?;
+        Ok(())
+    }
+}
+
+/// Removes the visibility and state mutability modifiers outside of parameter lists, which the
+/// alloy parser doesn't accept, and returns the state mutability.
+fn strip_modifiers(declaration: &str) -> Result<(String, StateMutability)> {
+    let mut state_mutability = StateMutability::NonPayable;
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    for c in declaration.chars().map(Some).chain([None]) {
+        match c {
+            Some('(') => depth += 1,
+            Some(')') => depth = depth.saturating_sub(1),
+            Some(c) if depth > 0 || !c.is_whitespace() => {}
+            _ => {
+                match word.as_str() {
+                    "view" | "constant" => state_mutability = StateMutability::View,
+                    "pure" => state_mutability = StateMutability::Pure,
+                    "payable" => state_mutability = StateMutability::Payable,
+                    "nonpayable" | "external" | "public" | "virtual" | "override" | "" => {}
+                    _ => words.push(word.clone()),
+                }
+                word.clear();
+                continue
+            }
+        }
+        word.extend(c);
+    }
+    if depth != 0 {
+        eyre::bail!("unbalanced parentheses");
+    }
+    Ok((words.join(" "), state_mutability))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HUMAN_READABLE: &[&str] = &[
+        "struct Asset { address token; uint256 amount; }",
+        "struct Order { address maker; Asset[] assets; }",
+        "constructor(address owner) payable",
+        "receive() external payable",
+        "function fill(Order order, bytes signature) returns (bool filled)",
+        "function orders(uint256) view returns ((address, uint256))",
+        "event Filled(address indexed maker, Order order) anonymous",
+        "error Expired(uint256 deadline)",
+    ];
+
+    #[test]
+    fn roundtrips_human_readable_abi() {
+        let abi = from_human_readable(HUMAN_READABLE.iter().copied()).unwrap();
+        let json = serde_json::to_string(&abi).unwrap();
+
+        let fill = &abi.functions["fill"][0];
+        let order = &fill.inputs[0];
+        assert_eq!(order.ty, "tuple");
+        assert_eq!(order.internal_type.as_ref().unwrap().to_string(), "struct Order");
+        assert_eq!(order.components[1].ty, "tuple[]");
+        assert_eq!(
+            order.components[1].internal_type.as_ref().unwrap().to_string(),
+            "struct Asset[]"
+        );
+        assert_eq!(abi.functions["orders"][0].state_mutability, StateMutability::View);
+
+        assert_eq!(to_human_readable(&json).unwrap(), HUMAN_READABLE);
+    }
+
+    #[test]
+    fn keeps_structs_of_different_contracts_apart() {
+        let artifact = serde_json::json!({
+            "abi": [{
+                "type": "function",
+                "name": "submit",
+                "inputs": [{
+                    "name": "a",
+                    "type": "tuple",
+                    "internalType": "struct A.Order",
+                    "components": [{ "name": "maker", "type": "address", "internalType": "address" }]
+                }, {
+                    "name": "b",
+                    "type": "tuple[]",
+                    "internalType": "struct B.Order[]",
+                    "components": [{ "name": "amount", "type": "uint256", "internalType": "uint256" }]
+                }],
+                "outputs": [],
+                "stateMutability": "nonpayable"
+            }]
+        })
+        .to_string();
+        assert!(is_json_abi(&artifact));
+
+        let human_readable = to_human_readable(&artifact).unwrap();
+        assert_eq!(
+            human_readable,
+            [
+                "struct A.Order { address maker; }",
+                "struct B.Order { uint256 amount; }",
+                "function submit(A.Order a, B.Order[] b)",
+            ]
+        );
+
+        let abi = from_human_readable(human_readable.iter().map(String::as_str)).unwrap();
+        let submit = &abi.functions["submit"][0];
+        assert_eq!(submit.signature(), "submit((address),(uint256)[])");
+        assert_eq!(
+            submit.inputs[1].internal_type.as_ref().unwrap().to_string(),
+            "struct B.Order[]"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_declarations() {
+        assert!(from_human_readable(["function f(notatype x)"]).is_err());
+        assert!(from_human_readable(["struct A { A a; }", "function f(A a)"]).is_err());
+        assert!(from_human_readable(["function f(address"]).is_err());
+        assert!(!is_json_abi("function f()"));
+    }
+}
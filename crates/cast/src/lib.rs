@@ -3,6 +3,7 @@ use alloy_json_abi::ContractObject;
 use alloy_primitives::{Address, I256, U256};
 use alloy_rlp::Decodable;
 use base::{Base, NumberWithBase, ToBase};
+use call::CallResult;
 use chrono::NaiveDateTime;
 use ethers_core::{
     types::{transaction::eip2718::TypedTransaction, *},
@@ -11,14 +12,16 @@ use ethers_core::{
         Units,
     },
 };
-use ethers_providers::{Middleware, PendingTransaction, PubsubClient};
+use ethers_providers::{Middleware, MiddlewareError, PendingTransaction, PubsubClient};
 use evm_disassembler::{disassemble_bytes, disassemble_str, format_operations};
 use eyre::{Context, ContextCompat, Result};
 use foundry_block_explorers::Client;
 use foundry_common::{
     abi::{encode_function_args, get_func},
+    compile::vendor_etherscan_sources,
     fmt::*,
     types::{ToAlloy, ToEthers},
+    units::UnitsDisplay,
     TransactionReceiptWithRevertReason,
 };
 use foundry_config::Chain;
@@ -26,7 +29,7 @@ use futures::{future::Either, FutureExt, StreamExt};
 use rayon::prelude::*;
 use std::{
     io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::atomic::{AtomicBool, Ordering},
 };
@@ -42,10 +45,21 @@ pub use rusoto_kms::KmsClient;
 pub use tx::TxBuilder;
 
 pub mod base;
+pub mod batch;
+mod bulk_decode;
+pub mod call;
+mod calldata;
+pub mod checksum;
 pub mod errors;
+pub mod human_abi;
+mod proof;
 mod rlp_converter;
 mod tx;
 
+pub use bulk_decode::{parse_abi_file, parse_calldata_list, BulkCalldataDecoder, DecodedCalldata};
+pub use calldata::{CalldataDecoder, DecodedCall};
+use proof::verify_account_proof;
+pub use proof::{TrieAccount, EMPTY_ROOT_HASH};
 use rlp_converter::Item;
 
 // TODO: CastContract with common contract initializers? Same for CastProviders?
@@ -143,6 +157,34 @@ where
         })
     }
 
+    /// Makes a read-only call like [Cast::call], returning the raw and decoded return data, the
+    /// gas estimate, or the revert data if the call failed.
+    pub async fn call_full(
+        &self,
+        builder_output: TxBuilderOutput,
+        block: Option<BlockId>,
+    ) -> Result<CallResult> {
+        let (tx, func) = builder_output;
+        match self.provider.call(&tx, block).await {
+            Ok(res) => {
+                let mut result = CallResult::success(res, func.as_ref()).wrap_err(
+                    "could not decode output; did you specify the wrong function return data type?",
+                )?;
+                result.gas_estimate =
+                    self.provider.estimate_gas(&tx, block).await.ok().map(|gas| gas.as_u64());
+                Ok(result)
+            }
+            Err(err) => match err.as_error_response() {
+                Some(rpc_err)
+                    if rpc_err.as_revert_data().is_some() || rpc_err.message.contains("revert") =>
+                {
+                    Ok(CallResult::revert(rpc_err.as_revert_data(), rpc_err.message.clone()))
+                }
+                _ => Err(err.into()),
+            },
+        }
+    }
+
     /// Generates an access list for the specified transaction
     ///
     /// # Example
@@ -634,6 +676,104 @@ where
         Ok(format!("{}", code.len()))
     }
 
+    /// Returns the account of `who` at the given block, using `eth_getProof`.
+    ///
+    /// The proof is verified against the state root of the block. Returns `None` if the proof
+    /// shows that the account does not exist.
+    pub async fn verified_account<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        who: T,
+        block: Option<BlockId>,
+    ) -> Result<Option<TrieAccount>> {
+        let address = match who.into() {
+            NameOrAddress::Name(name) => self.provider.resolve_name(&name).await?,
+            NameOrAddress::Address(address) => address,
+        };
+        let block = self
+            .provider
+            .get_block(block.unwrap_or(BlockId::Number(BlockNumber::Latest)))
+            .await?
+            .wrap_err("block not found")?;
+        let number = block.number.wrap_err("can not verify proofs against a pending block")?;
+
+        let proof = self.provider.get_proof(address, vec![], Some(number.into())).await?;
+        let account = verify_account_proof(
+            block.state_root.to_alloy(),
+            address.to_alloy(),
+            &proof.account_proof,
+        )?;
+        if let Some(account) = &account {
+            eyre::ensure!(
+                account.code_hash == proof.code_hash.to_alloy() &&
+                    account.storage_root == proof.storage_hash.to_alloy(),
+                "eth_getProof response does not match the proven account"
+            );
+        }
+        Ok(account)
+    }
+
+    /// Returns the code hash of `who`, verified with `eth_getProof`.
+    ///
+    /// Like `EXTCODEHASH`, this is zero for accounts that do not exist.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use cast::Cast;
+    /// use ethers_core::types::Address;
+    /// use ethers_providers::{Http, Provider};
+    /// use std::str::FromStr;
+    ///
+    /// # async fn foo() -> eyre::Result<()> {
+    /// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+    /// let cast = Cast::new(provider);
+    /// let addr = Address::from_str("0x00000000219ab540356cbb839cbe05303d7705fa")?;
+    /// let codehash = cast.codehash(addr, None).await?;
+    /// println!("{}", codehash);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn codehash<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        who: T,
+        block: Option<BlockId>,
+    ) -> Result<String> {
+        let account = self.verified_account(who, block).await?;
+        let code_hash = account.map(|account| account.code_hash).unwrap_or_default();
+        Ok(format!("0x{}", hex::encode(code_hash)))
+    }
+
+    /// Returns the storage root of `who`, verified with `eth_getProof`.
+    ///
+    /// This is the root of an empty trie for accounts that do not exist.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use cast::Cast;
+    /// use ethers_core::types::Address;
+    /// use ethers_providers::{Http, Provider};
+    /// use std::str::FromStr;
+    ///
+    /// # async fn foo() -> eyre::Result<()> {
+    /// let provider = Provider::<Http>::try_from("http://localhost:8545")?;
+    /// let cast = Cast::new(provider);
+    /// let addr = Address::from_str("0x00000000219ab540356cbb839cbe05303d7705fa")?;
+    /// let storage_root = cast.storage_root(addr, None).await?;
+    /// println!("{}", storage_root);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn storage_root<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        who: T,
+        block: Option<BlockId>,
+    ) -> Result<String> {
+        let account = self.verified_account(who, block).await?;
+        let storage_root = account.map_or(EMPTY_ROOT_HASH, |account| account.storage_root);
+        Ok(format!("0x{}", hex::encode(storage_root)))
+    }
+
     /// # Example
     ///
     /// ```ignore
@@ -1312,6 +1452,53 @@ impl SimpleCast {
         Ok(wei.to_string())
     }
 
+    /// Converts a decimal amount into the smallest unit, without losing precision.
+    ///
+    /// The amount can be negative and use scientific notation. The unit is either a unit name or
+    /// a number of decimals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cast::SimpleCast as Cast;
+    ///
+    /// assert_eq!(Cast::parse_units("1.5", "ether")?, "1500000000000000000");
+    /// assert_eq!(Cast::parse_units("1.5e18", "wei")?, "1500000000000000000");
+    /// assert_eq!(Cast::parse_units("-2.5", "6")?, "-2500000");
+    /// assert!(Cast::parse_units("1.0000001", "6").is_err());
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn parse_units(value: &str, unit: &str) -> Result<String> {
+        Ok(foundry_common::units::parse_units(value, unit)?.to_string())
+    }
+
+    /// Converts an amount in the smallest unit into a decimal amount of the given unit.
+    ///
+    /// The unit is either a unit name or a number of decimals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cast::SimpleCast as Cast;
+    /// use foundry_common::units::UnitsDisplay;
+    ///
+    /// let display = UnitsDisplay::default();
+    /// assert_eq!(Cast::format_units("1500000000000000000", "ether", display)?, "1.5");
+    /// assert_eq!(Cast::format_units("-2500000", "6", display)?, "-2.5");
+    ///
+    /// let display = UnitsDisplay { precision: Some(2), thousands_separator: Some(',') };
+    /// assert_eq!(Cast::format_units("1234567891", "3", display)?, "1,234,567.89");
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn format_units(value: &str, unit: &str, display: UnitsDisplay) -> Result<String> {
+        let value = NumberWithBase::parse_int(value, None)?;
+        Ok(if value.is_nonnegative() {
+            display.format(value.number(), unit)?
+        } else {
+            display.format(I256::from_raw(value.number()), unit)?
+        })
+    }
+
     /// Decodes rlp encoded list with hex data
     ///
     /// # Example
@@ -1713,6 +1900,28 @@ impl SimpleCast {
         Ok(hex::encode_prefixed(node))
     }
 
+    /// Calculates the ENS labelhash of a single label, which is the keccak256 hash of the label
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cast::SimpleCast as Cast;
+    ///
+    /// assert_eq!(
+    ///     Cast::labelhash("eth")?,
+    ///     "0x4f5b812789fc606be1b3b16908db13fc7a9adf7ca72641f84d75b47069d3d7f0"
+    /// );
+    /// assert_eq!(Cast::labelhash("ETH")?, Cast::labelhash("eth")?);
+    /// assert!(Cast::labelhash("foo.eth").is_err());
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn labelhash(label: &str) -> Result<String> {
+        if label.contains('.') {
+            eyre::bail!("`{label}` is not a single label, use the namehash of full names");
+        }
+        Ok(hex::encode_prefixed(keccak256(label.to_lowercase().as_bytes())))
+    }
+
     /// Keccak-256 hashes arbitrary data
     ///
     /// # Example
@@ -1749,6 +1958,32 @@ impl SimpleCast {
         Ok(format!("{:?}", H256(hash)))
     }
 
+    /// Hashes a message as specified in [EIP-191](https://eips.ethereum.org/EIPS/eip-191), the
+    /// digest signed by `eth_sign` and `personal_sign`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cast::SimpleCast as Cast;
+    ///
+    /// assert_eq!(
+    ///     Cast::hash_message("hello")?,
+    ///     "0x50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750"
+    /// );
+    /// assert_eq!(Cast::hash_message("0x68656c6c6f")?, Cast::hash_message("hello")?);
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn hash_message(message: &str) -> Result<String> {
+        let hash = match message.strip_prefix("0x") {
+            // 0x prefix => read as hex data
+            Some(data) => ethers_core::utils::hash_message(hex::decode(data)?),
+            // No 0x prefix => read as text
+            None => ethers_core::utils::hash_message(message),
+        };
+
+        Ok(format!("{hash:?}"))
+    }
+
     /// Performs the left shift operation (<<) on a number
     ///
     /// # Example
@@ -1870,6 +2105,55 @@ impl SimpleCast {
         Ok(())
     }
 
+    /// Fetches the source code of verified contracts from etherscan and writes it into the
+    /// project at `root`, under `<src>/vendor/<ContractName>/`.
+    ///
+    /// The remappings required to compile the sources are appended to the project's
+    /// `remappings.txt`. Returns the directory the sources were written to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cast::SimpleCast as Cast;
+    /// # use foundry_config::NamedChain;
+    /// # use std::path::Path;
+    /// # async fn expand() -> eyre::Result<()> {
+    /// Cast::expand_etherscan_source_to_project(
+    ///     NamedChain::Mainnet.into(),
+    ///     "0xBB9bc244D798123fDe783fCc1C72d3Bb8C189413".to_string(),
+    ///     "<etherscan_api_key>".to_string(),
+    ///     Path::new("."),
+    ///     Path::new("src"),
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn expand_etherscan_source_to_project(
+        chain: Chain,
+        contract_address: String,
+        etherscan_api_key: String,
+        root: &Path,
+        src: &Path,
+    ) -> eyre::Result<PathBuf> {
+        let client = Client::new(chain, etherscan_api_key)?;
+        let meta = client.contract_source_code(contract_address.parse()?).await?;
+        let metadata = meta.items.first().wrap_err("no source code found")?;
+        let remappings = vendor_etherscan_sources(metadata, root, src)?;
+
+        let remappings_txt = root.join("remappings.txt");
+        let mut contents = std::fs::read_to_string(&remappings_txt).unwrap_or_default();
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        for remapping in remappings {
+            contents.push_str(&format!("{remapping}\n"));
+        }
+        std::fs::write(&remappings_txt, contents)?;
+
+        Ok(src.join("vendor").join(&metadata.contract_name))
+    }
+
     /// Disassembles hex encoded bytecode into individual / human readable opcodes
     ///
     /// # Example
@@ -0,0 +1,217 @@
+//! Verification of `eth_getProof` account proofs against a state root.
+
+use crate::rlp_converter::Item;
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_rlp::Decodable;
+use eyre::{ensure, Result};
+
+/// The root hash of an empty trie, i.e. `keccak256(rlp(""))`
+pub const EMPTY_ROOT_HASH: B256 =
+    alloy_primitives::b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+
+/// An account, as stored in the state trie
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrieAccount {
+    pub nonce: u64,
+    pub balance: U256,
+    pub storage_root: B256,
+    pub code_hash: B256,
+}
+
+impl TrieAccount {
+    /// Decodes the RLP encoded `[nonce, balance, storage_root, code_hash]` leaf value
+    fn decode(mut value: &[u8]) -> Result<Self> {
+        let Item::Array(fields) = Item::decode(&mut value)? else {
+            eyre::bail!("account is not an RLP list")
+        };
+        let fields = fields
+            .into_iter()
+            .map(|field| match field {
+                Item::Data(data) => Ok(data),
+                Item::Array(_) => eyre::bail!("account field is not an RLP string"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let [nonce, balance, storage_root, code_hash] = fields.as_slice() else {
+            eyre::bail!("account has {} fields, expected 4", fields.len())
+        };
+        ensure!(nonce.len() <= 8 && balance.len() <= 32, "invalid account nonce or balance");
+        ensure!(storage_root.len() == 32 && code_hash.len() == 32, "invalid account hashes");
+
+        Ok(Self {
+            nonce: U256::from_be_slice(nonce).to(),
+            balance: U256::from_be_slice(balance),
+            storage_root: B256::from_slice(storage_root),
+            code_hash: B256::from_slice(code_hash),
+        })
+    }
+}
+
+/// Verifies the account proof of `address` against the given state root.
+///
+/// Returns the proven account, or `None` if the proof shows that the account does not exist.
+pub fn verify_account_proof(
+    state_root: B256,
+    address: Address,
+    proof: &[impl AsRef<[u8]>],
+) -> Result<Option<TrieAccount>> {
+    let key = keccak256(address);
+    let value = verify_proof(state_root, key, proof)?;
+    value.map(|value| TrieAccount::decode(&value)).transpose()
+}
+
+/// A reference to a child node, either by hash or the inlined node itself if shorter than 32 bytes
+enum NodeRef {
+    Hash(B256),
+    Inline(Item),
+}
+
+impl NodeRef {
+    /// Returns `None` for an empty reference, i.e. the child does not exist
+    fn new(item: Item) -> Result<Option<Self>> {
+        match item {
+            Item::Data(data) if data.is_empty() => Ok(None),
+            Item::Data(data) if data.len() == 32 => {
+                Ok(Some(NodeRef::Hash(B256::from_slice(&data))))
+            }
+            Item::Data(_) => eyre::bail!("invalid node reference"),
+            node => Ok(Some(NodeRef::Inline(node))),
+        }
+    }
+}
+
+/// Walks the Merkle-Patricia proof for `key` from `root`, checking the hash of every node.
+///
+/// Returns the value stored at `key`, or `None` if the proof shows its absence.
+fn verify_proof(root: B256, key: B256, proof: &[impl AsRef<[u8]>]) -> Result<Option<Vec<u8>>> {
+    let path = to_nibbles(key.as_slice());
+    let mut proof = proof.iter();
+    let mut next = NodeRef::Hash(root);
+    let mut pos = 0;
+
+    loop {
+        let node = match next {
+            NodeRef::Hash(hash) => {
+                let Some(node) = proof.next() else { eyre::bail!("proof is incomplete") };
+                let node = node.as_ref();
+                ensure!(keccak256(node) == hash, "proof node does not match its hash {hash}");
+                Item::decode(&mut &node[..])?
+            }
+            NodeRef::Inline(node) => node,
+        };
+        let Item::Array(mut items) = node else { eyre::bail!("proof node is not an RLP list") };
+
+        match items.len() {
+            // branch node
+            17 => {
+                if pos == path.len() {
+                    return match items.pop() {
+                        Some(Item::Data(value)) if !value.is_empty() => Ok(Some(value)),
+                        _ => Ok(None),
+                    }
+                }
+                match NodeRef::new(items.swap_remove(path[pos] as usize))? {
+                    Some(child) => next = child,
+                    None => return Ok(None),
+                }
+                pos += 1;
+            }
+            // extension or leaf node
+            2 => {
+                let (Item::Data(encoded_path), child) = (items.remove(0), items.remove(0)) else {
+                    eyre::bail!("invalid node path")
+                };
+                let (node_path, is_leaf) = decode_path(&encoded_path)?;
+                let rest = &path[pos..];
+                if is_leaf {
+                    return match child {
+                        Item::Data(value) if rest == node_path.as_slice() => Ok(Some(value)),
+                        Item::Data(_) => Ok(None),
+                        Item::Array(_) => eyre::bail!("leaf value is not an RLP string"),
+                    }
+                }
+                if !rest.starts_with(&node_path) {
+                    return Ok(None)
+                }
+                pos += node_path.len();
+                next = NodeRef::new(child)?.ok_or_else(|| eyre::eyre!("empty extension node"))?;
+            }
+            n => eyre::bail!("invalid proof node with {n} items"),
+        }
+    }
+}
+
+/// Decodes a hex-prefix encoded path, returning its nibbles and whether it belongs to a leaf
+fn decode_path(encoded: &[u8]) -> Result<(Vec<u8>, bool)> {
+    let Some((&first, rest)) = encoded.split_first() else { eyre::bail!("empty node path") };
+    let flag = first >> 4;
+    ensure!(flag < 4, "invalid node path prefix {flag}");
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    // odd length paths store their first nibble in the prefix byte
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(rest));
+    Ok((nibbles, flag & 2 == 2))
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(item: &Item) -> Vec<u8> {
+        alloy_rlp::encode(item)
+    }
+
+    /// Returns a proof of a trie that only contains the given account
+    fn single_account_trie(address: Address, account: &Item) -> (B256, Vec<Vec<u8>>) {
+        let mut path = vec![0x20];
+        path.extend_from_slice(keccak256(address).as_slice());
+        let leaf = encode(&Item::Array(vec![Item::Data(path), Item::Data(encode(account))]));
+        (keccak256(&leaf), vec![leaf])
+    }
+
+    #[test]
+    fn verifies_account_proof() {
+        let address = Address::repeat_byte(0x11);
+        let account = Item::Array(vec![
+            Item::Data(vec![0x05]),
+            Item::Data(vec![0x01, 0x00]),
+            Item::Data(vec![0x22; 32]),
+            Item::Data(vec![0x33; 32]),
+        ]);
+        let (root, proof) = single_account_trie(address, &account);
+
+        let proven = verify_account_proof(root, address, &proof).unwrap().unwrap();
+        assert_eq!(
+            proven,
+            TrieAccount {
+                nonce: 5,
+                balance: U256::from(256),
+                storage_root: B256::repeat_byte(0x22),
+                code_hash: B256::repeat_byte(0x33),
+            }
+        );
+
+        // a different account is proven to be absent
+        let other = Address::repeat_byte(0x12);
+        assert_eq!(verify_account_proof(root, other, &proof).unwrap(), None);
+
+        // tampered proofs are rejected
+        assert!(verify_account_proof(B256::ZERO, address, &proof).is_err());
+        assert!(verify_account_proof(root, address, &Vec::<Vec<u8>>::new()).is_err());
+    }
+
+    #[test]
+    fn decodes_hex_prefix_paths() {
+        assert_eq!(decode_path(&[0x00, 0x12]).unwrap(), (vec![1, 2], false));
+        assert_eq!(decode_path(&[0x11, 0x23]).unwrap(), (vec![1, 2, 3], false));
+        assert_eq!(decode_path(&[0x20, 0x12]).unwrap(), (vec![1, 2], true));
+        assert_eq!(decode_path(&[0x3f]).unwrap(), (vec![0x0f], true));
+        assert!(decode_path(&[0x40]).is_err());
+    }
+}
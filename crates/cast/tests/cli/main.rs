@@ -44,6 +44,47 @@ casttest!(finds_block, |_prj, cmd| {
     assert!(output.contains("14428082"), "{}", output);
 });
 
+// tests that `cast status` reports the status of mainnet
+casttest!(chain_status, |_prj, cmd| {
+    let eth_rpc_url = next_http_rpc_endpoint();
+
+    cmd.args(["status", "--rpc-url", eth_rpc_url.as_str()]);
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("chain id            1\n"), "{output}");
+    assert!(output.contains("base fee"), "{output}");
+
+    cmd.cast_fuse().args(["status", "--json", "--rpc-url", eth_rpc_url.as_str()]);
+    let status: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    assert_eq!(status["chainId"], 1);
+    assert!(status["blobBaseFee"].is_string(), "{status}");
+});
+
+// tests that `cast trace-filter` only reports calls to the given address
+casttest!(trace_filter_calls_to_address, |_prj, cmd| {
+    let eth_rpc_url = next_http_rpc_endpoint();
+    let weth = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+
+    cmd.args([
+        "trace-filter",
+        "--from-block",
+        "16000000",
+        "--to-block",
+        "16000000",
+        "--to",
+        weth,
+        "--offline",
+        "--json",
+        "--rpc-url",
+        eth_rpc_url.as_str(),
+    ]);
+    let calls: Vec<serde_json::Value> = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    assert!(!calls.is_empty());
+    for call in calls {
+        assert_eq!(call["to"], weth, "{call}");
+        assert_eq!(call["blockNumber"], 16000000, "{call}");
+    }
+});
+
 // tests that we can create a new wallet with keystore
 casttest!(new_wallet_keystore_with_password, |_prj, cmd| {
     cmd.args(["wallet", "new", ".", "--unsafe-password", "test"]);
@@ -71,6 +112,92 @@ casttest!(wallet_address_keystore_with_password_file, |_prj, cmd| {
     assert!(out.contains("0xeC554aeAFE75601AaAb43Bd4621A22284dB566C2"));
 });
 
+// tests that `cast wallet list` lists the signers of the wallet options with their sources
+casttest!(wallet_list_signers, |_prj, cmd| {
+    let keystore_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keystore");
+    let keystore = keystore_dir
+        .join("UTC--2022-12-20T10-30-43.591916000Z--ec554aeafe75601aaab43bd4621a22284db566c2");
+
+    cmd.args([
+        "wallet",
+        "list",
+        "--keystore",
+        keystore.to_str().unwrap(),
+        "--private-keys",
+        "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "--yes",
+    ]);
+    let out = cmd.stdout_lossy();
+    assert!(out.lines().any(|line| line == "0xeC554aeAFE75601AaAb43Bd4621A22284dB566C2"));
+    assert!(out.lines().any(|line| line == "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"));
+
+    cmd.arg("--long");
+    let out = cmd.stdout_lossy();
+    assert!(out.contains(&format!(
+        "0xeC554aeAFE75601AaAb43Bd4621A22284dB566C2 (keystore {})",
+        keystore.display()
+    )));
+    assert!(out.contains("0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf (private key)"));
+});
+
+// tests that keystore accounts can be renamed, re-encrypted and exported
+casttest!(wallet_keystore_maintenance, |prj, cmd| {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join(
+        "tests/fixtures/keystore/UTC--2022-12-20T10-30-43.591916000Z--ec554aeafe75601aaab43bd4621a22284db566c2",
+    );
+    let dir = prj.root().join("keystores");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(fixture, dir.join("alice")).unwrap();
+    let dir = dir.to_str().unwrap();
+
+    cmd.args(["wallet", "rename", "alice", "bob", "--keystore-dir", dir]);
+    assert!(cmd.stdout_lossy().contains("`alice` was renamed to `bob`"));
+
+    cmd.cast_fuse().args([
+        "wallet",
+        "change-password",
+        "bob",
+        "--keystore-dir",
+        dir,
+        "--unsafe-password",
+        "keystorepassword",
+        "--unsafe-new-password",
+        "newpassword",
+    ]);
+    assert!(cmd.stdout_lossy().contains("The password of `bob` was changed successfully"));
+
+    // the export must be confirmed twice
+    cmd.cast_fuse()
+        .args([
+            "wallet",
+            "export",
+            "bob",
+            "--keystore-dir",
+            dir,
+            "--unsafe-password",
+            "newpassword",
+        ])
+        .stdin(|mut stdin| stdin.write_all(b"y\nalice\n").unwrap());
+    cmd.assert_err();
+
+    cmd.cast_fuse()
+        .args([
+            "wallet",
+            "export",
+            "bob",
+            "--keystore-dir",
+            dir,
+            "--unsafe-password",
+            "newpassword",
+        ])
+        .stdin(|mut stdin| stdin.write_all(b"y\nbob\n").unwrap());
+    let out = cmd.stdout_lossy();
+    let private_key = out.trim().lines().last().unwrap();
+
+    cmd.cast_fuse().args(["wallet", "address", private_key]);
+    assert_eq!(cmd.stdout_lossy().trim(), "0xeC554aeAFE75601AaAb43Bd4621A22284dB566C2");
+});
+
 // tests that `cast wallet sign message` outputs the expected signature
 casttest!(wallet_sign_message_utf8_data, |_prj, cmd| {
     cmd.args([
@@ -97,6 +224,24 @@ casttest!(wallet_sign_message_hex_data, |_prj, cmd| {
     assert_eq!(output.trim(), "0x23a42ca5616ee730ff3735890c32fc7b9491a9f633faca9434797f2c845f5abf4d9ba23bd7edb8577acebaa3644dc5a4995296db420522bb40060f1693c33c9b1c");
 });
 
+// tests that signing the EIP-191 hash of a message with `--no-hash` matches signing the message
+casttest!(wallet_sign_hash_message, |_prj, cmd| {
+    cmd.args(["hash-message", "test"]);
+    let hash = cmd.stdout_lossy();
+    assert_eq!(hash.trim(), "0x4a5c5d454721bbbb25540c3317521e71c373ae36458f960d2ad46ef088110e95");
+
+    cmd.cast_fuse().args([
+        "wallet",
+        "sign",
+        "--private-key",
+        "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "--no-hash",
+        hash.trim(),
+    ]);
+    let output = cmd.stdout_lossy();
+    assert_eq!(output.trim(), "0xfe28833983d6faa0715c7e8c3873c725ddab6fa5bf84d40e780676e463e6bea20fc6aea97dc273a98eb26b0914e224c8dd5c615ceaab69ddddcf9b0ae3de0e371c");
+});
+
 // tests that `cast wallet sign typed-data` outputs the expected signature, given a JSON string
 casttest!(wallet_sign_typed_data_string, |_prj, cmd| {
     cmd.args([
@@ -359,6 +504,29 @@ casttest!(to_base, |_prj, cmd| {
     }
 });
 
+// tests that `cast parse-units` and `cast format-units` convert exactly in both directions
+casttest!(parse_and_format_units, |_prj, cmd| {
+    cmd.args(["parse-units", "-1.5e-3", "ether"]);
+    assert_eq!(cmd.stdout_lossy().trim(), "-1500000000000000");
+
+    cmd.cast_fuse().args(["format-units", "-1500000000000000", "ether"]);
+    assert_eq!(cmd.stdout_lossy().trim(), "-0.0015");
+
+    cmd.cast_fuse().args([
+        "format-units",
+        "1234567891",
+        "3",
+        "--precision",
+        "2",
+        "--separator",
+        ",",
+    ]);
+    assert_eq!(cmd.stdout_lossy().trim(), "1,234,567.89");
+
+    cmd.cast_fuse().args(["parse-units", "0.1234567", "6"]);
+    cmd.assert_err();
+});
+
 // tests that revert reason is only present if transaction has reverted.
 casttest!(receipt_revert_reason, |_prj, cmd| {
     let rpc = next_http_rpc_endpoint();
@@ -556,3 +724,357 @@ casttest!(storage, |_prj, cmd| {
     cmd.cast_fuse().args(["storage", usdt, decimals_slot, "--rpc-url", &rpc]);
     assert_eq!(cmd.stdout_lossy().trim(), six);
 });
+
+casttest!(storage_compare_slot, |prj, cmd| {
+    let rpc = next_http_rpc_endpoint();
+    let usdt = "0xdac17f958d2ee523a2206206994597c13d831ec7";
+    let decimals_slot = "0x09";
+    let others = prj.root().join("addresses.txt");
+    std::fs::write(&others, "# no code\nvitalik.eth\n").unwrap();
+    cmd.cast_fuse()
+        .args(["storage", usdt, decimals_slot, "--compare-file"])
+        .arg(&others)
+        .args(["--rpc-url", &rpc]);
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("| 0xdAC17F958D2ee523a2206206994597C13D831ec7 | 0x0000000000000000000000000000000000000000000000000000000000000006 |"), "{output}");
+    assert!(output.contains("| vitalik.eth "), "{output}");
+    assert!(output.contains("| *"), "{output}");
+});
+
+casttest!(codehash_and_storage_root, |_prj, cmd| {
+    let rpc = next_http_rpc_endpoint();
+    cmd.cast_fuse().args(["codehash", "vitalik.eth", "--rpc-url", &rpc]);
+    assert_eq!(
+        cmd.stdout_lossy().trim(),
+        "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+
+    let rpc = next_http_rpc_endpoint();
+    cmd.cast_fuse().args(["storage-root", "vitalik.eth", "--rpc-url", &rpc]);
+    assert_eq!(
+        cmd.stdout_lossy().trim(),
+        "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+    );
+});
+
+casttest!(decode_transaction_input_nested_calls, |_prj, cmd| {
+    // aggregate3([(0x2222.., false, execute(0x1111.., 1, 0xabababab))])
+    cmd.args([
+        "decode-transaction-input",
+        "--offline",
+        "0x82ad56cb00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000002000000000000000000000000022222222222222222222222222222222222222220000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000006000000000000000000000000000000000000000000000000000000000000000a4b61d27f60000000000000000000000001111111111111111111111111111111111111111000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000600000000000000000000000000000000000000000000000000000000000000004abababab0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    ]);
+    let output = cmd.stdout_lossy();
+    assert!(output.starts_with("aggregate3((address,bool,bytes)[])"), "{output}");
+    assert!(
+        output.contains(
+            "└─ [0] 0x2222222222222222222222222222222222222222::execute(address,uint256,bytes)"
+        ),
+        "{output}"
+    );
+    assert!(
+        output.contains(
+            "   └─ [0] 0x1111111111111111111111111111111111111111::0xabababab (unknown) {value: 1}"
+        ),
+        "{output}"
+    );
+});
+
+casttest!(call_batch, |prj, cmd| {
+    let rpc = next_http_rpc_endpoint();
+    let weth = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+    let calls = prj.root().join("calls.csv");
+    std::fs::write(
+        &calls,
+        format!(
+            "to,sig,args\n{weth},name()(string)\n{weth},decimals()(uint8)\n{weth},foo()(uint256)\n"
+        ),
+    )
+    .unwrap();
+
+    for multicall in [false, true] {
+        cmd.cast_fuse().args([
+            "call",
+            "--batch",
+            calls.to_str().unwrap(),
+            "--concurrency",
+            "2",
+            "--rpc-url",
+            rpc.as_str(),
+        ]);
+        if multicall {
+            cmd.arg("--multicall");
+        }
+
+        let output = cmd.stdout_lossy();
+        let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(results[0]["result"], "\"Wrapped Ether\"");
+        assert_eq!(results[1]["result"], "18");
+        assert_eq!(results[2]["success"], false);
+    }
+});
+
+// tests that repeated `--call` flags are aggregated, and that failing calls don't fail the others
+casttest!(call_repeated, |_prj, cmd| {
+    let rpc = next_http_rpc_endpoint();
+    let weth = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+    cmd.args([
+        "call",
+        "--call",
+        &format!("{weth},name()(string)"),
+        "--call",
+        &format!("{weth},balanceOf(address)(uint256),0x0000000000000000000000000000000000000000"),
+        "--call",
+        &format!("{weth},foo()(uint256)"),
+        "--rpc-url",
+        rpc.as_str(),
+    ]);
+
+    let output = cmd.stdout_lossy();
+    let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(results[0]["result"], "\"Wrapped Ether\"");
+    assert_eq!(results[1]["success"], true);
+    assert_eq!(results[2]["success"], false);
+});
+
+// tests that `cast call --json --full` prints the raw and decoded return data, and reverts
+casttest!(call_json_full, |_prj, cmd| {
+    let rpc = next_http_rpc_endpoint();
+    let weth = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+    cmd.args(["call", weth, "name()(string)", "--json", "--full", "--rpc-url", rpc.as_str()]);
+    let output = cmd.stdout_lossy();
+    let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(result["success"], true);
+    assert!(result["returnData"].as_str().unwrap().starts_with("0x"));
+    assert_eq!(result["decoded"][0]["type"], "string");
+    assert_eq!(result["decoded"][0]["value"], "\"Wrapped Ether\"");
+    assert!(result["gasEstimate"].as_u64().unwrap() > 0);
+
+    // transferring more than the balance of the zero address reverts
+    cmd.cast_fuse().args([
+        "call",
+        weth,
+        "transfer(address,uint256)(bool)",
+        weth,
+        "1000000000ether",
+        "--json",
+        "--full",
+        "--rpc-url",
+        rpc.as_str(),
+    ]);
+    let output = cmd.stdout_lossy();
+    let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(result["success"], false);
+    assert!(result.get("error").is_some());
+});
+
+// tests that `cast erc20` reads token metadata and encodes transfers
+casttest!(erc20_info_and_transfer, |_prj, cmd| {
+    let eth_rpc_url = next_http_rpc_endpoint();
+
+    // <https://etherscan.io/token/0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48>
+    let usdc = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+    cmd.args(["erc20", "info", usdc, "--rpc-url", eth_rpc_url.as_str()]);
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("symbol: USDC"));
+    assert!(output.contains("decimals: 6"));
+
+    cmd.cast_fuse().args([
+        "erc20",
+        "transfer",
+        usdc,
+        "0x0000000000000000000000000000000000000001",
+        "100",
+        "--raw",
+    ]);
+    assert_eq!(
+        cmd.stdout_lossy().trim(),
+        "0xa9059cbb00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000064"
+    );
+
+    // the sender is required to encode ERC-721 transfers
+    cmd.cast_fuse().args([
+        "erc721",
+        "transfer",
+        usdc,
+        "0x0000000000000000000000000000000000000001",
+        "1",
+    ]);
+    cmd.assert_err();
+});
+
+casttest!(ens_hashes_and_records, |_prj, cmd| {
+    cmd.args(["ens", "namehash", "eth"]);
+    assert_eq!(
+        cmd.stdout_lossy().trim(),
+        "0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+    );
+
+    cmd.cast_fuse().args(["ens", "labelhash", "vitalik"]);
+    assert_eq!(
+        cmd.stdout_lossy().trim(),
+        "0xaf2caa1c2ca1d027f1ac823b529d0a67cd144264b2789fa2ea4d63a67c7103cc"
+    );
+
+    let eth_rpc_url = next_http_rpc_endpoint();
+    let vitalik = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+    cmd.cast_fuse().args(["ens", "address", "vitalik.eth", "--rpc-url", eth_rpc_url.as_str()]);
+    assert_eq!(cmd.stdout_lossy().trim(), vitalik);
+
+    cmd.cast_fuse().args(["ens", "reverse", vitalik, "--rpc-url", eth_rpc_url.as_str()]);
+    assert_eq!(cmd.stdout_lossy().trim(), "vitalik.eth");
+
+    cmd.cast_fuse().args([
+        "ens",
+        "resolver",
+        "foundry-unregistered-7f3a9c2e.eth",
+        "--rpc-url",
+        eth_rpc_url.as_str(),
+    ]);
+    cmd.assert_err();
+});
+
+casttest!(validates_address_checksums, |_prj, cmd| {
+    // the last character should be lowercase
+    let invalid = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+    let create2 = |deployer: &str| {
+        vec![
+            "create2".to_string(),
+            "--starts-with".to_string(),
+            "0".to_string(),
+            "--init-code-hash".to_string(),
+            format!("0x{}", "00".repeat(32)),
+            "--deployer".to_string(),
+            deployer.to_string(),
+        ]
+    };
+    cmd.args(create2(invalid));
+    let err = cmd.stderr_lossy();
+    assert!(err.contains("did you mean `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`"), "{err}");
+
+    cmd.cast_fuse().args(create2(invalid)).arg("--no-checksum-validate");
+    assert!(cmd.stdout_lossy().contains("Address: "));
+
+    // only address arguments are validated, other values of the same shape are left alone
+    cmd.cast_fuse().args(["abi-encode", "f(bytes20)", invalid]);
+    assert_eq!(
+        cmd.stdout_lossy().trim(),
+        "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed000000000000000000000000"
+    );
+
+    cmd.cast_fuse().args(["to-check-sum-address", invalid]);
+    assert_eq!(cmd.stdout_lossy().trim(), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+
+    cmd.cast_fuse().args(["to-check-sum-address", invalid, "--chain", "30"]);
+    assert_eq!(cmd.stdout_lossy().trim(), "0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD");
+});
+
+// tests that `cast bytecode-diff` matches identical deployments and fails on different ones
+casttest!(bytecode_diff, |_prj, cmd| {
+    let eth_rpc_url = next_http_rpc_endpoint();
+    let weth = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+    cmd.args(["bytecode-diff", weth, weth, "--rpc-url", eth_rpc_url.as_str()]);
+    assert!(cmd.stdout_lossy().contains("The bytecodes are identical"));
+
+    let usdc = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+    cmd.cast_fuse().args(["bytecode-diff", weth, usdc, "--rpc-url", eth_rpc_url.as_str()]);
+    cmd.assert_err();
+});
+
+// tests that `cast prettify-abi` converts between JSON and human-readable ABIs
+casttest!(prettify_abi_roundtrip, |_prj, cmd| {
+    let human_readable = "struct Order { address maker; uint256 amount; }\n\
+                          function fill(Order order) payable returns (bool)";
+    cmd.args(["prettify-abi", human_readable, "--compact"]);
+    let json = cmd.stdout_lossy();
+    assert!(json.contains(r#""internalType":"struct Order""#), "{json}");
+
+    cmd.cast_fuse().args(["prettify-abi", json.trim()]);
+    assert_eq!(cmd.stdout_lossy().trim(), human_readable);
+});
+
+// tests that `cast abi-diff` reports changes and fails on breaking ones
+casttest!(abi_diff, |prj, cmd| {
+    let old = prj.root().join("old.json");
+    std::fs::write(
+        &old,
+        r#"[{"type":"function","name":"burn","inputs":[{"name":"amount","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"}]"#,
+    )
+    .unwrap();
+    // an artifact containing the ABI
+    let new = prj.root().join("new.json");
+    std::fs::write(
+        &new,
+        r#"{"abi":[{"type":"function","name":"burn","inputs":[{"name":"amount","type":"uint256"}],"outputs":[],"stateMutability":"payable"},{"type":"function","name":"pause","inputs":[],"outputs":[],"stateMutability":"nonpayable"}]}"#,
+    )
+    .unwrap();
+
+    cmd.args(["abi-diff", old.to_str().unwrap(), new.to_str().unwrap()]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("~ function burn(uint256): nonpayable -> payable"), "{stdout}");
+    assert!(stdout.contains("+ function pause()"), "{stdout}");
+
+    cmd.cast_fuse().args(["abi-diff", new.to_str().unwrap(), old.to_str().unwrap(), "--json"]);
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains(r#""kind": "removed""#), "{stdout}");
+    cmd.assert_err();
+});
+
+// tests that `cast constructor-encode` encodes the arguments checked against an artifact
+casttest!(constructor_encode, |prj, cmd| {
+    let artifact = prj.root().join("Token.json");
+    std::fs::write(
+        &artifact,
+        r#"{"abi":[{"type":"constructor","inputs":[{"name":"owner","type":"address","internalType":"address"},{"name":"decimals","type":"uint8","internalType":"uint8"}],"stateMutability":"nonpayable"}],"bytecode":{"object":"0x6080"}}"#,
+    )
+    .unwrap();
+    let owner = "0x1111111111111111111111111111111111111111";
+    let args = "0x00000000000000000000000011111111111111111111111111111111111111110000000000000000000000000000000000000000000000000000000000000012";
+
+    cmd.args(["constructor-encode", artifact.to_str().unwrap(), owner, "18", "--args-only"]);
+    assert_eq!(cmd.stdout_lossy().trim(), args);
+
+    cmd.cast_fuse().args([
+        "constructor-encode",
+        artifact.to_str().unwrap(),
+        owner,
+        "18",
+        "--bytecode-only",
+    ]);
+    assert_eq!(cmd.stdout_lossy().trim(), format!("0x6080{}", &args[2..]));
+
+    cmd.cast_fuse().args(["constructor-encode", artifact.to_str().unwrap(), owner, "256"]);
+    cmd.assert_err();
+});
+
+// tests that `cast calldata-decode --file` decodes every calldata of the file as NDJSON
+casttest!(calldata_decode_file, |prj, cmd| {
+    let calls = prj.root().join("calls.txt");
+    std::fs::write(
+        &calls,
+        "0xa9059cbb000000000000000000000000111111111111111111111111111111111111111100000000000000000000000000000000000000000000000000000000000003e8\n0xdeadbeef\n",
+    )
+    .unwrap();
+
+    cmd.args(["calldata-decode", "transfer(address,uint256)", "--file"]).arg(&calls);
+    let stdout = cmd.stdout_lossy();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2, "{stdout}");
+    assert!(lines[0].contains(r#""args":["0x1111111111111111111111111111111111111111","1000"]"#));
+    assert!(lines[1].contains(r#""index":1"#), "{stdout}");
+    assert!(lines[1].contains(r#""error":"no function found for selector 0xdeadbeef""#));
+});
+
+casttest!(sig_collision_wordlist, |prj, cmd| {
+    let words = prj.root().join("words.txt");
+    std::fs::write(&words, "approve\nfrom\ntransfer\n").unwrap();
+
+    cmd.args(["sig-collision", "0x23b872dd", "--params", "address,address,uint256", "--wordlist"])
+        .arg(&words);
+    assert_eq!(cmd.stdout_lossy().trim(), "transferFrom(address,address,uint256)");
+
+    cmd.cast_fuse()
+        .args(["sig-collision", "transferFrom(address,address,uint256)", "--wordlist"])
+        .arg(&words);
+    cmd.assert_err();
+});
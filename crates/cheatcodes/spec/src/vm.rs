@@ -383,6 +383,23 @@ interface Vm {
     function mockCallRevert(address callee, uint256 msgValue, bytes calldata data, bytes calldata revertData)
         external;
 
+    /// Mocks a call to an address, returning specified data, if the calldata matches `data` in the bits set in `mask`.
+    /// Bytes of `data` past the end of `mask` must match exactly, so e.g. a mask that clears the bytes of an argument
+    /// mocks the call for any value of that argument.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function mockCallMasked(address callee, bytes calldata data, bytes calldata mask, bytes calldata returnData)
+        external;
+
+    /// Mocks a call to an address from a specific `msg.sender`, returning specified data.
+    /// Takes precedence over mocks of the same calldata for any sender.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function mockCallFrom(address callee, address caller, bytes calldata data, bytes calldata returnData) external;
+
+    /// Reverts a call to an address from a specific `msg.sender`, with specified revert data.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function mockCallRevertFrom(address callee, address caller, bytes calldata data, bytes calldata revertData)
+        external;
+
     // --- Impersonation (pranks) ---
 
     /// Sets the *next* call's `msg.sender` to be the input address.
@@ -595,6 +612,44 @@ interface Vm {
     #[cheatcode(group = Testing, safety = Safe)]
     function sleep(uint256 duration) external;
 
+    /// Asserts that two `bool` arrays are equal, reverting with the differing elements otherwise.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEq(bool[] calldata left, bool[] calldata right) external pure;
+
+    /// Asserts that two `uint256` arrays are equal, reverting with the differing elements otherwise.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEq(uint256[] calldata left, uint256[] calldata right) external pure;
+
+    /// Asserts that two `int256` arrays are equal, reverting with the differing elements otherwise.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEq(int256[] calldata left, int256[] calldata right) external pure;
+
+    /// Asserts that two `address` arrays are equal, reverting with the differing elements otherwise.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEq(address[] calldata left, address[] calldata right) external pure;
+
+    /// Asserts that two `bytes32` arrays are equal, reverting with the differing elements otherwise.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEq(bytes32[] calldata left, bytes32[] calldata right) external pure;
+
+    /// Asserts that two `string` arrays are equal, reverting with the differing elements otherwise.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEq(string[] calldata left, string[] calldata right) external pure;
+
+    /// Asserts that two `bytes` arrays are equal, reverting with the differing elements otherwise.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEq(bytes[] calldata left, bytes[] calldata right) external pure;
+
+    /// Asserts that two `bytes` are equal, reverting with both values otherwise.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEq(bytes calldata left, bytes calldata right) external pure;
+
+    /// Asserts that two ABI-encoded values of the given type are equal, e.g. `abi.encode(s)` of a struct `s` with
+    /// the type `(uint256,address[])` or the name of the struct, reverting with the differing struct fields, tuple
+    /// components and array elements otherwise. Struct definitions are looked up in the ABIs of the artifacts.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertEqDecoded(bytes calldata left, bytes calldata right, string calldata typeDescription) external pure;
+
     /// Expects a call to an address with the specified calldata.
     /// Calldata can either be a strict or a partial match.
     #[cheatcode(group = Testing, safety = Unsafe)]
@@ -620,6 +675,14 @@ interface Vm {
     #[cheatcode(group = Testing, safety = Unsafe)]
     function expectCall(address callee, uint256 msgValue, uint64 gas, bytes calldata data, uint64 count) external;
 
+    /// Expects at least the given number of calls to an address with the specified calldata.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectCallAtLeast(address callee, bytes calldata data, uint64 count) external;
+
+    /// Expects at least the given number of calls to an address with the specified `msg.value` and calldata.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectCallAtLeast(address callee, uint256 msgValue, bytes calldata data, uint64 count) external;
+
     /// Expect a call to an address with the specified `msg.value` and calldata, and a *minimum* amount of gas.
     #[cheatcode(group = Testing, safety = Unsafe)]
     function expectCallMinGas(address callee, uint256 msgValue, uint64 minGas, bytes calldata data) external;
@@ -650,6 +713,42 @@ interface Vm {
     #[cheatcode(group = Testing, safety = Unsafe)]
     function expectEmit(address emitter) external;
 
+    /// Expect a log with (bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData) to be emitted
+    /// exactly `count` times during the next call, in any order relative to other logs.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData, uint64 count) external;
+
+    /// Same as the previous method, but also checks supplied address against emitting contract.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData, address emitter, uint64 count)
+        external;
+
+    /// Expect a log with all topic and data checks enabled to be emitted exactly `count` times during the next call.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmit(uint64 count) external;
+
+    /// Same as the previous method, but also checks supplied address against emitting contract.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmit(address emitter, uint64 count) external;
+
+    /// Prepare an expected log that only matches the event signature, the first topic, of the emitted log.
+    /// No log needs to be emitted by the test; call this function, then call a function.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmitSignature(bytes32 topic0) external;
+
+    /// Same as the previous method, but also checks supplied address against emitting contract.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmitSignature(bytes32 topic0, address emitter) external;
+
+    /// Expect a log with the given event signature to be emitted by `emitter` exactly `count` times during the next call.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmitSignature(bytes32 topic0, address emitter, uint64 count) external;
+
+    /// Expect `emitter` to emit no logs during the next call other than the ones expected with `expectEmit`
+    /// or `expectEmitSignature`.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectNoUnexpectedEmits(address emitter) external;
+
     /// Expects an error on next call with any revert data.
     #[cheatcode(group = Testing, safety = Unsafe)]
     function expectRevert() external;
@@ -677,6 +776,15 @@ interface Vm {
     #[cheatcode(group = Testing, safety = Unsafe)]
     function skip(bool skipTest) external;
 
+    /// Marks a test as skipped with a reason. Must be called at the top of the test.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function skip(bool skipTest, string calldata reason) external;
+
+    /// Marks a test as expected to fail with a reason. Must be called at the top of the test.
+    /// The test is reported as an expected failure if it fails, and fails if it passes.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function xfail(string calldata reason) external;
+
     // ======== OS and Filesystem ========
 
     // -------- Metadata --------
@@ -1001,6 +1109,17 @@ interface Vm {
     #[cheatcode(group = Scripting)]
     function stopBroadcast() external;
 
+    // -------- Deployments --------
+
+    /// Gets the address of the latest deployment of the given contract on the current chain, as
+    /// recorded by `forge script --broadcast` in `deployments/<chainId>/<contractName>.json`.
+    #[cheatcode(group = Scripting)]
+    function getDeployment(string calldata contractName) external view returns (address deployedAddress);
+
+    /// Gets the address of the latest deployment of the given contract on the given chain.
+    #[cheatcode(group = Scripting)]
+    function getDeployment(string calldata contractName, uint64 chainId) external view returns (address deployedAddress);
+
     // ======== Utilities ========
 
     // -------- Strings --------
@@ -1,5 +1,6 @@
 use super::Result;
 use crate::Vm::Rpc;
+use alloy_primitives::Address;
 use foundry_common::fs::normalize_path;
 use foundry_compilers::{utils::canonicalize, ProjectPathsConfig};
 use foundry_config::{
@@ -7,7 +8,10 @@ use foundry_config::{
     ResolvedRpcEndpoints,
 };
 use foundry_evm_core::opts::EvmOpts;
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 /// Additional, configurable context the `Cheatcodes` inspector has access to
 ///
@@ -30,6 +34,12 @@ pub struct CheatsConfig {
     pub allowed_paths: Vec<PathBuf>,
     /// How the evm was configured by the user
     pub evm_opts: EvmOpts,
+    /// Deployments simulated by the earlier scripts of a chained `forge script` run, by chain id
+    /// and contract name, which take precedence over the deployment registry
+    pub simulated_deployments: BTreeMap<(u64, String), Address>,
+    /// Whether broadcast `CREATE2` deployments of contracts that already exist resolve to the
+    /// existing contracts instead of being broadcast again, see `forge script --skip-deployed`
+    pub skip_deployed: bool,
 }
 
 impl CheatsConfig {
@@ -51,6 +61,8 @@ impl CheatsConfig {
             root: config.__root.0.clone(),
             allowed_paths,
             evm_opts,
+            simulated_deployments: Default::default(),
+            skip_deployed: false,
         }
     }
 
@@ -164,6 +176,8 @@ impl Default for CheatsConfig {
             root: Default::default(),
             allowed_paths: vec![],
             evm_opts: Default::default(),
+            simulated_deployments: Default::default(),
+            skip_deployed: false,
         }
     }
 }
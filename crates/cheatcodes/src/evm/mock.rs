@@ -10,6 +10,29 @@ pub struct MockCallDataContext {
     pub calldata: Bytes,
     /// The value to match for mock
     pub value: Option<U256>,
+    /// The `msg.sender` to match for mock
+    pub caller: Option<Address>,
+    /// The bits of the calldata to match, bytes past the end of the mask must match exactly
+    pub mask: Option<Bytes>,
+}
+
+impl MockCallDataContext {
+    /// Returns whether a call with the given input, value and sender is mocked by this context.
+    pub fn matches(&self, input: &[u8], value: U256, caller: Address) -> bool {
+        let Some(input) = input.get(..self.calldata.len()) else { return false };
+        let calldata_matches = match &self.mask {
+            Some(mask) => {
+                self.calldata.iter().zip(input).enumerate().all(|(i, (expected, actual))| {
+                    let mask = mask.get(i).copied().unwrap_or(0xff);
+                    expected & mask == actual & mask
+                })
+            }
+            None => input == &self.calldata[..],
+        };
+        calldata_matches &&
+            self.value.map_or(true, |expected| expected == value) &&
+            self.caller.map_or(true, |expected| expected == caller)
+    }
 }
 
 /// Mocked return data.
@@ -34,7 +57,15 @@ impl Ord for MockCallDataContext {
         // a partial match to calldata that is more specific than
         // a match to a msg.value, then the more specific calldata takes
         // precedence.
-        self.calldata.cmp(&other.calldata).reverse().then(self.value.cmp(&other.value).reverse())
+        //
+        // Likewise, mocks for a specific sender take precedence over mocks for any sender, and
+        // exact matches over masked ones.
+        self.calldata
+            .cmp(&other.calldata)
+            .reverse()
+            .then(self.value.cmp(&other.value).reverse())
+            .then(self.caller.cmp(&other.caller).reverse())
+            .then(self.mask.cmp(&other.mask))
     }
 }
 
@@ -49,17 +80,9 @@ impl Cheatcode for clearMockedCallsCall {
 impl Cheatcode for mockCall_0Call {
     fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { callee, data, returnData } = self;
-        let (acc, _) = ccx.data.journaled_state.load_account(*callee, ccx.data.db)?;
-
-        // Etches a single byte onto the account if it is empty to circumvent the `extcodesize`
-        // check Solidity might perform.
-        let empty_bytecode = acc.info.code.as_ref().map_or(true, Bytecode::is_empty);
-        if empty_bytecode {
-            let code = Bytecode::new_raw(Bytes::from_static(&[0u8])).to_checked();
-            ccx.data.journaled_state.set_code(*callee, code);
-        }
-
-        mock_call(ccx.state, callee, data, None, returnData, InstructionResult::Return);
+        etch_if_empty(ccx, callee)?;
+        let ctx = context(data, None, None, None);
+        mock_call(ccx.state, callee, ctx, returnData, InstructionResult::Return);
         Ok(Default::default())
     }
 }
@@ -67,39 +90,104 @@ impl Cheatcode for mockCall_0Call {
 impl Cheatcode for mockCall_1Call {
     fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { callee, msgValue, data, returnData } = self;
-        ccx.data.journaled_state.load_account(*callee, ccx.data.db)?;
-        mock_call(ccx.state, callee, data, Some(msgValue), returnData, InstructionResult::Return);
+        etch_if_empty(ccx, callee)?;
+        let ctx = context(data, Some(msgValue), None, None);
+        mock_call(ccx.state, callee, ctx, returnData, InstructionResult::Return);
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for mockCallMaskedCall {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { callee, data, mask, returnData } = self;
+        ensure!(mask.len() <= data.len(), "the mask is longer than the calldata");
+        etch_if_empty(ccx, callee)?;
+        let ctx = context(data, None, None, Some(&mask[..]));
+        mock_call(ccx.state, callee, ctx, returnData, InstructionResult::Return);
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for mockCallFromCall {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { callee, caller, data, returnData } = self;
+        etch_if_empty(ccx, callee)?;
+        let ctx = context(data, None, Some(caller), None);
+        mock_call(ccx.state, callee, ctx, returnData, InstructionResult::Return);
         Ok(Default::default())
     }
 }
 
 impl Cheatcode for mockCallRevert_0Call {
-    fn apply(&self, state: &mut Cheatcodes) -> Result {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { callee, data, revertData } = self;
-        mock_call(state, callee, data, None, revertData, InstructionResult::Revert);
+        etch_if_empty(ccx, callee)?;
+        let ctx = context(data, None, None, None);
+        mock_call(ccx.state, callee, ctx, revertData, InstructionResult::Revert);
         Ok(Default::default())
     }
 }
 
 impl Cheatcode for mockCallRevert_1Call {
-    fn apply(&self, state: &mut Cheatcodes) -> Result {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { callee, msgValue, data, revertData } = self;
-        mock_call(state, callee, data, Some(msgValue), revertData, InstructionResult::Revert);
+        etch_if_empty(ccx, callee)?;
+        let ctx = context(data, Some(msgValue), None, None);
+        mock_call(ccx.state, callee, ctx, revertData, InstructionResult::Revert);
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for mockCallRevertFromCall {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { callee, caller, data, revertData } = self;
+        etch_if_empty(ccx, callee)?;
+        let ctx = context(data, None, Some(caller), None);
+        mock_call(ccx.state, callee, ctx, revertData, InstructionResult::Revert);
         Ok(Default::default())
     }
 }
 
+/// Etches a single byte onto the account if it is empty to circumvent the `extcodesize` check
+/// Solidity might perform.
+///
+/// Loading the account first makes sure that the code of forked accounts is fetched, so that it
+/// isn't overwritten.
+fn etch_if_empty<DB: DatabaseExt>(ccx: &mut CheatsCtxt<DB>, callee: &Address) -> Result<()> {
+    let (acc, _) = ccx.data.journaled_state.load_account(*callee, ccx.data.db)?;
+    let empty_bytecode = acc.info.code.as_ref().map_or(true, Bytecode::is_empty);
+    if empty_bytecode {
+        let code = Bytecode::new_raw(Bytes::from_static(&[0u8])).to_checked();
+        ccx.data.journaled_state.set_code(*callee, code);
+    }
+    Ok(())
+}
+
+fn context(
+    calldata: &[u8],
+    value: Option<&U256>,
+    caller: Option<&Address>,
+    mask: Option<&[u8]>,
+) -> MockCallDataContext {
+    MockCallDataContext {
+        calldata: Bytes::copy_from_slice(calldata),
+        value: value.copied(),
+        caller: caller.copied(),
+        mask: mask.map(Bytes::copy_from_slice),
+    }
+}
+
 #[allow(clippy::ptr_arg)] // Not public API, doesn't matter
 fn mock_call(
     state: &mut Cheatcodes,
     callee: &Address,
-    cdata: &Vec<u8>,
-    value: Option<&U256>,
+    ctx: MockCallDataContext,
     rdata: &Vec<u8>,
     ret_type: InstructionResult,
 ) {
-    state.mocked_calls.entry(*callee).or_default().insert(
-        MockCallDataContext { calldata: Bytes::copy_from_slice(cdata), value: value.copied() },
-        MockCallReturnData { ret_type, data: Bytes::copy_from_slice(rdata) },
-    );
+    state
+        .mocked_calls
+        .entry(*callee)
+        .or_default()
+        .insert(ctx, MockCallReturnData { ret_type, data: Bytes::copy_from_slice(rdata) });
 }
@@ -9,7 +9,8 @@ use crate::{
     },
     script::Broadcast,
     test::expect::{
-        self, ExpectedCallData, ExpectedCallTracker, ExpectedCallType, ExpectedEmit, ExpectedRevert,
+        self, CountedExpectedEmit, ExpectedCallData, ExpectedCallTracker, ExpectedCallType,
+        ExpectedEmit, ExpectedRevert, StrictEmitter,
     },
     CheatsConfig, CheatsCtxt, Error, Result, Vm,
 };
@@ -22,7 +23,7 @@ use ethers_signers::LocalWallet;
 use foundry_common::{evm::Breakpoints, types::ToEthers, RpcUrl};
 use foundry_evm_core::{
     backend::{DatabaseError, DatabaseExt, RevertDiagnostic},
-    constants::{CHEATCODE_ADDRESS, DEFAULT_CREATE2_DEPLOYER, HARDHAT_CONSOLE_ADDRESS, MAGIC_SKIP},
+    constants::{CHEATCODE_ADDRESS, DEFAULT_CREATE2_DEPLOYER, HARDHAT_CONSOLE_ADDRESS},
     utils::get_create_address,
 };
 use itertools::Itertools;
@@ -134,6 +135,12 @@ pub struct Cheatcodes {
     /// Whether the skip cheatcode was activated
     pub skip: bool,
 
+    /// The reason passed to the skip cheatcode, if any
+    pub skip_reason: Option<String>,
+
+    /// The reason passed to the xfail cheatcode, if the test is expected to fail
+    pub xfail: Option<String>,
+
     /// Prank information
     pub prank: Option<Prank>,
 
@@ -164,6 +171,10 @@ pub struct Cheatcodes {
     pub expected_calls: ExpectedCallTracker,
     /// Expected emits
     pub expected_emits: VecDeque<ExpectedEmit>,
+    /// Expected emits with an exact number of occurrences
+    pub expected_emit_counts: Vec<CountedExpectedEmit>,
+    /// Emitters that must not emit unexpected logs
+    pub strict_emitters: Vec<StrictEmitter>,
 
     /// Map of context depths to memory offset ranges that may be written to within the call depth.
     pub allowed_mem_writes: HashMap<u64, Vec<Range<u64>>>,
@@ -697,8 +708,10 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
     }
 
     fn log(&mut self, _: &mut EVMData<'_, DB>, address: &Address, topics: &[B256], data: &Bytes) {
-        if !self.expected_emits.is_empty() {
+        let expected = (!self.expected_emits.is_empty() || !self.expected_emit_counts.is_empty()) &&
             expect::handle_expect_emit(self, address, topics, data);
+        if !expected && !self.strict_emitters.is_empty() {
+            expect::handle_strict_emitters(self, address, topics, data);
         }
 
         // Stores this log if `recordLogs` has been called
@@ -754,21 +767,34 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
             }
         }
 
-        // Handle mocked calls
+        // Handle mocked calls, this includes delegatecalls since `call.contract` is the address
+        // of the executed code
         if let Some(mocks) = self.mocked_calls.get(&call.contract) {
+            // Mocks match the sender the call is made with after applying the prank, see below
+            let caller = match &self.prank {
+                Some(prank)
+                    if data.journaled_state.depth() == prank.depth &&
+                        call.context.caller == prank.prank_caller =>
+                {
+                    prank.new_caller
+                }
+                _ => call.context.caller,
+            };
             let ctx = MockCallDataContext {
                 calldata: call.input.clone(),
                 value: Some(call.transfer.value),
+                caller: Some(caller),
+                mask: None,
             };
-            if let Some(return_data) = mocks.get(&ctx).or_else(|| {
-                mocks
-                    .iter()
-                    .find(|(mock, _)| {
-                        call.input.get(..mock.calldata.len()) == Some(&mock.calldata[..]) &&
-                            mock.value.map_or(true, |value| value == call.transfer.value)
-                    })
-                    .map(|(_, v)| v)
-            }) {
+            let any_caller = MockCallDataContext { caller: None, ..ctx.clone() };
+            if let Some(return_data) =
+                mocks.get(&ctx).or_else(|| mocks.get(&any_caller)).or_else(|| {
+                    mocks
+                        .iter()
+                        .find(|(mock, _)| mock.matches(&call.input, call.transfer.value, caller))
+                        .map(|(_, v)| v)
+                })
+            {
                 return (return_data.ret_type, gas, return_data.data.clone())
             }
         }
@@ -927,7 +953,7 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
             return (
                 InstructionResult::Revert,
                 remaining_gas,
-                super::Error::from(MAGIC_SKIP).abi_encode().into(),
+                crate::test::skip_data(self.skip_reason.as_deref()).into(),
             )
         }
 
@@ -1034,11 +1060,11 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
             !call.is_static;
         if should_check_emits {
             // Not all emits were matched.
-            if self.expected_emits.iter().any(|expected| !expected.found) {
+            if let Some(expected) = self.expected_emits.iter().find(|expected| !expected.found) {
                 return (
                     InstructionResult::Revert,
                     remaining_gas,
-                    "log != expected log".abi_encode().into(),
+                    expected.error().abi_encode().into(),
                 )
             } else {
                 // All emits were found, we're good.
@@ -1048,6 +1074,14 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
             }
         }
 
+        // Check the emits that are expected a number of times, and the emitters that must not emit
+        // unexpected logs, during this call.
+        if !call.is_static {
+            if let Err(msg) = expect::check_emit_counts(self, Some(data.journaled_state.depth())) {
+                return (InstructionResult::Revert, remaining_gas, Error::encode(msg))
+            }
+        }
+
         // this will ensure we don't have false positives when trying to diagnose reverts in fork
         // mode
         let diag = self.fork_revert_diagnostic.take();
@@ -1144,6 +1178,12 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
                 };
                 return (InstructionResult::Revert, remaining_gas, Error::encode(msg))
             }
+
+            // Emits expected a number of times without a call afterwards are checked against the
+            // logs of the test itself.
+            if let Err(msg) = expect::check_emit_counts(self, None) {
+                return (InstructionResult::Revert, remaining_gas, Error::encode(msg))
+            }
         }
 
         (status, remaining_gas, retdata)
@@ -1183,6 +1223,21 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
                 data.env.tx.caller = broadcast.new_origin;
 
                 if data.journaled_state.depth() == broadcast.depth {
+                    // A CREATE2 deployment that already exists would collide, with
+                    // `--skip-deployed` it resolves to the existing contract and isn't broadcast
+                    if self.config.skip_deployed {
+                        match existing_create2_deployment(data, call) {
+                            Ok(Some(address)) => {
+                                debug!(target: "cheatcodes", %address, "skipped deployed create2");
+                                return (InstructionResult::Return, Some(address), gas, Bytes::new())
+                            }
+                            Ok(None) => {}
+                            Err(err) => {
+                                return (InstructionResult::Revert, None, gas, Error::encode(err))
+                            }
+                        }
+                    }
+
                     let (bytecode, to, nonce) = match process_create(
                         broadcast.new_origin,
                         call.init_code.clone(),
@@ -1434,6 +1489,17 @@ fn process_create<DB: DatabaseExt>(
     }
 }
 
+/// Returns the address of the contract a broadcast `CREATE2` deploys, if it already has code.
+fn existing_create2_deployment<DB: DatabaseExt>(
+    data: &mut EVMData<'_, DB>,
+    call: &CreateInputs,
+) -> Result<Option<Address>, DB::Error> {
+    let CreateScheme::Create2 { salt } = call.scheme else { return Ok(None) };
+    let address = DEFAULT_CREATE2_DEPLOYER.create2_from_code(B256::from(salt), &call.init_code);
+    let (account, _) = data.journaled_state.load_code(address, data.db)?;
+    Ok(account.info.code.as_ref().is_some_and(|code| !code.is_empty()).then_some(address))
+}
+
 // Determines if the gas limit on a given call was manually set in the script and should therefore
 // not be overwritten by later estimations
 fn check_if_fixed_gas_limit<DB: DatabaseExt>(data: &EVMData<'_, DB>, call_gas_limit: u64) -> bool {
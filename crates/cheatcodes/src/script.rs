@@ -1,10 +1,11 @@
 //! Implementations of [`Scripting`](crate::Group::Scripting) cheatcodes.
 
-use crate::{Cheatcode, CheatsCtxt, DatabaseExt, Result, Vm::*};
+use crate::{Cheatcode, Cheatcodes, CheatsCtxt, DatabaseExt, Result, Vm::*};
 use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
 use ethers_signers::Signer;
-use foundry_common::types::ToAlloy;
-use foundry_config::Config;
+use foundry_common::{deployments::DeploymentRegistry, types::ToAlloy};
+use foundry_config::{fs_permissions::FsAccessKind, Config};
 
 impl Cheatcode for broadcast_0Call {
     fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
@@ -117,6 +118,39 @@ fn broadcast_key<DB: DatabaseExt>(
     result
 }
 
+impl Cheatcode for getDeployment_0Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { contractName } = self;
+        get_deployment(ccx.state, contractName, ccx.data.env.cfg.chain_id)
+    }
+}
+
+impl Cheatcode for getDeployment_1Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { contractName, chainId } = self;
+        get_deployment(state, contractName, *chainId)
+    }
+}
+
+/// Reads the address of the latest deployment of `contract_name` from the deployment registry.
+///
+/// Deployments simulated by earlier scripts of a chained run are preferred.
+fn get_deployment(state: &Cheatcodes, contract_name: &str, chain_id: u64) -> Result {
+    if let Some(address) =
+        state.config.simulated_deployments.get(&(chain_id, contract_name.to_string()))
+    {
+        return Ok(address.abi_encode())
+    }
+    let registry = DeploymentRegistry::new(&state.config.root);
+    state
+        .config
+        .ensure_path_allowed(registry.manifest_path(chain_id, contract_name), FsAccessKind::Read)?;
+    let manifest = registry
+        .get(chain_id, contract_name)?
+        .ok_or_else(|| fmt_err!("no deployment of `{contract_name}` found on chain {chain_id}"))?;
+    Ok(manifest.address.abi_encode())
+}
+
 /// When using `forge script`, the script method is called using the address from `--sender`.
 /// That leads to its nonce being incremented by `call_raw`. In a `broadcast` scenario this is
 /// undesirable. Therefore, we make sure to fix the sender's nonce **once**.
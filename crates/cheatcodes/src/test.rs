@@ -5,6 +5,7 @@ use alloy_primitives::Address;
 use alloy_sol_types::SolValue;
 use foundry_evm_core::constants::{MAGIC_ASSUME, MAGIC_SKIP};
 
+mod assert;
 pub(crate) mod expect;
 
 impl Cheatcode for assumeCall {
@@ -62,21 +63,54 @@ impl Cheatcode for sleepCall {
     }
 }
 
-impl Cheatcode for skipCall {
+impl Cheatcode for skip_0Call {
     fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { skipTest } = *self;
-        if skipTest {
-            // Skip should not work if called deeper than at test level.
-            // Since we're not returning the magic skip bytes, this will cause a test failure.
-            ensure!(ccx.data.journaled_state.depth() <= 1, "`skip` can only be used at test level");
-            ccx.state.skip = true;
-            Err(MAGIC_SKIP.into())
-        } else {
-            Ok(Default::default())
-        }
+        skip(ccx, skipTest, None)
+    }
+}
+
+impl Cheatcode for skip_1Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { skipTest, reason } = self;
+        skip(ccx, *skipTest, Some(reason.clone()))
     }
 }
 
+impl Cheatcode for xfailCall {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { reason } = self;
+        ensure!(ccx.data.journaled_state.depth() <= 1, "`xfail` can only be used at test level");
+        ccx.state.xfail = Some(reason.clone());
+        Ok(Default::default())
+    }
+}
+
+fn skip<DB: DatabaseExt>(
+    ccx: &mut CheatsCtxt<DB>,
+    skip_test: bool,
+    reason: Option<String>,
+) -> Result {
+    if skip_test {
+        // Skip should not work if called deeper than at test level.
+        // Since we're not returning the magic skip bytes, this will cause a test failure.
+        ensure!(ccx.data.journaled_state.depth() <= 1, "`skip` can only be used at test level");
+        ccx.state.skip = true;
+        let data = skip_data(reason.as_deref());
+        ccx.state.skip_reason = reason;
+        Err(data.into())
+    } else {
+        Ok(Default::default())
+    }
+}
+
+/// Returns the revert data of a skipped test: the magic skip bytes followed by the reason.
+pub(crate) fn skip_data(reason: Option<&str>) -> Vec<u8> {
+    let mut data = MAGIC_SKIP.to_vec();
+    data.extend_from_slice(reason.unwrap_or_default().as_bytes());
+    data
+}
+
 /// Adds or removes the given breakpoint to the state.
 fn breakpoint(state: &mut Cheatcodes, caller: &Address, s: &str, add: bool) -> Result {
     let mut chars = s.chars();
@@ -0,0 +1,311 @@
+//! Implementations of the assertion cheatcodes, which revert with decoded diffs of the values.
+
+use crate::{Cheatcode, Cheatcodes, Result, Vm::*};
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_json_abi::{InternalType, JsonAbi, Param};
+use alloy_primitives::{Address, Bytes, B256, I256, U256};
+use foundry_common::{
+    fmt::format_token,
+    fs::{json_files, read_json_file},
+};
+use std::{fmt::Write, path::Path};
+
+/// The maximum number of differences included in the revert message.
+const MAX_DIFFS: usize = 10;
+
+impl Cheatcode for assertEq_0Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { left, right } = self;
+        assert_eq_values(&bool_array(left), &bool_array(right))
+    }
+}
+
+impl Cheatcode for assertEq_1Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { left, right } = self;
+        assert_eq_values(&uint_array(left), &uint_array(right))
+    }
+}
+
+impl Cheatcode for assertEq_2Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { left, right } = self;
+        assert_eq_values(&int_array(left), &int_array(right))
+    }
+}
+
+impl Cheatcode for assertEq_3Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { left, right } = self;
+        assert_eq_values(&address_array(left), &address_array(right))
+    }
+}
+
+impl Cheatcode for assertEq_4Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { left, right } = self;
+        assert_eq_values(&bytes32_array(left), &bytes32_array(right))
+    }
+}
+
+impl Cheatcode for assertEq_5Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { left, right } = self;
+        assert_eq_values(&string_array(left), &string_array(right))
+    }
+}
+
+impl Cheatcode for assertEq_6Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { left, right } = self;
+        assert_eq_values(&bytes_array(left), &bytes_array(right))
+    }
+}
+
+impl Cheatcode for assertEq_7Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { left, right } = self;
+        assert_eq_values(&DynSolValue::Bytes(left.to_vec()), &DynSolValue::Bytes(right.to_vec()))
+    }
+}
+
+impl Cheatcode for assertEqDecodedCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { left, right, typeDescription } = self;
+        if left == right {
+            return Ok(Default::default())
+        }
+        let ty = match DynSolType::parse(typeDescription) {
+            Ok(ty) => ty,
+            Err(_) => find_struct_type(&state.config.paths.artifacts, typeDescription)
+                .ok_or_else(|| fmt_err!("unknown type or struct `{typeDescription}`"))?,
+        };
+        let decode = |data: &[u8]| {
+            ty.abi_decode(data).map_err(|e| fmt_err!("failed decoding `{typeDescription}`: {e}"))
+        };
+        assert_eq_values(&decode(left)?, &decode(right)?)
+    }
+}
+
+fn bool_array(values: &[bool]) -> DynSolValue {
+    DynSolValue::Array(values.iter().map(|v| DynSolValue::Bool(*v)).collect())
+}
+
+fn uint_array(values: &[U256]) -> DynSolValue {
+    DynSolValue::Array(values.iter().map(|v| DynSolValue::Uint(*v, 256)).collect())
+}
+
+fn int_array(values: &[I256]) -> DynSolValue {
+    DynSolValue::Array(values.iter().map(|v| DynSolValue::Int(*v, 256)).collect())
+}
+
+fn address_array(values: &[Address]) -> DynSolValue {
+    DynSolValue::Array(values.iter().map(|v| DynSolValue::Address(*v)).collect())
+}
+
+fn bytes32_array(values: &[B256]) -> DynSolValue {
+    DynSolValue::Array(values.iter().map(|v| DynSolValue::FixedBytes(*v, 32)).collect())
+}
+
+fn string_array(values: &[String]) -> DynSolValue {
+    DynSolValue::Array(values.iter().map(|v| DynSolValue::String(v.clone())).collect())
+}
+
+fn bytes_array(values: &[Bytes]) -> DynSolValue {
+    DynSolValue::Array(values.iter().map(|v| DynSolValue::Bytes(v.to_vec())).collect())
+}
+
+/// Returns the type of the struct with the given name, e.g. `Position` or `Vault.Position`, as
+/// found in the ABIs of the artifacts in the given directory.
+fn find_struct_type(artifacts: &Path, name: &str) -> Option<DynSolType> {
+    json_files(artifacts).into_iter().find_map(|path| {
+        let artifact = read_json_file::<serde_json::Value>(&path).ok()?;
+        let abi = serde_json::from_value::<JsonAbi>(artifact.get("abi")?.clone()).ok()?;
+        let params = abi
+            .functions()
+            .flat_map(|f| f.inputs.iter().chain(&f.outputs))
+            .chain(abi.errors().flat_map(|e| &e.inputs))
+            .chain(abi.constructor().into_iter().flat_map(|c| &c.inputs))
+            .chain(abi.events().flat_map(|e| e.inputs.iter().flat_map(|p| &p.components)));
+        params.into_iter().find_map(|param| find_struct(param, name))
+    })
+}
+
+/// Returns the type of the struct with the given name if it's the type of the parameter or one of
+/// its components.
+fn find_struct(param: &Param, name: &str) -> Option<DynSolType> {
+    if let Some(InternalType::Struct { contract, ty }) = &param.internal_type {
+        let ty = ty.split('[').next().unwrap_or(ty);
+        if name == ty || contract.as_ref().is_some_and(|c| name == format!("{c}.{ty}")) {
+            return struct_type(param)
+        }
+    }
+    param.components.iter().find_map(|c| find_struct(c, name))
+}
+
+/// Resolves the type of a parameter, keeping the field names of structs.
+fn param_type(param: &Param) -> Option<DynSolType> {
+    let Some(dims) = param.ty.strip_prefix("tuple") else {
+        return DynSolType::parse(&param.ty).ok()
+    };
+    let mut ty = struct_type(param)?;
+    for dim in dims.split_terminator(']') {
+        let size = dim.strip_prefix('[')?;
+        ty = if size.is_empty() {
+            DynSolType::Array(Box::new(ty))
+        } else {
+            DynSolType::FixedArray(Box::new(ty), size.parse().ok()?)
+        };
+    }
+    Some(ty)
+}
+
+/// Resolves the type of a tuple parameter without its array dimensions.
+fn struct_type(param: &Param) -> Option<DynSolType> {
+    let tuple = param.components.iter().map(param_type).collect::<Option<Vec<_>>>()?;
+    Some(match &param.internal_type {
+        Some(InternalType::Struct { ty, .. }) => DynSolType::CustomStruct {
+            name: ty.split('[').next().unwrap_or(ty).to_string(),
+            prop_names: param.components.iter().map(|c| c.name.clone()).collect(),
+            tuple,
+        },
+        _ => DynSolType::Tuple(tuple),
+    })
+}
+
+/// Reverts with the differences of the values if they are not equal.
+fn assert_eq_values(left: &DynSolValue, right: &DynSolValue) -> Result {
+    let mut diffs = Vec::new();
+    diff(&mut String::new(), left, right, &mut diffs);
+    if diffs.is_empty() {
+        return Ok(Default::default())
+    }
+
+    let mut msg = String::from("assertion failed: values differ");
+    for diff in diffs.iter().take(MAX_DIFFS) {
+        let _ = write!(msg, "\n  {diff}");
+    }
+    if diffs.len() > MAX_DIFFS {
+        let _ = write!(msg, "\n  ... and {} more", diffs.len() - MAX_DIFFS);
+    }
+    Err(msg.into())
+}
+
+/// Collects the differences of two values, as the paths of the differing struct fields, tuple
+/// components and array elements with both values, e.g. `.owners[2]: 3 != 4` or `.1[2]: 3 != 4`.
+fn diff(path: &mut String, left: &DynSolValue, right: &DynSolValue, diffs: &mut Vec<String>) {
+    let len = path.len();
+    match (left, right) {
+        (DynSolValue::Array(l), DynSolValue::Array(r)) |
+        (DynSolValue::FixedArray(l), DynSolValue::FixedArray(r)) => {
+            if l.len() != r.len() {
+                let length =
+                    if path.is_empty() { "length".to_string() } else { format!("{path}.length") };
+                diffs.push(format!("{length}: {} != {}", l.len(), r.len()));
+            }
+            for (i, (l, r)) in l.iter().zip(r).enumerate() {
+                let _ = write!(path, "[{i}]");
+                diff(path, l, r, diffs);
+                path.truncate(len);
+            }
+        }
+        (DynSolValue::Tuple(l), DynSolValue::Tuple(r)) if l.len() == r.len() => {
+            for (i, (l, r)) in l.iter().zip(r).enumerate() {
+                let _ = write!(path, ".{i}");
+                diff(path, l, r, diffs);
+                path.truncate(len);
+            }
+        }
+        (
+            DynSolValue::CustomStruct { prop_names, tuple: l, .. },
+            DynSolValue::CustomStruct { tuple: r, .. },
+        ) if l.len() == r.len() => {
+            for (name, (l, r)) in prop_names.iter().zip(l.iter().zip(r)) {
+                let _ = write!(path, ".{name}");
+                diff(path, l, r, diffs);
+                path.truncate(len);
+            }
+        }
+        _ if left != right => {
+            let path = if path.is_empty() { "value" } else { path.as_str() };
+            diffs.push(format!("{path}: {} != {}", format_token(left), format_token(right)));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diffs(left: &DynSolValue, right: &DynSolValue) -> Vec<String> {
+        let mut diffs = Vec::new();
+        diff(&mut String::new(), left, right, &mut diffs);
+        diffs
+    }
+
+    #[test]
+    fn diffs_arrays() {
+        let left = uint_array(&[U256::from(1), U256::from(2), U256::from(3)]);
+        let right = uint_array(&[U256::from(1), U256::from(5)]);
+        assert_eq!(diffs(&left, &right), ["length: 3 != 2", "[1]: 2 != 5"]);
+        assert!(diffs(&left, &left).is_empty());
+    }
+
+    #[test]
+    fn diffs_nested_tuples() {
+        let ty = DynSolType::parse("(uint256,string[])").unwrap();
+        let value = |n: u64, s: &str| {
+            DynSolValue::Tuple(vec![
+                DynSolValue::Uint(U256::from(n), 256),
+                DynSolValue::Array(vec![DynSolValue::String(s.to_string())]),
+            ])
+        };
+        let left = ty.abi_decode(&value(1, "a").abi_encode()).unwrap();
+        let right = ty.abi_decode(&value(2, "b").abi_encode()).unwrap();
+        assert_eq!(diffs(&left, &right), [".0: 1 != 2", ".1[0]: \"a\" != \"b\""]);
+    }
+
+    #[test]
+    fn diffs_structs_by_field_names() {
+        let abi: JsonAbi = serde_json::from_str(
+            r#"[{"type":"function","name":"f","stateMutability":"pure","outputs":[],"inputs":[
+                {"name":"p","type":"tuple[]","internalType":"struct Vault.Position[]","components":[
+                    {"name":"amount","type":"uint256","internalType":"uint256"},
+                    {"name":"owners","type":"address[]","internalType":"address[]"}
+                ]}
+            ]}]"#,
+        )
+        .unwrap();
+        let param = &abi.functions().next().unwrap().inputs[0];
+        assert!(find_struct(param, "Other").is_none());
+        let ty = find_struct(param, "Vault.Position").unwrap();
+        assert_eq!(find_struct(param, "Position"), Some(ty.clone()));
+
+        let value = |owner: Address| {
+            DynSolValue::Tuple(vec![
+                DynSolValue::Uint(U256::from(1), 256),
+                address_array(&[Address::ZERO, owner]),
+            ])
+        };
+        let left = ty.abi_decode(&value(Address::ZERO).abi_encode()).unwrap();
+        let right = ty.abi_decode(&value(Address::repeat_byte(1)).abi_encode()).unwrap();
+        assert_eq!(
+            diffs(&left, &right),
+            [format!(".owners[1]: {} != {}", Address::ZERO, Address::repeat_byte(1))]
+        );
+    }
+
+    #[test]
+    fn reverts_with_diffs() {
+        let left = DynSolValue::Bytes(vec![0x12]);
+        let right = DynSolValue::Bytes(vec![0x34]);
+        let err = assert_eq_values(&left, &right).unwrap_err();
+        assert_eq!(err.to_string(), "assertion failed: values differ\n  value: 0x12 != 0x34");
+
+        let left = bool_array(&[true; 12]);
+        let right = bool_array(&[false; 12]);
+        let err = assert_eq_values(&left, &right).unwrap_err();
+        assert!(err.to_string().ends_with("... and 2 more"), "{err}");
+    }
+}
@@ -76,8 +76,96 @@ pub struct ExpectedEmit {
     pub checks: [bool; 4],
     /// If present, check originating address against this
     pub address: Option<Address>,
+    /// Whether only the event signature, the first topic, is checked
+    pub signature_only: bool,
     /// Whether the log was actually found in the subcalls
     pub found: bool,
+    /// The last log with the same event signature that didn't match, used to report the
+    /// difference
+    pub closest: Option<(Address, RawLog)>,
+}
+
+impl ExpectedEmit {
+    /// Returns true if the log matches the expected log with the configured checks
+    fn matches(&self, address: &Address, topics: &[B256], data: &Bytes) -> bool {
+        let Some(expected) = &self.log else { return false };
+        if !same_signature(expected.topics(), topics) ||
+            self.address.map_or(false, |addr| addr != *address)
+        {
+            return false
+        }
+        if self.signature_only {
+            return true
+        }
+        expected.topics().len() == topics.len() &&
+            topics
+                .iter()
+                .enumerate()
+                .skip(1)
+                .filter(|(i, _)| self.checks[*i - 1])
+                .all(|(i, topic)| topic == &expected.topics()[i]) &&
+            (!self.checks[3] || expected.data == *data)
+    }
+
+    /// Describes how the closest log with the same event signature differs from the expected log
+    fn mismatch(&self) -> Option<String> {
+        let expected = self.log.as_ref()?;
+        let (address, actual) = self.closest.as_ref()?;
+        let mut diffs = Vec::new();
+        if let Some(emitter) = self.address.filter(|emitter| emitter != address) {
+            diffs.push(format!("emitter: expected {emitter}, got {address}"));
+        }
+        if expected.topics().len() != actual.topics().len() {
+            diffs.push(format!(
+                "number of topics: expected {}, got {}",
+                expected.topics().len(),
+                actual.topics().len()
+            ));
+        } else {
+            for (i, (expected, actual)) in
+                expected.topics().iter().zip(actual.topics()).enumerate().skip(1)
+            {
+                if self.checks[i - 1] && expected != actual {
+                    diffs.push(format!("topic {i}: expected {expected}, got {actual}"));
+                }
+            }
+            if self.checks[3] && expected.data != actual.data {
+                diffs.push(format!("data: expected {}, got {}", expected.data, actual.data));
+            }
+        }
+        (!diffs.is_empty()).then(|| diffs.join("; "))
+    }
+
+    /// Returns the error message for an expected log that wasn't emitted
+    pub(crate) fn error(&self) -> String {
+        match self.mismatch() {
+            Some(diff) => format!("log != expected log: {diff}"),
+            None => "log != expected log".to_string(),
+        }
+    }
+}
+
+/// An expected emit that has to occur an exact number of times during the next call, in any
+/// order relative to other logs
+#[derive(Clone, Debug)]
+pub struct CountedExpectedEmit {
+    /// The expected log and the checks to perform
+    pub emit: ExpectedEmit,
+    /// The number of matching logs expected
+    pub count: u64,
+    /// The number of matching logs emitted so far
+    pub seen: u64,
+}
+
+/// An address that must not emit any logs other than the expected ones during the next call
+#[derive(Clone, Debug)]
+pub struct StrictEmitter {
+    /// The depth at which the expectation was declared
+    pub depth: u64,
+    /// The emitter
+    pub address: Address,
+    /// The first log of the emitter that wasn't expected
+    pub unexpected: Option<RawLog>,
 }
 
 impl Cheatcode for expectCall_0Call {
@@ -149,6 +237,29 @@ impl Cheatcode for expectCall_5Call {
     }
 }
 
+impl Cheatcode for expectCallAtLeast_0Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { callee, data, count } = self;
+        expect_call(state, callee, data, None, None, None, *count, ExpectedCallType::NonCount)
+    }
+}
+
+impl Cheatcode for expectCallAtLeast_1Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { callee, msgValue, data, count } = self;
+        expect_call(
+            state,
+            callee,
+            data,
+            Some(msgValue),
+            None,
+            None,
+            *count,
+            ExpectedCallType::NonCount,
+        )
+    }
+}
+
 impl Cheatcode for expectCallMinGas_0Call {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { callee, msgValue, minGas, data } = self;
@@ -219,6 +330,95 @@ impl Cheatcode for expectEmit_3Call {
     }
 }
 
+impl Cheatcode for expectEmit_4Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { checkTopic1, checkTopic2, checkTopic3, checkData, count } = *self;
+        expect_emit_count(
+            ccx.state,
+            ccx.data.journaled_state.depth(),
+            [checkTopic1, checkTopic2, checkTopic3, checkData],
+            None,
+            None,
+            count,
+        )
+    }
+}
+
+impl Cheatcode for expectEmit_5Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { checkTopic1, checkTopic2, checkTopic3, checkData, emitter, count } = *self;
+        expect_emit_count(
+            ccx.state,
+            ccx.data.journaled_state.depth(),
+            [checkTopic1, checkTopic2, checkTopic3, checkData],
+            Some(emitter),
+            None,
+            count,
+        )
+    }
+}
+
+impl Cheatcode for expectEmit_6Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { count } = *self;
+        expect_emit_count(ccx.state, ccx.data.journaled_state.depth(), [true; 4], None, None, count)
+    }
+}
+
+impl Cheatcode for expectEmit_7Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { emitter, count } = *self;
+        expect_emit_count(
+            ccx.state,
+            ccx.data.journaled_state.depth(),
+            [true; 4],
+            Some(emitter),
+            None,
+            count,
+        )
+    }
+}
+
+impl Cheatcode for expectEmitSignature_0Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { topic0 } = *self;
+        expect_emit_signature(ccx.state, ccx.data.journaled_state.depth(), topic0, None)
+    }
+}
+
+impl Cheatcode for expectEmitSignature_1Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { topic0, emitter } = *self;
+        expect_emit_signature(ccx.state, ccx.data.journaled_state.depth(), topic0, Some(emitter))
+    }
+}
+
+impl Cheatcode for expectEmitSignature_2Call {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { topic0, emitter, count } = *self;
+        expect_emit_count(
+            ccx.state,
+            ccx.data.journaled_state.depth(),
+            [false; 4],
+            Some(emitter),
+            Some(topic0),
+            count,
+        )
+    }
+}
+
+impl Cheatcode for expectNoUnexpectedEmitsCall {
+    fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { emitter } = *self;
+        ccx.state.strict_emitters.push(StrictEmitter {
+            depth: ccx.data.journaled_state.depth(),
+            address: emitter,
+            unexpected: None,
+        });
+        Ok(Default::default())
+    }
+}
+
 impl Cheatcode for expectRevert_0Call {
     fn apply_full<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self {} = self;
@@ -271,6 +471,8 @@ impl Cheatcode for expectSafeMemoryCallCall {
 /// abi.encodeWithSelector(0xd34db33f))` will expect the call to address(0xc4f3) and selector
 /// `0xd34db33f` to be made at least once. If the amount of calls is 0, the test will fail. If the
 /// call is made more than once, the test will pass.
+/// - `vm.expectCallAtLeast` behaves like the latter, but expects the call to be made at least
+///   `count` more times.
 #[allow(clippy::too_many_arguments)] // It is what it is
 fn expect_call(
     state: &mut Cheatcodes,
@@ -314,7 +516,7 @@ fn expect_call(
         }
         ExpectedCallType::NonCount => {
             // Check if the expected calldata exists.
-            // If it does, increment the count by one as we expect to see it one more time.
+            // If it does, increment the count as we expect to see it `count` more times.
             match expecteds.entry(calldata.clone()) {
                 Entry::Occupied(mut entry) => {
                     let (expected, _) = entry.get_mut();
@@ -323,7 +525,7 @@ fn expect_call(
                         expected.call_type == ExpectedCallType::NonCount,
                         "cannot overwrite a counted expectCall with a non-counted expectCall"
                     );
-                    expected.count += 1;
+                    expected.count += count;
                 }
                 // If it does not exist, then create it.
                 Entry::Vacant(entry) => {
@@ -349,18 +551,85 @@ fn expect_emit(
         depth,
         checks,
         address,
+        signature_only: false,
         found: false,
         log: None,
+        closest: None,
+    });
+    Ok(Default::default())
+}
+
+/// Expects a log with the given event signature, without a template log emitted by the test.
+fn expect_emit_signature(
+    state: &mut Cheatcodes,
+    depth: u64,
+    topic0: B256,
+    address: Option<Address>,
+) -> Result {
+    state.expected_emits.push_back(ExpectedEmit {
+        depth,
+        checks: [false; 4],
+        address,
+        signature_only: true,
+        found: false,
+        log: Some(RawLog::new_unchecked(vec![topic0], Bytes::new())),
+        closest: None,
+    });
+    Ok(Default::default())
+}
+
+/// Expects a log to be emitted exactly `count` times during the next call.
+///
+/// If `signature` is set, only the event signature is matched and no template log is needed.
+fn expect_emit_count(
+    state: &mut Cheatcodes,
+    depth: u64,
+    checks: [bool; 4],
+    address: Option<Address>,
+    signature: Option<B256>,
+    count: u64,
+) -> Result {
+    state.expected_emit_counts.push(CountedExpectedEmit {
+        emit: ExpectedEmit {
+            depth,
+            checks,
+            address,
+            signature_only: signature.is_some(),
+            found: false,
+            log: signature.map(|topic0| RawLog::new_unchecked(vec![topic0], Bytes::new())),
+            closest: None,
+        },
+        count,
+        seen: 0,
     });
     Ok(Default::default())
 }
 
+/// Fills or checks the expected emits with an emitted log.
+///
+/// Returns true if the log was expected, i.e. it filled or matched an expected emit.
 pub(crate) fn handle_expect_emit(
     state: &mut Cheatcodes,
     address: &Address,
     topics: &[B256],
     data: &Bytes,
-) {
+) -> bool {
+    // Counted emits are filled by the log emitted right after they were declared, and then
+    // match any number of logs in any order.
+    if let Some(counted) =
+        state.expected_emit_counts.iter_mut().find(|counted| counted.emit.log.is_none())
+    {
+        counted.emit.log = Some(RawLog::new_unchecked(topics.to_vec(), data.clone()));
+        return true
+    }
+    let mut matched = false;
+    for counted in &mut state.expected_emit_counts {
+        if counted.emit.matches(address, topics, data) {
+            counted.seen += 1;
+            matched = true;
+        }
+    }
+
     // Fill or check the expected emits.
     // We expect for emit checks to be filled as they're declared (from oldest to newest),
     // so we fill them and push them to the back of the queue.
@@ -372,7 +641,7 @@ pub(crate) fn handle_expect_emit(
     // This allows a contract to arbitrarily emit more events than expected (additive behavior),
     // as long as all the previous events were matched in the order they were expected to be.
     if state.expected_emits.iter().all(|expected| expected.found) {
-        return
+        return matched
     }
 
     // if there's anything to fill, we need to pop back.
@@ -386,48 +655,115 @@ pub(crate) fn handle_expect_emit(
         }
         .expect("we should have an emit to fill or check");
 
-    let Some(expected) = &event_to_fill_or_check.log else {
+    let Some(expected_log) = &event_to_fill_or_check.log else {
         // Fill the event.
         event_to_fill_or_check.log = Some(RawLog::new_unchecked(topics.to_vec(), data.clone()));
         state.expected_emits.push_back(event_to_fill_or_check);
-        return
+        return true
     };
 
-    let expected_topic_0 = expected.topics().first();
-    let log_topic_0 = topics.first();
-
-    if expected_topic_0
-        .zip(log_topic_0)
-        .map_or(false, |(a, b)| a == b && expected.topics().len() == topics.len())
-    {
-        // Match topics
-        event_to_fill_or_check.found = topics
-            .iter()
-            .skip(1)
-            .enumerate()
-            .filter(|(i, _)| event_to_fill_or_check.checks[*i])
-            .all(|(i, topic)| topic == &expected.topics()[i + 1]);
-
-        // Maybe match source address
-        if let Some(addr) = event_to_fill_or_check.address {
-            event_to_fill_or_check.found &= addr == *address;
-        }
-
-        // Maybe match data
-        if event_to_fill_or_check.checks[3] {
-            event_to_fill_or_check.found &= expected.data == *data;
-        }
+    event_to_fill_or_check.found = event_to_fill_or_check.matches(address, topics, data);
+    if !event_to_fill_or_check.found && same_signature(expected_log.topics(), topics) {
+        event_to_fill_or_check.closest =
+            Some((*address, RawLog::new_unchecked(topics.to_vec(), data.clone())));
     }
 
     // If we found the event, we can push it to the back of the queue
     // and begin expecting the next event.
     if event_to_fill_or_check.found {
         state.expected_emits.push_back(event_to_fill_or_check);
+        true
     } else {
         // We did not match this event, so we need to keep waiting for the right one to
         // appear.
         state.expected_emits.push_front(event_to_fill_or_check);
+        matched
+    }
+}
+
+/// Records a log of an emitter that must not emit unexpected logs, if it wasn't expected.
+pub(crate) fn handle_strict_emitters(
+    state: &mut Cheatcodes,
+    address: &Address,
+    topics: &[B256],
+    data: &Bytes,
+) {
+    for strict in &mut state.strict_emitters {
+        if strict.address == *address && strict.unexpected.is_none() {
+            strict.unexpected = Some(RawLog::new_unchecked(topics.to_vec(), data.clone()));
+        }
+    }
+}
+
+/// Checks the counted emits and strict emitters declared at `depth`, or all of them if `None`,
+/// and removes them.
+pub(crate) fn check_emit_counts(
+    state: &mut Cheatcodes,
+    depth: Option<u64>,
+) -> std::result::Result<(), String> {
+    let is_due = |declared: u64| depth.map_or(true, |depth| depth == declared);
+    let (counted, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut state.expected_emit_counts)
+        .into_iter()
+        .partition(|counted| is_due(counted.emit.depth));
+    state.expected_emit_counts = pending;
+    let (strict, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut state.strict_emitters)
+        .into_iter()
+        .partition(|strict| is_due(strict.depth));
+    state.strict_emitters = pending;
+
+    for CountedExpectedEmit { emit, count, seen } in counted {
+        let Some(log) = &emit.log else {
+            return Err("expected a log to be emitted after `expectEmit` with a count, \
+                        but none was emitted"
+                .to_string())
+        };
+        if seen != count {
+            let times = |n: u64| if n == 1 { "1 time".to_string() } else { format!("{n} times") };
+            let mut msg = format!(
+                "expected {} to be emitted {}, but it was emitted {}",
+                describe_log(emit.address, log, emit.signature_only),
+                times(count),
+                times(seen)
+            );
+            if let Some(diff) = emit.mismatch() {
+                msg.push_str(&format!("; closest log: {diff}"));
+            }
+            return Err(msg)
+        }
+    }
+    for StrictEmitter { address, unexpected, .. } in strict {
+        if let Some(log) = unexpected {
+            return Err(format!(
+                "unexpected {}; expected {address} to emit only expected logs",
+                describe_log(Some(address), &log, false)
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if both logs have the same, non-anonymous event signature
+fn same_signature(expected: &[B256], actual: &[B256]) -> bool {
+    expected.first().zip(actual.first()).map_or(false, |(a, b)| a == b)
+}
+
+fn describe_log(address: Option<Address>, log: &RawLog, signature_only: bool) -> String {
+    let mut description = match log.topics().first() {
+        Some(topic0) => format!("log {topic0}"),
+        None => "anonymous log".to_string(),
+    };
+    if let Some(address) = address {
+        description.push_str(&format!(" from {address}"));
+    }
+    if !signature_only {
+        for topic in log.topics().iter().skip(1) {
+            description.push_str(&format!(", topic {topic}"));
+        }
+        if !log.data.is_empty() {
+            description.push_str(&format!(", data {}", log.data));
+        }
     }
+    description
 }
 
 fn expect_revert(state: &mut Cheatcodes, reason: Option<&[u8]>, depth: u64) -> Result {
@@ -1,5 +1,6 @@
 use clap::Parser;
 use foundry_compilers::{artifacts::output_selection::ContractOutputSelection, EvmVersion};
+use foundry_config::OptimizerPreset;
 use serde::Serialize;
 
 mod core;
@@ -30,6 +31,13 @@ pub struct CompilerArgs {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub optimizer_runs: Option<usize>,
 
+    /// Enable the optimizer with a named set of settings, overriding the optimizer runs.
+    ///
+    /// Valid values: size, balanced, gas
+    #[clap(long, value_name = "PRESET")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optimizer_preset: Option<OptimizerPreset>,
+
     /// Extra output to include in the contract's artifact.
     ///
     /// Example keys: evm.assembly, ewasm, ir, irOptimized, metadata
@@ -58,6 +66,13 @@ mod tests {
         assert_eq!(args.evm_version, Some(EvmVersion::London));
     }
 
+    #[test]
+    fn can_parse_optimizer_preset() {
+        let args: CompilerArgs =
+            CompilerArgs::parse_from(["foundry-cli", "--optimizer-preset", "size"]);
+        assert_eq!(args.optimizer_preset, Some(OptimizerPreset::Size));
+    }
+
     #[test]
     fn can_parse_extra_output() {
         let args: CompilerArgs =
@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use clap::Parser;
 use ethers_core::types::{
     transaction::{eip2718::TypedTransaction, eip712::Eip712},
-    Signature,
+    Signature, H256, U256,
 };
 use ethers_signers::{
     coins_bip39::English, AwsSigner, AwsSignerError, HDPath as LedgerHDPath, Ledger, LedgerError,
@@ -20,6 +20,7 @@ use rusoto_core::{
 use rusoto_kms::KmsClient;
 use serde::{Deserialize, Serialize};
 use std::{
+    fmt,
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -29,12 +30,15 @@ pub use multi_wallet::*;
 
 pub mod error;
 
+pub mod pkcs11;
+use pkcs11::{Pkcs11Opts, Pkcs11Signer, Pkcs11SignerError};
+
 /// A wrapper for the raw data options for `Wallet`, extracted to also be used standalone.
 /// The raw wallet options can either be:
 /// 1. Private Key (cleartext in CLI)
 /// 2. Private Key (interactively via secure prompt)
 /// 3. Mnemonic (via file path)
-#[derive(Parser, Debug, Default, Clone, Serialize)]
+#[derive(Parser, Default, Clone, Serialize)]
 #[clap(next_help_heading = "Wallet options - raw", about = None, long_about = None)]
 pub struct RawWallet {
     /// Open an interactive prompt to enter your private key.
@@ -47,14 +51,21 @@ pub struct RawWallet {
         value_name = "RAW_PRIVATE_KEY",
         value_parser = foundry_common::clap_helpers::strip_0x_prefix
     )]
+    #[serde(serialize_with = "serialize_redacted")]
     pub private_key: Option<String>,
 
+    /// Skip the confirmation prompt when a private key is passed in cleartext.
+    #[clap(long)]
+    pub yes: bool,
+
     /// Use the mnemonic phrase of mnemonic file at the specified path.
     #[clap(long, alias = "mnemonic-path")]
+    #[serde(serialize_with = "serialize_redacted")]
     pub mnemonic: Option<String>,
 
     /// Use a BIP39 passphrase for the mnemonic.
     #[clap(long, value_name = "PASSPHRASE")]
+    #[serde(serialize_with = "serialize_redacted")]
     pub mnemonic_passphrase: Option<String>,
 
     /// The wallet derivation path.
@@ -76,7 +87,8 @@ pub struct RawWallet {
 /// 3. Trezor
 /// 4. Keystore (via file path)
 /// 5. AWS KMS
-#[derive(Parser, Debug, Default, Clone, Serialize)]
+/// 6. PKCS#11 token
+#[derive(Parser, Default, Clone, Serialize)]
 #[clap(next_help_heading = "Wallet options", about = None, long_about = None)]
 pub struct Wallet {
     /// The sender account.
@@ -120,6 +132,7 @@ pub struct Wallet {
         requires = "keystore_path",
         value_name = "PASSWORD"
     )]
+    #[serde(serialize_with = "serialize_redacted")]
     pub keystore_password: Option<String>,
 
     /// The keystore password file path.
@@ -145,6 +158,41 @@ pub struct Wallet {
     /// Use AWS Key Management Service.
     #[clap(long, help_heading = "Wallet options - AWS KMS")]
     pub aws: bool,
+
+    #[clap(flatten)]
+    pub pkcs11: Pkcs11Opts,
+}
+
+impl fmt::Debug for RawWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redact = |secret: bool| secret.then_some(REDACTED);
+        f.debug_struct("RawWallet")
+            .field("interactive", &self.interactive)
+            .field("private_key", &redact(self.private_key.is_some()))
+            .field("yes", &self.yes)
+            .field("mnemonic", &redact(self.mnemonic.is_some()))
+            .field("mnemonic_passphrase", &redact(self.mnemonic_passphrase.is_some()))
+            .field("hd_path", &self.hd_path)
+            .field("mnemonic_index", &self.mnemonic_index)
+            .finish()
+    }
+}
+
+impl fmt::Debug for Wallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wallet")
+            .field("from", &self.from)
+            .field("raw", &self.raw)
+            .field("keystore_path", &self.keystore_path)
+            .field("keystore_account_name", &self.keystore_account_name)
+            .field("keystore_password", &self.keystore_password.as_ref().map(|_| REDACTED))
+            .field("keystore_password_file", &self.keystore_password_file)
+            .field("ledger", &self.ledger)
+            .field("trezor", &self.trezor)
+            .field("aws", &self.aws)
+            .field("pkcs11", &self.pkcs11)
+            .finish()
+    }
 }
 
 impl From<RawWallet> for Wallet {
@@ -160,6 +208,7 @@ impl Wallet {
 
     pub fn private_key(&self) -> Result<Option<LocalWallet>> {
         Ok(if let Some(ref private_key) = self.raw.private_key {
+            confirm_cleartext_private_keys(std::slice::from_ref(private_key), self.raw.yes)?;
             Some(self.get_from_private_key(private_key)?)
         } else {
             None
@@ -257,6 +306,9 @@ Make sure it's connected and unlocked, with no other conflicting desktop wallet
             let aws_signer = AwsSigner::new(kms, key_id, chain_id).await?;
 
             Ok(WalletSigner::Aws(aws_signer))
+        } else if let Some(signers) = self.pkcs11.signers(chain_id)? {
+            let signer = signers.into_iter().next().expect("at least one signer");
+            Ok(WalletSigner::Pkcs11(signer))
         } else {
             trace!("finding local key");
 
@@ -268,7 +320,7 @@ Make sure it's connected and unlocked, with no other conflicting desktop wallet
 Error accessing local wallet. Did you set a private key, mnemonic or keystore?
 Run `cast send --help` or `forge create --help` and use the corresponding CLI
 flag to set your key via:
---private-key, --mnemonic-path, --aws, --interactive, --trezor or --ledger.
+--private-key, --mnemonic-path, --aws, --pkcs11, --interactive, --trezor or --ledger.
 Alternatively, if you're using a local node with unlocked accounts,
 use the --unlocked flag and either set the `ETH_FROM` environment variable to the address
 of the unlocked account you want to use, or provide the --from flag with the address directly."
@@ -427,6 +479,8 @@ pub enum WalletSignerError {
     Trezor(#[from] TrezorError),
     #[error(transparent)]
     Aws(#[from] AwsSignerError),
+    #[error(transparent)]
+    Pkcs11(#[from] Pkcs11SignerError),
 }
 
 #[derive(Debug)]
@@ -435,6 +489,7 @@ pub enum WalletSigner {
     Ledger(Ledger),
     Trezor(Trezor),
     Aws(AwsSigner),
+    Pkcs11(Pkcs11Signer),
 }
 
 impl From<LocalWallet> for WalletSigner {
@@ -461,6 +516,55 @@ impl From<AwsSigner> for WalletSigner {
     }
 }
 
+impl From<Pkcs11Signer> for WalletSigner {
+    fn from(wallet: Pkcs11Signer) -> Self {
+        Self::Pkcs11(wallet)
+    }
+}
+
+impl WalletSigner {
+    /// Signs a raw 32 byte digest as is, without the EIP-191 prefix.
+    ///
+    /// Hardware wallets only sign data they can display to the user, so Ledger and Trezor devices
+    /// can't sign raw digests.
+    pub async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+        match self {
+            Self::Local(wallet) => Ok(wallet.sign_hash(hash)?),
+            Self::Pkcs11(signer) => Ok(signer.sign_digest(hash)?),
+            Self::Aws(signer) => {
+                let sig = signer.sign_digest(hash.into()).await?;
+                let (r, s) = sig.split_bytes();
+                let mut sig = Signature {
+                    r: U256::from_big_endian(&r),
+                    s: U256::from_big_endian(&s),
+                    v: 27,
+                };
+                // KMS doesn't return the recovery id
+                if sig.recover(hash)? != signer.address() {
+                    sig.v = 28;
+                }
+                Ok(sig)
+            }
+            Self::Ledger(_) | Self::Trezor(_) => bail!(
+                "{} devices can't sign raw digests, only messages, typed data and transactions.\n\
+                 Sign the message itself instead of its hash, or use a private key, keystore, AWS KMS or PKCS#11 signer.",
+                self.kind()
+            ),
+        }
+    }
+
+    /// Returns the name of the kind of wallet, for error messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Local(_) => "Local",
+            Self::Ledger(_) => "Ledger",
+            Self::Trezor(_) => "Trezor",
+            Self::Aws(_) => "AWS KMS",
+            Self::Pkcs11(_) => "PKCS#11",
+        }
+    }
+}
+
 macro_rules! delegate {
     ($s:ident, $inner:ident => $e:expr) => {
         match $s {
@@ -468,6 +572,7 @@ macro_rules! delegate {
             Self::Ledger($inner) => $e,
             Self::Trezor($inner) => $e,
             Self::Aws($inner) => $e,
+            Self::Pkcs11($inner) => $e,
         }
     };
 }
@@ -508,6 +613,7 @@ impl Signer for WalletSigner {
             Self::Ledger(inner) => Self::Ledger(inner.with_chain_id(chain_id)),
             Self::Trezor(inner) => Self::Trezor(inner.with_chain_id(chain_id)),
             Self::Aws(inner) => Self::Aws(inner.with_chain_id(chain_id)),
+            Self::Pkcs11(inner) => Self::Pkcs11(inner.with_chain_id(chain_id)),
         }
     }
 }
@@ -579,6 +685,7 @@ mod tests {
             raw: RawWallet {
                 interactive: false,
                 private_key: Some("123".to_string()),
+                yes: true,
                 mnemonic: None,
                 mnemonic_passphrase: None,
                 hd_path: None,
@@ -592,6 +699,7 @@ mod tests {
             ledger: false,
             trezor: false,
             aws: false,
+            pkcs11: Default::default(),
         };
         match wallet.private_key() {
             Ok(_) => {
@@ -606,6 +714,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn redacts_secrets() {
+        let private_key = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+        let wallet: Wallet = Wallet::parse_from([
+            "foundry-cli",
+            "--private-key",
+            private_key,
+            "--mnemonic-passphrase",
+            "correct horse",
+            "--keystore",
+            "my/keystore/path",
+            "--password",
+            "hunter2",
+        ]);
+
+        let json = serde_json::to_string(&wallet).unwrap();
+        let debug = format!("{wallet:?}");
+        for output in [json, debug] {
+            assert!(!output.contains(private_key), "{output}");
+            assert!(!output.contains("correct horse"), "{output}");
+            assert!(!output.contains("hunter2"), "{output}");
+            assert!(output.contains(REDACTED), "{output}");
+            assert!(output.contains("my/keystore/path"), "{output}");
+        }
+    }
+
     #[test]
     fn gets_password_from_file() {
         let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../cast/tests/fixtures/keystore/password");
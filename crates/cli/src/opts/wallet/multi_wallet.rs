@@ -1,4 +1,4 @@
-use super::{WalletSigner, WalletTrait};
+use super::{pkcs11::Pkcs11Opts, WalletSigner, WalletTrait};
 use alloy_primitives::Address;
 use clap::Parser;
 use ethers_providers::Middleware;
@@ -14,12 +14,77 @@ use rusoto_core::{
     request::HttpClient as AwsHttpClient, Client as AwsClient,
 };
 use rusoto_kms::KmsClient;
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    io::{IsTerminal, Write},
     iter::repeat,
-    sync::Arc,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
+use yansi::Paint;
+
+/// Placeholder for secrets in the serialized and debug output of the wallet options.
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// Whether the usage of cleartext private keys was already confirmed in this process.
+static CLEARTEXT_KEYS_CONFIRMED: AtomicBool = AtomicBool::new(false);
+
+/// Serializes secrets as [`REDACTED`], so they don't leak into logs.
+pub(crate) fn serialize_redacted<T, S: Serializer>(
+    value: &Option<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.as_ref().map(|_| REDACTED).serialize(serializer)
+}
+
+/// Warns about private keys passed in cleartext on the command line, and about the ones found
+/// in the shell history.
+///
+/// Asks the user to confirm their usage, unless `yes` is set or stdin is not a terminal.
+/// The confirmation is only asked for once per process.
+pub(crate) fn confirm_cleartext_private_keys(private_keys: &[String], yes: bool) -> Result<()> {
+    if private_keys.is_empty() || CLEARTEXT_KEYS_CONFIRMED.load(Ordering::Relaxed) {
+        return Ok(())
+    }
+
+    eprintln!(
+        "{} Private keys passed on the command line are visible to other processes and can \
+         be stored in your shell history. Consider using a keystore instead, see `cast wallet \
+         import --help`.",
+        Paint::yellow("Warning:").bold()
+    );
+    let history_files = shell_history_files();
+    let leaked = private_keys
+        .iter()
+        .filter_map(|key| find_in_shell_history(key, &history_files))
+        .collect::<BTreeSet<_>>();
+    for history_file in leaked {
+        eprintln!(
+            "{} A private key was found in your shell history at {}. Remove it from the \
+             history and consider the key compromised.",
+            Paint::red("Warning:").bold(),
+            history_file.display()
+        );
+    }
+
+    if !yes && std::io::stdin().is_terminal() {
+        eprint!("Do you want to continue with the cleartext private key? [y/N] ");
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            eyre::bail!("Aborted, the usage of the cleartext private key was not confirmed.")
+        }
+    }
+
+    CLEARTEXT_KEYS_CONFIRMED.store(true, Ordering::Relaxed);
+    Ok(())
+}
 
 macro_rules! get_wallets {
     ($id:ident, [ $($wallets:expr),+ ], $call:expr) => {
@@ -70,7 +135,10 @@ macro_rules! create_hw_wallets {
 /// 5. Private Keys (cleartext in CLI)
 /// 6. Private Keys (interactively via secure prompt)
 /// 7. AWS KMS
-#[derive(Parser, Debug, Clone, Serialize, Default)]
+/// 8. PKCS#11 tokens
+///
+/// Secrets are redacted from the serialized and debug output.
+#[derive(Parser, Clone, Serialize, Default)]
 #[clap(next_help_heading = "Wallet options", about = None, long_about = None)]
 pub struct MultiWallet {
     /// The sender accounts.
@@ -103,6 +171,7 @@ pub struct MultiWallet {
         value_name = "RAW_PRIVATE_KEYS",
         value_parser = foundry_common::clap_helpers::strip_0x_prefix,
     )]
+    #[serde(serialize_with = "serialize_redacted")]
     pub private_keys: Option<Vec<String>>,
 
     /// Use the provided private key.
@@ -113,14 +182,21 @@ pub struct MultiWallet {
         value_name = "RAW_PRIVATE_KEY",
         value_parser = foundry_common::clap_helpers::strip_0x_prefix,
     )]
+    #[serde(serialize_with = "serialize_redacted")]
     pub private_key: Option<String>,
 
+    /// Skip the confirmation prompt when private keys are passed in cleartext.
+    #[clap(long, help_heading = "Wallet options - raw")]
+    pub yes: bool,
+
     /// Use the mnemonic phrases of mnemonic files at the specified paths.
     #[clap(long, alias = "mnemonic-paths", help_heading = "Wallet options - raw")]
+    #[serde(serialize_with = "serialize_redacted")]
     pub mnemonics: Option<Vec<String>>,
 
     /// Use a BIP39 passphrases for the mnemonic.
     #[clap(long, help_heading = "Wallet options - raw", value_name = "PASSPHRASE")]
+    #[serde(serialize_with = "serialize_redacted")]
     pub mnemonic_passphrases: Option<Vec<String>>,
 
     /// The wallet derivation path.
@@ -176,6 +252,7 @@ pub struct MultiWallet {
         requires = "keystore_paths",
         value_name = "PASSWORDS"
     )]
+    #[serde(serialize_with = "serialize_redacted")]
     pub keystore_passwords: Option<Vec<String>>,
 
     /// The keystore password file path.
@@ -201,6 +278,34 @@ pub struct MultiWallet {
     /// Use AWS Key Management Service.
     #[clap(long, help_heading = "Wallet options - remote")]
     pub aws: bool,
+
+    #[clap(flatten)]
+    pub pkcs11: Pkcs11Opts,
+}
+
+impl fmt::Debug for MultiWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redact = |secret: bool| secret.then_some(REDACTED);
+        f.debug_struct("MultiWallet")
+            .field("froms", &self.froms)
+            .field("interactives", &self.interactives)
+            .field("private_keys", &redact(self.private_keys.is_some()))
+            .field("private_key", &redact(self.private_key.is_some()))
+            .field("yes", &self.yes)
+            .field("mnemonics", &redact(self.mnemonics.is_some()))
+            .field("mnemonic_passphrases", &redact(self.mnemonic_passphrases.is_some()))
+            .field("hd_paths", &self.hd_paths)
+            .field("mnemonic_indexes", &self.mnemonic_indexes)
+            .field("keystore_paths", &self.keystore_paths)
+            .field("keystore_account_names", &self.keystore_account_names)
+            .field("keystore_passwords", &redact(self.keystore_passwords.is_some()))
+            .field("keystore_password_files", &self.keystore_password_files)
+            .field("ledger", &self.ledger)
+            .field("trezor", &self.trezor)
+            .field("aws", &self.aws)
+            .field("pkcs11", &self.pkcs11)
+            .finish()
+    }
 }
 
 impl WalletTrait for MultiWallet {
@@ -234,6 +339,7 @@ impl MultiWallet {
                 self.mnemonics()?,
                 self.keystores()?,
                 self.aws_signers(chain).await?,
+                self.pkcs11.signers(chain)?,
                 (!script_wallets.is_empty()).then(|| script_wallets.to_vec())
             ],
             for wallet in wallets.into_iter() {
@@ -283,6 +389,7 @@ impl MultiWallet {
 
     pub fn private_keys(&self) -> Result<Option<Vec<LocalWallet>>> {
         if let Some(private_keys) = &self.private_keys {
+            confirm_cleartext_private_keys(private_keys, self.yes)?;
             let mut wallets = vec![];
             for private_key in private_keys.iter() {
                 wallets.push(self.get_from_private_key(private_key.trim())?);
@@ -440,10 +547,42 @@ impl MultiWallet {
     }
 }
 
+/// Returns the history files of the common shells: `$HISTFILE`, bash, zsh and fish.
+fn shell_history_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Some(histfile) = std::env::var_os("HISTFILE") {
+        files.push(PathBuf::from(histfile));
+    }
+    if let Some(home) = dirs_next::home_dir() {
+        files.extend(
+            [".bash_history", ".zsh_history", ".local/share/fish/fish_history"]
+                .into_iter()
+                .map(|file| home.join(file)),
+        );
+    }
+    files.dedup();
+    files
+}
+
+/// Returns the first of the history files that contains the private key.
+fn find_in_shell_history<'a>(private_key: &str, history_files: &'a [PathBuf]) -> Option<&'a Path> {
+    let private_key = private_key.trim().trim_start_matches("0x");
+    if private_key.is_empty() {
+        return None
+    }
+    history_files
+        .iter()
+        .find(|file| {
+            std::fs::read(file)
+                .map(|history| String::from_utf8_lossy(&history).contains(private_key))
+                .unwrap_or_default()
+        })
+        .map(PathBuf::as_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn parse_keystore_args() {
@@ -487,6 +626,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn redacts_secrets() {
+        let private_key = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+        let args: MultiWallet = MultiWallet::parse_from([
+            "foundry-cli",
+            "--private-keys",
+            private_key,
+            "--password",
+            "hunter2",
+            "--keystore",
+            "my/keystore/path",
+            "--yes",
+        ]);
+        assert!(args.yes);
+
+        let json = serde_json::to_string(&args).unwrap();
+        let debug = format!("{args:?}");
+        for output in [json, debug] {
+            assert!(!output.contains(private_key), "{output}");
+            assert!(!output.contains("hunter2"), "{output}");
+            assert!(output.contains(REDACTED), "{output}");
+            assert!(output.contains("my/keystore/path"), "{output}");
+        }
+    }
+
+    #[test]
+    fn finds_private_keys_in_shell_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = dir.path().join(".bash_history");
+        std::fs::write(
+            &history,
+            "ls\ncast send --private-key 0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80\n",
+        )
+        .unwrap();
+        let files = vec![dir.path().join(".zsh_history"), history.clone()];
+
+        assert_eq!(
+            find_in_shell_history(
+                "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+                &files
+            ),
+            Some(history.as_path())
+        );
+        assert_eq!(
+            find_in_shell_history(
+                "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+                &files
+            ),
+            None
+        );
+    }
+
     // https://github.com/foundry-rs/foundry/issues/5179
     #[test]
     fn should_not_require_the_mnemonics_flag_with_mnemonic_indexes() {
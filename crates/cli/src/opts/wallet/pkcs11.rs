@@ -0,0 +1,321 @@
+//! Signer backed by a PKCS#11 token, e.g. a YubiKey (PIV) or an HSM.
+
+use async_trait::async_trait;
+use clap::Parser;
+use cryptoki::{
+    context::{CInitializeArgs, Pkcs11},
+    mechanism::Mechanism,
+    object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle},
+    session::{Session, UserType},
+    types::AuthPin,
+};
+use ethers_core::{
+    k256::ecdsa::{RecoveryId, Signature as KSig, VerifyingKey},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        Address, Signature, H256, U256,
+    },
+    utils::{hash_message, keccak256},
+};
+use ethers_signers::{to_eip155_v, Signer};
+use serde::Serialize;
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// PKCS#11 signer options, shared by [Wallet](super::Wallet) and
+/// [MultiWallet](super::MultiWallet).
+#[derive(Parser, Default, Clone, Serialize)]
+#[clap(next_help_heading = "Wallet options - PKCS#11", about = None, long_about = None)]
+pub struct Pkcs11Opts {
+    /// Use a key stored on a PKCS#11 token, through the given PKCS#11 module.
+    ///
+    /// For example `/usr/lib/libykcs11.so` for YubiKeys.
+    #[clap(long = "pkcs11", value_name = "MODULE", env = "ETH_PKCS11_MODULE")]
+    pub module: Option<PathBuf>,
+
+    /// The id of the token slot to use.
+    ///
+    /// Defaults to the first slot with a token present.
+    #[clap(long = "pkcs11-slot", value_name = "SLOT", requires = "module")]
+    pub slot: Option<u64>,
+
+    /// The user PIN of the token.
+    ///
+    /// If not provided, it is prompted for interactively.
+    #[clap(long = "pkcs11-pin", value_name = "PIN", env = "ETH_PKCS11_PIN", requires = "module")]
+    #[serde(serialize_with = "super::multi_wallet::serialize_redacted")]
+    pub pin: Option<String>,
+
+    /// The labels of the secp256k1 keys to use.
+    ///
+    /// If not provided, all secp256k1 keys found on the token are used.
+    #[clap(long = "pkcs11-key-label", value_name = "LABEL", requires = "module")]
+    pub key_labels: Option<Vec<String>>,
+}
+
+impl fmt::Debug for Pkcs11Opts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkcs11Opts")
+            .field("module", &self.module)
+            .field("slot", &self.slot)
+            .field("pin", &self.pin.as_ref().map(|_| super::multi_wallet::REDACTED))
+            .field("key_labels", &self.key_labels)
+            .finish()
+    }
+}
+
+impl Pkcs11Opts {
+    /// Opens a session to the configured token and returns a signer for every selected key.
+    ///
+    /// Returns `Ok(None)` if no PKCS#11 module was provided.
+    pub fn signers(&self, chain_id: u64) -> eyre::Result<Option<Vec<Pkcs11Signer>>> {
+        let Some(module) = &self.module else { return Ok(None) };
+
+        let pkcs11 = Pkcs11::new(module)?;
+        pkcs11.initialize(CInitializeArgs::OsThreads)?;
+
+        let slots = pkcs11.get_slots_with_token()?;
+        let slot = match self.slot {
+            Some(id) => *slots
+                .iter()
+                .find(|slot| slot.id() == id)
+                .ok_or_else(|| eyre::eyre!("no token present in PKCS#11 slot {id}"))?,
+            None => *slots.first().ok_or_else(|| eyre::eyre!("no PKCS#11 token found"))?,
+        };
+
+        let pin = match &self.pin {
+            Some(pin) => pin.clone(),
+            None => rpassword::prompt_password("Enter PKCS#11 PIN: ")?,
+        };
+
+        // the login state is shared by all sessions of the application, so a single session is
+        // shared by all signers
+        let session = pkcs11.open_ro_session(slot)?;
+        session.login(UserType::User, Some(&AuthPin::new(pin)))?;
+
+        let keys = match &self.key_labels {
+            Some(labels) => labels
+                .iter()
+                .map(|label| {
+                    find_keys(&session, Some(label))?
+                        .into_iter()
+                        .next()
+                        .ok_or_else(|| eyre::eyre!("no secp256k1 key labeled `{label}` found"))
+                })
+                .collect::<eyre::Result<Vec<_>>>()?,
+            None => find_keys(&session, None)?,
+        };
+        if keys.is_empty() {
+            eyre::bail!("no secp256k1 keys found on the PKCS#11 token");
+        }
+
+        let session = Arc::new(Mutex::new(session));
+        let signers = keys
+            .into_iter()
+            .map(|(private, public)| {
+                Pkcs11Signer::new(session.clone(), private, public, chain_id).map_err(Into::into)
+            })
+            .collect::<eyre::Result<_>>()?;
+
+        Ok(Some(signers))
+    }
+}
+
+/// DER encoded OID of the secp256k1 curve, as stored in `CKA_EC_PARAMS`
+const SECP256K1_OID: [u8; 7] = [0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+/// Returns the `(private, public)` key pairs on the token that use the secp256k1 curve.
+///
+/// Keys are matched by `CKA_ID`, and optionally filtered by `CKA_LABEL`.
+fn find_keys(
+    session: &Session,
+    label: Option<&str>,
+) -> eyre::Result<Vec<(ObjectHandle, ObjectHandle)>> {
+    let mut template = vec![
+        Attribute::Class(ObjectClass::PUBLIC_KEY),
+        Attribute::KeyType(KeyType::EC),
+        Attribute::EcParams(SECP256K1_OID.to_vec()),
+    ];
+    if let Some(label) = label {
+        template.push(Attribute::Label(label.as_bytes().to_vec()));
+    }
+
+    let mut keys = vec![];
+    for public in session.find_objects(&template)? {
+        let id =
+            session.get_attributes(public, &[AttributeType::Id])?.into_iter().find_map(|attr| {
+                match attr {
+                    Attribute::Id(id) => Some(id),
+                    _ => None,
+                }
+            });
+        let Some(id) = id else { continue };
+        let private = session.find_objects(&[
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::KeyType(KeyType::EC),
+            Attribute::Id(id),
+        ])?;
+        if let Some(private) = private.first() {
+            keys.push((*private, public));
+        }
+    }
+    Ok(keys)
+}
+
+/// Errors of the [Pkcs11Signer]
+#[derive(Debug, thiserror::Error)]
+pub enum Pkcs11SignerError {
+    #[error(transparent)]
+    Pkcs11(#[from] cryptoki::error::Error),
+    #[error(transparent)]
+    Ecdsa(#[from] ethers_core::k256::ecdsa::Error),
+    #[error(transparent)]
+    Eip712(#[from] ethers_core::types::transaction::eip712::Eip712Error),
+    #[error("invalid EC point returned by the PKCS#11 token")]
+    InvalidPublicKey,
+    #[error("the PKCS#11 session is poisoned")]
+    Poisoned,
+}
+
+/// A [Signer] that signs with a secp256k1 key stored on a PKCS#11 token.
+///
+/// The key never leaves the token, only 32 byte digests are sent to it for signing.
+#[derive(Debug)]
+pub struct Pkcs11Signer {
+    session: Arc<Mutex<Session>>,
+    key: ObjectHandle,
+    pubkey: VerifyingKey,
+    address: Address,
+    chain_id: u64,
+}
+
+impl Pkcs11Signer {
+    /// Creates a new signer, deriving its address from the public key stored on the token.
+    pub fn new(
+        session: Arc<Mutex<Session>>,
+        key: ObjectHandle,
+        public: ObjectHandle,
+        chain_id: u64,
+    ) -> Result<Self, Pkcs11SignerError> {
+        let point = session
+            .lock()
+            .map_err(|_| Pkcs11SignerError::Poisoned)?
+            .get_attributes(public, &[AttributeType::EcPoint])?
+            .into_iter()
+            .find_map(|attr| match attr {
+                Attribute::EcPoint(point) => Some(point),
+                _ => None,
+            })
+            .ok_or(Pkcs11SignerError::InvalidPublicKey)?;
+        let pubkey = decode_ec_point(&point)?;
+        let encoded = pubkey.to_encoded_point(false);
+        let address = Address::from_slice(&keccak256(&encoded.as_bytes()[1..])[12..]);
+
+        Ok(Self { session, key, pubkey, address, chain_id })
+    }
+
+    /// Signs the given digest, returning a signature with `v` set to `recovery_id + 27`.
+    pub fn sign_digest(&self, digest: H256) -> Result<Signature, Pkcs11SignerError> {
+        let raw = {
+            let session = self.session.lock().map_err(|_| Pkcs11SignerError::Poisoned)?;
+            session.sign(&Mechanism::Ecdsa, self.key, digest.as_bytes())?
+        };
+        let mut sig = KSig::from_slice(&raw)?;
+        // tokens are not required to produce low-s signatures, which Ethereum mandates
+        if let Some(normalized) = sig.normalize_s() {
+            sig = normalized;
+        }
+
+        let recovery_id = (0..=1)
+            .filter_map(RecoveryId::from_byte)
+            .find(|id| {
+                VerifyingKey::recover_from_prehash(digest.as_bytes(), &sig, *id)
+                    .map_or(false, |key| key == self.pubkey)
+            })
+            .ok_or(Pkcs11SignerError::InvalidPublicKey)?;
+
+        let (r, s) = sig.split_bytes();
+        Ok(Signature {
+            r: U256::from_big_endian(&r),
+            s: U256::from_big_endian(&s),
+            v: recovery_id.to_byte() as u64 + 27,
+        })
+    }
+}
+
+/// Decodes a `CKA_EC_POINT`, which is an uncompressed point wrapped in a DER OCTET STRING.
+fn decode_ec_point(point: &[u8]) -> Result<VerifyingKey, Pkcs11SignerError> {
+    let raw = match point {
+        // DER OCTET STRING containing the 65 byte uncompressed point
+        [0x04, 0x41, rest @ ..] if rest.len() == 0x41 => rest,
+        // some modules return the raw point
+        _ => point,
+    };
+    VerifyingKey::from_sec1_bytes(raw).map_err(|_| Pkcs11SignerError::InvalidPublicKey)
+}
+
+#[async_trait]
+impl Signer for Pkcs11Signer {
+    type Error = Pkcs11SignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_digest(hash_message(message))
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx_with_chain = tx.clone();
+        let chain_id = tx_with_chain.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        tx_with_chain.set_chain_id(chain_id);
+
+        let mut sig = self.sign_digest(tx_with_chain.sighash())?;
+        sig.v = to_eip155_v(sig.v as u8 - 27, chain_id);
+        Ok(sig)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let digest = payload.encode_eip712().map_err(|e| {
+            ethers_core::types::transaction::eip712::Eip712Error::Message(e.to_string())
+        })?;
+        self.sign_digest(digest.into())
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::k256::ecdsa::SigningKey;
+
+    #[test]
+    fn decodes_der_wrapped_ec_point() {
+        let key = SigningKey::from_slice(&[1u8; 32]).unwrap();
+        let point = key.verifying_key().to_encoded_point(false);
+
+        let mut der = vec![0x04, point.as_bytes().len() as u8];
+        der.extend_from_slice(point.as_bytes());
+
+        assert_eq!(&decode_ec_point(&der).unwrap(), key.verifying_key());
+        assert_eq!(&decode_ec_point(point.as_bytes()).unwrap(), key.verifying_key());
+    }
+}
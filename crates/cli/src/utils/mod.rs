@@ -2,8 +2,11 @@ use alloy_json_abi::JsonAbi;
 use alloy_primitives::U256;
 use ethers_core::types::TransactionReceipt;
 use ethers_providers::Middleware;
-use eyre::{ContextCompat, Result};
-use foundry_common::{types::ToAlloy, units::format_units};
+use eyre::{Result, WrapErr};
+use foundry_common::{
+    types::ToAlloy,
+    units::{format_units, parse_units, ConversionError, Units},
+};
 use foundry_config::{Chain, Config};
 use std::{
     ffi::OsStr,
@@ -122,19 +125,33 @@ where
 
 /// Parses an ether value from a string.
 ///
-/// The amount can be tagged with a unit, e.g. "1ether".
+/// The amount can be tagged with a unit, e.g. "1ether", "0.5 ether" or "10gwei", and is parsed
+/// exactly: an amount that isn't a whole number of wei, e.g. "0.5wei", is an error instead of
+/// being rounded.
 ///
 /// If the string represents an untagged amount (e.g. "100") then
 /// it is interpreted as wei.
 pub fn parse_ether_value(value: &str) -> Result<U256> {
-    Ok(if value.starts_with("0x") {
-        U256::from_str_radix(value, 16)?
-    } else {
-        alloy_dyn_abi::DynSolType::coerce_str(&alloy_dyn_abi::DynSolType::Uint(256), value)?
-            .as_uint()
-            .wrap_err("Could not parse ether value from string")?
-            .0
-    })
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix("0x") {
+        return Ok(U256::from_str_radix(hex, 16)?)
+    }
+
+    // the unit is the trailing alphabetic part, if any, so that "1e18" stays in wei
+    let amount = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let unit = &value[amount.len()..];
+    let amount = amount.trim();
+    let units: Units = if unit.is_empty() { Units::Wei } else { unit.parse()? };
+    if amount.starts_with('-') {
+        eyre::bail!("Ether value `{value}` can't be negative")
+    }
+    match parse_units(amount, units) {
+        Ok(amount) => Ok(amount.into()),
+        Err(ConversionError::PrecisionLoss(_)) => {
+            eyre::bail!("Ether value `{value}` is not a whole number of wei")
+        }
+        Err(err) => Err(err).wrap_err_with(|| format!("Could not parse ether value `{value}`")),
+    }
 }
 
 /// Parses a `Duration` from a &str
@@ -674,4 +691,23 @@ mod tests {
         assert_eq!(env::var("TESTCWDKEY").unwrap(), "cwd_val");
         assert_eq!(env::var("TESTPRJKEY").unwrap(), "prj_val");
     }
+
+    #[test]
+    fn can_parse_ether_value() {
+        let ether = U256::from(10u64).pow(U256::from(18u64));
+        assert_eq!(parse_ether_value("100").unwrap(), U256::from(100u64));
+        assert_eq!(parse_ether_value("0x64").unwrap(), U256::from(100u64));
+        assert_eq!(parse_ether_value("1e18").unwrap(), ether);
+        assert_eq!(parse_ether_value("1ether").unwrap(), ether);
+        assert_eq!(parse_ether_value("0.5 ether").unwrap(), ether / U256::from(2u64));
+        assert_eq!(parse_ether_value("1.5gwei").unwrap(), U256::from(1_500_000_000u64));
+        assert_eq!(parse_ether_value("1000wei").unwrap(), U256::from(1000u64));
+
+        let err = parse_ether_value("0.5wei").unwrap_err().to_string();
+        assert!(err.contains("not a whole number of wei"), "{err}");
+        assert!(parse_ether_value("1.0000000000000000001ether").is_err());
+        assert!(parse_ether_value("-1ether").is_err());
+        assert!(parse_ether_value("1foo").is_err());
+        assert!(parse_ether_value("ether").is_err());
+    }
 }
@@ -0,0 +1,205 @@
+//! Versioned schema of the build artifacts.
+//!
+//! Every artifact written by `forge build` is stamped with a `schemaVersion`, see
+//! [ARTIFACT_SCHEMA_VERSION]. The fields of [VersionedArtifact] are stable within a schema version,
+//! so external tools can rely on them across releases.
+//!
+//! Readers are forward-compatible: artifacts of older versions are migrated on the fly, and
+//! artifacts of newer versions are read as is, with unknown fields collected in
+//! [VersionedArtifact::other]. Artifacts on disk are migrated with `forge artifacts migrate`.
+
+use crate::fs;
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::hex;
+use eyre::Result;
+use foundry_compilers::{
+    artifacts::{CompactBytecode, CompactDeployedBytecode, DevDoc, StorageLayout},
+    Project, ProjectCompileOutput,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{collections::BTreeMap, path::Path};
+
+/// The key of the schema version in artifacts.
+pub const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+/// The current version of the artifact schema.
+///
+/// Artifacts without a version predate the schema and have version `0`.
+pub const ARTIFACT_SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+/// The migrations of the artifact schema, where the migration at index `i` migrates an artifact
+/// from version `i` to `i + 1`.
+const MIGRATIONS: &[fn(&mut Map<String, Value>) -> Result<()>] = &[migrate_v1];
+
+/// The stable fields of an artifact.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionedArtifact {
+    /// The version of the schema the artifact was written with.
+    #[serde(default)]
+    pub schema_version: u64,
+    /// The ABI of the contract.
+    #[serde(default)]
+    pub abi: JsonAbi,
+    /// The creation bytecode, with its source map and link references.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytecode: Option<CompactBytecode>,
+    /// The runtime bytecode, with its source map, link references and immutable references.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployed_bytecode: Option<CompactDeployedBytecode>,
+    /// The selectors of the functions, by signature, as hex without `0x` prefix.
+    #[serde(default)]
+    pub method_identifiers: BTreeMap<String, String>,
+    /// The storage layout, if it was requested with `extra_output`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_layout: Option<StorageLayout>,
+    /// The developer documentation, if it was requested with `extra_output`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub devdoc: Option<DevDoc>,
+    /// All other fields, which are not covered by the schema, e.g. the AST, or were added by a
+    /// newer schema version.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl VersionedArtifact {
+    /// Reads the artifact at the given path, migrating it to the current schema if it's older.
+    pub fn read(path: &Path) -> Result<Self> {
+        let mut artifact: Map<String, Value> = fs::read_json_file(path)?;
+        migrate(&mut artifact)?;
+        Ok(serde_json::from_value(artifact.into())?)
+    }
+}
+
+/// Returns the schema version of an artifact.
+pub fn schema_version(artifact: &Map<String, Value>) -> u64 {
+    artifact.get(SCHEMA_VERSION_KEY).and_then(Value::as_u64).unwrap_or_default()
+}
+
+/// Migrates an artifact to the current schema version.
+///
+/// Returns whether the artifact was changed. Artifacts of newer versions are left as they are.
+pub fn migrate(artifact: &mut Map<String, Value>) -> Result<bool> {
+    let version = schema_version(artifact);
+    if version >= ARTIFACT_SCHEMA_VERSION {
+        return Ok(false)
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(artifact)?;
+    }
+    artifact.insert(SCHEMA_VERSION_KEY.to_string(), ARTIFACT_SCHEMA_VERSION.into());
+    Ok(true)
+}
+
+/// Migrates the artifact file at the given path to the current schema version.
+///
+/// Returns whether the file was rewritten.
+pub fn migrate_artifact_file(path: &Path) -> Result<bool> {
+    let mut artifact: Map<String, Value> = fs::read_json_file(path)?;
+    if !migrate(&mut artifact)? {
+        return Ok(false)
+    }
+    fs::write_json_file(path, &artifact)?;
+    Ok(true)
+}
+
+/// Stamps all artifacts that were just compiled with the current schema version and the given
+/// fields, e.g. the settings fingerprint.
+///
+/// Every artifact is rewritten at most once, and only if one of the stamps changed.
+pub fn write_stamps(
+    project: &Project,
+    output: &ProjectCompileOutput,
+    stamps: &Map<String, Value>,
+) -> Result<()> {
+    if project.no_artifacts {
+        return Ok(())
+    }
+    for file in output.compiled_artifacts().artifact_files() {
+        let path = project.paths.artifacts.join(&file.file);
+        let mut artifact: Map<String, Value> = fs::read_json_file(&path)?;
+        let mut changed = schema_version(&artifact) != ARTIFACT_SCHEMA_VERSION;
+        artifact.insert(SCHEMA_VERSION_KEY.to_string(), ARTIFACT_SCHEMA_VERSION.into());
+        for (key, value) in stamps {
+            if artifact.get(key) != Some(value) {
+                artifact.insert(key.clone(), value.clone());
+                changed = true;
+            }
+        }
+        if changed {
+            fs::write_json_file(&path, &artifact)?;
+        }
+    }
+    Ok(())
+}
+
+/// Migrates unversioned artifacts to version `1`.
+///
+/// Unversioned artifacts may lack the method identifiers, which are derived from the ABI, and
+/// may contain the bytecode as a plain hex string instead of an object.
+fn migrate_v1(artifact: &mut Map<String, Value>) -> Result<()> {
+    let abi = artifact.entry("abi").or_insert_with(|| Value::Array(Vec::new())).clone();
+    if !artifact.contains_key("methodIdentifiers") {
+        let abi: JsonAbi = serde_json::from_value(abi)?;
+        let identifiers = abi
+            .functions()
+            .map(|f| (f.signature(), Value::String(hex::encode(f.selector()))))
+            .collect::<Map<_, _>>();
+        artifact.insert("methodIdentifiers".to_string(), identifiers.into());
+    }
+    for key in ["bytecode", "deployedBytecode"] {
+        if let Some(Value::String(object)) = artifact.get(key).cloned() {
+            let object = serde_json::json!({ "object": object, "linkReferences": {} });
+            artifact.insert(key.to_string(), object);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrates_unversioned_artifacts() {
+        let mut artifact = json!({
+            "abi": [{
+                "type": "function",
+                "name": "increment",
+                "inputs": [],
+                "outputs": [],
+                "stateMutability": "nonpayable"
+            }],
+            "bytecode": "0x6080",
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+
+        assert!(migrate(&mut artifact).unwrap());
+        assert_eq!(schema_version(&artifact), ARTIFACT_SCHEMA_VERSION);
+        assert_eq!(artifact["methodIdentifiers"], json!({ "increment()": "d09de08a" }));
+        assert_eq!(artifact["bytecode"]["object"], "0x6080");
+        assert!(!migrate(&mut artifact).unwrap());
+
+        let artifact: VersionedArtifact = serde_json::from_value(artifact.into()).unwrap();
+        assert_eq!(artifact.method_identifiers["increment()"], "d09de08a");
+        assert!(artifact.bytecode.is_some());
+    }
+
+    #[test]
+    fn reads_newer_artifacts() {
+        let version = ARTIFACT_SCHEMA_VERSION + 1;
+        let mut artifact = json!({ "schemaVersion": version, "abi": [], "newField": true })
+            .as_object()
+            .unwrap()
+            .clone();
+        assert!(!migrate(&mut artifact).unwrap());
+
+        let artifact: VersionedArtifact = serde_json::from_value(artifact.into()).unwrap();
+        assert_eq!(artifact.schema_version, version);
+        assert_eq!(artifact.other["newField"], true);
+    }
+}
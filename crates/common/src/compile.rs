@@ -1,15 +1,25 @@
 //! Support for compiling [foundry_compilers::Project]
-use crate::{compact_to_contract, glob::GlobMatcher, term, TestFunctionExt};
+use crate::{
+    artifacts, compact_to_contract,
+    diagnostics::format_compiler_errors,
+    fs,
+    glob::GlobMatcher,
+    term,
+    vyper::{self, VyperCompileOutput},
+    TestFunctionExt,
+};
+use alloy_primitives::{keccak256, B256};
 use comfy_table::{presets::ASCII_MARKDOWN, *};
 use eyre::Result;
 use foundry_block_explorers::contract::Metadata;
 use foundry_compilers::{
-    artifacts::{BytecodeObject, ContractBytecodeSome},
+    artifacts::{BytecodeObject, ContractBytecodeSome, Settings},
     remappings::Remapping,
     report::NoReporter,
     Artifact, ArtifactId, FileFilter, Graph, Project, ProjectCompileOutput, ProjectPathsConfig,
     Solc, SolcConfig,
 };
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
     convert::Infallible,
@@ -18,6 +28,7 @@ use std::{
     result,
     str::FromStr,
 };
+use yansi::Paint;
 
 /// Helper type to configure how to compile a project
 ///
@@ -29,6 +40,8 @@ pub struct ProjectCompiler {
     print_names: bool,
     /// whether to also print the contract sizes
     print_sizes: bool,
+    /// whether to fail if a printed contract exceeds one of the size limits
+    check_sizes: bool,
     /// files to exclude
     filters: Vec<SkipBuildFilter>,
 }
@@ -45,7 +58,16 @@ impl ProjectCompiler {
         print_sizes: bool,
         filters: Vec<SkipBuildFilter>,
     ) -> Self {
-        Self { print_names, print_sizes, filters }
+        Self { print_names, print_sizes, check_sizes: false, filters }
+    }
+
+    /// Sets whether to fail if a contract exceeds the EIP-170 runtime or the EIP-3860 initcode
+    /// size limit, excluding test and script contracts.
+    ///
+    /// This only has an effect if the sizes are printed.
+    pub fn check_sizes(mut self, check_sizes: bool) -> Self {
+        self.check_sizes = check_sizes;
+        self
     }
 
     /// Compiles the project with [`Project::compile()`]
@@ -90,7 +112,7 @@ impl ProjectCompiler {
     where
         F: FnOnce(&Project) -> Result<ProjectCompileOutput>,
     {
-        if !project.paths.has_input_files() {
+        if !project.paths.has_input_files() && !vyper::has_vyper_sources(&project.paths) {
             println!("Nothing to compile");
             // nothing to do here
             std::process::exit(0);
@@ -106,22 +128,37 @@ impl ProjectCompiler {
 
         if output.has_compiler_errors() {
             warn!("compiled with errors");
-            eyre::bail!(output.to_string())
-        } else if output.is_unchanged() {
-            println!("No files changed, compilation skipped");
-            self.handle_output(&output);
+            eyre::bail!(format_compiler_errors(&output, project))
+        }
+
+        write_artifact_stamps(project, &output)?;
+
+        let vyper_output =
+            vyper::compile_project(project, &SkipBuildFilters(self.filters.clone()))?;
+
+        if output.is_unchanged() {
+            // a project can consist of Vyper sources only
+            if project.paths.has_input_files() {
+                println!("No files changed, compilation skipped");
+            }
         } else {
             // print the compiler output / warnings
             println!("{output}");
-
-            self.handle_output(&output);
         }
+        if let Some(vyper_output) = &vyper_output {
+            println!("{vyper_output}");
+        }
+        self.handle_output(&output, vyper_output.as_ref())?;
 
         Ok(output)
     }
 
     /// If configured, this will print sizes or names
-    fn handle_output(&self, output: &ProjectCompileOutput) {
+    fn handle_output(
+        &self,
+        output: &ProjectCompileOutput,
+        vyper: Option<&VyperCompileOutput>,
+    ) -> Result<()> {
         // print any sizes or names
         if self.print_names {
             let mut artifacts: BTreeMap<_, Vec<_>> = BTreeMap::new();
@@ -137,37 +174,27 @@ impl ProjectCompiler {
                     println!("    - {name}");
                 }
             }
+            if let Some(vyper) = vyper {
+                println!("  vyper version: {}", vyper.version);
+                for (_, name) in vyper.artifacts.keys() {
+                    println!("    - {name}");
+                }
+            }
         }
         if self.print_sizes {
             // add extra newline if names were already printed
             if self.print_names {
                 println!();
             }
-            let mut size_report = SizeReport { contracts: BTreeMap::new() };
-            let artifacts: BTreeMap<_, _> = output.artifacts().collect();
-            for (name, artifact) in artifacts {
-                let size = deployed_contract_size(artifact).unwrap_or_default();
-
-                let dev_functions =
-                    artifact.abi.as_ref().map(|abi| abi.functions()).into_iter().flatten().filter(
-                        |&func| {
-                            func.name.is_test() ||
-                                func.name == "IS_TEST" ||
-                                func.name == "IS_SCRIPT"
-                        },
-                    );
-
-                let is_dev_contract = dev_functions.count() > 0;
-                size_report.contracts.insert(name, ContractInfo { size, is_dev_contract });
-            }
-
+            let mut size_report = SizeReport::new(output);
+            size_report.exclude_dev_contracts();
             println!("{size_report}");
 
-            // exit with error if any contract exceeds the size limit, excluding test contracts.
-            if size_report.exceeds_size_limit() {
-                std::process::exit(1);
+            if self.check_sizes {
+                size_report.check()?;
             }
         }
+        Ok(())
     }
 }
 
@@ -176,7 +203,10 @@ impl ProjectCompiler {
 pub struct ContractSources(pub HashMap<String, HashMap<u32, (String, ContractBytecodeSome)>>);
 
 // https://eips.ethereum.org/EIPS/eip-170
-const CONTRACT_SIZE_LIMIT: usize = 24576;
+pub const CONTRACT_SIZE_LIMIT: usize = 24576;
+
+// https://eips.ethereum.org/EIPS/eip-3860
+pub const CONTRACT_INITCODE_SIZE_LIMIT: usize = 2 * CONTRACT_SIZE_LIMIT;
 
 /// Contracts with info about their size
 pub struct SizeReport {
@@ -185,6 +215,26 @@ pub struct SizeReport {
 }
 
 impl SizeReport {
+    /// Collects the sizes of all artifacts in the given compiler output.
+    pub fn new(output: &ProjectCompileOutput) -> Self {
+        let mut contracts = BTreeMap::new();
+        for (name, artifact) in output.artifacts() {
+            let size = deployed_contract_size(artifact).unwrap_or_default();
+            let init_size = initcode_size(artifact).unwrap_or_default();
+
+            let dev_functions =
+                artifact.abi.as_ref().map(|abi| abi.functions()).into_iter().flatten().filter(
+                    |&func| {
+                        func.name.is_test() || func.name == "IS_TEST" || func.name == "IS_SCRIPT"
+                    },
+                );
+
+            let is_dev_contract = dev_functions.count() > 0;
+            contracts.insert(name, ContractInfo { size, init_size, is_dev_contract });
+        }
+        Self { contracts }
+    }
+
     /// Returns the size of the largest contract, excluding test contracts.
     pub fn max_size(&self) -> usize {
         let mut max_size = 0;
@@ -196,35 +246,74 @@ impl SizeReport {
         max_size
     }
 
+    /// Returns the initcode size of the largest contract, excluding test contracts.
+    pub fn max_init_size(&self) -> usize {
+        self.contracts
+            .values()
+            .filter(|contract| !contract.is_dev_contract)
+            .map(|contract| contract.init_size)
+            .max()
+            .unwrap_or_default()
+    }
+
     /// Returns true if any contract exceeds the size limit, excluding test contracts.
     pub fn exceeds_size_limit(&self) -> bool {
         self.max_size() > CONTRACT_SIZE_LIMIT
     }
+
+    /// Returns true if any contract exceeds the initcode size limit, excluding test contracts.
+    pub fn exceeds_initcode_size_limit(&self) -> bool {
+        self.max_init_size() > CONTRACT_INITCODE_SIZE_LIMIT
+    }
+
+    /// Removes the test and script contracts from the report.
+    pub fn exclude_dev_contracts(&mut self) {
+        self.contracts.retain(|_, contract| !contract.is_dev_contract);
+    }
+
+    /// Returns an error naming the contracts that exceed one of the size limits, excluding test
+    /// contracts.
+    pub fn check(&self) -> Result<()> {
+        let oversized = self
+            .contracts
+            .iter()
+            .filter(|(_, contract)| {
+                !contract.is_dev_contract &&
+                    (contract.size > CONTRACT_SIZE_LIMIT ||
+                        contract.init_size > CONTRACT_INITCODE_SIZE_LIMIT)
+            })
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        if !oversized.is_empty() {
+            eyre::bail!("contracts exceed the size limits: {}", oversized.join(", "))
+        }
+        Ok(())
+    }
 }
 
 impl Display for SizeReport {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         let mut table = Table::new();
         table.load_preset(ASCII_MARKDOWN);
-        table.set_header([
-            Cell::new("Contract").add_attribute(Attribute::Bold).fg(Color::Blue),
-            Cell::new("Size (kB)").add_attribute(Attribute::Bold).fg(Color::Blue),
-            Cell::new("Margin (kB)").add_attribute(Attribute::Bold).fg(Color::Blue),
-        ]);
+        table.set_header(
+            ["Contract", "Size (kB)", "Margin (kB)", "Initcode Size (kB)", "Initcode Margin (kB)"]
+                .map(|header| Cell::new(header).add_attribute(Attribute::Bold).fg(Color::Blue)),
+        );
 
-        let contracts = self.contracts.iter().filter(|(_, c)| !c.is_dev_contract && c.size > 0);
+        let contracts = self.contracts.iter().filter(|(_, c)| c.size > 0 || c.init_size > 0);
         for (name, contract) in contracts {
-            let margin = CONTRACT_SIZE_LIMIT as isize - contract.size as isize;
-            let color = match contract.size {
-                0..=17999 => Color::Reset,
-                18000..=CONTRACT_SIZE_LIMIT => Color::Yellow,
-                _ => Color::Red,
-            };
+            let ContractInfo { size, init_size, .. } = *contract;
+            let margin = CONTRACT_SIZE_LIMIT as isize - size as isize;
+            let init_margin = CONTRACT_INITCODE_SIZE_LIMIT as isize - init_size as isize;
+            let color = size_color(size, CONTRACT_SIZE_LIMIT);
+            let init_color = size_color(init_size, CONTRACT_INITCODE_SIZE_LIMIT);
 
             table.add_row([
-                Cell::new(name).fg(color),
-                Cell::new(contract.size as f64 / 1000.0).fg(color),
+                Cell::new(name).fg(if color == Color::Reset { init_color } else { color }),
+                Cell::new(size as f64 / 1000.0).fg(color),
                 Cell::new(margin as f64 / 1000.0).fg(color),
+                Cell::new(init_size as f64 / 1000.0).fg(init_color),
+                Cell::new(init_margin as f64 / 1000.0).fg(init_color),
             ]);
         }
 
@@ -233,10 +322,31 @@ impl Display for SizeReport {
     }
 }
 
+/// Yellow once a size is within 25% of the limit, red if it exceeds it
+fn size_color(size: usize, limit: usize) -> Color {
+    if size > limit {
+        Color::Red
+    } else if size * 4 >= limit * 3 {
+        Color::Yellow
+    } else {
+        Color::Reset
+    }
+}
+
 /// Returns the size of the deployed contract
 pub fn deployed_contract_size<T: Artifact>(artifact: &T) -> Option<usize> {
     let bytecode = artifact.get_deployed_bytecode_object()?;
-    let size = match bytecode.as_ref() {
+    Some(bytecode_size(bytecode.as_ref()))
+}
+
+/// Returns the size of the contract's creation code, excluding constructor arguments
+pub fn initcode_size<T: Artifact>(artifact: &T) -> Option<usize> {
+    let bytecode = artifact.get_bytecode_object()?;
+    Some(bytecode_size(bytecode.as_ref()))
+}
+
+fn bytecode_size(bytecode: &BytecodeObject) -> usize {
+    match bytecode {
         BytecodeObject::Bytecode(bytes) => bytes.len(),
         BytecodeObject::Unlinked(unlinked) => {
             // we don't need to account for placeholders here, because library placeholders take up
@@ -248,15 +358,16 @@ pub fn deployed_contract_size<T: Artifact>(artifact: &T) -> Option<usize> {
             // hex -> bytes
             size / 2
         }
-    };
-    Some(size)
+    }
 }
 
 /// How big the contract is and whether it is a dev contract where size limits can be neglected
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct ContractInfo {
     /// size of the contract in bytes
     pub size: usize,
+    /// size of the contract's creation code in bytes
+    pub init_size: usize,
     /// A development contract is either a Script or a Test contract.
     pub is_dev_contract: bool,
 }
@@ -287,10 +398,12 @@ pub fn compile_with_filter(
 /// Compiles the provided [`Project`] and does not throw if there's any compiler error
 /// Doesn't print anything to stdout, thus is "suppressed".
 pub fn try_suppress_compile(project: &Project) -> Result<ProjectCompileOutput> {
-    Ok(foundry_compilers::report::with_scoped(
+    let output = foundry_compilers::report::with_scoped(
         &foundry_compilers::report::Report::new(NoReporter::default()),
         || project.compile(),
-    )?)
+    )?;
+    write_artifact_stamps(project, &output)?;
+    Ok(output)
 }
 
 /// Compiles the provided [`Project`], throws if there's any compiler error and logs whether
@@ -300,8 +413,9 @@ pub fn suppress_compile(project: &Project) -> Result<ProjectCompileOutput> {
     let output = try_suppress_compile(project)?;
 
     if output.has_compiler_errors() {
-        eyre::bail!(output.to_string())
+        eyre::bail!(format_compiler_errors(&output, project))
     }
+    vyper::compile_project(project, &SkipBuildFilters(Vec::new()))?;
 
     Ok(output)
 }
@@ -315,7 +429,9 @@ pub fn suppress_compile_with_filter(
     if skip.is_empty() {
         suppress_compile(project)
     } else {
-        suppress_compile_sparse(project, SkipBuildFilters(skip))
+        let output = suppress_compile_sparse(project, SkipBuildFilters(skip.clone()))?;
+        vyper::compile_project(project, &SkipBuildFilters(skip))?;
+        Ok(output)
     }
 }
 
@@ -340,10 +456,12 @@ pub fn try_suppress_compile_sparse<F: FileFilter + 'static>(
     project: &Project,
     filter: F,
 ) -> Result<ProjectCompileOutput> {
-    Ok(foundry_compilers::report::with_scoped(
+    let output = foundry_compilers::report::with_scoped(
         &foundry_compilers::report::Report::new(NoReporter::default()),
         || project.compile_sparse(filter),
-    )?)
+    )?;
+    write_artifact_stamps(project, &output)?;
+    Ok(output)
 }
 
 /// Compiles the provided [`Project`], throws if there's any compiler error and logs whether
@@ -358,12 +476,197 @@ pub fn suppress_compile_sparse<F: FileFilter + 'static>(
     let output = try_suppress_compile_sparse(project, filter)?;
 
     if output.has_compiler_errors() {
-        eyre::bail!(output.to_string())
+        eyre::bail!(format_compiler_errors(&output, project))
     }
 
     Ok(output)
 }
 
+/// Stamps the artifacts that were just compiled with the schema version and the
+/// [settings_fingerprint], and records the fingerprint in the [SettingsFingerprints] of the
+/// project, unless the compilation failed.
+///
+/// Every compilation path goes through this, so artifacts are stamped regardless of the command
+/// that built them.
+fn write_artifact_stamps(project: &Project, output: &ProjectCompileOutput) -> Result<()> {
+    if output.has_compiler_errors() {
+        return Ok(())
+    }
+    let fingerprint = settings_fingerprint(&project.solc_config.settings);
+    let stamps = serde_json::Map::from_iter([(
+        SETTINGS_FINGERPRINT_KEY.to_string(),
+        serde_json::to_value(fingerprint)?,
+    )]);
+    artifacts::write_stamps(project, output, &stamps)?;
+    write_settings_fingerprints(project, output)?;
+    Ok(())
+}
+
+/// The key of the [settings_fingerprint] in artifacts.
+pub const SETTINGS_FINGERPRINT_KEY: &str = "settingsFingerprint";
+
+/// The file next to the cache file of the project that indexes the [settings_fingerprint]s of the
+/// artifacts, so that they don't have to be read on every build.
+pub const SETTINGS_FINGERPRINTS_FILE_NAME: &str = "settings-fingerprints.json";
+
+/// Returns the hash of the compiler settings that affect the bytecode of the artifacts: the
+/// optimizer, EVM version, IR pipeline, metadata and linked libraries.
+pub fn settings_fingerprint(settings: &Settings) -> B256 {
+    let settings = serde_json::json!({
+        "optimizer": settings.optimizer,
+        "evmVersion": settings.evm_version,
+        "viaIR": settings.via_ir,
+        "metadata": settings.metadata,
+        "libraries": settings.libraries,
+    });
+    keccak256(settings.to_string())
+}
+
+/// Stores the [settings_fingerprint] of the project for all artifacts that were just compiled, and
+/// checks that all cached artifacts were built with the same settings.
+///
+/// The fingerprints are looked up in the [SettingsFingerprints] of the project, and read from the
+/// artifact itself if it's not indexed, e.g. because the index was deleted.
+///
+/// Artifacts built with different settings, e.g. another optimizer or EVM version, are reported as
+/// warning, or as error if `deny` is set. Artifacts without a fingerprint are not checked.
+pub fn check_settings_fingerprints(
+    project: &Project,
+    output: &ProjectCompileOutput,
+    deny: bool,
+) -> Result<()> {
+    if project.no_artifacts {
+        return Ok(())
+    }
+    let mut fingerprints = write_settings_fingerprints(project, output)?;
+
+    let fingerprint = settings_fingerprint(&project.solc_config.settings);
+    let mut mismatched = Vec::new();
+    let mut indexed = false;
+    for file in output.cached_artifacts().artifact_files() {
+        let path = project.paths.artifacts.join(&file.file);
+        let cached = match fingerprints.get(&project.paths, &path) {
+            Some(cached) => Some(cached),
+            None => {
+                let cached = read_settings_fingerprint(&path)?;
+                if let Some(cached) = cached {
+                    indexed |= fingerprints.insert(&project.paths, &path, cached);
+                }
+                cached
+            }
+        };
+        if cached.is_some_and(|cached| cached != fingerprint) {
+            mismatched.push(path);
+        }
+    }
+    if indexed {
+        fingerprints.write(&project.paths)?;
+    }
+    if mismatched.is_empty() {
+        return Ok(())
+    }
+
+    mismatched.sort();
+    let paths = mismatched
+        .iter()
+        .map(|path| {
+            format!("  {}", path.strip_prefix(&project.paths.root).unwrap_or(path).display())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let msg = format!(
+        "{} artifact(s) were built with different compiler settings than the current config:\n\
+         {paths}\n\
+         Run `forge clean` or build with `--force` to rebuild them",
+        mismatched.len()
+    );
+    if deny {
+        eyre::bail!(msg)
+    }
+    eprintln!("{} {msg}", Paint::yellow("Warning:").bold());
+    Ok(())
+}
+
+/// Stores the [settings_fingerprint] of the project for all artifacts that were just compiled, and
+/// returns the fingerprints of all artifacts.
+pub fn write_settings_fingerprints(
+    project: &Project,
+    output: &ProjectCompileOutput,
+) -> Result<SettingsFingerprints> {
+    let mut fingerprints = SettingsFingerprints::read(&project.paths);
+    if project.no_artifacts {
+        return Ok(fingerprints)
+    }
+    let fingerprint = settings_fingerprint(&project.solc_config.settings);
+    let mut changed = false;
+    for file in output.compiled_artifacts().artifact_files() {
+        let path = project.paths.artifacts.join(&file.file);
+        changed |= fingerprints.insert(&project.paths, &path, fingerprint);
+    }
+    if changed {
+        fingerprints.write(&project.paths)?;
+    }
+    Ok(fingerprints)
+}
+
+/// Reads the [settings_fingerprint] of an artifact, if it has one.
+fn read_settings_fingerprint(path: &Path) -> Result<Option<B256>> {
+    #[derive(Deserialize)]
+    struct Fingerprinted {
+        #[serde(rename = "settingsFingerprint")]
+        fingerprint: Option<B256>,
+    }
+
+    let artifact: Fingerprinted = fs::read_json_file(path)?;
+    Ok(artifact.fingerprint)
+}
+
+/// The index of the [settings_fingerprint]s that are stored in the artifacts of a project, by the
+/// path of the artifact relative to the artifacts directory.
+///
+/// Artifacts compiled before the fingerprints were recorded don't have one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettingsFingerprints {
+    artifacts: BTreeMap<PathBuf, B256>,
+}
+
+impl SettingsFingerprints {
+    /// Returns the path of the fingerprints file of the project.
+    fn path(paths: &ProjectPathsConfig) -> PathBuf {
+        paths.cache.with_file_name(SETTINGS_FINGERPRINTS_FILE_NAME)
+    }
+
+    /// Reads the fingerprints file of the project, or returns no fingerprints if there is none.
+    pub fn read(paths: &ProjectPathsConfig) -> Self {
+        fs::read_json_file(&Self::path(paths)).unwrap_or_default()
+    }
+
+    /// Writes the fingerprints file of the project.
+    pub fn write(&self, paths: &ProjectPathsConfig) -> Result<()> {
+        let path = Self::path(paths);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write_json_file(&path, self)?;
+        Ok(())
+    }
+
+    /// Returns the fingerprint of an artifact, if it has one.
+    pub fn get(&self, paths: &ProjectPathsConfig, artifact: &Path) -> Option<B256> {
+        self.artifacts.get(Self::key(paths, artifact)).copied()
+    }
+
+    /// Sets the fingerprint of an artifact and returns whether it changed.
+    fn insert(&mut self, paths: &ProjectPathsConfig, artifact: &Path, fingerprint: B256) -> bool {
+        let key = Self::key(paths, artifact).to_path_buf();
+        self.artifacts.insert(key, fingerprint) != Some(fingerprint)
+    }
+
+    fn key<'a>(paths: &ProjectPathsConfig, artifact: &'a Path) -> &'a Path {
+        artifact.strip_prefix(&paths.artifacts).unwrap_or(artifact)
+    }
+}
+
 /// Compile a set of files not necessarily included in the `project`'s source dir
 ///
 /// If `silent` no solc related output will be emitted to stdout
@@ -382,8 +685,9 @@ pub fn compile_files(
     }?;
 
     if output.has_compiler_errors() {
-        eyre::bail!(output.to_string())
+        eyre::bail!(format_compiler_errors(&output, project))
     }
+    write_artifact_stamps(project, &output)?;
     if !silent {
         println!("{output}");
     }
@@ -444,7 +748,7 @@ pub async fn compile_from_source(
     let project_output = project.compile()?;
 
     if project_output.has_compiler_errors() {
-        eyre::bail!(project_output.to_string())
+        eyre::bail!(format_compiler_errors(&project_output, &project))
     }
 
     let (artifact_id, file_id, contract) = project_output
@@ -481,14 +785,7 @@ pub fn etherscan_project(metadata: &Metadata, target_path: impl AsRef<Path>) ->
     }
 
     // add missing remappings
-    if !settings.remappings.iter().any(|remapping| remapping.name.starts_with("@openzeppelin/")) {
-        let oz = Remapping {
-            context: None,
-            name: "@openzeppelin/".into(),
-            path: sources_path.join("@openzeppelin").display().to_string(),
-        };
-        settings.remappings.push(oz);
-    }
+    add_missing_etherscan_remappings(&mut settings.remappings, &sources_path, None);
 
     // root/
     //   ContractName/
@@ -512,6 +809,56 @@ pub fn etherscan_project(metadata: &Metadata, target_path: impl AsRef<Path>) ->
         .build()?)
 }
 
+/// Writes the sources of an Etherscan contract into the `vendor` directory of the project's
+/// sources, i.e. `<src>/vendor/<ContractName>/`.
+///
+/// Returns the remappings required to compile the vendored sources. Paths are relative to `root`
+/// and every remapping is scoped to the vendored directory, so that sources of multiple vendored
+/// contracts can't conflict with each other or with the project's own remappings.
+pub fn vendor_etherscan_sources(
+    metadata: &Metadata,
+    root: impl AsRef<Path>,
+    src: impl AsRef<Path>,
+) -> Result<Vec<Remapping>> {
+    let root = root.as_ref();
+    let vendor_path = src.as_ref().join("vendor");
+    let sources_path = vendor_path.join(&metadata.contract_name);
+    if sources_path.exists() {
+        eyre::bail!("{} already exists", sources_path.display());
+    }
+    // the source tree is always nested in a `<ContractName>` directory
+    metadata.source_tree().write_to(&vendor_path)?;
+
+    let sources_path = sources_path.strip_prefix(root).unwrap_or(&sources_path).to_path_buf();
+    let context = format!("{}/", sources_path.display());
+
+    let mut remappings = metadata.source_code.settings()?.unwrap_or_default().remappings;
+    for remapping in remappings.iter_mut() {
+        remapping.context = Some(context.clone());
+        remapping.path =
+            sources_path.join(remapping.path.trim_start_matches('/')).display().to_string();
+    }
+    add_missing_etherscan_remappings(&mut remappings, &sources_path, Some(context));
+
+    Ok(remappings)
+}
+
+/// Adds remappings that are commonly missing from the settings of verified Etherscan contracts.
+fn add_missing_etherscan_remappings(
+    remappings: &mut Vec<Remapping>,
+    sources_path: &Path,
+    context: Option<String>,
+) {
+    if !remappings.iter().any(|remapping| remapping.name.starts_with("@openzeppelin/")) {
+        let oz = Remapping {
+            context,
+            name: "@openzeppelin/".into(),
+            path: sources_path.join("@openzeppelin").display().to_string(),
+        };
+        remappings.push(oz);
+    }
+}
+
 /// Bundles multiple `SkipBuildFilter` into a single `FileFilter`
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SkipBuildFilters(pub Vec<SkipBuildFilter>);
@@ -599,4 +946,43 @@ mod tests {
         let file = Path::new("/home/script/Contract.sol");
         assert!(!SkipBuildFilter::Custom("*/script/**".to_string()).is_match(file));
     }
+
+    #[test]
+    fn test_settings_fingerprint() {
+        let settings = Settings::default();
+        let fingerprint = settings_fingerprint(&settings);
+
+        let mut other = settings.clone();
+        other.output_selection = Default::default();
+        assert_eq!(settings_fingerprint(&other), fingerprint);
+
+        let mut other = settings.clone();
+        other.optimizer.runs = Some(1);
+        assert_ne!(settings_fingerprint(&other), fingerprint);
+
+        let mut other = settings.clone();
+        other.evm_version = Some(foundry_compilers::EvmVersion::Byzantium);
+        assert_ne!(settings_fingerprint(&other), fingerprint);
+    }
+
+    #[test]
+    fn test_write_settings_fingerprints() {
+        let root = tempfile::tempdir().unwrap();
+        let paths = ProjectPathsConfig::builder().build_with_root(root.path());
+        let artifact = paths.artifacts.join("Counter.sol/Counter.json");
+        assert_eq!(SettingsFingerprints::read(&paths).get(&paths, &artifact), None);
+
+        let fingerprint = settings_fingerprint(&Settings::default());
+        let mut fingerprints = SettingsFingerprints::default();
+        assert!(fingerprints.insert(&paths, &artifact, fingerprint));
+        assert!(!fingerprints.insert(&paths, &artifact, fingerprint));
+        fingerprints.write(&paths).unwrap();
+
+        let fingerprints = SettingsFingerprints::read(&paths);
+        assert_eq!(fingerprints.get(&paths, &artifact), Some(fingerprint));
+        assert_eq!(
+            fingerprints.artifacts.keys().collect::<Vec<_>>(),
+            vec![Path::new("Counter.sol/Counter.json")]
+        );
+    }
 }
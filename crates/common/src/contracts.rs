@@ -179,8 +179,11 @@ pub fn get_artifact_path(paths: &ProjectPathsConfig, path: &str) -> PathBuf {
     } else {
         let parts: Vec<&str> = path.split(':').collect();
         let file = parts[0];
-        let contract_name =
-            if parts.len() == 1 { parts[0].replace(".sol", "") } else { parts[1].to_string() };
+        let contract_name = if parts.len() == 1 {
+            parts[0].replace(".sol", "").replace(".vy", "")
+        } else {
+            parts[1].to_string()
+        };
         paths.artifacts.join(format!("{file}/{contract_name}.json"))
     }
 }
@@ -0,0 +1,149 @@
+//! Registry of deployed contracts.
+//!
+//! Every contract deployed by a broadcasted script is recorded in a manifest at
+//! `deployments/<chain-id>/<contract>.json`, relative to the project root. Later deployments of
+//! the same contract on the same chain replace the previous manifest.
+
+use crate::fs;
+use alloy_primitives::{Address, B256};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The name of the directory containing the deployment manifests, relative to the project root.
+pub const DEPLOYMENTS_DIR: &str = "deployments";
+
+/// The manifest of a single contract deployment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentManifest {
+    /// The name of the deployed contract.
+    pub contract_name: String,
+    /// The address of the deployed contract.
+    pub address: Address,
+    /// The hash of the transaction that deployed the contract.
+    pub transaction_hash: B256,
+    /// The constructor arguments.
+    #[serde(default)]
+    pub arguments: Vec<String>,
+    /// The keccak256 hash of the deployed runtime bytecode.
+    pub bytecode_hash: B256,
+    /// The commit of the project the contract was deployed from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// The unix timestamp of the deployment.
+    pub timestamp: u64,
+}
+
+/// Reads and writes the deployment manifests of a project.
+#[derive(Clone, Debug)]
+pub struct DeploymentRegistry {
+    /// The `deployments` directory.
+    dir: PathBuf,
+}
+
+impl DeploymentRegistry {
+    /// Creates a new registry for the project at `root`.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self { dir: root.as_ref().join(DEPLOYMENTS_DIR) }
+    }
+
+    /// Returns the path of the manifest of `contract_name` on the given chain.
+    pub fn manifest_path(&self, chain_id: u64, contract_name: &str) -> PathBuf {
+        self.dir.join(chain_id.to_string()).join(format!("{contract_name}.json"))
+    }
+
+    /// Writes the manifest, replacing any previous deployment of the same contract on the chain.
+    ///
+    /// Returns the path of the written manifest.
+    pub fn save(&self, chain_id: u64, manifest: &DeploymentManifest) -> Result<PathBuf> {
+        let path = self.manifest_path(chain_id, &manifest.contract_name);
+        fs::create_dir_all(path.parent().expect("has parent"))?;
+        fs::write(&path, serde_json::to_string_pretty(manifest)?)?;
+        Ok(path)
+    }
+
+    /// Returns the latest deployment of `contract_name` on the given chain, if any.
+    pub fn get(&self, chain_id: u64, contract_name: &str) -> Result<Option<DeploymentManifest>> {
+        let path = self.manifest_path(chain_id, contract_name);
+        if !path.is_file() {
+            return Ok(None)
+        }
+        Ok(Some(fs::read_json_file(&path)?))
+    }
+
+    /// Returns all deployments, optionally only the ones on the given chain, sorted by chain id
+    /// and contract name.
+    pub fn list(&self, chain_id: Option<u64>) -> Result<Vec<(u64, DeploymentManifest)>> {
+        let mut deployments = Vec::new();
+        if !self.dir.is_dir() {
+            return Ok(deployments)
+        }
+
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            // chain directories are named after the chain id
+            let Some(id) = entry.file_name().to_str().and_then(|name| name.parse::<u64>().ok())
+            else {
+                continue
+            };
+            if chain_id.map_or(false, |chain_id| chain_id != id) || !entry.path().is_dir() {
+                continue
+            }
+
+            for path in fs::json_files(entry.path()) {
+                deployments.push((id, fs::read_json_file(&path)?));
+            }
+        }
+
+        deployments.sort_by(|(a, a_manifest), (b, b_manifest)| {
+            a.cmp(b).then_with(|| a_manifest.contract_name.cmp(&b_manifest.contract_name))
+        });
+        Ok(deployments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(contract_name: &str, address: Address) -> DeploymentManifest {
+        DeploymentManifest {
+            contract_name: contract_name.to_string(),
+            address,
+            transaction_hash: B256::repeat_byte(1),
+            arguments: vec!["1".to_string()],
+            bytecode_hash: B256::repeat_byte(2),
+            commit: Some("abcdef".to_string()),
+            timestamp: 1,
+        }
+    }
+
+    #[test]
+    fn can_save_and_query_deployments() {
+        let root = tempfile::tempdir().unwrap();
+        let registry = DeploymentRegistry::new(root.path());
+        assert!(registry.list(None).unwrap().is_empty());
+
+        let token = manifest("Token", Address::repeat_byte(1));
+        let path = registry.save(1, &token).unwrap();
+        assert_eq!(path, root.path().join("deployments/1/Token.json"));
+        registry.save(1, &manifest("Vault", Address::repeat_byte(2))).unwrap();
+        registry.save(10, &manifest("Token", Address::repeat_byte(3))).unwrap();
+
+        assert_eq!(registry.get(1, "Token").unwrap(), Some(token));
+        assert_eq!(registry.get(5, "Token").unwrap(), None);
+
+        let all = registry.list(None).unwrap();
+        assert_eq!(
+            all.iter().map(|(chain, m)| (*chain, m.contract_name.as_str())).collect::<Vec<_>>(),
+            vec![(1, "Token"), (1, "Vault"), (10, "Token")]
+        );
+        assert_eq!(registry.list(Some(10)).unwrap().len(), 1);
+
+        // redeploying replaces the manifest
+        let redeployed = manifest("Token", Address::repeat_byte(4));
+        registry.save(1, &redeployed).unwrap();
+        assert_eq!(registry.get(1, "Token").unwrap(), Some(redeployed));
+    }
+}
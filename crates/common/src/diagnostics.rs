@@ -0,0 +1,288 @@
+//! Human friendly diagnostics for the errors and warnings of solc
+//!
+//! solc's own formatted messages use paths relative to the project root and end locations with a
+//! colon, which most terminals and editors can't open. The diagnostics here point to the
+//! absolute `path:line:column` of the source and render their own code frame.
+
+use foundry_compilers::{
+    artifacts::{Error, Severity},
+    Project, ProjectCompileOutput,
+};
+use serde::Serialize;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+use yansi::Paint;
+
+/// A diagnostic of the compiler, anchored to a location in a source file if solc reported one
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// `error`, `warning` or `info`
+    pub severity: String,
+    /// The solc error code, or the id of a lint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// The message, without solc's formatting
+    pub message: String,
+    /// The location of the diagnostic, if any
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    /// The source line of the start of the location, used for the code frame
+    #[serde(skip)]
+    source_line: Option<String>,
+}
+
+/// A location in a source file, with 1-based lines and columns
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    /// The absolute path of the source file
+    pub file: PathBuf,
+    /// The line of the start of the location
+    pub line: usize,
+    /// The column of the start of the location
+    pub column: usize,
+    /// The line of the end of the location
+    pub end_line: usize,
+    /// The column of the end of the location, exclusive
+    pub end_column: usize,
+}
+
+impl Diagnostic {
+    /// Converts a solc error, reading the source file relative to `root` to resolve the location
+    pub fn from_solc(error: &Error, root: &Path) -> Self {
+        let source = error.source_location.as_ref().and_then(|loc| {
+            let file = root.join(&loc.file);
+            let content = std::fs::read_to_string(&file).ok()?;
+            Some((file, content, loc.start, loc.end))
+        });
+        Self::new(
+            error,
+            source.as_ref().map(|(file, content, start, end)| {
+                (file.as_path(), content.as_str(), *start, *end)
+            }),
+        )
+    }
+
+    /// Creates a diagnostic anchored to the `start..end` byte range of the `content` of `file`
+    pub fn from_source(
+        severity: &str,
+        code: Option<String>,
+        message: impl Into<String>,
+        file: &Path,
+        content: &str,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        Self {
+            severity: severity.to_string(),
+            code,
+            message: message.into(),
+            location: None,
+            source_line: None,
+        }
+        .with_location(file, content, start, end)
+    }
+
+    fn new(error: &Error, source: Option<(&Path, &str, i32, i32)>) -> Self {
+        let diagnostic = Self {
+            severity: match error.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Info => "info",
+            }
+            .to_string(),
+            code: error.error_code.map(|code| code.to_string()),
+            message: error.message.clone(),
+            location: None,
+            source_line: None,
+        };
+        match source {
+            Some((file, content, start, end)) => {
+                let (Ok(start), Ok(end)) = (usize::try_from(start), usize::try_from(end)) else {
+                    return diagnostic
+                };
+                diagnostic.with_location(file, content, start, end)
+            }
+            None => diagnostic,
+        }
+    }
+
+    fn with_location(mut self, file: &Path, content: &str, start: usize, end: usize) -> Self {
+        let (Some((line, column)), Some((end_line, end_column))) =
+            (line_column(content, start), line_column(content, end.max(start)))
+        else {
+            return self
+        };
+        self.source_line = content.lines().nth(line - 1).map(str::to_string);
+        self.location =
+            Some(Location { file: file.to_path_buf(), line, column, end_line, end_column });
+        self
+    }
+
+    /// Returns true if this is an error
+    pub fn is_error(&self) -> bool {
+        self.severity == "error"
+    }
+
+    /// Returns true if this is a warning
+    pub fn is_warning(&self) -> bool {
+        self.severity == "warning"
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity.as_str() {
+            "error" => Paint::red(&self.severity).bold(),
+            "warning" => Paint::yellow(&self.severity).bold(),
+            _ => Paint::cyan(&self.severity).bold(),
+        };
+        match &self.code {
+            Some(code) => write!(f, "{severity}{}", Paint::new(format!("[{code}]")).bold())?,
+            None => write!(f, "{severity}")?,
+        }
+        writeln!(f, "{} {}", Paint::new(":").bold(), Paint::new(&self.message).bold())?;
+
+        let Some(location) = &self.location else { return Ok(()) };
+        let gutter = location.line.to_string().len();
+        writeln!(
+            f,
+            "{:gutter$}{} {}:{}:{}",
+            "",
+            Paint::blue("-->").bold(),
+            location.file.display(),
+            location.line,
+            location.column
+        )?;
+        if let Some(line) = &self.source_line {
+            let bar = Paint::blue("|").bold();
+            // a location spanning multiple lines is underlined until the end of its first line
+            let end_column = if location.end_line == location.line {
+                location.end_column
+            } else {
+                line.chars().count() + 1
+            };
+            let underline = "^".repeat(end_column.saturating_sub(location.column).max(1));
+            writeln!(f, "{:gutter$} {bar}", "")?;
+            writeln!(f, "{} {bar} {line}", Paint::blue(location.line).bold())?;
+            let marker =
+                if self.is_error() { Paint::red(underline) } else { Paint::yellow(underline) };
+            writeln!(
+                f,
+                "{:gutter$} {bar} {:pad$}{}",
+                "",
+                "",
+                marker.bold(),
+                pad = location.column - 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the diagnostics of all errors and warnings of the compiler output, without the
+/// warnings the project ignores
+pub fn compiler_diagnostics(output: &ProjectCompileOutput, project: &Project) -> Vec<Diagnostic> {
+    output
+        .output()
+        .errors
+        .iter()
+        .filter(|error| {
+            error.severity.is_error() ||
+                !error.error_code.is_some_and(|code| project.ignored_error_codes.contains(&code))
+        })
+        .map(|error| Diagnostic::from_solc(error, &project.paths.root))
+        .collect()
+}
+
+/// Formats the errors and warnings of a failed compilation as diagnostics
+///
+/// Falls back to solc's own formatting if none of the errors could be converted.
+pub fn format_compiler_errors(output: &ProjectCompileOutput, project: &Project) -> String {
+    let diagnostics = compiler_diagnostics(output, project);
+    let errors = diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count();
+    if errors == 0 {
+        return output.to_string()
+    }
+    let warnings = diagnostics.iter().filter(|diagnostic| diagnostic.is_warning()).count();
+    let mut summary = format!("Compilation failed with {errors} error{}", plural(errors));
+    if warnings > 0 {
+        summary.push_str(&format!(" and {warnings} warning{}", plural(warnings)));
+    }
+    let rendered = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error() || diagnostic.is_warning())
+        .map(|diagnostic| diagnostic.to_string())
+        .collect::<Vec<_>>();
+    format!("{}\n\n{summary}", rendered.join("\n"))
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+/// Returns the 1-based line and column, in characters, of a byte offset
+fn line_column(content: &str, offset: usize) -> Option<(usize, usize)> {
+    let before = content.get(..offset)?;
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Some((line, before[line_start..].chars().count() + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str =
+        "contract Dummy {\n    function f() public {\n        number = newnumber;\n    }\n}\n";
+
+    fn error(start: i32, end: i32) -> Error {
+        serde_json::from_value(serde_json::json!({
+            "sourceLocation": { "file": "src/Dummy.sol", "start": start, "end": end },
+            "type": "DeclarationError",
+            "component": "general",
+            "severity": "error",
+            "errorCode": "7576",
+            "message": "Undeclared identifier.",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn can_resolve_location() {
+        let start = SOURCE.find("newnumber").unwrap() as i32;
+        let diagnostic = Diagnostic::new(
+            &error(start, start + 9),
+            Some((Path::new("/project/src/Dummy.sol"), SOURCE, start, start + 9)),
+        );
+        let location = diagnostic.location.clone().unwrap();
+        assert_eq!((location.line, location.column), (3, 18));
+        assert_eq!((location.end_line, location.end_column), (3, 27));
+        assert_eq!(diagnostic.code.as_deref(), Some("7576"));
+
+        Paint::disable();
+        let rendered = diagnostic.to_string();
+        assert!(rendered.starts_with("error[7576]: Undeclared identifier.\n"), "{rendered}");
+        assert!(rendered.contains("--> /project/src/Dummy.sol:3:18\n"), "{rendered}");
+        assert!(rendered.contains("3 |         number = newnumber;\n"), "{rendered}");
+        assert!(rendered.contains("  |                  ^^^^^^^^^\n"), "{rendered}");
+
+        let json = serde_json::to_value(&diagnostic).unwrap();
+        assert_eq!(json["line"], 3);
+        assert_eq!(json["endColumn"], 27);
+    }
+
+    #[test]
+    fn ignores_missing_locations() {
+        let diagnostic = Diagnostic::new(&error(-1, -1), Some((Path::new("x"), SOURCE, -1, -1)));
+        assert!(diagnostic.location.is_none());
+        assert!(diagnostic.is_error());
+    }
+}
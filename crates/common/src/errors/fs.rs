@@ -17,6 +17,9 @@ pub enum FsPathError {
     /// Provides additional path context for [`std::fs::copy`].
     #[error("failed to copy from {from:?} to {to:?}: {source}")]
     Copy { source: io::Error, from: PathBuf, to: PathBuf },
+    /// Provides additional path context for [`std::fs::rename`].
+    #[error("failed to rename {from:?} to {to:?}: {source}")]
+    Rename { source: io::Error, from: PathBuf, to: PathBuf },
     /// Provides additional path context for [`std::fs::read_link`].
     #[error("failed to read from {path:?}: {source}")]
     ReadLink { source: io::Error, path: PathBuf },
@@ -59,6 +62,11 @@ impl FsPathError {
         FsPathError::Copy { source, from: from.into(), to: to.into() }
     }
 
+    /// Returns the complementary error variant for [`std::fs::rename`].
+    pub fn rename(source: io::Error, from: impl Into<PathBuf>, to: impl Into<PathBuf>) -> Self {
+        FsPathError::Rename { source, from: from.into(), to: to.into() }
+    }
+
     /// Returns the complementary error variant for [`std::fs::read_link`].
     pub fn read_link(source: io::Error, path: impl Into<PathBuf>) -> Self {
         FsPathError::ReadLink { source, path: path.into() }
@@ -97,6 +105,7 @@ impl AsRef<Path> for FsPathError {
             Self::Read { path, .. } |
             Self::ReadLink { path, .. } |
             Self::Copy { from: path, .. } |
+            Self::Rename { from: path, .. } |
             Self::CreateDir { path, .. } |
             Self::RemoveDir { path, .. } |
             Self::CreateFile { path, .. } |
@@ -115,6 +124,7 @@ impl From<FsPathError> for io::Error {
             FsPathError::Read { source, .. } |
             FsPathError::ReadLink { source, .. } |
             FsPathError::Copy { source, .. } |
+            FsPathError::Rename { source, .. } |
             FsPathError::CreateDir { source, .. } |
             FsPathError::RemoveDir { source, .. } |
             FsPathError::CreateFile { source, .. } |
@@ -44,6 +44,9 @@ pub struct EvmArgs {
     /// Fetch state over a remote endpoint instead of starting from an empty state.
     ///
     /// If you want to fetch state from a specific block number, see --fork-block-number.
+    ///
+    /// `forge test` also accepts comma separated endpoints in the format `<URL_OR_ALIAS>[@BLOCK]`
+    /// and runs the tests once against each of them.
     #[clap(long, short, visible_alias = "rpc-url", value_name = "URL")]
     #[serde(rename = "eth_rpc_url", skip_serializing_if = "Option::is_none")]
     pub fork_url: Option<String>,
@@ -57,12 +57,47 @@ pub fn write_json_file<T: Serialize>(path: &Path, obj: &T) -> Result<()> {
     writer.flush().map_err(|e| FsPathError::write(e, path))
 }
 
+/// Writes the object as a JSON object, atomically and durably.
+///
+/// The object is written to a temporary file next to `path`, which is synced to disk and then
+/// renamed to `path`. So `path` contains either the previous or the new object, even if the
+/// process is killed or the host crashes while writing.
+pub fn write_json_file_durable<T: Serialize>(path: &Path, obj: &T) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let file = create_file(&tmp)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, obj)
+        .map_err(|source| FsPathError::WriteJson { source, path: tmp.clone() })?;
+    let file = writer.into_inner().map_err(|e| FsPathError::write(e.into_error(), &tmp))?;
+    file.sync_all().map_err(|e| FsPathError::write(e, &tmp))?;
+    rename(&tmp, path)?;
+
+    // sync the directory as well, so that the rename itself survives a crash
+    #[cfg(unix)]
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
 /// Wrapper for `std::fs::write`
 pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
     let path = path.as_ref();
     fs::write(path, contents).map_err(|err| FsPathError::write(err, path))
 }
 
+/// Wrapper for `std::fs::rename`
+pub fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
+    let from = from.as_ref();
+    let to = to.as_ref();
+    fs::rename(from, to).map_err(|err| FsPathError::rename(err, from, to))
+}
+
 /// Wrapper for `std::fs::copy`
 pub fn copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<u64> {
     let from = from.as_ref();
@@ -167,4 +202,15 @@ mod tests {
         let normalized = normalize_path(p);
         assert_eq!(normalized, PathBuf::from("/file.txt"));
     }
+
+    #[test]
+    fn writes_json_file_durably() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        write_json_file_durable(&path, &vec![1, 2, 3]).unwrap();
+        write_json_file_durable(&path, &vec![4]).unwrap();
+
+        assert_eq!(read_json_file::<Vec<u64>>(&path).unwrap(), [4]);
+        assert!(!dir.path().join("state.json.tmp").exists());
+    }
 }
@@ -8,11 +8,14 @@ extern crate self as foundry_common;
 extern crate tracing;
 
 pub mod abi;
+pub mod artifacts;
 pub mod calc;
 pub mod clap_helpers;
 pub mod compile;
 pub mod constants;
 pub mod contracts;
+pub mod deployments;
+pub mod diagnostics;
 pub mod errors;
 pub mod evm;
 pub mod fmt;
@@ -24,11 +27,13 @@ pub mod rpc;
 pub mod runtime_client;
 pub mod selectors;
 pub mod shell;
+pub mod sigv4;
 pub mod term;
 pub mod traits;
 pub mod transactions;
 pub mod types;
 pub mod units;
+pub mod vyper;
 
 pub use constants::*;
 pub use contracts::*;
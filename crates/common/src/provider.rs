@@ -2,6 +2,7 @@
 
 use crate::{
     runtime_client::{RuntimeClient, RuntimeClientBuilder},
+    sigv4::SigV4Signer,
     ALCHEMY_FREE_TIER_CUPS, REQUEST_TIMEOUT,
 };
 use ethers_core::types::U256;
@@ -66,6 +67,8 @@ pub struct ProviderBuilder {
     /// JWT Secret
     jwt: Option<String>,
     headers: Vec<String>,
+    /// Credentials to sign the requests with AWS Signature Version 4
+    sigv4: Option<SigV4Signer>,
 }
 
 // === impl ProviderBuilder ===
@@ -110,6 +113,7 @@ impl ProviderBuilder {
             compute_units_per_second: ALCHEMY_FREE_TIER_CUPS,
             jwt: None,
             headers: vec![],
+            sigv4: None,
         }
     }
 
@@ -199,6 +203,12 @@ impl ProviderBuilder {
         self
     }
 
+    /// Sets the credentials to sign http requests with AWS Signature Version 4
+    pub fn sigv4(mut self, sigv4: Option<SigV4Signer>) -> Self {
+        self.sigv4 = sigv4;
+        self
+    }
+
     /// Same as [`Self:build()`] but also retrieves the `chainId` in order to derive an appropriate
     /// interval.
     pub async fn connect(self) -> Result<RetryProvider> {
@@ -223,6 +233,7 @@ impl ProviderBuilder {
             compute_units_per_second,
             jwt,
             headers,
+            sigv4,
         } = self;
         let url = url?;
 
@@ -235,7 +246,8 @@ impl ProviderBuilder {
             compute_units_per_second,
         )
         .with_headers(headers)
-        .with_jwt(jwt);
+        .with_jwt(jwt)
+        .with_sigv4(sigv4);
 
         let mut provider = Provider::new(client_builder.build());
 
@@ -1,11 +1,12 @@
 //! Wrap different providers
 
+use crate::sigv4::{SigV4Http, SigV4Signer};
 use async_trait::async_trait;
 use ethers_core::types::U256;
 use ethers_providers::{
-    Authorization, ConnectionDetails, Http, HttpRateLimitRetryPolicy, Ipc, JsonRpcClient,
-    JsonRpcError, JwtAuth, JwtKey, ProviderError, PubsubClient, RetryClient, RetryClientBuilder,
-    RpcError, Ws,
+    Authorization, ConnectionDetails, Http, HttpClientError, HttpRateLimitRetryPolicy, Ipc,
+    JsonRpcClient, JsonRpcError, JwtAuth, JwtKey, ProviderError, PubsubClient, RetryClient,
+    RetryClientBuilder, RpcError, Ws,
 };
 use reqwest::{
     header::{HeaderName, HeaderValue},
@@ -21,6 +22,8 @@ use tokio::sync::RwLock;
 enum InnerClient {
     /// HTTP client
     Http(RetryClient<Http>),
+    /// HTTP client signing requests with AWS Signature Version 4
+    SigV4Http(RetryClient<SigV4Http>),
     /// WebSocket client
     Ws(Ws),
     /// IPC client
@@ -90,6 +93,7 @@ pub struct RuntimeClient {
     compute_units_per_second: u64,
     jwt: Option<String>,
     headers: Vec<String>,
+    sigv4: Option<SigV4Signer>,
 }
 
 /// Builder for RuntimeClient
@@ -103,6 +107,7 @@ pub struct RuntimeClientBuilder {
     compute_units_per_second: u64,
     jwt: Option<String>,
     headers: Vec<String>,
+    sigv4: Option<SigV4Signer>,
 }
 
 impl ::core::fmt::Display for RuntimeClient {
@@ -163,16 +168,13 @@ impl RuntimeClient {
                 let client = client_builder
                     .build()
                     .map_err(|e| RuntimeClientError::ProviderError(e.into()))?;
-                let provider = Http::new_with_client(self.url.clone(), client);
 
-                #[allow(clippy::box_default)]
-                let provider = RetryClientBuilder::default()
-                    .initial_backoff(Duration::from_millis(self.initial_backoff))
-                    .rate_limit_retries(self.max_retry)
-                    .timeout_retries(self.timeout_retry)
-                    .compute_units_per_second(self.compute_units_per_second)
-                    .build(provider, Box::new(HttpRateLimitRetryPolicy));
-                Ok(InnerClient::Http(provider))
+                if let Some(signer) = self.sigv4.clone() {
+                    let provider = SigV4Http::new(client, self.url.clone(), signer);
+                    return Ok(InnerClient::SigV4Http(self.retry_client(provider)))
+                }
+                let provider = Http::new_with_client(self.url.clone(), client);
+                Ok(InnerClient::Http(self.retry_client(provider)))
             }
             "ws" | "wss" => {
                 let auth: Option<Authorization> =
@@ -199,6 +201,20 @@ impl RuntimeClient {
             _ => Err(RuntimeClientError::BadScheme(self.url.to_string())),
         }
     }
+
+    /// Wraps the HTTP client in a [RetryClient] with the configured retry settings.
+    #[allow(clippy::box_default)]
+    fn retry_client<T>(&self, provider: T) -> RetryClient<T>
+    where
+        T: JsonRpcClient<Error = HttpClientError> + 'static,
+    {
+        RetryClientBuilder::default()
+            .initial_backoff(Duration::from_millis(self.initial_backoff))
+            .rate_limit_retries(self.max_retry)
+            .timeout_retries(self.timeout_retry)
+            .compute_units_per_second(self.compute_units_per_second)
+            .build(provider, Box::new(HttpRateLimitRetryPolicy))
+    }
 }
 
 impl RuntimeClientBuilder {
@@ -220,6 +236,7 @@ impl RuntimeClientBuilder {
             compute_units_per_second,
             jwt: None,
             headers: vec![],
+            sigv4: None,
         }
     }
 
@@ -236,6 +253,13 @@ impl RuntimeClientBuilder {
         self
     }
 
+    /// Set the credentials to sign requests with AWS Signature Version 4
+    /// Only works with http/https schemas
+    pub fn with_sigv4(mut self, sigv4: Option<SigV4Signer>) -> Self {
+        self.sigv4 = sigv4;
+        self
+    }
+
     /// Builds RuntimeClient instance
     pub fn build(self) -> RuntimeClient {
         RuntimeClient {
@@ -248,6 +272,7 @@ impl RuntimeClientBuilder {
             compute_units_per_second: self.compute_units_per_second,
             jwt: self.jwt,
             headers: self.headers,
+            sigv4: self.sigv4,
         }
     }
 }
@@ -294,6 +319,9 @@ impl JsonRpcClient for RuntimeClient {
             InnerClient::Http(http) => RetryClient::request(http, method, params)
                 .await
                 .map_err(|e| RuntimeClientError::ProviderError(e.into())),
+            InnerClient::SigV4Http(http) => RetryClient::request(http, method, params)
+                .await
+                .map_err(|e| RuntimeClientError::ProviderError(e.into())),
             InnerClient::Ws(ws) => JsonRpcClient::request(ws, method, params)
                 .await
                 .map_err(|e| RuntimeClientError::ProviderError(e.into())),
@@ -313,7 +341,7 @@ impl PubsubClient for RuntimeClient {
 
     fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Self::NotificationStream, Self::Error> {
         match self.client.try_read().map_err(|_| RuntimeClientError::LockError)?.as_ref().unwrap() {
-            InnerClient::Http(_) => {
+            InnerClient::Http(_) | InnerClient::SigV4Http(_) => {
                 Err(RuntimeClientError::ProviderError(ProviderError::UnsupportedRPC))
             }
             InnerClient::Ws(client) => Ok(PubsubClient::subscribe(client, id)
@@ -326,7 +354,7 @@ impl PubsubClient for RuntimeClient {
     fn unsubscribe<T: Into<U256>>(&self, id: T) -> Result<(), Self::Error> {
         match self.client.try_read().map_err(|_| (RuntimeClientError::LockError))?.as_ref().unwrap()
         {
-            InnerClient::Http(_) => {
+            InnerClient::Http(_) | InnerClient::SigV4Http(_) => {
                 Err(RuntimeClientError::ProviderError(ProviderError::UnsupportedRPC))
             }
             InnerClient::Ws(client) => Ok(PubsubClient::unsubscribe(client, id)
@@ -1,12 +1,14 @@
 #![allow(missing_docs)]
 //! Support for handling/identifying selectors
 use crate::abi::abi_decode_calldata;
-use alloy_json_abi::JsonAbi;
+use alloy_json_abi::{Event, Function, JsonAbi};
+use foundry_config::Config;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
@@ -550,6 +552,269 @@ pub fn parse_signatures(tokens: Vec<String>) -> ParsedSignatures {
     ParsedSignatures { signatures, abis }
 }
 
+/// The local signatures database in `~/.foundry/signatures`.
+///
+/// Holds the signatures added with `cast signatures add` or uploaded with `cast upload-signature`,
+/// and caches the signatures resolved from https://api.openchain.xyz, so that known selectors can
+/// be decoded offline.
+#[derive(Debug, Default)]
+pub struct SignaturesDb {
+    signatures: LocalSignatures,
+    /// Where to save the database, if anywhere
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct LocalSignatures {
+    /// Function and error selector -> signatures
+    #[serde(default)]
+    functions: BTreeMap<String, BTreeSet<String>>,
+    /// Event topic -> signatures
+    #[serde(default)]
+    events: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SignaturesDb {
+    /// Returns the directory of the local database, `~/.foundry/signatures`
+    pub fn dir() -> Option<PathBuf> {
+        Config::foundry_dir().map(|dir| dir.join("signatures"))
+    }
+
+    /// Loads the database from `~/.foundry/signatures`.
+    ///
+    /// Returns an empty database if it doesn't exist yet or can't be read.
+    pub fn load() -> Self {
+        match Self::dir() {
+            Some(dir) => Self::load_from(dir),
+            None => Self::default(),
+        }
+    }
+
+    /// Loads the database from the given directory.
+    pub fn load_from(dir: impl AsRef<Path>) -> Self {
+        let path = dir.as_ref().join("signatures.json");
+        let signatures = if path.is_file() {
+            crate::fs::read_json_file(&path)
+                .map_err(|err| warn!(?path, ?err, "failed to read signatures database"))
+                .unwrap_or_default()
+        } else {
+            LocalSignatures::default()
+        };
+        Self { signatures, path: Some(path) }
+    }
+
+    /// Writes the database to disk.
+    pub fn save(&self) -> eyre::Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                crate::fs::create_dir_all(parent)?;
+            }
+            crate::fs::write_json_file(path, &self.signatures)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the parsed signatures and the functions, errors and events of the parsed ABIs.
+    ///
+    /// Returns the number of signatures that weren't known before.
+    pub fn import(&mut self, parsed: &ParsedSignatures) -> eyre::Result<usize> {
+        let mut added = 0;
+        for sig in parsed.signatures.function.iter().chain(&parsed.signatures.error) {
+            let func = Function::parse(sig)
+                .map_err(|err| eyre::eyre!("invalid function signature `{sig}`: {err}"))?;
+            added +=
+                self.insert(SelectorType::Function, &func.selector().to_string(), func.signature())
+                    as usize;
+        }
+        for sig in &parsed.signatures.event {
+            let event = Event::parse(sig)
+                .map_err(|err| eyre::eyre!("invalid event signature `{sig}`: {err}"))?;
+            added +=
+                self.insert(SelectorType::Event, &event.selector().to_string(), event.signature())
+                    as usize;
+        }
+        for abi in &parsed.abis {
+            for func in abi.functions() {
+                added += self.insert(
+                    SelectorType::Function,
+                    &func.selector().to_string(),
+                    func.signature(),
+                ) as usize;
+            }
+            for error in abi.errors() {
+                added += self.insert(
+                    SelectorType::Function,
+                    &error.selector().to_string(),
+                    error.signature(),
+                ) as usize;
+            }
+            for event in abi.events() {
+                added += self.insert(
+                    SelectorType::Event,
+                    &event.selector().to_string(),
+                    event.signature(),
+                ) as usize;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Adds a signature for the given selector, returns `true` if it wasn't known before.
+    pub fn insert(
+        &mut self,
+        selector_type: SelectorType,
+        selector: &str,
+        signature: impl Into<String>,
+    ) -> bool {
+        self.map_mut(selector_type)
+            .entry(selector.to_lowercase())
+            .or_default()
+            .insert(signature.into())
+    }
+
+    /// Returns the known signatures of the given selector.
+    pub fn get(&self, selector_type: SelectorType, selector: &str) -> Option<Vec<String>> {
+        let map = match selector_type {
+            SelectorType::Function => &self.signatures.functions,
+            SelectorType::Event => &self.signatures.events,
+        };
+        map.get(&selector.to_lowercase()).map(|sigs| sigs.iter().cloned().collect())
+    }
+
+    /// Removes a selector, or a single signature, from the database.
+    ///
+    /// Returns `true` if anything was removed.
+    pub fn remove(&mut self, selector_or_signature: &str) -> bool {
+        let key = selector_or_signature.to_lowercase();
+        let mut removed = false;
+        for map in [&mut self.signatures.functions, &mut self.signatures.events] {
+            removed |= map.remove(&key).is_some();
+            map.retain(|_, sigs| {
+                removed |= sigs.remove(selector_or_signature);
+                !sigs.is_empty()
+            });
+        }
+        removed
+    }
+
+    /// Removes all signatures.
+    pub fn clear(&mut self) {
+        self.signatures = LocalSignatures::default();
+    }
+
+    /// Returns all `(selector, signature)` pairs of the given type.
+    pub fn iter(&self, selector_type: SelectorType) -> impl Iterator<Item = (&str, &str)> {
+        let map = match selector_type {
+            SelectorType::Function => &self.signatures.functions,
+            SelectorType::Event => &self.signatures.events,
+        };
+        map.iter().flat_map(|(selector, sigs)| {
+            sigs.iter().map(move |sig| (selector.as_str(), sig.as_str()))
+        })
+    }
+
+    /// Returns `true` if the database holds no signatures.
+    pub fn is_empty(&self) -> bool {
+        self.signatures.functions.is_empty() && self.signatures.events.is_empty()
+    }
+
+    /// Resolves the signatures of a function selector or an event topic.
+    ///
+    /// Known selectors are resolved from the database. Unknown selectors are looked up on
+    /// https://api.openchain.xyz, unless `offline` is set, and the results are cached.
+    pub async fn decode_selector(
+        &mut self,
+        selector: &str,
+        selector_type: SelectorType,
+        offline: bool,
+    ) -> eyre::Result<Vec<String>> {
+        let selector = normalize_selector(selector, selector_type)?;
+        if let Some(sigs) = self.get(selector_type, &selector) {
+            return Ok(sigs)
+        }
+        if offline {
+            return Ok(vec![])
+        }
+
+        let sigs = SignEthClient::new()?.decode_selector(&selector, selector_type).await?;
+        if !sigs.is_empty() {
+            for sig in &sigs {
+                self.insert(selector_type, &selector, sig.clone());
+            }
+            if let Err(err) = self.save() {
+                warn!(?err, "failed to save signatures database");
+            }
+        }
+        Ok(sigs)
+    }
+
+    /// Resolves the function signatures of the calldata's selector, and returns the ones that
+    /// can decode the calldata.
+    pub async fn decode_calldata(
+        &mut self,
+        calldata: &str,
+        offline: bool,
+    ) -> eyre::Result<Vec<String>> {
+        let calldata = calldata.strip_prefix("0x").unwrap_or(calldata);
+        if calldata.len() < 8 {
+            eyre::bail!(
+                "Calldata too short: expected at least 8 characters (excluding 0x prefix), got {}.",
+                calldata.len()
+            )
+        }
+
+        let sigs = self.decode_selector(&calldata[..8], SelectorType::Function, offline).await?;
+        Ok(sigs
+            .into_iter()
+            .filter(|sig| abi_decode_calldata(sig, calldata, true, true).is_ok())
+            .collect())
+    }
+
+    /// Pretty prints the calldata, with the possible function signatures of its selector.
+    pub async fn pretty_calldata(
+        &mut self,
+        calldata: impl AsRef<str>,
+        offline: bool,
+    ) -> eyre::Result<PossibleSigs> {
+        let calldata = calldata.as_ref();
+        let mut possible_info = SignEthClient::new()?.pretty_calldata(calldata, true).await?;
+        let selector = &calldata.trim_start_matches("0x")[..8];
+        let sigs = self
+            .decode_selector(selector, SelectorType::Function, offline)
+            .await
+            .unwrap_or_default();
+        if !sigs.is_empty() {
+            possible_info.method = SelectorOrSig::Sig(sigs);
+        }
+        Ok(possible_info)
+    }
+
+    fn map_mut(&mut self, selector_type: SelectorType) -> &mut BTreeMap<String, BTreeSet<String>> {
+        match selector_type {
+            SelectorType::Function => &mut self.signatures.functions,
+            SelectorType::Event => &mut self.signatures.events,
+        }
+    }
+}
+
+/// Validates a function selector or event topic, and returns it `0x` prefixed and lowercased.
+fn normalize_selector(selector: &str, selector_type: SelectorType) -> eyre::Result<String> {
+    let stripped = selector.strip_prefix("0x").unwrap_or(selector);
+    match selector_type {
+        SelectorType::Function if stripped.len() != 8 => eyre::bail!(
+            "Invalid selector: expected 8 characters (excluding 0x prefix), got {}.",
+            stripped.len()
+        ),
+        SelectorType::Event if stripped.len() != 64 => eyre::bail!(
+            "Invalid topic: expected 64 characters (excluding 0x prefix), got {}.",
+            stripped.len()
+        ),
+        _ => {}
+    }
+    eyre::ensure!(hex::decode(stripped).is_ok(), "Invalid selector: `{selector}` is not hex");
+    Ok(format!("0x{}", stripped.to_lowercase()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -684,4 +949,50 @@ mod tests {
                 .await;
         assert_eq!(decoded.unwrap()[0], "canCall(address,address,bytes4)".to_string());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_decode_from_local_signatures() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = SignaturesDb::load_from(dir.path());
+        let parsed = parse_signatures(vec![
+            "transfer(address,uint256)".to_string(),
+            "event Transfer(address,address,uint256)".to_string(),
+            "error InsufficientBalance(uint256,uint256)".to_string(),
+        ]);
+        assert_eq!(db.import(&parsed).unwrap(), 3);
+        assert_eq!(db.import(&parsed).unwrap(), 0);
+        db.save().unwrap();
+
+        let mut db = SignaturesDb::load_from(dir.path());
+        assert_eq!(
+            db.decode_selector("A9059CBB", SelectorType::Function, true).await.unwrap(),
+            vec!["transfer(address,uint256)".to_string()]
+        );
+        assert_eq!(
+            db.decode_selector(
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                SelectorType::Event,
+                true
+            )
+            .await
+            .unwrap(),
+            vec!["Transfer(address,address,uint256)".to_string()]
+        );
+        let decoded = db.decode_calldata("0xa9059cbb0000000000000000000000000a2ac0c368dc8ec680a0c98c907656bd970675950000000000000000000000000000000000000000000000000000000767954a79", true).await.unwrap();
+        assert_eq!(decoded, vec!["transfer(address,uint256)".to_string()]);
+
+        // unknown selectors aren't looked up offline
+        assert!(db
+            .decode_selector("0x095ea7b3", SelectorType::Function, true)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(db.remove("transfer(address,uint256)"));
+        assert!(db.remove("0xDDF252AD1BE2C89B69C2B068FC378DAA952BA7F163C4A11628F55A4DF523B3EF"));
+        assert_eq!(db.iter(SelectorType::Function).count(), 1);
+        assert!(!db.remove("0xa9059cbb"));
+        db.clear();
+        assert!(db.is_empty());
+    }
 }
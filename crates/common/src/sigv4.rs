@@ -0,0 +1,264 @@
+//! Signing of JSON-RPC requests with AWS Signature Version 4, for endpoints behind AWS gateways
+//! such as Amazon Managed Blockchain.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ethers_providers::{HttpClientError, JsonRpcClient, JsonRpcError};
+use hmac::{Hmac, Mac};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE},
+    Client, Url,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The algorithm identifier of AWS Signature Version 4.
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// The credentials and scope used to sign requests with AWS Signature Version 4.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SigV4Signer {
+    /// The AWS access key id.
+    pub access_key_id: String,
+    /// The AWS secret access key.
+    pub secret_access_key: String,
+    /// The session token of temporary credentials, if any.
+    pub session_token: Option<String>,
+    /// The region of the endpoint, e.g. `us-east-1`.
+    pub region: String,
+    /// The service of the endpoint, e.g. `managedblockchain`.
+    pub service: String,
+}
+
+impl fmt::Debug for SigV4Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigV4Signer")
+            .field("access_key_id", &self.access_key_id)
+            .field("region", &self.region)
+            .field("service", &self.service)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SigV4Signer {
+    /// Returns the headers that sign a request with the given method, URL and body at `time`.
+    ///
+    /// The `host` and `x-amz-date` headers are signed, and the `x-amz-security-token` header too if
+    /// a session token is set.
+    pub fn sign(
+        &self,
+        method: &str,
+        url: &Url,
+        body: &[u8],
+        time: DateTime<Utc>,
+    ) -> Vec<(HeaderName, HeaderValue)> {
+        let amz_date = time.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = &amz_date[..8];
+
+        let host = url.host_str().unwrap_or_default();
+        let host = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        let mut headers = vec![("host", host.as_str()), ("x-amz-date", amz_date.as_str())];
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.as_str()));
+        }
+        let canonical_headers = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+            .collect::<String>();
+        let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+
+        let mut query =
+            url.query_pairs().map(|(k, v)| (uri_encode(&k), uri_encode(&v))).collect::<Vec<_>>();
+        query.sort();
+        let query = query.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join("&");
+        let path = if url.path().is_empty() { "/" } else { url.path() };
+
+        let canonical_request = format!(
+            "{method}\n{path}\n{query}\n{canonical_headers}\n{signed_headers}\n{}",
+            hex::encode(Sha256::digest(body))
+        );
+        let scope = format!("{date}/{}/{}/aws4_request", self.region, self.service);
+        let string_to_sign = format!(
+            "{ALGORITHM}\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let key = [date, &self.region, &self.service, "aws4_request"]
+            .iter()
+            .fold(format!("AWS4{}", self.secret_access_key).into_bytes(), |key, part| {
+                hmac_sha256(&key, part.as_bytes())
+            });
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.access_key_id
+        );
+
+        let mut signed = vec![
+            (HeaderName::from_static("x-amz-date"), header_value(&amz_date)),
+            (AUTHORIZATION, header_value(&authorization)),
+        ];
+        if let Some(token) = &self.session_token {
+            signed.push((HeaderName::from_static("x-amz-security-token"), header_value(token)));
+        }
+        signed
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Creates a sensitive header value, replacing invalid values with an empty one that the endpoint
+/// rejects.
+fn header_value(value: &str) -> HeaderValue {
+    let mut value = HeaderValue::from_str(value).unwrap_or_else(|_| HeaderValue::from_static(""));
+    value.set_sensitive(true);
+    value
+}
+
+/// Percent-encodes everything except the unreserved characters, as required by SigV4.
+fn uri_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// A JSON-RPC client over HTTP that signs every request with [SigV4Signer].
+#[derive(Debug)]
+pub struct SigV4Http {
+    id: AtomicU64,
+    client: Client,
+    url: Url,
+    signer: SigV4Signer,
+}
+
+impl SigV4Http {
+    /// Creates a new client for the endpoint at `url`.
+    pub fn new(client: Client, url: Url, signer: SigV4Signer) -> Self {
+        Self { id: AtomicU64::new(1), client, url, signer }
+    }
+}
+
+#[derive(Serialize)]
+struct Request<'a, T> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: T,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl JsonRpcClient for SigV4Http {
+    type Error = HttpClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let id = self.id.fetch_add(1, Ordering::SeqCst);
+        let body = serde_json::to_vec(&Request { jsonrpc: "2.0", id, method, params })
+            .map_err(|err| HttpClientError::SerdeJson { err, text: String::new() })?;
+
+        let headers = self
+            .signer
+            .sign("POST", &self.url, &body, Utc::now())
+            .into_iter()
+            .collect::<HeaderMap>();
+        let text = self
+            .client
+            .post(self.url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .headers(headers)
+            .body(body)
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        let serde_err = |err| HttpClientError::SerdeJson { err, text: text.clone() };
+        let mut response: serde_json::Value = serde_json::from_str(&text).map_err(serde_err)?;
+        if let Some(error) =
+            response.get_mut("error").map(serde_json::Value::take).filter(|e| !e.is_null())
+        {
+            let error: JsonRpcError = serde_json::from_value(error).map_err(serde_err)?;
+            return Err(error.into())
+        }
+        let result = response.get_mut("result").map(serde_json::Value::take).unwrap_or_default();
+        serde_json::from_value(result).map_err(serde_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // <https://docs.aws.amazon.com/general/latest/gr/signature-v4-test-suite.html>
+    fn signer() -> SigV4Signer {
+        SigV4Signer {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+            region: "us-east-1".to_string(),
+            service: "service".to_string(),
+        }
+    }
+
+    fn authorization(headers: &[(HeaderName, HeaderValue)]) -> &str {
+        headers.iter().find(|(name, _)| *name == AUTHORIZATION).unwrap().1.to_str().unwrap()
+    }
+
+    #[test]
+    fn can_sign_aws_test_suite_requests() {
+        let url = Url::parse("https://example.amazonaws.com/").unwrap();
+        let time = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+
+        let headers = signer().sign("GET", &url, b"", time);
+        assert_eq!(headers[0].1, "20150830T123600Z");
+        assert_eq!(
+            authorization(&headers),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+
+        let headers = signer().sign("POST", &url, b"", time);
+        assert!(authorization(&headers).ends_with(
+            "Signature=5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+        ));
+    }
+
+    #[test]
+    fn signs_session_token() {
+        let signer = SigV4Signer { session_token: Some("token".to_string()), ..signer() };
+        let url = Url::parse("https://example.amazonaws.com/").unwrap();
+        let headers = signer.sign("POST", &url, b"{}", Utc::now());
+        assert!(
+            authorization(&headers).contains("SignedHeaders=host;x-amz-date;x-amz-security-token")
+        );
+        assert_eq!(headers[2].1, "token");
+        assert!(!format!("{signer:?}").contains("EXAMPLEKEY"));
+    }
+
+    #[test]
+    fn can_uri_encode() {
+        assert_eq!(uri_encode("a b/c~d"), "a%20b%2Fc~d");
+    }
+}
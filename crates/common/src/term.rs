@@ -7,6 +7,7 @@ use foundry_compilers::{
 use once_cell::sync::Lazy;
 use semver::Version;
 use std::{
+    collections::BTreeMap,
     io,
     io::{prelude::*, IsTerminal},
     path::{Path, PathBuf},
@@ -176,10 +177,42 @@ impl Reporter for SpinnerReporter {
         duration: &Duration,
     ) {
         self.solc_io_report.log_compiler_output(output, version);
-        self.send_msg(format!(
-            "Solc {}.{}.{} finished in {duration:.2?}",
-            version.major, version.minor, version.patch
-        ));
+        // the number of errors and warnings per file, files that failed have no output source
+        let mut files = output
+            .sources
+            .keys()
+            .map(|file| (file.as_str(), (0, 0)))
+            .collect::<BTreeMap<_, (usize, usize)>>();
+        for err in &output.errors {
+            let Some(loc) = &err.source_location else { continue };
+            let (errors, warnings) = files.entry(loc.file.as_str()).or_default();
+            if err.severity.is_error() {
+                *errors += 1;
+            } else if err.severity.is_warning() {
+                *warnings += 1;
+            }
+        }
+        let failed = files.values().filter(|(errors, _)| *errors > 0).count();
+        let mut msg = format!(
+            "Solc {}.{}.{} finished in {duration:.2?}: {} files compiled",
+            version.major,
+            version.minor,
+            version.patch,
+            files.len() - failed
+        );
+        if failed > 0 {
+            msg.push_str(&format!(", {}", Paint::red(format!("{failed} with errors"))));
+        }
+        self.send_msg(msg);
+
+        for (file, (errors, warnings)) in files {
+            let status = match (errors, warnings) {
+                (0, 0) => Paint::green("✓".to_string()),
+                (0, warnings) => Paint::yellow(format!("! {warnings} warning(s)")),
+                (errors, _) => Paint::red(format!("✗ {errors} error(s)")),
+            };
+            self.send_msg(format!("  {file} {status}"));
+        }
     }
 
     /// Invoked before a new [`Solc`] bin is installed
@@ -1,6 +1,6 @@
 //! Unit conversion utilities.
 
-use alloy_primitives::{Address, ParseSignedError, I256, U256};
+use alloy_primitives::{Address, ParseSignedError, Sign, I256, U256};
 use std::{fmt, str::FromStr};
 use thiserror::Error;
 
@@ -96,7 +96,10 @@ impl FromStr for Units {
             "mwei" | "pico" | "picoether" | "lovelace" => Units::Mwei,
             "kwei" | "femto" | "femtoether" | "babbage" => Units::Kwei,
             "wei" => Units::Wei,
-            _ => return Err(ConversionError::UnrecognizedUnits(s.to_string())),
+            _ => match s.parse() {
+                Ok(decimals) => Units::Other(decimals),
+                Err(_) => return Err(ConversionError::UnrecognizedUnits(s.to_string())),
+            },
         })
     }
 }
@@ -241,6 +244,9 @@ pub enum ConversionError {
     /// Invalid hex.
     #[error(transparent)]
     FromHexError(<Address as std::str::FromStr>::Err),
+    /// The amount has more decimals than the units, so it can't be represented exactly.
+    #[error("{0} has more decimals than the units allow")]
+    PrecisionLoss(String),
 }
 
 /// Divides the provided amount with 10^{units} provided.
@@ -282,6 +288,150 @@ where
     }
 }
 
+/// Multiplies the provided decimal amount with 10^{units} provided, without losing precision.
+///
+/// The amount can have a sign, a fractional part and an exponent, e.g. `-1.5e-3`. Underscores are
+/// ignored. Returns an error instead of rounding if the result isn't an integer.
+pub fn parse_units<K>(amount: &str, units: K) -> Result<ParseUnits, ConversionError>
+where
+    K: TryInto<Units, Error = ConversionError>,
+{
+    let units = units.try_into()?.as_num() as i64;
+    let invalid = || ConversionError::FromDecStrError(amount.to_string());
+
+    let cleaned = amount.trim().replace('_', "");
+    let (negative, unsigned) = match cleaned.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, cleaned.strip_prefix('+').unwrap_or(&cleaned)),
+    };
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().map_err(|_| invalid())?),
+        None => (unsigned, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if integer.is_empty() && fraction.is_empty() ||
+        !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid())
+    }
+
+    // the amount is `digits * 10^scale`
+    let mut digits = format!("{integer}{fraction}").trim_start_matches('0').to_string();
+    if digits.is_empty() {
+        return Ok(ParseUnits::U256(U256::ZERO))
+    }
+    let scale = units
+        .checked_add(exponent)
+        .and_then(|scale| scale.checked_sub(fraction.len() as i64))
+        .ok_or(ConversionError::ParseOverflow)?;
+    if scale >= 0 {
+        if scale as usize > OVERFLOW_U256_UNITS {
+            return Err(ConversionError::ParseOverflow)
+        }
+        digits.extend(std::iter::repeat('0').take(scale as usize));
+    } else {
+        let keep = digits.len().saturating_sub(scale.unsigned_abs() as usize);
+        if digits[keep..].chars().any(|c| c != '0') {
+            return Err(ConversionError::PrecisionLoss(amount.to_string()))
+        }
+        digits.truncate(keep);
+    }
+    let value = if digits.is_empty() {
+        U256::ZERO
+    } else {
+        U256::from_str_radix(&digits, 10).map_err(|_| ConversionError::ParseOverflow)?
+    };
+
+    if negative {
+        let value = I256::checked_from_sign_and_abs(Sign::Negative, value)
+            .ok_or(ConversionError::ParseOverflow)?;
+        Ok(ParseUnits::I256(value))
+    } else {
+        Ok(ParseUnits::U256(value))
+    }
+}
+
+/// How to display an amount formatted with [`format_units`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnitsDisplay {
+    /// The number of decimals to round to, half away from zero. Trailing zeros are trimmed if
+    /// not set.
+    pub precision: Option<usize>,
+    /// The separator to group the digits of the integer part in thousands with.
+    ///
+    /// This is independent of the locale, the decimal separator is always a `.`.
+    pub thousands_separator: Option<char>,
+}
+
+impl UnitsDisplay {
+    /// Divides the provided amount with 10^{units} provided and formats it for display.
+    pub fn format<T, K>(&self, amount: T, units: K) -> Result<String, ConversionError>
+    where
+        T: Into<ParseUnits>,
+        K: TryInto<Units, Error = ConversionError>,
+    {
+        let formatted = format_units(amount, units)?;
+        let (sign, unsigned) = match formatted.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", formatted.as_str()),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+        let (integer, fraction) = match self.precision {
+            Some(precision) if fraction.len() > precision => {
+                let round_up = fraction.as_bytes()[precision] >= b'5';
+                let mut digits = format!("{integer}{}", &fraction[..precision]).into_bytes();
+                if round_up {
+                    increment_digits(&mut digits);
+                }
+                let split = digits.len() - precision;
+                let digits = String::from_utf8(digits).expect("ascii digits");
+                (digits[..split].to_string(), digits[split..].to_string())
+            }
+            Some(precision) => (integer.to_string(), format!("{fraction:0<precision$}")),
+            None => (integer.to_string(), fraction.trim_end_matches('0').to_string()),
+        };
+
+        let integer = match self.thousands_separator {
+            Some(separator) => group_thousands(&integer, separator),
+            None => integer,
+        };
+        let is_zero =
+            integer.chars().chain(fraction.chars()).all(|c| !c.is_ascii_digit() || c == '0');
+        let sign = if is_zero { "" } else { sign };
+        if fraction.is_empty() {
+            Ok(format!("{sign}{integer}"))
+        } else {
+            Ok(format!("{sign}{integer}.{fraction}"))
+        }
+    }
+}
+
+/// Adds one to a big-endian string of ASCII digits, growing it on overflow.
+fn increment_digits(digits: &mut Vec<u8>) {
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return
+        }
+    }
+    digits.insert(0, b'1');
+}
+
+/// Inserts `separator` between every group of three digits, from the right.
+fn group_thousands(integer: &str, separator: char) -> String {
+    let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -326,4 +476,48 @@ mod tests {
         assert_eq!(Units::try_from(&"pwei".to_string()).unwrap(), Pwei);
         assert_eq!(Units::try_from(&"ether".to_string()).unwrap(), Ether);
     }
+
+    #[test]
+    fn test_parse_units() {
+        let parse = |amount: &str, units: &str| parse_units(amount, units).unwrap().to_string();
+        assert_eq!(parse("1", "ether"), "1000000000000000000");
+        assert_eq!(parse("1.5", "ether"), "1500000000000000000");
+        assert_eq!(parse("1.5e18", "wei"), "1500000000000000000");
+        assert_eq!(parse("15E-1", "gwei"), "1500000000");
+        assert_eq!(parse("-0.000000001", "ether"), "-1000000000");
+        assert_eq!(parse("1_000.00", "wei"), "1000");
+        assert_eq!(parse("0e100", "wei"), "0");
+        assert_eq!(parse(".5", "kwei"), "500");
+        assert_eq!(parse("1.20000", "kwei"), "1200");
+        assert_eq!(parse_units("0", "ether").unwrap(), ParseUnits::U256(U256::ZERO));
+        assert_eq!(parse("1.5", "6"), "1500000");
+
+        assert!(matches!(parse_units("1.0001", "kwei"), Err(ConversionError::PrecisionLoss(_))));
+        assert!(matches!(parse_units("1e78", "wei"), Err(ConversionError::ParseOverflow)));
+        assert!(matches!(
+            parse_units("1e9223372036854775807", "ether"),
+            Err(ConversionError::ParseOverflow)
+        ));
+        assert!(matches!(
+            parse_units("1.5e-9223372036854775808", "wei"),
+            Err(ConversionError::ParseOverflow)
+        ));
+        assert!(parse_units("1.2.3", "wei").is_err());
+        assert!(parse_units("abc", "wei").is_err());
+        assert!(parse_units("", "wei").is_err());
+    }
+
+    #[test]
+    fn test_display_units() {
+        let display = UnitsDisplay::default();
+        assert_eq!(display.format(U256::from(1_500_000_000_000_000_000u128), 18).unwrap(), "1.5");
+        assert_eq!(display.format(U256::from(1_000u64), 3).unwrap(), "1");
+        assert_eq!(display.format(I256::from_str("-1500").unwrap(), 3).unwrap(), "-1.5");
+
+        let display = UnitsDisplay { precision: Some(2), thousands_separator: Some(',') };
+        assert_eq!(display.format(U256::from(1_234_567_895u64), 3).unwrap(), "1,234,567.90");
+        assert_eq!(display.format(U256::from(999_995u64), 3).unwrap(), "1,000.00");
+        assert_eq!(display.format(I256::from_str("-4").unwrap(), 3).unwrap(), "0.00");
+        assert_eq!(display.format(U256::from(12u64), 0).unwrap(), "12.00");
+    }
 }
@@ -0,0 +1,578 @@
+//! Support for compiling Vyper sources alongside a [Project].
+//!
+//! [foundry_compilers] only compiles Solidity, so `.vy` files in the project's source, test and
+//! script directories are compiled with the `vyper` binary found in `PATH`, through its standard
+//! JSON interface. The output is normalized to the Solidity artifact format, including the storage
+//! layout, and written to `<out>/<File>.vy/<File>.json`, so Vyper contracts can be deployed with
+//! `vm.getCode` and `deployCode` in tests and scripts. Files with the same name in different
+//! directories are written to `<out>/<path>/<File>.vy/<File>.json` instead.
+//!
+//! The compilation is skipped if the sources, the compiler and the settings are unchanged since
+//! the last one, which is recorded in a cache file next to the Solidity cache.
+
+use crate::compile::SkipBuildFilters;
+use alloy_primitives::{keccak256, B256};
+use eyre::{Context, ContextCompat, Result};
+use foundry_compilers::{
+    artifacts::{Source, Sources},
+    ArtifactId, ConfigurableContractArtifact, FileFilter, Project, ProjectPathsConfig,
+};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// The extension of Vyper source files.
+pub const VYPER_EXTENSION: &str = "vy";
+
+/// The name of the cache file of the Vyper sources, next to the Solidity cache file.
+const CACHE_FILE_NAME: &str = "vyper-files-cache.json";
+
+/// The outputs requested from the compiler.
+const OUTPUT_SELECTION: &[&str] =
+    &["abi", "evm.bytecode", "evm.deployedBytecode", "evm.methodIdentifiers", "layout"];
+
+/// A `vyper` compiler binary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vyper {
+    /// The path to the binary.
+    pub path: PathBuf,
+    /// The version of the compiler.
+    pub version: Version,
+}
+
+impl Vyper {
+    /// Creates a new instance for the binary at the given path, and queries its version.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let output = Command::new(&path)
+            .arg("--version")
+            .output()
+            .wrap_err_with(|| format!("failed to run {}", path.display()))?;
+        let version = String::from_utf8_lossy(&output.stdout);
+        let version = parse_version(&version)
+            .wrap_err_with(|| format!("invalid vyper version: {}", version.trim()))?;
+        Ok(Self { path, version })
+    }
+
+    /// Finds the `vyper` binary in `PATH`.
+    pub fn find() -> Option<Self> {
+        let binary = if cfg!(windows) { "vyper.exe" } else { "vyper" };
+        let paths = std::env::var_os("PATH")?;
+        let path =
+            std::env::split_paths(&paths).map(|dir| dir.join(binary)).find(|p| p.is_file())?;
+        Self::new(path).ok()
+    }
+
+    /// Compiles the input with `vyper --standard-json`, resolving imports relative to `root`.
+    pub fn compile(&self, input: &VyperInput, root: &Path) -> Result<VyperOutput> {
+        let mut child = Command::new(&self.path)
+            .arg("--standard-json")
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .wrap_err_with(|| format!("failed to run {}", self.path.display()))?;
+        let stdin = child.stdin.as_mut().wrap_err("failed to open vyper stdin")?;
+        serde_json::to_writer(stdin, input)?;
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            eyre::bail!("vyper failed: {}", String::from_utf8_lossy(&output.stderr).trim())
+        }
+        serde_json::from_slice(&output.stdout).wrap_err("failed to parse vyper output")
+    }
+}
+
+/// The standard JSON input of the Vyper compiler.
+#[derive(Clone, Debug, Serialize)]
+pub struct VyperInput {
+    /// Always `Vyper`.
+    pub language: String,
+    /// The sources to compile, by path relative to the project root.
+    pub sources: Sources,
+    /// The compiler settings.
+    pub settings: VyperSettings,
+}
+
+/// The settings of the Vyper compiler.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VyperSettings {
+    /// The EVM version to compile for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evm_version: Option<String>,
+    /// The outputs to generate, by file.
+    pub output_selection: BTreeMap<String, Vec<String>>,
+}
+
+impl VyperInput {
+    /// Creates the input for the given sources.
+    pub fn new(sources: Sources, evm_version: Option<String>) -> Self {
+        let output_selection = BTreeMap::from([(
+            "*".to_string(),
+            OUTPUT_SELECTION.iter().map(|s| s.to_string()).collect(),
+        )]);
+        Self {
+            language: "Vyper".to_string(),
+            sources,
+            settings: VyperSettings { evm_version, output_selection },
+        }
+    }
+}
+
+/// The standard JSON output of the Vyper compiler.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct VyperOutput {
+    /// Errors and warnings.
+    #[serde(default)]
+    pub errors: Vec<VyperError>,
+    /// The raw contract outputs, by file and contract name.
+    #[serde(default)]
+    pub contracts: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+/// An error or warning of the Vyper compiler.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VyperError {
+    /// `error` or `warning`, errors are assumed if missing.
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// The error message.
+    pub message: String,
+    /// The error message with source location.
+    #[serde(default)]
+    pub formatted_message: Option<String>,
+}
+
+impl VyperError {
+    /// Returns true if this is a warning.
+    pub fn is_warning(&self) -> bool {
+        self.severity.as_deref().map_or(false, |s| s.eq_ignore_ascii_case("warning"))
+    }
+}
+
+impl fmt::Display for VyperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.formatted_message.as_deref().unwrap_or(&self.message).trim_end())
+    }
+}
+
+impl VyperOutput {
+    /// Converts the raw contract outputs to Solidity artifacts, by file and contract name.
+    pub fn artifacts(&self) -> Result<BTreeMap<(String, String), ConfigurableContractArtifact>> {
+        let mut artifacts = BTreeMap::new();
+        for (file, contracts) in &self.contracts {
+            for (name, contract) in contracts {
+                let artifact = to_artifact(&format!("{file}:{name}"), contract)
+                    .wrap_err_with(|| format!("invalid vyper output for {file}:{name}"))?;
+                artifacts.insert((file.clone(), name.clone()), artifact);
+            }
+        }
+        Ok(artifacts)
+    }
+}
+
+/// The output of compiling the Vyper sources of a project.
+#[derive(Clone, Debug)]
+pub struct VyperCompileOutput {
+    /// The compiler version.
+    pub version: Version,
+    /// The warnings of the compiler.
+    pub warnings: Vec<VyperError>,
+    /// The written artifacts, by source file and contract name.
+    pub artifacts: BTreeMap<(String, String), ConfigurableContractArtifact>,
+    /// The paths of the written artifacts, by source file and contract name.
+    pub artifact_paths: BTreeMap<(String, String), PathBuf>,
+    /// Whether the compilation was skipped because nothing changed.
+    pub unchanged: bool,
+}
+
+impl VyperCompileOutput {
+    /// Returns the artifacts with their IDs, with source paths relative to the project root like
+    /// the artifacts of a Solidity output with stripped file prefixes.
+    pub fn artifact_ids(&self) -> Vec<(ArtifactId, ConfigurableContractArtifact)> {
+        self.artifacts
+            .iter()
+            .map(|(key, artifact)| {
+                let (file, name) = key;
+                let id = ArtifactId {
+                    path: self.artifact_paths[key].clone(),
+                    name: name.clone(),
+                    source: PathBuf::from(file),
+                    version: self.version.clone(),
+                };
+                (id, artifact.clone())
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for VyperCompileOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.unchanged {
+            return f.write_str("No Vyper files changed, compilation skipped")
+        }
+        let files = self.artifacts.keys().map(|(file, _)| file).collect::<BTreeSet<_>>().len();
+        write!(
+            f,
+            "Compiled {files} Vyper {} with vyper {}",
+            if files == 1 { "file" } else { "files" },
+            self.version
+        )?;
+        for warning in &self.warnings {
+            write!(f, "\nWarning: {warning}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The record of the last Vyper compilation of a project.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct VyperCache {
+    /// The hash of the compiler version and the standard JSON input, which contains the sources
+    /// and the settings.
+    fingerprint: B256,
+    /// The compiler version.
+    version: String,
+    /// The paths of the written artifacts, by source file and contract name.
+    artifacts: BTreeMap<String, BTreeMap<String, PathBuf>>,
+}
+
+impl VyperCache {
+    /// Returns the path of the cache file of the project.
+    fn path(paths: &ProjectPathsConfig) -> PathBuf {
+        paths.cache.with_file_name(CACHE_FILE_NAME)
+    }
+
+    /// Reads the cache file of the project, if any.
+    fn read(paths: &ProjectPathsConfig) -> Option<Self> {
+        crate::fs::read_json_file(&Self::path(paths)).ok()
+    }
+
+    /// Reads the artifacts listed in the cache, if all of them still exist.
+    fn read_artifacts(
+        &self,
+    ) -> Option<BTreeMap<(String, String), (PathBuf, ConfigurableContractArtifact)>> {
+        let mut artifacts = BTreeMap::new();
+        for (file, contracts) in &self.artifacts {
+            for (name, path) in contracts {
+                let artifact = crate::fs::read_json_file(path).ok()?;
+                artifacts.insert((file.clone(), name.clone()), (path.clone(), artifact));
+            }
+        }
+        Some(artifacts)
+    }
+}
+
+/// Returns the Vyper files in the source, test and script directories of the project.
+pub fn vyper_sources(paths: &ProjectPathsConfig) -> Vec<PathBuf> {
+    let mut sources = [&paths.sources, &paths.tests, &paths.scripts]
+        .into_iter()
+        .filter(|dir| dir.is_dir())
+        .flat_map(|dir| crate::fs::files_with_ext(dir, VYPER_EXTENSION))
+        .collect::<Vec<_>>();
+    // the directories can be nested
+    sources.sort();
+    sources.dedup();
+    sources
+}
+
+/// Returns true if the project contains Vyper sources.
+pub fn has_vyper_sources(paths: &ProjectPathsConfig) -> bool {
+    !vyper_sources(paths).is_empty()
+}
+
+/// Compiles the Vyper sources of the project that are not excluded by the filters, and writes
+/// their artifacts.
+///
+/// Returns `None` if there are no Vyper sources.
+pub fn compile_project(
+    project: &Project,
+    filters: &SkipBuildFilters,
+) -> Result<Option<VyperCompileOutput>> {
+    let root = &project.paths.root;
+    let files = vyper_sources(&project.paths)
+        .into_iter()
+        .filter(|file| filters.is_match(file))
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        return Ok(None)
+    }
+
+    let vyper = Vyper::find().wrap_err(
+        "found Vyper sources, but no `vyper` compiler in PATH; install it with `pip install vyper`",
+    )?;
+
+    let mut sources = Sources::new();
+    for file in files {
+        let source = Source::read(&file)?;
+        sources.insert(file.strip_prefix(root).unwrap_or(&file).to_path_buf(), source);
+    }
+    let evm_version = project.solc_config.settings.evm_version.map(|v| v.to_string());
+    let input = VyperInput::new(sources, evm_version);
+
+    let mut fingerprint = serde_json::to_vec(&input)?;
+    fingerprint.extend_from_slice(vyper.version.to_string().as_bytes());
+    let fingerprint = keccak256(fingerprint);
+    if let Some(cache) = VyperCache::read(&project.paths).filter(|c| c.fingerprint == fingerprint) {
+        if let Some(cached) = cache.read_artifacts() {
+            trace!(version=%vyper.version, "vyper sources unchanged, skipping compilation");
+            let (artifact_paths, artifacts) = cached
+                .into_iter()
+                .map(|(key, (path, artifact))| ((key.clone(), path), (key, artifact)))
+                .unzip();
+            return Ok(Some(VyperCompileOutput {
+                version: vyper.version,
+                warnings: Vec::new(),
+                artifacts,
+                artifact_paths,
+                unchanged: true,
+            }))
+        }
+    }
+
+    trace!(version=%vyper.version, files=input.sources.len(), "compiling vyper sources");
+    let output = vyper.compile(&input, root)?;
+    let (warnings, errors): (Vec<_>, Vec<_>) =
+        output.errors.iter().cloned().partition(VyperError::is_warning);
+    if !errors.is_empty() {
+        eyre::bail!(
+            "vyper compilation failed:\n{}",
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+        )
+    }
+
+    let artifacts = output.artifacts()?;
+    let files = artifacts.keys().map(|(file, _)| file.as_str()).collect::<BTreeSet<_>>();
+    let mut cache =
+        VyperCache { fingerprint, version: vyper.version.to_string(), artifacts: BTreeMap::new() };
+    let mut artifact_paths = BTreeMap::new();
+    for ((file, name), artifact) in &artifacts {
+        let path =
+            artifact_dir(&project.paths.artifacts, file, &files)?.join(format!("{name}.json"));
+        crate::fs::create_dir_all(path.parent().expect("has parent"))?;
+        crate::fs::write_json_file(&path, artifact)?;
+        cache.artifacts.entry(file.clone()).or_default().insert(name.clone(), path.clone());
+        artifact_paths.insert((file.clone(), name.clone()), path);
+    }
+    let cache_path = VyperCache::path(&project.paths);
+    crate::fs::create_dir_all(cache_path.parent().expect("has parent"))?;
+    crate::fs::write_json_file(&cache_path, &cache)?;
+
+    Ok(Some(VyperCompileOutput {
+        version: vyper.version,
+        warnings,
+        artifacts,
+        artifact_paths,
+        unchanged: false,
+    }))
+}
+
+/// Returns the artifacts of the last Vyper compilation of the project, with their IDs.
+///
+/// The artifacts are read from the cache, so this is cheap after compiling the project.
+pub fn project_artifacts(
+    paths: &ProjectPathsConfig,
+) -> Result<Vec<(ArtifactId, ConfigurableContractArtifact)>> {
+    if !has_vyper_sources(paths) {
+        return Ok(Vec::new())
+    }
+    let Some(cache) = VyperCache::read(paths) else { return Ok(Vec::new()) };
+    let version = Version::parse(&cache.version)?;
+    let artifacts = cache.read_artifacts().wrap_err("Vyper artifacts are missing, rebuild")?;
+    Ok(artifacts
+        .into_iter()
+        .map(|((file, name), (path, artifact))| {
+            (ArtifactId { path, name, source: file.into(), version: version.clone() }, artifact)
+        })
+        .collect())
+}
+
+/// Returns the directory of the artifacts of a source file: `<out>/<File>.vy`, or
+/// `<out>/<path>/<File>.vy` if another source file has the same name.
+fn artifact_dir(out: &Path, file: &str, files: &BTreeSet<&str>) -> Result<PathBuf> {
+    let file_name = Path::new(file).file_name().wrap_err("invalid vyper source path")?;
+    let conflicts =
+        files.iter().filter(|other| Path::new(other).file_name() == Some(file_name)).count() > 1;
+    Ok(if conflicts { out.join(file) } else { out.join(file_name) })
+}
+
+/// Parses the output of `vyper --version`, e.g. `0.3.10+commit.91361694`.
+fn parse_version(version: &str) -> Option<Version> {
+    let version = Version::parse(version.trim().lines().next()?.trim()).ok()?;
+    Some(Version::new(version.major, version.minor, version.patch))
+}
+
+/// Converts a contract of the Vyper standard JSON output to a Solidity artifact.
+fn to_artifact(id: &str, contract: &Value) -> Result<ConfigurableContractArtifact> {
+    let mut artifact = Map::new();
+    artifact.insert("abi".to_string(), contract.get("abi").cloned().unwrap_or_else(|| json!([])));
+
+    let evm = contract.get("evm").cloned().unwrap_or_default();
+    for (key, output) in [("bytecode", "bytecode"), ("deployedBytecode", "deployedBytecode")] {
+        if let Some(object) = evm[output]["object"].as_str() {
+            artifact.insert(key.to_string(), json!({ "object": object, "linkReferences": {} }));
+        }
+    }
+
+    // vyper prefixes the selectors with `0x`, unlike solc
+    if let Some(ids) = evm["methodIdentifiers"].as_object() {
+        let ids = ids
+            .iter()
+            .filter_map(|(sig, id)| {
+                Some((sig.clone(), id.as_str()?.trim_start_matches("0x").into()))
+            })
+            .collect::<Map<_, _>>();
+        artifact.insert("methodIdentifiers".to_string(), ids.into());
+    }
+
+    if let Some(layout) = contract.get("layout") {
+        artifact.insert("storageLayout".to_string(), storage_layout(id, layout));
+    }
+
+    Ok(serde_json::from_value(artifact.into())?)
+}
+
+/// Converts a Vyper storage layout to the Solidity storage layout format.
+///
+/// Vyper lists variables as `name -> {type, slot, n_slots}`, nested for the variables of imported
+/// modules, which are labeled `module.name`.
+fn storage_layout(id: &str, layout: &Value) -> Value {
+    fn collect(prefix: &str, layout: &Value, vars: &mut Vec<(u64, String, String, u64)>) {
+        let Some(layout) = layout.as_object() else { return };
+        for (name, item) in layout {
+            let label = if prefix.is_empty() { name.clone() } else { format!("{prefix}.{name}") };
+            match (item.get("slot").and_then(Value::as_u64), item.get("type")) {
+                (Some(slot), Some(ty)) => {
+                    let ty = ty.as_str().map(str::to_string).unwrap_or_else(|| ty.to_string());
+                    let n_slots = item.get("n_slots").and_then(Value::as_u64).unwrap_or(1);
+                    vars.push((slot, label, ty, n_slots));
+                }
+                _ => collect(&label, item, vars),
+            }
+        }
+    }
+
+    let layout = layout.get("storage_layout").unwrap_or(layout);
+    let mut vars = Vec::new();
+    collect("", layout, &mut vars);
+    vars.sort();
+
+    let mut storage = Vec::new();
+    let mut types = Map::new();
+    for (slot, label, ty, n_slots) in vars {
+        let type_id = format!(
+            "t_{}",
+            ty.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect::<String>()
+        );
+        storage.push(json!({
+            "astId": 0,
+            "contract": id,
+            "label": label,
+            "offset": 0,
+            "slot": slot.to_string(),
+            "type": type_id,
+        }));
+        types.entry(type_id).or_insert_with(|| {
+            json!({
+                "encoding": "inplace",
+                "label": ty,
+                "numberOfBytes": (n_slots * 32).to_string(),
+            })
+        });
+    }
+
+    json!({ "storage": storage, "types": types })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_compilers::Artifact;
+
+    #[test]
+    fn can_parse_vyper_version() {
+        assert_eq!(parse_version("0.3.10+commit.91361694\n"), Some(Version::new(0, 3, 10)));
+        assert_eq!(parse_version("0.4.0"), Some(Version::new(0, 4, 0)));
+        assert_eq!(parse_version("vyper"), None);
+    }
+
+    #[test]
+    fn can_convert_vyper_output() {
+        let output: VyperOutput = serde_json::from_value(json!({
+            "compiler": "vyper-0.3.10",
+            "contracts": {
+                "src/Counter.vy": {
+                    "Counter": {
+                        "abi": [{
+                            "type": "function",
+                            "name": "number",
+                            "stateMutability": "view",
+                            "inputs": [],
+                            "outputs": [{"name": "", "type": "uint256"}]
+                        }],
+                        "evm": {
+                            "bytecode": {"object": "0x6003"},
+                            "deployedBytecode": {"object": "0x6004"},
+                            "methodIdentifiers": {"number()": "0x8381f58a"}
+                        },
+                        "layout": {
+                            "storage_layout": {
+                                "owner": {"type": "address", "slot": 1},
+                                "number": {"type": "uint256", "slot": 0},
+                                "lib": {"balances": {"type": "HashMap[address, uint256]", "slot": 2}}
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let artifacts = output.artifacts().unwrap();
+        let artifact = &artifacts[&("src/Counter.vy".to_string(), "Counter".to_string())];
+        assert_eq!(artifact.get_bytecode_bytes().unwrap().as_ref(), &[0x60, 0x03]);
+        assert_eq!(artifact.get_deployed_bytecode_bytes().unwrap().as_ref(), &[0x60, 0x04]);
+        assert_eq!(artifact.method_identifiers.as_ref().unwrap()["number()"], "8381f58a");
+
+        let layout = artifact.storage_layout.as_ref().unwrap();
+        let labels = layout.storage.iter().map(|s| s.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["number", "owner", "lib.balances"]);
+        assert_eq!(layout.storage[2].storage_type, "t_HashMap_address__uint256_");
+        assert_eq!(layout.types["t_address"].label, "address");
+    }
+
+    #[test]
+    fn same_named_sources_get_separate_artifact_dirs() {
+        let out = Path::new("out");
+        let files = BTreeSet::from(["src/Token.vy", "src/v2/Token.vy", "src/Vault.vy"]);
+        assert_eq!(artifact_dir(out, "src/Vault.vy", &files).unwrap(), out.join("Vault.vy"));
+        assert_eq!(artifact_dir(out, "src/Token.vy", &files).unwrap(), out.join("src/Token.vy"));
+        assert_eq!(
+            artifact_dir(out, "src/v2/Token.vy", &files).unwrap(),
+            out.join("src/v2/Token.vy")
+        );
+    }
+
+    #[test]
+    fn vyper_errors_fail_but_warnings_do_not() {
+        let output: VyperOutput = serde_json::from_value(json!({
+            "errors": [
+                {"severity": "warning", "message": "unused"},
+                {"type": "SyntaxException", "message": "invalid syntax", "formattedMessage": "line 1: invalid syntax"}
+            ]
+        }))
+        .unwrap();
+        assert!(output.errors[0].is_warning());
+        assert!(!output.errors[1].is_warning());
+        assert_eq!(output.errors[1].to_string(), "line 1: invalid syntax");
+    }
+}
@@ -1,7 +1,8 @@
 //! Configuration for fuzz testing.
 
 use crate::inline::{
-    parse_config_u32, InlineConfigParser, InlineConfigParserError, INLINE_CONFIG_FUZZ_KEY,
+    parse_config_bool, parse_config_u32, InlineConfigParser, InlineConfigParserError,
+    INLINE_CONFIG_FUZZ_KEY,
 };
 use alloy_primitives::U256;
 use serde::{Deserialize, Serialize};
@@ -19,6 +20,12 @@ pub struct FuzzConfig {
     pub max_test_rejects: u32,
     /// Optional seed for the fuzzing RNG algorithm
     pub seed: Option<U256>,
+    /// Whether to guide the fuzzer with the edge coverage of the executions.
+    ///
+    /// Inputs that take new branches are kept in a corpus shared by the fuzz tests of a contract,
+    /// and half of the inputs are generated by mutating them. The fuzz tests of a contract then
+    /// run one after another, so that the seed reproduces the corpus.
+    pub coverage_guided: bool,
     /// The fuzz dictionary configuration
     #[serde(flatten)]
     pub dictionary: FuzzDictionaryConfig,
@@ -30,6 +37,7 @@ impl Default for FuzzConfig {
             runs: 256,
             max_test_rejects: 65536,
             seed: None,
+            coverage_guided: false,
             dictionary: FuzzDictionaryConfig::default(),
         }
     }
@@ -56,6 +64,7 @@ impl InlineConfigParser for FuzzConfig {
             match key.as_str() {
                 "runs" => conf_clone.runs = parse_config_u32(key, value)?,
                 "max-test-rejects" => conf_clone.max_test_rejects = parse_config_u32(key, value)?,
+                "coverage-guided" => conf_clone.coverage_guided = parse_config_bool(key, value)?,
                 "dictionary-weight" => {
                     conf_clone.dictionary.dictionary_weight = parse_config_u32(key, value)?
                 }
@@ -122,11 +131,13 @@ mod tests {
         let configs = &[
             "forge-config: default.fuzz.runs = 42424242".to_string(),
             "forge-config: default.fuzz.dictionary-weight = 42".to_string(),
+            "forge-config: default.fuzz.coverage-guided = true".to_string(),
         ];
         let base_config = FuzzConfig::default();
         let merged: FuzzConfig = base_config.try_merge(configs).expect("No errors").unwrap();
         assert_eq!(merged.runs, 42424242);
         assert_eq!(merged.dictionary.dictionary_weight, 42);
+        assert!(merged.coverage_guided);
     }
 
     #[test]
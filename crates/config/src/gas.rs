@@ -0,0 +1,68 @@
+//! Configuration for the gas of test calls
+
+use crate::inline::{
+    parse_config_u64, InlineConfigParser, InlineConfigParserError, INLINE_CONFIG_GAS_KEY,
+};
+use serde::{Deserialize, Serialize};
+
+/// Contains the gas settings of a test, which are only set with inline configs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasConfig {
+    /// The gas limit of the test calls, overriding the `gas_limit` of the profile.
+    pub limit: Option<u64>,
+}
+
+impl InlineConfigParser for GasConfig {
+    fn config_key() -> String {
+        INLINE_CONFIG_GAS_KEY.into()
+    }
+
+    fn try_merge(&self, configs: &[String]) -> Result<Option<Self>, InlineConfigParserError> {
+        let overrides: Vec<(String, String)> = Self::get_config_overrides(configs);
+
+        if overrides.is_empty() {
+            return Ok(None)
+        }
+
+        let mut conf_clone = *self;
+
+        for (key, value) in overrides {
+            match key.as_str() {
+                "limit" => conf_clone.limit = Some(parse_config_u64(key, value)?),
+                _ => Err(InlineConfigParserError::InvalidConfigProperty(key))?,
+            }
+        }
+        Ok(Some(conf_clone))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{inline::InlineConfigParser, GasConfig};
+
+    #[test]
+    fn successful_merge() {
+        let configs = &[
+            "forge-config: default.gas.limit = 30000000000".to_string(),
+            "forge-config: default.fuzz.runs = 2".to_string(),
+        ];
+        let merged = GasConfig::default().try_merge(configs).expect("No errors").unwrap();
+        assert_eq!(merged.limit, Some(30_000_000_000));
+    }
+
+    #[test]
+    fn merge_is_none_unrelated_property() {
+        let unrelated_configs = &["forge-config: default.invariant.runs = 2".to_string()];
+        let merged = GasConfig::default().try_merge(unrelated_configs).expect("No errors");
+        assert!(merged.is_none());
+    }
+
+    #[test]
+    fn invalid_values() {
+        let configs = &["forge-config: default.gas.limit = -1".to_string()];
+        assert!(GasConfig::default().try_merge(configs).is_err());
+        let configs = &["forge-config: default.gas.price = 1".to_string()];
+        let err = GasConfig::default().try_merge(configs).unwrap_err();
+        assert_eq!(err.to_string(), "'price' is an invalid config property");
+    }
+}
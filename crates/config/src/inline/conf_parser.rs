@@ -19,6 +19,9 @@ use regex::Regex;
 /// function test_ImportantFuzzTest(uint256 x) public {...}
 /// }
 /// ```
+///
+/// Configurations annotating a contract apply to all of its test functions, unless the test
+/// function overrides them.
 pub trait InlineConfigParser
 where
     Self: Clone + Default + Sized + 'static,
@@ -131,6 +134,12 @@ pub fn parse_config_u32(key: String, value: String) -> Result<u32, InlineConfigP
     value.parse().map_err(|_| InlineConfigParserError::ParseInt(key, value))
 }
 
+/// Tries to parse a `u64` from `value`. The `key` argument is used to give details
+/// in the case of an error.
+pub fn parse_config_u64(key: String, value: String) -> Result<u64, InlineConfigParserError> {
+    value.parse().map_err(|_| InlineConfigParserError::ParseInt(key, value))
+}
+
 /// Tries to parse a `bool` from `value`. The `key` argument is used to give details
 /// in the case of an error.
 pub fn parse_config_bool(key: String, value: String) -> Result<bool, InlineConfigParserError> {
@@ -1,5 +1,7 @@
 use crate::Config;
-pub use conf_parser::{parse_config_bool, parse_config_u32, validate_profiles, InlineConfigParser};
+pub use conf_parser::{
+    parse_config_bool, parse_config_u32, parse_config_u64, validate_profiles, InlineConfigParser,
+};
 pub use error::{InlineConfigError, InlineConfigParserError};
 pub use natspec::NatSpec;
 use once_cell::sync::Lazy;
@@ -11,6 +13,7 @@ mod natspec;
 
 pub const INLINE_CONFIG_FUZZ_KEY: &str = "fuzz";
 pub const INLINE_CONFIG_INVARIANT_KEY: &str = "invariant";
+pub const INLINE_CONFIG_GAS_KEY: &str = "gas";
 const INLINE_CONFIG_PREFIX: &str = "forge-config";
 
 static INLINE_CONFIG_PREFIX_SELECTED_PROFILE: Lazy<String> = Lazy::new(|| {
@@ -35,7 +35,9 @@ impl NatSpec {
             // id.identifier
             let contract = format!("{}:{}", path.display(), id.name);
             let Some(node) = contract_root_node(&ast.nodes, &contract) else { continue };
-            apply(&mut natspecs, &contract, node)
+            // configs of the contract apply to all of its functions
+            let contract_docs = get_docs(&node.other);
+            apply(&mut natspecs, &contract, node, contract_docs.as_ref())
         }
 
         natspecs
@@ -85,28 +87,44 @@ fn contract_root_node<'a>(nodes: &'a [Node], contract_id: &'a str) -> Option<&'a
 
 /// Implements a DFS over a compiler output node and its children.
 /// If a natspec is found it is added to `natspecs`
-fn apply(natspecs: &mut Vec<NatSpec>, contract: &str, node: &Node) {
+fn apply(
+    natspecs: &mut Vec<NatSpec>,
+    contract: &str,
+    node: &Node,
+    contract_docs: Option<&(String, String)>,
+) {
     for n in node.nodes.iter() {
-        if let Some((function, docs, line)) = get_fn_data(n) {
+        if let Some((function, docs, line)) = get_fn_data(n, contract_docs) {
             natspecs.push(NatSpec { contract: contract.into(), function, line, docs })
         }
-        apply(natspecs, contract, n);
+        apply(natspecs, contract, n, contract_docs);
     }
 }
 
 /// Given a compilation output node, if it is a function definition
-/// that also contains a natspec then return a tuple of:
+/// that also contains a natspec, or is declared in a contract that does,
+/// then return a tuple of:
 /// - Function name
-/// - Natspec text
+/// - Natspec text, the contract natspec followed by the function natspec
 /// - Natspec position with format "row:col:length"
 ///
 /// Return None otherwise.
-fn get_fn_data(node: &Node) -> Option<(String, String, String)> {
+fn get_fn_data(
+    node: &Node,
+    contract_docs: Option<&(String, String)>,
+) -> Option<(String, String, String)> {
     if let NodeType::FunctionDefinition = node.node_type {
         let fn_data = &node.other;
         let fn_name: String = get_fn_name(fn_data)?;
-        let (fn_docs, docs_src_line): (String, String) = get_fn_docs(fn_data)?;
-        return Some((fn_name, fn_docs, docs_src_line))
+        // the function natspec comes last, so that its configs override the contract ones
+        let (docs, docs_src_line): (String, String) = match (contract_docs, get_docs(fn_data)) {
+            (Some((contract_docs, _)), Some((fn_docs, src_line))) => {
+                (format!("{contract_docs}\n{fn_docs}"), src_line)
+            }
+            (Some(contract_docs), None) => contract_docs.clone(),
+            (None, fn_docs) => fn_docs?,
+        };
+        return Some((fn_name, docs, docs_src_line))
     }
 
     None
@@ -120,11 +138,12 @@ fn get_fn_name(fn_data: &BTreeMap<String, Value>) -> Option<String> {
     }
 }
 
-/// Inspects Solc compiler output for documentation comments. Returns:
-/// - `Some((String, String))` in case the function has natspec comments. First item is a textual
-///   natspec representation, the second item is the natspec src line, in the form "raw:col:length".
-/// - `None` in case the function has not natspec comments.
-fn get_fn_docs(fn_data: &BTreeMap<String, Value>) -> Option<(String, String)> {
+/// Inspects Solc compiler output of a function or contract for documentation comments. Returns:
+/// - `Some((String, String))` in case the function or contract has natspec comments. First item is
+///   a textual natspec representation, the second item is the natspec src line, in the form
+///   "raw:col:length".
+/// - `None` in case the function or contract has not natspec comments.
+fn get_docs(fn_data: &BTreeMap<String, Value>) -> Option<(String, String)> {
     if let Value::Object(fn_docs) = fn_data.get("documentation")? {
         if let Value::String(comment) = fn_docs.get("text")? {
             if comment.contains(INLINE_CONFIG_PREFIX) {
@@ -143,7 +162,11 @@ fn get_fn_docs(fn_data: &BTreeMap<String, Value>) -> Option<(String, String)> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{inline::natspec::get_fn_docs, NatSpec};
+    use crate::{
+        inline::natspec::{get_docs, get_fn_data},
+        NatSpec,
+    };
+    use foundry_compilers::artifacts::Node;
     use serde_json::{json, Value};
     use std::collections::BTreeMap;
 
@@ -195,7 +218,7 @@ mod tests {
         let mut fn_data: BTreeMap<String, Value> = BTreeMap::new();
         let doc_without_src_field = json!({ "text":  "forge-config:default.fuzz.runs=600" });
         fn_data.insert("documentation".into(), doc_without_src_field);
-        let (_, src_line) = get_fn_docs(&fn_data).expect("Some docs");
+        let (_, src_line) = get_docs(&fn_data).expect("Some docs");
         assert_eq!(src_line, "<no-src-line-available>".to_string());
     }
 
@@ -205,10 +228,42 @@ mod tests {
         let doc_without_src_field =
             json!({ "text":  "forge-config:default.fuzz.runs=600", "src": "73:21:12" });
         fn_data.insert("documentation".into(), doc_without_src_field);
-        let (_, src_line) = get_fn_docs(&fn_data).expect("Some docs");
+        let (_, src_line) = get_docs(&fn_data).expect("Some docs");
         assert_eq!(src_line, "73:21:12".to_string());
     }
 
+    #[test]
+    fn merges_contract_docs() {
+        let contract_docs =
+            ("forge-config: default.fuzz.runs = 10".to_string(), "1:2:3".to_string());
+        let function = |docs: Option<&str>| -> Node {
+            let mut node =
+                json!({ "nodeType": "FunctionDefinition", "src": "0:0:0", "name": "test_fuzz" });
+            if let Some(docs) = docs {
+                node["documentation"] = json!({ "text": docs, "src": "4:5:6" });
+            }
+            serde_json::from_value(node).unwrap()
+        };
+
+        let (_, docs, src_line) = get_fn_data(
+            &function(Some("forge-config: default.fuzz.runs = 20")),
+            Some(&contract_docs),
+        )
+        .unwrap();
+        assert_eq!(
+            docs,
+            "forge-config: default.fuzz.runs = 10\nforge-config: default.fuzz.runs = 20"
+        );
+        assert_eq!(src_line, "4:5:6");
+
+        let (name, docs, src_line) = get_fn_data(&function(None), Some(&contract_docs)).unwrap();
+        assert_eq!(name, "test_fuzz");
+        assert_eq!(docs, "forge-config: default.fuzz.runs = 10");
+        assert_eq!(src_line, "1:2:3");
+
+        assert!(get_fn_data(&function(None), None).is_none());
+    }
+
     fn natspec() -> NatSpec {
         let conf = r"
         forge-config: default.fuzz.runs = 600 
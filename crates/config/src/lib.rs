@@ -50,7 +50,10 @@ pub use endpoints::{ResolvedRpcEndpoints, RpcEndpoint, RpcEndpoints};
 
 mod etherscan;
 mod resolve;
+
+mod wallets;
 pub use resolve::UnresolvedEnvVarError;
+pub use wallets::{WalletConfig, WalletConfigs};
 
 pub mod cache;
 use cache::{Cache, ChainCache};
@@ -67,6 +70,9 @@ pub use error::SolidityErrorCode;
 pub mod doc;
 pub use doc::DocConfig;
 
+pub mod lint;
+pub use lint::{LintConfig, LintSeverity};
+
 mod warning;
 pub use warning::*;
 
@@ -89,6 +95,12 @@ use providers::*;
 mod fuzz;
 pub use fuzz::{FuzzConfig, FuzzDictionaryConfig};
 
+mod gas;
+pub use gas::GasConfig;
+
+mod optimizer;
+pub use optimizer::OptimizerPreset;
+
 mod invariant;
 use crate::fs_permissions::PathPermission;
 pub use invariant::InvariantConfig;
@@ -198,6 +210,9 @@ pub struct Config {
     /// The "enabled" switch above provides two defaults which can be
     /// tweaked here. If "details" is given, "enabled" can be omitted.
     pub optimizer_details: Option<OptimizerDetails>,
+    /// A named set of optimizer settings, if set this enables the optimizer and overrides
+    /// `optimizer_runs`.
+    pub optimizer_preset: Option<OptimizerPreset>,
     /// Model checker settings.
     pub model_checker: Option<ModelCheckerSettings>,
     /// verbosity to use
@@ -211,10 +226,16 @@ pub struct Config {
     /// Multiple etherscan api configs and their aliases
     #[serde(default, skip_serializing_if = "EtherscanConfigs::is_empty")]
     pub etherscan: EtherscanConfigs,
+    /// Per-sender gas settings of the wallets used to broadcast transactions
+    #[serde(default, skip_serializing_if = "WalletConfigs::is_empty")]
+    pub wallets: WalletConfigs,
     /// list of solidity error codes to always silence in the compiler output
     pub ignored_error_codes: Vec<SolidityErrorCode>,
     /// When true, compiler warnings are treated as errors
     pub deny_warnings: bool,
+    /// When true, `forge test` and `forge script` fail if cached artifacts were built with
+    /// different compiler settings than the current config, instead of warning
+    pub deny_settings_mismatch: bool,
     /// Only run test functions matching the specified regex pattern.
     #[serde(rename = "match_test")]
     pub test_pattern: Option<RegexWrapper>,
@@ -239,6 +260,9 @@ pub struct Config {
     pub invariant: InvariantConfig,
     /// Whether to allow ffi cheatcodes in test
     pub ffi: bool,
+    /// Whether to run the top-level calls of tests as separate transactions, with their own
+    /// nonce, gas metering and cold storage
+    pub isolate: bool,
     /// The address which will be executing all tests
     pub sender: Address,
     /// The tx.origin value during EVM execution
@@ -278,6 +302,16 @@ pub struct Config {
     ///
     /// The default is 128MiB.
     pub memory_limit: u64,
+    /// The maximum call depth a test may reach, tests going deeper fail. Fuzz and invariant
+    /// tests fail if any of their runs goes deeper.
+    ///
+    /// Catches code that will hit the EVM's call depth limit of 1024 in production.
+    pub max_call_depth: Option<u64>,
+    /// The maximum number of bytes the memory of a single call frame of a test may expand to,
+    /// tests expanding further fail.
+    ///
+    /// Catches code whose memory costs grow quadratically with its inputs.
+    pub max_memory_expansion: Option<u64>,
     /// Additional output selection for all contracts, such as "ir", "devdoc", "storageLayout",
     /// etc.
     ///
@@ -358,6 +392,8 @@ pub struct Config {
     pub fmt: FormatterConfig,
     /// Configuration for `forge doc`
     pub doc: DocConfig,
+    /// Configuration for `forge lint`
+    pub lint: LintConfig,
     /// Configures the permissions of cheat codes that touch the file system.
     ///
     /// This includes what operations can be executed (read, write)
@@ -411,7 +447,7 @@ impl Config {
 
     /// Standalone sections in the config which get integrated into the selected profile
     pub const STANDALONE_SECTIONS: &'static [&'static str] =
-        &["rpc_endpoints", "etherscan", "fmt", "doc", "fuzz", "invariant"];
+        &["rpc_endpoints", "etherscan", "wallets", "fmt", "doc", "lint", "fuzz", "invariant"];
 
     /// File name of config toml file
     pub const FILE_NAME: &'static str = "foundry.toml";
@@ -984,10 +1020,14 @@ impl Config {
 
     /// Returns the `Optimizer` based on the configured settings
     pub fn optimizer(&self) -> Optimizer {
+        let (enabled, runs) = match self.optimizer_preset {
+            Some(preset) => (true, preset.runs()),
+            None => (self.optimizer, self.optimizer_runs),
+        };
         // only configure optimizer settings if optimizer is enabled
-        let details = if self.optimizer { self.optimizer_details.clone() } else { None };
+        let details = if enabled { self.optimizer_details.clone() } else { None };
 
-        Optimizer { enabled: Some(self.optimizer), runs: Some(self.optimizer_runs), details }
+        Optimizer { enabled: Some(enabled), runs: Some(runs), details }
     }
 
     /// returns the [`foundry_compilers::ConfigurableArtifacts`] for this config, that includes the
@@ -1315,9 +1355,11 @@ impl Config {
     }
 
     /// Returns the path to the cache file of the `block` on the `chain`
-    /// `~/.foundry/cache/rpc/<chain>/<block>/storage.json`
+    /// `~/.foundry/cache/rpc/<chain>/<block>/storage.bin`
+    ///
+    /// Caches of older versions are stored in `storage.json` next to it.
     pub fn foundry_block_cache_file(chain_id: impl Into<Chain>, block: u64) -> Option<PathBuf> {
-        Some(Self::foundry_block_cache_dir(chain_id, block)?.join("storage.json"))
+        Some(Self::foundry_block_cache_dir(chain_id, block)?.join("storage.bin"))
     }
 
     #[doc = r#"Returns the path to `foundry`'s data directory inside the user's data directory
@@ -1473,11 +1515,13 @@ impl Config {
             return Ok(blocks)
         }
         for block in chain_path.read_dir()?.flatten().filter(|x| x.file_type().unwrap().is_dir()) {
-            let filepath = block.path().join("storage.json");
-            blocks.push((
-                block.file_name().to_string_lossy().into_owned(),
-                fs::metadata(filepath)?.len(),
-            ));
+            // the memory-mapped cache, and the json cache of older versions
+            let size = ["storage.bin", "storage.json"]
+                .iter()
+                .filter_map(|file| fs::metadata(block.path().join(file)).ok())
+                .map(|metadata| metadata.len())
+                .sum::<u64>();
+            blocks.push((block.file_name().to_string_lossy().into_owned(), size));
         }
         Ok(blocks)
     }
@@ -1773,6 +1817,7 @@ impl Default for Config {
             optimizer: true,
             optimizer_runs: 200,
             optimizer_details: None,
+            optimizer_preset: None,
             model_checker: None,
             extra_output: Default::default(),
             extra_output_files: Default::default(),
@@ -1787,6 +1832,7 @@ impl Default for Config {
             fuzz: Default::default(),
             invariant: Default::default(),
             ffi: false,
+            isolate: false,
             sender: Config::DEFAULT_SENDER,
             tx_origin: Config::DEFAULT_SENDER,
             initial_balance: U256::from(0xffffffffffffffffffffffffu128),
@@ -1803,6 +1849,8 @@ impl Default for Config {
             block_prevrandao: Default::default(),
             block_gas_limit: None,
             memory_limit: 1 << 27, // 2**27 = 128MiB = 134_217_728 bytes
+            max_call_depth: None,
+            max_memory_expansion: None,
             eth_rpc_url: None,
             eth_rpc_jwt: None,
             etherscan_api_key: None,
@@ -1816,10 +1864,12 @@ impl Default for Config {
                 SolidityErrorCode::ContractInitCodeSizeExceeds49152Bytes,
             ],
             deny_warnings: false,
+            deny_settings_mismatch: false,
             via_ir: false,
             rpc_storage_caching: Default::default(),
             rpc_endpoints: Default::default(),
             etherscan: Default::default(),
+            wallets: Default::default(),
             no_storage_caching: false,
             no_rpc_rate_limit: false,
             use_literal_content: false,
@@ -1831,6 +1881,7 @@ impl Default for Config {
             build_info_path: None,
             fmt: Default::default(),
             doc: Default::default(),
+            lint: Default::default(),
             __non_exhaustive: (),
             __warnings: vec![],
         }
@@ -2910,6 +2961,36 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_wallet_gas_policies() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+
+                [wallets.0x1804c8AB1F12E6bbf3894d4083f33e07309d1f38]
+                max_fee_per_gas = 50000000000
+                max_priority_fee_per_gas = 2000000000
+                gas_estimate_multiplier = 200
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.wallets.gas_policy(&Config::DEFAULT_SENDER),
+                WalletConfig {
+                    max_fee_per_gas: Some(50_000_000_000),
+                    max_priority_fee_per_gas: Some(2_000_000_000),
+                    gas_estimate_multiplier: Some(200),
+                }
+            );
+            assert_eq!(config.wallets.gas_policy(&Address::ZERO), WalletConfig::default());
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_resolve_etherscan_with_chain() {
         figment::Jail::expect_with(|jail| {
@@ -4043,6 +4124,45 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_optimizer_preset() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r"
+                [profile.default]
+                optimizer = false
+                optimizer_runs = 10
+
+                [profile.size]
+                optimizer = false
+                optimizer_preset = 'size'
+
+                [profile.gas]
+                optimizer_preset = 'gas'
+                via_ir = true
+            ",
+            )?;
+            let config = Config::load();
+            assert_eq!(config.optimizer_preset, None);
+            assert_eq!(config.optimizer().runs, Some(10));
+            assert_eq!(config.optimizer().enabled, Some(false));
+
+            jail.set_env("FOUNDRY_PROFILE", "size");
+            let config = Config::load();
+            assert_eq!(config.optimizer_preset, Some(OptimizerPreset::Size));
+            assert_eq!(config.optimizer().runs, Some(1));
+            assert_eq!(config.optimizer().enabled, Some(true));
+
+            jail.set_env("FOUNDRY_PROFILE", "gas");
+            let config = Config::load();
+            assert_eq!(config.optimizer().runs, Some(1_000_000));
+            assert!(config.via_ir);
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_model_checker_settings_basic() {
         figment::Jail::expect_with(|jail| {
@@ -4175,6 +4295,36 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_lint_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [lint]
+                severity = { floating-pragma = "off", shadowing = "error" }
+                ignore = ["src/legacy/**"]
+            "#,
+            )?;
+            let loaded = Config::load().sanitized();
+            assert_eq!(
+                loaded.lint.severity_of("floating-pragma", LintSeverity::Warning),
+                LintSeverity::Off
+            );
+            assert_eq!(
+                loaded.lint.severity_of("shadowing", LintSeverity::Warning),
+                LintSeverity::Error
+            );
+            assert_eq!(
+                loaded.lint.severity_of("missing-visibility", LintSeverity::Warning),
+                LintSeverity::Warning
+            );
+            assert_eq!(loaded.lint.ignore, vec!["src/legacy/**".to_string()]);
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_invariant_config() {
         figment::Jail::expect_with(|jail| {
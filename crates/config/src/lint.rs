@@ -0,0 +1,54 @@
+//! Configuration specific to the `forge lint` command
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+/// Contains the config for the built-in lints
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Severities of lints by their id, overriding the default severity of the lint.
+    ///
+    /// Lints set to `off` are not run.
+    pub severity: BTreeMap<String, LintSeverity>,
+    /// Globs to ignore
+    pub ignore: Vec<String>,
+}
+
+impl LintConfig {
+    /// Returns the configured severity of the lint, or `default` if none is configured
+    pub fn severity_of(&self, id: &str, default: LintSeverity) -> LintSeverity {
+        self.severity.get(id).copied().unwrap_or(default)
+    }
+}
+
+/// The severity of a lint finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// The lint is disabled
+    Off,
+    /// Findings are reported as suggestions
+    Info,
+    /// Findings are reported and counted as warnings, but don't make `forge lint` fail
+    Warning,
+    /// Findings make `forge lint` fail
+    Error,
+}
+
+impl LintSeverity {
+    /// Returns the name of the severity as used in `foundry.toml`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintSeverity::Off => "off",
+            LintSeverity::Info => "info",
+            LintSeverity::Warning => "warning",
+            LintSeverity::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,60 @@
+//! Optimizer presets
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// A named set of optimizer settings.
+///
+/// If a preset is configured it enables the optimizer and overrides `optimizer_runs`, while
+/// `optimizer_details` still apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptimizerPreset {
+    /// Optimize for the size of the deployed code, at the cost of runtime gas.
+    Size,
+    /// The default number of runs of solc.
+    Balanced,
+    /// Optimize for runtime gas, at the cost of code size and deployment gas.
+    Gas,
+}
+
+impl OptimizerPreset {
+    /// Returns the number of optimizer runs of the preset
+    pub fn runs(&self) -> usize {
+        match self {
+            OptimizerPreset::Size => 1,
+            OptimizerPreset::Balanced => 200,
+            OptimizerPreset::Gas => 1_000_000,
+        }
+    }
+
+    /// Returns the name of the preset as used in `foundry.toml`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OptimizerPreset::Size => "size",
+            OptimizerPreset::Balanced => "balanced",
+            OptimizerPreset::Gas => "gas",
+        }
+    }
+}
+
+impl fmt::Display for OptimizerPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OptimizerPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "size" => Ok(OptimizerPreset::Size),
+            "balanced" => Ok(OptimizerPreset::Balanced),
+            "gas" => Ok(OptimizerPreset::Gas),
+            s => {
+                Err(format!("unknown optimizer preset `{s}`, expected `size`, `balanced` or `gas`"))
+            }
+        }
+    }
+}
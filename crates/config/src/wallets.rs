@@ -0,0 +1,66 @@
+//! Support for per-sender wallet settings.
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+};
+
+/// Container type for the settings of wallets, by address, configured with
+/// `[wallets.<address>]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WalletConfigs {
+    configs: BTreeMap<Address, WalletConfig>,
+}
+
+// === impl WalletConfigs ===
+
+impl WalletConfigs {
+    /// Creates a new list of wallet configs
+    pub fn new(configs: impl IntoIterator<Item = (Address, WalletConfig)>) -> Self {
+        Self { configs: configs.into_iter().collect() }
+    }
+
+    /// Returns `true` if this type doesn't contain any configs
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+    }
+
+    /// Returns the gas policy of the sender, or the default policy if none is configured
+    pub fn gas_policy(&self, sender: &Address) -> WalletConfig {
+        self.configs.get(sender).copied().unwrap_or_default()
+    }
+}
+
+impl Deref for WalletConfigs {
+    type Target = BTreeMap<Address, WalletConfig>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.configs
+    }
+}
+
+impl DerefMut for WalletConfigs {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.configs
+    }
+}
+
+/// Gas settings applied to the transactions sent by a wallet, e.g. to use a different fee strategy
+/// for a hot wallet than for a hardware-signed treasury.
+///
+/// Fees set on the command line take precedence over these settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletConfig {
+    /// The max fee per gas in wei, used as the gas price of legacy transactions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<u64>,
+    /// The max priority fee per gas in wei
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<u64>,
+    /// Relative percentage to multiply gas estimates by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_estimate_multiplier: Option<u64>,
+}
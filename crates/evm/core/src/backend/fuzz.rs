@@ -11,8 +11,8 @@ use alloy_primitives::{Address, B256, U256};
 use ethers_core::utils::GenesisAccount;
 use revm::{
     db::DatabaseRef,
-    primitives::{AccountInfo, Bytecode, Env, ResultAndState},
-    Database, Inspector, JournaledState,
+    primitives::{Account, AccountInfo, Bytecode, Env, HashMap as Map, ResultAndState},
+    Database, DatabaseCommit, Inspector, JournaledState,
 };
 use std::{borrow::Cow, collections::HashMap};
 
@@ -260,6 +260,13 @@ impl<'a> DatabaseRef for FuzzBackendWrapper<'a> {
     }
 }
 
+impl<'a> DatabaseCommit for FuzzBackendWrapper<'a> {
+    fn commit(&mut self, changes: Map<Address, Account>) {
+        // committing clones the backend, the borrowed `backend` is never modified
+        self.backend.to_mut().commit(changes)
+    }
+}
+
 impl<'a> Database for FuzzBackendWrapper<'a> {
     type Error = DatabaseError;
 
@@ -26,7 +26,8 @@ pub const TEST_CONTRACT_ADDRESS: Address = address!("b4c79daB8f259C7Aee6E5b2Aa72
 /// Magic return value returned by the `assume` cheatcode.
 pub const MAGIC_ASSUME: &[u8] = b"FOUNDRY::ASSUME";
 
-/// Magic return value returned by the `skip` cheatcode.
+/// Magic return value returned by the `skip` cheatcode, followed by the reason of the skip, if
+/// any.
 pub const MAGIC_SKIP: &[u8] = b"FOUNDRY::SKIP";
 
 /// The default CREATE2 deployer.
@@ -67,9 +67,12 @@ pub fn maybe_decode_revert(
         }
     }
 
-    if err == crate::constants::MAGIC_SKIP {
+    if let Some(reason) = err.strip_prefix(crate::constants::MAGIC_SKIP) {
         // Also used in forge fuzz runner
-        return Some("SKIPPED".to_string());
+        if reason.is_empty() {
+            return Some("SKIPPED".to_string());
+        }
+        return Some(format!("SKIPPED: {}", String::from_utf8_lossy(reason)));
     }
 
     // Solidity's `Error(string)` or `Panic(uint256)`
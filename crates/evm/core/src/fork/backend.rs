@@ -1,7 +1,7 @@
 //! Smart caching and deduplication of requests when using a forking provider
 use crate::{
     backend::{DatabaseError, DatabaseResult},
-    fork::{cache::FlushJsonBlockCacheDB, BlockchainDb},
+    fork::{cache::FlushBlockCacheDB, BlockchainDb},
 };
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
 use ethers_core::{
@@ -139,7 +139,7 @@ where
         match req {
             BackendRequest::Basic(addr, sender) => {
                 trace!(target: "backendhandler", "received request basic address={:?}", addr);
-                let acc = self.db.accounts().read().get(&addr).cloned();
+                let acc = self.cached(|db| db.accounts().read().get(&addr).cloned());
                 if let Some(basic) = acc {
                     let _ = sender.send(Ok(basic));
                 } else {
@@ -147,7 +147,8 @@ where
                 }
             }
             BackendRequest::BlockHash(number, sender) => {
-                let hash = self.db.block_hashes().read().get(&U256::from(number)).cloned();
+                let hash =
+                    self.cached(|db| db.block_hashes().read().get(&U256::from(number)).cloned());
                 if let Some(hash) = hash {
                     let _ = sender.send(Ok(hash));
                 } else {
@@ -162,8 +163,9 @@ where
             }
             BackendRequest::Storage(addr, idx, sender) => {
                 // account is already stored in the cache
-                let value =
-                    self.db.storage().read().get(&addr).and_then(|acc| acc.get(&idx).copied());
+                let value = self.cached(|db| {
+                    db.storage().read().get(&addr).and_then(|acc| acc.get(&idx).copied())
+                });
                 if let Some(value) = value {
                     let _ = sender.send(Ok(value));
                 } else {
@@ -177,6 +179,12 @@ where
         }
     }
 
+    /// Looks up a value in the cache. If it's missing, the entries other processes appended to the
+    /// cache file in the meantime are imported and the value is looked up again.
+    fn cached<T>(&self, get: impl Fn(&BlockchainDb) -> Option<T>) -> Option<T> {
+        get(&self.db).or_else(|| if self.db.cache().refresh() { get(&self.db) } else { None })
+    }
+
     /// process a request for account's storage
     fn request_account_storage(&mut self, address: Address, idx: U256, listener: StorageSender) {
         match self.storage_requests.entry((address, idx)) {
@@ -361,7 +369,7 @@ where
                                 code: Some(Bytecode::new_raw(code).to_checked()),
                                 code_hash,
                             };
-                            pin.db.accounts().write().insert(addr, acc.clone());
+                            pin.db.insert_account(addr, acc.clone());
 
                             // notify all listeners
                             if let Some(listeners) = pin.account_requests.remove(&addr) {
@@ -395,7 +403,7 @@ where
                             };
 
                             // update the cache
-                            pin.db.storage().write().entry(addr).or_default().insert(idx, value);
+                            pin.db.insert_storage(addr, idx, value);
 
                             // notify all listeners
                             if let Some(listeners) = pin.storage_requests.remove(&(addr, idx)) {
@@ -426,7 +434,7 @@ where
                             };
 
                             // update the cache
-                            pin.db.block_hashes().write().insert(U256::from(number), value);
+                            pin.db.insert_block_hash(U256::from(number), value);
 
                             // notify all listeners
                             if let Some(listeners) = pin.block_requests.remove(&number) {
@@ -514,8 +522,8 @@ pub struct SharedBackend {
     /// Ensures that the underlying cache gets flushed once the last `SharedBackend` is dropped.
     ///
     /// There is only one instance of the type, so as soon as the last `SharedBackend` is deleted,
-    /// `FlushJsonBlockCacheDB` is also deleted and the cache is flushed.
-    cache: Arc<FlushJsonBlockCacheDB>,
+    /// `FlushBlockCacheDB` is also deleted and the cache is flushed.
+    cache: Arc<FlushBlockCacheDB>,
 }
 
 impl SharedBackend {
@@ -577,7 +585,7 @@ impl SharedBackend {
         M: Middleware + Unpin + 'static + Clone,
     {
         let (backend, backend_rx) = channel(1);
-        let cache = Arc::new(FlushJsonBlockCacheDB(Arc::clone(db.cache())));
+        let cache = Arc::new(FlushBlockCacheDB(Arc::clone(db.cache())));
         let handler = BackendHandler::new(provider, db, backend_rx, pin_block);
         (Self { backend, cache }, handler)
     }
@@ -796,7 +804,7 @@ mod tests {
 
         let db = BlockchainDb::new(
             meta,
-            Some(Config::foundry_block_cache_dir(NamedChain::Mainnet, block_num).unwrap()),
+            Some(Config::foundry_block_cache_file(NamedChain::Mainnet, block_num).unwrap()),
         );
         assert!(db.accounts().read().contains_key(&address));
         assert!(db.storage().read().contains_key(&address));
@@ -1,4 +1,5 @@
 //! Cache related abstraction
+use super::mmap::MmapBlockCacheDB;
 use crate::backend::StateSnapshot;
 use alloy_primitives::{Address, B256, U256};
 use parking_lot::RwLock;
@@ -7,13 +8,7 @@ use revm::{
     DatabaseCommit,
 };
 use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
-use std::{
-    collections::BTreeSet,
-    fs,
-    io::{BufWriter, Write},
-    path::PathBuf,
-    sync::Arc,
-};
+use std::{collections::BTreeSet, fs, path::PathBuf, sync::Arc};
 use url::Url;
 
 pub type StorageInfo = Map<U256, U256>;
@@ -26,13 +21,13 @@ pub struct BlockchainDb {
     /// metadata of the current config
     meta: Arc<RwLock<BlockchainDbMeta>>,
     /// the cache that can be flushed
-    cache: Arc<JsonBlockCacheDB>,
+    cache: Arc<MmapBlockCacheDB>,
 }
 
 impl BlockchainDb {
     /// Creates a new instance of the [BlockchainDb]
     ///
-    /// if a `cache_path` is provided it attempts to load a previously stored [JsonBlockCacheData]
+    /// if a `cache_path` is provided it attempts to map a previously stored [MmapBlockCacheDB]
     /// and will try to use the cached entries it holds.
     ///
     /// This will return a new and empty [MemDb] if
@@ -47,7 +42,7 @@ impl BlockchainDb {
     /// Creates a new instance of the [BlockchainDb] and skips check when comparing meta
     /// This is useful for offline-start mode when we don't want to fetch metadata of `block`.
     ///
-    /// if a `cache_path` is provided it attempts to load a previously stored [JsonBlockCacheData]
+    /// if a `cache_path` is provided it attempts to map a previously stored [MmapBlockCacheDB]
     /// and will try to use the cached entries it holds.
     ///
     /// This will return a new and empty [MemDb] if
@@ -65,7 +60,7 @@ impl BlockchainDb {
         let cache = cache_path
             .as_ref()
             .and_then(|p| {
-                JsonBlockCacheDB::load(p).ok().filter(|cache| {
+                MmapBlockCacheDB::load(p).ok().filter(|cache| {
                     if skip_check {
                         return true
                     }
@@ -79,7 +74,7 @@ impl BlockchainDb {
                     }
                 })
             })
-            .unwrap_or_else(|| JsonBlockCacheDB::new(Arc::new(RwLock::new(meta)), cache_path));
+            .unwrap_or_else(|| MmapBlockCacheDB::new(Arc::new(RwLock::new(meta)), cache_path));
 
         Self { db: Arc::clone(cache.db()), meta: Arc::clone(cache.meta()), cache: Arc::new(cache) }
     }
@@ -99,13 +94,31 @@ impl BlockchainDb {
         &self.db.block_hashes
     }
 
+    /// Inserts an account fetched from the remote endpoint, and queues it for the cache file
+    pub fn insert_account(&self, address: Address, info: AccountInfo) {
+        self.cache.append_account(address, &info);
+        self.db.accounts.write().insert(address, info);
+    }
+
+    /// Inserts a storage slot fetched from the remote endpoint, and queues it for the cache file
+    pub fn insert_storage(&self, address: Address, slot: U256, value: U256) {
+        self.cache.append_storage(address, slot, value);
+        self.db.storage.write().entry(address).or_default().insert(slot, value);
+    }
+
+    /// Inserts a block hash fetched from the remote endpoint, and queues it for the cache file
+    pub fn insert_block_hash(&self, number: U256, hash: B256) {
+        self.cache.append_block_hash(number, hash);
+        self.db.block_hashes.write().insert(number, hash);
+    }
+
     /// Returns the [revm::Env] related metadata
     pub fn meta(&self) -> &Arc<RwLock<BlockchainDbMeta>> {
         &self.meta
     }
 
     /// Returns the inner cache
-    pub fn cache(&self) -> &Arc<JsonBlockCacheDB> {
+    pub fn cache(&self) -> &Arc<MmapBlockCacheDB> {
         &self.cache
     }
 
@@ -332,6 +345,9 @@ impl DatabaseCommit for MemDb {
 }
 
 /// A [BlockCacheDB] that stores the cached content in a json file
+///
+/// This was the format of the fork cache before [MmapBlockCacheDB], existing json caches are
+/// imported by it.
 #[derive(Debug)]
 pub struct JsonBlockCacheDB {
     /// Where this cache file is stored.
@@ -343,11 +359,6 @@ pub struct JsonBlockCacheDB {
 }
 
 impl JsonBlockCacheDB {
-    /// Creates a new instance.
-    fn new(meta: Arc<RwLock<BlockchainDbMeta>>, cache_path: Option<PathBuf>) -> Self {
-        Self { cache_path, data: JsonBlockCacheData { meta, data: Arc::new(Default::default()) } }
-    }
-
     /// Loads the contents of the diskmap file and returns the read object
     ///
     /// # Errors
@@ -393,18 +404,10 @@ impl JsonBlockCacheDB {
             let _ = fs::create_dir_all(parent);
         }
 
-        let file = match fs::File::create(path) {
-            Ok(file) => file,
-            Err(e) => return warn!(target: "cache", %e, "Failed to open json cache for writing"),
-        };
-
-        let mut writer = BufWriter::new(file);
-        if let Err(e) = serde_json::to_writer(&mut writer, &self.data) {
+        // written atomically and synced to disk, so a crash never leaves a truncated cache
+        if let Err(e) = foundry_common::fs::write_json_file_durable(path, &self.data) {
             return warn!(target: "cache", %e, "Failed to write to json cache")
         }
-        if let Err(e) = writer.flush() {
-            return warn!(target: "cache", %e, "Failed to flush to json cache")
-        }
 
         trace!(target: "cache", "saved json cache");
     }
@@ -462,14 +465,14 @@ impl<'de> Deserialize<'de> for JsonBlockCacheData {
     }
 }
 
-/// A type that flushes a `JsonBlockCacheDB` on drop
+/// A type that flushes a `MmapBlockCacheDB` on drop
 ///
 /// This type intentionally does not implement `Clone` since it's intended that there's only once
 /// instance that will flush the cache.
 #[derive(Debug)]
-pub struct FlushJsonBlockCacheDB(pub Arc<JsonBlockCacheDB>);
+pub struct FlushBlockCacheDB(pub Arc<MmapBlockCacheDB>);
 
-impl Drop for FlushJsonBlockCacheDB {
+impl Drop for FlushBlockCacheDB {
     fn drop(&mut self) {
         trace!(target: "fork::cache", "flushing cache");
         self.0.flush();
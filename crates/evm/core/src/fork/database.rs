@@ -74,8 +74,9 @@ impl ForkedDatabase {
 
         // TODO need to find a way to update generic provider via url
 
-        // wipe the storage retrieved from remote
+        // wipe the storage retrieved from remote, the cache file belongs to the previous block
         self.inner().db().clear();
+        self.inner().cache().disable();
         // create a fresh `CacheDB`, effectively wiping modified state
         self.cache_db = CacheDB::new(self.backend.clone());
         trace!(target: "backend::forkdb", "Cleared database");
@@ -0,0 +1,818 @@
+//! A memory-mapped fork cache that is shared by all processes forking the same block.
+//!
+//! The cache file is an append-only log of the fetched accounts, storage slots and block hashes:
+//!
+//! ```text
+//! magic (8 bytes) | committed length (u64 LE) | record | record | ...
+//! record = tag (u8) | payload length (u32 LE) | payload
+//! ```
+//!
+//! The first record holds the [BlockchainDbMeta] of the cache. Values fetched from the remote
+//! endpoint are handed to a writer thread, which appends them in batches under an exclusive lock
+//! of the file, and the committed length is only bumped after the records were written. Readers
+//! read the committed length from the file and only map the records up to it, so they never take
+//! the lock and never see partially written records. A process that misses a value first imports
+//! the records other processes appended in the meantime, instead of fetching the value again.
+
+use super::cache::{BlockchainDbMeta, JsonBlockCacheDB, MemDb};
+use crate::backend::StateSnapshot;
+use alloy_primitives::{Address, B256, U256};
+use fs2::FileExt;
+use memmap2::{Mmap, MmapOptions};
+use parking_lot::{Mutex, RwLock};
+use revm::primitives::{AccountInfo, Bytecode};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+};
+
+/// The magic bytes at the start of a cache file, including the version of the format.
+const MAGIC: &[u8; 8] = b"FNDRYC01";
+
+/// The length of the magic bytes and the committed length.
+const HEADER_LEN: usize = 16;
+
+/// The length of the tag and payload length of a record.
+const RECORD_HEADER_LEN: usize = 5;
+
+const TAG_META: u8 = 0;
+const TAG_ACCOUNT: u8 = 1;
+const TAG_STORAGE: u8 = 2;
+const TAG_BLOCK_HASH: u8 = 3;
+
+/// An entry of the cache file.
+#[derive(Clone, Debug)]
+enum Record {
+    Meta(BlockchainDbMeta),
+    Account(Address, AccountInfo),
+    Storage(Address, U256, U256),
+    BlockHash(U256, B256),
+}
+
+impl Record {
+    /// Appends the encoded record to `buf`.
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let start = buf.len();
+        buf.extend_from_slice(&[0; RECORD_HEADER_LEN]);
+        let tag = match self {
+            Record::Meta(meta) => {
+                serde_json::to_writer(&mut *buf, meta).expect("meta is serializable");
+                TAG_META
+            }
+            Record::Account(address, info) => {
+                buf.extend_from_slice(address.as_slice());
+                buf.extend_from_slice(&info.balance.to_be_bytes::<32>());
+                buf.extend_from_slice(&info.nonce.to_be_bytes());
+                buf.extend_from_slice(info.code_hash.as_slice());
+                match &info.code {
+                    Some(code) => {
+                        buf.push(1);
+                        buf.extend_from_slice(&code.original_bytes());
+                    }
+                    None => buf.push(0),
+                }
+                TAG_ACCOUNT
+            }
+            Record::Storage(address, slot, value) => {
+                buf.extend_from_slice(address.as_slice());
+                buf.extend_from_slice(&slot.to_be_bytes::<32>());
+                buf.extend_from_slice(&value.to_be_bytes::<32>());
+                TAG_STORAGE
+            }
+            Record::BlockHash(number, hash) => {
+                buf.extend_from_slice(&number.to_be_bytes::<32>());
+                buf.extend_from_slice(hash.as_slice());
+                TAG_BLOCK_HASH
+            }
+        };
+        let len = (buf.len() - start - RECORD_HEADER_LEN) as u32;
+        buf[start] = tag;
+        buf[start + 1..start + RECORD_HEADER_LEN].copy_from_slice(&len.to_le_bytes());
+    }
+
+    /// Decodes the record at the start of `buf`, and returns it with its encoded length.
+    ///
+    /// Records with an unknown tag are skipped and returned as `None`. Returns `None` if the
+    /// record is truncated or malformed.
+    fn decode(buf: &[u8]) -> Option<(Option<Self>, usize)> {
+        let header = buf.get(..RECORD_HEADER_LEN)?;
+        let len = u32::from_le_bytes(header[1..].try_into().unwrap()) as usize;
+        let payload = buf.get(RECORD_HEADER_LEN..RECORD_HEADER_LEN + len)?;
+        let word = |offset: usize| U256::from_be_slice(&payload[offset..offset + 32]);
+
+        let record = match header[0] {
+            TAG_META => Record::Meta(serde_json::from_slice(payload).ok()?),
+            TAG_ACCOUNT if len >= 93 => {
+                let code = match payload[92] {
+                    0 => None,
+                    _ => Some(Bytecode::new_raw(payload[93..].to_vec().into()).to_checked()),
+                };
+                Record::Account(
+                    Address::from_slice(&payload[..20]),
+                    AccountInfo {
+                        balance: word(20),
+                        nonce: u64::from_be_bytes(payload[52..60].try_into().unwrap()),
+                        code_hash: B256::from_slice(&payload[60..92]),
+                        code,
+                    },
+                )
+            }
+            TAG_STORAGE if len == 84 => {
+                Record::Storage(Address::from_slice(&payload[..20]), word(20), word(52))
+            }
+            TAG_BLOCK_HASH if len == 64 => {
+                Record::BlockHash(word(0), B256::from_slice(&payload[32..]))
+            }
+            TAG_ACCOUNT | TAG_STORAGE | TAG_BLOCK_HASH => return None,
+            _ => return Some((None, RECORD_HEADER_LEN + len)),
+        };
+        Some((Some(record), RECORD_HEADER_LEN + len))
+    }
+}
+
+/// The records decoded from a cache file.
+#[derive(Debug, Default)]
+struct Decoded {
+    /// The last metadata record.
+    meta: Option<BlockchainDbMeta>,
+    /// The entries, later records of the same key take precedence.
+    state: StateSnapshot,
+    /// The length of the decoded records.
+    len: usize,
+}
+
+/// Decodes the records in `buf`, up to the first truncated or malformed one.
+fn decode_records(buf: &[u8]) -> Decoded {
+    let mut decoded = Decoded::default();
+    while let Some((record, len)) = Record::decode(&buf[decoded.len..]) {
+        decoded.len += len;
+        match record {
+            Some(Record::Meta(meta)) => decoded.meta = Some(meta),
+            Some(Record::Account(address, info)) => {
+                decoded.state.accounts.insert(address, info);
+            }
+            Some(Record::Storage(address, slot, value)) => {
+                decoded.state.storage.entry(address).or_default().insert(slot, value);
+            }
+            Some(Record::BlockHash(number, hash)) => {
+                decoded.state.block_hashes.insert(number, hash);
+            }
+            None => {}
+        }
+    }
+    decoded
+}
+
+/// Returns the committed length of a cache file header, or 0 if it's not a cache file.
+fn committed_len(header: &[u8; HEADER_LEN]) -> usize {
+    if &header[..8] != MAGIC {
+        return 0
+    }
+    u64::from_le_bytes(header[8..].try_into().unwrap()) as usize
+}
+
+/// Reads the committed length of the cache file.
+///
+/// The header is read from the file instead of the map, because it's rewritten by the processes
+/// appending to the file.
+fn read_committed_len(file: &File) -> io::Result<usize> {
+    let mut header = [0; HEADER_LEN];
+    read_exact_at(file, &mut header, 0)?;
+    let committed = committed_len(&header);
+    if committed < HEADER_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid fork cache file"))
+    }
+    Ok(committed)
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    while !buf.is_empty() {
+        match std::os::windows::fs::FileExt::seek_read(file, buf, offset)? {
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the header and the metadata record of a new cache file.
+fn encode_new_file(meta: &BlockchainDbMeta) -> Vec<u8> {
+    let mut buf = MAGIC.to_vec();
+    buf.extend_from_slice(&[0; 8]);
+    Record::Meta(meta.clone()).encode(&mut buf);
+    let len = buf.len() as u64;
+    buf[8..HEADER_LEN].copy_from_slice(&len.to_le_bytes());
+    buf
+}
+
+/// Maps the records of the cache file up to the committed length.
+fn map(file: &File, committed: usize) -> io::Result<Mmap> {
+    // SAFETY: the header is not mapped, and cache files are only ever appended to after the
+    // committed length, or replaced by renaming a new file over them, so the mapped bytes never
+    // change while they are mapped
+    unsafe { MmapOptions::new().offset(HEADER_LEN as u64).len(committed - HEADER_LEN).map(file) }
+}
+
+/// Appends the encoded records to the cache file, under an exclusive lock of the file.
+///
+/// Returns the committed length before and after the records were appended.
+fn append_locked(file: &mut File, buf: &[u8]) -> io::Result<(usize, usize)> {
+    file.lock_exclusive()?;
+    let res = (|| {
+        let committed = read_committed_len(file)?;
+        file.seek(SeekFrom::Start(committed as u64))?;
+        file.write_all(buf)?;
+        // the records are only visible to readers once the committed length covers them
+        let end = committed + buf.len();
+        file.seek(SeekFrom::Start(8))?;
+        file.write_all(&(end as u64).to_le_bytes())?;
+        Ok((committed, end))
+    })();
+    file.unlock()?;
+    res
+}
+
+/// Returns a path next to `path` that's not used by any other process or cache.
+fn tmp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.{}.tmp", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+    PathBuf::from(tmp)
+}
+
+/// Creates the cache file, or opens it if another process with the same block environment
+/// already created it.
+///
+/// An existing file of a different block environment is replaced.
+fn create(path: &Path, meta: &BlockchainDbMeta) -> io::Result<MappedFile> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let contents = encode_new_file(meta);
+
+    // the file is written under a temporary name and then linked or renamed into place, so that
+    // other processes never see a file without header, and processes which still have an old file
+    // mapped are not affected
+    let tmp = tmp_path(path);
+    let mut file = OpenOptions::new().read(true).write(true).create_new(true).open(&tmp)?;
+    let res = (|| {
+        file.write_all(&contents)?;
+        match fs::hard_link(&tmp, path) {
+            Ok(()) => return Ok(None),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(err),
+        }
+
+        if let Ok(mut mapped) = MappedFile::open(path) {
+            if let Ok(Some(decoded)) = mapped.read_new_records() {
+                if decoded.meta.as_ref() == Some(meta) {
+                    // the entries of the other process are imported on the next refresh
+                    mapped.synced = HEADER_LEN;
+                    return Ok(Some(mapped))
+                }
+            }
+        }
+
+        trace!(target: "cache", ?path, "replacing fork cache of a different block environment");
+        fs::rename(&tmp, path)?;
+        Ok(None)
+    })();
+    let _ = fs::remove_file(&tmp);
+    if let Some(mapped) = res? {
+        return Ok(mapped)
+    }
+    let map = map(&file, contents.len())?;
+    Ok(MappedFile { file, map, synced: contents.len() })
+}
+
+/// An open cache file.
+#[derive(Debug)]
+struct MappedFile {
+    file: File,
+    /// The records of the file, from the end of the header up to the committed length when the
+    /// file was last mapped.
+    map: Mmap,
+    /// The end of the records that were imported into the [MemDb].
+    synced: usize,
+}
+
+impl MappedFile {
+    /// Opens and maps an existing cache file.
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let map = map(&file, HEADER_LEN)?;
+        Ok(Self { file, map, synced: HEADER_LEN })
+    }
+
+    /// Remaps the file if records were committed since it was last mapped.
+    fn remap(&mut self) -> io::Result<()> {
+        let committed = read_committed_len(&self.file)?.min(self.file.metadata()?.len() as usize);
+        if committed > HEADER_LEN + self.map.len() {
+            self.map = map(&self.file, committed)?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the records that weren't imported yet, remapping the file if it grew.
+    fn read_new_records(&mut self) -> io::Result<Option<Decoded>> {
+        self.remap()?;
+        if HEADER_LEN + self.map.len() <= self.synced {
+            return Ok(None)
+        }
+        let decoded = decode_records(&self.map[self.synced - HEADER_LEN..]);
+        self.synced += decoded.len;
+        Ok(Some(decoded))
+    }
+}
+
+/// A message to the writer thread of a [MmapBlockCacheDB].
+enum WriterMsg {
+    /// Appends the record to the cache file.
+    Append(Record),
+    /// Notifies the sender once all previous records were written.
+    Sync(mpsc::Sender<()>),
+}
+
+/// Spawns the thread that appends the records sent to it to the cache file.
+///
+/// All records that are queued when the thread wakes up are appended at once. The thread exits
+/// once the sender is dropped and all records were written.
+fn spawn_writer(
+    meta: Arc<RwLock<BlockchainDbMeta>>,
+    file: Arc<Mutex<Option<CacheFile>>>,
+) -> mpsc::Sender<WriterMsg> {
+    let (tx, rx) = mpsc::channel();
+    let spawned = std::thread::Builder::new().name("fork-cache-writer".into()).spawn(move || {
+        while let Ok(msg) = rx.recv() {
+            let mut records = Vec::new();
+            let mut syncs = Vec::new();
+            for msg in std::iter::once(msg).chain(rx.try_iter()) {
+                match msg {
+                    WriterMsg::Append(record) => records.push(record),
+                    WriterMsg::Sync(tx) => syncs.push(tx),
+                }
+            }
+            if let Err(err) = write_records(&meta, &file, &records) {
+                warn!(target: "cache", %err, "Failed to append to fork cache")
+            }
+            for tx in syncs {
+                let _ = tx.send(());
+            }
+        }
+    });
+    if let Err(err) = spawned {
+        // the records are dropped with the receiver, and nothing is written
+        warn!(target: "cache", %err, "Failed to spawn fork cache writer");
+    }
+    tx
+}
+
+/// Appends the records to the cache file, creating the file first if it's not open yet.
+///
+/// The file is locked while the records are appended, so this must not be called on the async
+/// path of the fork backend.
+fn write_records(
+    meta: &RwLock<BlockchainDbMeta>,
+    file: &Mutex<Option<CacheFile>>,
+    records: &[Record],
+) -> io::Result<()> {
+    if records.is_empty() {
+        return Ok(())
+    }
+    // the cache isn't locked while waiting for the file lock, so that lookups are not blocked by
+    // other processes
+    let mut handle = {
+        let mut file = file.lock();
+        let Some(file) = file.as_mut() else { return Ok(()) };
+        if file.mapped.is_none() {
+            file.mapped = Some(create(&file.path, &meta.read())?);
+        }
+        file.mapped.as_ref().unwrap().file.try_clone()?
+    };
+
+    let mut buf = Vec::new();
+    for record in records {
+        record.encode(&mut buf);
+    }
+    let (start, end) = append_locked(&mut handle, &buf)?;
+    // our own records don't need to be imported again
+    if let Some(mapped) = file.lock().as_mut().and_then(|file| file.mapped.as_mut()) {
+        if mapped.synced == start {
+            mapped.synced = end;
+        }
+    }
+    Ok(())
+}
+
+/// The cache file of a [MmapBlockCacheDB].
+#[derive(Debug)]
+struct CacheFile {
+    path: PathBuf,
+    /// The open file, `None` until the first entry is written if the file doesn't exist yet or
+    /// belongs to a different block environment.
+    mapped: Option<MappedFile>,
+}
+
+/// A block cache that's stored in a memory-mapped file shared by all processes forking the
+/// same block.
+///
+/// Lookups are served by the [MemDb], which is filled from the file when the cache is loaded and
+/// whenever a value is missing and other processes appended to the file since.
+#[derive(Debug)]
+pub struct MmapBlockCacheDB {
+    meta: Arc<RwLock<BlockchainDbMeta>>,
+    data: Arc<MemDb>,
+    /// The cache file, `None` if caching is disabled.
+    file: Arc<Mutex<Option<CacheFile>>>,
+    /// The sender to the writer thread, `None` if this is a transient cache.
+    writer: Option<mpsc::Sender<WriterMsg>>,
+}
+
+impl MmapBlockCacheDB {
+    /// Creates a new, empty instance.
+    ///
+    /// The file at `cache_path` is replaced once the first entry is written.
+    pub fn new(meta: Arc<RwLock<BlockchainDbMeta>>, cache_path: Option<PathBuf>) -> Self {
+        let file = cache_path.map(|path| CacheFile { path, mapped: None });
+        Self::with_file(meta, Default::default(), file)
+    }
+
+    /// Creates an instance that writes to `file`, spawning the writer thread if it's set.
+    fn with_file(
+        meta: Arc<RwLock<BlockchainDbMeta>>,
+        data: Arc<MemDb>,
+        file: Option<CacheFile>,
+    ) -> Self {
+        let writer = file.is_some();
+        let file = Arc::new(Mutex::new(file));
+        let writer = writer.then(|| spawn_writer(Arc::clone(&meta), Arc::clone(&file)));
+        Self { meta, data, file, writer }
+    }
+
+    /// Maps the cache file and imports its entries.
+    ///
+    /// If the file doesn't exist yet, the legacy JSON cache next to it is imported instead, and
+    /// written to the file on the next flush.
+    ///
+    /// # Errors
+    /// This will fail if
+    ///   - neither the `path` nor the legacy JSON cache exists
+    ///   - the file is not a cache file or has no metadata
+    pub fn load(path: impl Into<PathBuf>) -> eyre::Result<Self> {
+        let path = path.into();
+        let legacy = path.with_extension("json");
+        if !path.exists() && legacy.exists() && legacy != path {
+            let json = JsonBlockCacheDB::load(legacy)?;
+            return Ok(Self::with_file(
+                Arc::clone(json.meta()),
+                Arc::clone(json.db()),
+                Some(CacheFile { path, mapped: None }),
+            ))
+        }
+
+        trace!(target: "cache", ?path, "mapping fork cache");
+        let mut mapped = MappedFile::open(&path).map_err(|err| {
+            trace!(target: "cache", ?err, ?path, "Failed to open cache file");
+            err
+        })?;
+        let decoded = mapped.read_new_records()?.unwrap_or_default();
+        let Some(meta) = decoded.meta else {
+            warn!(target: "cache", ?path, "Invalid fork cache file");
+            eyre::bail!("invalid fork cache file {}", path.display())
+        };
+        let StateSnapshot { accounts, storage, block_hashes } = decoded.state;
+        Ok(Self::with_file(
+            Arc::new(RwLock::new(meta)),
+            Arc::new(MemDb {
+                accounts: RwLock::new(accounts),
+                storage: RwLock::new(storage),
+                block_hashes: RwLock::new(block_hashes),
+            }),
+            Some(CacheFile { path, mapped: Some(mapped) }),
+        ))
+    }
+
+    /// Returns the [MemDb] it holds access to
+    pub fn db(&self) -> &Arc<MemDb> {
+        &self.data
+    }
+
+    /// Metadata stored alongside the data
+    pub fn meta(&self) -> &Arc<RwLock<BlockchainDbMeta>> {
+        &self.meta
+    }
+
+    /// Returns `true` if this is a transient cache and nothing will be written
+    pub fn is_transient(&self) -> bool {
+        self.file.lock().is_none()
+    }
+
+    /// Stops writing to the cache file, for example because the fork moved to another block.
+    pub fn disable(&self) {
+        *self.file.lock() = None;
+    }
+
+    /// Queues a fetched account to be appended to the cache file.
+    pub fn append_account(&self, address: Address, info: &AccountInfo) {
+        self.append(Record::Account(address, info.clone()));
+    }
+
+    /// Queues a fetched storage slot to be appended to the cache file.
+    pub fn append_storage(&self, address: Address, slot: U256, value: U256) {
+        self.append(Record::Storage(address, slot, value));
+    }
+
+    /// Queues a fetched block hash to be appended to the cache file.
+    pub fn append_block_hash(&self, number: U256, hash: B256) {
+        self.append(Record::BlockHash(number, hash));
+    }
+
+    /// Imports the entries other processes appended to the cache file since it was last read.
+    ///
+    /// Entries that are already in the [MemDb] are kept. Returns `true` if there were new
+    /// entries.
+    pub fn refresh(&self) -> bool {
+        let mut file = self.file.lock();
+        let Some(mapped) = file.as_mut().and_then(|file| file.mapped.as_mut()) else {
+            return false
+        };
+        let decoded = match mapped.read_new_records() {
+            Ok(Some(decoded)) => decoded,
+            Ok(None) => return false,
+            Err(err) => {
+                warn!(target: "cache", %err, "Failed to read fork cache");
+                return false
+            }
+        };
+        trace!(target: "cache", len = decoded.len, "importing new fork cache entries");
+
+        let StateSnapshot { accounts, storage, block_hashes } = decoded.state;
+        let mut db_accounts = self.data.accounts.write();
+        for (address, info) in accounts {
+            db_accounts.entry(address).or_insert(info);
+        }
+        let mut db_storage = self.data.storage.write();
+        for (address, slots) in storage {
+            let db_slots = db_storage.entry(address).or_default();
+            for (slot, value) in slots {
+                db_slots.entry(slot).or_insert(value);
+            }
+        }
+        let mut db_block_hashes = self.data.block_hashes.write();
+        for (number, hash) in block_hashes {
+            db_block_hashes.entry(number).or_insert(hash);
+        }
+        true
+    }
+
+    /// Writes the entries of the [MemDb] that aren't in the cache file yet, if caching is enabled,
+    /// and waits until all queued entries were written.
+    ///
+    /// Fetched values are queued as they are received, so this only writes the entries that
+    /// were inserted into the [MemDb] directly, and the metadata if hosts were added.
+    #[instrument(level = "warn", skip_all)]
+    pub fn flush(&self) {
+        if self.writer.is_none() {
+            return
+        }
+        // the fetched values are written first, so they're not written twice
+        self.sync();
+
+        let mut guard = self.file.lock();
+        let Some(file) = guard.as_mut() else { return };
+        trace!(target: "cache", path = ?file.path, "flushing fork cache");
+
+        let on_disk = match &mut file.mapped {
+            Some(mapped) => {
+                if let Err(err) = mapped.remap() {
+                    return warn!(target: "cache", %err, "Failed to read fork cache")
+                }
+                decode_records(&mapped.map)
+            }
+            None => Decoded::default(),
+        };
+
+        let mut records = Vec::new();
+        let meta = self.meta.read();
+        if on_disk.meta.as_ref().map_or(true, |on_disk| on_disk.hosts != meta.hosts) {
+            records.push(Record::Meta(meta.clone()));
+        }
+        drop(meta);
+        for (address, info) in self.data.accounts.read().iter() {
+            let unchanged = on_disk.state.accounts.get(address).is_some_and(|on_disk| {
+                on_disk.balance == info.balance &&
+                    on_disk.nonce == info.nonce &&
+                    on_disk.code_hash == info.code_hash
+            });
+            if !unchanged {
+                records.push(Record::Account(*address, info.clone()));
+            }
+        }
+        for (address, slots) in self.data.storage.read().iter() {
+            let on_disk = on_disk.state.storage.get(address);
+            for (slot, value) in slots {
+                if on_disk.and_then(|slots| slots.get(slot)) != Some(value) {
+                    records.push(Record::Storage(*address, *slot, *value));
+                }
+            }
+        }
+        for (number, hash) in self.data.block_hashes.read().iter() {
+            if on_disk.state.block_hashes.get(number) != Some(hash) {
+                records.push(Record::BlockHash(*number, *hash));
+            }
+        }
+
+        drop(guard);
+
+        for record in records {
+            self.append(record);
+        }
+        self.sync();
+        trace!(target: "cache", "flushed fork cache");
+    }
+
+    /// Sends the record to the writer thread.
+    fn append(&self, record: Record) {
+        if let Some(writer) = &self.writer {
+            let _ = writer.send(WriterMsg::Append(record));
+        }
+    }
+
+    /// Waits until the writer thread wrote all records that were sent to it.
+    fn sync(&self) {
+        let Some(writer) = &self.writer else { return };
+        let (tx, rx) = mpsc::channel();
+        if writer.send(WriterMsg::Sync(tx)).is_ok() {
+            let _ = rx.recv();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn meta(number: u64) -> Arc<RwLock<BlockchainDbMeta>> {
+        let mut meta = BlockchainDbMeta {
+            cfg_env: Default::default(),
+            block_env: Default::default(),
+            hosts: BTreeSet::from(["localhost".to_string()]),
+        };
+        meta.block_env.number = U256::from(number);
+        Arc::new(RwLock::new(meta))
+    }
+
+    fn account(nonce: u64) -> AccountInfo {
+        let code = Bytecode::new_raw(vec![0x60, 0x00].into()).to_checked();
+        AccountInfo {
+            balance: U256::from(10),
+            nonce,
+            code_hash: code.hash_slow(),
+            code: Some(code),
+        }
+    }
+
+    #[test]
+    fn can_encode_records() {
+        let records = [
+            Record::Meta(meta(1).read().clone()),
+            Record::Account(Address::with_last_byte(1), account(3)),
+            Record::Storage(Address::with_last_byte(1), U256::from(2), U256::from(3)),
+            Record::BlockHash(U256::from(4), B256::with_last_byte(5)),
+        ];
+        let mut buf = Vec::new();
+        for record in &records {
+            record.encode(&mut buf);
+        }
+        let len = buf.len();
+        // a truncated record is ignored
+        buf.extend_from_slice(&[TAG_STORAGE, 84, 0, 0, 0, 1, 2]);
+
+        let decoded = decode_records(&buf);
+        assert_eq!(decoded.len, len);
+        assert_eq!(decoded.meta.as_ref(), Some(&*meta(1).read()));
+        let info = &decoded.state.accounts[&Address::with_last_byte(1)];
+        assert_eq!(info.nonce, 3);
+        assert_eq!(
+            info.code.as_ref().unwrap().original_bytes(),
+            account(3).code.unwrap().original_bytes()
+        );
+        assert_eq!(
+            decoded.state.storage[&Address::with_last_byte(1)][&U256::from(2)],
+            U256::from(3)
+        );
+        assert_eq!(decoded.state.block_hashes[&U256::from(4)], B256::with_last_byte(5));
+    }
+
+    #[test]
+    fn shares_entries_between_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("1").join("storage.bin");
+
+        let writer = MmapBlockCacheDB::new(meta(1), Some(path.clone()));
+        let address = Address::with_last_byte(1);
+        writer.append_account(address, &account(1));
+        writer.flush();
+
+        let reader = MmapBlockCacheDB::load(&path).unwrap();
+        assert_eq!(reader.db().accounts.read()[&address].nonce, 1);
+        assert!(!reader.refresh());
+
+        // values appended after the reader was loaded are imported on refresh
+        writer.append_storage(address, U256::from(1), U256::from(2));
+        writer.append_block_hash(U256::from(1), B256::with_last_byte(1));
+        writer.flush();
+        assert!(reader.refresh());
+        assert_eq!(reader.db().storage.read()[&address][&U256::from(1)], U256::from(2));
+        assert_eq!(reader.db().block_hashes.read().len(), 1);
+
+        // only entries inserted directly are written on flush
+        reader.db().do_insert_account(Address::with_last_byte(2), account(2));
+        reader.flush();
+        let len = fs::metadata(&path).unwrap().len();
+        reader.flush();
+        assert_eq!(fs::metadata(&path).unwrap().len(), len);
+        assert!(writer.refresh());
+        assert_eq!(writer.db().accounts.read().len(), 2);
+    }
+
+    #[test]
+    fn replaces_cache_of_other_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("storage.bin");
+
+        let old = MmapBlockCacheDB::new(meta(1), Some(path.clone()));
+        old.append_account(Address::with_last_byte(1), &account(1));
+        old.flush();
+
+        let new = MmapBlockCacheDB::new(meta(2), Some(path.clone()));
+        new.append_account(Address::with_last_byte(2), &account(1));
+        new.flush();
+
+        let loaded = MmapBlockCacheDB::load(&path).unwrap();
+        assert_eq!(*loaded.meta().read(), *meta(2).read());
+        assert_eq!(loaded.db().accounts.read().len(), 1);
+        assert!(loaded.db().accounts.read().contains_key(&Address::with_last_byte(2)));
+    }
+
+    #[test]
+    fn concurrent_instances_share_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("storage.bin");
+
+        // instances that create the file at the same time append to the same file
+        let caches: Vec<_> = (0..8u8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let cache = MmapBlockCacheDB::new(meta(1), Some(path));
+                    cache.append_account(Address::with_last_byte(i), &account(1));
+                    cache.flush();
+                    cache
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        let loaded = MmapBlockCacheDB::load(&path).unwrap();
+        assert_eq!(loaded.db().accounts.read().len(), caches.len());
+        // the temporary files were removed
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn migrates_json_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("storage.bin");
+        fs::copy(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/storage.json"),
+            dir.path().join("storage.json"),
+        )
+        .unwrap();
+
+        let cache = MmapBlockCacheDB::load(&path).unwrap();
+        let accounts = cache.db().accounts.read().len();
+        assert!(accounts > 0);
+        cache.flush();
+
+        let loaded = MmapBlockCacheDB::load(&path).unwrap();
+        assert_eq!(loaded.db().accounts.read().len(), accounts);
+    }
+}
@@ -10,6 +10,9 @@ pub use init::environment;
 mod cache;
 pub use cache::{BlockchainDb, BlockchainDbMeta, JsonBlockCacheDB, MemDb};
 
+mod mmap;
+pub use mmap::MmapBlockCacheDB;
+
 pub mod database;
 
 mod multi;
@@ -468,7 +468,7 @@ async fn create_fork(mut fork: CreateFork) -> eyre::Result<(CreatedFork, Handler
 
     // determine the cache path if caching is enabled
     let cache_path = if fork.enable_caching {
-        Config::foundry_block_cache_dir(meta.cfg_env.chain_id, number)
+        Config::foundry_block_cache_file(meta.cfg_env.chain_id, number)
     } else {
         None
     };
@@ -15,4 +15,5 @@ pub mod decode;
 pub mod fork;
 pub mod opts;
 pub mod snapshot;
+pub mod usage;
 pub mod utils;
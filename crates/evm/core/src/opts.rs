@@ -49,6 +49,10 @@ pub struct EvmOpts {
     /// Enables the FFI cheatcode.
     pub ffi: bool,
 
+    /// Whether to run top-level calls as separate transactions.
+    #[serde(default)]
+    pub isolate: bool,
+
     /// Verbosity mode of EVM output as number of occurrences.
     pub verbosity: u8,
 
@@ -136,10 +140,10 @@ impl EvmOpts {
     ///   - storage is allowed (`no_storage_caching = false`)
     ///
     /// If all these criteria are met, then storage caching is enabled and storage info will be
-    /// written to [Config::foundry_cache_dir()]/<str(chainid)>/<block>/storage.json
+    /// written to [Config::foundry_cache_dir()]/<str(chainid)>/<block>/storage.bin
     ///
     /// for `mainnet` and `--fork-block-number 14435000` on mac the corresponding storage cache will
-    /// be at `~/.foundry/cache/mainnet/14435000/storage.json`
+    /// be at `~/.foundry/cache/mainnet/14435000/storage.bin`
     pub fn get_fork(&self, config: &Config, env: revm::primitives::Env) -> Option<CreateFork> {
         let url = self.fork_url.clone()?;
         let enable_caching = config.enable_caching(&url, env.cfg.chain_id);
@@ -0,0 +1,20 @@
+//! Peak resource usage of executions.
+
+use serde::{Deserialize, Serialize};
+
+/// The peak memory and call depth of an execution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionUsage {
+    /// The largest memory of a single call frame in bytes.
+    pub max_memory: u64,
+    /// The deepest call depth, the top-level call has a depth of 0.
+    pub max_depth: u64,
+}
+
+impl ExecutionUsage {
+    /// Merges the peak usage of another execution into this one.
+    pub fn merge(&mut self, other: ExecutionUsage) {
+        self.max_memory = self.max_memory.max(other.max_memory);
+        self.max_depth = self.max_depth.max(other.max_depth);
+    }
+}
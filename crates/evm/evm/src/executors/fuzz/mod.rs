@@ -1,20 +1,21 @@
 use crate::executors::{Executor, RawCallResult};
-use alloy_dyn_abi::JsonAbiExt;
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
 use alloy_json_abi::{Function, JsonAbi as Abi};
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{Address, Bytes, I256, U256};
 use eyre::Result;
 use foundry_config::FuzzConfig;
 use foundry_evm_core::{
     constants::MAGIC_ASSUME,
     decode::{self, decode_console_logs},
+    usage::ExecutionUsage,
 };
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::{
     strategies::{
-        build_initial_state, collect_state_from_call, fuzz_calldata, fuzz_calldata_from_state,
-        EvmFuzzState,
+        build_initial_state, collect_state_from_call, fuzz_calldata, fuzz_calldata_from_corpus,
+        fuzz_calldata_from_state, EvmFuzzState,
     },
-    BaseCounterExample, CounterExample, FuzzCase, FuzzError, FuzzTestResult,
+    BaseCounterExample, CounterExample, FuzzCase, FuzzError, FuzzTestResult, SharedCorpus,
 };
 use foundry_evm_traces::CallTraceArena;
 use proptest::test_runner::{TestCaseError, TestError, TestRunner};
@@ -37,17 +38,30 @@ pub struct FuzzedExecutor {
     sender: Address,
     /// The fuzz configuration
     config: FuzzConfig,
+    /// The inputs that reached new coverage, if the fuzzing is coverage-guided
+    corpus: SharedCorpus,
 }
 
 impl FuzzedExecutor {
     /// Instantiates a fuzzed executor given a testrunner
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         sender: Address,
         config: FuzzConfig,
     ) -> Self {
-        Self { executor, runner, sender, config }
+        if config.coverage_guided {
+            executor.inspector.collect_edge_coverage(true);
+        }
+        Self { executor, runner, sender, config, corpus: Default::default() }
+    }
+
+    /// Sets the corpus of coverage-guided fuzzing, e.g. to share it between the tests of a
+    /// contract.
+    #[must_use]
+    pub fn with_corpus(mut self, corpus: SharedCorpus) -> Self {
+        self.corpus = corpus;
+        self
     }
 
     /// Fuzzes the provided function, assuming it is available at the contract at `address`
@@ -77,6 +91,14 @@ impl FuzzedExecutor {
         // Stores coverage information for all fuzz cases
         let coverage: RefCell<Option<HitMaps>> = RefCell::default();
 
+        // Stores the peak memory and call depth over all fuzz cases
+        let usage: RefCell<Option<ExecutionUsage>> = RefCell::default();
+        let record_usage = |case_usage: Option<ExecutionUsage>| {
+            if let Some(case_usage) = case_usage {
+                usage.borrow_mut().get_or_insert_with(Default::default).merge(case_usage);
+            }
+        };
+
         let state = self.build_fuzz_state();
 
         let mut weights = vec![];
@@ -90,6 +112,10 @@ impl FuzzedExecutor {
                 fuzz_calldata_from_state(func.clone(), state.clone()),
             ));
         }
+        if self.config.coverage_guided {
+            // half of the inputs are mutations of the inputs that reached new coverage
+            weights.push((100, fuzz_calldata_from_corpus(func.clone(), self.corpus.clone())));
+        }
 
         let strat = proptest::strategy::Union::new_weighted(weights);
         debug!(func=?func.name, should_fail, "fuzzing");
@@ -98,6 +124,7 @@ impl FuzzedExecutor {
 
             match fuzz_res {
                 FuzzOutcome::Case(case) => {
+                    record_usage(case.usage);
                     let mut first_case = first_case.borrow_mut();
                     gas_by_case.borrow_mut().push((case.case.gas, case.case.stipend));
                     if first_case.is_none() {
@@ -121,6 +148,7 @@ impl FuzzedExecutor {
                     counterexample: _counterexample,
                     ..
                 }) => {
+                    record_usage(_counterexample.1.usage);
                     let status = exit_reason;
                     // We cannot use the calldata returned by the test runner in `TestError::Fail`,
                     // since that input represents the last run case, which may not correspond with
@@ -136,7 +164,13 @@ impl FuzzedExecutor {
             }
         });
 
-        let (calldata, call) = counterexample.into_inner();
+        let (mut calldata, mut call) = counterexample.into_inner();
+        let mut shrunk_reason = None;
+        if let Err(TestError::Fail(..)) = run_result {
+            (calldata, call) = self.shrink(func, address, should_fail, calldata, call);
+            shrunk_reason =
+                decode::maybe_decode_revert(&call.result, errors, Some(call.exit_reason));
+        }
         let mut result = FuzzTestResult {
             first_case: first_case.take().unwrap_or_default(),
             gas_by_case: gas_by_case.take(),
@@ -148,6 +182,7 @@ impl FuzzedExecutor {
             labeled_addresses: call.labels,
             traces: if run_result.is_ok() { traces.into_inner() } else { call.traces.clone() },
             coverage: coverage.into_inner(),
+            usage: usage.into_inner(),
         };
 
         match run_result {
@@ -162,9 +197,8 @@ impl FuzzedExecutor {
             Err(TestError::Abort(reason)) => {
                 result.reason = Some(reason.to_string());
             }
-            Err(TestError::Fail(reason, _)) => {
-                let reason = reason.to_string();
-                result.reason = if reason.is_empty() { None } else { Some(reason) };
+            Err(TestError::Fail(..)) => {
+                result.reason = shrunk_reason.filter(|reason| !reason.is_empty());
 
                 let args = if let Some(data) = calldata.get(4..) {
                     func.abi_decode_input(data, false).unwrap_or_default()
@@ -179,6 +213,7 @@ impl FuzzedExecutor {
                     traces: call.traces,
                     calldata,
                     args,
+                    labels: Default::default(),
                 }));
             }
             _ => {}
@@ -218,6 +253,12 @@ impl FuzzedExecutor {
             return Err(TestCaseError::reject(FuzzError::AssumeReject))
         }
 
+        if let Some(edge_coverage) = &call.edge_coverage {
+            if self.corpus.write().add_if_interesting(&calldata, edge_coverage) {
+                trace!(input=?calldata, "new coverage");
+            }
+        }
+
         let breakpoints = call
             .cheatcodes
             .as_ref()
@@ -233,6 +274,7 @@ impl FuzzedExecutor {
                 coverage: call.coverage,
                 debug: call.debug,
                 breakpoints,
+                usage: call.usage,
             }))
         } else {
             Ok(FuzzOutcome::CounterExample(CounterExampleOutcome {
@@ -244,6 +286,42 @@ impl FuzzedExecutor {
         }
     }
 
+    /// Simplifies the arguments of a failing call for as long as the call keeps failing.
+    ///
+    /// Every argument is repeatedly replaced by a simpler value, e.g. zero, half of a number or a
+    /// shorter array, and the replacement is kept if the call still fails. Inputs that are
+    /// rejected by `vm.assume` are never kept, so the shrunk counterexample satisfies the same
+    /// assumptions as the original one.
+    fn shrink(
+        &self,
+        func: &Function,
+        address: Address,
+        should_fail: bool,
+        calldata: Bytes,
+        call: RawCallResult,
+    ) -> (Bytes, RawCallResult) {
+        let Some(args) = calldata.get(4..).and_then(|data| func.abi_decode_input(data, false).ok())
+        else {
+            return (calldata, call)
+        };
+        let shrunk = shrink_args(args, |args| {
+            let data = Bytes::from(func.abi_encode_input(args).ok()?);
+            let call =
+                self.executor.call_raw(self.sender, address, data.clone(), U256::ZERO).ok()?;
+            // inputs rejected by `vm.assume` are never kept
+            if call.result.as_ref() == MAGIC_ASSUME {
+                return None
+            }
+            let state_changeset = call.state_changeset.clone()?;
+            if self.executor.is_raw_call_success(address, state_changeset, &call, should_fail) {
+                return None
+            }
+            trace!(?data, "shrunk counterexample");
+            Some((data, call))
+        });
+        shrunk.unwrap_or((calldata, call))
+    }
+
     /// Stores fuzz state for use with [fuzz_calldata_from_state]
     pub fn build_fuzz_state(&self) -> EvmFuzzState {
         if let Some(fork_db) = self.executor.backend.active_fork_db() {
@@ -253,3 +331,189 @@ impl FuzzedExecutor {
         }
     }
 }
+
+/// The maximum number of calls made to shrink a counterexample.
+const MAX_SHRINK_ATTEMPTS: usize = 256;
+
+/// Shrinks the arguments of a failing call for as long as `fails` returns `Some` for them.
+///
+/// Every argument is repeatedly replaced by its simpler variants, see [shrink_value], and the first
+/// variant for which `fails` returns `Some` is kept. Returns the last kept arguments with the
+/// value returned by `fails`, or `None` if no argument could be shrunk.
+fn shrink_args<T>(
+    mut args: Vec<DynSolValue>,
+    mut fails: impl FnMut(&[DynSolValue]) -> Option<T>,
+) -> Option<(Vec<DynSolValue>, T)> {
+    let mut best = None;
+    let mut attempts = 0;
+    'shrink: while attempts < MAX_SHRINK_ATTEMPTS {
+        for index in 0..args.len() {
+            for candidate in shrink_value(&args[index]) {
+                if attempts == MAX_SHRINK_ATTEMPTS {
+                    break 'shrink
+                }
+                attempts += 1;
+
+                let mut shrunk = args.clone();
+                shrunk[index] = candidate;
+                if let Some(value) = fails(&shrunk) {
+                    args = shrunk;
+                    best = Some(value);
+                    continue 'shrink
+                }
+            }
+        }
+        break
+    }
+    best.map(|value| (args, value))
+}
+
+/// Returns simpler variants of the value, from the simplest, e.g. zero, to the closest one.
+fn shrink_value(value: &DynSolValue) -> Vec<DynSolValue> {
+    match value {
+        DynSolValue::Bool(true) => vec![DynSolValue::Bool(false)],
+        DynSolValue::Uint(value, size) if !value.is_zero() => {
+            let mut candidates = vec![DynSolValue::Uint(U256::ZERO, *size)];
+            if *value > U256::from(2) {
+                candidates.push(DynSolValue::Uint(*value >> 1, *size));
+            }
+            if *value > U256::from(1) {
+                candidates.push(DynSolValue::Uint(*value - U256::from(1), *size));
+            }
+            candidates
+        }
+        DynSolValue::Int(value, size) if !value.is_zero() => {
+            let mut candidates = vec![DynSolValue::Int(I256::ZERO, *size)];
+            let half = value.asr(1);
+            if !half.is_zero() && half != *value {
+                candidates.push(DynSolValue::Int(half, *size));
+            }
+            if value.is_negative() && *value != I256::MIN {
+                candidates.push(DynSolValue::Int(-*value, *size));
+            }
+            candidates
+        }
+        DynSolValue::Address(address) if !address.is_zero() => {
+            vec![DynSolValue::Address(Address::ZERO)]
+        }
+        DynSolValue::FixedBytes(word, size) if !word.is_zero() => {
+            vec![DynSolValue::FixedBytes(Default::default(), *size)]
+        }
+        DynSolValue::Bytes(bytes) if !bytes.is_empty() => {
+            let mut candidates = vec![DynSolValue::Bytes(vec![])];
+            if bytes.len() > 1 {
+                candidates.push(DynSolValue::Bytes(bytes[..bytes.len() / 2].to_vec()));
+            }
+            candidates
+        }
+        DynSolValue::String(string) if !string.is_empty() => {
+            let mut candidates = vec![DynSolValue::String(String::new())];
+            let half = string.char_indices().nth(string.chars().count() / 2).map(|(i, _)| i);
+            if let Some(half) = half.filter(|half| *half > 0) {
+                candidates.push(DynSolValue::String(string[..half].to_string()));
+            }
+            candidates
+        }
+        DynSolValue::Array(values) => {
+            let mut candidates = Vec::new();
+            if !values.is_empty() {
+                candidates.push(DynSolValue::Array(vec![]));
+                candidates.push(DynSolValue::Array(values[..values.len() - 1].to_vec()));
+            }
+            candidates.extend(shrink_elements(values).map(DynSolValue::Array));
+            candidates
+        }
+        DynSolValue::FixedArray(values) => {
+            shrink_elements(values).map(DynSolValue::FixedArray).collect()
+        }
+        DynSolValue::Tuple(values) => shrink_elements(values).map(DynSolValue::Tuple).collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns the variants of the values with one of them shrunk.
+fn shrink_elements(values: &[DynSolValue]) -> impl Iterator<Item = Vec<DynSolValue>> + '_ {
+    values.iter().enumerate().flat_map(move |(index, value)| {
+        shrink_value(value).into_iter().map(move |candidate| {
+            let mut values = values.to_vec();
+            values[index] = candidate;
+            values
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uint(value: u64) -> DynSolValue {
+        DynSolValue::Uint(U256::from(value), 256)
+    }
+
+    fn shrink(args: Vec<DynSolValue>, fails: impl Fn(&[DynSolValue]) -> bool) -> Vec<DynSolValue> {
+        shrink_args(args, |args| fails(args).then_some(())).map(|(args, _)| args).unwrap()
+    }
+
+    #[test]
+    fn shrinks_values() {
+        assert_eq!(shrink_value(&uint(0)), vec![]);
+        assert_eq!(shrink_value(&uint(10)), vec![uint(0), uint(5), uint(9)]);
+        assert_eq!(shrink_value(&DynSolValue::Bool(true)), vec![DynSolValue::Bool(false)]);
+        assert_eq!(
+            shrink_value(&DynSolValue::Int(I256::try_from(-6i64).unwrap(), 256)),
+            vec![
+                DynSolValue::Int(I256::ZERO, 256),
+                DynSolValue::Int(I256::try_from(-3i64).unwrap(), 256),
+                DynSolValue::Int(I256::try_from(6i64).unwrap(), 256),
+            ]
+        );
+
+        let fails = |args: &[DynSolValue]| args[0].as_uint().unwrap().0 >= U256::from(37);
+        assert_eq!(shrink(vec![uint(1000)], fails), vec![uint(37)]);
+    }
+
+    #[test]
+    fn shrinks_arrays() {
+        let array = DynSolValue::Array(vec![uint(1), uint(9), uint(3)]);
+        let fails = |args: &[DynSolValue]| {
+            args[0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|value| value.as_uint().unwrap().0 >= U256::from(5))
+        };
+        assert_eq!(shrink(vec![array], fails), vec![DynSolValue::Array(vec![uint(0), uint(5)])]);
+
+        let fixed = DynSolValue::FixedArray(vec![uint(8), uint(2)]);
+        let fails = |args: &[DynSolValue]| {
+            args[0].as_fixed_array().unwrap()[0].as_uint().unwrap().0 > U256::from(3)
+        };
+        assert_eq!(
+            shrink(vec![fixed], fails),
+            vec![DynSolValue::FixedArray(vec![uint(4), uint(0)])]
+        );
+    }
+
+    #[test]
+    fn does_not_shrink_to_assumed_away_inputs() {
+        // every input fails, but the test assumes that `x >= 10`
+        let fails = |args: &[DynSolValue]| args[0].as_uint().unwrap().0 >= U256::from(10);
+        assert_eq!(shrink(vec![uint(1000)], fails), vec![uint(10)]);
+
+        // rejected inputs are skipped, and simpler inputs are still tried afterwards
+        let mut tried = Vec::new();
+        let shrunk = shrink_args(vec![uint(4)], |args| {
+            let value = args[0].as_uint().unwrap().0;
+            tried.push(value);
+            (value != U256::ZERO && value != U256::from(2)).then_some(())
+        });
+        assert_eq!(shrunk.map(|(args, _)| args), Some(vec![uint(1)]));
+        assert_eq!(tried, [0, 2, 3, 0, 1, 0].map(U256::from));
+    }
+
+    #[test]
+    fn returns_none_if_nothing_shrinks() {
+        assert!(shrink_args(vec![uint(7)], |_| None::<()>).is_none());
+        assert!(shrink_args(vec![uint(0)], |_| Some(())).is_none());
+    }
+}
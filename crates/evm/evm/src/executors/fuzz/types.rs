@@ -1,7 +1,7 @@
 use crate::executors::RawCallResult;
 use alloy_primitives::Bytes;
 use foundry_common::evm::Breakpoints;
-use foundry_evm_core::debug::DebugArena;
+use foundry_evm_core::{debug::DebugArena, usage::ExecutionUsage};
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::FuzzCase;
 use foundry_evm_traces::CallTraceArena;
@@ -20,6 +20,8 @@ pub struct CaseOutcome {
     pub debug: Option<DebugArena>,
     /// Breakpoints char pc map
     pub breakpoints: Breakpoints,
+    /// The peak memory and call depth of the call
+    pub usage: Option<ExecutionUsage>,
 }
 
 /// Returned by a single fuzz when a counterexample has been discovered
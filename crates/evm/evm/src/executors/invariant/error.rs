@@ -8,7 +8,7 @@ use ethers_core::{
 };
 use eyre::Result;
 use foundry_common::contracts::{ContractsByAddress, ContractsByArtifact};
-use foundry_evm_core::{constants::CALLER, decode::decode_revert};
+use foundry_evm_core::{constants::CALLER, decode::decode_revert, usage::ExecutionUsage};
 use foundry_evm_fuzz::{BaseCounterExample, CounterExample, FuzzedCases, Reason};
 use foundry_evm_traces::{load_contracts, CallTraceArena, TraceKind, Traces};
 use itertools::Itertools;
@@ -53,6 +53,8 @@ pub struct InvariantFuzzTestResult {
     /// The entire inputs of the last run of the invariant campaign, used for
     /// replaying the run for collecting traces.
     pub last_run_inputs: Vec<BasicTxDetails>,
+    /// The peak memory and call depth over all fuzzed calls, if tracked
+    pub usage: Option<ExecutionUsage>,
 }
 
 #[derive(Debug, Clone)]
@@ -10,6 +10,7 @@ use foundry_common::contracts::{ContractsByAddress, ContractsByArtifact};
 use foundry_config::{FuzzDictionaryConfig, InvariantConfig};
 use foundry_evm_core::{
     constants::{CALLER, CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    usage::ExecutionUsage,
     utils::{get_function, StateChangeset},
 };
 use foundry_evm_fuzz::{
@@ -115,6 +116,9 @@ impl<'a> InvariantExecutor<'a> {
         // Stores the calldata in the last run.
         let last_run_calldata: RefCell<Vec<BasicTxDetails>> = RefCell::new(vec![]);
 
+        // Stores the peak memory and call depth over all fuzzed calls.
+        let usage: RefCell<Option<ExecutionUsage>> = RefCell::default();
+
         // Let's make sure the invariant is sound before actually starting the run:
         // We'll assert the invariant in its initial state, and if it fails, we'll
         // already know if we can early exit the invariant run.
@@ -160,6 +164,9 @@ impl<'a> InvariantExecutor<'a> {
                 let call_result = executor
                     .call_raw(*sender, *address, calldata.clone(), U256::ZERO)
                     .expect("could not make raw evm call");
+                if let Some(call_usage) = call_result.usage {
+                    usage.borrow_mut().get_or_insert_with(Default::default).merge(call_usage);
+                }
 
                 // Collect data for fuzzing from the state changeset.
                 let mut state_changeset =
@@ -249,6 +256,7 @@ impl<'a> InvariantExecutor<'a> {
             cases: fuzz_cases.into_inner(),
             reverts,
             last_run_inputs: last_run_calldata.take(),
+            usage: usage.into_inner(),
         })
     }
 
@@ -7,7 +7,8 @@
 // the concrete `Executor` type.
 
 use crate::inspectors::{
-    cheatcodes::BroadcastableTransactions, Cheatcodes, InspectorData, InspectorStack,
+    cheatcodes::BroadcastableTransactions, Cheatcodes, ExecutionUsage, InspectorData,
+    InspectorStack,
 };
 use alloy_dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt};
 use alloy_json_abi::{Function, JsonAbi as Abi};
@@ -25,6 +26,7 @@ use foundry_evm_core::{
     utils::{eval_to_instruction_result, halt_to_instruction_result, StateChangeset},
 };
 use foundry_evm_coverage::HitMaps;
+use foundry_evm_fuzz::EdgeCoverage;
 use foundry_evm_traces::CallTraceArena;
 use revm::{
     db::{DatabaseCommit, DatabaseRef},
@@ -143,6 +145,25 @@ impl Executor {
         Ok(self.backend.basic_ref(address)?.map(|acc| acc.nonce).unwrap_or_default())
     }
 
+    /// Set the code of an account.
+    pub fn set_code(&mut self, address: Address, code: Bytecode) -> DatabaseResult<&mut Self> {
+        let mut account = self.backend.basic_ref(address)?.unwrap_or_default();
+        account.code_hash = code.hash_slow();
+        account.code = Some(code);
+
+        self.backend.insert_account_info(address, account);
+        Ok(self)
+    }
+
+    /// Gets the code of an account
+    pub fn get_code(&self, address: Address) -> DatabaseResult<Bytecode> {
+        let Some(account) = self.backend.basic_ref(address)? else { return Ok(Bytecode::new()) };
+        match account.code {
+            Some(code) => Ok(code),
+            None => self.backend.code_by_hash_ref(account.code_hash),
+        }
+    }
+
     #[inline]
     pub fn set_tracing(&mut self, tracing: bool) -> &mut Self {
         self.inspector.tracing(tracing);
@@ -206,6 +227,7 @@ impl Executor {
                         state_changeset: None,
                         transactions: None,
                         script_wallets: res.script_wallets,
+                        xfail: None,
                     })))
                 }
             }
@@ -401,7 +423,8 @@ impl Executor {
                         labels,
                         state_changeset: None,
                         transactions: None,
-                        script_wallets
+                        script_wallets,
+                        xfail: None,
                     })));
                 }
             }
@@ -420,6 +443,7 @@ impl Executor {
                     state_changeset: None,
                     transactions: None,
                     script_wallets,
+                    xfail: None,
                 })))
             }
         };
@@ -597,6 +621,8 @@ pub struct ExecutionErr {
     pub transactions: Option<BroadcastableTransactions>,
     pub state_changeset: Option<StateChangeset>,
     pub script_wallets: Vec<LocalWallet>,
+    /// The reason the test was expected to fail, set by the `xfail` cheatcode
+    pub xfail: Option<String>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -607,9 +633,10 @@ pub enum EvmError {
     /// Error which occurred during ABI encoding/decoding
     #[error(transparent)]
     AbiError(#[from] alloy_dyn_abi::Error),
-    /// Error caused which occurred due to calling the skip() cheatcode.
+    /// Error caused which occurred due to calling the skip() cheatcode, with the reason of the
+    /// skip, if any.
     #[error("Skipped")]
-    SkipError,
+    SkipError(Option<String>),
     /// Any other error.
     #[error(transparent)]
     Eyre(#[from] eyre::Error),
@@ -673,6 +700,10 @@ pub struct CallResult {
     pub env: Env,
     /// breakpoints
     pub breakpoints: Breakpoints,
+    /// The reason the test is expected to fail, set by the `xfail` cheatcode
+    pub xfail: Option<String>,
+    /// The peak memory and call depth of the call
+    pub usage: Option<ExecutionUsage>,
 }
 
 /// The result of a raw call.
@@ -703,6 +734,8 @@ pub struct RawCallResult {
     pub traces: Option<CallTraceArena>,
     /// The coverage info collected during the call
     pub coverage: Option<HitMaps>,
+    /// The edge coverage collected during the call, to guide the fuzzer
+    pub edge_coverage: Option<EdgeCoverage>,
     /// The debug nodes of the call
     pub debug: Option<DebugArena>,
     /// Scripted transactions generated from this call
@@ -722,6 +755,8 @@ pub struct RawCallResult {
     pub out: Option<Output>,
     /// The chisel state
     pub chisel_state: Option<(Stack, Vec<u8>, InstructionResult)>,
+    /// The peak memory and call depth of the call
+    pub usage: Option<ExecutionUsage>,
 }
 
 impl Default for RawCallResult {
@@ -738,6 +773,7 @@ impl Default for RawCallResult {
             labels: BTreeMap::new(),
             traces: None,
             coverage: None,
+            edge_coverage: None,
             debug: None,
             transactions: None,
             state_changeset: None,
@@ -746,6 +782,7 @@ impl Default for RawCallResult {
             cheatcodes: Default::default(),
             out: None,
             chisel_state: None,
+            usage: None,
         }
     }
 }
@@ -788,10 +825,12 @@ fn convert_executed_result(
         labels,
         traces,
         coverage,
+        edge_coverage,
         debug,
         cheatcodes,
         script_wallets,
         chisel_state,
+        usage,
     } = inspector.collect();
 
     let transactions = match cheatcodes.as_ref() {
@@ -813,6 +852,7 @@ fn convert_executed_result(
         labels,
         traces,
         coverage,
+        edge_coverage,
         debug,
         transactions,
         state_changeset: Some(state_changeset),
@@ -821,6 +861,7 @@ fn convert_executed_result(
         cheatcodes,
         out,
         chisel_state,
+        usage,
     })
 }
 
@@ -845,13 +886,14 @@ fn convert_call_result(
         state_changeset,
         script_wallets,
         env,
+        usage,
         ..
     } = call_result;
 
-    let breakpoints = if let Some(c) = call_result.cheatcodes {
-        c.breakpoints
+    let (breakpoints, xfail) = if let Some(c) = call_result.cheatcodes {
+        (c.breakpoints, c.xfail)
     } else {
-        std::collections::HashMap::new()
+        (std::collections::HashMap::new(), None)
     };
 
     match status {
@@ -880,11 +922,14 @@ fn convert_call_result(
                 env,
                 breakpoints,
                 skipped: false,
+                xfail,
+                usage,
             })
         }
         _ => {
-            if &result == crate::constants::MAGIC_SKIP {
-                return Err(EvmError::SkipError)
+            if let Some(reason) = result.strip_prefix(crate::constants::MAGIC_SKIP) {
+                let reason = (!reason.is_empty()).then(|| String::from_utf8_lossy(reason).into());
+                return Err(EvmError::SkipError(reason))
             }
             let reason = decode::decode_revert(&result, abi, Some(status));
             Err(EvmError::Execution(Box::new(ExecutionErr {
@@ -900,6 +945,7 @@ fn convert_call_result(
                 transactions,
                 state_changeset,
                 script_wallets,
+                xfail,
             })))
         }
     }
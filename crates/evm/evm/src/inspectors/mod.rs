@@ -2,7 +2,7 @@
 
 pub use foundry_cheatcodes::{self as cheatcodes, Cheatcodes, CheatsConfig};
 pub use foundry_evm_coverage::CoverageCollector;
-pub use foundry_evm_fuzz::Fuzzer;
+pub use foundry_evm_fuzz::{EdgeCoverageCollector, Fuzzer};
 pub use foundry_evm_traces::Tracer;
 
 mod access_list;
@@ -22,3 +22,6 @@ pub use printer::TracePrinter;
 
 mod stack;
 pub use stack::{InspectorData, InspectorStack, InspectorStackBuilder};
+
+mod usage;
+pub use usage::{ExecutionUsage, UsageTracker};
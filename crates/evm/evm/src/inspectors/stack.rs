@@ -1,19 +1,25 @@
 use super::{
-    Cheatcodes, CheatsConfig, ChiselState, CoverageCollector, Debugger, Fuzzer, LogCollector,
-    TracePrinter, Tracer,
+    Cheatcodes, CheatsConfig, ChiselState, CoverageCollector, Debugger, EdgeCoverageCollector,
+    ExecutionUsage, Fuzzer, LogCollector, TracePrinter, Tracer, UsageTracker,
 };
 use alloy_primitives::{Address, Bytes, B256, U256};
 use ethers_core::types::Log;
 use ethers_signers::LocalWallet;
-use foundry_evm_core::{backend::DatabaseExt, debug::DebugArena};
+use foundry_evm_core::{
+    backend::{DatabaseExt, RevertSnapshotAction},
+    debug::DebugArena,
+    utils::{eval_to_instruction_result, halt_to_instruction_result},
+};
 use foundry_evm_coverage::HitMaps;
+use foundry_evm_fuzz::EdgeCoverage;
 use foundry_evm_traces::CallTraceArena;
 use revm::{
     interpreter::{
-        return_revert, CallInputs, CreateInputs, Gas, InstructionResult, Interpreter, Stack,
+        return_ok, return_revert, CallInputs, CallScheme, CreateInputs, Gas, InstructionResult,
+        Interpreter, Stack,
     },
-    primitives::{BlockEnv, Env},
-    EVMData, Inspector,
+    primitives::{BlockEnv, Env, ExecutionResult, State, TransactTo, KECCAK_EMPTY},
+    DatabaseCommit, EVMData, Inspector,
 };
 use std::{collections::BTreeMap, sync::Arc};
 
@@ -42,10 +48,16 @@ pub struct InspectorStackBuilder {
     pub logs: Option<bool>,
     /// Whether coverage info should be collected.
     pub coverage: Option<bool>,
+    /// Whether the edge coverage should be collected, to guide the fuzzer.
+    pub edge_coverage: Option<bool>,
     /// Whether to print all opcode traces into the console. Useful for debugging the EVM.
     pub print: Option<bool>,
     /// The chisel state inspector.
     pub chisel_state: Option<usize>,
+    /// Whether the peak memory and call depth should be recorded.
+    pub usage: Option<bool>,
+    /// Whether to run top-level calls as separate transactions.
+    pub isolation: Option<bool>,
 }
 
 impl InspectorStackBuilder {
@@ -104,6 +116,13 @@ impl InspectorStackBuilder {
         self
     }
 
+    /// Set whether to collect the edge coverage, to guide the fuzzer.
+    #[inline]
+    pub fn edge_coverage(mut self, yes: bool) -> Self {
+        self.edge_coverage = Some(yes);
+        self
+    }
+
     /// Set whether to enable the debugger.
     #[inline]
     pub fn debug(mut self, yes: bool) -> Self {
@@ -125,6 +144,20 @@ impl InspectorStackBuilder {
         self
     }
 
+    /// Set whether to record the peak memory and call depth.
+    #[inline]
+    pub fn usage(mut self, yes: bool) -> Self {
+        self.usage = Some(yes);
+        self
+    }
+
+    /// Set whether to run top-level calls as separate transactions.
+    #[inline]
+    pub fn isolation(mut self, yes: bool) -> Self {
+        self.isolation = Some(yes);
+        self
+    }
+
     /// Builds the stack of inspectors to use when transacting/committing on the EVM.
     ///
     /// See also [`revm::Evm::inspect_ref`] and [`revm::Evm::commit_ref`].
@@ -138,8 +171,11 @@ impl InspectorStackBuilder {
             debug,
             logs,
             coverage,
+            edge_coverage,
             print,
             chisel_state,
+            usage,
+            isolation,
         } = self;
         let mut stack = InspectorStack::new();
 
@@ -154,10 +190,13 @@ impl InspectorStackBuilder {
             stack.set_chisel(chisel_state);
         }
         stack.collect_coverage(coverage.unwrap_or(false));
+        stack.collect_edge_coverage(edge_coverage.unwrap_or(false));
         stack.collect_logs(logs.unwrap_or(true));
         stack.enable_debugger(debug.unwrap_or(false));
         stack.print(print.unwrap_or(false));
         stack.tracing(trace.unwrap_or(false));
+        stack.track_usage(usage.unwrap_or(false));
+        stack.enable_isolation(isolation.unwrap_or(false));
 
         // environment, must come after all of the inspectors
         if let Some(block) = block {
@@ -189,9 +228,11 @@ pub struct InspectorData {
     pub traces: Option<CallTraceArena>,
     pub debug: Option<DebugArena>,
     pub coverage: Option<HitMaps>,
+    pub edge_coverage: Option<EdgeCoverage>,
     pub cheatcodes: Option<Cheatcodes>,
     pub script_wallets: Vec<LocalWallet>,
     pub chisel_state: Option<(Stack, Vec<u8>, InstructionResult)>,
+    pub usage: Option<ExecutionUsage>,
 }
 
 /// An inspector that calls multiple inspectors in sequence.
@@ -204,10 +245,19 @@ pub struct InspectorStack {
     pub chisel_state: Option<ChiselState>,
     pub coverage: Option<CoverageCollector>,
     pub debugger: Option<Debugger>,
+    pub edge_coverage: Option<EdgeCoverageCollector>,
     pub fuzzer: Option<Fuzzer>,
     pub log_collector: Option<LogCollector>,
     pub printer: Option<TracePrinter>,
     pub tracer: Option<Tracer>,
+    pub usage: Option<UsageTracker>,
+    /// Whether top-level calls are run as separate transactions.
+    pub enable_isolation: bool,
+    /// Whether a top-level call is currently executed as a separate transaction.
+    in_inner_context: bool,
+    /// The snapshot of the database taken before the first separate transaction of the current
+    /// outer transaction was committed to it, used to roll the commits back if it reverts.
+    isolation_snapshot: Option<U256>,
 }
 
 impl InspectorStack {
@@ -268,6 +318,12 @@ impl InspectorStack {
         self.coverage = yes.then(Default::default);
     }
 
+    /// Set whether to enable the edge coverage collector.
+    #[inline]
+    pub fn collect_edge_coverage(&mut self, yes: bool) {
+        self.edge_coverage = yes.then(Default::default);
+    }
+
     /// Set whether to enable the debugger.
     #[inline]
     pub fn enable_debugger(&mut self, yes: bool) {
@@ -292,6 +348,22 @@ impl InspectorStack {
         self.tracer = yes.then(Default::default);
     }
 
+    /// Set whether to enable the usage tracker.
+    #[inline]
+    pub fn track_usage(&mut self, yes: bool) {
+        self.usage = yes.then(Default::default);
+    }
+
+    /// Set whether to run top-level calls as separate transactions.
+    ///
+    /// Every call made by the transaction at depth 1, e.g. every call of a test to a contract, is
+    /// then executed with its own nonce and gas metering and with cold storage, like it would be
+    /// if it was sent as a transaction on chain.
+    #[inline]
+    pub fn enable_isolation(&mut self, yes: bool) {
+        self.enable_isolation = yes;
+    }
+
     /// Collects all the data gathered during inspection into a single struct.
     #[inline]
     pub fn collect(self) -> InspectorData {
@@ -307,6 +379,7 @@ impl InspectorStack {
             traces: self.tracer.map(|tracer| tracer.traces),
             debug: self.debugger.map(|debugger| debugger.arena),
             coverage: self.coverage.map(|coverage| coverage.maps),
+            edge_coverage: self.edge_coverage.map(|collector| collector.coverage),
             script_wallets: self
                 .cheatcodes
                 .as_ref()
@@ -314,6 +387,7 @@ impl InspectorStack {
                 .unwrap_or_default(),
             cheatcodes: self.cheatcodes,
             chisel_state: self.chisel_state.and_then(|state| state.state),
+            usage: self.usage.map(|usage| usage.usage),
         }
     }
 
@@ -351,54 +425,204 @@ impl InspectorStack {
 
         (status, remaining_gas, retdata)
     }
-}
 
-impl<DB: DatabaseExt> Inspector<DB> for InspectorStack {
-    fn initialize_interp(&mut self, interpreter: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
-        let res = interpreter.instruction_result;
-        call_inspectors!(
-            [
-                &mut self.debugger,
-                &mut self.coverage,
-                &mut self.tracer,
-                &mut self.log_collector,
-                &mut self.cheatcodes,
-                &mut self.printer
-            ],
-            |inspector| {
-                inspector.initialize_interp(interpreter, data);
+    /// Runs `f` with the call depth of the outer transaction while a top-level call is executed
+    /// as a separate transaction, so the inspectors see the same depths as without isolation.
+    #[inline]
+    fn with_adjusted_depth<'a, DB: DatabaseExt, O>(
+        &mut self,
+        data: &mut EVMData<'a, DB>,
+        f: impl FnOnce(&mut Self, &mut EVMData<'a, DB>) -> O,
+    ) -> O {
+        let in_inner_context = self.in_inner_context;
+        if in_inner_context {
+            data.journaled_state.depth += 1;
+        }
+        let out = f(self, data);
+        if in_inner_context {
+            data.journaled_state.depth -= 1;
+        }
+        out
+    }
 
-                // Allow inspectors to exit early
-                if interpreter.instruction_result != res {
-                    #[allow(clippy::needless_return)]
-                    return
+    /// Executes the top-level call as a separate transaction and merges its state changes into
+    /// the outer transaction.
+    fn transact_inner<DB: DatabaseExt + DatabaseCommit>(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        call: &CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        let caller = call.context.caller;
+        let caller_nonce = match data.journaled_state.load_account(caller, data.db) {
+            Ok((account, _)) => account.info.nonce,
+            Err(_) => return (InstructionResult::Revert, Gas::new(call.gas_limit), Bytes::new()),
+        };
+
+        // the commits can't be undone by the journal of the outer transaction, so checkpoint the
+        // database before the first one
+        if self.isolation_snapshot.is_none() {
+            self.isolation_snapshot = Some(data.db.snapshot(&data.journaled_state, data.env));
+        }
+
+        // the transaction must see the changes made so far
+        data.db.commit(data.journaled_state.state.clone());
+
+        let mut env = data.env.clone();
+        env.tx.caller = caller;
+        env.tx.transact_to = TransactTo::Call(call.contract);
+        env.tx.data = call.input.clone();
+        env.tx.value = call.transfer.value;
+        env.tx.nonce = None;
+        env.tx.access_list = Vec::new();
+        // the call pays for the intrinsic gas of the transaction on top of its own gas limit
+        env.tx.gas_limit = call.gas_limit.saturating_add(21_000);
+        if !env.cfg.disable_block_gas_limit {
+            env.tx.gas_limit = env.tx.gas_limit.min(env.block.gas_limit.saturating_to());
+        }
+        env.tx.gas_price = U256::ZERO;
+        env.tx.gas_priority_fee = None;
+        env.block.basefee = U256::ZERO;
+
+        self.in_inner_context = true;
+        let res = {
+            let inspector: &mut dyn Inspector<DB> = self;
+            revm::evm_inner::<DB>(&mut env, &mut *data.db, Some(inspector)).transact()
+        };
+        self.in_inner_context = false;
+
+        let mut gas = Gas::new(call.gas_limit);
+        let Ok(mut res) = res else { return (InstructionResult::Revert, gas, Bytes::new()) };
+
+        data.db.commit(res.state.clone());
+        if update_state(&mut data.journaled_state.state, data.db).is_err() ||
+            update_state(&mut res.state, data.db).is_err()
+        {
+            return (InstructionResult::Revert, gas, Bytes::new())
+        }
+
+        // merge the changes of the transaction into the outer transaction
+        for (address, account) in res.state {
+            if let Some(existing) = data.journaled_state.state.get_mut(&address) {
+                existing.status |= account.status;
+                for (slot, value) in account.storage {
+                    existing.storage.entry(slot).or_insert(value);
                 }
+            } else {
+                data.journaled_state.state.insert(address, account);
             }
-        );
+        }
+
+        // transactions only increase the nonce of the sender if it's an EOA
+        if let Some(account) = data.journaled_state.state.get_mut(&caller) {
+            if account.info.code_hash != KECCAK_EMPTY {
+                account.info.nonce = caller_nonce;
+            }
+        }
+
+        let (status, gas_used, output) = match res.result {
+            ExecutionResult::Success { reason, gas_used, output, .. } => {
+                (eval_to_instruction_result(reason), gas_used, output.into_data())
+            }
+            ExecutionResult::Revert { gas_used, output } => {
+                (InstructionResult::Revert, gas_used, output)
+            }
+            ExecutionResult::Halt { reason, gas_used } => {
+                (halt_to_instruction_result(reason), gas_used, Bytes::new())
+            }
+        };
+        // the intrinsic gas can exceed the gas limit of the call
+        if !gas.record_cost(gas_used) {
+            let _ = gas.record_cost(gas.remaining());
+        }
+
+        (status, gas, output)
     }
 
-    fn step(&mut self, interpreter: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
-        let res = interpreter.instruction_result;
-        call_inspectors!(
-            [
-                &mut self.fuzzer,
-                &mut self.debugger,
-                &mut self.tracer,
-                &mut self.coverage,
-                &mut self.log_collector,
-                &mut self.cheatcodes,
-                &mut self.printer
-            ],
-            |inspector| {
-                inspector.step(interpreter, data);
+    /// Rolls back the separate transactions committed to the database if the outer transaction
+    /// failed, once its top-level call or create ends.
+    fn end_isolation<DB: DatabaseExt>(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        status: InstructionResult,
+    ) {
+        if self.in_inner_context || data.journaled_state.depth != 0 {
+            return
+        }
+        let Some(id) = self.isolation_snapshot.take() else { return };
+
+        if matches!(status, return_ok!()) {
+            data.db.delete_snapshot(id);
+        } else if let Some(journaled_state) =
+            data.db.revert(id, &data.journaled_state, data.env, RevertSnapshotAction::RevertRemove)
+        {
+            data.journaled_state = journaled_state;
+        }
+    }
+}
 
-                // Allow inspectors to exit early
-                if interpreter.instruction_result != res {
-                    #[allow(clippy::needless_return)]
-                    return
+/// Reloads the accounts and storage slots of `state` from the database, after a separate
+/// transaction was committed to it.
+fn update_state<DB: DatabaseExt>(state: &mut State, db: &mut DB) -> Result<(), DB::Error> {
+    for (address, account) in state.iter_mut() {
+        account.info = db.basic(*address)?.unwrap_or_default();
+        for (slot, value) in account.storage.iter_mut() {
+            value.present_value = db.storage(*address, *slot)?;
+        }
+    }
+    Ok(())
+}
+
+impl<DB: DatabaseExt + DatabaseCommit> Inspector<DB> for InspectorStack {
+    fn initialize_interp(&mut self, interpreter: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        self.with_adjusted_depth(data, |stack, data| {
+            let res = interpreter.instruction_result;
+            call_inspectors!(
+                [
+                    &mut stack.debugger,
+                    &mut stack.coverage,
+                    &mut stack.tracer,
+                    &mut stack.log_collector,
+                    &mut stack.cheatcodes,
+                    &mut stack.printer
+                ],
+                |inspector| {
+                    inspector.initialize_interp(interpreter, data);
+
+                    // Allow inspectors to exit early
+                    if interpreter.instruction_result != res {
+                        #[allow(clippy::needless_return)]
+                        return
+                    }
                 }
-            }
-        );
+            );
+        })
+    }
+
+    fn step(&mut self, interpreter: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        self.with_adjusted_depth(data, |stack, data| {
+            let res = interpreter.instruction_result;
+            call_inspectors!(
+                [
+                    &mut stack.fuzzer,
+                    &mut stack.debugger,
+                    &mut stack.tracer,
+                    &mut stack.coverage,
+                    &mut stack.edge_coverage,
+                    &mut stack.log_collector,
+                    &mut stack.cheatcodes,
+                    &mut stack.printer
+                ],
+                |inspector| {
+                    inspector.step(interpreter, data);
+
+                    // Allow inspectors to exit early
+                    if interpreter.instruction_result != res {
+                        #[allow(clippy::needless_return)]
+                        return
+                    }
+                }
+            );
+        })
     }
 
     fn log(
@@ -408,35 +632,45 @@ impl<DB: DatabaseExt> Inspector<DB> for InspectorStack {
         topics: &[B256],
         data: &Bytes,
     ) {
-        call_inspectors!(
-            [&mut self.tracer, &mut self.log_collector, &mut self.cheatcodes, &mut self.printer],
-            |inspector| {
-                inspector.log(evm_data, address, topics, data);
-            }
-        );
+        self.with_adjusted_depth(evm_data, |stack, evm_data| {
+            call_inspectors!(
+                [
+                    &mut stack.tracer,
+                    &mut stack.log_collector,
+                    &mut stack.cheatcodes,
+                    &mut stack.printer
+                ],
+                |inspector| {
+                    inspector.log(evm_data, address, topics, data);
+                }
+            );
+        })
     }
 
     fn step_end(&mut self, interpreter: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
-        let res = interpreter.instruction_result;
-        call_inspectors!(
-            [
-                &mut self.debugger,
-                &mut self.tracer,
-                &mut self.log_collector,
-                &mut self.cheatcodes,
-                &mut self.printer,
-                &mut self.chisel_state
-            ],
-            |inspector| {
-                inspector.step_end(interpreter, data);
-
-                // Allow inspectors to exit early
-                if interpreter.instruction_result != res {
-                    #[allow(clippy::needless_return)]
-                    return
+        self.with_adjusted_depth(data, |stack, data| {
+            let res = interpreter.instruction_result;
+            call_inspectors!(
+                [
+                    &mut stack.debugger,
+                    &mut stack.tracer,
+                    &mut stack.log_collector,
+                    &mut stack.cheatcodes,
+                    &mut stack.printer,
+                    &mut stack.chisel_state,
+                    &mut stack.usage
+                ],
+                |inspector| {
+                    inspector.step_end(interpreter, data);
+
+                    // Allow inspectors to exit early
+                    if interpreter.instruction_result != res {
+                        #[allow(clippy::needless_return)]
+                        return
+                    }
                 }
-            }
-        );
+            );
+        })
     }
 
     fn call(
@@ -444,28 +678,49 @@ impl<DB: DatabaseExt> Inspector<DB> for InspectorStack {
         data: &mut EVMData<'_, DB>,
         call: &mut CallInputs,
     ) -> (InstructionResult, Gas, Bytes) {
-        call_inspectors!(
-            [
-                &mut self.fuzzer,
-                &mut self.debugger,
-                &mut self.tracer,
-                &mut self.coverage,
-                &mut self.log_collector,
-                &mut self.cheatcodes,
-                &mut self.printer
-            ],
-            |inspector| {
-                let (status, gas, retdata) = inspector.call(data, call);
+        if self.in_inner_context && data.journaled_state.depth == 0 {
+            // the top-level call of a separate transaction, which the inspectors already saw as
+            // part of the outer transaction
+            return (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
+        }
 
-                // Allow inspectors to exit early
-                #[allow(clippy::needless_return)]
-                if status != InstructionResult::Continue {
-                    return (status, gas, retdata)
+        let res = self.with_adjusted_depth(data, |stack, data| {
+            call_inspectors!(
+                [
+                    &mut stack.usage,
+                    &mut stack.fuzzer,
+                    &mut stack.debugger,
+                    &mut stack.tracer,
+                    &mut stack.coverage,
+                    &mut stack.log_collector,
+                    &mut stack.cheatcodes,
+                    &mut stack.printer
+                ],
+                |inspector| {
+                    let (status, gas, retdata) = inspector.call(data, call);
+
+                    // Allow inspectors to exit early
+                    #[allow(clippy::needless_return)]
+                    if status != InstructionResult::Continue {
+                        return (status, gas, retdata)
+                    }
                 }
-            }
-        );
+            );
+
+            (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
+        });
+
+        if res.0 == InstructionResult::Continue &&
+            self.enable_isolation &&
+            !self.in_inner_context &&
+            data.journaled_state.depth == 1 &&
+            call.context.scheme == CallScheme::Call &&
+            !call.is_static
+        {
+            return self.transact_inner(data, call)
+        }
 
-        (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
+        res
     }
 
     fn call_end(
@@ -476,17 +731,26 @@ impl<DB: DatabaseExt> Inspector<DB> for InspectorStack {
         status: InstructionResult,
         retdata: Bytes,
     ) -> (InstructionResult, Gas, Bytes) {
-        let res = self.do_call_end(data, call, remaining_gas, status, retdata);
-
-        if matches!(res.0, return_revert!()) {
-            // Encountered a revert, since cheatcodes may have altered the evm state in such a way
-            // that violates some constraints, e.g. `deal`, we need to manually roll back on revert
-            // before revm reverts the state itself
-            if let Some(cheats) = self.cheatcodes.as_mut() {
-                cheats.on_revert(data);
-            }
+        if self.in_inner_context && data.journaled_state.depth == 0 {
+            return (status, remaining_gas, retdata)
         }
 
+        let res = self.with_adjusted_depth(data, |stack, data| {
+            let res = stack.do_call_end(data, call, remaining_gas, status, retdata);
+
+            if matches!(res.0, return_revert!()) {
+                // Encountered a revert, since cheatcodes may have altered the evm state in such a
+                // way that violates some constraints, e.g. `deal`, we need to manually roll back
+                // on revert before revm reverts the state itself
+                if let Some(cheats) = stack.cheatcodes.as_mut() {
+                    cheats.on_revert(data);
+                }
+            }
+
+            res
+        });
+        self.end_isolation(data, res.0);
+
         res
     }
 
@@ -495,26 +759,29 @@ impl<DB: DatabaseExt> Inspector<DB> for InspectorStack {
         data: &mut EVMData<'_, DB>,
         call: &mut CreateInputs,
     ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
-        call_inspectors!(
-            [
-                &mut self.debugger,
-                &mut self.tracer,
-                &mut self.coverage,
-                &mut self.log_collector,
-                &mut self.cheatcodes,
-                &mut self.printer
-            ],
-            |inspector| {
-                let (status, addr, gas, retdata) = inspector.create(data, call);
-
-                // Allow inspectors to exit early
-                if status != InstructionResult::Continue {
-                    return (status, addr, gas, retdata)
+        self.with_adjusted_depth(data, |stack, data| {
+            call_inspectors!(
+                [
+                    &mut stack.usage,
+                    &mut stack.debugger,
+                    &mut stack.tracer,
+                    &mut stack.coverage,
+                    &mut stack.log_collector,
+                    &mut stack.cheatcodes,
+                    &mut stack.printer
+                ],
+                |inspector| {
+                    let (status, addr, gas, retdata) = inspector.create(data, call);
+
+                    // Allow inspectors to exit early
+                    if status != InstructionResult::Continue {
+                        return (status, addr, gas, retdata)
+                    }
                 }
-            }
-        );
+            );
 
-        (InstructionResult::Continue, None, Gas::new(call.gas_limit), Bytes::new())
+            (InstructionResult::Continue, None, Gas::new(call.gas_limit), Bytes::new())
+        })
     }
 
     fn create_end(
@@ -526,32 +793,37 @@ impl<DB: DatabaseExt> Inspector<DB> for InspectorStack {
         remaining_gas: Gas,
         retdata: Bytes,
     ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
-        call_inspectors!(
-            [
-                &mut self.debugger,
-                &mut self.tracer,
-                &mut self.coverage,
-                &mut self.log_collector,
-                &mut self.cheatcodes,
-                &mut self.printer
-            ],
-            |inspector| {
-                let (new_status, new_address, new_gas, new_retdata) = inspector.create_end(
-                    data,
-                    call,
-                    status,
-                    address,
-                    remaining_gas,
-                    retdata.clone(),
-                );
-
-                if new_status != status {
-                    return (new_status, new_address, new_gas, new_retdata)
+        let res = self.with_adjusted_depth(data, |stack, data| {
+            call_inspectors!(
+                [
+                    &mut stack.debugger,
+                    &mut stack.tracer,
+                    &mut stack.coverage,
+                    &mut stack.log_collector,
+                    &mut stack.cheatcodes,
+                    &mut stack.printer
+                ],
+                |inspector| {
+                    let (new_status, new_address, new_gas, new_retdata) = inspector.create_end(
+                        data,
+                        call,
+                        status,
+                        address,
+                        remaining_gas,
+                        retdata.clone(),
+                    );
+
+                    if new_status != status {
+                        return (new_status, new_address, new_gas, new_retdata)
+                    }
                 }
-            }
-        );
+            );
 
-        (status, address, remaining_gas, retdata)
+            (status, address, remaining_gas, retdata)
+        });
+        self.end_isolation(data, res.0);
+
+        res
     }
 
     fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
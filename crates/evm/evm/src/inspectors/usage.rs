@@ -0,0 +1,48 @@
+use revm::{
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::{Address, Bytes},
+    Database, EVMData, Inspector,
+};
+
+pub use foundry_evm_core::usage::ExecutionUsage;
+
+/// An inspector that records the peak memory expansion and call depth of an execution.
+#[derive(Clone, Debug, Default)]
+pub struct UsageTracker {
+    /// The peak usage so far.
+    pub usage: ExecutionUsage,
+}
+
+impl UsageTracker {
+    fn record_depth(&mut self, depth: u64) {
+        self.usage.max_depth = self.usage.max_depth.max(depth);
+    }
+}
+
+impl<DB: Database> Inspector<DB> for UsageTracker {
+    #[inline]
+    fn step_end(&mut self, interp: &mut Interpreter<'_>, _: &mut EVMData<'_, DB>) {
+        let memory = interp.shared_memory.context_memory().len() as u64;
+        self.usage.max_memory = self.usage.max_memory.max(memory);
+    }
+
+    #[inline]
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        call: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.record_depth(data.journaled_state.depth());
+        (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
+    }
+
+    #[inline]
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        call: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.record_depth(data.journaled_state.depth());
+        (InstructionResult::Continue, None, Gas::new(call.gas_limit), Bytes::new())
+    }
+}
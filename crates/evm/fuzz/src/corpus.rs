@@ -0,0 +1,197 @@
+//! Edge coverage feedback for coverage-guided fuzzing.
+
+use alloy_primitives::{Bytes, Selector, B256, U256};
+use parking_lot::RwLock;
+use revm::{
+    interpreter::{opcode, Interpreter},
+    Database, EVMData, Inspector,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// The number of edges tracked by an [EdgeCoverage] map, like the map of AFL.
+pub const EDGE_MAP_SIZE: usize = 1 << 16;
+
+/// The maximum number of inputs kept in the corpus for a single function.
+const MAX_ENTRIES_PER_FUNCTION: usize = 1024;
+
+/// The branches taken by an execution, as the hit counts of `(jump, destination)` edges hashed
+/// into a fixed size map.
+///
+/// Collisions of edges are accepted for speed, like in AFL.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EdgeCoverage(Vec<u8>);
+
+impl Default for EdgeCoverage {
+    fn default() -> Self {
+        Self(vec![0; EDGE_MAP_SIZE])
+    }
+}
+
+impl std::fmt::Debug for EdgeCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EdgeCoverage").field("edges", &self.edges()).finish()
+    }
+}
+
+impl EdgeCoverage {
+    /// Records a jump from `pc` to `dest` in the code with the given hash.
+    #[inline]
+    pub fn hit(&mut self, code_hash: B256, pc: usize, dest: usize) {
+        let code = u64::from_be_bytes(code_hash[..8].try_into().unwrap());
+        let edge = code ^ ((pc as u64) << 32) ^ dest as u64;
+        let index = (edge.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 48) as usize;
+        self.0[index] = self.0[index].saturating_add(1);
+    }
+
+    /// Returns the number of distinct edges that were hit.
+    pub fn edges(&self) -> usize {
+        self.0.iter().filter(|&&count| count > 0).count()
+    }
+}
+
+/// Maps a hit count to its AFL bucket, so that only significant changes of the number of
+/// iterations of a loop count as new coverage.
+fn bucket(count: u8) -> u8 {
+    match count {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        3 => 4,
+        4..=7 => 8,
+        8..=15 => 16,
+        16..=31 => 32,
+        32..=127 => 64,
+        _ => 128,
+    }
+}
+
+/// An inspector that records the [EdgeCoverage] of an execution.
+#[derive(Clone, Debug, Default)]
+pub struct EdgeCoverageCollector {
+    /// The edges hit so far.
+    pub coverage: EdgeCoverage,
+}
+
+impl<DB: Database> Inspector<DB> for EdgeCoverageCollector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter<'_>, _: &mut EVMData<'_, DB>) {
+        let pc = interp.program_counter();
+        let dest = match interp.current_opcode() {
+            opcode::JUMP => interp.stack().peek(0),
+            opcode::JUMPI => match interp.stack().peek(1) {
+                Ok(condition) if condition.is_zero() => Ok(U256::from(pc + 1)),
+                _ => interp.stack().peek(0),
+            },
+            _ => return,
+        };
+        if let Ok(dest) = dest {
+            self.coverage.hit(interp.contract.hash, pc, dest.saturating_to());
+        }
+    }
+}
+
+/// A [FuzzCorpus] shared by the fuzz tests of a contract.
+pub type SharedCorpus = Arc<RwLock<FuzzCorpus>>;
+
+/// The inputs that reached new coverage, which are mutated to generate new inputs.
+#[derive(Debug, Default)]
+pub struct FuzzCorpus {
+    /// The buckets of hit counts seen so far for every edge, as bit sets.
+    seen: Vec<u8>,
+    /// The inputs of each function, by selector.
+    entries: HashMap<Selector, Vec<Bytes>>,
+    /// The number of inputs that were added, including evicted ones.
+    added: usize,
+}
+
+impl FuzzCorpus {
+    /// Adds the input of an execution to the corpus if it hit an edge, or an edge a number of
+    /// times, that no previous input hit.
+    ///
+    /// Returns whether the input was added.
+    pub fn add_if_interesting(&mut self, calldata: &Bytes, coverage: &EdgeCoverage) -> bool {
+        if calldata.len() < 4 {
+            return false
+        }
+        if self.seen.is_empty() {
+            self.seen = vec![0; EDGE_MAP_SIZE];
+        }
+        let mut interesting = false;
+        for (seen, &count) in self.seen.iter_mut().zip(&coverage.0) {
+            let bucket = bucket(count);
+            if bucket & !*seen != 0 {
+                *seen |= bucket;
+                interesting = true;
+            }
+        }
+        if !interesting {
+            return false
+        }
+
+        let entries = self.entries.entry(Selector::from_slice(&calldata[..4])).or_default();
+        if entries.len() < MAX_ENTRIES_PER_FUNCTION {
+            entries.push(calldata.clone());
+        } else {
+            entries[self.added % MAX_ENTRIES_PER_FUNCTION] = calldata.clone();
+        }
+        self.added += 1;
+        true
+    }
+
+    /// Returns the inputs of the function with the given selector.
+    pub fn entries(&self, selector: Selector) -> &[Bytes] {
+        self.entries.get(&selector).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the number of inputs in the corpus.
+    pub fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    /// Returns whether the corpus is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_adds_new_coverage() {
+        let calldata = Bytes::from_static(&[1, 2, 3, 4]);
+        let mut coverage = EdgeCoverage::default();
+        coverage.hit(B256::ZERO, 1, 10);
+
+        let mut corpus = FuzzCorpus::default();
+        assert!(corpus.add_if_interesting(&calldata, &coverage));
+        assert!(!corpus.add_if_interesting(&calldata, &coverage));
+
+        // hitting the same edge twice is a new bucket
+        coverage.hit(B256::ZERO, 1, 10);
+        assert!(corpus.add_if_interesting(&calldata, &coverage));
+        coverage.hit(B256::ZERO, 1, 10);
+        coverage.hit(B256::ZERO, 1, 10);
+        assert!(corpus.add_if_interesting(&calldata, &coverage));
+        // but 5 times is in the same bucket as 4 times
+        coverage.hit(B256::ZERO, 1, 10);
+        assert!(!corpus.add_if_interesting(&calldata, &coverage));
+
+        // the other branch is a new edge
+        coverage.hit(B256::ZERO, 1, 2);
+        assert!(corpus.add_if_interesting(&calldata, &coverage));
+
+        assert_eq!(corpus.entries(Selector::new([1, 2, 3, 4])).len(), 4);
+        assert_eq!(corpus.len(), 4);
+        assert!(!corpus.add_if_interesting(&Bytes::new(), &coverage));
+    }
+
+    #[test]
+    fn buckets_hit_counts() {
+        assert_eq!(bucket(3), 4);
+        assert_eq!(bucket(5), bucket(7));
+        assert_ne!(bucket(7), bucket(8));
+        assert_eq!(bucket(255), 128);
+    }
+}
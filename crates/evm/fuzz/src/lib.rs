@@ -11,6 +11,7 @@ use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
 use alloy_primitives::{Address, Bytes, U256};
 use ethers_core::types::Log;
 use foundry_common::{calc, contracts::ContractsByAddress};
+use foundry_evm_core::usage::ExecutionUsage;
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_traces::CallTraceArena;
 use itertools::Itertools;
@@ -25,6 +26,9 @@ pub use error::FuzzError;
 pub mod invariant;
 pub mod strategies;
 
+mod corpus;
+pub use corpus::{EdgeCoverage, EdgeCoverageCollector, FuzzCorpus, SharedCorpus, EDGE_MAP_SIZE};
+
 mod inspector;
 pub use inspector::Fuzzer;
 
@@ -36,6 +40,18 @@ pub enum CounterExample {
     Sequence(Vec<BaseCounterExample>),
 }
 
+impl CounterExample {
+    /// Attaches the labels of the addresses the counterexample refers to.
+    pub fn apply_labels(&mut self, labels: &BTreeMap<Address, String>) {
+        match self {
+            CounterExample::Single(example) => example.apply_labels(labels),
+            CounterExample::Sequence(sequence) => {
+                sequence.iter_mut().for_each(|example| example.apply_labels(labels))
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BaseCounterExample {
     /// Address which makes the call
@@ -52,6 +68,9 @@ pub struct BaseCounterExample {
     pub traces: Option<CallTraceArena>,
     #[serde(skip)]
     pub args: Vec<DynSolValue>,
+    /// Labels of the sender, the callee and the address arguments
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<Address, String>,
 }
 
 impl BaseCounterExample {
@@ -74,6 +93,7 @@ impl BaseCounterExample {
                         contract_name: Some(name.clone()),
                         traces,
                         args,
+                        labels: BTreeMap::new(),
                     }
                 }
             }
@@ -87,14 +107,36 @@ impl BaseCounterExample {
             contract_name: None,
             traces,
             args: vec![],
+            labels: BTreeMap::new(),
+        }
+    }
+
+    /// Attaches the labels of the sender, the callee and the address arguments.
+    pub fn apply_labels(&mut self, labels: &BTreeMap<Address, String>) {
+        let args = self.args.iter().filter_map(|arg| match arg {
+            DynSolValue::Address(addr) => Some(*addr),
+            _ => None,
+        });
+        for addr in self.sender.into_iter().chain(self.addr).chain(args) {
+            if let Some(label) = labels.get(&addr) {
+                self.labels.insert(addr, label.clone());
+            }
+        }
+    }
+
+    /// Formats the address with its label, like in traces.
+    fn format_address(&self, addr: &Address) -> String {
+        match self.labels.get(addr) {
+            Some(label) => format!("{label}: [{addr}]"),
+            None => addr.to_string(),
         }
     }
 }
 
 impl fmt::Display for BaseCounterExample {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(sender) = self.sender {
-            write!(f, "sender={sender} addr=")?
+        if let Some(sender) = &self.sender {
+            write!(f, "sender={} addr=", self.format_address(sender))?
         }
 
         if let Some(name) = &self.contract_name {
@@ -102,7 +144,7 @@ impl fmt::Display for BaseCounterExample {
         }
 
         if let Some(addr) = &self.addr {
-            write!(f, "{addr} ")?
+            write!(f, "{} ", self.format_address(addr))?
         }
 
         if let Some(sig) = &self.signature {
@@ -111,7 +153,11 @@ impl fmt::Display for BaseCounterExample {
             write!(f, "calldata={}", self.calldata)?
         }
 
-        write!(f, " args=[{}]", foundry_common::fmt::format_tokens(&self.args).format(", "))
+        let args = self.args.iter().map(|arg| match arg {
+            DynSolValue::Address(addr) => self.format_address(addr),
+            arg => foundry_common::fmt::format_token(arg),
+        });
+        write!(f, " args=[{}]", args.format(", "))
     }
 }
 
@@ -152,6 +198,9 @@ pub struct FuzzTestResult {
 
     /// Raw coverage info
     pub coverage: Option<HitMaps>,
+
+    /// The peak memory and call depth over all fuzz cases, if tracked
+    pub usage: Option<ExecutionUsage>,
 }
 
 impl FuzzTestResult {
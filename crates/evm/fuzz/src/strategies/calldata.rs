@@ -1,24 +1,175 @@
 use super::fuzz_param;
-use alloy_dyn_abi::JsonAbiExt;
+use crate::SharedCorpus;
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
 use alloy_json_abi::Function;
-use alloy_primitives::Bytes;
-use proptest::prelude::{BoxedStrategy, Strategy};
+use alloy_primitives::{Bytes, I256, U256};
+use proptest::{
+    prelude::{any, BoxedStrategy, Strategy},
+    sample::Index,
+};
 
 /// Given a function, it returns a strategy which generates valid calldata
 /// for that function's input types.
 pub fn fuzz_calldata(func: Function) -> BoxedStrategy<Bytes> {
+    fuzz_inputs(&func)
+        .prop_map(move |tokens| {
+            trace!(input=?tokens);
+            func.abi_encode_input(&tokens).unwrap().into()
+        })
+        .boxed()
+}
+
+/// Returns a strategy which generates values for the inputs of the function.
+fn fuzz_inputs(func: &Function) -> BoxedStrategy<Vec<DynSolValue>> {
     // We need to compose all the strategies generated for each parameter in all
     // possible combinations
-    let strats = func
-        .inputs
+    func.inputs
         .iter()
         .map(|input| fuzz_param(&input.selector_type().parse().unwrap()))
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+        .boxed()
+}
 
-    strats
-        .prop_map(move |tokens| {
+/// Given a function and a corpus, it returns a strategy which generates calldata by mutating one
+/// input of a random corpus entry of the function: the input is replaced with a new random value,
+/// or, if it's a number, bool or bytes, a bit of it is flipped or a small delta is added to it.
+///
+/// Random calldata is generated while the corpus has no entries for the function.
+pub fn fuzz_calldata_from_corpus(func: Function, corpus: SharedCorpus) -> BoxedStrategy<Bytes> {
+    let selector = func.selector();
+    (fuzz_inputs(&func), any::<Index>(), any::<Index>(), any::<(u8, u8)>())
+        .prop_map(move |(fresh, entry, input, mutation)| {
+            let mutated = {
+                let corpus = corpus.read();
+                let entries = corpus.entries(selector);
+                (!entries.is_empty() && !fresh.is_empty())
+                    .then(|| {
+                        func.abi_decode_input(&entries[entry.index(entries.len())][4..], false)
+                    })
+                    .and_then(Result::ok)
+            };
+            let tokens = match mutated {
+                Some(mut tokens) => {
+                    let i = input.index(tokens.len());
+                    if !mutate_value(&mut tokens[i], mutation) {
+                        tokens[i] = fresh[i].clone();
+                    }
+                    tokens
+                }
+                None => fresh,
+            };
             trace!(input=?tokens);
             func.abi_encode_input(&tokens).unwrap().into()
         })
         .boxed()
 }
+
+/// Applies a small mutation to the value, selected by `kind` and parameterized by `n`.
+///
+/// Returns `false` if the value should be replaced instead, because `kind` asks for it or the
+/// value can't be mutated.
+fn mutate_value(value: &mut DynSolValue, (kind, n): (u8, u8)) -> bool {
+    // a third of the mutations replace the value
+    let (kind, n) = match kind % 3 {
+        0 => return false,
+        kind => (kind, n as usize),
+    };
+    match value {
+        DynSolValue::Uint(word, size) => *word = mutate_word(*word, *size, kind, n),
+        DynSolValue::Int(word, size) => {
+            let raw = mutate_word(word.into_raw(), *size, kind, n);
+            *word = I256::from_raw(sign_extend(raw, *size));
+        }
+        DynSolValue::Bool(value) => *value = !*value,
+        DynSolValue::FixedBytes(word, size) if *size > 0 => {
+            word[n % *size] ^= 1 << (n % 8);
+        }
+        DynSolValue::Bytes(bytes) if !bytes.is_empty() => {
+            let len = bytes.len();
+            bytes[n % len] ^= 1 << (n % 8);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Adds or subtracts a small delta from the word if `kind` is 1, or flips one of its bits
+/// otherwise, keeping it in the `size` lowest bits.
+fn mutate_word(word: U256, size: usize, kind: u8, n: usize) -> U256 {
+    let mutated = if kind == 1 {
+        let delta = U256::from(n / 2 + 1);
+        if n % 2 == 0 {
+            word.wrapping_add(delta)
+        } else {
+            word.wrapping_sub(delta)
+        }
+    } else {
+        word ^ (U256::from(1) << (n % size))
+    };
+    mutated & mask(size)
+}
+
+/// Extends the sign bit of a `size` bits two's complement number to the whole word.
+fn sign_extend(word: U256, size: usize) -> U256 {
+    if size < 256 && word.bit(size - 1) {
+        word | !mask(size)
+    } else {
+        word
+    }
+}
+
+/// Returns a word with the `size` lowest bits set.
+fn mask(size: usize) -> U256 {
+    if size >= 256 {
+        U256::MAX
+    } else {
+        (U256::from(1) << size) - U256::from(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EdgeCoverage, FuzzCorpus};
+    use alloy_primitives::B256;
+    use parking_lot::RwLock;
+    use proptest::strategy::ValueTree;
+    use std::sync::Arc;
+
+    #[test]
+    fn mutates_words_in_range() {
+        assert_eq!(mutate_word(U256::from(255), 8, 1, 0), U256::ZERO);
+        assert_eq!(mutate_word(U256::ZERO, 8, 1, 1), U256::from(255));
+        assert_eq!(mutate_word(U256::ZERO, 8, 2, 9), U256::from(2));
+
+        let mut value = DynSolValue::Int(I256::ZERO, 8);
+        assert!(mutate_value(&mut value, (1, 1)));
+        assert_eq!(value, DynSolValue::Int(I256::MINUS_ONE, 8));
+        assert!(!mutate_value(&mut value, (3, 1)));
+        assert!(!mutate_value(&mut DynSolValue::String("a".into()), (1, 1)));
+    }
+
+    #[test]
+    fn mutates_corpus_entries() {
+        let func = Function::parse("f(uint8 a, uint8 b)").unwrap();
+        let entry = func
+            .abi_encode_input(&[
+                DynSolValue::Uint(U256::from(7), 8),
+                DynSolValue::Uint(U256::from(7), 8),
+            ])
+            .unwrap();
+        let mut coverage = EdgeCoverage::default();
+        coverage.hit(B256::ZERO, 0, 1);
+        let mut corpus = FuzzCorpus::default();
+        assert!(corpus.add_if_interesting(&entry.into(), &coverage));
+
+        let strategy = fuzz_calldata_from_corpus(func.clone(), Arc::new(RwLock::new(corpus)));
+        let mut runner = proptest::test_runner::TestRunner::deterministic();
+        for _ in 0..32 {
+            let calldata = strategy.new_tree(&mut runner).unwrap().current();
+            let tokens = func.abi_decode_input(&calldata[4..], false).unwrap();
+            // only one of the inputs is mutated
+            assert!(tokens.contains(&DynSolValue::Uint(U256::from(7), 8)), "{tokens:?}");
+        }
+    }
+}
@@ -8,7 +8,7 @@ mod param;
 pub use param::{fuzz_param, fuzz_param_from_state};
 
 mod calldata;
-pub use calldata::fuzz_calldata;
+pub use calldata::{fuzz_calldata, fuzz_calldata_from_corpus};
 
 mod state;
 pub use state::{
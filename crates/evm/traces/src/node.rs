@@ -51,6 +51,34 @@ impl CallTraceNode {
         }
     }
 
+    /// Returns the `error` of a parity trace, `None` if the call succeeded
+    ///
+    /// The messages are the ones of OpenEthereum, which indexers match on.
+    pub fn parity_error(&self) -> Option<String> {
+        if self.trace.success {
+            return None
+        }
+        let error = match self.status() {
+            InstructionResult::Revert => "Reverted".to_string(),
+            InstructionResult::OutOfGas |
+            InstructionResult::MemoryOOG |
+            InstructionResult::MemoryLimitOOG |
+            InstructionResult::PrecompileOOG |
+            InstructionResult::InvalidOperandOOG => "Out of gas".to_string(),
+            InstructionResult::OpcodeNotFound | InstructionResult::InvalidFEOpcode => {
+                "Bad instruction".to_string()
+            }
+            InstructionResult::StackUnderflow => "Stack underflow".to_string(),
+            InstructionResult::StackOverflow | InstructionResult::CallTooDeep => {
+                "Out of stack".to_string()
+            }
+            InstructionResult::InvalidJump => "Bad jump destination".to_string(),
+            InstructionResult::PrecompileError => "Built-in failed".to_string(),
+            status => format!("{status:?}"),
+        };
+        Some(error)
+    }
+
     /// Returns the `Action` for a parity trace
     pub fn parity_action(&self) -> Action {
         if self.status() == InstructionResult::SelfDestruct {
@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^{{solc_version}};
+
+import {Script} from "forge-std/Script.sol";
+import {Token} from "../src/Token.sol";
+
+contract TokenScript is Script {
+    function run() public returns (Token token) {
+        vm.startBroadcast();
+        token = new Token(1_000_000 ether);
+        vm.stopBroadcast();
+    }
+}
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^{{solc_version}};
+
+import {Test} from "forge-std/Test.sol";
+import {Token} from "../src/Token.sol";
+
+contract TokenTest is Test {
+    Token public token;
+
+    function setUp() public {
+        token = new Token(1_000_000 ether);
+    }
+
+    function test_InitialSupply() public {
+        assertEq(token.totalSupply(), 1_000_000 ether);
+        assertEq(token.balanceOf(address(this)), 1_000_000 ether);
+    }
+
+    function testFuzz_Transfer(address to, uint256 amount) public {
+        vm.assume(to != address(0) && to != address(this));
+        amount = bound(amount, 0, token.totalSupply());
+
+        token.transfer(to, amount);
+        assertEq(token.balanceOf(to), amount);
+    }
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^{{solc_version}};
+
+import {Test} from "forge-std/Test.sol";
+import {Math} from "../src/Math.sol";
+
+contract MathTest is Test {
+    function testFuzz_Max(uint256 a, uint256 b) public {
+        uint256 max = Math.max(a, b);
+        assertGe(max, a);
+        assertGe(max, b);
+    }
+
+    function testFuzz_Min(uint256 a, uint256 b) public {
+        uint256 min = Math.min(a, b);
+        assertLe(min, a);
+        assertLe(min, b);
+    }
+}
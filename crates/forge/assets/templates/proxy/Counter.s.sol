@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^{{solc_version}};
+
+import {Script} from "forge-std/Script.sol";
+import {ERC1967Proxy} from "@openzeppelin/contracts/proxy/ERC1967/ERC1967Proxy.sol";
+import {Counter} from "../src/Counter.sol";
+
+contract CounterScript is Script {
+    function run() public returns (Counter counter) {
+        vm.startBroadcast();
+        Counter implementation = new Counter();
+        bytes memory data = abi.encodeCall(Counter.initialize, (msg.sender));
+        counter = Counter(address(new ERC1967Proxy(address(implementation), data)));
+        vm.stopBroadcast();
+    }
+}
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^{{solc_version}};
+
+import {Test} from "forge-std/Test.sol";
+import {ERC1967Proxy} from "@openzeppelin/contracts/proxy/ERC1967/ERC1967Proxy.sol";
+import {Counter} from "../src/Counter.sol";
+
+contract CounterTest is Test {
+    Counter public counter;
+
+    function setUp() public {
+        Counter implementation = new Counter();
+        bytes memory data = abi.encodeCall(Counter.initialize, (address(this)));
+        counter = Counter(address(new ERC1967Proxy(address(implementation), data)));
+    }
+
+    function test_Increment() public {
+        counter.increment();
+        assertEq(counter.number(), 1);
+    }
+
+    function test_Upgrade() public {
+        counter.setNumber(42);
+        counter.upgradeToAndCall(address(new Counter()), "");
+        assertEq(counter.number(), 42);
+    }
+
+    function test_RevertWhen_UpgradeByNonOwner() public {
+        address newImplementation = address(new Counter());
+        vm.prank(address(0xdead));
+        vm.expectRevert();
+        counter.upgradeToAndCall(newImplementation, "");
+    }
+}
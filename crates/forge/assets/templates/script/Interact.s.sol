@@ -0,0 +1,15 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^{{solc_version}};
+
+import {Script, console2} from "forge-std/Script.sol";
+
+/// @notice Entry point of {{project_name}}, run with `forge script script/Interact.s.sol`
+contract InteractScript is Script {
+    function run() public {
+        console2.log("sender", msg.sender);
+        console2.log("block", block.number);
+
+        vm.startBroadcast();
+        vm.stopBroadcast();
+    }
+}
@@ -0,0 +1,87 @@
+use clap::{Parser, Subcommand, ValueHint};
+use eyre::Result;
+use foundry_cli::utils;
+use foundry_common::{
+    artifacts::{migrate_artifact_file, schema_version, ARTIFACT_SCHEMA_VERSION},
+    fs,
+};
+use std::path::PathBuf;
+
+/// CLI arguments for `forge artifacts`.
+#[derive(Debug, Clone, Parser)]
+pub struct ArtifactsArgs {
+    #[clap(subcommand)]
+    pub sub: ArtifactsSubcommands,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ArtifactsSubcommands {
+    /// Migrate the build artifacts to the current artifact schema version.
+    Migrate {
+        /// Only check whether any artifacts need to be migrated, without rewriting them.
+        ///
+        /// Exits with an error if any artifact is outdated.
+        #[clap(long)]
+        check: bool,
+
+        /// The project's root path.
+        ///
+        /// By default root of the Git repository, if in one,
+        /// or the current working directory.
+        #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+        root: Option<PathBuf>,
+    },
+}
+
+impl ArtifactsSubcommands {
+    pub fn run(self) -> Result<()> {
+        match self {
+            ArtifactsSubcommands::Migrate { check, root } => {
+                let config = utils::load_config_with_root(root);
+                let build_info = config.out.join("build-info");
+                let files = fs::json_files(&config.out)
+                    .into_iter()
+                    .filter(|path| !path.starts_with(&build_info))
+                    .collect::<Vec<_>>();
+
+                let mut outdated = Vec::new();
+                for path in files {
+                    if check {
+                        let artifact: serde_json::Map<_, _> = fs::read_json_file(&path)?;
+                        if schema_version(&artifact) < ARTIFACT_SCHEMA_VERSION {
+                            outdated.push(path);
+                        }
+                    } else if migrate_artifact_file(&path)? {
+                        outdated.push(path);
+                    }
+                }
+
+                let paths = outdated
+                    .iter()
+                    .map(|path| {
+                        format!(
+                            "  {}",
+                            path.strip_prefix(&config.__root.0).unwrap_or(path).display()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if outdated.is_empty() {
+                    println!("All artifacts are at schema version {ARTIFACT_SCHEMA_VERSION}");
+                } else if check {
+                    eyre::bail!(
+                        "{} artifact(s) need to be migrated to schema version \
+                         {ARTIFACT_SCHEMA_VERSION}:\n{paths}",
+                        outdated.len()
+                    );
+                } else {
+                    println!(
+                        "Migrated {} artifact(s) to schema version {ARTIFACT_SCHEMA_VERSION}:\n{paths}",
+                        outdated.len()
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+}
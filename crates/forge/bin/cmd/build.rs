@@ -1,12 +1,17 @@
 use super::{install, watch::WatchArgs};
 use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Attribute, Cell, Color, Table};
 use eyre::Result;
 use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
 use foundry_common::{
     compile,
-    compile::{ProjectCompiler, SkipBuildFilter},
+    compile::{ProjectCompiler, SizeReport, SkipBuildFilter},
+    diagnostics,
+};
+use foundry_compilers::{
+    artifacts::output_selection::{ContractOutputSelection, EvmOutputSelection},
+    Project, ProjectCompileOutput,
 };
-use foundry_compilers::{Project, ProjectCompileOutput};
 use foundry_config::{
     figment::{
         self,
@@ -17,6 +22,7 @@ use foundry_config::{
     Config,
 };
 use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
 use watchexec::config::{InitConfig, RuntimeConfig};
 
 foundry_config::merge_impl_figment_convert!(BuildArgs, args);
@@ -55,6 +61,12 @@ pub struct BuildArgs {
     #[serde(skip)]
     pub sizes: bool,
 
+    /// Exit with an error if a contract exceeds the EIP-170 runtime or the EIP-3860 initcode size
+    /// limit, excluding test and script contracts.
+    #[clap(long, requires = "sizes")]
+    #[serde(skip)]
+    pub check: bool,
+
     /// Skip building files whose names contain the given filter.
     ///
     /// `test` and `script` are aliases for `.t.sol` and `.s.sol`.
@@ -70,11 +82,21 @@ pub struct BuildArgs {
     #[serde(skip)]
     pub watch: WatchArgs,
 
-    /// Output the compilation errors in the json format.
+    /// Output the compilation errors in the json format, along with their source-anchored
+    /// `diagnostics`.
     /// This is useful when you want to use the output in other tools.
     #[clap(long, conflicts_with = "silent")]
     #[serde(skip)]
     pub format_json: bool,
+
+    /// Build the project with both the legacy and the IR pipeline, and compare the code size,
+    /// the estimated deployment gas and the estimated gas of the external functions of the
+    /// contracts.
+    ///
+    /// No artifacts are written.
+    #[clap(long, conflicts_with = "format_json")]
+    #[serde(skip)]
+    pub compare_pipelines: bool,
 }
 
 impl BuildArgs {
@@ -92,15 +114,20 @@ impl BuildArgs {
 
         let filters = self.skip.unwrap_or_default();
 
-        if self.format_json {
+        if self.compare_pipelines {
+            compare_pipelines(&config, filters, self.args.silent)
+        } else if self.format_json {
             let output = compile::suppress_compile_with_filter_json(&project, filters)?;
-            let json = serde_json::to_string_pretty(&output.clone().output())?;
-            println!("{}", json);
+            let mut json = serde_json::to_value(output.clone().output())?;
+            json["diagnostics"] =
+                serde_json::to_value(diagnostics::compiler_diagnostics(&output, &project))?;
+            println!("{}", serde_json::to_string_pretty(&json)?);
             Ok(output)
         } else if self.args.silent {
             compile::suppress_compile_with_filter(&project, filters)
         } else {
-            let compiler = ProjectCompiler::with_filter(self.names, self.sizes, filters);
+            let compiler = ProjectCompiler::with_filter(self.names, self.sizes, filters)
+                .check_sizes(self.check);
             compiler.compile(&project)
         }
     }
@@ -130,6 +157,182 @@ impl BuildArgs {
     }
 }
 
+/// Compiles the project with the legacy and the IR pipeline, without writing any artifacts, and
+/// prints how the code size and estimated deployment gas of every contract change with the IR
+/// pipeline.
+///
+/// Returns the output of the pipeline selected in the config.
+fn compare_pipelines(
+    config: &Config,
+    filters: Vec<SkipBuildFilter>,
+    silent: bool,
+) -> Result<ProjectCompileOutput> {
+    let mut config = config.clone();
+    let gas_estimates = ContractOutputSelection::Evm(EvmOutputSelection::GasEstimates);
+    if !config.extra_output.contains(&gas_estimates) {
+        config.extra_output.push(gas_estimates);
+    }
+
+    let compile = |via_ir: bool| -> Result<ProjectCompileOutput> {
+        let project = Config { via_ir, ..config.clone() }.ephemeral_no_artifacts_project()?;
+        if silent {
+            compile::suppress_compile_with_filter(&project, filters.clone())
+        } else {
+            println!("Compiling with the {} pipeline", if via_ir { "IR" } else { "legacy" });
+            ProjectCompiler::with_filter(false, false, filters.clone()).compile(&project)
+        }
+    };
+    let legacy = compile(false)?;
+    let ir = compile(true)?;
+
+    println!("{}", PipelineComparison::new(&legacy, &ir));
+
+    Ok(if config.via_ir { ir } else { legacy })
+}
+
+/// The code size and estimated deployment and runtime gas of a contract compiled with one pipeline
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PipelineStats {
+    size: usize,
+    /// The deployment gas estimated by solc, if it is finite
+    deployment_gas: Option<u64>,
+    /// The gas of the external functions estimated by solc, by signature, if it is finite
+    runtime_gas: BTreeMap<String, Option<u64>>,
+}
+
+impl PipelineStats {
+    /// Collects the stats of all non-test contracts in the given compiler output, by name
+    fn collect(output: &ProjectCompileOutput) -> BTreeMap<String, Self> {
+        let mut gas = output
+            .artifacts()
+            .filter_map(|(name, artifact)| {
+                let estimates = artifact.gas_estimates.as_ref()?;
+                let deployment_gas = estimates.creation.total_cost.parse::<u64>().ok();
+                let runtime_gas = estimates
+                    .external
+                    .iter()
+                    .map(|(signature, gas)| (signature.clone(), gas.parse::<u64>().ok()))
+                    .collect();
+                Some((name, (deployment_gas, runtime_gas)))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        SizeReport::new(output)
+            .contracts
+            .into_iter()
+            .filter(|(_, contract)| !contract.is_dev_contract && contract.size > 0)
+            .map(|(name, contract)| {
+                let (deployment_gas, runtime_gas) = gas.remove(&name).unwrap_or_default();
+                (name, Self { size: contract.size, deployment_gas, runtime_gas })
+            })
+            .collect()
+    }
+}
+
+/// The stats of the contracts compiled with the legacy and the IR pipeline
+struct PipelineComparison {
+    /// `<contract name>: (<legacy>, <IR>)`
+    contracts: BTreeMap<String, (PipelineStats, PipelineStats)>,
+}
+
+impl PipelineComparison {
+    fn new(legacy: &ProjectCompileOutput, ir: &ProjectCompileOutput) -> Self {
+        let mut ir = PipelineStats::collect(ir);
+        let contracts = PipelineStats::collect(legacy)
+            .into_iter()
+            .filter_map(|(name, legacy)| {
+                let ir = ir.remove(&name)?;
+                Some((name, (legacy, ir)))
+            })
+            .collect();
+        Self { contracts }
+    }
+}
+
+impl fmt::Display for PipelineComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(
+            [
+                "Contract",
+                "Legacy Size (kB)",
+                "IR Size (kB)",
+                "Size Change",
+                "Legacy Deployment Gas",
+                "IR Deployment Gas",
+                "Gas Change",
+            ]
+            .map(|header| Cell::new(header).add_attribute(Attribute::Bold).fg(Color::Blue)),
+        );
+
+        let gas = |gas: Option<u64>| gas.map_or_else(|| "-".to_string(), |gas| gas.to_string());
+        let gas_change = |legacy: Option<u64>, ir: Option<u64>| {
+            change_cell(legacy.zip(ir).and_then(|(legacy, ir)| relative_change(legacy, ir)))
+        };
+        for (name, (legacy, ir)) in &self.contracts {
+            table.add_row([
+                Cell::new(name),
+                Cell::new(legacy.size as f64 / 1000.0),
+                Cell::new(ir.size as f64 / 1000.0),
+                change_cell(relative_change(legacy.size as u64, ir.size as u64)),
+                Cell::new(gas(legacy.deployment_gas)),
+                Cell::new(gas(ir.deployment_gas)),
+                gas_change(legacy.deployment_gas, ir.deployment_gas),
+            ]);
+        }
+        write!(f, "{table}")?;
+
+        // the runtime gas of the external functions, as estimated by solc
+        let mut runtime = Table::new();
+        runtime.load_preset(ASCII_MARKDOWN);
+        runtime.set_header(
+            ["Contract", "Function", "Legacy Runtime Gas", "IR Runtime Gas", "Gas Change"]
+                .map(|header| Cell::new(header).add_attribute(Attribute::Bold).fg(Color::Blue)),
+        );
+        let mut has_functions = false;
+        for (name, (legacy, ir)) in &self.contracts {
+            for (signature, legacy_gas) in &legacy.runtime_gas {
+                let ir_gas = ir.runtime_gas.get(signature).copied().flatten();
+                runtime.add_row([
+                    Cell::new(name),
+                    Cell::new(signature),
+                    Cell::new(gas(*legacy_gas)),
+                    Cell::new(gas(ir_gas)),
+                    gas_change(*legacy_gas, ir_gas),
+                ]);
+                has_functions = true;
+            }
+        }
+        if has_functions {
+            write!(f, "\n\n{runtime}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the change from `legacy` to `ir` in percent
+fn relative_change(legacy: u64, ir: u64) -> Option<f64> {
+    (legacy != 0).then(|| (ir as f64 - legacy as f64) * 100.0 / legacy as f64)
+}
+
+/// Green if the IR pipeline is smaller or cheaper, red if it is larger or more expensive
+fn change_cell(change: Option<f64>) -> Cell {
+    match change {
+        Some(change) => {
+            let color = if change < 0.0 {
+                Color::Green
+            } else if change > 0.0 {
+                Color::Red
+            } else {
+                Color::Reset
+            };
+            Cell::new(format!("{change:+.2}%")).fg(color)
+        }
+        None => Cell::new("-"),
+    }
+}
+
 // Make this args a `figment::Provider` so that it can be merged into the `Config`
 impl Provider for BuildArgs {
     fn metadata(&self) -> Metadata {
@@ -173,6 +376,13 @@ mod tests {
         assert_eq!(args.skip, Some(vec![SkipBuildFilter::Tests, SkipBuildFilter::Scripts]));
     }
 
+    #[test]
+    fn can_compute_relative_change() {
+        assert_eq!(relative_change(200, 150), Some(-25.0));
+        assert_eq!(relative_change(200, 250), Some(25.0));
+        assert_eq!(relative_change(0, 100), None);
+    }
+
     #[test]
     fn check_conflicts() {
         let args: std::result::Result<BuildArgs, clap::Error> =
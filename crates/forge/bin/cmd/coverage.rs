@@ -63,6 +63,12 @@ pub struct CoverageArgs {
     )]
     report_file: Option<PathBuf>,
 
+    /// Include the sources in the script directory in the coverage report.
+    ///
+    /// Scripts are excluded by default, as they are only covered by the tests that call them.
+    #[clap(long)]
+    include_scripts: bool,
+
     #[clap(flatten)]
     filter: FilterArgs,
 
@@ -166,6 +172,13 @@ impl CoverageArgs {
                 continue
             }
 
+            // Filter out scripts, unless requested
+            if !self.include_scripts &&
+                project_paths.root.join(&path).starts_with(&project_paths.scripts)
+            {
+                continue
+            }
+
             if let Some(ast) = source_file.ast.take() {
                 versioned_asts
                     .entry(version.clone())
@@ -0,0 +1,120 @@
+use clap::{Parser, Subcommand, ValueHint};
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::{ContextCompat, Result};
+use foundry_cli::utils;
+use foundry_common::deployments::DeploymentRegistry;
+use foundry_config::Chain;
+use std::path::PathBuf;
+
+/// CLI arguments for `forge deployments`.
+#[derive(Debug, Clone, Parser)]
+pub struct DeploymentsArgs {
+    #[clap(subcommand)]
+    pub sub: DeploymentsSubcommands,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum DeploymentsSubcommands {
+    /// List the deployments recorded by `forge script --broadcast`.
+    #[clap(visible_alias = "ls")]
+    List {
+        /// Only list the deployments on the given chain.
+        #[clap(long)]
+        chain: Option<Chain>,
+
+        /// Print the deployments as JSON.
+        #[clap(long, short)]
+        json: bool,
+
+        /// The project's root path.
+        ///
+        /// By default root of the Git repository, if in one,
+        /// or the current working directory.
+        #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+        root: Option<PathBuf>,
+    },
+
+    /// Get the latest deployment of a contract.
+    Get {
+        /// The name of the deployed contract.
+        contract: String,
+
+        /// The chain the contract was deployed on.
+        ///
+        /// Defaults to the chain of the project's configuration.
+        #[clap(long)]
+        chain: Option<Chain>,
+
+        /// Print the full deployment manifest as JSON, instead of only the address.
+        #[clap(long, short)]
+        json: bool,
+
+        /// The project's root path.
+        ///
+        /// By default root of the Git repository, if in one,
+        /// or the current working directory.
+        #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+        root: Option<PathBuf>,
+    },
+}
+
+impl DeploymentsSubcommands {
+    pub fn run(self) -> Result<()> {
+        match self {
+            DeploymentsSubcommands::List { chain, json, root } => {
+                let config = utils::load_config_with_root(root);
+                let registry = DeploymentRegistry::new(&config.__root.0);
+                let deployments = registry.list(chain.map(|chain| chain.id()))?;
+
+                if json {
+                    let deployments = deployments
+                        .into_iter()
+                        .map(|(chain, manifest)| {
+                            let mut value = serde_json::to_value(manifest)?;
+                            value["chainId"] = chain.into();
+                            Ok(value)
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    println!("{}", serde_json::to_string_pretty(&deployments)?);
+                    return Ok(())
+                }
+
+                if deployments.is_empty() {
+                    println!("No deployments found.");
+                    return Ok(())
+                }
+
+                let mut table = Table::new();
+                table.load_preset(ASCII_MARKDOWN);
+                table.set_header(["Chain", "Contract", "Address", "Transaction", "Commit"]);
+                for (chain, manifest) in deployments {
+                    table.add_row([
+                        chain.to_string(),
+                        manifest.contract_name,
+                        manifest.address.to_string(),
+                        manifest.transaction_hash.to_string(),
+                        manifest.commit.unwrap_or_default(),
+                    ]);
+                }
+                println!("{table}");
+            }
+            DeploymentsSubcommands::Get { contract, chain, json, root } => {
+                let config = utils::load_config_with_root(root);
+                let chain = chain.or(config.chain).wrap_err(
+                    "no chain configured, pass the chain of the deployment with --chain",
+                )?;
+                let registry = DeploymentRegistry::new(&config.__root.0);
+                let manifest = registry
+                    .get(chain.id(), &contract)?
+                    .wrap_err_with(|| format!("no deployment of `{contract}` found on {chain}"))?;
+
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&manifest)?);
+                } else {
+                    println!("{}", manifest.address);
+                }
+            }
+        }
+        Ok(())
+    }
+}
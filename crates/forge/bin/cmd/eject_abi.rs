@@ -0,0 +1,227 @@
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::Address;
+use clap::{Parser, ValueHint};
+use eyre::{Result, WrapErr};
+use foundry_cli::{
+    opts::CoreBuildArgs,
+    utils::{FoundryPathExt, LoadConfig},
+};
+use foundry_common::{compile, deployments::DeploymentRegistry, fs};
+use foundry_config::Chain;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+use yansi::Paint;
+
+/// CLI arguments for `forge eject-abi`.
+#[derive(Debug, Clone, Parser)]
+pub struct EjectAbiArgs {
+    /// The directory to write a `<Contract>.json` file with the ABI and addresses of every
+    /// contract to.
+    #[clap(long, short, value_hint = ValueHint::DirPath, value_name = "DIR", default_value = "abis")]
+    out: PathBuf,
+
+    /// Write a single TypeScript module exporting the ABIs and addresses instead.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "FILE", conflicts_with = "out")]
+    ts: Option<PathBuf>,
+
+    /// Only export the contracts whose name or source path matches the glob.
+    ///
+    /// Can be passed multiple times. By default all contracts in the sources dir, except tests,
+    /// are exported.
+    #[clap(long, value_name = "GLOB")]
+    select: Vec<String>,
+
+    /// Only include the addresses of the deployments on the given chain.
+    #[clap(long)]
+    chain: Option<Chain>,
+
+    #[clap(flatten)]
+    build: CoreBuildArgs,
+}
+
+foundry_config::impl_figment_convert!(EjectAbiArgs, build);
+
+impl EjectAbiArgs {
+    pub fn run(self) -> Result<()> {
+        let config = self.try_load_config_emit_warnings()?;
+        let project = config.project()?;
+        let output = compile::suppress_compile(&project)?;
+
+        let select = selection(&self.select)?;
+        let root = &config.__root.0;
+        let sources = project.paths.sources.to_string_lossy().to_string();
+        let mut contracts = BTreeMap::<String, ExportedAbi>::new();
+        for (file, name, artifact) in output.into_artifacts_with_files() {
+            let path = Path::new(&file).strip_prefix(root).unwrap_or(Path::new(&file));
+            let selected = match &select {
+                Some(select) => select.is_match(&name) || select.is_match(path),
+                None => file.starts_with(&sources) && !file.is_sol_test(),
+            };
+            let Some(abi) = artifact.abi.filter(|_| selected) else { continue };
+
+            if let Some(existing) = contracts.get(&name) {
+                eprintln!(
+                    "{} contract `{name}` is defined in both {} and {}, skipping the latter",
+                    Paint::yellow("Warning:").bold(),
+                    existing.source.display(),
+                    path.display()
+                );
+                continue
+            }
+            contracts.insert(
+                name.clone(),
+                ExportedAbi {
+                    contract_name: name,
+                    source: path.to_path_buf(),
+                    abi,
+                    addresses: BTreeMap::new(),
+                },
+            );
+        }
+
+        let registry = DeploymentRegistry::new(root);
+        for (chain, manifest) in registry.list(self.chain.map(|chain| chain.id()))? {
+            if let Some(contract) = contracts.get_mut(&manifest.contract_name) {
+                contract.addresses.insert(chain, manifest.address);
+            }
+        }
+
+        let contracts = contracts.into_values().collect::<Vec<_>>();
+        if contracts.is_empty() {
+            eyre::bail!("no contracts matched the selection")
+        }
+
+        let (written, unchanged) = if let Some(ts) = &self.ts {
+            let written = write_if_changed(&root.join(ts), &typescript_module(&contracts)?)?;
+            (usize::from(written), usize::from(!written))
+        } else {
+            let out = root.join(&self.out);
+            fs::create_dir_all(&out)?;
+            let mut written = 0;
+            for contract in &contracts {
+                let path = out.join(format!("{}.json", contract.contract_name));
+                let content = serde_json::to_string_pretty(contract)? + "\n";
+                written += usize::from(write_if_changed(&path, &content)?);
+            }
+            (written, contracts.len() - written)
+        };
+
+        println!(
+            "Exported {} contract(s) to {}: {written} file(s) written, {unchanged} unchanged",
+            contracts.len(),
+            self.ts.as_ref().unwrap_or(&self.out).display()
+        );
+        Ok(())
+    }
+}
+
+/// The ABI and deployed addresses of a contract, as exported for frontends.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedAbi {
+    contract_name: String,
+    #[serde(skip)]
+    source: PathBuf,
+    abi: JsonAbi,
+    /// The latest deployed address of the contract on each chain, by chain id.
+    addresses: BTreeMap<u64, Address>,
+}
+
+/// Builds the set of `--select` globs, if any were passed.
+fn selection(globs: &[String]) -> Result<Option<GlobSet>> {
+    if globs.is_empty() {
+        return Ok(None)
+    }
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob).wrap_err_with(|| format!("invalid glob `{glob}`"))?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+/// Renders a TypeScript module exporting a `<Contract>Abi` and a `<Contract>Addresses` constant
+/// for every contract.
+fn typescript_module(contracts: &[ExportedAbi]) -> Result<String> {
+    let mut out = String::from("// This file was generated by `forge eject-abi`. Do not edit.\n");
+    for contract in contracts {
+        let name = &contract.contract_name;
+        let _ = write!(
+            out,
+            "\nexport const {name}Abi = {} as const;\n\nexport const {name}Addresses = {} as const;\n",
+            serde_json::to_string_pretty(&contract.abi)?,
+            serde_json::to_string_pretty(&contract.addresses)?
+        );
+    }
+    Ok(out)
+}
+
+/// Writes `content` to `path`, unless the file already has that content.
+///
+/// Returns whether the file was written.
+fn write_if_changed(path: &Path, content: &str) -> Result<bool> {
+    if path.is_file() && fs::read_to_string(path)? == content {
+        return Ok(false)
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(name: &str) -> ExportedAbi {
+        ExportedAbi {
+            contract_name: name.to_string(),
+            source: PathBuf::from(format!("src/{name}.sol")),
+            abi: serde_json::from_str(
+                r#"[{"type":"function","name":"count","inputs":[],"outputs":[{"name":"","type":"uint256","internalType":"uint256"}],"stateMutability":"view"}]"#,
+            )
+            .unwrap(),
+            addresses: BTreeMap::from([(1, Address::repeat_byte(1))]),
+        }
+    }
+
+    #[test]
+    fn can_select_contracts() {
+        assert!(selection(&[]).unwrap().is_none());
+        let select =
+            selection(&["Counter*".to_string(), "src/tokens/**".to_string()]).unwrap().unwrap();
+        assert!(select.is_match("CounterV2"));
+        assert!(select.is_match("src/tokens/Token.sol"));
+        assert!(!select.is_match("Vault"));
+        assert!(selection(&["[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn can_render_typescript_module() {
+        let ts = typescript_module(&[contract("Counter")]).unwrap();
+        assert!(ts.contains("export const CounterAbi = [\n"), "{ts}");
+        assert!(ts.contains("\"name\": \"count\""), "{ts}");
+        assert!(
+            ts.contains(&format!(
+                "export const CounterAddresses = {{\n  \"1\": {}\n}} as const;\n",
+                serde_json::to_string(&Address::repeat_byte(1)).unwrap()
+            )),
+            "{ts}"
+        );
+    }
+
+    #[test]
+    fn only_writes_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abis").join("Counter.json");
+        assert!(write_if_changed(&path, "a").unwrap());
+        assert!(!write_if_changed(&path, "a").unwrap());
+        assert!(write_if_changed(&path, "b").unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "b");
+    }
+}
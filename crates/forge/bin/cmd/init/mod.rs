@@ -1,13 +1,19 @@
 use super::install::DependencyInstallOpts;
 use clap::{Parser, ValueHint};
 use eyre::Result;
-use foundry_cli::{p_println, utils::Git};
+use foundry_cli::{
+    p_println,
+    utils::{CommandUtils, Git},
+};
 use foundry_common::fs;
 use foundry_compilers::remappings::Remapping;
-use foundry_config::Config;
+use foundry_config::{Config, SolcReq};
 use std::path::{Path, PathBuf};
 use yansi::Paint;
 
+mod template;
+use template::{BuiltinTemplate, Placeholders, DEFAULT_SOLC_VERSION};
+
 /// CLI arguments for `forge init`.
 #[derive(Debug, Clone, Parser)]
 pub struct InitArgs {
@@ -16,6 +22,12 @@ pub struct InitArgs {
     root: PathBuf,
 
     /// The template to start from.
+    ///
+    /// Either one of the built-in templates `library`, `script`, `erc20` and `proxy`, or a git
+    /// repository, as a URL or as `<owner>/<repo>` on GitHub.
+    ///
+    /// The `{{project_name}}` and `{{solc_version}}` placeholders in the template's files are
+    /// replaced with the name of the root directory and the configured solc version.
     #[clap(long, short)]
     template: Option<String>,
 
@@ -25,16 +37,22 @@ pub struct InitArgs {
     branch: Option<String>,
 
     /// Do not install dependencies from the network.
-    #[clap(long, conflicts_with = "template", visible_alias = "no-deps")]
+    ///
+    /// Not supported for git templates.
+    #[clap(long, visible_alias = "no-deps")]
     offline: bool,
 
     /// Create the project even if the specified root directory is not empty.
-    #[clap(long, conflicts_with = "template")]
+    ///
+    /// Not supported for git templates.
+    #[clap(long)]
     force: bool,
 
     /// Create a .vscode/settings.json file with Solidity settings, and generate a remappings.txt
     /// file.
-    #[clap(long, conflicts_with = "template")]
+    ///
+    /// Not supported for git templates.
+    #[clap(long)]
     vscode: bool,
 
     #[clap(flatten)]
@@ -53,10 +71,32 @@ impl InitArgs {
         let root = dunce::canonicalize(root)?;
         let git = Git::new(&root).quiet(quiet).shallow(shallow);
 
-        // if a template is provided, then this command initializes a git repo,
+        let builtin = template.as_deref().and_then(BuiltinTemplate::from_name);
+        if let Some(builtin) = builtin {
+            if branch.is_some() {
+                eyre::bail!("`--branch` can't be used with the built-in `{builtin}` template");
+            }
+        }
+        let placeholders = Placeholders {
+            project_name: root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            solc_version: match Config::load_with_root(&root).solc {
+                Some(SolcReq::Version(version)) => version.to_string(),
+                _ => DEFAULT_SOLC_VERSION.to_string(),
+            },
+        };
+
+        // if a git template is provided, then this command initializes a git repo,
         // fetches the template repo, and resets the git history to the head of the fetched
         // repo with no other history
-        if let Some(template) = template {
+        if let Some(template) = template.filter(|_| builtin.is_none()) {
+            for (set, flag) in [(offline, "--offline"), (force, "--force"), (vscode, "--vscode")] {
+                if set {
+                    eyre::bail!("`{flag}` can't be used with git templates");
+                }
+            }
             let template = if template.contains("://") {
                 template
             } else {
@@ -86,6 +126,14 @@ impl InitArgs {
                 // if not shallow, initialize and clone submodules (without fetching latest)
                 git.submodule_update(false, false, true, true, None::<PathBuf>)?;
             }
+
+            // render the placeholders of the template's own files
+            let files = git.cmd().arg("ls-files").get_stdout_lossy()?;
+            let files = files.lines().map(str::to_string);
+            if placeholders.render_files(&root, files)? && !no_commit {
+                git.add(Some("--all"))?;
+                git.commit("chore: render template placeholders")?;
+            }
         } else {
             // if target is not empty
             if root.read_dir().map_or(false, |mut i| i.next().is_some()) {
@@ -116,18 +164,22 @@ impl InitArgs {
             let script = root.join("script");
             fs::create_dir_all(&script)?;
 
-            // write the contract file
-            let contract_path = src.join("Counter.sol");
-            fs::write(contract_path, include_str!("../../assets/CounterTemplate.sol"))?;
-            // write the tests
-            let contract_path = test.join("Counter.t.sol");
-            fs::write(contract_path, include_str!("../../assets/CounterTemplate.t.sol"))?;
-            // write the script
-            let contract_path = script.join("Counter.s.sol");
-            fs::write(contract_path, include_str!("../../assets/CounterTemplate.s.sol"))?;
+            if let Some(builtin) = builtin {
+                builtin.write(&root, &placeholders)?;
+            } else {
+                // write the contract file
+                let contract_path = src.join("Counter.sol");
+                fs::write(contract_path, include_str!("../../../assets/CounterTemplate.sol"))?;
+                // write the tests
+                let contract_path = test.join("Counter.t.sol");
+                fs::write(contract_path, include_str!("../../../assets/CounterTemplate.t.sol"))?;
+                // write the script
+                let contract_path = script.join("Counter.s.sol");
+                fs::write(contract_path, include_str!("../../../assets/CounterTemplate.s.sol"))?;
+            }
             // Write the default README file
             let readme_path = root.join("README.md");
-            fs::write(readme_path, include_str!("../../assets/README.md"))?;
+            fs::write(readme_path, include_str!("../../../assets/README.md"))?;
 
             // write foundry.toml, if it doesn't exist already
             let dest = root.join(Config::FILE_NAME);
@@ -142,15 +194,18 @@ impl InitArgs {
                 init_git_repo(git, no_commit)?;
             }
 
-            // install forge-std
+            // install forge-std and the dependencies of the template
             if !offline {
+                let mut deps = Vec::new();
                 if root.join("lib/forge-std").exists() {
                     p_println!(!quiet => "\"lib/forge-std\" already exists, skipping install....");
-                    self.opts.install(&mut config, vec![])?;
                 } else {
-                    let dep = "https://github.com/foundry-rs/forge-std".parse()?;
-                    self.opts.install(&mut config, vec![dep])?;
+                    deps.push("https://github.com/foundry-rs/forge-std".parse()?);
+                }
+                for dep in builtin.map(BuiltinTemplate::dependencies).unwrap_or_default() {
+                    deps.push(dep.parse()?);
                 }
+                self.opts.install(&mut config, deps)?;
             }
 
             // init vscode settings
@@ -183,14 +238,14 @@ fn init_git_repo(git: Git<'_>, no_commit: bool) -> Result<()> {
     // .gitignore
     let gitignore = git.root.join(".gitignore");
     if !gitignore.exists() {
-        fs::write(gitignore, include_str!("../../assets/.gitignoreTemplate"))?;
+        fs::write(gitignore, include_str!("../../../assets/.gitignoreTemplate"))?;
     }
 
     // github workflow
     let workflow = git.root.join(".github/workflows/test.yml");
     if !workflow.exists() {
         fs::create_dir_all(workflow.parent().unwrap())?;
-        fs::write(workflow, include_str!("../../assets/workflowTemplate.yml"))?;
+        fs::write(workflow, include_str!("../../../assets/workflowTemplate.yml"))?;
     }
 
     // commit everything
@@ -0,0 +1,181 @@
+//! Project templates of `forge init --template`.
+
+use eyre::Result;
+use foundry_common::fs;
+use std::{fmt, path::Path};
+
+/// The solc version the built-in templates are rendered with, unless `foundry.toml` pins one.
+///
+/// The OpenZeppelin contracts used by the `erc20` and `proxy` templates require at least 0.8.20.
+pub const DEFAULT_SOLC_VERSION: &str = "0.8.20";
+
+/// A template that ships with forge, selected by name instead of a git URL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuiltinTemplate {
+    /// A library with fuzz tests.
+    Library,
+    /// A project with a script only.
+    Script,
+    /// An OpenZeppelin ERC-20 token with a test and a deployment script.
+    Erc20,
+    /// An OpenZeppelin UUPS upgradeable contract behind an ERC-1967 proxy.
+    Proxy,
+}
+
+impl BuiltinTemplate {
+    /// All built-in templates.
+    pub const ALL: [Self; 4] = [Self::Library, Self::Script, Self::Erc20, Self::Proxy];
+
+    /// Returns the built-in template with the given name, if any.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|template| template.name() == name)
+    }
+
+    /// Returns the name used to select the template.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Library => "library",
+            Self::Script => "script",
+            Self::Erc20 => "erc20",
+            Self::Proxy => "proxy",
+        }
+    }
+
+    /// Returns the files of the template, relative to the project root.
+    pub fn files(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Library => &[
+                ("src/Math.sol", include_str!("../../../assets/templates/library/Math.sol")),
+                ("test/Math.t.sol", include_str!("../../../assets/templates/library/Math.t.sol")),
+            ],
+            Self::Script => &[(
+                "script/Interact.s.sol",
+                include_str!("../../../assets/templates/script/Interact.s.sol"),
+            )],
+            Self::Erc20 => &[
+                ("src/Token.sol", include_str!("../../../assets/templates/erc20/Token.sol")),
+                ("test/Token.t.sol", include_str!("../../../assets/templates/erc20/Token.t.sol")),
+                ("script/Token.s.sol", include_str!("../../../assets/templates/erc20/Token.s.sol")),
+            ],
+            Self::Proxy => &[
+                ("src/Counter.sol", include_str!("../../../assets/templates/proxy/Counter.sol")),
+                (
+                    "test/Counter.t.sol",
+                    include_str!("../../../assets/templates/proxy/Counter.t.sol"),
+                ),
+                (
+                    "script/Counter.s.sol",
+                    include_str!("../../../assets/templates/proxy/Counter.s.sol"),
+                ),
+            ],
+        }
+    }
+
+    /// Returns the dependencies the template needs in addition to forge-std.
+    pub fn dependencies(self) -> &'static [&'static str] {
+        match self {
+            Self::Library | Self::Script => &[],
+            Self::Erc20 => &["OpenZeppelin/openzeppelin-contracts@v5.0.1"],
+            Self::Proxy => &[
+                "OpenZeppelin/openzeppelin-contracts@v5.0.1",
+                "OpenZeppelin/openzeppelin-contracts-upgradeable@v5.0.1",
+            ],
+        }
+    }
+
+    /// Returns the remappings the template's imports rely on.
+    pub fn remappings(self) -> &'static [&'static str] {
+        match self {
+            Self::Library | Self::Script => &[],
+            Self::Erc20 => &["@openzeppelin/contracts/=lib/openzeppelin-contracts/contracts/"],
+            Self::Proxy => &[
+                "@openzeppelin/contracts/=lib/openzeppelin-contracts/contracts/",
+                "@openzeppelin/contracts-upgradeable/=lib/openzeppelin-contracts-upgradeable/contracts/",
+            ],
+        }
+    }
+
+    /// Writes the rendered files and remappings of the template to `root`.
+    pub fn write(self, root: &Path, placeholders: &Placeholders) -> Result<()> {
+        for (path, content) in self.files() {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, placeholders.render(content))?;
+        }
+        let remappings = root.join("remappings.txt");
+        if !self.remappings().is_empty() && !remappings.exists() {
+            fs::write(remappings, self.remappings().join("\n") + "\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BuiltinTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The values substituted for the `{{project_name}}` and `{{solc_version}}` placeholders of a
+/// template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placeholders {
+    pub project_name: String,
+    pub solc_version: String,
+}
+
+impl Placeholders {
+    /// Returns `content` with all placeholders replaced.
+    pub fn render(&self, content: &str) -> String {
+        content
+            .replace("{{project_name}}", &self.project_name)
+            .replace("{{solc_version}}", &self.solc_version)
+    }
+
+    /// Renders the placeholders of `files`, relative to `root`, in place.
+    ///
+    /// Returns true if any file changed.
+    pub fn render_files(
+        &self,
+        root: &Path,
+        files: impl IntoIterator<Item = String>,
+    ) -> Result<bool> {
+        let mut changed = false;
+        for file in files {
+            let path = root.join(file);
+            // skip binary files and files that were deleted in the working tree
+            let Ok(content) = std::fs::read_to_string(&path) else { continue };
+            let rendered = self.render(&content);
+            if rendered != content {
+                fs::write(path, rendered)?;
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_render_builtin_templates() {
+        let placeholders = Placeholders {
+            project_name: "MyToken".to_string(),
+            solc_version: "0.8.23".to_string(),
+        };
+        for template in BuiltinTemplate::ALL {
+            assert_eq!(BuiltinTemplate::from_name(template.name()), Some(template));
+            for (path, content) in template.files() {
+                let rendered = placeholders.render(content);
+                assert!(!rendered.contains("{{"), "unrendered placeholder in {template}/{path}");
+                assert!(rendered.contains("pragma solidity ^0.8.23;"), "{template}/{path}");
+            }
+        }
+        let token = placeholders.render(BuiltinTemplate::Erc20.files()[0].1);
+        assert!(token.contains(r#"ERC20("MyToken", "TKN")"#));
+
+        assert_eq!(BuiltinTemplate::from_name("foundry-rs/forge-template"), None);
+    }
+}
@@ -192,6 +192,13 @@ impl InspectArgs {
                 }
                 println!("{}", serde_json::to_string_pretty(&out)?);
             }
+            ContractArtifactField::Size => {
+                let size = serde_json::json!({
+                    "size": compile::deployed_contract_size(artifact).unwrap_or_default(),
+                    "init_size": compile::initcode_size(artifact).unwrap_or_default(),
+                });
+                println!("{}", serde_json::to_string_pretty(&size)?);
+            }
         };
 
         Ok(())
@@ -344,6 +351,7 @@ impl_value_enum! {
         Ewasm             => "ewasm" | "e-wasm",
         Errors            => "errors" | "er",
         Events            => "events" | "ev",
+        Size              => "size",
     }
 }
 
@@ -368,6 +376,9 @@ impl From<ContractArtifactField> for ContractOutputSelection {
             Caf::Ewasm => Self::Ewasm(EwasmOutputSelection::All),
             Caf::Errors => Self::Abi,
             Caf::Events => Self::Abi,
+            Caf::Size => Self::Evm(EvmOutputSelection::DeployedByteCode(
+                DeployedBytecodeOutputSelection::All,
+            )),
         }
     }
 }
@@ -381,7 +392,7 @@ impl PartialEq<ContractOutputSelection> for ContractArtifactField {
             (Self::Abi | Self::Events, Cos::Abi) |
                 (Self::Errors, Cos::Abi) |
                 (Self::Bytecode, Cos::Evm(Eos::ByteCode(_))) |
-                (Self::DeployedBytecode, Cos::Evm(Eos::DeployedByteCode(_))) |
+                (Self::DeployedBytecode | Self::Size, Cos::Evm(Eos::DeployedByteCode(_))) |
                 (Self::Assembly | Self::AssemblyOptimized, Cos::Evm(Eos::Assembly)) |
                 (Self::MethodIdentifiers, Cos::Evm(Eos::MethodIdentifiers)) |
                 (Self::GasEstimates, Cos::Evm(Eos::GasEstimates)) |
@@ -405,7 +416,7 @@ impl fmt::Display for ContractArtifactField {
 impl ContractArtifactField {
     /// Returns true if this field is generated by default.
     pub const fn is_default(&self) -> bool {
-        matches!(self, Self::Bytecode | Self::DeployedBytecode)
+        matches!(self, Self::Bytecode | Self::DeployedBytecode | Self::Size)
     }
 }
 
@@ -0,0 +1,665 @@
+use foundry_config::LintSeverity;
+use solang_parser::pt::{
+    CatchClause, ContractDefinition, ContractPart, ContractTy, Expression, FunctionAttribute,
+    FunctionDefinition, FunctionTy, Identifier, Loc, Parameter, ParameterList, SourceUnit,
+    SourceUnitPart, Statement, StorageLocation, StringLiteral, VariableAttribute,
+    VariableDefinition,
+};
+use std::{collections::HashSet, fmt, ops::Range};
+
+/// The built-in lints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// The return value of a low-level call or of an ERC20-style transfer is discarded.
+    UncheckedCall,
+    /// A local variable or parameter shadows a state variable or another local variable.
+    Shadowing,
+    /// A state variable or function doesn't declare its visibility.
+    MissingVisibility,
+    /// The `pragma solidity` accepts more than one compiler version.
+    FloatingPragma,
+    /// A state variable, or storage through a local storage pointer, is written inside a loop,
+    /// paying for an `SSTORE` on every iteration.
+    StorageWriteInLoop,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 5] = [
+        Lint::UncheckedCall,
+        Lint::Shadowing,
+        Lint::MissingVisibility,
+        Lint::FloatingPragma,
+        Lint::StorageWriteInLoop,
+    ];
+
+    /// The id of the lint, used in the output and in `foundry.toml`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Lint::UncheckedCall => "unchecked-call",
+            Lint::Shadowing => "shadowing",
+            Lint::MissingVisibility => "missing-visibility",
+            Lint::FloatingPragma => "floating-pragma",
+            Lint::StorageWriteInLoop => "storage-write-in-loop",
+        }
+    }
+
+    /// The severity of the lint if none is configured.
+    pub fn default_severity(&self) -> LintSeverity {
+        match self {
+            Lint::UncheckedCall | Lint::Shadowing | Lint::MissingVisibility => {
+                LintSeverity::Warning
+            }
+            Lint::FloatingPragma | Lint::StorageWriteInLoop => LintSeverity::Info,
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A mechanical rewrite that resolves a [`Finding`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+    /// The byte range of the source to replace.
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+/// An issue found by a lint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub lint: Lint,
+    /// The byte range of the source the finding is anchored to.
+    pub range: Range<usize>,
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+/// Runs all lints on the parsed source unit.
+pub fn lint_source_unit(src: &str, source_unit: &SourceUnit) -> Vec<Finding> {
+    let mut visitor = LintVisitor { src, ..Default::default() };
+    visitor.visit_source_unit(source_unit);
+    visitor.findings.sort_by_key(|finding| finding.range.start);
+    visitor.findings
+}
+
+/// Applies the fixes to the source, skipping fixes that overlap a previous one.
+///
+/// Returns the fixed source and the number of applied fixes.
+pub fn apply_fixes<'a>(src: &str, fixes: impl IntoIterator<Item = &'a Fix>) -> (String, usize) {
+    let mut fixes = fixes.into_iter().collect::<Vec<_>>();
+    fixes.sort_by_key(|fix| fix.range.start);
+
+    let mut out = String::with_capacity(src.len());
+    let mut pos = 0;
+    let mut applied = 0;
+    for fix in fixes {
+        if fix.range.start < pos {
+            continue
+        }
+        out.push_str(&src[pos..fix.range.start]);
+        out.push_str(&fix.replacement);
+        pos = fix.range.end;
+        applied += 1;
+    }
+    out.push_str(&src[pos..]);
+    (out, applied)
+}
+
+/// Walks the parse tree, tracking the declarations in scope.
+#[derive(Default)]
+struct LintVisitor<'a> {
+    src: &'a str,
+    findings: Vec<Finding>,
+    /// The state variables of the contract being visited.
+    state_vars: HashSet<String>,
+    /// The local declarations of the function being visited, innermost scope last.
+    scopes: Vec<Vec<String>>,
+    /// The locals and parameters of the function being visited that are storage pointers.
+    storage_pointers: HashSet<String>,
+    /// The number of loops around the code being visited.
+    loop_depth: usize,
+}
+
+impl<'a> LintVisitor<'a> {
+    fn report(&mut self, lint: Lint, loc: Loc, message: String, fix: Option<Fix>) {
+        self.findings.push(Finding { lint, range: loc.start()..loc.end(), message, fix });
+    }
+
+    fn visit_source_unit(&mut self, source_unit: &SourceUnit) {
+        for part in &source_unit.0 {
+            match part {
+                SourceUnitPart::PragmaDirective(loc, Some(ident), Some(version))
+                    if ident.name == "solidity" =>
+                {
+                    self.check_pragma(*loc, version)
+                }
+                SourceUnitPart::ContractDefinition(contract) => self.visit_contract(contract),
+                SourceUnitPart::FunctionDefinition(function) => self.visit_function(function),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_pragma(&mut self, loc: Loc, version: &StringLiteral) {
+        let requirement = version.string.trim();
+        if is_exact_version(requirement.trim_start_matches('=')) {
+            return
+        }
+
+        // a single lower bound can be pinned to that version
+        let fix = ["^", "~", ">="]
+            .iter()
+            .find_map(|op| requirement.strip_prefix(op))
+            .map(str::trim)
+            .filter(|version| is_exact_version(version))
+            .and_then(|version| {
+                let offset = self.src.get(loc.start()..loc.end())?.find(requirement)?;
+                let start = loc.start() + offset;
+                Some(Fix {
+                    range: start..start + requirement.len(),
+                    replacement: version.to_string(),
+                })
+            });
+        self.report(
+            Lint::FloatingPragma,
+            loc,
+            format!("`pragma solidity {requirement}` is not pinned to a single compiler version"),
+            fix,
+        );
+    }
+
+    fn visit_contract(&mut self, contract: &ContractDefinition) {
+        let is_interface = matches!(contract.ty, ContractTy::Interface(_));
+        self.state_vars = contract
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                ContractPart::VariableDefinition(var) => var.name.as_ref(),
+                _ => None,
+            })
+            .map(|name| name.name.clone())
+            .collect();
+
+        for part in &contract.parts {
+            match part {
+                ContractPart::VariableDefinition(var) => self.check_state_var(var),
+                ContractPart::FunctionDefinition(function) => {
+                    self.check_function_visibility(function, is_interface);
+                    self.visit_function(function);
+                }
+                _ => {}
+            }
+        }
+        self.state_vars.clear();
+    }
+
+    fn check_state_var(&mut self, var: &VariableDefinition) {
+        let Some(name) = &var.name else { return };
+        if var.attrs.iter().any(|attr| matches!(attr, VariableAttribute::Visibility(_))) {
+            return
+        }
+        let start = name.loc.start();
+        self.report(
+            Lint::MissingVisibility,
+            var.loc,
+            format!("state variable `{}` has no explicit visibility", name.name),
+            Some(Fix { range: start..start, replacement: "internal ".to_string() }),
+        );
+    }
+
+    fn check_function_visibility(&mut self, function: &FunctionDefinition, is_interface: bool) {
+        if !matches!(function.ty, FunctionTy::Function) ||
+            function
+                .attributes
+                .iter()
+                .any(|attr| matches!(attr, FunctionAttribute::Visibility(_)))
+        {
+            return
+        }
+        let Some(name) = &function.name else { return };
+        // functions had the visibility of their contract kind before it became mandatory
+        let visibility = if is_interface { "external" } else { "public" };
+        let fix = params_end(self.src, name.loc.end())
+            .map(|end| Fix { range: end..end, replacement: format!(" {visibility}") });
+        self.report(
+            Lint::MissingVisibility,
+            function.loc,
+            format!("function `{}` has no explicit visibility", name.name),
+            fix,
+        );
+    }
+
+    fn visit_function(&mut self, function: &FunctionDefinition) {
+        self.storage_pointers.clear();
+        self.scopes.push(Vec::new());
+        self.declare_params(&function.params);
+        self.declare_params(&function.returns);
+        if let Some(body) = &function.body {
+            self.visit_statement(body);
+        }
+        self.scopes.pop();
+    }
+
+    fn declare_params(&mut self, params: &ParameterList) {
+        for (_, param) in params {
+            if let Some(Parameter { name: Some(name), storage, .. }) = param {
+                self.declare(name);
+                self.set_storage_pointer(name, storage.as_ref());
+            }
+        }
+    }
+
+    /// Records whether a local declared with the `storage` location is a storage pointer.
+    fn set_storage_pointer(&mut self, ident: &Identifier, storage: Option<&StorageLocation>) {
+        if matches!(storage, Some(StorageLocation::Storage(_))) {
+            self.storage_pointers.insert(ident.name.clone());
+        } else {
+            self.storage_pointers.remove(&ident.name);
+        }
+    }
+
+    /// Declares a local variable in the innermost scope, reporting it if it shadows another
+    /// declaration.
+    fn declare(&mut self, ident: &Identifier) {
+        let name = &ident.name;
+        if self.scopes.iter().flatten().any(|local| local == name) {
+            self.report(
+                Lint::Shadowing,
+                ident.loc,
+                format!("`{name}` shadows a local variable declared in an outer scope"),
+                None,
+            );
+        } else if self.state_vars.contains(name) {
+            self.report(
+                Lint::Shadowing,
+                ident.loc,
+                format!("`{name}` shadows the state variable `{name}`"),
+                None,
+            );
+        }
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(name.clone());
+        }
+    }
+
+    fn is_state_var(&self, name: &str) -> bool {
+        self.state_vars.contains(name) && !self.scopes.iter().flatten().any(|local| local == name)
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Block { statements, .. } => {
+                self.scopes.push(Vec::new());
+                statements.iter().for_each(|stmt| self.visit_statement(stmt));
+                self.scopes.pop();
+            }
+            Statement::VariableDefinition(_, decl, init) => {
+                if let Some(init) = init {
+                    self.visit_expr(init);
+                }
+                if let Some(name) = &decl.name {
+                    self.declare(name);
+                    self.set_storage_pointer(name, decl.storage.as_ref());
+                }
+            }
+            Statement::If(_, cond, if_branch, else_branch) => {
+                self.visit_expr(cond);
+                self.visit_statement(if_branch);
+                if let Some(else_branch) = else_branch {
+                    self.visit_statement(else_branch);
+                }
+            }
+            Statement::While(_, cond, body) | Statement::DoWhile(_, body, cond) => {
+                self.loop_depth += 1;
+                self.visit_expr(cond);
+                self.visit_statement(body);
+                self.loop_depth -= 1;
+            }
+            Statement::For(_, init, cond, update, body) => {
+                self.scopes.push(Vec::new());
+                if let Some(init) = init {
+                    self.visit_statement(init);
+                }
+                self.loop_depth += 1;
+                if let Some(cond) = cond {
+                    self.visit_expr(cond);
+                }
+                if let Some(update) = update {
+                    self.visit_expr(update);
+                }
+                if let Some(body) = body {
+                    self.visit_statement(body);
+                }
+                self.loop_depth -= 1;
+                self.scopes.pop();
+            }
+            Statement::Expression(_, expr) => {
+                self.check_unchecked_call(expr);
+                self.visit_expr(expr);
+            }
+            Statement::Return(_, Some(expr)) | Statement::Emit(_, expr) => self.visit_expr(expr),
+            Statement::Revert(_, _, args) => args.iter().for_each(|arg| self.visit_expr(arg)),
+            Statement::RevertNamedArgs(_, _, args) => {
+                args.iter().for_each(|arg| self.visit_expr(&arg.expr))
+            }
+            Statement::Try(_, expr, returns, clauses) => {
+                self.visit_expr(expr);
+                if let Some((params, body)) = returns {
+                    self.scopes.push(Vec::new());
+                    self.declare_params(params);
+                    self.visit_statement(body);
+                    self.scopes.pop();
+                }
+                for clause in clauses {
+                    self.scopes.push(Vec::new());
+                    let body = match clause {
+                        CatchClause::Simple(_, param, body) => {
+                            if let Some(Parameter { name: Some(name), .. }) = param {
+                                self.declare(name);
+                            }
+                            body
+                        }
+                        CatchClause::Named(_, _, param, body) => {
+                            if let Some(name) = &param.name {
+                                self.declare(name);
+                            }
+                            body
+                        }
+                    };
+                    self.visit_statement(body);
+                    self.scopes.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports calls whose boolean result is the only way to detect their failure, if the
+    /// result is discarded.
+    fn check_unchecked_call(&mut self, expr: &Expression) {
+        let Expression::FunctionCall(loc, callee, args) = expr else { return };
+        let callee = match callee.as_ref() {
+            Expression::FunctionCallBlock(_, callee, _) => callee.as_ref(),
+            callee => callee,
+        };
+        let Expression::MemberAccess(_, _, member) = callee else { return };
+        let message = match member.name.as_str() {
+            "call" | "delegatecall" | "staticcall" | "send" => {
+                format!("the success of the low-level `{}` is not checked", member.name)
+            }
+            // `address.transfer` reverts on failure and takes a single argument
+            "transfer" | "transferFrom" | "approve" if args.len() >= 2 => {
+                format!("the return value of `{}` is not checked", member.name)
+            }
+            _ => return,
+        };
+        self.report(Lint::UncheckedCall, *loc, message, None);
+    }
+
+    fn check_storage_write(&mut self, loc: Loc, target: &Expression) {
+        if self.loop_depth == 0 {
+            return
+        }
+        let mut target = target;
+        // whether the write goes through an index or a member, rather than to the variable itself
+        let mut indirect = false;
+        loop {
+            match target {
+                Expression::ArraySubscript(_, base, _) | Expression::MemberAccess(_, base, _) => {
+                    indirect = true;
+                    target = base
+                }
+                Expression::Parenthesis(_, base) => target = base,
+                _ => break,
+            }
+        }
+        let Expression::Variable(ident) = target else { return };
+        let message = if self.is_state_var(&ident.name) {
+            format!(
+                "state variable `{}` is written inside a loop, consider caching it in memory",
+                ident.name
+            )
+        } else if indirect && self.storage_pointers.contains(&ident.name) {
+            // assigning the pointer itself doesn't write to storage
+            format!(
+                "storage is written through `{}` inside a loop, consider caching it in memory",
+                ident.name
+            )
+        } else {
+            return
+        };
+        self.report(Lint::StorageWriteInLoop, loc, message, None);
+    }
+
+    fn visit_expr(&mut self, expr: &Expression) {
+        match expr {
+            Expression::PreIncrement(loc, target) |
+            Expression::PostIncrement(loc, target) |
+            Expression::PreDecrement(loc, target) |
+            Expression::PostDecrement(loc, target) => {
+                self.check_storage_write(*loc, target);
+                self.visit_expr(target);
+            }
+            Expression::Assign(loc, target, value) |
+            Expression::AssignOr(loc, target, value) |
+            Expression::AssignAnd(loc, target, value) |
+            Expression::AssignXor(loc, target, value) |
+            Expression::AssignShiftLeft(loc, target, value) |
+            Expression::AssignShiftRight(loc, target, value) |
+            Expression::AssignAdd(loc, target, value) |
+            Expression::AssignSubtract(loc, target, value) |
+            Expression::AssignMultiply(loc, target, value) |
+            Expression::AssignDivide(loc, target, value) |
+            Expression::AssignModulo(loc, target, value) => {
+                self.check_storage_write(*loc, target);
+                self.visit_expr(target);
+                self.visit_expr(value);
+            }
+            Expression::FunctionCall(_, callee, args) => {
+                self.visit_expr(callee);
+                args.iter().for_each(|arg| self.visit_expr(arg));
+            }
+            Expression::NamedFunctionCall(_, callee, args) => {
+                self.visit_expr(callee);
+                args.iter().for_each(|arg| self.visit_expr(&arg.expr));
+            }
+            Expression::FunctionCallBlock(_, callee, block) => {
+                self.visit_expr(callee);
+                if let Statement::Args(_, args) = block.as_ref() {
+                    args.iter().for_each(|arg| self.visit_expr(&arg.expr));
+                }
+            }
+            Expression::ArraySubscript(_, base, index) => {
+                self.visit_expr(base);
+                if let Some(index) = index {
+                    self.visit_expr(index);
+                }
+            }
+            Expression::ArraySlice(_, base, start, end) => {
+                self.visit_expr(base);
+                start.iter().chain(end).for_each(|expr| self.visit_expr(expr));
+            }
+            Expression::MemberAccess(_, expr, _) |
+            Expression::Parenthesis(_, expr) |
+            Expression::New(_, expr) => self.visit_expr(expr),
+            Expression::ConditionalOperator(_, cond, first, second) => {
+                self.visit_expr(cond);
+                self.visit_expr(first);
+                self.visit_expr(second);
+            }
+            Expression::ArrayLiteral(_, exprs) => {
+                exprs.iter().for_each(|expr| self.visit_expr(expr))
+            }
+            expr => {
+                let (left, right) = expr.components();
+                left.into_iter().chain(right).for_each(|expr| self.visit_expr(expr));
+            }
+        }
+    }
+}
+
+/// Returns true if the version is a plain `major.minor.patch` version.
+fn is_exact_version(version: &str) -> bool {
+    !version.is_empty() && version.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// Returns the offset after the closing parenthesis of the parameter list that starts after
+/// `from`.
+fn params_end(src: &str, from: usize) -> Option<usize> {
+    let open = from + src.get(from..)?.find('(')?;
+    let mut depth = 0usize;
+    for (offset, c) in src[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + offset + 1)
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(src: &str) -> Vec<Finding> {
+        let (source_unit, _) = solang_parser::parse(src, 0).unwrap();
+        lint_source_unit(src, &source_unit)
+    }
+
+    fn ids(findings: &[Finding]) -> Vec<&'static str> {
+        findings.iter().map(|finding| finding.lint.id()).collect()
+    }
+
+    #[test]
+    fn can_find_unchecked_calls() {
+        let src = r#"
+        pragma solidity 0.8.23;
+        contract A {
+            function f(address to, IERC20 token) external {
+                to.call{value: 1}("");
+                payable(to).send(1);
+                payable(to).transfer(1);
+                token.transfer(to, 1);
+                (bool ok,) = to.call("");
+                require(ok);
+                require(token.transfer(to, 1));
+            }
+        }
+        "#;
+        let findings = lint(src);
+        assert_eq!(ids(&findings), ["unchecked-call"; 3]);
+        assert_eq!(&src[findings[0].range.clone()], r#"to.call{value: 1}("")"#);
+        assert!(findings[2].message.contains("`transfer`"), "{}", findings[2].message);
+    }
+
+    #[test]
+    fn can_find_shadowing() {
+        let src = r"
+        pragma solidity 0.8.23;
+        contract A {
+            uint256 public owner;
+            function f(uint256 owner) external {
+                uint256 x;
+                {
+                    uint256 x = owner;
+                }
+            }
+            function g() external returns (uint256 y) {
+                for (uint256 i; i < 1; i++) {}
+                for (uint256 i; i < 1; i++) {}
+            }
+        }
+        ";
+        let findings = lint(src);
+        assert_eq!(ids(&findings), ["shadowing"; 2]);
+        assert_eq!(findings[0].message, "`owner` shadows the state variable `owner`");
+        assert_eq!(findings[1].message, "`x` shadows a local variable declared in an outer scope");
+    }
+
+    #[test]
+    fn can_fix_missing_visibility_and_floating_pragma() {
+        let src = r"
+        pragma solidity ^0.8.13;
+        contract A {
+            uint256 constant X = 1;
+            mapping(address => uint256) balances;
+            uint256 private y;
+        }
+        ";
+        let findings = lint(src);
+        assert_eq!(ids(&findings), ["floating-pragma", "missing-visibility", "missing-visibility"]);
+
+        let (fixed, applied) =
+            apply_fixes(src, findings.iter().filter_map(|finding| finding.fix.as_ref()));
+        assert_eq!(applied, 3);
+        assert!(fixed.contains("pragma solidity 0.8.13;"), "{fixed}");
+        assert!(fixed.contains("uint256 constant internal X = 1;"), "{fixed}");
+        assert!(fixed.contains("mapping(address => uint256) internal balances;"), "{fixed}");
+        assert!(lint(&fixed).is_empty());
+
+        let range = lint("pragma solidity >=0.8.0 <0.9.0;");
+        assert_eq!(ids(&range), ["floating-pragma"]);
+        assert!(range[0].fix.is_none());
+    }
+
+    #[test]
+    fn can_find_storage_writes_in_loops() {
+        let src = r"
+        pragma solidity 0.8.23;
+        contract A {
+            uint256 public total;
+            uint256[] public values;
+            function f(uint256[] memory xs) external {
+                for (uint256 i; i < xs.length; i++) {
+                    total += xs[i];
+                    values[i] = xs[i];
+                }
+                uint256 sum;
+                while (sum < 10) {
+                    sum++;
+                }
+                total = sum;
+            }
+        }
+        ";
+        let findings = lint(src);
+        assert_eq!(ids(&findings), ["storage-write-in-loop"; 2]);
+        assert!(findings[0].message.contains("`total`"));
+        assert!(findings[1].message.contains("`values`"));
+    }
+
+    #[test]
+    fn can_find_storage_writes_through_pointers_in_loops() {
+        let src = r"
+        pragma solidity 0.8.23;
+        contract A {
+            struct Position { uint256 amount; }
+            mapping(address => Position) internal positions;
+            function f(address[] memory owners, Position storage extra) internal {
+                for (uint256 i; i < owners.length; i++) {
+                    Position storage position = positions[owners[i]];
+                    position.amount += 1;
+                    extra.amount = i;
+                    Position memory copy = position;
+                    copy.amount = 0;
+                    position = extra;
+                }
+            }
+        }
+        ";
+        let findings = lint(src);
+        assert_eq!(ids(&findings), ["storage-write-in-loop"; 2]);
+        assert!(findings[0].message.contains("`position`"), "{}", findings[0].message);
+        assert!(findings[1].message.contains("`extra`"), "{}", findings[1].message);
+    }
+}
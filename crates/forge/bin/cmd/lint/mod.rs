@@ -0,0 +1,171 @@
+use clap::{Parser, ValueHint};
+use eyre::{Result, WrapErr};
+use forge_fmt::print_diagnostics_report;
+use foundry_cli::utils::{FoundryPathExt, LoadConfig};
+use foundry_common::{diagnostics::Diagnostic, fs, glob::expand_globs, term::cli_warn};
+use foundry_config::{impl_figment_convert_basic, Config, LintSeverity};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+mod lints;
+use lints::{apply_fixes, lint_source_unit, Finding, Lint};
+
+/// CLI arguments for `forge lint`.
+#[derive(Debug, Clone, Parser)]
+pub struct LintArgs {
+    /// Paths to files or directories to lint.
+    #[clap(value_hint = ValueHint::FilePath, value_name = "PATH", num_args(1..))]
+    paths: Vec<PathBuf>,
+
+    /// The project's root path.
+    ///
+    /// By default root of the Git repository, if in one,
+    /// or the current working directory.
+    #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    root: Option<PathBuf>,
+
+    /// Apply the mechanical fixes of the findings, such as adding missing visibilities and pinning
+    /// floating pragmas, and only report the remaining findings.
+    #[clap(long)]
+    fix: bool,
+}
+
+impl_figment_convert_basic!(LintArgs);
+
+/// The findings of a single file.
+struct FileFindings {
+    file: PathBuf,
+    content: String,
+    findings: Vec<(Finding, LintSeverity)>,
+    fixed: usize,
+}
+
+// === impl LintArgs ===
+
+impl LintArgs {
+    pub fn run(self) -> Result<()> {
+        let config = self.try_load_config_emit_warnings()?;
+
+        for id in config.lint.severity.keys() {
+            if !Lint::ALL.iter().any(|lint| lint.id() == id) {
+                cli_warn!("Unknown lint `{id}` in the `[lint]` config");
+            }
+        }
+
+        let sources = self.sources(&config)?;
+        let results = sources
+            .par_iter()
+            .map(|file| self.lint_file(&config, file))
+            .collect::<Result<Vec<_>>>()?;
+
+        let (mut errors, mut warnings, mut fixed) = (0, 0, 0);
+        for result in &results {
+            let file = result.file.strip_prefix(&config.__root.0).unwrap_or(&result.file);
+            for (finding, severity) in &result.findings {
+                match severity {
+                    LintSeverity::Error => errors += 1,
+                    LintSeverity::Warning => warnings += 1,
+                    _ => {}
+                }
+                let diagnostic = Diagnostic::from_source(
+                    severity.as_str(),
+                    Some(finding.lint.id().to_string()),
+                    &finding.message,
+                    file,
+                    &result.content,
+                    finding.range.start,
+                    finding.range.end,
+                );
+                println!("{diagnostic}");
+            }
+            fixed += result.fixed;
+        }
+
+        if fixed > 0 {
+            println!("Fixed {fixed} finding{}", if fixed == 1 { "" } else { "s" });
+        }
+        if errors > 0 {
+            eyre::bail!(
+                "Linting failed with {errors} error{} and {warnings} warning{}",
+                if errors == 1 { "" } else { "s" },
+                if warnings == 1 { "" } else { "s" }
+            )
+        }
+        Ok(())
+    }
+
+    /// Returns the Solidity files to lint, without the ignored ones.
+    fn sources(&self, config: &Config) -> Result<Vec<PathBuf>> {
+        let ignored = expand_globs(&config.__root.0, config.lint.ignore.iter())?
+            .iter()
+            .flat_map(fs::canonicalize_path)
+            .collect::<Vec<_>>();
+        let is_ignored =
+            |path: &Path| fs::canonicalize_path(path).map_or(false, |path| ignored.contains(&path));
+
+        let mut sources = Vec::new();
+        if self.paths.is_empty() {
+            sources.extend(config.project_paths().input_files_iter());
+        } else {
+            for path in &self.paths {
+                if path.is_dir() {
+                    sources.extend(foundry_compilers::utils::source_files_iter(path));
+                } else if path.is_sol() {
+                    sources.push(path.clone());
+                } else {
+                    warn!("Cannot process path {}", path.display());
+                }
+            }
+        }
+        sources.retain(|path| !is_ignored(path));
+        Ok(sources)
+    }
+
+    fn lint_file(&self, config: &Config, file: &Path) -> Result<FileFindings> {
+        let mut content = fs::read_to_string(file)?;
+        let mut findings = lint_source(config, &content, file)?;
+
+        let mut fixed = 0;
+        if self.fix && findings.iter().any(|(finding, _)| finding.fix.is_some()) {
+            let fixes = findings.iter().filter_map(|(finding, _)| finding.fix.as_ref());
+            let (fixed_content, applied) = apply_fixes(&content, fixes);
+            solang_parser::parse(&fixed_content, 0).map_err(|diags| {
+                eyre::eyre!(
+                    "Failed to construct valid Solidity code for {}. Leaving source unchanged.\n\
+                     Debug info: {diags:?}",
+                    file.display()
+                )
+            })?;
+            fs::write(file, &fixed_content).wrap_err("Failed to write the fixed source")?;
+
+            // lint the fixed source again, so the remaining findings point to the new locations
+            findings = lint_source(config, &fixed_content, file)?;
+            content = fixed_content;
+            fixed = applied;
+        }
+
+        Ok(FileFindings { file: file.to_path_buf(), content, findings, fixed })
+    }
+}
+
+/// Lints the source, returning the findings of the lints that aren't turned off.
+fn lint_source(
+    config: &Config,
+    content: &str,
+    file: &Path,
+) -> Result<Vec<(Finding, LintSeverity)>> {
+    let (source_unit, _) = solang_parser::parse(content, 0).map_err(|diagnostics| {
+        let _ = print_diagnostics_report(content, Some(file), diagnostics);
+        eyre::eyre!("Failed to parse Solidity code for {}", file.display())
+    })?;
+
+    Ok(lint_source_unit(content, &source_unit)
+        .into_iter()
+        .map(|finding| {
+            let severity =
+                config.lint.severity_of(finding.lint.id(), finding.lint.default_severity());
+            (finding, severity)
+        })
+        .filter(|(_, severity)| *severity != LintSeverity::Off)
+        .collect())
+}
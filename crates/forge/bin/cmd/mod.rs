@@ -39,6 +39,7 @@
 //! let config: Config = From::from(&args);
 //! ```
 
+pub mod artifacts;
 pub mod bind;
 pub mod build;
 pub mod cache;
@@ -46,7 +47,9 @@ pub mod config;
 pub mod coverage;
 pub mod create;
 pub mod debug;
+pub mod deployments;
 pub mod doc;
+pub mod eject_abi;
 pub mod flatten;
 pub mod fmt;
 pub mod geiger;
@@ -54,11 +57,13 @@ pub mod generate;
 pub mod init;
 pub mod inspect;
 pub mod install;
+pub mod lint;
 pub mod remappings;
 pub mod remove;
 pub mod retry;
 pub mod script;
 pub mod selectors;
+pub mod size;
 pub mod snapshot;
 pub mod test;
 pub mod tree;
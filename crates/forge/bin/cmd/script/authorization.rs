@@ -0,0 +1,254 @@
+//! EIP-7702 authorizations, used by `forge script --authorize` to delegate EOAs to contract code.
+//!
+//! ethers has no support for EIP-7702 transactions, so the authorizations and the type 4
+//! transactions carrying them are encoded and signed here.
+
+use alloy_primitives::Address;
+use ethers_core::{
+    types::{Eip1559TransactionRequest, NameOrAddress, Signature, H256, U64},
+    utils::{keccak256, rlp::RlpStream},
+};
+use eyre::{bail, Result, WrapErr};
+use foundry_cli::opts::WalletSigner;
+use foundry_common::types::ToEthers;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// The EIP-2718 type of EIP-7702 transactions.
+pub const SET_CODE_TX_TYPE: u8 = 0x04;
+
+/// The magic byte prepended to an encoded authorization before hashing it.
+pub const AUTHORIZATION_MAGIC: u8 = 0x05;
+
+/// The intrinsic gas charged for every authorization of a transaction.
+pub const PER_AUTHORIZATION_GAS: u64 = 25_000;
+
+/// A `--authorize <AUTHORITY>=<DELEGATE>` argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuthorizeArg {
+    /// The EOA whose code is delegated.
+    pub authority: Address,
+    /// The contract the EOA delegates to.
+    pub delegate: Address,
+}
+
+impl FromStr for AuthorizeArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (authority, delegate) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected <AUTHORITY>=<DELEGATE>, got `{s}`"))?;
+        let parse = |addr: &str| {
+            Address::from_str(addr.trim()).map_err(|err| format!("invalid address `{addr}`: {err}"))
+        };
+        Ok(Self { authority: parse(authority)?, delegate: parse(delegate)? })
+    }
+}
+
+/// An unsigned EIP-7702 authorization, as recorded in the script sequence by the simulation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorization {
+    /// The EOA that signs the authorization.
+    pub authority: Address,
+    /// The chain the authorization is valid on, or 0 for all chains.
+    pub chain_id: u64,
+    /// The contract to delegate to.
+    pub address: Address,
+    /// The nonce of the authority at the time the authorization is applied.
+    pub nonce: u64,
+}
+
+impl Authorization {
+    /// Returns the hash the authority signs: `keccak256(0x05 || rlp([chain_id, address, nonce]))`.
+    pub fn signature_hash(&self) -> H256 {
+        let mut rlp = RlpStream::new_list(3);
+        self.rlp_append_fields(&mut rlp);
+        let mut buf = vec![AUTHORIZATION_MAGIC];
+        buf.extend_from_slice(&rlp.out());
+        keccak256(buf).into()
+    }
+
+    /// Signs the authorization with the authority's signer.
+    pub async fn sign(self, signer: &WalletSigner) -> Result<SignedAuthorization> {
+        let signature = signer
+            .sign_hash(self.signature_hash())
+            .await
+            .wrap_err("Failed to sign EIP-7702 authorization")?;
+        Ok(SignedAuthorization { inner: self, signature })
+    }
+
+    fn rlp_append_fields(&self, rlp: &mut RlpStream) {
+        rlp.append(&self.chain_id);
+        rlp.append(&self.address.to_ethers());
+        rlp.append(&self.nonce);
+    }
+}
+
+/// A signed EIP-7702 authorization, as included in the authorization list of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedAuthorization {
+    pub inner: Authorization,
+    pub signature: Signature,
+}
+
+impl SignedAuthorization {
+    /// Returns the authorization in the format of the `authorizationList` of RPC requests.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "chainId": U64::from(self.inner.chain_id),
+            "address": self.inner.address,
+            "nonce": U64::from(self.inner.nonce),
+            "yParity": U64::from(y_parity(&self.signature)),
+            "r": self.signature.r,
+            "s": self.signature.s,
+        })
+    }
+
+    fn rlp_append(&self, rlp: &mut RlpStream) {
+        rlp.begin_list(6);
+        self.inner.rlp_append_fields(rlp);
+        rlp.append(&y_parity(&self.signature));
+        rlp.append(&self.signature.r);
+        rlp.append(&self.signature.s);
+    }
+}
+
+/// Returns the hash the sender signs for an EIP-7702 transaction with the given authorizations.
+pub fn transaction_signature_hash(
+    tx: &Eip1559TransactionRequest,
+    authorizations: &[SignedAuthorization],
+) -> Result<H256> {
+    let mut rlp = RlpStream::new_list(10);
+    rlp_append_transaction_fields(tx, authorizations, &mut rlp)?;
+    Ok(keccak256(typed(rlp)).into())
+}
+
+/// Returns the signed EIP-7702 transaction, ready for `eth_sendRawTransaction`.
+pub fn rlp_signed(
+    tx: &Eip1559TransactionRequest,
+    authorizations: &[SignedAuthorization],
+    signature: &Signature,
+) -> Result<Vec<u8>> {
+    let mut rlp = RlpStream::new_list(13);
+    rlp_append_transaction_fields(tx, authorizations, &mut rlp)?;
+    rlp.append(&y_parity(signature));
+    rlp.append(&signature.r);
+    rlp.append(&signature.s);
+    Ok(typed(rlp))
+}
+
+/// Signs the transaction with the sender's signer and returns the signed transaction.
+pub async fn sign_transaction(
+    signer: &WalletSigner,
+    tx: &Eip1559TransactionRequest,
+    authorizations: &[SignedAuthorization],
+) -> Result<Vec<u8>> {
+    let signature = signer
+        .sign_hash(transaction_signature_hash(tx, authorizations)?)
+        .await
+        .wrap_err("Failed to sign EIP-7702 transaction")?;
+    rlp_signed(tx, authorizations, &signature)
+}
+
+/// Appends `[chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit, destination,
+/// value, data, access_list, authorization_list]`.
+fn rlp_append_transaction_fields(
+    tx: &Eip1559TransactionRequest,
+    authorizations: &[SignedAuthorization],
+    rlp: &mut RlpStream,
+) -> Result<()> {
+    let Some(NameOrAddress::Address(to)) = tx.to else {
+        bail!("EIP-7702 transactions can't create contracts, authorizations must be attached to a call")
+    };
+    rlp.append(&tx.chain_id.unwrap_or_default().as_u64());
+    rlp.append(&tx.nonce.unwrap_or_default());
+    rlp.append(&tx.max_priority_fee_per_gas.unwrap_or_default());
+    rlp.append(&tx.max_fee_per_gas.unwrap_or_default());
+    rlp.append(&tx.gas.unwrap_or_default());
+    rlp.append(&to);
+    rlp.append(&tx.value.unwrap_or_default());
+    rlp.append(&tx.data.as_ref().map(|data| data.to_vec()).unwrap_or_default());
+    rlp.append(&tx.access_list);
+    rlp.begin_list(authorizations.len());
+    for authorization in authorizations {
+        authorization.rlp_append(rlp);
+    }
+    Ok(())
+}
+
+fn typed(rlp: RlpStream) -> Vec<u8> {
+    let mut buf = vec![SET_CODE_TX_TYPE];
+    buf.extend_from_slice(&rlp.out());
+    buf
+}
+
+/// Signers return a `v` of 27 or 28 for raw hashes.
+fn y_parity(signature: &Signature) -> u64 {
+    signature.v.saturating_sub(27)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::Address as EthersAddress;
+    use ethers_signers::{LocalWallet, Signer};
+    use foundry_common::types::ToAlloy;
+
+    #[test]
+    fn can_parse_authorize_arg() {
+        let arg: AuthorizeArg =
+            "0x0000000000000000000000000000000000000001=0x0000000000000000000000000000000000000002"
+                .parse()
+                .unwrap();
+        assert_eq!(arg.authority, Address::with_last_byte(1));
+        assert_eq!(arg.delegate, Address::with_last_byte(2));
+        assert!("0x0000000000000000000000000000000000000001".parse::<AuthorizeArg>().is_err());
+    }
+
+    #[tokio::test]
+    async fn can_sign_authorization_and_transaction() {
+        let wallet: LocalWallet =
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".parse().unwrap();
+        let signer = WalletSigner::Local(wallet.clone());
+
+        let authorization = Authorization {
+            authority: wallet.address().to_alloy(),
+            chain_id: 1,
+            address: Address::with_last_byte(2),
+            nonce: 0,
+        };
+        // keccak256(0x05 || rlp([1, 0x00..02, 0]))
+        let mut expected = vec![AUTHORIZATION_MAGIC, 0xd7, 0x01, 0x94];
+        expected.extend_from_slice(Address::with_last_byte(2).as_slice());
+        expected.push(0x80);
+        assert_eq!(authorization.signature_hash(), H256::from(keccak256(expected)));
+
+        let signed = authorization.sign(&signer).await.unwrap();
+        assert_eq!(
+            signed.signature.recover(authorization.signature_hash()).unwrap(),
+            wallet.address()
+        );
+
+        let tx = Eip1559TransactionRequest::new()
+            .to(EthersAddress::from_low_u64_be(3))
+            .chain_id(U64::from(1))
+            .nonce(0)
+            .gas(50_000)
+            .max_fee_per_gas(1)
+            .max_priority_fee_per_gas(1);
+        let json = signed.to_json();
+        assert_eq!(json["chainId"], "0x1");
+        assert_eq!(json["nonce"], "0x0");
+
+        let raw = sign_transaction(&signer, &tx, &[signed]).await.unwrap();
+        assert_eq!(raw[0], SET_CODE_TX_TYPE);
+
+        let json = serde_json::to_value(authorization).unwrap();
+        assert_eq!(serde_json::from_value::<Authorization>(json).unwrap(), authorization);
+
+        let create = Eip1559TransactionRequest::new().chain_id(U64::from(1));
+        assert!(transaction_signature_hash(&create, &[]).is_err());
+    }
+}
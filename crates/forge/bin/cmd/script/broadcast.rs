@@ -1,20 +1,201 @@
 use super::{
-    multi::MultiChainSequence, providers::ProvidersManager, receipts::clear_pendings,
-    sequence::ScriptSequence, transaction::TransactionWithMetadata, verify::VerifyBundle, *,
+    authorization::{self, Authorization, SignedAuthorization},
+    chain,
+    multi::MultiChainSequence,
+    plan::TransactionPlan,
+    price,
+    providers::ProvidersManager,
+    receipts::clear_pendings,
+    sequence::ScriptSequence,
+    skip,
+    transaction::TransactionWithMetadata,
+    verify::VerifyBundle,
+    *,
+};
+use ethers_core::{
+    types::{Eip1559TransactionRequest, TxHash},
+    utils::format_units,
 };
-use ethers_core::{types::TxHash, utils::format_units};
 use ethers_providers::{JsonRpcClient, Middleware, Provider};
 use ethers_signers::Signer;
 use eyre::{bail, ContextCompat, Result, WrapErr};
 use foundry_cli::{
     init_progress,
     opts::WalletSigner,
-    update_progress,
+    prompt, update_progress,
     utils::{has_batch_support, has_different_gas_calc},
 };
 use foundry_common::{estimate_eip1559_fees, shell, try_get_http_provider, RetryProvider};
+use foundry_config::WalletConfigs;
 use futures::StreamExt;
-use std::{cmp::min, collections::HashSet, ops::Mul, sync::Arc};
+use std::{
+    cmp::min,
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Mul,
+    sync::Arc,
+};
+
+/// The decision of the operator on a transaction, with `--interactive-broadcast`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Approval {
+    Send,
+    Skip,
+    Abort,
+}
+
+impl Approval {
+    fn parse(answer: &str) -> Option<Self> {
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => Some(Approval::Send),
+            "s" | "skip" => Some(Approval::Skip),
+            "a" | "abort" => Some(Approval::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// Shows the decoded call and estimated cost of the transaction at `position` and asks the
+/// operator whether to send it.
+fn prompt_approval(
+    position: usize,
+    total: usize,
+    metadata: &TransactionWithMetadata,
+    tx: &TypedTransaction,
+) -> Result<Approval> {
+    let contract = metadata.contract_name.as_deref().filter(|name| !name.is_empty());
+    let call = match (metadata.opcode, metadata.function.as_deref()) {
+        (CallKind::Create | CallKind::Create2, _) => {
+            format!("deploy {}", contract.unwrap_or("contract"))
+        }
+        (_, Some(function)) if !function.is_empty() => {
+            let name = function.split('(').next().unwrap_or(function);
+            let arguments = metadata.arguments.as_deref().unwrap_or_default().join(", ");
+            match contract {
+                Some(contract) => format!("{contract}::{name}({arguments})"),
+                None => format!("{name}({arguments})"),
+            }
+        }
+        _ => format!("call with {} bytes of calldata", tx.data().map_or(0, |data| data.len())),
+    };
+    let to = tx
+        .to()
+        .and_then(|to| to.as_address())
+        .map(|to| format!(" to {}", to.to_alloy()))
+        .unwrap_or_default();
+
+    let gas = tx.gas().copied().unwrap_or_default();
+    let gas_price = tx.gas_price().unwrap_or_default();
+    let cost =
+        format_units(gas.saturating_mul(gas_price), 18).unwrap_or_else(|_| "N/A".to_string());
+    let gwei = format_units(gas_price, 9).unwrap_or_else(|_| "N/A".to_string());
+
+    shell::println(format!(
+        "\n##\nTransaction {}/{total} from {}{to}\n  {call}\n  Estimated cost: {} ETH ({gas} gas * {} gwei)",
+        position + 1,
+        tx.from().expect("no sender").to_alloy(),
+        cost.trim_end_matches('0').trim_end_matches('.'),
+        gwei.trim_end_matches('0').trim_end_matches('.'),
+    ))?;
+    loop {
+        let answer: String = prompt!("Send this transaction? [y]es, [s]kip, [a]bort: ")?;
+        if let Some(approval) = Approval::parse(&answer) {
+            return Ok(approval)
+        }
+    }
+}
+
+/// Returns why the transaction at `position` can't be skipped, if it can't.
+///
+/// Skipping a transaction lowers the nonces of the later transactions of its sender, so a later
+/// `CREATE` of that sender would deploy to a different address than simulated. Later transactions
+/// also can't use the contracts the skipped transaction deploys.
+fn skip_conflict(
+    transactions: &VecDeque<TransactionWithMetadata>,
+    position: usize,
+) -> Option<String> {
+    let skipped = &transactions[position];
+    let from = skipped.transaction.from().copied();
+    let mut deployed =
+        skipped.additional_contracts.iter().map(|contract| contract.address).collect::<Vec<_>>();
+    if matches!(skipped.opcode, CallKind::Create | CallKind::Create2) {
+        deployed.extend(skipped.contract_address);
+    }
+
+    for (later, tx) in transactions.iter().enumerate().skip(position + 1) {
+        if tx.opcode == CallKind::Create && tx.transaction.from().copied() == from {
+            return Some(format!(
+                "transaction {} deploys a contract at an address that depends on the nonce",
+                later + 1
+            ))
+        }
+        let to = tx.transaction.to().and_then(|to| to.as_address()).map(|to| to.to_alloy());
+        let data = tx.transaction.data().map(|data| data.as_ref()).unwrap_or_default();
+        let used = deployed.iter().find(|address| {
+            to == Some(**address) || data.windows(20).any(|word| word == address.as_slice())
+        });
+        if let Some(address) = used {
+            return Some(format!("transaction {} uses the contract at {address}", later + 1))
+        }
+    }
+    None
+}
+
+/// Returns the maximum amount of native token each sender spends on its transactions: the value
+/// plus the gas limit at the gas price, or max fee per gas.
+fn sender_costs<'a>(
+    txs: impl IntoIterator<Item = &'a TypedTransaction>,
+) -> HashMap<ethers_core::types::Address, U256> {
+    let mut costs = HashMap::<_, U256>::new();
+    for tx in txs {
+        let Some(&from) = tx.from() else { continue };
+        let gas = tx.gas().copied().unwrap_or_default().to_alloy();
+        let gas_price = tx.gas_price().unwrap_or_default().to_alloy();
+        let value = tx.value().copied().unwrap_or_default().to_alloy();
+        let cost = gas.saturating_mul(gas_price).saturating_add(value);
+        let total = costs.entry(from).or_default();
+        *total = total.saturating_add(cost);
+    }
+    costs
+}
+
+/// Sets the balance of every sender whose balance doesn't cover its costs to the costs, if the
+/// node is an anvil or hardhat node.
+async fn auto_fund_senders(
+    provider: &RetryProvider,
+    costs: HashMap<ethers_core::types::Address, U256>,
+) -> Result<()> {
+    let client_version: String =
+        provider.request("web3_clientVersion", ()).await.unwrap_or_default();
+    let client_version = client_version.to_lowercase();
+    let method = if client_version.contains("anvil") {
+        "anvil_setBalance"
+    } else if client_version.contains("hardhat") {
+        "hardhat_setBalance"
+    } else {
+        shell::println(format!(
+            "{}",
+            Paint::yellow("Skipping --auto-fund, the RPC is neither an anvil nor a hardhat node.")
+        ))?;
+        return Ok(())
+    };
+
+    for (sender, cost) in costs {
+        let balance = provider.get_balance(sender, None).await?.to_alloy();
+        if balance >= cost {
+            continue
+        }
+        provider
+            .request::<_, serde_json::Value>(method, (sender, cost.to_ethers()))
+            .await
+            .wrap_err_with(|| format!("failed to fund {}", sender.to_alloy()))?;
+        shell::println(format!(
+            "Funded {} with {} ETH",
+            sender.to_alloy(),
+            format_units(cost.to_ethers(), 18).unwrap_or_else(|_| cost.to_string())
+        ))?;
+    }
+    Ok(())
+}
 
 impl ScriptArgs {
     /// Sends the transactions which haven't been broadcasted yet.
@@ -23,17 +204,26 @@ impl ScriptArgs {
         deployment_sequence: &mut ScriptSequence,
         fork_url: &str,
         script_wallets: &[LocalWallet],
+        wallet_configs: &WalletConfigs,
     ) -> Result<()> {
         let provider = Arc::new(try_get_http_provider(fork_url)?);
         let already_broadcasted = deployment_sequence.receipts.len();
 
         if already_broadcasted < deployment_sequence.transactions.len() {
-            let required_addresses: HashSet<Address> = deployment_sequence
+            let mut required_addresses: HashSet<Address> = deployment_sequence
                 .typed_transactions()
                 .into_iter()
                 .skip(already_broadcasted)
                 .map(|(_, tx)| (*tx.from().expect("No sender for onchain transaction!")).to_alloy())
                 .collect();
+            // Authorities sign their authorizations with the wallets passed to the script.
+            required_addresses.extend(
+                deployment_sequence
+                    .transactions
+                    .iter()
+                    .skip(already_broadcasted)
+                    .flat_map(|tx| tx.authorizations.iter().map(|auth| auth.authority)),
+            );
 
             let (send_kind, chain) = if self.unlocked {
                 let chain = provider.get_chainid().await?;
@@ -61,8 +251,11 @@ impl ScriptArgs {
             // We only wait for a transaction receipt before sending the next transaction, if there
             // is more than one signer. There would be no way of assuring their order
             // otherwise. Or if the chain does not support batched transactions (eg. Arbitrum).
-            let sequential_broadcast =
-                send_kind.signers_count() != 1 || self.slow || !has_batch_support(chain);
+            let sequential_broadcast = send_kind.signers_count() != 1 ||
+                self.slow ||
+                self.interactive_broadcast ||
+                self.skip_deployed ||
+                !has_batch_support(chain);
 
             // Make a one-time gas price estimation
             let (gas_price, eip1559_fees) = {
@@ -80,7 +273,7 @@ impl ScriptArgs {
 
             // Iterate through transactions, matching the `from` field with the associated
             // wallet. Then send the transaction. Panics if we find a unknown `from`
-            let sequence = deployment_sequence
+            let mut sequence = deployment_sequence
                 .transactions
                 .iter()
                 .skip(already_broadcasted)
@@ -90,15 +283,32 @@ impl ScriptArgs {
 
                     let kind = send_kind.for_sender(&from)?;
                     let is_fixed_gas_limit = tx_with_metadata.is_fixed_gas_limit;
+                    let gas_policy = wallet_configs.gas_policy(&from);
+                    let gas_estimate_multiplier =
+                        gas_policy.gas_estimate_multiplier.unwrap_or(self.gas_estimate_multiplier);
 
                     let mut tx = tx.clone();
 
                     tx.set_chain_id(chain);
 
+                    // The simulated gas was inflated by `--gas-estimate-multiplier`, so it's
+                    // rescaled to the multiplier of the sender.
+                    if !is_fixed_gas_limit &&
+                        gas_estimate_multiplier != self.gas_estimate_multiplier
+                    {
+                        if let Some(gas) = tx.gas().copied() {
+                            tx.set_gas(
+                                gas * gas_estimate_multiplier / self.gas_estimate_multiplier.max(1),
+                            );
+                        }
+                    }
+
                     if let Some(gas_price) = self.with_gas_price {
                         tx.set_gas_price(gas_price.to_ethers());
                     } else {
-                        // fill gas price
+                        // fill gas price, preferring the fees configured for the sender
+                        let max_fee =
+                            gas_policy.max_fee_per_gas.map(ethers_core::types::U256::from);
                         match tx {
                             TypedTransaction::Eip1559(ref mut inner) => {
                                 let eip1559_fees =
@@ -106,27 +316,55 @@ impl ScriptArgs {
                                 if let Some(priority_gas_price) = self.priority_gas_price {
                                     inner.max_priority_fee_per_gas =
                                         Some(priority_gas_price.to_ethers());
+                                } else if let Some(priority_fee) =
+                                    gas_policy.max_priority_fee_per_gas
+                                {
+                                    inner.max_priority_fee_per_gas = Some(priority_fee.into());
                                 } else {
                                     inner.max_priority_fee_per_gas = Some(eip1559_fees.1);
                                 }
-                                inner.max_fee_per_gas = Some(eip1559_fees.0);
+                                inner.max_fee_per_gas = Some(max_fee.unwrap_or(eip1559_fees.0));
                             }
                             _ => {
-                                tx.set_gas_price(gas_price.expect("Could not get gas_price."));
+                                tx.set_gas_price(max_fee.unwrap_or_else(|| {
+                                    gas_price.expect("Could not get gas_price.")
+                                }));
                             }
                         }
                     }
 
-                    Ok((tx, kind, is_fixed_gas_limit))
+                    Ok((tx, kind, is_fixed_gas_limit, gas_estimate_multiplier, vec![]))
                 })
                 .collect::<Result<Vec<_>>>()?;
 
+            if self.auto_fund {
+                let costs = sender_costs(sequence.iter().map(|(tx, ..)| tx));
+                auto_fund_senders(&provider, costs).await?;
+            }
+
+            // Sign the EIP-7702 authorizations recorded by the simulation with the wallets of
+            // their authorities.
+            for (offset, entry) in sequence.iter_mut().enumerate() {
+                let authorizations =
+                    &deployment_sequence.transactions[already_broadcasted + offset].authorizations;
+                if authorizations.is_empty() {
+                    continue
+                }
+                let SendTransactionsKind::Raw(signers) = &send_kind else {
+                    bail!("EIP-7702 authorizations can't be signed for unlocked accounts")
+                };
+                entry.4 = sign_authorizations(authorizations, signers).await?;
+            }
+
             let pb = init_progress!(deployment_sequence.transactions, "txes");
 
             // We send transactions and wait for receipts in batches of 100, since some networks
             // cannot handle more than that.
             let batch_size = 100;
             let mut index = 0;
+            // the number of skipped transactions by sender, whose later transactions are sent
+            // with correspondingly lower nonces
+            let mut skipped: HashMap<ethers_core::types::Address, u64> = HashMap::new();
 
             for (batch_number, batch) in sequence.chunks(batch_size).map(|f| f.to_vec()).enumerate()
             {
@@ -137,7 +375,75 @@ impl ScriptArgs {
                     batch_number * batch_size,
                     batch_number * batch_size + min(batch_size, batch.len()) - 1
                 ))?;
-                for (tx, kind, is_fixed_gas_limit) in batch.into_iter() {
+                for (mut tx, kind, is_fixed_gas_limit, gas_estimate_multiplier, authorizations) in
+                    batch.into_iter()
+                {
+                    let position = already_broadcasted + index;
+                    let from = *tx.from().expect("no sender");
+                    if let (Some(&count), Some(&nonce)) = (skipped.get(&from), tx.nonce()) {
+                        tx.set_nonce(nonce - count);
+                        *deployment_sequence.transactions[position].typed_tx_mut() = tx.clone();
+                    }
+
+                    if self.skip_deployed {
+                        let metadata = &deployment_sequence.transactions[position];
+                        if let Some(reason) =
+                            skip::already_applied(&provider, metadata, &self.skip_if).await?
+                        {
+                            let transactions = &deployment_sequence.transactions;
+                            if let Some(conflict) = skip_conflict(transactions, position) {
+                                bail!(
+                                    "{reason}, but it can't be skipped since {conflict}. Re-run \
+                                     the script without the matching --skip-if predicate"
+                                )
+                            }
+                            shell::println(format!("Skipped, {reason}."))?;
+                            deployment_sequence.transactions.remove(position);
+                            deployment_sequence.save()?;
+                            *skipped.entry(from).or_default() += 1;
+                            continue
+                        }
+                    }
+
+                    if self.interactive_broadcast {
+                        let metadata = &deployment_sequence.transactions[position];
+                        let total = deployment_sequence.transactions.len();
+                        let approval = loop {
+                            let approval = prompt_approval(position, total, metadata, &tx)?;
+                            if approval == Approval::Skip {
+                                let transactions = &deployment_sequence.transactions;
+                                if let Some(conflict) = skip_conflict(transactions, position) {
+                                    shell::println(format!(
+                                        "This transaction can't be skipped, {conflict}."
+                                    ))?;
+                                    continue
+                                }
+                            }
+                            break approval
+                        };
+                        match approval {
+                            Approval::Send => {}
+                            Approval::Skip => {
+                                shell::println(format!(
+                                    "Skipped. The following transactions of {} are sent with \
+                                     lower nonces.",
+                                    from.to_alloy()
+                                ))?;
+                                deployment_sequence.transactions.remove(position);
+                                deployment_sequence.save()?;
+                                *skipped.entry(from).or_default() += 1;
+                                continue
+                            }
+                            Approval::Abort => {
+                                deployment_sequence.save()?;
+                                bail!(
+                                    "Broadcast aborted, {} transaction(s) were not sent",
+                                    total - position
+                                )
+                            }
+                        }
+                    }
+
                     let tx_hash = self.send_transaction(
                         provider.clone(),
                         tx,
@@ -145,6 +451,8 @@ impl ScriptArgs {
                         sequential_broadcast,
                         fork_url,
                         is_fixed_gas_limit,
+                        gas_estimate_multiplier,
+                        authorizations,
                     );
 
                     if sequential_broadcast {
@@ -202,9 +510,11 @@ impl ScriptArgs {
             },
         );
         let paid = format_units(total_paid.to_ethers(), 18).unwrap_or_else(|_| "N/A".to_string());
-        let avg_gas_price =
-            format_units(total_gas_price.to_ethers() / deployment_sequence.receipts.len(), 9)
-                .unwrap_or_else(|_| "N/A".to_string());
+        let avg_gas_price = format_units(
+            total_gas_price.to_ethers() / deployment_sequence.receipts.len().max(1),
+            9,
+        )
+        .unwrap_or_else(|_| "N/A".to_string());
         shell::println(format!(
             "Total Paid: {} ETH ({} gas * avg {} gwei)",
             paid.trim_end_matches('0'),
@@ -212,9 +522,12 @@ impl ScriptArgs {
             avg_gas_price.trim_end_matches('0').trim_end_matches('.')
         ))?;
 
+        deployment_sequence.save_deployments(&provider).await?;
+
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn send_transaction(
         &self,
         provider: Arc<RetryProvider>,
@@ -223,6 +536,8 @@ impl ScriptArgs {
         sequential_broadcast: bool,
         fork_url: &str,
         is_fixed_gas_limit: bool,
+        gas_estimate_multiplier: u64,
+        authorizations: Vec<SignedAuthorization>,
     ) -> Result<TxHash> {
         let from = tx.from().expect("no sender");
 
@@ -249,7 +564,7 @@ impl ScriptArgs {
                     (has_different_gas_calc(provider.get_chainid().await?.as_u64()) ||
                         self.skip_simulation)
                 {
-                    self.estimate_gas(&mut tx, &provider).await?;
+                    self.estimate_gas(&mut tx, &provider, gas_estimate_multiplier).await?;
                 }
 
                 // Submit the transaction
@@ -257,10 +572,58 @@ impl ScriptArgs {
 
                 Ok(pending.tx_hash())
             }
-            SendTransactionKind::Raw(signer) => self.broadcast(provider, signer, tx).await,
+            SendTransactionKind::Raw(signer) if !authorizations.is_empty() => {
+                self.broadcast_with_authorizations(
+                    provider,
+                    signer,
+                    tx,
+                    gas_estimate_multiplier,
+                    &authorizations,
+                )
+                .await
+            }
+            SendTransactionKind::Raw(signer) => {
+                self.broadcast(provider, signer, tx, gas_estimate_multiplier).await
+            }
         }
     }
 
+    /// Signs and submits a transaction with an EIP-7702 authorization list.
+    async fn broadcast_with_authorizations(
+        &self,
+        provider: Arc<RetryProvider>,
+        signer: &WalletSigner,
+        mut tx: TypedTransaction,
+        gas_estimate_multiplier: u64,
+        authorizations: &[SignedAuthorization],
+    ) -> Result<TxHash> {
+        debug!("sending transaction with {} authorizations: {:?}", authorizations.len(), tx);
+
+        let TypedTransaction::Eip1559(mut tx) = tx else {
+            bail!("EIP-7702 authorizations can only be attached to EIP1559 transactions")
+        };
+
+        // Chains which use `eth_estimateGas` are being sent sequentially and require their gas
+        // to be re-estimated right before broadcasting.
+        if has_different_gas_calc(signer.chain_id()) {
+            self.estimate_gas_with_authorizations(
+                &mut tx,
+                &provider,
+                gas_estimate_multiplier,
+                authorizations,
+            )
+            .await?;
+        }
+
+        let raw = authorization::sign_transaction(signer, &tx, authorizations).await?;
+        let pending = provider
+            .send_raw_transaction(raw.into())
+            .await
+            .wrap_err("Failed to send EIP-7702 transaction, the RPC might not support them yet")?;
+
+        Ok(pending.tx_hash())
+    }
+
     /// Executes the created transactions, and if no error has occurred, broadcasts
     /// them.
     pub async fn handle_broadcastable_transactions(
@@ -288,6 +651,23 @@ impl ScriptArgs {
                         &verify.known_contracts,
                     )
                     .await?;
+                // later scripts of a chained run resolve these with `vm.getDeployment`
+                chain::record_simulated_deployments(&self.simulated_deployments, &deployments);
+
+                if self.plan_out.is_some() || self.require_plan_hash.is_some() {
+                    let plan = TransactionPlan::new(&deployments);
+                    if let Some(path) = &self.plan_out {
+                        plan.write(path)?;
+                        shell::println(format!(
+                            "\nTransaction plan written to {} with hash {}",
+                            path.display(),
+                            plan.hash
+                        ))?;
+                    }
+                    if let Some(expected) = self.require_plan_hash {
+                        plan.ensure_hash(expected)?;
+                    }
+                }
 
                 if script_config.has_multiple_rpcs() {
                     trace!(target: "script", "broadcasting multi chain deployment");
@@ -350,7 +730,13 @@ impl ScriptArgs {
 
         deployment_sequence.add_libraries(libraries);
 
-        self.send_transactions(deployment_sequence, &rpc, &result.script_wallets).await?;
+        self.send_transactions(
+            deployment_sequence,
+            &rpc,
+            &result.script_wallets,
+            &script_config.config.wallets,
+        )
+        .await?;
 
         if self.verify {
             return deployment_sequence.verify_contracts(&script_config.config, verify).await
@@ -491,7 +877,14 @@ impl ScriptArgs {
                     // for chains where `has_different_gas_calc` returns true,
                     // we await each transaction before broadcasting the next
                     // one.
-                    if let Err(err) = self.estimate_gas(typed_tx, &provider_info.provider).await {
+                    if let Err(err) = self
+                        .estimate_gas(
+                            typed_tx,
+                            &provider_info.provider,
+                            self.gas_estimate_multiplier,
+                        )
+                        .await
+                    {
                         trace!("gas estimation failed: {err}");
 
                         // Restore gas value, since `estimate_gas` will remove it.
@@ -532,6 +925,7 @@ impl ScriptArgs {
         config.chain = original_config_chain;
 
         if !self.skip_simulation {
+            let mut total_usd = Vec::new();
             // Present gas information on a per RPC basis.
             for (rpc, total_gas) in total_gas_per_rpc {
                 let provider_info = manager.get(&rpc).expect("provider is set.");
@@ -555,14 +949,48 @@ impl ScriptArgs {
                         .trim_end_matches('.')
                 ))?;
                 shell::println(format!("\nEstimated total gas used for script: {total_gas}"))?;
+                let total_cost = total_gas.saturating_mul(per_gas);
                 shell::println(format!(
                     "\nEstimated amount required: {} ETH",
-                    format_units(total_gas.saturating_mul(per_gas).to_ethers(), 18)
+                    format_units(total_cost.to_ethers(), 18)
                         .unwrap_or_else(|_| "[Could not calculate]".to_string())
                         .trim_end_matches('0')
                 ))?;
+                if let Some(source) = self.price_source {
+                    let price = source
+                        .native_token_price(
+                            provider_info.chain,
+                            provider_info.provider.as_ref(),
+                            &self.price_feed,
+                        )
+                        .await;
+                    match price {
+                        Ok(Some(price)) => {
+                            let usd = price::wei_to_native(total_cost) * price;
+                            total_usd.push(usd);
+                            shell::println(format!(
+                                "\nEstimated cost in USD: ${usd:.2} (native token price: \
+                                 ${price:.2}, {source})"
+                            ))?;
+                        }
+                        Ok(None) => shell::println(format!(
+                            "\nEstimated cost in USD: [No {source} price for chain {}]",
+                            provider_info.chain
+                        ))?,
+                        Err(err) => shell::println(format!(
+                            "\nEstimated cost in USD: [Could not fetch the {source} price: {err}]"
+                        ))?,
+                    }
+                }
                 shell::println("\n==========================")?;
             }
+
+            if total_usd.len() > 1 {
+                shell::println(format!(
+                    "\nEstimated total cost in USD: ${:.2}",
+                    total_usd.iter().sum::<f64>()
+                ))?;
+            }
         }
         Ok(deployments)
     }
@@ -574,6 +1002,7 @@ impl ScriptArgs {
         provider: Arc<RetryProvider>,
         signer: &WalletSigner,
         mut legacy_or_1559: TypedTransaction,
+        gas_estimate_multiplier: u64,
     ) -> Result<TxHash> {
         debug!("sending transaction: {:?}", legacy_or_1559);
 
@@ -585,7 +1014,7 @@ impl ScriptArgs {
             // we remove it here
             let _ = legacy_or_1559.gas_mut().take();
 
-            self.estimate_gas(&mut legacy_or_1559, &provider).await?;
+            self.estimate_gas(&mut legacy_or_1559, &provider, gas_estimate_multiplier).await?;
         }
 
         // Signing manually so we skip `fill_transaction` and its `eth_createAccessList`
@@ -601,7 +1030,40 @@ impl ScriptArgs {
         Ok(pending.tx_hash())
     }
 
-    async fn estimate_gas<T>(&self, tx: &mut TypedTransaction, provider: &Provider<T>) -> Result<()>
+    /// Estimates the gas of an EIP-7702 transaction including the delegations of its
+    /// authorizations, inflated by the relative percentage `gas_estimate_multiplier`.
+    ///
+    /// ethers can't encode authorization lists, so they're added to the request manually.
+    async fn estimate_gas_with_authorizations(
+        &self,
+        tx: &mut Eip1559TransactionRequest,
+        provider: &RetryProvider,
+        gas_estimate_multiplier: u64,
+        authorizations: &[SignedAuthorization],
+    ) -> Result<()> {
+        tx.gas = None;
+
+        let mut request = serde_json::to_value(&*tx)?;
+        request["type"] = "0x4".into();
+        request["authorizationList"] =
+            authorizations.iter().map(SignedAuthorization::to_json).collect();
+        let gas: ethers_core::types::U256 = provider
+            .request("eth_estimateGas", [request])
+            .await
+            .wrap_err("Failed to estimate gas for EIP-7702 transaction")?;
+
+        tx.gas = Some(gas * gas_estimate_multiplier / 100);
+        Ok(())
+    }
+
+    /// Estimates the gas of the transaction, inflated by the relative percentage
+    /// `gas_estimate_multiplier`.
+    async fn estimate_gas<T>(
+        &self,
+        tx: &mut TypedTransaction,
+        provider: &Provider<T>,
+        gas_estimate_multiplier: u64,
+    ) -> Result<()>
     where
         T: JsonRpcClient,
     {
@@ -614,13 +1076,28 @@ impl ScriptArgs {
                 .estimate_gas(tx, None)
                 .await
                 .wrap_err_with(|| format!("Failed to estimate gas for tx: {:?}", tx.sighash()))? *
-                self.gas_estimate_multiplier /
+                gas_estimate_multiplier /
                 100,
         );
         Ok(())
     }
 }
 
+/// Signs the authorizations with the wallets of their authorities.
+async fn sign_authorizations(
+    authorizations: &[Authorization],
+    signers: &HashMap<Address, WalletSigner>,
+) -> Result<Vec<SignedAuthorization>> {
+    let mut signed = Vec::with_capacity(authorizations.len());
+    for authorization in authorizations {
+        let signer = signers.get(&authorization.authority).wrap_err_with(|| {
+            format!("No matching signer for authority {}", authorization.authority)
+        })?;
+        signed.push(authorization.sign(signer).await?);
+    }
+    Ok(signed)
+}
+
 /// How to send a single transaction
 #[derive(Clone)]
 enum SendTransactionKind<'a> {
@@ -666,3 +1143,30 @@ impl SendTransactionsKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_approval() {
+        assert_eq!(Approval::parse("y\n"), Some(Approval::Send));
+        assert_eq!(Approval::parse("Skip"), Some(Approval::Skip));
+        assert_eq!(Approval::parse(" a "), Some(Approval::Abort));
+        assert_eq!(Approval::parse(""), None);
+    }
+
+    #[test]
+    fn can_compute_sender_costs() {
+        let alice = ethers_core::types::Address::repeat_byte(1);
+        let bob = ethers_core::types::Address::repeat_byte(2);
+        let tx = |from, value: u64| -> TypedTransaction {
+            TransactionRequest::new().from(from).gas(21_000).gas_price(10).value(value).into()
+        };
+        let txs = [tx(alice, 5), tx(alice, 0), tx(bob, 1)];
+
+        let costs = sender_costs(&txs);
+        assert_eq!(costs[&alice], U256::from(2 * 21_000 * 10 + 5));
+        assert_eq!(costs[&bob], U256::from(21_000 * 10 + 1));
+    }
+}
@@ -6,7 +6,7 @@ use foundry_cli::utils::get_cached_entry_by_name;
 use foundry_common::{
     compact_to_contract,
     compile::{self, ContractSources},
-    fs,
+    fs, vyper,
 };
 use foundry_compilers::{
     artifacts::{CompactContractBytecode, ContractBytecode, ContractBytecodeSome, Libraries},
@@ -28,12 +28,18 @@ impl ScriptArgs {
     /// Compiles the file with auto-detection and compiler params.
     pub fn build(&mut self, script_config: &mut ScriptConfig) -> Result<BuildOutput> {
         let (project, output) = self.get_project_and_output(script_config)?;
+        compile::check_settings_fingerprints(
+            &project,
+            &output,
+            script_config.config.deny_settings_mismatch,
+        )?;
         let output = output.with_stripped_file_prefixes(project.root());
 
         let mut sources: ContractSources = Default::default();
 
         let contracts = output
             .into_artifacts()
+            .chain(vyper::project_artifacts(&project.paths)?)
             .map(|(id, artifact)| -> Result<_> {
                 // Sources are only required for the debugger, but it *might* mean that there's
                 // something wrong with the build and/or artifacts.
@@ -0,0 +1,321 @@
+//! Chained script runs, `forge script --chain-run ScriptA ScriptB ...`.
+//!
+//! Scripts declare the contracts they deploy and the deployments they depend on with NatSpec tags
+//! on the script contract:
+//!
+//! ```solidity
+//! /// @custom:deploys Token
+//! contract DeployToken is Script { ... }
+//!
+//! /// @custom:depends-on Token
+//! contract DeployVault is Script { ... }
+//! ```
+//!
+//! The scripts run in the order of their dependencies, and read the outputs of earlier scripts
+//! with `vm.getDeployment`, from the deployment manifests or, without `--broadcast`, from the
+//! deployments simulated by the earlier scripts. A script is skipped if all the contracts it
+//! deploys already have a deployment on the target chain.
+
+use super::{sequence::ScriptSequence, ScriptArgs};
+use alloy_primitives::Address;
+use ethers_providers::Middleware;
+use eyre::{Result, WrapErr};
+use foundry_common::{deployments::DeploymentRegistry, fs, shell, try_get_http_provider};
+use foundry_config::Config;
+use foundry_evm::utils::CallKind;
+use futures::FutureExt;
+use parking_lot::Mutex;
+use solang_parser::{
+    doccomment::{parse_doccomments, DocComment},
+    pt::SourceUnitPart,
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The deployments simulated by the scripts of a chained run, by chain id and contract name.
+pub type SimulatedDeployments = Arc<Mutex<BTreeMap<(u64, String), Address>>>;
+
+/// The NatSpec tag declaring the contracts deployed by a script.
+const DEPLOYS_TAG: &str = "custom:deploys";
+
+/// The NatSpec tag declaring the deployments a script depends on.
+const DEPENDS_ON_TAG: &str = "custom:depends-on";
+
+/// The chain declarations of a script contract.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScriptDeclaration {
+    /// The name of the script contract.
+    pub name: String,
+    /// The contracts deployed by the script.
+    pub deploys: Vec<String>,
+    /// The contracts whose deployments the script depends on.
+    pub depends_on: Vec<String>,
+}
+
+/// A script of a chained run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ChainStep {
+    /// The script as passed on the command line.
+    target: String,
+    declaration: ScriptDeclaration,
+}
+
+impl ScriptArgs {
+    /// Runs the script and the scripts passed as arguments in the order of their dependencies.
+    pub(super) async fn run_chain(self) -> Result<()> {
+        let (config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
+
+        let steps = std::iter::once(&self.path)
+            .chain(&self.args)
+            .map(|target| {
+                Ok(ChainStep {
+                    target: target.clone(),
+                    declaration: find_declaration(&config, target)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let steps = resolve_order(steps)?;
+
+        let chain_id = match (evm_opts.env.chain_id, &evm_opts.fork_url) {
+            (Some(chain_id), _) => chain_id,
+            (None, Some(fork_url)) => {
+                try_get_http_provider(fork_url)?.get_chainid().await?.as_u64()
+            }
+            (None, None) => foundry_common::DEV_CHAIN_ID,
+        };
+        let registry = DeploymentRegistry::new(&config.__root.0);
+
+        for ChainStep { target, declaration } in steps {
+            let ScriptDeclaration { deploys, depends_on, .. } = &declaration;
+            if !deploys.is_empty() && is_deployed(&registry, chain_id, deploys)? {
+                shell::println(format!(
+                    "Skipping {target}: {} already deployed on chain {chain_id}",
+                    deploys.join(", ")
+                ))?;
+                continue
+            }
+            for dependency in depends_on {
+                let simulated =
+                    self.simulated_deployments.lock().contains_key(&(chain_id, dependency.clone()));
+                if !simulated && registry.get(chain_id, dependency)?.is_none() {
+                    eyre::bail!(
+                        "{target} depends on `{dependency}`, which isn't deployed on chain \
+                         {chain_id} nor by an earlier script"
+                    )
+                }
+            }
+
+            shell::println(format!("Running {target}"))?;
+            // every script runs its `run()` function, the remaining arguments are the scripts
+            let mut args = self.clone();
+            args.path = target;
+            args.args = vec![];
+            args.chain_run = false;
+            args.run_script().boxed_local().await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Records the contracts deployed by the sequences, so later scripts of the chained run can resolve
+/// their addresses before they are broadcast.
+pub(super) fn record_simulated_deployments(
+    deployments: &SimulatedDeployments,
+    sequences: &[ScriptSequence],
+) {
+    let mut deployments = deployments.lock();
+    for sequence in sequences {
+        for tx in &sequence.transactions {
+            if !matches!(tx.opcode, CallKind::Create | CallKind::Create2) {
+                continue
+            }
+            let (Some(contract_name), Some(address)) = (&tx.contract_name, tx.contract_address)
+            else {
+                continue
+            };
+            if !contract_name.is_empty() {
+                deployments.insert((sequence.chain, contract_name.clone()), address);
+            }
+        }
+    }
+}
+
+/// Returns whether all the contracts have a deployment on the chain.
+fn is_deployed(registry: &DeploymentRegistry, chain_id: u64, contracts: &[String]) -> Result<bool> {
+    for contract in contracts {
+        if registry.get(chain_id, contract)?.is_none() {
+            return Ok(false)
+        }
+    }
+    Ok(true)
+}
+
+/// Finds the declarations of the script, given as `path`, `path:contract` or `contract`.
+fn find_declaration(config: &Config, target: &str) -> Result<ScriptDeclaration> {
+    if Path::new(target).is_file() {
+        let declarations = parse_declarations(&fs::read_to_string(target)?)
+            .wrap_err_with(|| format!("failed to parse {target}"))?;
+        return match <[_; 1]>::try_from(declarations) {
+            Ok([declaration]) => Ok(declaration),
+            Err(_) => eyre::bail!(
+                "{target} doesn't define exactly one contract, use `<path>:<contract>` instead"
+            ),
+        }
+    }
+
+    let (files, name) = match target.rsplit_once(':') {
+        Some((path, name)) => (vec![PathBuf::from(path)], name),
+        None => (config.project_paths().input_files(), target),
+    };
+    for file in files {
+        let Ok(content) = fs::read_to_string(&file) else { continue };
+        if !content.contains(name) {
+            continue
+        }
+        // sources that fail to parse can't define the script either
+        let Ok(declarations) = parse_declarations(&content) else { continue };
+        if let Some(declaration) = declarations.into_iter().find(|d| d.name == name) {
+            return Ok(declaration)
+        }
+    }
+    eyre::bail!("could not find the script contract `{name}`")
+}
+
+/// Returns the declarations of the contracts defined in the Solidity source.
+pub fn parse_declarations(src: &str) -> Result<Vec<ScriptDeclaration>> {
+    let (source_unit, comments) = solang_parser::parse(src, 0)
+        .map_err(|diagnostics| eyre::eyre!("failed to parse Solidity code: {diagnostics:?}"))?;
+
+    let mut declarations = Vec::new();
+    let mut doc_start = 0;
+    for part in &source_unit.0 {
+        let SourceUnitPart::ContractDefinition(contract) = part else { continue };
+        let mut declaration = ScriptDeclaration {
+            name: contract.name.as_ref().map(|name| name.name.clone()).unwrap_or_default(),
+            ..Default::default()
+        };
+        for comment in parse_doccomments(&comments, doc_start, contract.loc.start()) {
+            let tags = match comment {
+                DocComment::Line { comment } => vec![comment],
+                DocComment::Block { comments } => comments,
+            };
+            for tag in tags {
+                let names = tag
+                    .value
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string);
+                match tag.tag.as_str() {
+                    DEPLOYS_TAG => declaration.deploys.extend(names),
+                    DEPENDS_ON_TAG => declaration.depends_on.extend(names),
+                    _ => {}
+                }
+            }
+        }
+        declarations.push(declaration);
+        doc_start = contract.loc.end();
+    }
+    Ok(declarations)
+}
+
+/// Orders the steps so that every script runs after the scripts deploying its dependencies,
+/// keeping the given order otherwise.
+fn resolve_order(mut pending: Vec<ChainStep>) -> Result<Vec<ChainStep>> {
+    let mut ordered: Vec<ChainStep> = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        // a step is ready once no other pending step deploys one of its dependencies
+        let ready = pending.iter().position(|step| {
+            step.declaration.depends_on.iter().all(|dependency| {
+                !pending
+                    .iter()
+                    .any(|other| other != step && other.declaration.deploys.contains(dependency))
+            })
+        });
+        match ready {
+            Some(index) => ordered.push(pending.remove(index)),
+            None => eyre::bail!(
+                "the dependencies of {} are cyclic",
+                pending.iter().map(|step| step.target.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+    Ok(ordered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(name: &str, deploys: &[&str], depends_on: &[&str]) -> ChainStep {
+        ChainStep {
+            target: name.to_string(),
+            declaration: ScriptDeclaration {
+                name: name.to_string(),
+                deploys: deploys.iter().map(|s| s.to_string()).collect(),
+                depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+            },
+        }
+    }
+
+    #[test]
+    fn can_parse_declarations() {
+        let src = r#"
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+import "forge-std/Script.sol";
+
+/// @notice Deploys the token
+/// @custom:deploys Token
+contract DeployToken is Script {
+    function run() public {}
+}
+
+/**
+ * @custom:deploys Vault, Router
+ * @custom:depends-on Token
+ */
+contract DeployVault is Script {
+    function run() public {}
+}
+"#;
+        let declarations = parse_declarations(src).unwrap();
+        assert_eq!(
+            declarations,
+            vec![
+                ScriptDeclaration {
+                    name: "DeployToken".to_string(),
+                    deploys: vec!["Token".to_string()],
+                    depends_on: vec![],
+                },
+                ScriptDeclaration {
+                    name: "DeployVault".to_string(),
+                    deploys: vec!["Vault".to_string(), "Router".to_string()],
+                    depends_on: vec!["Token".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_resolve_order() {
+        let steps = vec![
+            step("DeployRouter", &["Router"], &["Vault", "Token"]),
+            step("DeployVault", &["Vault"], &["Token"]),
+            step("Configure", &[], &[]),
+            step("DeployToken", &["Token"], &[]),
+        ];
+        let ordered = resolve_order(steps).unwrap();
+        assert_eq!(
+            ordered.iter().map(|step| step.target.as_str()).collect::<Vec<_>>(),
+            vec!["Configure", "DeployToken", "DeployVault", "DeployRouter"]
+        );
+
+        let cyclic = vec![step("A", &["A"], &["B"]), step("B", &["B"], &["A"])];
+        assert!(resolve_order(cyclic).unwrap_err().to_string().contains("cyclic"));
+    }
+}
@@ -17,6 +17,10 @@ impl ScriptArgs {
     pub async fn run_script(mut self) -> Result<()> {
         trace!(target: "script", "executing script command");
 
+        if self.chain_run {
+            return self.run_chain().await
+        }
+
         let (config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
         let mut script_config = ScriptConfig {
             // dapptools compatibility
@@ -265,8 +269,13 @@ impl ScriptArgs {
         receipts::wait_for_pending(provider, &mut deployment_sequence).await?;
 
         if self.resume {
-            self.send_transactions(&mut deployment_sequence, fork_url, &result.script_wallets)
-                .await?;
+            self.send_transactions(
+                &mut deployment_sequence,
+                fork_url,
+                &result.script_wallets,
+                &script_config.config.wallets,
+            )
+            .await?;
         }
 
         if self.verify {
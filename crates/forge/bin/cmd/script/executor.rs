@@ -1,5 +1,6 @@
 use super::{
     artifacts::ArtifactInfo,
+    authorization::PER_AUTHORIZATION_GAS,
     runner::SimulationStage,
     transaction::{AdditionalContract, TransactionWithMetadata},
     *,
@@ -134,84 +135,116 @@ impl ScriptArgs {
             })
             .collect();
 
+        // The EIP-7702 authorizations are attached to the first call on every chain, since
+        // transactions that create contracts can't carry them.
+        let mut carriers = HashSet::new();
+        if !self.authorize.is_empty() {
+            let mut rpcs = HashSet::new();
+            for (index, transaction) in transactions.iter().enumerate() {
+                if transaction.transaction.to().is_some() && rpcs.insert(transaction.rpc.clone()) {
+                    carriers.insert(index);
+                }
+            }
+            if carriers.is_empty() {
+                eyre::bail!("EIP-7702 authorizations must be attached to a call, but the script only creates contracts")
+            }
+        }
+
         let mut final_txs = VecDeque::new();
 
         // Executes all transactions from the different forks concurrently.
-        let futs =
-            transactions
-                .into_iter()
-                .map(|transaction| async {
-                    let mut runner = runners
-                        .get(transaction.rpc.as_ref().expect("to have been filled already."))
-                        .expect("to have been built.")
-                        .write();
-
-                    if let TypedTransaction::Legacy(mut tx) = transaction.transaction {
-                        let result = runner
+        let futs = transactions
+            .into_iter()
+            .enumerate()
+            .map(|entry| async {
+                let (index, transaction) = entry;
+                let mut runner = runners
+                    .get(transaction.rpc.as_ref().expect("to have been filled already."))
+                    .expect("to have been built.")
+                    .write();
+
+                if let TypedTransaction::Legacy(mut tx) = transaction.transaction {
+                    // The delegations are applied right before the transaction that carries
+                    // them, so they can delegate to contracts deployed earlier by the script.
+                    let authorizations = if carriers.contains(&index) {
+                        let from = tx
+                            .from
+                            .expect("Transaction doesn't have a `from` address at execution time");
+                        runner.authorize(&self.authorize, from.to_alloy())?
+                    } else {
+                        vec![]
+                    };
+
+                    let result = runner
                         .simulate(
-                            tx.from.expect(
-                                "Transaction doesn't have a `from` address at execution time",
-                            ).to_alloy(),
+                            tx.from
+                                .expect(
+                                    "Transaction doesn't have a `from` address at execution time",
+                                )
+                                .to_alloy(),
                             tx.to.clone(),
                             tx.data.clone().map(|b| b.to_alloy()),
                             tx.value.map(|v| v.to_alloy()),
                         )
                         .wrap_err("Internal EVM error during simulation")?;
 
-                        if !result.success || result.traces.is_empty() {
-                            return Ok((None, result.traces))
-                        }
-
-                        let created_contracts = result
-                            .traces
-                            .iter()
-                            .flat_map(|(_, traces)| {
-                                traces.arena.iter().filter_map(|node| {
-                                    if matches!(node.kind(), CallKind::Create | CallKind::Create2) {
-                                        return Some(AdditionalContract {
-                                            opcode: node.kind(),
-                                            address: node.trace.address,
-                                            init_code: node.trace.data.as_bytes().to_vec().into(),
-                                        })
-                                    }
-                                    None
-                                })
+                    if !result.success || result.traces.is_empty() {
+                        return Ok((None, result.traces))
+                    }
+
+                    let created_contracts = result
+                        .traces
+                        .iter()
+                        .flat_map(|(_, traces)| {
+                            traces.arena.iter().filter_map(|node| {
+                                if matches!(node.kind(), CallKind::Create | CallKind::Create2) {
+                                    return Some(AdditionalContract {
+                                        opcode: node.kind(),
+                                        address: node.trace.address,
+                                        init_code: node.trace.data.as_bytes().to_vec().into(),
+                                    })
+                                }
+                                None
                             })
-                            .collect();
-
-                        // Simulate mining the transaction if the user passes `--slow`.
-                        if self.slow {
-                            runner.executor.env.block.number += U256::from(1);
-                        }
-
-                        let is_fixed_gas_limit = tx.gas.is_some();
-                        // If tx.gas is already set that means it was specified in script
-                        if !is_fixed_gas_limit {
-                            // We inflate the gas used by the user specified percentage
-                            tx.gas = Some(
-                                U256::from(result.gas_used * self.gas_estimate_multiplier / 100)
-                                    .to_ethers(),
-                            );
-                        } else {
-                            println!("Gas limit was set in script to {:}", tx.gas.unwrap());
-                        }
-
-                        let tx = TransactionWithMetadata::new(
-                            tx.into(),
-                            transaction.rpc,
-                            &result,
-                            &address_to_abi,
-                            decoder,
-                            created_contracts,
-                            is_fixed_gas_limit,
-                        )?;
-
-                        Ok((Some(tx), result.traces))
+                        })
+                        .collect();
+
+                    // Simulate mining the transaction if the user passes `--slow`.
+                    if self.slow {
+                        runner.executor.env.block.number += U256::from(1);
+                    }
+
+                    let is_fixed_gas_limit = tx.gas.is_some();
+                    // If tx.gas is already set that means it was specified in script
+                    if !is_fixed_gas_limit {
+                        // We inflate the gas used by the user specified percentage, after adding
+                        // the intrinsic gas of the authorizations
+                        let gas_used =
+                            result.gas_used + PER_AUTHORIZATION_GAS * authorizations.len() as u64;
+                        tx.gas = Some(
+                            U256::from(gas_used * self.gas_estimate_multiplier / 100).to_ethers(),
+                        );
                     } else {
-                        unreachable!()
+                        println!("Gas limit was set in script to {:}", tx.gas.unwrap());
                     }
-                })
-                .collect::<Vec<_>>();
+
+                    let mut tx = TransactionWithMetadata::new(
+                        tx.into(),
+                        transaction.rpc,
+                        &result,
+                        &address_to_abi,
+                        decoder,
+                        created_contracts,
+                        is_fixed_gas_limit,
+                    )?;
+                    tx.authorizations = authorizations;
+
+                    Ok((Some(tx), result.traces))
+                } else {
+                    unreachable!()
+                }
+            })
+            .collect::<Vec<_>>();
 
         let mut abort = false;
         for res in join_all(futs).await {
@@ -316,11 +349,12 @@ impl ScriptArgs {
             .gas_limit(script_config.evm_opts.gas_limit());
 
         if let SimulationStage::Local = stage {
-            builder = builder.inspectors(|stack| {
-                stack.debug(self.debug).cheatcodes(
-                    CheatsConfig::new(&script_config.config, script_config.evm_opts.clone()).into(),
-                )
-            });
+            let mut cheats_config =
+                CheatsConfig::new(&script_config.config, script_config.evm_opts.clone());
+            cheats_config.simulated_deployments = self.simulated_deployments.lock().clone();
+            cheats_config.skip_deployed = self.skip_deployed;
+            builder = builder
+                .inspectors(|stack| stack.debug(self.debug).cheatcodes(cheats_config.into()));
         }
 
         ScriptRunner::new(builder.build(env, db), script_config.evm_opts.initial_balance, sender)
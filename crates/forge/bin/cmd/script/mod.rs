@@ -2,7 +2,7 @@ use self::{build::BuildOutput, runner::ScriptRunner};
 use super::{build::BuildArgs, retry::RetryArgs};
 use alloy_dyn_abi::FunctionExt;
 use alloy_json_abi::{Function, InternalType, JsonAbi as Abi};
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{Address, Bytes, B256, U256};
 use clap::{Parser, ValueHint};
 use dialoguer::Confirm;
 use ethers_core::types::{
@@ -54,19 +54,27 @@ use foundry_evm::{
 use futures::future;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    path::PathBuf,
+};
 use yansi::Paint;
 
 mod artifacts;
+mod authorization;
 mod broadcast;
 mod build;
+mod chain;
 mod cmd;
 mod executor;
 mod multi;
+mod plan;
+mod price;
 mod providers;
 mod receipts;
 mod runner;
 mod sequence;
+mod skip;
 pub mod transaction;
 mod verify;
 
@@ -158,6 +166,44 @@ pub struct ScriptArgs {
     #[clap(long)]
     pub slow: bool,
 
+    /// Pause before broadcasting each transaction to approve, skip or abort it.
+    ///
+    /// Shows the decoded call and estimated cost of every transaction first. Transactions are sent
+    /// one at a time, as with `--slow`.
+    ///
+    /// A transaction can't be skipped if a later one uses a contract it deploys, or if a later
+    /// transaction of the same sender deploys a contract with `CREATE`, as the lower nonce would
+    /// change its address.
+    #[clap(long, requires = "broadcast")]
+    pub interactive_broadcast: bool,
+
+    /// Top up the balance of every sender with enough native token to pay for its transactions
+    /// before broadcasting.
+    ///
+    /// Only has an effect on anvil and hardhat nodes, where the balances are set with
+    /// `anvil_setBalance`.
+    #[clap(long, requires = "broadcast")]
+    pub auto_fund: bool,
+
+    /// Skips the transactions whose effect already exists on chain, which makes scripts safe to
+    /// re-run.
+    ///
+    /// CREATE2 deployments of contracts that already exist resolve to the existing contracts
+    /// during the simulation and aren't broadcast. Calls are skipped before broadcasting them if
+    /// the `--skip-if` predicates matching them hold, unless a later transaction depends on them
+    /// or on the nonce of their sender. Plain CREATE deployments are never skipped. Transactions
+    /// are sent one at a time, as with `--slow`.
+    #[clap(long, requires = "broadcast")]
+    pub skip_deployed: bool,
+
+    /// Skips calls to a contract with `--skip-deployed` if one of its storage slots holds a value,
+    /// in the format `<TARGET>[.<FUNCTION>]:<SLOT>=<VALUE>`.
+    ///
+    /// If a function name or signature is given, only calls of that function are matched. Can be
+    /// used multiple times, a call is skipped if all predicates matching it hold.
+    #[clap(long, value_name = "PREDICATE", requires = "skip_deployed")]
+    pub skip_if: Vec<skip::SkipIfArg>,
+
     /// Disables interactive prompts that might appear when deploying big contracts.
     ///
     /// For more info on the contract size limit, see EIP-170: <https://eips.ethereum.org/EIPS/eip-170>
@@ -185,6 +231,60 @@ pub struct ScriptArgs {
     )]
     pub with_gas_price: Option<U256>,
 
+    /// Estimates the cost of the broadcast in USD with the native token price of the source.
+    #[clap(long, value_enum, value_name = "SOURCE")]
+    pub price_source: Option<price::PriceSource>,
+
+    /// The Chainlink USD price feed of the native token of a chain, for chains without a known
+    /// feed, in the format `<CHAIN>=<ADDRESS>`. Can be used multiple times.
+    ///
+    /// Answers older than three hours are rejected as stale.
+    #[clap(long, value_name = "CHAIN=ADDRESS", requires = "price_source")]
+    pub price_feed: Vec<price::PriceFeedArg>,
+
+    /// Signs an EIP-7702 authorization delegating the code of an EOA to a contract, in the format
+    /// `<AUTHORITY>=<DELEGATE>`.
+    ///
+    /// The authorizations are attached to the first call of the script on every chain, which is
+    /// simulated with the delegations applied and sent as an EIP-7702 transaction. They are
+    /// signed with the wallets of the authorities right before broadcasting. Can be used multiple
+    /// times.
+    #[clap(
+        long,
+        value_name = "AUTHORITY=DELEGATE",
+        conflicts_with_all = &["legacy", "unlocked", "skip_simulation"],
+    )]
+    pub authorize: Vec<authorization::AuthorizeArg>,
+
+    /// Writes the decoded transactions of the script to a file for review, as JSON if the file
+    /// ends with `.json` and as Markdown otherwise.
+    ///
+    /// The plan includes its hash, which can be passed to `--require-plan-hash` later.
+    #[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub plan_out: Option<PathBuf>,
+
+    /// Refuses to run if the hash of the transaction plan differs from the given one.
+    ///
+    /// The hash covers the senders, targets, functions, arguments, values, calldata and predicted
+    /// addresses of the transactions, but not their gas.
+    #[clap(long, value_name = "HASH")]
+    pub require_plan_hash: Option<B256>,
+
+    /// Runs the script and the scripts passed as arguments as a chain, in the order of their
+    /// deployment dependencies.
+    ///
+    /// Scripts declare the contracts they deploy with `@custom:deploys <CONTRACT>` and the
+    /// deployments they depend on with `@custom:depends-on <CONTRACT>` NatSpec tags, and read the
+    /// outputs of earlier scripts with `vm.getDeployment`, which resolves the deployments
+    /// simulated by earlier scripts without `--broadcast`. Every script runs its `run()`
+    /// function. Scripts whose contracts are all deployed on the chain already are skipped.
+    #[clap(long, conflicts_with_all = &["resume", "multi", "debug", "target_contract", "sig"])]
+    pub chain_run: bool,
+
+    /// The deployments simulated by the scripts of a chained run, shared by all its scripts.
+    #[clap(skip)]
+    pub simulated_deployments: chain::SimulatedDeployments,
+
     #[clap(flatten)]
     pub opts: BuildArgs,
 
@@ -737,6 +837,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_parse_chain_run() {
+        let args: ScriptArgs = ScriptArgs::parse_from([
+            "foundry-cli",
+            "--chain-run",
+            "DeployToken",
+            "DeployVault",
+            "--broadcast",
+        ]);
+        assert!(args.chain_run);
+        assert_eq!(args.path, "DeployToken");
+        assert_eq!(args.args, vec!["DeployVault"]);
+    }
+
     #[test]
     fn can_parse_unlocked() {
         let args: ScriptArgs = ScriptArgs::parse_from([
@@ -761,6 +875,75 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn can_parse_authorize() {
+        let authorization =
+            "0x4e59b44847b379578588920ca78fbf26c0b4956c=0x0000000000000000000000000000000000000001";
+        let args: ScriptArgs = ScriptArgs::parse_from([
+            "foundry-cli",
+            "Contract.sol",
+            "--broadcast",
+            "--authorize",
+            authorization,
+        ]);
+        assert_eq!(args.authorize.len(), 1);
+        assert_eq!(args.authorize[0].delegate, Address::with_last_byte(1));
+
+        let args = ScriptArgs::try_parse_from([
+            "foundry-cli",
+            "Contract.sol",
+            "--broadcast",
+            "--legacy",
+            "--authorize",
+            authorization,
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_skip_deployed() {
+        let predicate = "0x0000000000000000000000000000000000000001.setOwner:0=1";
+        let args: ScriptArgs = ScriptArgs::parse_from([
+            "foundry-cli",
+            "Contract.sol",
+            "--broadcast",
+            "--skip-deployed",
+            "--skip-if",
+            predicate,
+        ]);
+        assert!(args.skip_deployed);
+        assert_eq!(args.skip_if.len(), 1);
+        assert_eq!(args.skip_if[0].target, Address::with_last_byte(1));
+
+        let args =
+            ScriptArgs::try_parse_from(["foundry-cli", "Contract.sol", "--skip-if", predicate]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_price_source() {
+        let args: ScriptArgs = ScriptArgs::parse_from([
+            "foundry-cli",
+            "Contract.sol",
+            "--price-source",
+            "chainlink",
+            "--price-feed",
+            "1=0x5f4eC3Df9cbd43714FE2740F5E3616155c5b8419",
+            "--price-feed",
+            "10=0x13e3Ee699D1909E989722E753853AE30b17e08c5",
+        ]);
+        assert_eq!(args.price_source, Some(price::PriceSource::Chainlink));
+        assert_eq!(args.price_feed.iter().map(|feed| feed.chain).collect::<Vec<_>>(), vec![1, 10]);
+
+        let args = ScriptArgs::try_parse_from([
+            "foundry-cli",
+            "Contract.sol",
+            "--price-feed",
+            "1=0x5f4eC3Df9cbd43714FE2740F5E3616155c5b8419",
+        ]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn can_merge_script_config() {
         let args: ScriptArgs = ScriptArgs::parse_from([
@@ -158,6 +158,7 @@ impl ScriptArgs {
                     sequence,
                     &sequence.typed_transactions().first().unwrap().0.clone(),
                     &script_wallets,
+                    &config.wallets,
                 )
                 .await
             {
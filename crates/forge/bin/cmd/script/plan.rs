@@ -0,0 +1,208 @@
+//! Reviewable plans of the transactions of a script, written by `forge script --plan-out`.
+//!
+//! The hash of a plan only covers what the transactions do, not their gas or fees, so that a plan
+//! reviewed after a simulation can be enforced with `--require-plan-hash` when broadcasting later.
+
+use super::{sequence::ScriptSequence, transaction::TransactionWithMetadata};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use ethers_core::types::NameOrAddress;
+use eyre::{bail, Result};
+use foundry_common::{fs, types::ToAlloy};
+use foundry_evm::utils::CallKind;
+use serde::Serialize;
+use std::{fmt::Write, path::Path};
+
+/// The decoded transactions of one or more script sequences, in broadcast order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TransactionPlan {
+    /// The hash of the planned transactions.
+    pub hash: B256,
+    pub transactions: Vec<PlannedTransaction>,
+}
+
+/// A transaction of a [`TransactionPlan`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTransaction {
+    pub chain: u64,
+    /// `call`, `create` or `create2`.
+    pub kind: &'static str,
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub contract_name: Option<String>,
+    /// The called contract, or the predicted address of the created contract.
+    pub contract_address: Option<Address>,
+    pub function: Option<String>,
+    pub arguments: Vec<String>,
+    pub value: U256,
+    pub data: Bytes,
+    /// The predicted addresses of the contracts created by the transaction's subcalls.
+    pub additional_contracts: Vec<Address>,
+}
+
+impl PlannedTransaction {
+    fn new(chain: u64, tx: &TransactionWithMetadata) -> Self {
+        let to = match tx.transaction.to() {
+            Some(NameOrAddress::Address(to)) => Some(to.to_alloy()),
+            _ => None,
+        };
+        Self {
+            chain,
+            kind: kind_name(tx.opcode),
+            from: tx.transaction.from().map(|from| from.to_alloy()),
+            to,
+            contract_name: tx.contract_name.clone().filter(|name| !name.is_empty()),
+            contract_address: tx.contract_address,
+            function: tx.function.clone().filter(|function| !function.is_empty()),
+            arguments: tx.arguments.clone().unwrap_or_default(),
+            value: tx.transaction.value().map(|value| value.to_alloy()).unwrap_or_default(),
+            data: tx.transaction.data().map(|data| data.clone().to_alloy()).unwrap_or_default(),
+            additional_contracts: tx
+                .additional_contracts
+                .iter()
+                .map(|contract| contract.address)
+                .collect(),
+        }
+    }
+
+    fn title(&self) -> String {
+        let contract = self.contract_name.as_deref().map(|name| format!("`{name}`"));
+        let address = self.contract_address.map(|address| format!("`{address}`"));
+        let target = contract.or(address).unwrap_or_else(|| "unknown contract".to_string());
+        match (self.kind, &self.function) {
+            ("call", Some(function)) => format!("Call {target} `{function}`"),
+            ("call", None) => format!("Call {target}"),
+            (kind, _) => format!("Deploy {target} with {}", kind.to_uppercase()),
+        }
+    }
+}
+
+impl TransactionPlan {
+    /// Collects the transactions of the sequences.
+    pub fn new(sequences: &[ScriptSequence]) -> Self {
+        let transactions = sequences
+            .iter()
+            .flat_map(|sequence| {
+                sequence.transactions.iter().map(|tx| PlannedTransaction::new(sequence.chain, tx))
+            })
+            .collect::<Vec<_>>();
+        let hash = keccak256(serde_json::to_vec(&transactions).expect("plan is serializable"));
+        Self { hash, transactions }
+    }
+
+    /// Fails if the hash of the plan differs from the reviewed one.
+    pub fn ensure_hash(&self, expected: B256) -> Result<()> {
+        if self.hash != expected {
+            bail!(
+                "The transaction plan changed since it was reviewed: expected hash {expected}, got {}.\nReview the new plan with `--plan-out` before broadcasting.",
+                self.hash
+            )
+        }
+        Ok(())
+    }
+
+    /// Writes the plan to `path`, as JSON if its extension is `json` and as Markdown otherwise.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        if path.extension().is_some_and(|ext| ext == "json") {
+            fs::write(path, serde_json::to_string_pretty(self)?)?;
+        } else {
+            fs::write(path, self.to_markdown())?;
+        }
+        Ok(())
+    }
+
+    /// Renders the plan as a Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Transaction plan\n");
+        let _ = writeln!(out, "Plan hash: `{}`\n", self.hash);
+        if self.transactions.is_empty() {
+            let _ = writeln!(out, "No transactions.");
+        }
+        for (i, tx) in self.transactions.iter().enumerate() {
+            let _ = writeln!(out, "## {}. {} on chain {}\n", i + 1, tx.title(), tx.chain);
+            if let Some(from) = tx.from {
+                let _ = writeln!(out, "- From: `{from}`");
+            }
+            if let Some(to) = tx.to {
+                let _ = writeln!(out, "- To: `{to}`");
+            }
+            if tx.kind != "call" {
+                if let Some(address) = tx.contract_address {
+                    let _ = writeln!(out, "- Predicted address: `{address}`");
+                }
+            }
+            if !tx.arguments.is_empty() {
+                let _ = writeln!(out, "- Arguments:");
+                for arg in &tx.arguments {
+                    let _ = writeln!(out, "  - `{arg}`");
+                }
+            }
+            let _ = writeln!(out, "- Value: {} wei", tx.value);
+            if !tx.additional_contracts.is_empty() {
+                let _ = writeln!(out, "- Additional contracts:");
+                for address in &tx.additional_contracts {
+                    let _ = writeln!(out, "  - `{address}`");
+                }
+            }
+            let _ = writeln!(out, "- Data: `{}`\n", tx.data);
+        }
+        out
+    }
+}
+
+fn kind_name(kind: CallKind) -> &'static str {
+    match kind {
+        CallKind::Create => "create",
+        CallKind::Create2 => "create2",
+        _ => "call",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::{transaction::eip2718::TypedTransaction, TransactionRequest};
+
+    fn sequence(value: u64) -> ScriptSequence {
+        let tx = TransactionWithMetadata {
+            opcode: CallKind::Call,
+            contract_name: Some("Counter".to_string()),
+            contract_address: Some(Address::with_last_byte(2)),
+            function: Some("setNumber(uint256)".to_string()),
+            arguments: Some(vec!["1".to_string()]),
+            transaction: TypedTransaction::Legacy(
+                TransactionRequest::new()
+                    .from(ethers_core::types::Address::from_low_u64_be(1))
+                    .to(ethers_core::types::Address::from_low_u64_be(2))
+                    .value(value)
+                    .gas(100_000),
+            ),
+            ..Default::default()
+        };
+        ScriptSequence { transactions: vec![tx].into(), chain: 1, ..Default::default() }
+    }
+
+    #[test]
+    fn plan_hash_ignores_gas() {
+        let plan = TransactionPlan::new(&[sequence(0)]);
+        let mut regassed = sequence(0);
+        regassed.transactions[0].transaction.set_gas(200_000);
+        assert_eq!(TransactionPlan::new(&[regassed]).hash, plan.hash);
+
+        let changed = TransactionPlan::new(&[sequence(1)]);
+        assert_ne!(changed.hash, plan.hash);
+        assert!(plan.ensure_hash(plan.hash).is_ok());
+        assert!(plan.ensure_hash(changed.hash).is_err());
+
+        let markdown = plan.to_markdown();
+        assert!(
+            markdown.contains("## 1. Call `Counter` `setNumber(uint256)` on chain 1"),
+            "{markdown}"
+        );
+        assert!(markdown.contains("  - `1`"), "{markdown}");
+    }
+}
@@ -0,0 +1,216 @@
+//! Estimation of the cost of a broadcast in USD
+
+use alloy_primitives::{address, Address, I256, U256};
+use ethers_core::types::{transaction::eip2718::TypedTransaction, BlockNumber, TransactionRequest};
+use ethers_providers::Middleware;
+use eyre::{Result, WrapErr};
+use foundry_common::types::ToEthers;
+use foundry_config::Chain;
+use std::{collections::HashMap, fmt, str::FromStr};
+
+/// The CoinGecko API endpoint for the USD price of a coin.
+const COINGECKO_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+/// The CoinGecko IDs of the native tokens of chains, by chain ID.
+///
+/// Testnets are omitted, since their tokens have no price.
+const COINGECKO_IDS: &[(u64, &str)] = &[
+    (1, "ethereum"),
+    (10, "ethereum"),
+    (56, "binancecoin"),
+    (100, "xdai"),
+    (137, "matic-network"),
+    (250, "fantom"),
+    (324, "ethereum"),
+    (8453, "ethereum"),
+    (42161, "ethereum"),
+    (42220, "celo"),
+    (43114, "avalanche-2"),
+    (59144, "ethereum"),
+    (534352, "ethereum"),
+];
+
+/// The Chainlink USD price feeds of the native tokens of chains, by chain ID.
+const CHAINLINK_FEEDS: &[(u64, Address)] =
+    &[(1, address!("5f4eC3Df9cbd43714FE2740F5E3616155c5b8419"))];
+
+/// The maximum age of a Chainlink answer in seconds, relative to the latest block.
+///
+/// This is longer than the heartbeat of the USD feeds of the native tokens, which update at least
+/// once an hour.
+const MAX_PRICE_AGE: u64 = 3 * 60 * 60;
+
+/// `latestRoundData()`
+const LATEST_ROUND_DATA_SELECTOR: [u8; 4] = [0xfe, 0xaf, 0x96, 0x8c];
+
+/// `decimals()`
+const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+
+/// A `--price-feed <CHAIN>=<ADDRESS>` argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceFeedArg {
+    /// The ID of the chain the feed is deployed on.
+    pub chain: u64,
+    /// The Chainlink USD price feed of the chain's native token.
+    pub feed: Address,
+}
+
+impl FromStr for PriceFeedArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (chain, feed) =
+            s.split_once('=').ok_or_else(|| format!("expected <CHAIN>=<ADDRESS>, got `{s}`"))?;
+        let chain = Chain::from_str(chain.trim())
+            .map_err(|err| format!("invalid chain `{chain}`: {err}"))?;
+        let feed = Address::from_str(feed.trim())
+            .map_err(|err| format!("invalid address `{feed}`: {err}"))?;
+        Ok(Self { chain: chain.id(), feed })
+    }
+}
+
+/// The source of the USD price of a chain's native token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum PriceSource {
+    /// The price API of CoinGecko.
+    Coingecko,
+    /// The Chainlink price feed of the native token, read from the chain itself.
+    Chainlink,
+}
+
+impl PriceSource {
+    /// Returns the USD price of the native token of the chain, or `None` if the source has no
+    /// price for the chain.
+    ///
+    /// `price_feeds` override the Chainlink price feeds of their chains.
+    pub async fn native_token_price<M: Middleware>(
+        &self,
+        chain: u64,
+        provider: &M,
+        price_feeds: &[PriceFeedArg],
+    ) -> Result<Option<f64>>
+    where
+        M::Error: 'static,
+    {
+        match self {
+            PriceSource::Coingecko => {
+                let Some(id) = lookup(COINGECKO_IDS, chain) else { return Ok(None) };
+                coingecko_price(id).await.map(Some)
+            }
+            PriceSource::Chainlink => {
+                let feed =
+                    price_feeds.iter().find(|feed| feed.chain == chain).map(|feed| feed.feed);
+                let Some(feed) = feed.or_else(|| lookup(CHAINLINK_FEEDS, chain)) else {
+                    return Ok(None)
+                };
+                chainlink_price(provider, feed).await.map(Some)
+            }
+        }
+    }
+}
+
+impl fmt::Display for PriceSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceSource::Coingecko => f.write_str("CoinGecko"),
+            PriceSource::Chainlink => f.write_str("Chainlink"),
+        }
+    }
+}
+
+fn lookup<T: Copy>(table: &[(u64, T)], chain: u64) -> Option<T> {
+    table.iter().find(|(id, _)| *id == chain).map(|(_, value)| *value)
+}
+
+async fn coingecko_price(id: &str) -> Result<f64> {
+    let prices: HashMap<String, HashMap<String, f64>> = reqwest::Client::new()
+        .get(COINGECKO_PRICE_URL)
+        .query(&[("ids", id), ("vs_currencies", "usd")])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .wrap_err("Failed to decode the CoinGecko response")?;
+    prices
+        .get(id)
+        .and_then(|prices| prices.get("usd"))
+        .copied()
+        .ok_or_else(|| eyre::eyre!("CoinGecko has no USD price of {id}"))
+}
+
+async fn chainlink_price<M: Middleware>(provider: &M, feed: Address) -> Result<f64>
+where
+    M::Error: 'static,
+{
+    let call = |selector: [u8; 4]| {
+        let tx: TypedTransaction =
+            TransactionRequest::new().to(feed.to_ethers()).data(selector.to_vec()).into();
+        async move { provider.call(&tx, None).await }
+    };
+
+    let round = call(LATEST_ROUND_DATA_SELECTOR).await?;
+    let decimals = call(DECIMALS_SELECTOR).await?;
+    // `answer` is the second word of `(roundId, answer, startedAt, updatedAt, answeredInRound)`
+    let (Some(answer), Some(updated_at), Some(decimals)) =
+        (round.get(32..64), round.get(96..128), decimals.get(..32))
+    else {
+        eyre::bail!("{feed} is not a Chainlink price feed");
+    };
+
+    let updated_at = U256::try_from_be_slice(updated_at).unwrap_or_default();
+    let now = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| eyre::eyre!("the latest block is not available"))?
+        .timestamp;
+    let age = U256::from(now.as_u64()).saturating_sub(updated_at);
+    if age > U256::from(MAX_PRICE_AGE) {
+        eyre::bail!("the answer of the Chainlink price feed {feed} is stale, updated {age}s ago");
+    }
+
+    let answer = I256::try_from_be_slice(answer).unwrap_or_default();
+    let decimals = U256::try_from_be_slice(decimals).unwrap_or_default();
+    if answer <= I256::ZERO || decimals > U256::from(36) {
+        eyre::bail!("invalid answer of the Chainlink price feed {feed}");
+    }
+    Ok(answer.to_string().parse::<f64>()? / 10f64.powi(decimals.to::<u8>() as i32))
+}
+
+/// Converts an amount in wei to the native token, as floating point number for display.
+pub fn wei_to_native(wei: U256) -> f64 {
+    ethers_core::utils::format_units(wei.to_ethers(), 18)
+        .ok()
+        .and_then(|amount| amount.parse().ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn knows_native_tokens() {
+        assert_eq!(lookup(COINGECKO_IDS, 1), Some("ethereum"));
+        assert_eq!(lookup(COINGECKO_IDS, 137), Some("matic-network"));
+        // testnets have no price
+        assert_eq!(lookup(COINGECKO_IDS, 11155111), None);
+        assert!(lookup(CHAINLINK_FEEDS, 1).is_some());
+    }
+
+    #[test]
+    fn can_parse_price_feed() {
+        let feed: PriceFeedArg = "1=0x5f4eC3Df9cbd43714FE2740F5E3616155c5b8419".parse().unwrap();
+        assert_eq!(feed.chain, 1);
+        let feed: PriceFeedArg =
+            "mainnet=0x5f4eC3Df9cbd43714FE2740F5E3616155c5b8419".parse().unwrap();
+        assert_eq!(feed.chain, 1);
+        assert!("0x5f4eC3Df9cbd43714FE2740F5E3616155c5b8419".parse::<PriceFeedArg>().is_err());
+    }
+
+    #[test]
+    fn converts_wei_to_native() {
+        assert_eq!(wei_to_native(U256::from(1_500_000_000_000_000_000u128)), 1.5);
+        assert_eq!(wei_to_native(U256::ZERO), 0.0);
+    }
+}
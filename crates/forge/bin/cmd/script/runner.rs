@@ -1,4 +1,7 @@
-use super::*;
+use super::{
+    authorization::{Authorization, AuthorizeArg},
+    *,
+};
 use alloy_primitives::{Address, Bytes, U256};
 use ethers_core::types::NameOrAddress;
 use eyre::Result;
@@ -28,6 +31,46 @@ impl ScriptRunner {
         Self { executor, initial_balance, sender }
     }
 
+    /// Applies the EIP-7702 delegations of `args` before the transaction of `sender` that carries
+    /// them is simulated, and returns the authorizations with the nonces of their authorities.
+    ///
+    /// The EVM doesn't support delegations yet, so the code of each delegate is copied to its
+    /// authority, which executes calls to the authority like the delegation does.
+    pub fn authorize(
+        &mut self,
+        args: &[AuthorizeArg],
+        sender: Address,
+    ) -> Result<Vec<Authorization>> {
+        let chain_id = self.executor.env.cfg.chain_id;
+        let mut nonces = HashMap::new();
+        let mut authorizations = Vec::with_capacity(args.len());
+        for arg in args {
+            let nonce = match nonces.get(&arg.authority) {
+                Some(nonce) => *nonce,
+                // the nonce of the sender is incremented before the authorizations are applied
+                None => {
+                    self.executor.get_nonce(arg.authority)? + u64::from(arg.authority == sender)
+                }
+            };
+            // every applied authorization increments the nonce of its authority
+            nonces.insert(arg.authority, nonce + 1);
+
+            let code = self.executor.get_code(arg.delegate)?;
+            self.executor.set_code(arg.authority, code)?;
+            authorizations.push(Authorization {
+                authority: arg.authority,
+                chain_id,
+                address: arg.delegate,
+                nonce,
+            });
+        }
+        for (authority, nonce) in nonces {
+            // the simulation of the transaction increments the nonce of the sender itself
+            self.executor.set_nonce(authority, nonce - u64::from(authority == sender))?;
+        }
+        Ok(authorizations)
+    }
+
     /// Deploys the libraries and broadcast contract. Calls setUp method if requested.
     pub fn setup(
         &mut self,
@@ -5,19 +5,23 @@ use crate::cmd::{
         transaction::{wrapper, AdditionalContract, TransactionWithMetadata},
         verify::VerifyBundle,
     },
-    verify::provider::VerificationProviderType,
+    verify::{provider::VerificationProviderType, VerifyArgs},
 };
-use alloy_primitives::{Address, TxHash};
+use alloy_primitives::{keccak256, Address, TxHash};
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
 use ethers_core::types::{transaction::eip2718::TypedTransaction, TransactionReceipt};
+use ethers_providers::Middleware;
 use eyre::{ContextCompat, Result, WrapErr};
 use foundry_cli::utils::now;
 use foundry_common::{
+    deployments::{DeploymentManifest, DeploymentRegistry},
     fs, shell,
     types::{ToAlloy, ToEthers},
-    SELECTOR_LEN,
+    RetryProvider, SELECTOR_LEN,
 };
 use foundry_compilers::{artifacts::Libraries, ArtifactId};
 use foundry_config::Config;
+use foundry_evm::utils::CallKind;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, VecDeque},
@@ -41,6 +45,9 @@ pub struct ScriptSequence {
     pub path: PathBuf,
     #[serde(skip)]
     pub sensitive_path: PathBuf,
+    /// The project root, under which the deployment manifests are written
+    #[serde(skip)]
+    pub root: PathBuf,
     pub returns: HashMap<String, NestedValue>,
     pub timestamp: u64,
     pub chain: u64,
@@ -103,6 +110,7 @@ impl ScriptSequence {
             pending: vec![],
             path,
             sensitive_path,
+            root: config.__root.0.clone(),
             timestamp: now().as_secs(),
             libraries: vec![],
             chain,
@@ -144,6 +152,7 @@ impl ScriptSequence {
 
         script_sequence.path = path;
         script_sequence.sensitive_path = sensitive_path;
+        script_sequence.root = config.__root.0.clone();
 
         Ok(script_sequence)
     }
@@ -181,6 +190,52 @@ impl ScriptSequence {
         Ok(())
     }
 
+    /// Writes a manifest for every contract deployed by the sequence to the
+    /// [DeploymentRegistry] of the project.
+    ///
+    /// Only successful deployments of known contracts are recorded.
+    pub async fn save_deployments(&self, provider: &RetryProvider) -> Result<()> {
+        let registry = DeploymentRegistry::new(&self.root);
+        for tx in &self.transactions {
+            if !matches!(tx.opcode, CallKind::Create | CallKind::Create2) {
+                continue
+            }
+            let (Some(contract_name), Some(address), Some(hash)) =
+                (&tx.contract_name, tx.contract_address, tx.hash)
+            else {
+                continue
+            };
+            if contract_name.is_empty() {
+                continue
+            }
+            let Some(receipt) =
+                self.receipts.iter().find(|receipt| receipt.transaction_hash.to_alloy() == hash)
+            else {
+                continue
+            };
+            if receipt.status != Some(1u64.into()) {
+                continue
+            }
+
+            let code = provider
+                .get_code(address.to_ethers(), receipt.block_number.map(Into::into))
+                .await?;
+            let manifest = DeploymentManifest {
+                contract_name: contract_name.clone(),
+                address,
+                transaction_hash: hash,
+                arguments: tx.arguments.clone().unwrap_or_default(),
+                bytecode_hash: keccak256(code),
+                commit: self.commit.clone(),
+                timestamp: self.timestamp,
+            };
+            let path = registry.save(self.chain, &manifest)?;
+            shell::println(format!("Deployment of {contract_name} saved to: {}", path.display()))?;
+        }
+
+        Ok(())
+    }
+
     pub fn add_receipt(&mut self, receipt: TransactionReceipt) {
         self.receipts.push(receipt);
     }
@@ -299,7 +354,7 @@ impl ScriptSequence {
                     (receipt.contract_address.map(|h| h.to_alloy()), tx.typed_tx().data())
                 {
                     match verify.get_verify_args(address, offset, &data.0, &self.libraries) {
-                        Some(verify) => future_verifications.push(verify.run()),
+                        Some(verify) => future_verifications.push(verification(verify)),
                         None => unverifiable_contracts.push(address),
                     };
                 }
@@ -307,7 +362,7 @@ impl ScriptSequence {
                 // Verify potential contracts created during the transaction execution
                 for AdditionalContract { address, init_code, .. } in &tx.additional_contracts {
                     match verify.get_verify_args(*address, 0, init_code, &self.libraries) {
-                        Some(verify) => future_verifications.push(verify.run()),
+                        Some(verify) => future_verifications.push(verification(verify)),
                         None => unverifiable_contracts.push(*address),
                     };
                 }
@@ -319,10 +374,17 @@ impl ScriptSequence {
 
             let num_verifications = future_verifications.len();
             println!("##\nStart verification for ({num_verifications}) contracts",);
+            // a failed verification doesn't stop the remaining ones
+            let mut results = Vec::with_capacity(num_verifications);
             for verification in future_verifications {
-                verification.await?;
+                results.push(verification.await);
             }
 
+            let failed = results.iter().filter(|result| result.outcome.is_err()).count();
+            println!("\n{}", verification_report(&results));
+            if failed > 0 {
+                eyre::bail!("{failed} of ({num_verifications}) contracts failed to verify")
+            }
             println!("All ({num_verifications}) contracts were verified!");
         }
 
@@ -375,6 +437,41 @@ impl Drop for ScriptSequence {
     }
 }
 
+/// The result of the verification of a deployed contract.
+struct VerificationResult {
+    name: String,
+    address: Address,
+    /// The ID of the submission, e.g. the GUID on Etherscan, or the error.
+    outcome: Result<Option<String>>,
+}
+
+/// Verifies a contract, returning the result instead of failing.
+async fn verification(verify: VerifyArgs) -> VerificationResult {
+    let name = verify.contract.name.clone();
+    let address = verify.address;
+    VerificationResult { name, address, outcome: verify.submit().await }
+}
+
+/// Returns a table of the status and submission ID of every verified contract.
+fn verification_report(results: &[VerificationResult]) -> Table {
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    table.set_header(["Contract", "Address", "Status", "GUID"]);
+    for VerificationResult { name, address, outcome } in results {
+        let (status, id) = match outcome {
+            Ok(id) => ("verified".to_string(), id.clone()),
+            Err(err) => (format!("failed: {err:#}"), None),
+        };
+        table.add_row([
+            name.clone(),
+            address.to_string(),
+            status,
+            id.unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table
+}
+
 /// Converts the `sig` argument into the corresponding file path.
 ///
 /// This accepts either the signature of the function or the raw calldata
@@ -398,6 +495,27 @@ fn sig_to_file_name(sig: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn can_report_verifications() {
+        let results = [
+            VerificationResult {
+                name: "Counter".to_string(),
+                address: Address::repeat_byte(1),
+                outcome: Ok(Some("guid".to_string())),
+            },
+            VerificationResult {
+                name: "Token".to_string(),
+                address: Address::repeat_byte(2),
+                outcome: Err(eyre::eyre!("Contract failed to verify")),
+            },
+        ];
+        let report = verification_report(&results).to_string();
+        let lines = report.lines().collect::<Vec<_>>();
+        assert!(lines[2].contains("Counter") && lines[2].contains("verified"), "{report}");
+        assert!(lines[2].contains("guid"), "{report}");
+        assert!(lines[3].contains("failed: Contract failed to verify"), "{report}");
+    }
+
     #[test]
     fn can_convert_sig() {
         assert_eq!(sig_to_file_name("run()").as_str(), "run");
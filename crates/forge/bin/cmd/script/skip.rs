@@ -0,0 +1,148 @@
+//! On-chain checks of `forge script --skip-deployed`, which skips the transactions whose effect
+//! already exists so that scripts can be re-run safely.
+
+use super::transaction::TransactionWithMetadata;
+use alloy_primitives::{Address, B256, U256};
+use ethers_providers::Middleware;
+use eyre::Result;
+use foundry_common::{
+    types::{ToAlloy, ToEthers},
+    RetryProvider,
+};
+use foundry_evm::utils::CallKind;
+use std::str::FromStr;
+
+/// A `--skip-if <TARGET>[.<FUNCTION>]:<SLOT>=<VALUE>` argument: calls to the target, optionally
+/// only of the given function, are skipped if the storage slot of the target holds the value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkipIfArg {
+    /// The called contract, whose storage is read.
+    pub target: Address,
+    /// The name or signature of the called function, any function of the target if `None`.
+    pub function: Option<String>,
+    /// The storage slot of the target.
+    pub slot: U256,
+    /// The value of the slot once the call was applied.
+    pub value: U256,
+}
+
+impl SkipIfArg {
+    /// Returns whether the predicate applies to a call of `function` on `to`.
+    fn matches(&self, to: Address, function: Option<&str>) -> bool {
+        if self.target != to {
+            return false
+        }
+        match (&self.function, function) {
+            (None, _) => true,
+            (Some(expected), Some(function)) => {
+                expected == function || function.split('(').next() == Some(expected.as_str())
+            }
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl FromStr for SkipIfArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected <TARGET>[.<FUNCTION>]:<SLOT>=<VALUE>, got `{s}`");
+        let (call, predicate) = s.split_once(':').ok_or_else(invalid)?;
+        let (slot, value) = predicate.split_once('=').ok_or_else(invalid)?;
+        let (target, function) = match call.split_once('.') {
+            Some((target, function)) if !function.trim().is_empty() => {
+                (target, Some(function.trim().to_string()))
+            }
+            Some(_) => return Err(invalid()),
+            None => (call, None),
+        };
+        let parse = |word: &str| {
+            U256::from_str(word.trim()).map_err(|err| format!("invalid word `{word}`: {err}"))
+        };
+        Ok(Self {
+            target: Address::from_str(target.trim())
+                .map_err(|err| format!("invalid address `{target}`: {err}"))?,
+            function,
+            slot: parse(slot)?,
+            value: parse(value)?,
+        })
+    }
+}
+
+/// Checks whether the effect of the call already exists on chain, and returns why if so.
+///
+/// Calls are applied if they match at least one `--skip-if` predicate and all the matching
+/// predicates hold. Contract creations are never skipped here: `CREATE2` deployments that already
+/// exist are skipped during the simulation, and the address of a `CREATE` depends on the nonce.
+pub async fn already_applied(
+    provider: &RetryProvider,
+    tx: &TransactionWithMetadata,
+    predicates: &[SkipIfArg],
+) -> Result<Option<String>> {
+    if matches!(tx.opcode, CallKind::Create | CallKind::Create2) {
+        return Ok(None)
+    }
+    let Some(to) = tx.typed_tx().to().and_then(|to| to.as_address()) else { return Ok(None) };
+    let to = to.to_alloy();
+    let function = tx.function.as_deref().filter(|function| !function.is_empty());
+    let mut matching =
+        predicates.iter().filter(|predicate| predicate.matches(to, function)).peekable();
+    if matching.peek().is_none() {
+        return Ok(None)
+    }
+    for predicate in matching {
+        let slot = B256::from(predicate.slot).to_ethers();
+        let value = provider.get_storage_at(to.to_ethers(), slot, None).await?;
+        if U256::from_be_bytes(value.to_alloy().0) != predicate.value {
+            return Ok(None)
+        }
+    }
+    let name = tx.contract_name.as_deref().filter(|name| !name.is_empty()).unwrap_or("contract");
+    let call = function.unwrap_or("call");
+    Ok(Some(format!("{call} on {name} at {to} is already applied")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_skip_if_arg() {
+        let arg: SkipIfArg = "0x0000000000000000000000000000000000000001:0x2=42".parse().unwrap();
+        assert_eq!(
+            arg,
+            SkipIfArg {
+                target: Address::with_last_byte(1),
+                function: None,
+                slot: U256::from(2),
+                value: U256::from(42),
+            }
+        );
+
+        let arg: SkipIfArg =
+            "0x0000000000000000000000000000000000000001.setOwner:0=0x01".parse().unwrap();
+        assert_eq!(arg.function.as_deref(), Some("setOwner"));
+        assert_eq!(arg.value, U256::from(1));
+
+        assert!("0x0000000000000000000000000000000000000001:0".parse::<SkipIfArg>().is_err());
+        assert!("0x0000000000000000000000000000000000000001.:0=1".parse::<SkipIfArg>().is_err());
+        assert!("owner:0=1".parse::<SkipIfArg>().is_err());
+    }
+
+    #[test]
+    fn matches_calls_by_target_and_function() {
+        let mut arg: SkipIfArg = "0x0000000000000000000000000000000000000001:0=1".parse().unwrap();
+        let target = Address::with_last_byte(1);
+        assert!(arg.matches(target, None));
+        assert!(arg.matches(target, Some("setOwner(address)")));
+        assert!(!arg.matches(Address::with_last_byte(2), None));
+
+        arg.function = Some("setOwner".to_string());
+        assert!(arg.matches(target, Some("setOwner(address)")));
+        assert!(!arg.matches(target, Some("setOwnerOf(address)")));
+        assert!(!arg.matches(target, None));
+
+        arg.function = Some("setOwner(address)".to_string());
+        assert!(arg.matches(target, Some("setOwner(address)")));
+    }
+}
@@ -1,4 +1,4 @@
-use super::{artifacts::ArtifactInfo, ScriptResult};
+use super::{artifacts::ArtifactInfo, authorization::Authorization, ScriptResult};
 use alloy_dyn_abi::JsonAbiExt;
 use alloy_json_abi::Function;
 use alloy_primitives::{Address, Bytes, B256};
@@ -42,6 +42,9 @@ pub struct TransactionWithMetadata {
     pub transaction: TypedTransaction,
     pub additional_contracts: Vec<AdditionalContract>,
     pub is_fixed_gas_limit: bool,
+    /// The EIP-7702 authorizations the transaction carries, signed right before broadcasting.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authorizations: Vec<Authorization>,
 }
 
 fn default_string() -> Option<String> {
@@ -0,0 +1,55 @@
+use clap::Parser;
+use eyre::Result;
+use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
+use foundry_common::compile::{self, ProjectCompiler, SizeReport};
+
+foundry_config::impl_figment_convert!(SizeArgs, opts);
+
+/// CLI arguments for `forge size`.
+#[derive(Debug, Clone, Parser)]
+pub struct SizeArgs {
+    /// Also include test and script contracts in the report.
+    #[clap(long)]
+    include_dev: bool,
+
+    /// Exit with an error if a contract exceeds the EIP-170 runtime or the EIP-3860 initcode size
+    /// limit, excluding test and script contracts.
+    #[clap(long)]
+    check: bool,
+
+    /// Print the report as JSON.
+    #[clap(long, short)]
+    json: bool,
+
+    #[clap(flatten)]
+    opts: CoreBuildArgs,
+}
+
+impl SizeArgs {
+    pub fn run(self) -> Result<()> {
+        let config = self.try_load_config_emit_warnings()?;
+        let project = config.project()?;
+        let output = if self.json || self.opts.silent {
+            compile::suppress_compile(&project)?
+        } else {
+            ProjectCompiler::default().compile(&project)?
+        };
+
+        let mut report = SizeReport::new(&output);
+        if !self.include_dev {
+            report.exclude_dev_contracts();
+        }
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report.contracts)?);
+        } else {
+            println!("{report}");
+        }
+
+        if self.check {
+            report.check()?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,17 +1,19 @@
 use super::{install, test::filter::ProjectPathsAwareFilter, watch::WatchArgs};
-use alloy_primitives::U256;
-use clap::Parser;
+use alloy_primitives::{Address, U256};
+use clap::{Parser, ValueEnum, ValueHint};
 use eyre::Result;
 use forge::{
     decode::decode_console_logs,
     gas_report::GasReport,
     inspectors::CheatsConfig,
+    opts::EvmOpts,
+    order::{TestOrder, TestTimings},
     result::{SuiteResult, TestResult, TestStatus},
     traces::{
         identifier::{EtherscanIdentifier, LocalTraceIdentifier, SignaturesIdentifier},
         CallTraceDecoderBuilder, TraceKind,
     },
-    MultiContractRunner, MultiContractRunnerBuilder, TestOptions, TestOptionsBuilder,
+    MultiContractRunner, MultiContractRunnerBuilder, TestOptions, TestOptionsBuilder, UsageOptions,
 };
 use foundry_cli::{
     opts::CoreBuildArgs,
@@ -21,8 +23,9 @@ use foundry_common::{
     compact_to_contract,
     compile::{self, ContractSources, ProjectCompiler},
     evm::EvmArgs,
-    get_contract_name, get_file_name, shell,
+    get_contract_name, get_file_name, shell, vyper,
 };
+use foundry_compilers::{Artifact, ProjectCompileOutput};
 use foundry_config::{
     figment,
     figment::{
@@ -33,12 +36,20 @@ use foundry_config::{
 };
 use foundry_debugger::Debugger;
 use regex::Regex;
-use std::{collections::BTreeMap, fs, sync::mpsc::channel, time::Duration};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{mpsc::channel, Arc},
+    time::Duration,
+};
 use watchexec::config::{InitConfig, RuntimeConfig};
 use yansi::Paint;
 
 mod filter;
+mod matrix;
 mod summary;
+use matrix::ForkEndpoint;
 use summary::TestSummaryReporter;
 
 pub use filter::FilterArgs;
@@ -72,6 +83,21 @@ pub struct TestArgs {
     #[clap(long, env = "FORGE_GAS_REPORT")]
     gas_report: bool,
 
+    /// Report the peak memory expansion and call depth of every test, the maximum over all runs
+    /// for fuzz and invariant tests.
+    ///
+    /// Tests exceeding the `max_call_depth` or `max_memory_expansion` limits of the config fail,
+    /// regardless of this flag.
+    #[clap(long)]
+    pub usage: bool,
+
+    /// Run every top-level call of a test as a separate transaction.
+    ///
+    /// Each call then has its own nonce and gas metering and starts with cold storage, like it
+    /// would on chain.
+    #[clap(long)]
+    pub isolate: bool,
+
     /// Exit with code 0 even if a test fails.
     #[clap(long, env = "FORGE_ALLOW_FAILURE")]
     allow_failure: bool,
@@ -80,10 +106,31 @@ pub struct TestArgs {
     #[clap(long, short, help_heading = "Display options")]
     json: bool,
 
+    /// Write the labels of all labeled addresses to the given JSON file, per test suite.
+    ///
+    /// Includes the labels set with `vm.label` and the names of the deployed test contracts.
+    #[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Display options")]
+    export_labels: Option<PathBuf>,
+
     /// Stop running tests after the first failure.
+    ///
+    /// Tests that are running in parallel finish, but no more tests are started.
     #[clap(long)]
     pub fail_fast: bool,
 
+    /// The order in which test suites and tests are started.
+    ///
+    /// `slowest-first` starts the tests that took the longest in the previous runs first, which
+    /// shortens the run on many cores. `random` shuffles the tests, see `--order-seed`.
+    #[clap(long, value_enum, default_value_t = TestOrderKind::Alphabetical)]
+    pub order: TestOrderKind,
+
+    /// The seed to shuffle the tests with `--order random`.
+    ///
+    /// If not set, a random seed is generated and printed, so that the order can be reproduced.
+    #[clap(long, value_name = "SEED")]
+    pub order_seed: Option<u64>,
+
     /// The Etherscan (or equivalent) API key.
     #[clap(long, env = "ETHERSCAN_API_KEY", value_name = "KEY")]
     etherscan_api_key: Option<String>,
@@ -93,12 +140,20 @@ pub struct TestArgs {
     list: bool,
 
     /// Set seed used to generate randomness during your fuzz runs.
+    ///
+    /// If not set, a random seed is generated and printed for every failing fuzz or invariant
+    /// test, so that the failure can be replayed by passing it back to this flag.
     #[clap(long)]
     pub fuzz_seed: Option<U256>,
 
     #[clap(long, env = "FOUNDRY_FUZZ_RUNS", value_name = "RUNS")]
     pub fuzz_runs: Option<u64>,
 
+    /// Guide the fuzzer with the branches taken by the inputs, prioritizing the inputs that reach
+    /// new code.
+    #[clap(long)]
+    pub fuzz_coverage_guided: bool,
+
     #[clap(flatten)]
     filter: FilterArgs,
 
@@ -138,7 +193,18 @@ impl TestArgs {
     /// configured filter will be executed
     ///
     /// Returns the test results for all matching tests.
-    pub async fn execute_tests(self) -> Result<TestOutcome> {
+    pub async fn execute_tests(mut self) -> Result<TestOutcome> {
+        // A `--fork-url` with multiple endpoints runs the tests once against each of them, so it
+        // can't be merged into the config as is
+        let fork_matrix = self.fork_matrix()?;
+        if !fork_matrix.is_empty() {
+            if self.debug.is_some() || self.list {
+                eyre::bail!("--debug and --list can't be used with multiple --fork-url endpoints");
+            }
+            self.evm_opts.fork_url = None;
+            self.evm_opts.fork_block_number = None;
+        }
+
         // Merge all configs
         let (mut config, mut evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
 
@@ -165,16 +231,32 @@ impl TestArgs {
             (false, true) => compile::suppress_compile(&project),
             (true, true) => compile::suppress_compile_sparse(&project, filter.clone()),
         }?;
+        compile::check_settings_fingerprints(&project, &output, config.deny_settings_mismatch)?;
+
         // Create test options from general project settings
         // and compiler output
         let project_root = &project.paths.root;
         let toml = config.get_config_path();
         let profiles = get_available_profiles(toml)?;
 
+        // Always fuzz with a known seed, so that any failing fuzz or invariant case can be
+        // replayed exactly with `--fuzz-seed`, including on another machine.
+        if config.fuzz.seed.is_none() {
+            config.fuzz.seed = Some(U256::from_be_bytes(rand::random::<[u8; 32]>()));
+        }
+
+        let timings_path = TestTimings::path(&config.cache_path);
         let test_options: TestOptions = TestOptionsBuilder::default()
+            .order(self.test_order(&timings_path))
+            .fail_fast(self.fail_fast)
             .fuzz(config.fuzz)
             .invariant(config.invariant)
             .profiles(profiles)
+            .usage(UsageOptions {
+                report: self.usage,
+                max_call_depth: config.max_call_depth,
+                max_memory_expansion: config.max_memory_expansion,
+            })
             .build(&output, project_root)?;
 
         // Determine print verbosity and executor verbosity
@@ -195,7 +277,30 @@ impl TestArgs {
             .sender(evm_opts.sender)
             .with_fork(evm_opts.get_fork(&config, env.clone()))
             .with_cheats_config(CheatsConfig::new(&config, evm_opts.clone()))
-            .with_test_options(test_options.clone());
+            .with_test_options(test_options.clone())
+            .with_extra_contracts(
+                vyper::project_artifacts(&project.paths)?
+                    .into_iter()
+                    .map(|(id, artifact)| (id, artifact.into_contract_bytecode())),
+            );
+
+        if !fork_matrix.is_empty() {
+            let outcome = self
+                .run_fork_matrix(
+                    &fork_matrix,
+                    runner_builder,
+                    &config,
+                    &evm_opts,
+                    project_root,
+                    output,
+                    verbosity,
+                    &filter,
+                    test_options,
+                )
+                .await?;
+            self.export_labels(&outcome)?;
+            return Ok(outcome)
+        }
 
         let runner = runner_builder.clone().build(
             project_root,
@@ -223,6 +328,17 @@ impl TestArgs {
             .run_tests(runner, config.clone(), verbosity, &filter, test_options.clone())
             .await?;
 
+        // Remember how long the tests took, for `--order slowest-first`
+        if config.cache && !should_debug {
+            let mut timings = TestTimings::read(&timings_path);
+            timings.record(&outcome.results);
+            if let Err(err) = timings.write(&timings_path) {
+                trace!(target: "forge::test", ?err, "failed to write test timings");
+            }
+        }
+
+        self.export_labels(&outcome)?;
+
         if should_debug {
             let tests = outcome.clone().into_tests();
             let mut decoders = Vec::new();
@@ -318,6 +434,97 @@ impl TestArgs {
         Ok(outcome)
     }
 
+    /// Returns the endpoints of a `--fork-url` with multiple comma separated values.
+    ///
+    /// A single endpoint is returned as is by the config, so an empty list is returned for it.
+    fn fork_matrix(&self) -> Result<Vec<ForkEndpoint>> {
+        match &self.evm_opts.fork_url {
+            Some(urls) if urls.contains(',') => urls
+                .split(',')
+                .map(|url| url.trim().parse().map_err(|err: String| eyre::eyre!(err)))
+                .collect(),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Writes the labels of the outcome to the file of `--export-labels`.
+    fn export_labels(&self, outcome: &TestOutcome) -> Result<()> {
+        if let Some(path) = &self.export_labels {
+            foundry_common::fs::write_json_file(path, &outcome.labels())?;
+        }
+        Ok(())
+    }
+
+    /// Runs the tests once against every endpoint of a multi-valued `--fork-url`.
+    ///
+    /// The suite names of the results are suffixed with the endpoint they ran against.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_fork_matrix(
+        &self,
+        fork_matrix: &[ForkEndpoint],
+        runner_builder: MultiContractRunnerBuilder,
+        config: &Config,
+        evm_opts: &EvmOpts,
+        project_root: &Path,
+        output: ProjectCompileOutput,
+        verbosity: u8,
+        filter: &ProjectPathsAwareFilter,
+        test_options: TestOptions,
+    ) -> Result<TestOutcome> {
+        let mut results = BTreeMap::new();
+        let mut fork_summaries = Vec::with_capacity(fork_matrix.len());
+        let mut labels = HashSet::new();
+
+        for (i, endpoint) in fork_matrix.iter().enumerate() {
+            // endpoints are displayed without their API keys, which could make them ambiguous
+            let mut label = endpoint.to_string();
+            if !labels.insert(label.clone()) {
+                label = format!("{label}#{i}");
+                labels.insert(label.clone());
+            }
+
+            let mut evm_opts = evm_opts.clone();
+            evm_opts.fork_url = Some(endpoint.resolve(config)?);
+            evm_opts.fork_block_number = endpoint.block;
+            let env = evm_opts.evm_env().await?;
+
+            let runner = runner_builder
+                .clone()
+                .with_fork(evm_opts.get_fork(config, env.clone()))
+                .with_cheats_config(CheatsConfig::new(config, evm_opts.clone()))
+                .build(project_root, output.clone(), env, evm_opts)?;
+
+            if !self.json {
+                println!("\n{}", Paint::cyan(format!("Running tests against fork {label}")).bold());
+            }
+            let outcome = self
+                .run_tests(runner, config.clone(), verbosity, filter, test_options.clone())
+                .await?;
+
+            fork_summaries.push(format!(
+                "{label}: {} passed; {} failed; {} skipped",
+                outcome.successes().count(),
+                outcome.failures().count(),
+                outcome.skips().count()
+            ));
+            results.extend(
+                outcome
+                    .results
+                    .into_iter()
+                    .map(|(name, suite)| (format!("{name} [{label}]"), suite)),
+            );
+        }
+
+        if !self.json {
+            println!("\n{}", Paint::new("Fork matrix:").bold());
+            for summary in fork_summaries {
+                println!("  {summary}");
+            }
+        }
+
+        Ok(TestOutcome::new(results, self.allow_failure))
+    }
+
     /// Run all tests that matches the filter predicate from a test runner
     pub async fn run_tests(
         &self,
@@ -390,6 +597,7 @@ impl TestArgs {
         let mut total_passed = 0;
         let mut total_failed = 0;
         let mut total_skipped = 0;
+        let mut total_expected_failures = 0;
         let mut suite_results: Vec<TestOutcome> = Vec::new();
 
         'outer: for (contract_name, suite_result) in rx {
@@ -491,6 +699,7 @@ impl TestArgs {
             total_passed += block_outcome.successes().count();
             total_failed += block_outcome.failures().count();
             total_skipped += block_outcome.skips().count();
+            total_expected_failures += block_outcome.expected_failures().count();
 
             println!("{}", block_outcome.summary());
 
@@ -512,7 +721,8 @@ impl TestArgs {
                     num_test_suites,
                     total_passed,
                     total_failed,
-                    total_skipped
+                    total_skipped,
+                    total_expected_failures
                 )
             );
 
@@ -536,6 +746,24 @@ impl TestArgs {
         Ok(TestOutcome::new(results, self.allow_failure))
     }
 
+    /// Returns the order in which to start the tests, reading the timings of previous runs from
+    /// the given path for `--order slowest-first`.
+    fn test_order(&self, timings_path: &Path) -> TestOrder {
+        match self.order {
+            TestOrderKind::Alphabetical => TestOrder::Alphabetical,
+            TestOrderKind::Random => {
+                let seed = self.order_seed.unwrap_or_else(rand::random);
+                if !self.json {
+                    println!("Running tests in random order with `--order-seed {seed}`");
+                }
+                TestOrder::Random { seed }
+            }
+            TestOrderKind::SlowestFirst => {
+                TestOrder::SlowestFirst(Arc::new(TestTimings::read(timings_path)))
+            }
+        }
+    }
+
     /// Returns the flattened [`FilterArgs`] arguments merged with [`Config`].
     pub fn filter(&self, config: &Config) -> ProjectPathsAwareFilter {
         self.filter.merge_with_config(config)
@@ -571,8 +799,15 @@ impl Provider for TestArgs {
         if let Some(fuzz_runs) = self.fuzz_runs {
             fuzz_dict.insert("runs".to_string(), fuzz_runs.into());
         }
+        if self.fuzz_coverage_guided {
+            fuzz_dict.insert("coverage_guided".to_string(), true.into());
+        }
         dict.insert("fuzz".to_string(), fuzz_dict.into());
 
+        if self.isolate {
+            dict.insert("isolate".to_string(), true.into());
+        }
+
         if let Some(ref etherscan_api_key) = self.etherscan_api_key {
             dict.insert("etherscan_api_key".to_string(), etherscan_api_key.to_string().into());
         }
@@ -581,6 +816,18 @@ impl Provider for TestArgs {
     }
 }
 
+/// The order in which tests are started, see [TestOrder].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TestOrderKind {
+    /// By name.
+    #[default]
+    Alphabetical,
+    /// Shuffled with the `--order-seed`.
+    Random,
+    /// By descending duration in previous runs.
+    SlowestFirst,
+}
+
 /// The result of a single test
 #[derive(Debug, Clone)]
 pub struct Test {
@@ -633,15 +880,39 @@ impl TestOutcome {
         self.tests().filter(|(_, t)| t.status == TestStatus::Failure)
     }
 
+    /// Iterator over all skipped tests and their names
     pub fn skips(&self) -> impl Iterator<Item = (&String, &TestResult)> {
         self.tests().filter(|(_, t)| t.status == TestStatus::Skipped)
     }
 
+    /// Iterator over all tests that failed as expected and their names
+    pub fn expected_failures(&self) -> impl Iterator<Item = (&String, &TestResult)> {
+        self.tests().filter(|(_, t)| t.status == TestStatus::ExpectedFailure)
+    }
+
     /// Iterator over all tests and their names
     pub fn tests(&self) -> impl Iterator<Item = (&String, &TestResult)> {
         self.results.values().flat_map(|suite| suite.tests())
     }
 
+    /// Returns the labels of all labeled addresses, per test suite
+    ///
+    /// The labels are kept apart per suite, since the same address can be labeled differently by
+    /// different suites.
+    pub fn labels(&self) -> BTreeMap<String, BTreeMap<Address, String>> {
+        self.results
+            .iter()
+            .map(|(name, suite)| {
+                let labels = suite
+                    .tests()
+                    .flat_map(|(_, result)| result.labeled_addresses.iter())
+                    .map(|(address, label)| (*address, label.clone()))
+                    .collect();
+                (name.clone(), labels)
+            })
+            .collect()
+    }
+
     /// Returns an iterator over all `Test`
     pub fn into_tests(self) -> impl Iterator<Item = Test> {
         self.results
@@ -699,18 +970,40 @@ impl TestOutcome {
         let failed = self.failures().count();
         let result = if failed == 0 { Paint::green("ok") } else { Paint::red("FAILED") };
         format!(
-            "Test result: {}. {} passed; {} failed; {} skipped; finished in {:.2?}",
+            "Test result: {}. {} passed; {} failed; {} skipped;{} finished in {:.2?}",
             result,
             Paint::green(self.successes().count()),
             Paint::red(failed),
             Paint::yellow(self.skips().count()),
+            format_expected_failures(self.expected_failures().count()),
             self.duration()
         )
     }
 }
 
 fn short_test_result(name: &str, result: &TestResult) {
-    println!("{result} {name} {}", result.kind.report());
+    match &result.usage {
+        Some(usage) => println!(
+            "{result} {name} {} (depth: {}, memory: {} bytes)",
+            result.kind.report(),
+            usage.max_depth,
+            usage.max_memory
+        ),
+        None => println!("{result} {name} {}", result.kind.report()),
+    }
+    if result.status.is_failure() && result.counterexample.is_some() {
+        if let Some(seed) = result.seed {
+            println!("  Replay this failure with `--fuzz-seed {seed}`");
+        }
+    }
+}
+
+/// Formats the number of expected failures for the summaries, which is omitted if there are none.
+fn format_expected_failures(expected_failures: usize) -> String {
+    if expected_failures == 0 {
+        return String::new()
+    }
+    format!(" {} expected to fail;", Paint::yellow(expected_failures))
 }
 
 /// Formats the aggregated summary of all test suites into a string (for printing).
@@ -719,14 +1012,21 @@ fn format_aggregated_summary(
     total_passed: usize,
     total_failed: usize,
     total_skipped: usize,
+    total_expected_failures: usize,
 ) -> String {
-    let total_tests = total_passed + total_failed + total_skipped;
+    let total_tests = total_passed + total_failed + total_skipped + total_expected_failures;
+    let expected_failures = if total_expected_failures > 0 {
+        format!(", {} expected to fail", Paint::yellow(total_expected_failures))
+    } else {
+        String::new()
+    };
     format!(
-        " \nRan {} test suites: {} tests passed, {} failed, {} skipped ({} total tests)",
+        " \nRan {} test suites: {} tests passed, {} failed, {} skipped{} ({} total tests)",
         num_test_suites,
         Paint::green(total_passed),
         Paint::red(total_failed),
         Paint::yellow(total_skipped),
+        expected_failures,
         total_tests
     )
 }
@@ -770,6 +1070,30 @@ mod tests {
         assert!(args.fuzz_seed.is_some());
     }
 
+    #[test]
+    fn test_order() {
+        let args: TestArgs =
+            TestArgs::parse_from(["foundry-cli", "--order", "random", "--order-seed", "7"]);
+        assert_eq!(args.order, TestOrderKind::Random);
+        assert_eq!(args.test_order(Path::new("timings.json")), TestOrder::Random { seed: 7 });
+
+        let args: TestArgs = TestArgs::parse_from(["foundry-cli", "--order", "slowest-first"]);
+        assert_eq!(args.order, TestOrderKind::SlowestFirst);
+    }
+
+    #[test]
+    fn fork_matrix() {
+        let args: TestArgs =
+            TestArgs::parse_from(["foundry-cli", "--fork-url", "mainnet@18000000,sepolia"]);
+        let fork_matrix = args.fork_matrix().unwrap();
+        assert_eq!(fork_matrix.len(), 2);
+        assert_eq!(fork_matrix[0].block, Some(18000000));
+        assert_eq!(fork_matrix[1].url, "sepolia");
+
+        let args: TestArgs = TestArgs::parse_from(["foundry-cli", "--fork-url", "mainnet"]);
+        assert!(args.fork_matrix().unwrap().is_empty());
+    }
+
     // <https://github.com/foundry-rs/foundry/issues/5913>
     #[test]
     fn issue_5913() {
@@ -30,6 +30,10 @@ impl TestSummaryReporter {
                 .set_alignment(CellAlignment::Center)
                 .add_attribute(Attribute::Bold)
                 .fg(Color::Yellow),
+            Cell::new("Expected Failures")
+                .set_alignment(CellAlignment::Center)
+                .add_attribute(Attribute::Bold)
+                .fg(Color::Yellow),
         ]);
         if is_detailed {
             row.add_cell(
@@ -78,6 +82,10 @@ impl TestSummaryReporter {
                 let skipped = suite.skips().count();
                 let mut skipped_cell = Cell::new(skipped).set_alignment(CellAlignment::Center);
 
+                let expected_failures = suite.expected_failures().count();
+                let mut expected_failures_cell =
+                    Cell::new(expected_failures).set_alignment(CellAlignment::Center);
+
                 let duration = suite.duration();
 
                 row.add_cell(Cell::new(suite_name));
@@ -97,6 +105,11 @@ impl TestSummaryReporter {
                 }
                 row.add_cell(skipped_cell);
 
+                if expected_failures > 0 {
+                    expected_failures_cell = expected_failures_cell.fg(Color::Yellow);
+                }
+                row.add_cell(expected_failures_cell);
+
                 if self.is_detailed {
                     row.add_cell(Cell::new(suite_path));
                     row.add_cell(Cell::new(format!("{:.2?}", duration).to_string()));
@@ -1,10 +1,18 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use eyre::Result;
 use foundry_cli::{opts::ProjectPathsArgs, utils::LoadConfig};
 use foundry_compilers::{
     resolver::{Charset, TreeOptions},
     Graph,
 };
+use serde::Serialize;
+use solang_parser::pt::SourceUnitPart;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+use yansi::Paint;
 
 /// CLI arguments for `forge tree`.
 #[derive(Debug, Clone, Parser)]
@@ -19,19 +27,276 @@ pub struct TreeArgs {
     #[clap(long, default_value = "utf8")]
     charset: Charset,
 
+    /// The output format of the graph.
+    #[clap(long, value_enum, default_value = "text")]
+    format: TreeFormat,
+
+    /// Show the graph of the contracts instead of the files.
+    ///
+    /// A contract depends on all contracts defined in the files imported by its file.
+    #[clap(long)]
+    contracts: bool,
+
+    /// Only list the source files that aren't imported by any other file, including tests and
+    /// scripts.
+    #[clap(long)]
+    unused: bool,
+
     #[clap(flatten)]
     opts: ProjectPathsArgs,
 }
 
 foundry_config::impl_figment_convert!(TreeArgs, opts);
 
+/// The output format of `forge tree`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TreeFormat {
+    /// A tree of the dependencies of every file.
+    Text,
+    /// A graphviz DOT graph.
+    Dot,
+    /// The nodes of the graph with their imports, as JSON.
+    Json,
+}
+
 impl TreeArgs {
     pub fn run(self) -> Result<()> {
         let config = self.try_load_config_emit_warnings()?;
-        let graph = Graph::resolve(&config.project_paths())?;
-        let opts = TreeOptions { charset: self.charset, no_dedupe: self.no_dedupe };
-        graph.print_with_options(opts);
+        let paths = config.project_paths();
+        let graph = Graph::resolve(&paths)?;
+        let imports = ImportGraph::new(&graph, &paths.root);
+
+        for (name, files) in imports.duplicate_contracts() {
+            let files = files.iter().map(|file| file.display().to_string()).collect::<Vec<_>>();
+            eprintln!(
+                "{} contract `{name}` is defined in multiple files: {}",
+                Paint::yellow("Warning:").bold(),
+                files.join(", ")
+            );
+        }
+
+        if self.unused {
+            let sources = paths.sources.strip_prefix(&paths.root).unwrap_or(&paths.sources);
+            let unused = imports.unused_files(sources);
+            match self.format {
+                TreeFormat::Json => println!("{}", serde_json::to_string_pretty(&unused)?),
+                _ => unused.iter().for_each(|file| println!("{}", file.display())),
+            }
+            return Ok(())
+        }
+
+        match (self.format, self.contracts) {
+            (TreeFormat::Text, false) => {
+                let opts = TreeOptions { charset: self.charset, no_dedupe: self.no_dedupe };
+                graph.print_with_options(opts);
+            }
+            (TreeFormat::Text, true) => print!("{}", imports.contracts_text(self.charset)),
+            (TreeFormat::Dot, contracts) => print!("{}", imports.to_dot(contracts)),
+            (TreeFormat::Json, _) => println!("{}", serde_json::to_string_pretty(&imports)?),
+        }
 
         Ok(())
     }
 }
+
+/// The import graph of a project, with the paths relative to the project root.
+#[derive(Debug, Default, Serialize)]
+struct ImportGraph {
+    files: Vec<FileNode>,
+}
+
+/// A file in the [ImportGraph].
+#[derive(Debug, Default, Serialize)]
+struct FileNode {
+    path: PathBuf,
+    /// The contracts, interfaces and libraries defined in the file.
+    contracts: Vec<String>,
+    /// The files imported directly by this file.
+    imports: Vec<PathBuf>,
+}
+
+impl ImportGraph {
+    fn new(graph: &Graph, root: &Path) -> Self {
+        let relative = |index: usize| {
+            let path = graph.node(index).path();
+            path.strip_prefix(root).unwrap_or(path).to_path_buf()
+        };
+        let mut files = graph
+            .files()
+            .values()
+            .map(|&index| {
+                let mut imports: Vec<_> =
+                    graph.imported_nodes(index).iter().map(|&import| relative(import)).collect();
+                imports.sort();
+                FileNode {
+                    path: relative(index),
+                    contracts: contract_names(graph.node(index).content()),
+                    imports,
+                }
+            })
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { files }
+    }
+
+    /// Returns the files of the contracts whose name is defined in more than one file.
+    fn duplicate_contracts(&self) -> BTreeMap<&str, Vec<&Path>> {
+        let mut contracts = BTreeMap::<_, Vec<_>>::new();
+        for file in &self.files {
+            for name in &file.contracts {
+                contracts.entry(name.as_str()).or_default().push(file.path.as_path());
+            }
+        }
+        contracts.retain(|_, files| files.len() > 1);
+        contracts
+    }
+
+    /// Returns the files in the `sources` dir that aren't imported by any other file.
+    fn unused_files(&self, sources: &Path) -> Vec<&Path> {
+        self.files
+            .iter()
+            .map(|file| file.path.as_path())
+            .filter(|path| path.starts_with(sources))
+            .filter(|path| !self.files.iter().any(|file| file.imports.iter().any(|i| i == path)))
+            .collect()
+    }
+
+    /// Returns the file with the given path.
+    fn file(&self, path: &Path) -> Option<&FileNode> {
+        self.files.iter().find(|file| file.path == path)
+    }
+
+    /// Returns the contracts the contracts of `file` depend on, as `(file, name)` pairs.
+    fn contract_dependencies<'a>(&'a self, file: &'a FileNode) -> Vec<(&'a Path, &'a str)> {
+        file.imports
+            .iter()
+            .filter_map(|import| self.file(import))
+            .flat_map(|import| {
+                import.contracts.iter().map(|name| (import.path.as_path(), name.as_str()))
+            })
+            .collect()
+    }
+
+    /// Renders the graph of the contracts, listing the dependencies of each contract.
+    fn contracts_text(&self, charset: Charset) -> String {
+        let (branch, last) = match charset {
+            Charset::Utf8 => ("├── ", "└── "),
+            Charset::Ascii => ("|-- ", "`-- "),
+        };
+        let mut out = String::new();
+        for file in &self.files {
+            let dependencies = self.contract_dependencies(file);
+            for name in &file.contracts {
+                let _ = writeln!(out, "{name} ({})", file.path.display());
+                for (i, (path, dependency)) in dependencies.iter().enumerate() {
+                    let prefix = if i + 1 == dependencies.len() { last } else { branch };
+                    let _ = writeln!(out, "{prefix}{dependency} ({})", path.display());
+                }
+            }
+        }
+        out
+    }
+
+    /// Renders the graph of the files, or of the contracts, in the graphviz DOT format.
+    fn to_dot(&self, contracts: bool) -> String {
+        let mut out = String::new();
+        if contracts {
+            let id =
+                |path: &Path, name: &str| format!("{:?}", format!("{}:{name}", path.display()));
+            out.push_str("digraph contracts {\n");
+            for file in &self.files {
+                let dependencies = self.contract_dependencies(file);
+                for name in &file.contracts {
+                    let node = id(&file.path, name);
+                    let _ = writeln!(out, "    {node} [label={name:?}];");
+                    for (path, dependency) in &dependencies {
+                        let _ = writeln!(out, "    {node} -> {};", id(path, dependency));
+                    }
+                }
+            }
+        } else {
+            let id = |path: &Path| format!("{:?}", path.display().to_string());
+            out.push_str("digraph imports {\n");
+            for file in &self.files {
+                let _ = writeln!(out, "    {};", id(&file.path));
+                for import in &file.imports {
+                    let _ = writeln!(out, "    {} -> {};", id(&file.path), id(import));
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Returns the names of the contracts, interfaces and libraries defined in the source.
+///
+/// Sources that can't be parsed are treated as defining no contracts.
+fn contract_names(content: &str) -> Vec<String> {
+    let Ok((source_unit, _)) = solang_parser::parse(content, 0) else { return vec![] };
+    source_unit
+        .0
+        .into_iter()
+        .filter_map(|part| match part {
+            SourceUnitPart::ContractDefinition(contract) => contract.name.map(|name| name.name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> ImportGraph {
+        let file = |path: &str, contracts: &[&str], imports: &[&str]| FileNode {
+            path: path.into(),
+            contracts: contracts.iter().map(|c| c.to_string()).collect(),
+            imports: imports.iter().map(Into::into).collect(),
+        };
+        ImportGraph {
+            files: vec![
+                file("src/Bar.sol", &["Bar"], &[]),
+                file("src/Foo.sol", &["Foo", "IFoo"], &["src/Bar.sol"]),
+                file("src/Unused.sol", &["Bar"], &[]),
+                file("test/Foo.t.sol", &["FooTest"], &["src/Foo.sol"]),
+            ],
+        }
+    }
+
+    #[test]
+    fn can_find_contract_names() {
+        let names = contract_names(
+            "pragma solidity ^0.8.0; interface IFoo {} library Lib {} contract Foo is IFoo {}",
+        );
+        assert_eq!(names, ["IFoo", "Lib", "Foo"]);
+        assert!(contract_names("contract {").is_empty());
+    }
+
+    #[test]
+    fn can_analyze_graph() {
+        let graph = graph();
+        let duplicates = graph.duplicate_contracts();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates["Bar"], [Path::new("src/Bar.sol"), Path::new("src/Unused.sol")]);
+        assert_eq!(graph.unused_files(Path::new("src")), [Path::new("src/Unused.sol")]);
+    }
+
+    #[test]
+    fn can_render_dot() {
+        let graph = graph();
+        let dot = graph.to_dot(false);
+        assert!(dot.starts_with("digraph imports {\n"), "{dot}");
+        assert!(dot.contains("    \"test/Foo.t.sol\" -> \"src/Foo.sol\";\n"), "{dot}");
+
+        let dot = graph.to_dot(true);
+        assert!(dot.contains("    \"src/Foo.sol:IFoo\" [label=\"IFoo\"];\n"), "{dot}");
+        assert!(dot.contains("    \"test/Foo.t.sol:FooTest\" -> \"src/Foo.sol:IFoo\";\n"), "{dot}");
+    }
+
+    #[test]
+    fn can_render_contracts() {
+        let text = graph().contracts_text(Charset::Ascii);
+        assert!(text.contains("Foo (src/Foo.sol)\n`-- Bar (src/Bar.sol)\n"), "{text}");
+    }
+}
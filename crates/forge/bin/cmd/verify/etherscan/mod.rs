@@ -54,7 +54,7 @@ impl VerificationProvider for EtherscanVerificationProvider {
         Ok(())
     }
 
-    async fn verify(&mut self, args: VerifyArgs) -> Result<()> {
+    async fn verify(&mut self, args: VerifyArgs) -> Result<Option<String>> {
         let (etherscan, verify_args) = self.prepare_request(&args).await?;
 
         if !args.skip_is_verified_check &&
@@ -66,7 +66,7 @@ impl VerificationProvider for EtherscanVerificationProvider {
                 verify_args.address.to_checksum(None)
             );
 
-            return Ok(())
+            return Ok(None)
         }
 
         trace!(target: "forge::verify", ?verify_args, "submitting verification request");
@@ -103,18 +103,22 @@ impl VerificationProvider for EtherscanVerificationProvider {
                         return Err(eyre!("Etherscan could not detect the deployment."))
                     }
 
+                    // rejected submissions are not retried
                     warn!("Failed verify submission: {:?}", resp);
-                    eprintln!(
-                    "Encountered an error verifying this contract:\nResponse: `{}`\nDetails: `{}`",
-                    resp.message, resp.result
-                );
-                    std::process::exit(1);
                 }
 
                 Ok(Some(resp))
             })
             .await?;
 
+        if let Some(resp) = resp.as_ref().filter(|resp| resp.status == "0") {
+            eyre::bail!(
+                "Encountered an error verifying this contract:\nResponse: `{}`\nDetails: `{}`",
+                resp.message,
+                resp.result
+            );
+        }
+
         if let Some(resp) = resp {
             println!(
                 "Submitted contract for verification:\n\tResponse: `{}`\n\tGUID: `{}`\n\tURL:
@@ -126,19 +130,18 @@ impl VerificationProvider for EtherscanVerificationProvider {
 
             if args.watch {
                 let check_args = VerifyCheckArgs {
-                    id: resp.result,
+                    id: resp.result.clone(),
                     etherscan: args.etherscan,
                     retry: RETRY_CHECK_ON_VERIFY,
                     verifier: args.verifier,
                 };
-                // return check_args.run().await
-                return self.check(check_args).await
+                self.check(check_args).await?;
             }
-        } else {
-            println!("Contract source code already verified");
+            return Ok(Some(resp.result))
         }
 
-        Ok(())
+        println!("Contract source code already verified");
+        Ok(None)
     }
 
     /// Executes the command to check verification status on Etherscan
@@ -151,7 +154,7 @@ impl VerificationProvider for EtherscanVerificationProvider {
             &config,
         )?;
         let retry: Retry = args.retry.into();
-        retry
+        let resp = retry
             .run_async(|| {
                 async {
                     let resp = etherscan
@@ -174,26 +177,27 @@ impl VerificationProvider for EtherscanVerificationProvider {
                         return Err(eyre!("Unable to verify.",))
                     }
 
-                    if resp.result == "Already Verified" {
-                        println!("Contract source code already verified");
-                        return Ok(())
-                    }
-
-                    if resp.status == "0" {
-                        println!("Contract failed to verify.");
-                        std::process::exit(1);
-                    }
-
-                    if resp.result == "Pass - Verified" {
-                        println!("Contract successfully verified");
-                    }
-
-                    Ok(())
+                    Ok(resp)
                 }
                 .boxed()
             })
             .await
-            .wrap_err("Checking verification result failed:")
+            .wrap_err("Checking verification result failed:")?;
+
+        if resp.result == "Already Verified" {
+            println!("Contract source code already verified");
+            return Ok(())
+        }
+
+        if resp.status == "0" {
+            eyre::bail!("Contract failed to verify: {}", resp.result)
+        }
+
+        if resp.result == "Pass - Verified" {
+            println!("Contract successfully verified");
+        }
+
+        Ok(())
     }
 }
 
@@ -135,7 +135,15 @@ impl figment::Provider for VerifyArgs {
 
 impl VerifyArgs {
     /// Run the verify command to submit the contract's source code for verification on etherscan
-    pub async fn run(mut self) -> Result<()> {
+    pub async fn run(self) -> Result<()> {
+        self.submit().await.map(drop)
+    }
+
+    /// Submits the contract's source code for verification.
+    ///
+    /// Returns the ID of the submission, e.g. the GUID on Etherscan, if the contract was submitted
+    /// and not already verified.
+    pub async fn submit(mut self) -> Result<Option<String>> {
         let config = self.load_config_emit_warnings();
         let chain = config.chain.unwrap_or_default();
         self.etherscan.chain = Some(chain);
@@ -145,7 +153,7 @@ impl VerifyArgs {
             let args =
                 EtherscanVerificationProvider::default().create_verify_request(&self, None).await?;
             println!("{}", args.source);
-            return Ok(())
+            return Ok(None)
         }
 
         let verifier_url = self.verifier.verifier_url.clone();
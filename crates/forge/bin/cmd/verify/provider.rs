@@ -19,7 +19,10 @@ pub trait VerificationProvider {
     async fn preflight_check(&mut self, args: VerifyArgs) -> Result<()>;
 
     /// Sends the actual verify request for the targeted contract.
-    async fn verify(&mut self, args: VerifyArgs) -> Result<()>;
+    ///
+    /// Returns the ID of the submission, if the provider assigns one and the contract was not
+    /// already verified.
+    async fn verify(&mut self, args: VerifyArgs) -> Result<Option<String>>;
 
     /// Checks whether the contract is verified.
     async fn check(&self, args: VerifyCheckArgs) -> Result<()>;
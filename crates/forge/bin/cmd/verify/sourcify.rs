@@ -22,7 +22,7 @@ impl VerificationProvider for SourcifyVerificationProvider {
         Ok(())
     }
 
-    async fn verify(&mut self, args: VerifyArgs) -> Result<()> {
+    async fn verify(&mut self, args: VerifyArgs) -> Result<Option<String>> {
         let body = self.prepare_request(&args)?;
 
         trace!("submitting verification request {:?}", body);
@@ -48,14 +48,13 @@ impl VerificationProvider for SourcifyVerificationProvider {
                     let status = response.status();
                     if !status.is_success() {
                         let error: serde_json::Value = response.json().await?;
-                        eprintln!(
+                        warn!("Failed verify submission: {:?}", error);
+                        eyre::bail!(
                             "Sourcify verification request for address ({}) failed with status code {}\nDetails: {:#}",
                             format_args!("{:?}", args.address),
                             status,
                             error
                         );
-                        warn!("Failed verify submission: {:?}", error);
-                        std::process::exit(1);
                     }
 
                     let text = response.text().await?;
@@ -65,8 +64,8 @@ impl VerificationProvider for SourcifyVerificationProvider {
             })
             .await?;
 
-        self.process_sourcify_response(resp.map(|r| r.result));
-        Ok(())
+        self.process_sourcify_response(resp.map(|r| r.result))?;
+        Ok(None)
     }
 
     async fn check(&self, args: VerifyCheckArgs) -> Result<()> {
@@ -96,8 +95,7 @@ impl VerificationProvider for SourcifyVerificationProvider {
             })
             .await?;
 
-        self.process_sourcify_response(resp);
-        Ok(())
+        self.process_sourcify_response(resp)
     }
 }
 
@@ -165,7 +163,10 @@ metadata output can be enabled via `extra_output = ["metadata"]` in `foundry.tom
         Ok(req)
     }
 
-    fn process_sourcify_response(&self, response: Option<Vec<SourcifyResponseElement>>) {
+    fn process_sourcify_response(
+        &self,
+        response: Option<Vec<SourcifyResponseElement>>,
+    ) -> Result<()> {
         let response = response.unwrap().remove(0);
         if response.status == "perfect" {
             if let Some(ts) = response.storage_timestamp {
@@ -178,9 +179,9 @@ metadata output can be enabled via `extra_output = ["metadata"]` in `foundry.tom
         } else if response.status == "false" {
             println!("Contract source code is not verified")
         } else {
-            eprintln!("Unknown status from sourcify. Status: {}", response.status);
-            std::process::exit(1);
+            eyre::bail!("Unknown status from sourcify. Status: {}", response.status)
         }
+        Ok(())
     }
 }
 
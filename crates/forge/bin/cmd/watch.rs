@@ -2,8 +2,14 @@ use super::{build::BuildArgs, snapshot::SnapshotArgs, test::TestArgs};
 use clap::Parser;
 use eyre::Result;
 use foundry_cli::utils::{self, FoundryPathExt};
+use foundry_compilers::{utils::canonicalize, Graph, ProjectPathsConfig};
 use foundry_config::Config;
-use std::{collections::HashSet, convert::Infallible, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use watchexec::{
     action::{Action, Outcome, PreSpawn},
     command::Command,
@@ -35,10 +41,15 @@ pub struct WatchArgs {
 
     /// Explicitly re-run all tests when a change is made.
     ///
-    /// By default, only the tests of the last modified test file are executed.
+    /// By default, only the test files affected by the change are executed: the modified test
+    /// files and the test files that import a modified file, directly or through other imports.
     #[clap(long)]
     pub run_all: bool,
 
+    /// Clear the screen before each run, so that only the output of the latest run is shown.
+    #[clap(long)]
+    pub clear: bool,
+
     /// File update debounce delay.
     ///
     /// During the delay, incoming change events are accumulated and
@@ -137,7 +148,7 @@ pub async fn watch_test(args: TestArgs) -> Result<()> {
         args.watch.run_all;
 
     let state = WatchTestState {
-        project_root: config.__root.0,
+        paths: config.project_paths(),
         no_reconfigure,
         last_test_files: Default::default(),
     };
@@ -152,20 +163,22 @@ pub async fn watch_test(args: TestArgs) -> Result<()> {
 
 #[derive(Debug, Clone)]
 struct WatchTestState {
-    /// the root directory of the project
-    project_root: PathBuf,
+    /// the paths of the project, used to resolve the import graph
+    paths: ProjectPathsConfig,
     /// marks whether we can reconfigure the watcher command with the `--match-path` arg
     no_reconfigure: bool,
-    /// Tracks the last changed test files, if any so that if a non-test file was modified we run
-    /// this file instead *Note:* this is a vec, so we can also watch out for changes
-    /// introduced by `forge fmt`
-    last_test_files: HashSet<String>,
+    /// Tracks the test files that were last run, if any, so that if a change doesn't affect any
+    /// test file, for example a change of a non-solidity file, we run these files again
+    last_test_files: HashSet<PathBuf>,
 }
 
 /// The `on_action` hook for `forge test --watch`
+///
+/// Reconfigures the command to only run the test files that are affected by the changed files,
+/// which are the changed test files and the test files that (transitively) import a changed file.
 fn on_test(action: OnActionState<WatchTestState>) {
     let OnActionState { args, runtime, action, wx, cmd, other } = action;
-    let WatchTestState { project_root, no_reconfigure, last_test_files } = other;
+    let WatchTestState { paths, no_reconfigure, last_test_files } = other;
 
     if no_reconfigure {
         // nothing to reconfigure
@@ -174,63 +187,53 @@ fn on_test(action: OnActionState<WatchTestState>) {
 
     let mut cmd = cmd.clone();
 
-    let mut changed_sol_test_files: HashSet<_> = action
-        .events
-        .iter()
-        .flat_map(|e| e.paths())
-        .filter(|(path, _)| path.is_sol_test())
-        .filter_map(|(path, _)| path.to_str())
-        .map(str::to_string)
-        .collect();
-
     // replace `--match-path` | `-mp` argument
     if let Some(pos) = cmd.iter().position(|arg| arg == "--match-path" || arg == "-mp") {
         // --match-path requires 1 argument
         cmd.drain(pos..=(pos + 1));
     }
 
-    if changed_sol_test_files.len() > 1 ||
-        (changed_sol_test_files.is_empty() && last_test_files.is_empty())
-    {
-        // this could happen if multiple files were changed at once, for example `forge fmt` was
-        // run, or if no test files were changed and no previous test files were modified in which
-        // case we simply run all
-        let mut config = runtime.clone();
-        config.command(watch_command(cmd.clone()));
-        // re-register the action
-        on_action(
-            args.clone(),
-            config,
-            wx,
-            cmd,
-            WatchTestState {
-                project_root,
-                no_reconfigure,
-                last_test_files: changed_sol_test_files,
-            },
-            on_test,
-        );
-        return
-    }
-
-    if changed_sol_test_files.is_empty() {
-        // reuse the old test files if a non-test file was changed
-        changed_sol_test_files = last_test_files;
-    }
-
-    // append `--match-path` glob
-    let mut file = changed_sol_test_files.clone().into_iter().next().expect("test file present");
+    let changed_sol_files: HashSet<_> = action
+        .events
+        .iter()
+        .flat_map(|e| e.paths())
+        .map(|(path, _)| path)
+        .filter(|path| path.is_sol())
+        .map(|path| canonicalize(path).unwrap_or_else(|_| path.to_path_buf()))
+        .collect();
 
-    // remove the project root dir from the detected file
-    if let Some(root) = project_root.as_os_str().to_str() {
-        if let Some(f) = file.strip_prefix(root) {
-            file = f.trim_start_matches('/').to_string();
+    let test_files = if changed_sol_files.is_empty() {
+        last_test_files
+    } else {
+        match Graph::resolve(&paths) {
+            Ok(graph) => {
+                let affected = affected_test_files(&graph, &changed_sol_files);
+                // reuse the last test files if the changes didn't affect any test file
+                if affected.is_empty() {
+                    last_test_files
+                } else {
+                    affected
+                }
+            }
+            Err(err) => {
+                // the sources can't be resolved, for example because of a missing import, so we
+                // clear the filter and run all tests to surface the error
+                trace!("failed to resolve the import graph: {err}");
+                HashSet::new()
+            }
         }
-    }
+    };
 
-    let mut new_cmd = cmd.clone();
-    new_cmd.push("--match-path".to_string());
-    new_cmd.push(file);
+    // without any test file to narrow down to, we simply run all tests
+    let new_cmd = match match_path_glob(&paths.root, &test_files) {
+        Some(glob) => {
+            let mut new_cmd = cmd.clone();
+            new_cmd.push("--match-path".to_string());
+            new_cmd.push(glob);
+            new_cmd
+        }
+        None => cmd.clone(),
+    };
     trace!("reconfigure test command {:?}", new_cmd);
 
     // reconfigure the executor with a new runtime
@@ -243,11 +246,42 @@ fn on_test(action: OnActionState<WatchTestState>) {
         config,
         wx,
         cmd,
-        WatchTestState { project_root, no_reconfigure, last_test_files: changed_sol_test_files },
+        WatchTestState { paths, no_reconfigure, last_test_files: test_files },
         on_test,
     );
 }
 
+/// Returns the test files that are affected by the changed files: the changed test files and all
+/// test files that (transitively) import one of the changed files.
+fn affected_test_files(graph: &Graph, changed: &HashSet<PathBuf>) -> HashSet<PathBuf> {
+    graph
+        .files()
+        .iter()
+        .filter(|(file, _)| file.is_sol_test())
+        .filter(|(file, &index)| {
+            changed.contains(*file) ||
+                graph
+                    .all_imported_nodes(index)
+                    .any(|import| changed.contains(graph.node(import).path()))
+        })
+        .map(|(file, _)| file.clone())
+        .collect()
+}
+
+/// Returns the `--match-path` glob that matches all the given files, relative to the project root
+fn match_path_glob(root: &Path, files: &HashSet<PathBuf>) -> Option<String> {
+    let mut files: Vec<_> = files
+        .iter()
+        .map(|file| file.strip_prefix(root).unwrap_or(file).to_string_lossy().into_owned())
+        .collect();
+    files.sort_unstable();
+    match files.len() {
+        0 => None,
+        1 => files.pop(),
+        _ => Some(format!("{{{}}}", files.join(","))),
+    }
+}
+
 /// Converts a list of arguments to a `watchexec::Command`
 ///
 /// The first index in `args`, is expected to be the path to the executable, See `cmd_args`
@@ -392,8 +426,7 @@ fn on_action<F, T>(
             other: other.clone(),
         });
 
-        // mattsse: could be made into flag to never clear the shell
-        let clear = false;
+        let clear = args.clear;
         let when_running = match (clear, on_busy) {
             (_, "do-nothing") => Outcome::DoNothing,
             (true, "restart") => {
@@ -448,4 +481,46 @@ mod tests {
         let cleaned = clean_cmd_args(0, args);
         assert_eq!(cleaned, vec!["-v".to_string()]);
     }
+
+    #[test]
+    fn finds_affected_test_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = canonicalize(tmp.path()).unwrap();
+        let files = [
+            ("src/A.sol", "pragma solidity ^0.8.0;\ncontract A {}"),
+            ("src/B.sol", "pragma solidity ^0.8.0;\nimport \"./A.sol\";\ncontract B is A {}"),
+            ("src/C.sol", "pragma solidity ^0.8.0;\ncontract C {}"),
+            (
+                "test/B.t.sol",
+                "pragma solidity ^0.8.0;\nimport \"../src/B.sol\";\ncontract BTest {}",
+            ),
+            (
+                "test/C.t.sol",
+                "pragma solidity ^0.8.0;\nimport \"../src/C.sol\";\ncontract CTest {}",
+            ),
+        ];
+        for (file, content) in files {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let paths = ProjectPathsConfig::builder()
+            .root(&root)
+            .sources(root.join("src"))
+            .tests(root.join("test"))
+            .build()
+            .unwrap();
+        let graph = Graph::resolve(&paths).unwrap();
+
+        let changed = HashSet::from([root.join("src/A.sol")]);
+        let affected = affected_test_files(&graph, &changed);
+        assert_eq!(affected, HashSet::from([root.join("test/B.t.sol")]));
+        assert_eq!(match_path_glob(&root, &affected).unwrap(), "test/B.t.sol");
+
+        let changed = HashSet::from([root.join("src/A.sol"), root.join("test/C.t.sol")]);
+        let affected = affected_test_files(&graph, &changed);
+        assert_eq!(match_path_glob(&root, &affected).unwrap(), "{test/B.t.sol,test/C.t.sol}");
+
+        assert_eq!(match_path_glob(&root, &HashSet::new()), None);
+    }
 }
@@ -84,10 +84,15 @@ fn main() -> Result<()> {
             }
         }
         Subcommands::Fmt(cmd) => cmd.run(),
+        Subcommands::Lint(cmd) => cmd.run(),
         Subcommands::Config(cmd) => cmd.run(),
         Subcommands::Flatten(cmd) => cmd.run(),
         Subcommands::Inspect(cmd) => cmd.run(),
         Subcommands::Tree(cmd) => cmd.run(),
+        Subcommands::Size(cmd) => cmd.run(),
+        Subcommands::Deployments(cmd) => cmd.sub.run(),
+        Subcommands::Artifacts(cmd) => cmd.sub.run(),
+        Subcommands::EjectAbi(cmd) => cmd.run(),
         Subcommands::Geiger(cmd) => {
             let check = cmd.check;
             let n = cmd.run()?;
@@ -1,21 +1,26 @@
 use crate::cmd::{
+    artifacts::ArtifactsArgs,
     bind::BindArgs,
     build::BuildArgs,
     cache::CacheArgs,
     config, coverage,
     create::CreateArgs,
     debug::DebugArgs,
+    deployments::DeploymentsArgs,
     doc::DocArgs,
+    eject_abi::EjectAbiArgs,
     flatten,
     fmt::FmtArgs,
     geiger, generate,
     init::InitArgs,
     inspect,
     install::InstallArgs,
+    lint::LintArgs,
     remappings::RemappingArgs,
     remove::RemoveArgs,
     script::ScriptArgs,
     selectors::SelectorsSubcommands,
+    size::SizeArgs,
     snapshot, test, tree, update,
     verify::{VerifyArgs, VerifyCheckArgs},
 };
@@ -143,6 +148,9 @@ pub enum Subcommands {
     /// Format Solidity source files.
     Fmt(FmtArgs),
 
+    /// Run static checks on Solidity source files.
+    Lint(LintArgs),
+
     /// Get specialized information about a smart contract.
     #[clap(visible_alias = "in")]
     Inspect(inspect::InspectArgs),
@@ -151,6 +159,18 @@ pub enum Subcommands {
     #[clap(visible_alias = "tr")]
     Tree(tree::TreeArgs),
 
+    /// Report the runtime and initcode size of every contract in the project.
+    Size(SizeArgs),
+
+    /// Query the contracts deployed by broadcasted scripts.
+    Deployments(DeploymentsArgs),
+
+    /// Manage the build artifacts.
+    Artifacts(ArtifactsArgs),
+
+    /// Export the ABIs and deployed addresses of the project's contracts for frontends.
+    EjectAbi(EjectAbiArgs),
+
     /// Detects usage of unsafe cheat codes in a project and its dependencies.
     Geiger(geiger::GeigerArgs),
 
@@ -9,7 +9,10 @@ use alloy_primitives::U256;
 use comfy_table::{presets::ASCII_MARKDOWN, *};
 use foundry_common::{calc, TestFunctionExt};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt::Display};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
 
 /// Represents the gas report for a set of contracts.
 #[derive(Default, Debug, Serialize, Deserialize)]
@@ -77,6 +80,9 @@ impl GasReport {
 
             if self.should_report(contract_name) {
                 let contract_info = self.contracts.entry(name.to_string()).or_default();
+                if let Some(label) = trace.label.as_ref().filter(|label| *label != contract_name) {
+                    contract_info.labels.insert(label.clone());
+                }
 
                 match &trace.data {
                     TraceCallData::Raw(bytes) => {
@@ -136,9 +142,15 @@ impl Display for GasReport {
 
             let mut table = Table::new();
             table.load_preset(ASCII_MARKDOWN);
-            table.set_header([Cell::new(format!("{name} contract"))
-                .add_attribute(Attribute::Bold)
-                .fg(Color::Green)]);
+            let header = if contract.labels.is_empty() {
+                format!("{name} contract")
+            } else {
+                format!(
+                    "{name} contract ({})",
+                    contract.labels.iter().cloned().collect::<Vec<_>>().join(", ")
+                )
+            };
+            table.set_header([Cell::new(header).add_attribute(Attribute::Bold).fg(Color::Green)]);
             table.add_row([
                 Cell::new("Deployment Cost").add_attribute(Attribute::Bold).fg(Color::Cyan),
                 Cell::new("Deployment Size").add_attribute(Attribute::Bold).fg(Color::Cyan),
@@ -181,6 +193,9 @@ pub struct ContractInfo {
     pub gas: U256,
     pub size: U256,
     pub functions: BTreeMap<String, BTreeMap<String, GasInfo>>,
+    /// The labels of the analyzed instances of the contract
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub labels: BTreeSet<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -4,12 +4,20 @@ extern crate tracing;
 use alloy_primitives::B256;
 use foundry_compilers::ProjectCompileOutput;
 use foundry_config::{
-    validate_profiles, Config, FuzzConfig, InlineConfig, InlineConfigError, InlineConfigParser,
-    InvariantConfig, NatSpec,
+    validate_profiles, Config, FuzzConfig, GasConfig, InlineConfig, InlineConfigError,
+    InlineConfigParser, InvariantConfig, NatSpec,
 };
 
+use foundry_evm::inspectors::ExecutionUsage;
 use proptest::test_runner::{RngAlgorithm, TestRng, TestRunner};
-use std::path::Path;
+use result::TestStatus;
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 pub mod coverage;
 
@@ -17,6 +25,9 @@ pub mod gas_report;
 
 pub mod link;
 
+pub mod order;
+use order::TestOrder;
+
 mod multi_runner;
 pub use multi_runner::{MultiContractRunner, MultiContractRunnerBuilder};
 
@@ -42,6 +53,14 @@ pub struct TestOptions {
     pub inline_fuzz: InlineConfig<FuzzConfig>,
     /// Contains per-test specific "invariant" configurations.
     pub inline_invariant: InlineConfig<InvariantConfig>,
+    /// Contains per-test specific "gas" configurations.
+    pub inline_gas: InlineConfig<GasConfig>,
+    /// The peak memory and call depth tracking of tests.
+    pub usage: UsageOptions,
+    /// The order in which test suites and tests are started.
+    pub order: TestOrder,
+    /// Whether to stop running tests after the first failure.
+    pub fail_fast: FailFast,
 }
 
 impl TestOptions {
@@ -57,12 +76,14 @@ impl TestOptions {
         let natspecs: Vec<NatSpec> = NatSpec::parse(output, root);
         let mut inline_invariant = InlineConfig::<InvariantConfig>::default();
         let mut inline_fuzz = InlineConfig::<FuzzConfig>::default();
+        let mut inline_gas = InlineConfig::<GasConfig>::default();
 
         for natspec in natspecs {
             // Perform general validation
             validate_profiles(&natspec, &profiles)?;
             FuzzConfig::validate_configs(&natspec)?;
             InvariantConfig::validate_configs(&natspec)?;
+            GasConfig::validate_configs(&natspec)?;
 
             // Apply in-line configurations for the current profile
             let configs: Vec<String> = natspec.current_profile_configs().collect();
@@ -81,9 +102,24 @@ impl TestOptions {
                 Ok(None) => { /* No inline config found, do nothing */ }
                 Err(e) => Err(InlineConfigError { line: line.clone(), source: e })?,
             }
+
+            match GasConfig::default().try_merge(&configs) {
+                Ok(Some(conf)) => inline_gas.insert(c, f, conf),
+                Ok(None) => { /* No inline config found, do nothing */ }
+                Err(e) => Err(InlineConfigError { line: line.clone(), source: e })?,
+            }
         }
 
-        Ok(Self { fuzz: base_fuzz, invariant: base_invariant, inline_fuzz, inline_invariant })
+        Ok(Self {
+            fuzz: base_fuzz,
+            invariant: base_invariant,
+            inline_fuzz,
+            inline_invariant,
+            inline_gas,
+            usage: Default::default(),
+            order: Default::default(),
+            fail_fast: Default::default(),
+        })
     }
 
     /// Returns a "fuzz" test runner instance. Parameters are used to select tight scoped fuzz
@@ -144,6 +180,19 @@ impl TestOptions {
         self.inline_invariant.get(contract_id, test_fn).unwrap_or(&self.invariant)
     }
 
+    /// Returns the gas limit of the calls of a test, if an inline configuration overrides it for
+    /// the contract-function pair.
+    ///
+    /// - `contract_id` is the id of the test contract, expressed as a relative path from the
+    ///   project root.
+    /// - `test_fn` is the name of the test function declared inside the test contract.
+    pub fn gas_limit<S>(&self, contract_id: S, test_fn: S) -> Option<u64>
+    where
+        S: Into<String>,
+    {
+        self.inline_gas.get(contract_id, test_fn).and_then(|gas| gas.limit)
+    }
+
     pub fn fuzzer_with_cases(&self, cases: u32) -> TestRunner {
         // TODO: Add Options to modify the persistence
         let cfg = proptest::test_runner::Config {
@@ -164,6 +213,74 @@ impl TestOptions {
     }
 }
 
+/// Settings of the peak memory and call depth tracking of tests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageOptions {
+    /// Whether to report the peak usage of every test, even if no limits are set.
+    pub report: bool,
+    /// The maximum call depth a test may reach.
+    pub max_call_depth: Option<u64>,
+    /// The maximum number of bytes the memory of a call frame may expand to.
+    pub max_memory_expansion: Option<u64>,
+}
+
+impl UsageOptions {
+    /// Returns whether the usage of tests needs to be tracked.
+    pub fn is_enabled(&self) -> bool {
+        self.report || self.max_call_depth.is_some() || self.max_memory_expansion.is_some()
+    }
+
+    /// Returns why the usage exceeds the limits, if it does.
+    pub fn exceeded(&self, usage: &ExecutionUsage) -> Option<String> {
+        if let Some(max_call_depth) = self.max_call_depth.filter(|max| usage.max_depth > *max) {
+            return Some(format!(
+                "call depth of {} exceeds the limit of {max_call_depth}",
+                usage.max_depth
+            ))
+        }
+        if let Some(max_memory) = self.max_memory_expansion.filter(|max| usage.max_memory > *max) {
+            return Some(format!(
+                "memory expansion to {} bytes exceeds the limit of {max_memory} bytes",
+                usage.max_memory
+            ))
+        }
+        None
+    }
+}
+
+/// Stops starting tests after the first failure, shared by all suites running in parallel.
+///
+/// Tests that already started still finish, tests that didn't start are not run and have no
+/// result.
+#[derive(Debug, Clone, Default)]
+pub struct FailFast(Option<Arc<AtomicBool>>);
+
+impl FailFast {
+    /// Creates a new instance, which never stops if not enabled.
+    pub fn new(enabled: bool) -> Self {
+        Self(enabled.then(Default::default))
+    }
+
+    /// Returns whether fail-fast is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Records the status of a finished test, stopping the run if it failed.
+    pub fn record(&self, status: TestStatus) {
+        if let Some(failed) = &self.0 {
+            if status.is_failure() {
+                failed.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Returns whether no more tests should be started.
+    pub fn should_stop(&self) -> bool {
+        self.0.as_ref().is_some_and(|failed| failed.load(Ordering::Relaxed))
+    }
+}
+
 /// Builder utility to create a [`TestOptions`] instance.
 #[derive(Default)]
 #[must_use = "builders do nothing unless you call `build` on them"]
@@ -171,6 +288,9 @@ pub struct TestOptionsBuilder {
     fuzz: Option<FuzzConfig>,
     invariant: Option<InvariantConfig>,
     profiles: Option<Vec<String>>,
+    usage: Option<UsageOptions>,
+    order: Option<TestOrder>,
+    fail_fast: bool,
 }
 
 impl TestOptionsBuilder {
@@ -186,6 +306,24 @@ impl TestOptionsBuilder {
         self
     }
 
+    /// Sets the peak memory and call depth tracking of tests.
+    pub fn usage(mut self, usage: UsageOptions) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Sets the order in which test suites and tests are started.
+    pub fn order(mut self, order: TestOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Sets whether to stop running tests after the first failure.
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
     /// Sets available configuration profiles. Profiles are useful to validate existing in-line
     /// configurations. This argument is necessary in case a `compile_output`is provided.
     pub fn profiles(mut self, p: Vec<String>) -> Self {
@@ -208,7 +346,11 @@ impl TestOptionsBuilder {
             self.profiles.unwrap_or_else(|| vec![Config::selected_profile().into()]);
         let base_fuzz = self.fuzz.unwrap_or_default();
         let base_invariant = self.invariant.unwrap_or_default();
-        TestOptions::new(output, root, profiles, base_fuzz, base_invariant)
+        let mut options = TestOptions::new(output, root, profiles, base_fuzz, base_invariant)?;
+        options.usage = self.usage.unwrap_or_default();
+        options.order = self.order.unwrap_or_default();
+        options.fail_fast = FailFast::new(self.fail_fast);
+        Ok(options)
     }
 }
 
@@ -169,10 +169,19 @@ impl MultiContractRunner {
         // the db backend that serves all the data, each contract gets its own instance
         let db = Backend::spawn(self.fork.take()).await;
 
-        self.contracts
-            .par_iter()
+        let mut contracts = self
+            .contracts
+            .iter()
             .filter(|(id, _)| filter.matches_path(&id.source) && filter.matches_contract(&id.name))
             .filter(|(_, (abi, _, _))| abi.functions().any(|func| filter.matches_test(&func.name)))
+            .collect::<Vec<_>>();
+        test_options.order.sort_suites(&mut contracts, |(id, _)| id.identifier());
+
+        // suites are started in order as workers become available
+        contracts
+            .into_iter()
+            .par_bridge()
+            .filter(|_| !test_options.fail_fast.should_stop())
             .for_each_with(stream_result, |stream_result, (id, (abi, deploy_code, libs))| {
                 let executor = ExecutorBuilder::new()
                     .inspectors(|stack| {
@@ -181,6 +190,8 @@ impl MultiContractRunner {
                             .trace(self.evm_opts.verbosity >= 3 || self.debug)
                             .debug(self.debug)
                             .coverage(self.coverage)
+                            .usage(test_options.usage.is_enabled())
+                            .isolation(self.evm_opts.isolate)
                     })
                     .spec(self.evm_spec)
                     .gas_limit(self.evm_opts.gas_limit())
@@ -250,6 +261,8 @@ pub struct MultiContractRunnerBuilder {
     pub debug: bool,
     /// Settings related to fuzz and/or invariant tests
     pub test_options: Option<TestOptions>,
+    /// Contracts that are not part of the Solidity compiler output, e.g. Vyper contracts
+    pub extra_contracts: Vec<(ArtifactId, CompactContractBytecode)>,
 }
 
 impl MultiContractRunnerBuilder {
@@ -271,6 +284,7 @@ impl MultiContractRunnerBuilder {
             .with_stripped_file_prefixes(&root)
             .into_artifacts()
             .map(|(i, c)| (i, c.into_contract_bytecode()))
+            .chain(self.extra_contracts)
             .collect::<Vec<(ArtifactId, CompactContractBytecode)>>();
 
         let mut known_contracts = ContractsByArtifact::default();
@@ -401,6 +415,15 @@ impl MultiContractRunnerBuilder {
         self
     }
 
+    #[must_use]
+    pub fn with_extra_contracts(
+        mut self,
+        contracts: impl IntoIterator<Item = (ArtifactId, CompactContractBytecode)>,
+    ) -> Self {
+        self.extra_contracts.extend(contracts);
+        self
+    }
+
     #[must_use]
     pub fn set_coverage(mut self, enable: bool) -> Self {
         self.coverage = enable;
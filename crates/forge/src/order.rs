@@ -0,0 +1,166 @@
+//! The order in which test suites and tests are started.
+
+use crate::result::SuiteResult;
+use alloy_primitives::keccak256;
+use foundry_common::fs;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+/// The order in which test suites, and the tests of a suite, are started.
+///
+/// Tests run in parallel, so the order only determines which tests are started first: results
+/// are still reported by name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum TestOrder {
+    /// By name.
+    #[default]
+    Alphabetical,
+    /// Shuffled with the given seed, the same seed always results in the same order.
+    Random { seed: u64 },
+    /// By descending duration in previous runs, tests that haven't run yet first.
+    SlowestFirst(Arc<TestTimings>),
+}
+
+impl TestOrder {
+    /// Sorts the suites, named by `name`, in this order.
+    pub fn sort_suites<T>(&self, suites: &mut [T], name: impl Fn(&T) -> String) {
+        self.sort(suites, "", name, |timings, name| timings.suite(name))
+    }
+
+    /// Sorts the tests of the suite, named by `name`, in this order.
+    pub fn sort_tests<T>(&self, suite: &str, tests: &mut [T], name: impl Fn(&T) -> String) {
+        self.sort(tests, suite, name, |timings, name| timings.test(suite, name))
+    }
+
+    fn sort<T>(
+        &self,
+        items: &mut [T],
+        scope: &str,
+        name: impl Fn(&T) -> String,
+        duration: impl Fn(&TestTimings, &str) -> Option<Duration>,
+    ) {
+        // always start from the same order, so the shuffle only depends on the seed
+        items.sort_by_cached_key(&name);
+        match self {
+            TestOrder::Alphabetical => {}
+            TestOrder::Random { seed } => {
+                // every suite is shuffled differently, but deterministically
+                let mut scope_seed = [0u8; 8];
+                scope_seed.copy_from_slice(&keccak256(scope)[..8]);
+                let mut rng = StdRng::seed_from_u64(seed ^ u64::from_be_bytes(scope_seed));
+                items.shuffle(&mut rng);
+            }
+            TestOrder::SlowestFirst(timings) => items.sort_by_cached_key(|item| {
+                Reverse(duration(timings, &name(item)).unwrap_or(Duration::MAX))
+            }),
+        }
+    }
+}
+
+/// The durations of the test suites and tests in previous runs, used by
+/// [TestOrder::SlowestFirst].
+///
+/// The timings are persisted in the cache directory of the project, see [TestTimings::path].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestTimings {
+    /// The duration of the suites in milliseconds, by suite.
+    suites: BTreeMap<String, u64>,
+    /// The duration of the tests in milliseconds, by suite and test signature.
+    tests: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl TestTimings {
+    /// The name of the timings file in the cache directory.
+    pub const FILE_NAME: &'static str = "test-timings.json";
+
+    /// Returns the path of the timings file in the given cache directory.
+    pub fn path(cache_dir: &Path) -> PathBuf {
+        cache_dir.join(Self::FILE_NAME)
+    }
+
+    /// Reads the timings at the given path, which are empty if the file doesn't exist or is
+    /// invalid.
+    pub fn read(path: &Path) -> Self {
+        fs::read_json_file(path).unwrap_or_default()
+    }
+
+    /// Writes the timings to the given path.
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write_json_file(path, self)?;
+        Ok(())
+    }
+
+    /// Records the durations of the given results, keeping the timings of the suites and tests
+    /// that didn't run.
+    pub fn record<'a>(&mut self, results: impl IntoIterator<Item = (&'a String, &'a SuiteResult)>) {
+        for (suite, result) in results {
+            self.suites.insert(suite.clone(), result.duration.as_millis() as u64);
+            let tests = self.tests.entry(suite.clone()).or_default();
+            for (test, result) in &result.test_results {
+                tests.insert(test.clone(), result.duration.as_millis() as u64);
+            }
+        }
+    }
+
+    /// Returns the duration of the suite in the last run.
+    pub fn suite(&self, suite: &str) -> Option<Duration> {
+        self.suites.get(suite).copied().map(Duration::from_millis)
+    }
+
+    /// Returns the duration of the test in the last run.
+    pub fn test(&self, suite: &str, test: &str) -> Option<Duration> {
+        self.tests.get(suite)?.get(test).copied().map(Duration::from_millis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::TestResult;
+
+    #[test]
+    fn random_order_is_deterministic() {
+        let names = || (0..20).map(|i| format!("test{i}")).collect::<Vec<_>>();
+        let order = TestOrder::Random { seed: 42 };
+
+        let mut a = names();
+        order.sort_tests("Suite", &mut a, Clone::clone);
+        let mut b = names();
+        b.reverse();
+        order.sort_tests("Suite", &mut b, Clone::clone);
+        assert_eq!(a, b);
+
+        let mut other = names();
+        TestOrder::Random { seed: 43 }.sort_tests("Suite", &mut other, Clone::clone);
+        assert_ne!(a, other);
+    }
+
+    #[test]
+    fn slowest_first_uses_recorded_timings() {
+        let result =
+            |millis| TestResult { duration: Duration::from_millis(millis), ..Default::default() };
+        let suite = SuiteResult::new(
+            Duration::from_millis(30),
+            [("fast()".to_string(), result(10)), ("slow()".to_string(), result(20))].into(),
+            Vec::new(),
+        );
+        let mut timings = TestTimings::default();
+        timings.record([(&"Suite".to_string(), &suite)]);
+        assert_eq!(timings.suite("Suite"), Some(Duration::from_millis(30)));
+
+        let order = TestOrder::SlowestFirst(Arc::new(timings));
+        let mut tests = vec!["fast()".to_string(), "new()".to_string(), "slow()".to_string()];
+        order.sort_tests("Suite", &mut tests, Clone::clone);
+        assert_eq!(tests, ["new()", "slow()", "fast()"]);
+    }
+}
@@ -1,6 +1,6 @@
 //! Test outcomes.
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, U256};
 use ethers_core::types::Log;
 use foundry_common::evm::Breakpoints;
 use foundry_evm::{
@@ -8,9 +8,10 @@ use foundry_evm::{
     debug::DebugArena,
     executors::EvmError,
     fuzz::{CounterExample, FuzzCase},
+    inspectors::ExecutionUsage,
     traces::{TraceKind, Traces},
 };
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use std::{
     collections::BTreeMap,
     fmt::{self, Write},
@@ -19,7 +20,7 @@ use std::{
 use yansi::Paint;
 
 /// Results and duration for a set of tests included in the same test contract
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone)]
 pub struct SuiteResult {
     /// Total duration of the test run for this block of tests
     pub duration: Duration,
@@ -48,6 +49,16 @@ impl SuiteResult {
         self.tests().filter(|(_, t)| t.status == TestStatus::Failure)
     }
 
+    /// Iterator over all skipped tests and their names
+    pub fn skips(&self) -> impl Iterator<Item = (&String, &TestResult)> {
+        self.tests().filter(|(_, t)| t.status == TestStatus::Skipped)
+    }
+
+    /// Iterator over all tests that failed as expected and their names
+    pub fn expected_failures(&self) -> impl Iterator<Item = (&String, &TestResult)> {
+        self.tests().filter(|(_, t)| t.status == TestStatus::ExpectedFailure)
+    }
+
     /// Iterator over all tests and their names
     pub fn tests(&self) -> impl Iterator<Item = (&String, &TestResult)> {
         self.test_results.iter()
@@ -64,12 +75,28 @@ impl SuiteResult {
     }
 }
 
+impl Serialize for SuiteResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut suite = serializer.serialize_struct("SuiteResult", 7)?;
+        suite.serialize_field("duration", &self.duration)?;
+        suite.serialize_field("test_results", &self.test_results)?;
+        suite.serialize_field("warnings", &self.warnings)?;
+        suite.serialize_field("passed", &self.successes().count())?;
+        suite.serialize_field("failed", &self.failures().count())?;
+        suite.serialize_field("skipped", &self.skips().count())?;
+        suite.serialize_field("expected_failures", &self.expected_failures().count())?;
+        suite.end()
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TestStatus {
     Success,
     #[default]
     Failure,
     Skipped,
+    /// The test failed, but was marked as expected to fail with `vm.xfail()`.
+    ExpectedFailure,
 }
 
 impl TestStatus {
@@ -90,6 +117,12 @@ impl TestStatus {
     pub fn is_skipped(self) -> bool {
         matches!(self, Self::Skipped)
     }
+
+    /// Returns `true` if the test failed as expected.
+    #[inline]
+    pub fn is_expected_failure(self) -> bool {
+        matches!(self, Self::ExpectedFailure)
+    }
 }
 
 /// The result of an executed solidity test
@@ -103,6 +136,8 @@ pub struct TestResult {
 
     /// If there was a revert, this field will be populated. Note that the test can
     /// still be successful (i.e self.success == true) when it's expected to fail.
+    ///
+    /// For skipped tests and tests marked with vm.xfail(), this is the given reason.
     pub reason: Option<String>,
 
     /// Minimal reproduction test case for failing test
@@ -133,13 +168,32 @@ pub struct TestResult {
 
     /// pc breakpoint char map
     pub breakpoints: Breakpoints,
+
+    /// The seed of the fuzzer RNG, if this is a fuzz or invariant test.
+    ///
+    /// Passing it back via `--fuzz-seed` replays the exact same sequence of inputs.
+    pub seed: Option<U256>,
+
+    /// The peak memory and call depth of a unit test, if tracked.
+    pub usage: Option<ExecutionUsage>,
+
+    /// The time it took to run the test.
+    #[serde(skip)]
+    pub duration: Duration,
 }
 
 impl fmt::Display for TestResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.status {
             TestStatus::Success => Paint::green("[PASS]").fmt(f),
-            TestStatus::Skipped => Paint::yellow("[SKIP]").fmt(f),
+            TestStatus::Skipped => match &self.reason {
+                Some(reason) => Paint::yellow(format!("[SKIP. Reason: {reason}]")).fmt(f),
+                None => Paint::yellow("[SKIP]").fmt(f),
+            },
+            TestStatus::ExpectedFailure => {
+                let reason = self.reason.as_deref().unwrap_or_default();
+                Paint::yellow(format!("[XFAIL. Reason: {reason}]")).fmt(f)
+            }
             TestStatus::Failure => {
                 let mut s = String::from("[FAIL. Reason: ");
 
@@ -2,7 +2,7 @@
 
 use crate::{
     result::{SuiteResult, TestKind, TestResult, TestSetup, TestStatus},
-    TestFilter, TestOptions,
+    TestFilter, TestOptions, UsageOptions,
 };
 use alloy_json_abi::{Function, JsonAbi as Abi};
 use alloy_primitives::{Address, Bytes, U256};
@@ -21,12 +21,13 @@ use foundry_evm::{
         invariant::{replay_run, InvariantExecutor, InvariantFuzzError, InvariantFuzzTestResult},
         CallResult, EvmError, ExecutionErr, Executor,
     },
-    fuzz::{invariant::InvariantContract, CounterExample},
+    fuzz::{invariant::InvariantContract, CounterExample, SharedCorpus},
     traces::{load_contracts, TraceKind},
 };
 use proptest::test_runner::{TestError, TestRunner};
 use rayon::prelude::*;
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, HashMap},
     time::Instant,
 };
@@ -51,6 +52,8 @@ pub struct ContractRunner<'a> {
     pub sender: Address,
     /// Should generate debug traces
     pub debug: bool,
+    /// The corpus of coverage-guided fuzzing, shared by the fuzz tests of the contract
+    pub corpus: SharedCorpus,
 }
 
 impl<'a> ContractRunner<'a> {
@@ -76,6 +79,7 @@ impl<'a> ContractRunner<'a> {
             errors,
             predeploy_libs,
             debug,
+            corpus: Default::default(),
         }
     }
 }
@@ -139,10 +143,13 @@ impl<'a> ContractRunner<'a> {
 
         self.executor.deploy_create2_deployer()?;
 
+        // The test contract is always labeled with its name, unless `setUp` labels it itself
+        let label = self.name.rsplit(':').next().unwrap_or(self.name).to_string();
+
         // Optionally call the `setUp` function
         let setup = if setup {
             trace!("setting up");
-            let (setup_logs, setup_traces, labeled_addresses, reason, coverage) = match self
+            let (setup_logs, setup_traces, mut labeled_addresses, reason, coverage) = match self
                 .executor
                 .setup(None, address)
             {
@@ -162,10 +169,11 @@ impl<'a> ContractRunner<'a> {
             };
             traces.extend(setup_traces.map(|traces| (TraceKind::Setup, traces)));
             logs.extend(setup_logs);
+            labeled_addresses.entry(address).or_insert(label);
 
             TestSetup { address, logs, traces, labeled_addresses, reason, coverage }
         } else {
-            TestSetup::success(address, logs, traces, Default::default(), None)
+            TestSetup::success(address, logs, traces, BTreeMap::from([(address, label)]), None)
         };
 
         Ok(setup)
@@ -220,6 +228,7 @@ impl<'a> ContractRunner<'a> {
         }
 
         if setup.reason.is_some() {
+            test_options.fail_fast.record(TestStatus::Failure);
             // The setup failed, so we return a single test result for `setUp`
             return SuiteResult::new(
                 start.elapsed(),
@@ -244,31 +253,68 @@ impl<'a> ContractRunner<'a> {
         }
 
         let functions: Vec<_> = self.contract.functions().collect();
-        let mut test_results = functions
-            .par_iter()
-            .filter(|&&func| func.is_test() && filter.matches_test(&func.signature()))
-            .map(|&func| {
-                let should_fail = func.is_test_fail();
-                let res = if func.is_fuzz_test() {
-                    let runner = test_options.fuzz_runner(self.name, &func.name);
-                    let fuzz_config = test_options.fuzz_config(self.name, &func.name);
-                    self.run_fuzz_test(func, should_fail, runner, setup.clone(), *fuzz_config)
-                } else {
-                    self.run_test(func, should_fail, setup.clone())
-                };
-                (func.signature(), res)
-            })
-            .collect::<BTreeMap<_, _>>();
+        let mut tests: Vec<_> = functions
+            .iter()
+            .filter(|func| func.is_test() && filter.matches_test(&func.signature()))
+            .collect();
+        test_options.order.sort_tests(self.name, &mut tests, |func| func.signature());
+
+        // coverage-guided fuzz tests share the corpus of the contract, so they run one after
+        // another in alphabetical order, for a seed to reproduce the corpus of every test
+        let (mut guided, tests): (Vec<_>, Vec<_>) = tests.into_iter().partition(|func| {
+            func.is_fuzz_test() && test_options.fuzz_config(self.name, &func.name).coverage_guided
+        });
+        guided.sort_by_cached_key(|func| func.signature());
+
+        let execute = |func: &Function| {
+            let should_fail = func.is_test_fail();
+            let this = self.for_test(test_options, func);
+            let mut res = if func.is_fuzz_test() {
+                let runner = test_options.fuzz_runner(self.name, &func.name);
+                let fuzz_config = test_options.fuzz_config(self.name, &func.name);
+                this.run_fuzz_test(func, should_fail, runner, setup.clone(), *fuzz_config)
+            } else {
+                this.run_test(func, should_fail, setup.clone())
+            };
+            enforce_usage_limits(&mut res, &test_options.usage);
+            test_options.fail_fast.record(res.status);
+            (func.signature(), res)
+        };
+        let (mut test_results, guided_results) = rayon::join(
+            || {
+                tests
+                    .into_iter()
+                    .par_bridge()
+                    .filter(|_| !test_options.fail_fast.should_stop())
+                    .map(|&func| execute(func))
+                    .collect::<BTreeMap<_, _>>()
+            },
+            || {
+                guided
+                    .into_iter()
+                    .filter(|_| !test_options.fail_fast.should_stop())
+                    .map(|&func| execute(func))
+                    .collect::<Vec<_>>()
+            },
+        );
+        test_results.extend(guided_results);
 
         if has_invariants {
             let identified_contracts = load_contracts(setup.traces.clone(), known_contracts);
-            let results: Vec<_> = functions
-                .par_iter()
-                .filter(|&&func| func.is_invariant_test() && filter.matches_test(&func.signature()))
+            let mut invariants: Vec<_> = functions
+                .iter()
+                .filter(|func| func.is_invariant_test() && filter.matches_test(&func.signature()))
+                .collect();
+            test_options.order.sort_tests(self.name, &mut invariants, |func| func.signature());
+            let results: Vec<_> = invariants
+                .into_iter()
+                .par_bridge()
+                .filter(|_| !test_options.fail_fast.should_stop())
                 .map(|&func| {
+                    let start = Instant::now();
                     let runner = test_options.invariant_runner(self.name, &func.name);
                     let invariant_config = test_options.invariant_config(self.name, &func.name);
-                    let res = self.run_invariant_test(
+                    let mut res = self.for_test(test_options, func).run_invariant_test(
                         runner,
                         setup.clone(),
                         *invariant_config,
@@ -276,6 +322,10 @@ impl<'a> ContractRunner<'a> {
                         known_contracts,
                         &identified_contracts,
                     );
+                    res.seed = test_options.fuzz.seed;
+                    res.duration = start.elapsed();
+                    enforce_usage_limits(&mut res, &test_options.usage);
+                    test_options.fail_fast.record(res.status);
                     (func.signature(), res)
                 })
                 .collect();
@@ -297,6 +347,18 @@ impl<'a> ContractRunner<'a> {
         SuiteResult::new(duration, test_results, warnings)
     }
 
+    /// Returns the runner of a test function, with the gas limit of its inline configuration.
+    fn for_test(&self, test_options: &TestOptions, func: &Function) -> Cow<'_, Self> {
+        match test_options.gas_limit(self.name, &func.name) {
+            Some(gas_limit) => {
+                let mut runner = self.clone();
+                runner.executor.set_gas_limit(U256::from(gas_limit));
+                Cow::Owned(runner)
+            }
+            None => Cow::Borrowed(self),
+        }
+    }
+
     /// Runs a single test
     ///
     /// Calls the given functions and returns the `TestResult`.
@@ -313,7 +375,8 @@ impl<'a> ContractRunner<'a> {
         let mut executor = self.executor.clone();
         let start = Instant::now();
         let debug_arena;
-        let (reverted, reason, gas, stipend, coverage, state_changeset, breakpoints) =
+        let mut usage = None;
+        let (reverted, reason, gas, stipend, coverage, state_changeset, breakpoints, xfail) =
             match executor.execute_test::<_, _>(
                 self.sender,
                 address,
@@ -333,15 +396,18 @@ impl<'a> ContractRunner<'a> {
                     state_changeset,
                     debug,
                     breakpoints,
+                    xfail,
+                    usage: execution_usage,
                     ..
                 }) => {
                     traces.extend(execution_trace.map(|traces| (TraceKind::Execution, traces)));
                     labeled_addresses.extend(new_labels);
                     logs.extend(execution_logs);
                     debug_arena = debug;
+                    usage = execution_usage;
                     coverage = merge_coverages(coverage, execution_coverage);
 
-                    (reverted, None, gas, stipend, coverage, state_changeset, breakpoints)
+                    (reverted, None, gas, stipend, coverage, state_changeset, breakpoints, xfail)
                 }
                 Err(EvmError::Execution(err)) => {
                     traces.extend(err.traces.map(|traces| (TraceKind::Execution, traces)));
@@ -356,12 +422,13 @@ impl<'a> ContractRunner<'a> {
                         None,
                         err.state_changeset,
                         HashMap::new(),
+                        err.xfail,
                     )
                 }
-                Err(EvmError::SkipError) => {
+                Err(EvmError::SkipError(reason)) => {
                     return TestResult {
                         status: TestStatus::Skipped,
-                        reason: None,
+                        reason,
                         decoded_logs: decode_console_logs(&logs),
                         traces,
                         labeled_addresses,
@@ -398,11 +465,19 @@ impl<'a> ContractRunner<'a> {
             success,
         );
 
+        // Tests marked with `xfail` are expected to fail
+        let (status, reason) = match (success, xfail) {
+            (true, None) => (TestStatus::Success, reason),
+            (false, None) => (TestStatus::Failure, reason),
+            (false, Some(xfail)) => (TestStatus::ExpectedFailure, Some(xfail)),
+            (true, Some(xfail)) => (
+                TestStatus::Failure,
+                Some(format!("test passed, but was expected to fail: {xfail}")),
+            ),
+        };
+
         TestResult {
-            status: match success {
-                true => TestStatus::Success,
-                false => TestStatus::Failure,
-            },
+            status,
             reason,
             counterexample: None,
             decoded_logs: decode_console_logs(&logs),
@@ -413,6 +488,9 @@ impl<'a> ContractRunner<'a> {
             labeled_addresses,
             debug: debug_arena,
             breakpoints,
+            seed: None,
+            usage,
+            duration: start.elapsed(),
         }
     }
 
@@ -432,7 +510,7 @@ impl<'a> ContractRunner<'a> {
         let TestSetup { address, logs, traces, labeled_addresses, coverage, .. } = setup;
 
         // First, run the test normally to see if it needs to be skipped.
-        if let Err(EvmError::SkipError) = self.executor.clone().execute_test::<_, _>(
+        if let Err(EvmError::SkipError(reason)) = self.executor.clone().execute_test::<_, _>(
             self.sender,
             address,
             func.clone(),
@@ -442,7 +520,7 @@ impl<'a> ContractRunner<'a> {
         ) {
             return TestResult {
                 status: TestStatus::Skipped,
-                reason: None,
+                reason,
                 decoded_logs: decode_console_logs(&logs),
                 traces,
                 labeled_addresses,
@@ -463,7 +541,7 @@ impl<'a> ContractRunner<'a> {
         let invariant_contract =
             InvariantContract { address, invariant_function: func, abi: self.contract };
 
-        let InvariantFuzzTestResult { error, cases, reverts, last_run_inputs } = match evm
+        let InvariantFuzzTestResult { error, cases, reverts, last_run_inputs, usage } = match evm
             .invariant_fuzz(invariant_contract.clone())
         {
             Ok(x) => x,
@@ -498,7 +576,12 @@ impl<'a> ContractRunner<'a> {
                     &mut logs,
                     &mut traces,
                 ) {
-                    Ok(c) => counterexample = c,
+                    Ok(c) => {
+                        counterexample = c.map(|mut c| {
+                            c.apply_labels(&labeled_addresses);
+                            c
+                        })
+                    }
                     Err(err) => {
                         error!(%err, "Failed to replay invariant error");
                     }
@@ -541,6 +624,7 @@ impl<'a> ContractRunner<'a> {
             coverage,
             traces,
             labeled_addresses: labeled_addresses.clone(),
+            usage,
             ..Default::default() // TODO collect debug traces on the last run or error
         }
     }
@@ -561,7 +645,8 @@ impl<'a> ContractRunner<'a> {
         // Run fuzz test
         let start = Instant::now();
         let fuzzed_executor =
-            FuzzedExecutor::new(self.executor.clone(), runner.clone(), self.sender, fuzz_config);
+            FuzzedExecutor::new(self.executor.clone(), runner.clone(), self.sender, fuzz_config)
+                .with_corpus(self.corpus.clone());
         let state = fuzzed_executor.build_fuzz_state();
         let mut result = fuzzed_executor.fuzz(func, address, should_fail, self.errors);
 
@@ -570,10 +655,10 @@ impl<'a> ContractRunner<'a> {
 
         // Check the last test result and skip the test
         // if it's marked as so.
-        if let Some("SKIPPED") = result.reason.as_deref() {
+        if let Some(skip) = result.reason.as_deref().and_then(|r| r.strip_prefix("SKIPPED")) {
             return TestResult {
                 status: TestStatus::Skipped,
-                reason: None,
+                reason: skip.strip_prefix(": ").map(str::to_string),
                 decoded_logs: decode_console_logs(&logs),
                 traces,
                 labeled_addresses,
@@ -634,6 +719,9 @@ impl<'a> ContractRunner<'a> {
         logs.append(&mut result.logs);
         labeled_addresses.append(&mut result.labeled_addresses);
         traces.extend(result.traces.map(|traces| (TraceKind::Execution, traces)));
+        if let Some(counterexample) = &mut result.counterexample {
+            counterexample.apply_labels(&labeled_addresses);
+        }
         coverage = merge_coverages(coverage, result.coverage);
 
         // Record test execution time
@@ -657,10 +745,23 @@ impl<'a> ContractRunner<'a> {
             labeled_addresses,
             debug,
             breakpoints,
+            seed: fuzz_config.seed,
+            usage: result.usage,
+            duration: start.elapsed(),
         }
     }
 }
 
+/// Fails passing tests whose peak usage, the maximum over all runs of fuzz and invariant tests,
+/// exceeds the configured limits.
+fn enforce_usage_limits(result: &mut TestResult, options: &UsageOptions) {
+    let exceeded = result.usage.as_ref().and_then(|usage| options.exceeded(usage));
+    if let (TestStatus::Success, Some(reason)) = (result.status, exceeded) {
+        result.status = TestStatus::Failure;
+        result.reason = Some(reason);
+    }
+}
+
 /// Utility function to merge coverage options
 fn merge_coverages(mut coverage: Option<HitMaps>, other: Option<HitMaps>) -> Option<HitMaps> {
     let old_coverage = std::mem::take(&mut coverage);
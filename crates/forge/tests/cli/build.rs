@@ -1,5 +1,4 @@
-use foundry_test_utils::{forgetest, util::OutputExt};
-use std::path::PathBuf;
+use foundry_test_utils::forgetest;
 
 // tests that json is printed when --json is passed
 forgetest!(compile_json, |prj, cmd| {
@@ -20,7 +19,135 @@ contract Dummy {
     cmd.args(["compile", "--format-json"]);
 
     // run command and assert
-    cmd.unchecked_output().stdout_matches_path(
-        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/compile_json.stdout"),
-    );
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["errors"][0]["errorCode"], "7576");
+    assert_eq!(json["errors"][0]["sourceLocation"]["file"], "src/jsonError.sol");
+
+    let diagnostic = &json["diagnostics"][0];
+    assert_eq!(diagnostic["severity"], "error");
+    assert_eq!(diagnostic["code"], "7576");
+    assert_eq!(diagnostic["message"], "Undeclared identifier. Did you mean \"newNumber\"?");
+    assert_eq!(diagnostic["file"], prj.paths().sources.join("jsonError.sol").display().to_string());
+    assert_eq!((diagnostic["line"].as_u64(), diagnostic["column"].as_u64()), (Some(7), Some(18)));
+});
+
+// tests that Vyper sources are compiled alongside Solidity sources
+forgetest!(can_build_vyper_sources, |prj, cmd| {
+    if foundry_common::vyper::Vyper::find().is_none() {
+        eprintln!("skipping, vyper is not installed");
+        return
+    }
+
+    prj.add_source("Dummy", "contract Dummy {}").unwrap();
+    std::fs::write(
+        prj.paths().sources.join("Counter.vy"),
+        r"
+number: public(uint256)
+
+@external
+def increment():
+    self.number += 1
+",
+    )
+    .unwrap();
+
+    cmd.arg("build");
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("Compiled 1 Vyper file with vyper"), "{output}");
+
+    let artifact: foundry_compilers::ConfigurableContractArtifact =
+        foundry_common::fs::read_json_file(&prj.paths().artifacts.join("Counter.vy/Counter.json"))
+            .unwrap();
+    assert!(artifact.method_identifiers.unwrap().contains_key("increment()"));
+    assert_eq!(artifact.storage_layout.unwrap().storage[0].label, "number");
+    assert!(prj.paths().artifacts.join("Dummy.sol/Dummy.json").exists());
+
+    // unchanged sources are not recompiled
+    cmd.forge_fuse().arg("build");
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("No Vyper files changed, compilation skipped"), "{output}");
+
+    // same-named sources in different directories don't overwrite each other's artifacts
+    let nested = prj.paths().sources.join("nested");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(nested.join("Counter.vy"), "count: public(uint256)\n").unwrap();
+    cmd.forge_fuse().arg("build");
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("Compiled 2 Vyper files with vyper"), "{output}");
+    assert!(prj.paths().artifacts.join("src/Counter.vy/Counter.json").exists());
+    assert!(prj.paths().artifacts.join("src/nested/Counter.vy/Counter.json").exists());
+});
+
+// tests that artifacts are stamped with the schema version and old artifacts can be migrated
+forgetest!(can_migrate_artifacts, |prj, cmd| {
+    prj.add_source(
+        "Counter",
+        r"
+contract Counter {
+    uint256 public number;
+    function increment() public {
+        number++;
+    }
+}
+",
+    )
+    .unwrap();
+
+    cmd.arg("build");
+    cmd.assert_non_empty_stdout();
+
+    let path = prj.paths().artifacts.join("Counter.sol/Counter.json");
+    let mut artifact: serde_json::Map<String, serde_json::Value> =
+        foundry_common::fs::read_json_file(&path).unwrap();
+    assert_eq!(artifact["schemaVersion"], foundry_common::artifacts::ARTIFACT_SCHEMA_VERSION);
+
+    // strip the artifact down to an unversioned one
+    artifact.remove("schemaVersion");
+    artifact.remove("methodIdentifiers");
+    foundry_common::fs::write_json_file(&path, &artifact).unwrap();
+
+    cmd.forge_fuse().args(["artifacts", "migrate", "--check"]);
+    cmd.assert_err();
+
+    cmd.forge_fuse().args(["artifacts", "migrate"]);
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("Migrated 1 artifact(s)"), "{output}");
+
+    let artifact = foundry_common::artifacts::VersionedArtifact::read(&path).unwrap();
+    assert!(artifact.method_identifiers.contains_key("increment()"));
+    assert!(artifact.method_identifiers.contains_key("number()"));
+
+    // artifacts built by the silent compilation paths are stamped as well
+    cmd.forge_fuse().args(["build", "--force", "--silent"]);
+    cmd.assert_success();
+    let artifact: serde_json::Map<String, serde_json::Value> =
+        foundry_common::fs::read_json_file(&path).unwrap();
+    assert_eq!(artifact["schemaVersion"], foundry_common::artifacts::ARTIFACT_SCHEMA_VERSION);
+});
+
+// tests that `--compare-pipelines` reports both pipelines without writing artifacts
+forgetest!(can_compare_pipelines, |prj, cmd| {
+    prj.add_source(
+        "Counter",
+        r"
+contract Counter {
+    uint256 public number;
+    function increment() public {
+        number++;
+    }
+}
+",
+    )
+    .unwrap();
+
+    cmd.args(["build", "--compare-pipelines"]);
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("Compiling with the legacy pipeline"), "{output}");
+    assert!(output.contains("Compiling with the IR pipeline"), "{output}");
+    assert!(output.contains("IR Size (kB)"), "{output}");
+    assert!(output.contains("| Counter"), "{output}");
+    assert!(output.contains("IR Runtime Gas"), "{output}");
+    assert!(output.contains("| increment()"), "{output}");
+    assert!(!prj.paths().artifacts.join("Counter.sol/Counter.json").exists());
 });
@@ -1,8 +1,12 @@
 //! Contains various tests for checking forge's commands
 
 use crate::constants::*;
+use alloy_primitives::{Address, B256};
+use foundry_common::deployments::{DeploymentManifest, DeploymentRegistry};
 use foundry_compilers::{artifacts::Metadata, remappings::Remapping, ConfigurableContractArtifact};
-use foundry_config::{parse_with_profile, BasicConfig, Chain, Config, SolidityErrorCode};
+use foundry_config::{
+    parse_with_profile, BasicConfig, Chain, Config, LintConfig, LintSeverity, SolidityErrorCode,
+};
 use foundry_test_utils::{
     foundry_compilers::PathStyle,
     util::{pretty_err, read_string, OutputExt, TestCommand},
@@ -425,6 +429,27 @@ forgetest!(can_init_template_with_branch, |prj, cmd| {
     assert!(prj.root().join("scripts").exists());
 });
 
+// checks that forge can init with a built-in template and renders its placeholders
+forgetest!(can_init_builtin_template, |prj, cmd| {
+    prj.wipe();
+    cmd.args(["init", "--template", "erc20", "--offline"]).arg(prj.root());
+    cmd.assert_non_empty_stdout();
+
+    let token = std::fs::read_to_string(prj.root().join("src/Token.sol")).unwrap();
+    assert!(token.contains("pragma solidity ^0.8.20;"));
+    let name = prj.root().file_name().unwrap().to_string_lossy();
+    assert!(token.contains(&format!("ERC20(\"{name}\", \"TKN\")")));
+    assert!(prj.root().join("test/Token.t.sol").exists());
+    assert!(prj.root().join("script/Token.s.sol").exists());
+    assert!(!prj.root().join("src/Counter.sol").exists());
+
+    let remappings = std::fs::read_to_string(prj.root().join("remappings.txt")).unwrap();
+    assert_eq!(remappings, "@openzeppelin/contracts/=lib/openzeppelin-contracts/contracts/\n");
+
+    cmd.forge_fuse().args(["init", "--template", "erc20", "--branch", "main"]).arg(prj.root());
+    cmd.assert_non_empty_stderr();
+});
+
 // checks that init fails when the provided template doesn't exist
 forgetest!(fail_init_nonexistent_template, |prj, cmd| {
     prj.wipe();
@@ -1596,6 +1621,120 @@ forgetest_init!(can_build_sizes_repeatedly, |prj, cmd| {
     assert!(unchanged.contains(table), "{}", table);
 });
 
+// checks that the settings fingerprint is stored in the artifacts, and that artifacts built with
+// other settings are detected even without the fingerprints index
+forgetest_init!(can_detect_settings_mismatch_from_artifacts, |prj, cmd| {
+    prj.clear_cache();
+
+    cmd.args(["build"]);
+    cmd.assert_non_empty_stdout();
+
+    let artifact =
+        prj.artifacts().join(format!("{TEMPLATE_CONTRACT}.sol/{TEMPLATE_CONTRACT}.json"));
+    let mut json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&artifact).unwrap()).unwrap();
+    assert!(json["settingsFingerprint"].is_string(), "{json}");
+
+    // pretend the artifact was built with other settings, and drop the index
+    json["settingsFingerprint"] = B256::repeat_byte(1).to_string().into();
+    fs::write(&artifact, json.to_string()).unwrap();
+    let index = prj.cache().with_file_name("settings-fingerprints.json");
+    assert!(index.exists());
+    fs::remove_file(&index).unwrap();
+
+    cmd.forge_fuse().args(["test"]);
+    let output = cmd.unchecked_output();
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(err.contains("were built with different compiler settings"), "{err}");
+    assert!(err.contains(&format!("{TEMPLATE_CONTRACT}.json")), "{err}");
+});
+
+// checks that `build --sizes` only fails on oversized contracts with `--check`
+forgetest!(can_check_sizes, |prj, cmd| {
+    prj.add_source(
+        "Big.sol",
+        &format!(
+            r#"
+contract Big {{
+    function data() external pure returns (bytes memory) {{
+        return hex"{}";
+    }}
+}}
+   "#,
+            "01".repeat(25_000)
+        ),
+    )
+    .unwrap();
+
+    cmd.args(["build", "--sizes"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("Big"), "{out}");
+    assert!(out.contains("Initcode Margin (kB)"), "{out}");
+
+    let assert_oversized = |output: std::process::Output| {
+        let err = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "{err}");
+        assert!(err.contains("contracts exceed the size limits: Big"), "{err}");
+    };
+    cmd.arg("--check");
+    assert_oversized(cmd.unchecked_output());
+
+    cmd.forge_fuse().args(["size", "--check"]);
+    assert_oversized(cmd.unchecked_output());
+});
+
+// checks that `forge size` reports both runtime and initcode sizes
+forgetest_init!(can_report_contract_sizes, |prj, cmd| {
+    prj.clear_cache();
+
+    cmd.args(["size", "--json"]);
+    let out = cmd.stdout_lossy();
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    let counter = &report[TEMPLATE_CONTRACT];
+    assert!(counter["size"].as_u64().unwrap() > 0);
+    assert!(counter["init_size"].as_u64().unwrap() > counter["size"].as_u64().unwrap());
+
+    // test and script contracts are excluded by default
+    assert!(report.get("CounterTest").is_none());
+
+    // the counter is well below the limits
+    cmd.forge_fuse().args(["size", "--check"]);
+    cmd.assert_non_empty_stdout();
+
+    cmd.forge_fuse().args(["inspect", TEMPLATE_CONTRACT, "size"]);
+    let out = cmd.stdout_lossy();
+    let size: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(size["size"], counter["size"]);
+    assert_eq!(size["init_size"], counter["init_size"]);
+});
+
+// checks that deployments recorded in the registry can be queried
+forgetest!(can_query_deployments, |prj, cmd| {
+    let registry = DeploymentRegistry::new(prj.root());
+    let manifest = DeploymentManifest {
+        contract_name: "Token".to_string(),
+        address: Address::repeat_byte(0x11),
+        transaction_hash: B256::repeat_byte(0x22),
+        arguments: vec![],
+        bytecode_hash: B256::repeat_byte(0x33),
+        commit: None,
+        timestamp: 0,
+    };
+    registry.save(1, &manifest).unwrap();
+
+    cmd.args(["deployments", "get", "Token", "--chain", "1"]);
+    assert_eq!(cmd.stdout_lossy().trim(), manifest.address.to_string());
+
+    cmd.forge_fuse().args(["deployments", "get", "Token", "--chain", "10"]);
+    cmd.assert_err();
+
+    cmd.forge_fuse().args(["deployments", "ls", "--json"]);
+    let deployments: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    assert_eq!(deployments[0]["contractName"], "Token");
+    assert_eq!(deployments[0]["chainId"], 1);
+});
+
 // checks that build --names includes all contracts even if unchanged
 forgetest_init!(can_build_names_repeatedly, |prj, cmd| {
     prj.clear_cache();
@@ -1611,3 +1750,94 @@ forgetest_init!(can_build_names_repeatedly, |prj, cmd| {
     let unchanged = cmd.stdout_lossy();
     assert!(unchanged.contains(list), "{}", list);
 });
+
+// checks that `forge lint` reports findings with their configured severity and fixes them
+forgetest!(can_lint_and_fix, |prj, cmd| {
+    let path = prj
+        .add_source(
+            "Vault",
+            r#"
+pragma solidity ^0.8.13;
+
+contract Vault {
+    uint256 total;
+
+    function deposit(uint256 total) external {
+        payable(msg.sender).send(total);
+    }
+}
+"#,
+        )
+        .unwrap();
+    let config = Config {
+        lint: LintConfig {
+            severity: [("unchecked-call".to_string(), LintSeverity::Error)].into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    prj.write_config(config);
+
+    cmd.args(["lint"]);
+    let (stdout, stderr) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("info[floating-pragma]"), "{stdout}");
+    assert!(stdout.contains("warning[missing-visibility]"), "{stdout}");
+    assert!(stdout.contains("warning[shadowing]"), "{stdout}");
+    assert!(stdout.contains("error[unchecked-call]"), "{stdout}");
+    assert!(stdout.contains("--> src/Vault.sol:9:9"), "{stdout}");
+    assert!(stderr.contains("Linting failed with 1 error and 2 warnings"), "{stderr}");
+
+    cmd.arg("--fix");
+    let stdout = cmd.unchecked_output_lossy().0;
+    assert!(stdout.contains("Fixed 2 findings"), "{stdout}");
+    assert!(!stdout.contains("missing-visibility"), "{stdout}");
+
+    let fixed = fs::read_to_string(path).unwrap();
+    assert!(fixed.contains("pragma solidity 0.8.13;"), "{fixed}");
+    assert!(fixed.contains("uint256 internal total;"), "{fixed}");
+});
+
+forgetest!(can_analyze_import_graph, |prj, cmd| {
+    prj.add_source("Bar", "pragma solidity ^0.8.0; contract Bar {}").unwrap();
+    prj.add_source("Foo", r#"pragma solidity ^0.8.0; import "./Bar.sol"; contract Foo is Bar {}"#)
+        .unwrap();
+    prj.add_source("Unused", "pragma solidity ^0.8.0; contract Bar {}").unwrap();
+
+    cmd.args(["tree", "--unused"]);
+    let (stdout, stderr) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("src/Foo.sol"), "{stdout}");
+    assert!(stdout.contains("src/Unused.sol"), "{stdout}");
+    assert!(!stdout.contains("src/Bar.sol"), "{stdout}");
+    assert!(stderr.contains("contract `Bar` is defined in multiple files"), "{stderr}");
+
+    cmd.forge_fuse().args(["tree", "--format", "dot"]);
+    let stdout = cmd.unchecked_output_lossy().0;
+    assert!(stdout.contains("\"src/Foo.sol\" -> \"src/Bar.sol\";"), "{stdout}");
+
+    cmd.forge_fuse().args(["tree", "--contracts"]);
+    let stdout = cmd.unchecked_output_lossy().0;
+    assert!(stdout.contains("Foo (src/Foo.sol)\n└── Bar (src/Bar.sol)"), "{stdout}");
+});
+
+forgetest!(can_eject_abis, |prj, cmd| {
+    prj.add_source("Counter", "pragma solidity ^0.8.0; contract Counter { uint256 public count; }")
+        .unwrap();
+    prj.add_source("Vault", "pragma solidity ^0.8.0; contract Vault {}").unwrap();
+
+    cmd.args(["eject-abi", "--select", "Counter"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("1 file(s) written, 0 unchanged"), "{stdout}");
+    let abi = std::fs::read_to_string(prj.root().join("abis/Counter.json")).unwrap();
+    assert!(abi.contains("\"contractName\": \"Counter\""), "{abi}");
+    assert!(!prj.root().join("abis/Vault.json").exists());
+
+    // nothing is rewritten if the ABIs didn't change
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("0 file(s) written, 1 unchanged"), "{stdout}");
+
+    cmd.forge_fuse().args(["eject-abi", "--ts", "abis.ts"]);
+    cmd.assert_non_empty_stdout();
+    let ts = std::fs::read_to_string(prj.root().join("abis.ts")).unwrap();
+    assert!(ts.contains("export const CounterAbi = ["), "{ts}");
+    assert!(ts.contains("export const VaultAddresses = {} as const;"), "{ts}");
+});
@@ -45,6 +45,7 @@ forgetest!(can_extract_config_values, |prj, cmd| {
             yul_details: Some(YulDetails { stack_allocation: Some(true), ..Default::default() }),
             ..Default::default()
         }),
+        optimizer_preset: None,
         model_checker: None,
         extra_output: Default::default(),
         extra_output_files: Default::default(),
@@ -64,6 +65,7 @@ forgetest!(can_extract_config_values, |prj, cmd| {
         },
         invariant: InvariantConfig { runs: 256, ..Default::default() },
         ffi: true,
+        isolate: false,
         sender: "00a329c0648769A73afAc7F9381D08FB43dBEA72".parse().unwrap(),
         tx_origin: "00a329c0648769A73afAc7F9F81E08FB43dBEA72".parse().unwrap(),
         initial_balance: U256::from(0xffffffffffffffffffffffffu128),
@@ -80,6 +82,8 @@ forgetest!(can_extract_config_values, |prj, cmd| {
         block_prevrandao: B256::random(),
         block_gas_limit: Some(100u64.into()),
         memory_limit: 1 << 27,
+        max_call_depth: Some(512),
+        max_memory_expansion: Some(1 << 20),
         eth_rpc_url: Some("localhost".to_string()),
         eth_rpc_jwt: None,
         etherscan_api_key: None,
@@ -91,6 +95,7 @@ forgetest!(can_extract_config_values, |prj, cmd| {
         ],
         ignored_error_codes: vec![],
         deny_warnings: false,
+        deny_settings_mismatch: false,
         via_ir: true,
         rpc_storage_caching: StorageCachingConfig {
             chains: CachedChains::None,
@@ -110,6 +115,7 @@ forgetest!(can_extract_config_values, |prj, cmd| {
         build_info_path: None,
         fmt: Default::default(),
         doc: Default::default(),
+        lint: Default::default(),
         fs_permissions: Default::default(),
         cancun: true,
         __non_exhaustive: (),
@@ -78,3 +78,50 @@ contract AContractTest is DSTest {
         .unwrap() >
         0)));
 });
+
+forgetest!(include_scripts_coverage, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_script(
+        "Deploy.s.sol",
+        r#"
+contract Deploy {
+    uint256 public deployed;
+
+    function run() public {
+        deployed = 1;
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    prj.add_source(
+        "DeployTest.sol",
+        r#"
+import "./test.sol";
+import {Deploy} from "../script/Deploy.s.sol";
+
+contract DeployTest is DSTest {
+    function testRun() public {
+        new Deploy().run();
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    let lcov_info = prj.root().join("lcov.info");
+    let args = ["coverage", "--report", "lcov", "--report-file", lcov_info.to_str().unwrap()];
+
+    // scripts are excluded by default
+    cmd.args(args);
+    cmd.assert_success();
+    let lcov_data = std::fs::read_to_string(&lcov_info).unwrap();
+    assert!(!lcov_data.contains("Deploy.s.sol"), "{lcov_data}");
+
+    cmd.forge_fuse().args(args).arg("--include-scripts");
+    cmd.assert_success();
+    let lcov_data = std::fs::read_to_string(&lcov_info).unwrap();
+    assert!(lcov_data.contains("SF:script/Deploy.s.sol"), "{lcov_data}");
+    assert!(lcov_data.contains("FNDA:1,Deploy.run"), "{lcov_data}");
+});
@@ -4,10 +4,10 @@ use crate::constants::TEMPLATE_CONTRACT;
 use alloy_primitives::Address;
 use anvil::{spawn, NodeConfig};
 use foundry_common::{rpc, types::ToEthers};
-use foundry_test_utils::{util::OutputExt, ScriptOutcome, ScriptTester};
+use foundry_test_utils::{util::OutputExt, ScriptOutcome, ScriptTester, TestCommand};
 use regex::Regex;
 use serde_json::Value;
-use std::{env, path::PathBuf, str::FromStr};
+use std::{env, io::Write, path::PathBuf, str::FromStr};
 
 // Tests that fork cheat codes can be used in script
 forgetest_init!(
@@ -375,6 +375,290 @@ contract RunScript is Script {
     assert!(output.contains("ONCHAIN EXECUTION COMPLETE & SUCCESSFUL"));
 });
 
+// Tests that a reviewed transaction plan can be enforced when broadcasting
+forgetest_async!(can_enforce_reviewed_transaction_plan, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let deploy_script = prj
+        .add_source(
+            "Foo",
+            r#"
+import "forge-std/Script.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function setNumber(uint256 newNumber) public {
+        number = newNumber;
+    }
+}
+contract DeployScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        Counter counter = new Counter();
+        counter.setNumber(42);
+    }
+}
+   "#,
+        )
+        .unwrap();
+    let deploy_contract = deploy_script.display().to_string() + ":DeployScript";
+
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let dev = format!("{:?}", handle.dev_accounts().next().unwrap());
+    let endpoint = handle.http_endpoint();
+    let root = prj.root().to_str().unwrap().to_string();
+    let script_args = |cmd: &mut TestCommand| {
+        cmd.args([
+            "script",
+            &deploy_contract,
+            "--root",
+            &root,
+            "--fork-url",
+            &endpoint,
+            "--sender",
+            &dev,
+            "--unlocked",
+        ]);
+    };
+    cmd.set_current_dir(prj.root());
+
+    script_args(&mut cmd);
+    cmd.args(["--plan-out", "plan.json"]);
+    cmd.assert_non_empty_stdout();
+    let plan: Value =
+        serde_json::from_str(&std::fs::read_to_string(prj.root().join("plan.json")).unwrap())
+            .unwrap();
+    let hash = plan["hash"].as_str().unwrap().to_string();
+    assert_eq!(plan["transactions"][0]["kind"], "create");
+    assert_eq!(plan["transactions"][1]["function"], "setNumber(uint256)");
+    assert_eq!(plan["transactions"][1]["arguments"][0], "42");
+
+    script_args(cmd.forge_fuse());
+    cmd.args(["--plan-out", "plan.md"]);
+    cmd.assert_non_empty_stdout();
+    let markdown = std::fs::read_to_string(prj.root().join("plan.md")).unwrap();
+    assert!(markdown.contains(&format!("Plan hash: `{hash}`")));
+
+    script_args(cmd.forge_fuse());
+    cmd.args(["--broadcast", "--require-plan-hash", &format!("0x{}", "00".repeat(32))]);
+    cmd.assert_err();
+
+    script_args(cmd.forge_fuse());
+    cmd.args(["--broadcast", "--require-plan-hash", &hash]);
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("ONCHAIN EXECUTION COMPLETE & SUCCESSFUL"), "{output}");
+});
+
+// checks that `--interactive-broadcast` only skips transactions the later ones don't depend on
+forgetest_async!(can_skip_transactions_interactively, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let script = prj
+        .add_source(
+            "Foo",
+            r#"
+import "forge-std/Script.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function setNumber(uint256 newNumber) public {
+        number = newNumber;
+    }
+}
+contract DeployScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        Counter counter = new Counter();
+        counter.setNumber(42);
+        counter.setNumber(7);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let dev = handle.dev_accounts().next().unwrap();
+    cmd.set_current_dir(prj.root());
+    cmd.args([
+        "script",
+        &(script.display().to_string() + ":DeployScript"),
+        "--root",
+        prj.root().to_str().unwrap(),
+        "--fork-url",
+        &handle.http_endpoint(),
+        "--sender",
+        &format!("{dev:?}"),
+        "--unlocked",
+        "--broadcast",
+        "--interactive-broadcast",
+    ]);
+    // skipping the deployment is refused since the next transaction calls the contract
+    cmd.stdin(|mut stdin| stdin.write_all(b"s\ny\ns\ny\n").unwrap());
+
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("This transaction can't be skipped, transaction 2 uses"), "{output}");
+    assert!(output.contains("ONCHAIN EXECUTION COMPLETE & SUCCESSFUL"), "{output}");
+    assert_eq!(api.transaction_count(dev, None).await.unwrap().as_u64(), 2);
+});
+
+// checks that a script can be re-run with `--skip-deployed`
+forgetest_async!(can_rerun_script_skipping_deployed, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let script = prj
+        .add_source(
+            "Foo",
+            r#"
+import "forge-std/Script.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function setNumber(uint256 newNumber) public {
+        number = newNumber;
+    }
+}
+contract DeployScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        Counter counter = new Counter{salt: bytes32(0)}();
+        counter.setNumber(42);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let dev = handle.dev_accounts().next().unwrap();
+    let target = script.display().to_string() + ":DeployScript";
+    let endpoint = handle.http_endpoint();
+    let sender = format!("{dev:?}");
+    let root = prj.root().to_str().unwrap().to_string();
+    let script_args = |cmd: &mut TestCommand| {
+        cmd.args([
+            "script",
+            &target,
+            "--root",
+            &root,
+            "--fork-url",
+            &endpoint,
+            "--sender",
+            &sender,
+            "--unlocked",
+            "--broadcast",
+        ]);
+    };
+    cmd.set_current_dir(prj.root());
+
+    script_args(&mut cmd);
+    cmd.assert_non_empty_stdout();
+    assert_eq!(api.transaction_count(dev, None).await.unwrap().as_u64(), 2);
+    let run: Value = serde_json::from_str(
+        &std::fs::read_to_string(prj.root().join("broadcast/Foo.sol/31337/run-latest.json"))
+            .unwrap(),
+    )
+    .unwrap();
+    let counter = run["transactions"][0]["contractAddress"].as_str().unwrap().to_string();
+
+    // the deployment collides
+    script_args(cmd.forge_fuse());
+    cmd.assert_err();
+
+    // both transactions are skipped
+    script_args(cmd.forge_fuse());
+    cmd.args(["--skip-deployed", "--skip-if", &format!("{counter}.setNumber:0=42")]);
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("is already applied"), "{output}");
+    assert_eq!(api.transaction_count(dev, None).await.unwrap().as_u64(), 2);
+});
+
+// checks that a chained dry run resolves the deployments simulated by earlier scripts
+forgetest_async!(can_simulate_chained_scripts, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let token = prj
+        .add_source(
+            "DeployToken",
+            r#"
+import "forge-std/Script.sol";
+
+contract Token {}
+
+/// @custom:deploys Token
+contract DeployToken is Script {
+    function run() external {
+        vm.broadcast();
+        new Token();
+    }
+}
+   "#,
+        )
+        .unwrap();
+    let vault = prj
+        .add_source(
+            "DeployVault",
+            r#"
+import "forge-std/Script.sol";
+
+interface Deployments {
+    function getDeployment(string calldata contractName) external view returns (address);
+}
+
+contract Vault {
+    address public token;
+
+    constructor(address _token) {
+        token = _token;
+    }
+}
+
+/// @custom:deploys Vault
+/// @custom:depends-on Token
+contract DeployVault is Script {
+    function run() external {
+        address token = Deployments(address(vm)).getDeployment("Token");
+        vm.broadcast();
+        new Vault(token);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let dev = format!("{:?}", handle.dev_accounts().next().unwrap());
+    cmd.set_current_dir(prj.root());
+    // the vault is passed first, but depends on the token
+    cmd.args([
+        "script",
+        "--chain-run",
+        &format!("{}:DeployVault", vault.display()),
+        &format!("{}:DeployToken", token.display()),
+        "--root",
+        prj.root().to_str().unwrap(),
+        "--fork-url",
+        &handle.http_endpoint(),
+        "--sender",
+        &dev,
+    ]);
+    let output = cmd.stdout_lossy();
+    let token_run = output.find("Running").unwrap();
+    assert!(output[token_run..].starts_with(&format!("Running {}:DeployToken", token.display())));
+    assert!(output.contains(&format!("Running {}:DeployVault", vault.display())), "{output}");
+
+    let run_latest = |script: &str| -> Value {
+        let path = prj.root().join(format!("broadcast/{script}.sol/31337/dry-run/run-latest.json"));
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    };
+    let token_address = run_latest("DeployToken")["transactions"][0]["contractAddress"].clone();
+    let vault_tx = &run_latest("DeployVault")["transactions"][0];
+    assert_eq!(vault_tx["contractName"], "Vault");
+    assert_eq!(
+        vault_tx["arguments"][0].as_str().unwrap().to_lowercase(),
+        token_address.as_str().unwrap().to_lowercase()
+    );
+});
+
 forgetest_async!(can_deploy_script_without_lib, |prj, cmd| {
     let (_api, handle) = spawn(NodeConfig::test()).await;
     let mut tester = ScriptTester::new_broadcast(cmd, &handle.http_endpoint(), prj.root());
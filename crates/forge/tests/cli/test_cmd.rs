@@ -2,7 +2,7 @@
 use foundry_common::rpc;
 use foundry_config::Config;
 use foundry_test_utils::util::{OutputExt, OTHER_SOLC_VERSION, SOLC_VERSION};
-use std::{path::PathBuf, process::Command, str::FromStr};
+use std::{collections::BTreeMap, path::PathBuf, process::Command, str::FromStr};
 
 // tests that test filters are handled correctly
 forgetest!(can_set_filter_values, |prj, cmd| {
@@ -432,3 +432,324 @@ contract CustomTypesTest is Test {
             .join("tests/fixtures/include_custom_types_in_traces.stdout"),
     );
 });
+
+// tests that skipped and expected-to-fail tests are reported with their reasons
+forgetest!(can_report_skipped_and_expected_failures, |prj, cmd| {
+    prj.insert_ds_test();
+
+    prj.add_source(
+        "MarkersTest.t.sol",
+        r#"
+import "./test.sol";
+
+interface Vm {
+    function skip(bool skipTest, string calldata reason) external;
+    function xfail(string calldata reason) external;
+}
+
+contract MarkersTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testSkipped() public {
+        vm.skip(true, "not supported on this chain");
+        revert("should not be reached");
+    }
+
+    function testExpectedFailure() public {
+        vm.xfail("known bug");
+        assertTrue(false);
+    }
+
+    function testUnexpectedPass() public {
+        vm.xfail("fixed bug");
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.arg("test");
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    assert!(
+        stdout.contains("[SKIP. Reason: not supported on this chain] testSkipped()"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("[XFAIL. Reason: known bug] testExpectedFailure()"), "{stdout}");
+    assert!(
+        stdout.contains("[FAIL. Reason: test passed, but was expected to fail: fixed bug]"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("0 passed; 1 failed; 1 skipped; 1 expected to fail;"), "{stdout}");
+
+    cmd.arg("--json");
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    let results: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let suite = &results["src/MarkersTest.t.sol:MarkersTest"];
+    assert_eq!(suite["passed"], 0);
+    assert_eq!(suite["failed"], 1);
+    assert_eq!(suite["skipped"], 1);
+    assert_eq!(suite["expected_failures"], 1);
+    assert_eq!(suite["test_results"]["testExpectedFailure()"]["status"], "ExpectedFailure");
+    assert_eq!(suite["test_results"]["testSkipped()"]["reason"], "not supported on this chain");
+});
+
+// tests that `vm.label` labels and test contract names are exported as JSON
+forgetest!(can_export_labels, |prj, cmd| {
+    prj.insert_ds_test();
+
+    prj.add_source(
+        "LabelsTest.t.sol",
+        r#"
+import "./test.sol";
+
+interface Vm {
+    function label(address account, string calldata newLabel) external;
+}
+
+contract LabelsTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+    address constant ALICE = address(0xA11CE);
+
+    function setUp() public {
+        vm.label(ALICE, "alice");
+    }
+
+    function testLabel() public {
+        assertTrue(ALICE != address(this));
+    }
+}
+
+contract OtherLabelsTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+    address constant ALICE = address(0xA11CE);
+
+    function setUp() public {
+        vm.label(ALICE, "bob");
+    }
+
+    function testLabel() public {
+        assertTrue(ALICE != address(this));
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    let labels = prj.root().join("labels.json");
+    cmd.args(["test", "--export-labels"]).arg(&labels);
+    cmd.assert_success();
+
+    let labels: BTreeMap<String, BTreeMap<String, String>> =
+        serde_json::from_str(&std::fs::read_to_string(labels).unwrap()).unwrap();
+    let suite_labels = |suite: &str| {
+        labels
+            .iter()
+            .find(|(name, _)| name.ends_with(&format!(":{suite}")))
+            .map(|(_, labels)| labels.clone())
+            .unwrap_or_else(|| panic!("no labels for {suite}: {labels:?}"))
+    };
+    let alice = |labels: &BTreeMap<String, String>| {
+        labels
+            .iter()
+            .find(|(address, _)| {
+                address.eq_ignore_ascii_case("0x00000000000000000000000000000000000A11cE")
+            })
+            .map(|(_, label)| label.clone())
+    };
+
+    let labels_test = suite_labels("LabelsTest");
+    assert_eq!(alice(&labels_test).as_deref(), Some("alice"), "{labels_test:?}");
+    assert!(labels_test.values().any(|label| label == "LabelsTest"), "{labels_test:?}");
+
+    let other_labels_test = suite_labels("OtherLabelsTest");
+    assert_eq!(alice(&other_labels_test).as_deref(), Some("bob"), "{other_labels_test:?}");
+});
+
+// tests that the peak call depth is reported and tests, including fuzz tests, exceeding the limit
+// fail
+forgetest!(can_fail_on_usage_limits, |prj, cmd| {
+    prj.insert_ds_test();
+
+    prj.add_source(
+        "UsageTest.t.sol",
+        r#"
+import "./test.sol";
+
+contract Recurse {
+    function recurse(uint256 depth) external returns (uint256) {
+        if (depth == 0) return 0;
+        return Recurse(address(this)).recurse(depth - 1) + 1;
+    }
+}
+
+contract UsageTest is DSTest {
+    Recurse recurse;
+
+    function setUp() public {
+        recurse = new Recurse();
+    }
+
+    function testShallow() public {
+        assertEq(recurse.recurse(2), 2);
+    }
+
+    function testDeep() public {
+        assertEq(recurse.recurse(20), 20);
+    }
+
+    function testFuzzDeep(uint8 x) public {
+        assertEq(recurse.recurse(uint256(x % 4) + 12), uint256(x % 4) + 12);
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--usage"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("testShallow() (gas:"), "{stdout}");
+    assert!(stdout.contains("(depth: 3, memory:"), "{stdout}");
+    assert!(stdout.contains("(depth: 21, memory:"), "{stdout}");
+
+    prj.write_config(Config { max_call_depth: Some(10), ..Default::default() });
+    cmd.forge_fuse().args(["test"]);
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("[PASS] testShallow()"), "{stdout}");
+    assert!(
+        stdout.contains("[FAIL. Reason: call depth of 21 exceeds the limit of 10] testDeep()"),
+        "{stdout}"
+    );
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.contains("exceeds the limit of 10") &&
+                line.contains("testFuzzDeep(uint8)")),
+        "{stdout}"
+    );
+});
+
+// tests that the durations of the tests are cached for `--order slowest-first`, and that
+// `--fail-fast` stops at the first failure
+forgetest_init!(can_order_tests_by_timings, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_source("failing_test", FAILING_TEST).unwrap();
+
+    cmd.args(["test", "--order", "slowest-first"]);
+    cmd.assert_err();
+    let timings = prj.root().join("cache").join("test-timings.json");
+    let content = std::fs::read_to_string(&timings).unwrap();
+    assert!(content.contains("FailingTest"), "{content}");
+    assert!(content.contains("testShouldFail()"), "{content}");
+
+    cmd.forge_fuse().args(["test", "--order", "random", "--order-seed", "1", "--fail-fast"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("--order-seed 1"), "{out}");
+    assert!(out.contains("[FAIL"), "{out}");
+});
+
+// tests that `--isolate` runs every top-level call as a separate transaction
+forgetest!(can_isolate_top_level_calls, |prj, cmd| {
+    prj.insert_ds_test();
+
+    prj.add_source(
+        "IsolateTest.t.sol",
+        r#"
+import "./test.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function increment() external {
+        number++;
+    }
+
+    function read() external returns (uint256) {
+        return number;
+    }
+}
+
+contract IsolateTest is DSTest {
+    Counter counter;
+
+    function setUp() public {
+        counter = new Counter();
+    }
+
+    function measure() internal returns (uint256) {
+        uint256 before = gasleft();
+        counter.read();
+        return before - gasleft();
+    }
+
+    function testIsolation() public {
+        counter.increment();
+        assertEq(counter.number(), 1);
+
+        uint256 first = measure();
+        uint256 second = measure();
+        // every call pays for the transaction and starts with cold storage
+        assertGt(first, 21000);
+        assertEq(first, second);
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--isolate"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("[PASS] testIsolation()"), "{stdout}");
+
+    cmd.forge_fuse().args(["test"]);
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("[FAIL") && stdout.contains("testIsolation()"), "{stdout}");
+});
+
+// tests that a failing fuzz test shrinks to the same counterexample when it's replayed with the
+// printed seed, and that the shrunk counterexample satisfies `vm.assume`
+forgetest!(can_replay_shrunk_fuzz_failure, |prj, cmd| {
+    prj.insert_ds_test();
+
+    prj.add_source(
+        "ShrinkTest.t.sol",
+        r#"
+import "./test.sol";
+
+interface Vm {
+    function assume(bool condition) external pure;
+}
+
+contract ShrinkTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testShrink(uint256 x, uint256[] memory values) public {
+        vm.assume(x % 2 == 1);
+        assertTrue(x < 1000 && values.length < 2);
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    let counterexample = |stdout: &str| {
+        let line = stdout
+            .lines()
+            .find(|line| line.contains("counterexample:"))
+            .unwrap_or_else(|| panic!("no counterexample: {stdout}"));
+        line[line.find("counterexample:").unwrap()..line.find("] testShrink").unwrap()].to_string()
+    };
+
+    cmd.args(["test", "--fuzz-seed", "7"]);
+    let (first, _) = cmd.unchecked_output_lossy();
+    assert!(first.contains("Replay this failure with `--fuzz-seed 7`"), "{first}");
+    let shrunk = counterexample(&first);
+    // the shrinker only keeps inputs that pass `vm.assume`, so the shrunk `x` is still odd
+    let x: String = shrunk[shrunk.find("args=[").unwrap() + 6..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    assert!(x.ends_with(['1', '3', '5', '7', '9']), "{shrunk}");
+
+    let (second, _) = cmd.unchecked_output_lossy();
+    assert_eq!(counterexample(&second), shrunk);
+});
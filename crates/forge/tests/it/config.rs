@@ -102,6 +102,7 @@ pub fn test_opts() -> TestOptions {
             runs: 256,
             max_test_rejects: 65536,
             seed: None,
+            coverage_guided: false,
             dictionary: FuzzDictionaryConfig {
                 include_storage: true,
                 include_push_bytes: true,
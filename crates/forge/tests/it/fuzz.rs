@@ -2,20 +2,37 @@
 
 use crate::config::*;
 use alloy_primitives::U256;
-use forge::result::{SuiteResult, TestStatus};
+use forge::{
+    result::{SuiteResult, TestStatus},
+    TestOptions,
+};
+use foundry_evm::fuzz::CounterExample;
 use foundry_test_utils::Filter;
 use std::collections::BTreeMap;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fuzz() {
+    test_fuzz_with(test_opts()).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuzz_coverage_guided() {
+    let mut opts = test_opts();
+    opts.fuzz.coverage_guided = true;
+    test_fuzz_with(opts).await;
+}
+
+async fn test_fuzz_with(opts: TestOptions) {
     let mut runner = runner().await;
 
     let suite_result = runner
         .test_collect(
             &Filter::new(".*", ".*", ".*fuzz/")
-                .exclude_tests(r"invariantCounter|testIncrement\(address\)|testNeedle\(uint256\)")
+                .exclude_tests(
+                    r"invariantCounter|testIncrement\(address\)|testNeedle\(uint256\)|testFindNeedle",
+                )
                 .exclude_paths("invariant"),
-            test_opts(),
+            opts,
         )
         .await;
 
@@ -46,6 +63,39 @@ async fn test_fuzz() {
     }
 }
 
+/// Test that coverage guidance finds a needle that random inputs with the same seed don't, and
+/// that the seed reproduces the guided run.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuzz_coverage_guided_finds_needle() {
+    let mut runner = runner().await;
+    let filter = Filter::new("testFindNeedle", ".*", ".*fuzz/FuzzCoverageGuided.t.sol");
+
+    let mut opts = test_opts();
+    opts.fuzz.runs = 20_000;
+    opts.fuzz.seed = Some(U256::from(6u32));
+    let mut outcomes = Vec::new();
+    for coverage_guided in [false, true, true] {
+        opts.fuzz.coverage_guided = coverage_guided;
+        runner.test_options = opts.clone();
+        let mut results = runner.test_collect(&filter, opts.clone()).await;
+
+        let suite = results.remove("fuzz/FuzzCoverageGuided.t.sol:CoverageGuidedFuzzTest").unwrap();
+        let result = suite.test_results.into_values().next().unwrap();
+        let counterexample = match result.counterexample {
+            Some(CounterExample::Single(counterexample)) => Some(counterexample.calldata),
+            _ => None,
+        };
+        outcomes.push((result.status, result.reason, counterexample));
+    }
+
+    let [unguided, guided, guided_again] = &outcomes[..] else { unreachable!() };
+    assert_eq!(unguided.0, TestStatus::Success, "{:?}", unguided.1);
+    assert_eq!(guided.0, TestStatus::Failure, "{:?}", guided.1);
+    assert_eq!(guided.1.as_deref(), Some("needle found."));
+    assert!(guided.2.is_some());
+    assert_eq!(guided, guided_again);
+}
+
 /// Test that showcases PUSH collection on normal fuzzing. Ignored until we collect them in a
 /// smarter way.
 #[tokio::test(flavor = "multi_thread")]
@@ -5,7 +5,7 @@ use crate::{
     test_helpers::{COMPILED, PROJECT},
 };
 use forge::{
-    result::{SuiteResult, TestKind, TestResult},
+    result::{SuiteResult, TestKind, TestResult, TestStatus},
     TestOptions, TestOptionsBuilder,
 };
 use foundry_config::{FuzzConfig, InvariantConfig};
@@ -72,6 +72,29 @@ async fn inline_config_run_invariant() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn inline_config_run_contract() {
+    let opts = default_test_options();
+    let filter = Filter::new(".*", ".*", ".*inline/ContractInlineConf.t.sol");
+    let mut runner = runner().await;
+    runner.test_options = opts.clone();
+
+    let result = runner.test_collect(&filter, opts).await;
+    let suite_result = result.get("inline/ContractInlineConf.t.sol:ContractInlineConf").unwrap();
+
+    for (test, expected_runs) in
+        [("testInlineConfContractFuzz(uint8)", 10), ("testInlineConfFunctionFuzz(uint8)", 20)]
+    {
+        match &suite_result.test_results.get(test).unwrap().kind {
+            TestKind::Fuzz { runs, .. } => assert_eq!(*runs, expected_runs, "{test}"),
+            _ => unreachable!(),
+        }
+    }
+
+    let test_result = suite_result.test_results.get("testFailInlineConfGasLimit()").unwrap();
+    assert_eq!(test_result.status, TestStatus::Success, "{:?}", test_result.reason);
+}
+
 #[test]
 fn build_test_options() {
     let root = &PROJECT.paths.root;
@@ -215,7 +215,10 @@ test_repro!(6170, false, None, |res| {
     let mut res = res.remove("repros/Issue6170.t.sol:Issue6170Test").unwrap();
     let test = res.test_results.remove("test()").unwrap();
     assert_eq!(test.status, TestStatus::Failure);
-    assert_eq!(test.reason, Some("log != expected log".to_string()));
+    assert_eq!(
+        test.reason,
+        Some("log != expected log: number of topics: expected 2, got 3".to_string())
+    );
 });
 
 // <https://github.com/foundry-rs/foundry/issues/6293>
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "./Vm.sol";
+
+contract AssertTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    struct Position {
+        uint256 amount;
+        address[] owners;
+    }
+
+    function assertUintArrays(uint256[] memory left, uint256[] memory right) external pure {
+        vm.assertEq(left, right);
+    }
+
+    function assertPositions(Position memory left, Position memory right) external pure {
+        vm.assertEqDecoded(abi.encode(left), abi.encode(right), "Position");
+    }
+
+    function expectCheatcodeError(bytes memory reason, string memory message) internal {
+        assertEq(keccak256(reason), keccak256(abi.encodeWithSignature("CheatcodeError(string)", message)));
+    }
+
+    function testAssertEqArrays() public {
+        uint256[] memory left = new uint256[](2);
+        left[0] = 1;
+        left[1] = 2;
+        vm.assertEq(left, left);
+
+        string[] memory strings = new string[](1);
+        strings[0] = "foo";
+        vm.assertEq(strings, strings);
+        vm.assertEq(bytes("foo"), bytes("foo"));
+    }
+
+    function testAssertEqArraysDiff() public {
+        uint256[] memory left = new uint256[](2);
+        left[0] = 1;
+        left[1] = 2;
+        uint256[] memory right = new uint256[](3);
+        right[0] = 1;
+        right[1] = 5;
+
+        try this.assertUintArrays(left, right) {
+            fail();
+        } catch (bytes memory reason) {
+            expectCheatcodeError(reason, "assertion failed: values differ\n  length: 2 != 3\n  [1]: 2 != 5");
+        }
+    }
+
+    function testAssertEqDecoded() public {
+        address[] memory owners = new address[](1);
+        owners[0] = address(1);
+        Position memory left = Position(100, owners);
+        this.assertPositions(left, left);
+
+        address[] memory otherOwners = new address[](1);
+        otherOwners[0] = address(2);
+        Position memory right = Position(100, otherOwners);
+
+        try this.assertPositions(left, right) {
+            fail();
+        } catch (bytes memory reason) {
+            expectCheatcodeError(
+                reason,
+                "assertion failed: values differ\n  .owners[0]: 0x0000000000000000000000000000000000000001 != 0x0000000000000000000000000000000000000002"
+            );
+        }
+    }
+}
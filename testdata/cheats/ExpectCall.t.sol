@@ -99,6 +99,32 @@ contract ExpectCallTest is DSTest {
         this.exposed_callTargetNTimes(target, 1, 2, 2);
     }
 
+    function testExpectCallAtLeast() public {
+        Contract target = new Contract();
+        vm.expectCallAtLeast(address(target), abi.encodeWithSelector(target.add.selector, 1, 2), 2);
+        this.exposed_callTargetNTimes(target, 1, 2, 3);
+    }
+
+    function testExpectCallAtLeastAdditive() public {
+        Contract target = new Contract();
+        vm.expectCall(address(target), abi.encodeWithSelector(target.add.selector, 1, 2));
+        vm.expectCallAtLeast(address(target), abi.encodeWithSelector(target.add.selector, 1, 2), 2);
+        this.exposed_callTargetNTimes(target, 1, 2, 3);
+    }
+
+    function testFailExpectCallAtLeast() public {
+        Contract target = new Contract();
+        vm.expectCallAtLeast(address(target), abi.encodeWithSelector(target.add.selector, 1, 2), 2);
+        this.exposed_callTargetNTimes(target, 1, 2, 1);
+    }
+
+    function testExpectCallAtLeastWithValue() public {
+        Contract target = new Contract();
+        vm.expectCallAtLeast(address(target), 1, abi.encodeWithSelector(target.pay.selector, 2), 2);
+        this.exposed_expectCallWithValue(target, 1, 2);
+        this.exposed_expectCallWithValue(target, 1, 2);
+    }
+
     function testFailExpectCallWithData() public {
         Contract target = new Contract();
         vm.expectCall(address(target), abi.encodeWithSelector(target.add.selector, 1, 2), 1);
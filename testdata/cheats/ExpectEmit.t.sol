@@ -578,6 +578,87 @@ contract ExpectEmitTest is DSTest {
         emitter.emitWindow();
     }
 
+    function testExpectEmitCount() public {
+        vm.expectEmit(address(emitter), 2);
+        emit Something(1, 2, 3, 4);
+
+        emitter.emitMultiple([uint256(1), 1], [uint256(2), 2], [uint256(3), 3], [uint256(4), 4]);
+    }
+
+    function testExpectEmitCountWithArgs() public {
+        vm.expectEmit(true, false, false, false, 2);
+        emit Something(1, 0, 0, 0);
+
+        emitter.emitMultiple([uint256(1), 1], [uint256(2), 5], [uint256(3), 6], [uint256(4), 7]);
+    }
+
+    function testFailExpectEmitCount() public {
+        vm.expectEmit(address(emitter), 3);
+        emit Something(1, 2, 3, 4);
+
+        emitter.emitMultiple([uint256(1), 1], [uint256(2), 2], [uint256(3), 3], [uint256(4), 4]);
+    }
+
+    function testFailExpectEmitCountNotEmitted() public {
+        vm.expectEmit(1);
+        emit Something(1, 2, 3, 4);
+
+        emitter.emitEvent(1, 2, 3, 5);
+    }
+
+    function testExpectEmitSignature() public {
+        vm.expectEmitSignature(Something.selector);
+        emitter.emitEvent(5, 6, 7, 8);
+    }
+
+    function testExpectEmitSignatureAddress() public {
+        vm.expectEmitSignature(B.selector, address(emitter));
+        vm.expectEmitSignature(D.selector, address(emitter));
+        emitter.emitWindow();
+    }
+
+    function testFailExpectEmitSignature() public {
+        vm.expectEmitSignature(A.selector);
+        emitter.emitEvent(1, 2, 3, 4);
+    }
+
+    function testFailExpectEmitSignatureAddress() public {
+        vm.expectEmitSignature(Something.selector, address(0));
+        emitter.emitEvent(1, 2, 3, 4);
+    }
+
+    function testExpectEmitSignatureCount() public {
+        vm.expectEmitSignature(Something.selector, address(emitter), 2);
+        emitter.emitMultiple([uint256(1), 5], [uint256(2), 6], [uint256(3), 7], [uint256(4), 8]);
+    }
+
+    function testFailExpectEmitSignatureCount() public {
+        vm.expectEmitSignature(Something.selector, address(emitter), 1);
+        emitter.emitMultiple([uint256(1), 5], [uint256(2), 6], [uint256(3), 7], [uint256(4), 8]);
+    }
+
+    function testExpectNoUnexpectedEmits() public {
+        vm.expectNoUnexpectedEmits(address(emitter));
+        vm.expectEmit(address(emitter));
+        emit Something(1, 2, 3, 4);
+
+        emitter.emitEvent(1, 2, 3, 4);
+    }
+
+    function testExpectNoUnexpectedEmitsWithCount() public {
+        vm.expectNoUnexpectedEmits(address(emitter));
+        vm.expectEmitSignature(Something.selector, address(emitter), 2);
+        emitter.emitMultiple([uint256(1), 5], [uint256(2), 6], [uint256(3), 7], [uint256(4), 8]);
+    }
+
+    function testFailExpectNoUnexpectedEmits() public {
+        vm.expectNoUnexpectedEmits(address(emitter));
+        vm.expectEmit(true, false, false, true);
+        emit A(1);
+
+        emitter.emitWindow();
+    }
+
     /// This test will fail if we check that all expected logs were emitted
     /// after every call from the same depth as the call that invoked the cheatcode.
     ///
@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "./Vm.sol";
+
+contract GetDeploymentTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testGetDeployment() public {
+        assertEq(vm.getDeployment("Token"), 0x5FbDB2315678afecb367f032d93F642f64180aa3);
+        assertEq(vm.getDeployment("Token", 31337), 0x5FbDB2315678afecb367f032d93F642f64180aa3);
+    }
+
+    function testGetDeploymentOnOtherChain() public {
+        vm.chainId(1);
+        vm.expectRevert("no deployment of `Token` found on chain 1");
+        vm.getDeployment("Token");
+    }
+
+    function testGetMissingDeployment() public {
+        vm.expectRevert("no deployment of `Vault` found on chain 31337");
+        vm.getDeployment("Vault", 31337);
+    }
+}
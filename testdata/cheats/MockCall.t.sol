@@ -42,6 +42,14 @@ contract NestedMock {
     }
 }
 
+contract DelegateCaller {
+    function delegateNumberB(address implementation) public returns (uint256) {
+        (bool success, bytes memory data) = implementation.delegatecall(abi.encodeWithSelector(Mock.numberB.selector));
+        require(success);
+        return abi.decode(data, (uint256));
+    }
+}
+
 contract MockCallTest is DSTest {
     Vm constant vm = Vm(HEVM_ADDRESS);
 
@@ -146,6 +154,50 @@ contract MockCallTest is DSTest {
         assertEq(mock.pay(2), 2);
     }
 
+    function testMockCallMasked() public {
+        Mock mock = new Mock();
+
+        // any first argument, but the second argument must be 5
+        bytes memory data = abi.encodeWithSelector(mock.add.selector, 0, 5);
+        bytes memory mask = abi.encodePacked(bytes4(0xffffffff), bytes32(0));
+        vm.mockCallMasked(address(mock), data, mask, abi.encode(42));
+
+        assertEq(mock.add(1, 5), 42);
+        assertEq(mock.add(2, 5), 42);
+        assertEq(mock.add(1, 6), 7);
+    }
+
+    function testMockCallFrom() public {
+        Mock mock = new Mock();
+
+        vm.mockCallFrom(address(mock), address(0xbeef), abi.encodeWithSelector(mock.numberB.selector), abi.encode(10));
+
+        assertEq(mock.numberB(), 2);
+        vm.prank(address(0xbeef));
+        assertEq(mock.numberB(), 10);
+    }
+
+    function testMockCallFromPrecedence() public {
+        Mock inner = new Mock();
+        NestedMock target = new NestedMock(inner);
+
+        vm.mockCall(address(inner), abi.encodeWithSelector(inner.numberB.selector), abi.encode(5));
+        vm.mockCallFrom(address(inner), address(target), abi.encodeWithSelector(inner.numberB.selector), abi.encode(10));
+
+        assertEq(inner.numberB(), 5);
+        assertEq(target.sum(), 11);
+    }
+
+    function testMockDelegateCall() public {
+        Mock implementation = new Mock();
+        DelegateCaller proxy = new DelegateCaller();
+        assertEq(proxy.delegateNumberB(address(implementation)), 2);
+
+        vm.mockCall(address(implementation), abi.encodeWithSelector(Mock.numberB.selector), abi.encode(10));
+
+        assertEq(proxy.delegateNumberB(address(implementation)), 10);
+    }
+
     function testMockCallEmptyAccount() public {
         Mock mock = Mock(address(100));
 
@@ -286,6 +338,19 @@ contract MockCallRevertTest is DSTest {
         assertEq(data, customError);
     }
 
+    function testMockCallRevertFrom() public {
+        Mock mock = new Mock();
+
+        vm.mockCallRevertFrom(
+            address(mock), address(0xbeef), abi.encodeWithSelector(mock.numberB.selector), ERROR_MESSAGE
+        );
+
+        assertEq(mock.numberB(), 2);
+        vm.prank(address(0xbeef));
+        vm.expectRevert(ERROR_MESSAGE);
+        mock.numberB();
+    }
+
     function testMockCallEmptyAccount() public {
         Mock mock = Mock(address(100));
 
@@ -12,6 +12,11 @@ contract SkipTest is DSTest {
         revert("Should not reach this revert");
     }
 
+    function testSkipWithReason() public {
+        vm.skip(true, "not supported on this chain");
+        revert("Should not reach this revert");
+    }
+
     function testFailNotSkip() public {
         vm.skip(false);
         revert("This test should fail");
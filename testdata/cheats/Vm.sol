@@ -22,6 +22,15 @@ interface Vm {
     function activeFork() external view returns (uint256 forkId);
     function addr(uint256 privateKey) external pure returns (address keyAddr);
     function allowCheatcodes(address account) external;
+    function assertEqDecoded(bytes calldata left, bytes calldata right, string calldata typeDescription) external pure;
+    function assertEq(bool[] calldata left, bool[] calldata right) external pure;
+    function assertEq(uint256[] calldata left, uint256[] calldata right) external pure;
+    function assertEq(int256[] calldata left, int256[] calldata right) external pure;
+    function assertEq(address[] calldata left, address[] calldata right) external pure;
+    function assertEq(bytes32[] calldata left, bytes32[] calldata right) external pure;
+    function assertEq(string[] calldata left, string[] calldata right) external pure;
+    function assertEq(bytes[] calldata left, bytes[] calldata right) external pure;
+    function assertEq(bytes calldata left, bytes calldata right) external pure;
     function assume(bool condition) external pure;
     function breakpoint(string calldata char) external;
     function breakpoint(string calldata char, bool value) external;
@@ -86,6 +95,8 @@ interface Vm {
     function etch(address target, bytes calldata newRuntimeBytecode) external;
     function eth_getLogs(uint256 fromBlock, uint256 toBlock, address addr, bytes32[] memory topics) external returns (EthGetLogs[] memory logs);
     function exists(string calldata path) external returns (bool result);
+    function expectCallAtLeast(address callee, bytes calldata data, uint64 count) external;
+    function expectCallAtLeast(address callee, uint256 msgValue, bytes calldata data, uint64 count) external;
     function expectCallMinGas(address callee, uint256 msgValue, uint64 minGas, bytes calldata data) external;
     function expectCallMinGas(address callee, uint256 msgValue, uint64 minGas, bytes calldata data, uint64 count) external;
     function expectCall(address callee, bytes calldata data) external;
@@ -94,10 +105,18 @@ interface Vm {
     function expectCall(address callee, uint256 msgValue, bytes calldata data, uint64 count) external;
     function expectCall(address callee, uint256 msgValue, uint64 gas, bytes calldata data) external;
     function expectCall(address callee, uint256 msgValue, uint64 gas, bytes calldata data, uint64 count) external;
+    function expectEmitSignature(bytes32 topic0) external;
+    function expectEmitSignature(bytes32 topic0, address emitter) external;
+    function expectEmitSignature(bytes32 topic0, address emitter, uint64 count) external;
     function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData) external;
     function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData, address emitter) external;
     function expectEmit() external;
     function expectEmit(address emitter) external;
+    function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData, uint64 count) external;
+    function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData, address emitter, uint64 count) external;
+    function expectEmit(uint64 count) external;
+    function expectEmit(address emitter, uint64 count) external;
+    function expectNoUnexpectedEmits(address emitter) external;
     function expectRevert() external;
     function expectRevert(bytes4 revertData) external;
     function expectRevert(bytes calldata revertData) external;
@@ -108,6 +127,8 @@ interface Vm {
     function fsMetadata(string calldata path) external view returns (FsMetadata memory metadata);
     function getCode(string calldata artifactPath) external view returns (bytes memory creationBytecode);
     function getDeployedCode(string calldata artifactPath) external view returns (bytes memory runtimeBytecode);
+    function getDeployment(string calldata contractName) external view returns (address deployedAddress);
+    function getDeployment(string calldata contractName, uint64 chainId) external view returns (address deployedAddress);
     function getLabel(address account) external returns (string memory currentLabel);
     function getMappingKeyAndParentOf(address target, bytes32 elementSlot) external returns (bool found, bytes32 key, bytes32 parent);
     function getMappingLength(address target, bytes32 mappingSlot) external returns (uint256 length);
@@ -126,6 +147,9 @@ interface Vm {
     function makePersistent(address account0, address account1) external;
     function makePersistent(address account0, address account1, address account2) external;
     function makePersistent(address[] calldata accounts) external;
+    function mockCallFrom(address callee, address caller, bytes calldata data, bytes calldata returnData) external;
+    function mockCallMasked(address callee, bytes calldata data, bytes calldata mask, bytes calldata returnData) external;
+    function mockCallRevertFrom(address callee, address caller, bytes calldata data, bytes calldata revertData) external;
     function mockCallRevert(address callee, bytes calldata data, bytes calldata revertData) external;
     function mockCallRevert(address callee, uint256 msgValue, bytes calldata data, bytes calldata revertData) external;
     function mockCall(address callee, bytes calldata data, bytes calldata returnData) external;
@@ -208,6 +232,7 @@ interface Vm {
     function sign(uint256 privateKey, bytes32 digest) external pure returns (uint8 v, bytes32 r, bytes32 s);
     function sign(Wallet calldata wallet, bytes32 digest) external returns (uint8 v, bytes32 r, bytes32 s);
     function skip(bool skipTest) external;
+    function skip(bool skipTest, string calldata reason) external;
     function sleep(uint256 duration) external;
     function snapshot() external returns (uint256 snapshotId);
     function startBroadcast() external;
@@ -239,4 +264,5 @@ interface Vm {
     function writeJson(string calldata json, string calldata path) external;
     function writeJson(string calldata json, string calldata path, string calldata valueKey) external;
     function writeLine(string calldata path, string calldata data) external;
+    function xfail(string calldata reason) external;
 }
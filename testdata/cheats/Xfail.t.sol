@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "./Vm.sol";
+
+contract XfailTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testXfailRevert() public {
+        vm.xfail("reverts until the bug is fixed");
+        revert("This test is expected to fail");
+    }
+
+    function testXfailAssertion() public {
+        vm.xfail("assertion fails until the bug is fixed");
+        assertEq(uint256(1), uint256(2));
+    }
+}
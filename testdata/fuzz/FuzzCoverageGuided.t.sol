@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+
+// A needle behind seven checks that each pass for one in 16 inputs: out of reach of random
+// inputs, but found check by check by mutating the inputs that passed the most checks so far.
+contract CoverageGuidedFuzzTest is DSTest {
+    function passes(uint256 x) internal pure returns (bool) {
+        return uint256(keccak256(abi.encode(x))) % 16 == 0;
+    }
+
+    function testFindNeedle(
+        uint256 a,
+        uint256 b,
+        uint256 c,
+        uint256 d,
+        uint256 e,
+        uint256 f,
+        uint256 g
+    ) public {
+        if (passes(a)) {
+            if (passes(b)) {
+                if (passes(c)) {
+                    if (passes(d)) {
+                        if (passes(e)) {
+                            if (passes(f)) {
+                                require(!passes(g), "needle found.");
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+
+/// forge-config: default.fuzz.runs = 10
+/// forge-config: default.gas.limit = 200000
+contract ContractInlineConf is DSTest {
+    function testInlineConfContractFuzz(uint8 x) public {
+        require(true, "this is not going to revert");
+    }
+
+    /// forge-config: default.fuzz.runs = 20
+    function testInlineConfFunctionFuzz(uint8 x) public {
+        require(true, "this is not going to revert");
+    }
+
+    // only runs out of gas with the gas limit of the contract
+    function testFailInlineConfGasLimit() public {
+        for (uint256 i = 0; i < 100000; i++) {}
+    }
+}